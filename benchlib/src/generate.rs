@@ -16,23 +16,19 @@ impl Generate for bool {
     }
 }
 
-impl Generate for u32 {
-    fn generate<R: Rng>(rng: &mut R) -> Self {
-        rng.gen()
-    }
-}
-
-impl Generate for f32 {
-    fn generate<R: Rng>(rng: &mut R) -> Self {
-        rng.gen()
-    }
+macro_rules! impl_primitive {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Generate for $ty {
+                fn generate<R: Rng>(rng: &mut R) -> Self {
+                    rng.gen()
+                }
+            }
+        )*
+    };
 }
 
-impl Generate for f64 {
-    fn generate<R: Rng>(rng: &mut R) -> Self {
-        rng.gen()
-    }
-}
+impl_primitive!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
 
 impl<T: Generate, const N: usize> Generate for [T; N] {
     fn generate<R: Rng>(rng: &mut R) -> Self {
@@ -1,21 +1,96 @@
 mod generate;
 
+pub use benchlib_derive::Generate;
+pub use bincode;
 pub use divan;
+pub use postcard;
 pub use rand::Rng;
 use rand_pcg::Lcg64Xsh32;
 pub use rkyv;
+use rkyv::{
+    rancor::{Panic, Strategy},
+    ser::{
+        allocator::{AllocationStats, AllocationTracker, Arena, ArenaHandle},
+        sharing::Share,
+        Serializer,
+    },
+    util::{serialize_into, AlignedVec},
+    Serialize,
+};
+pub use serde_json;
 
 pub use self::generate::*;
 
+/// Generates benchmarks for serializing, deserializing, and checking a
+/// dataset.
+///
+/// Reading the archived data in place can be benchmarked by passing a function
+/// which traverses it, and comparison benchmarks against other formats can be
+/// generated by listing them after the dataset:
+///
+/// ```ignore
+/// bench_dataset!(
+///     Logs = generate_logs();
+///     align(4096);
+///     pretouch(true);
+///     access(access_logs);
+///     compare(bincode, postcard, serde_json)
+/// );
+/// ```
+///
+/// `align` sets the alignment of the `AlignedVec` which the dataset is
+/// serialized into, and defaults to 16. If `pretouch` is `true`, the output
+/// buffers for the serialization benchmarks are grown to the size of the
+/// serialized dataset and written to before benchmarking, so page faults from
+/// growing the buffer aren't measured. It defaults to `false`.
+///
+/// Every benchmark reports the size of the serialized dataset as a byte
+/// counter, so divan reports throughput alongside timings.
+///
+/// The access function takes a reference to the archived dataset, and its
+/// result is passed through `black_box` so the traversal is not optimized out.
+///
+/// The dataset type must implement serde's `Serialize` and `DeserializeOwned`
+/// to be compared. Each format's benchmarks are generated in a module named
+/// after the format.
+///
+/// The benchmark binary uses divan's allocation profiler as its global
+/// allocator, so allocation counts and sizes are reported alongside timings.
+/// The peak scratch space used to serialize the dataset is printed before the
+/// benchmarks run.
 #[macro_export]
 macro_rules! bench_dataset {
-    ($ty:ty = $generate:expr) => {
+    (
+        $ty:ty = $generate:expr
+        $(; align($align:expr))?
+        $(; pretouch($pretouch:expr))?
+        $(; access($access:expr))?
+        $(; compare($($format:ident),* $(,)?))?
+    ) => {
+        #[global_allocator]
+        static ALLOC: $crate::divan::AllocProfiler =
+            $crate::divan::AllocProfiler::system();
+
+        const ALIGN: usize = $crate::__or_default!(16 $(, $align)?);
+        const PRETOUCH: bool = $crate::__or_default!(false $(, $pretouch)?);
+
         #[$crate::divan::bench(min_time = std::time::Duration::from_secs(3))]
         pub fn serialize(bencher: $crate::divan::Bencher) {
             let data = $generate;
-            let mut bytes = rkyv::util::AlignedVec::<16>::new();
+            let len = rkyv::to_bytes_in::<_, rkyv::rancor::Panic>(
+                &data,
+                rkyv::util::AlignedVec::<ALIGN>::new(),
+            )
+            .unwrap()
+            .len();
+            let mut bytes = rkyv::util::AlignedVec::<ALIGN>::new();
+            if PRETOUCH {
+                bytes.resize(len, 0);
+                bytes.clear();
+            }
 
-            bencher.bench_local(|| {
+            let counter = $crate::divan::counter::BytesCount::new(len);
+            bencher.counter(counter).bench_local(|| {
                 let mut buffer = core::mem::take(&mut bytes);
                 buffer.clear();
 
@@ -33,11 +108,12 @@ macro_rules! bench_dataset {
         pub fn deserialize(bencher: $crate::divan::Bencher) {
             let bytes = rkyv::to_bytes_in::<_, rkyv::rancor::Panic>(
                 &$generate,
-                rkyv::util::AlignedVec::<16>::new(),
+                rkyv::util::AlignedVec::<ALIGN>::new(),
             )
             .unwrap();
 
-            bencher.bench_local(|| {
+            let counter = $crate::divan::counter::BytesCount::new(bytes.len());
+            bencher.counter(counter).bench_local(|| {
                 rkyv::from_bytes::<$ty, rkyv::rancor::Panic>(
                     $crate::divan::black_box(&bytes),
                 )
@@ -49,23 +125,200 @@ macro_rules! bench_dataset {
         pub fn check_bytes(bencher: $crate::divan::Bencher) {
             let bytes = rkyv::to_bytes_in::<_, rkyv::rancor::Panic>(
                 &$generate,
-                rkyv::util::AlignedVec::<16>::new(),
+                rkyv::util::AlignedVec::<ALIGN>::new(),
             )
             .unwrap();
 
-            bencher.bench_local(|| {
+            let counter = $crate::divan::counter::BytesCount::new(bytes.len());
+            bencher.counter(counter).bench_local(|| {
                 rkyv::access::<rkyv::Archived<$ty>, rkyv::rancor::Panic>(
                     $crate::divan::black_box(&bytes),
                 )
             })
         }
 
+        $(
+            #[$crate::divan::bench(
+                min_time = std::time::Duration::from_secs(3),
+            )]
+            pub fn access(bencher: $crate::divan::Bencher) {
+                let bytes = rkyv::to_bytes_in::<_, rkyv::rancor::Panic>(
+                    &$generate,
+                    rkyv::util::AlignedVec::<ALIGN>::new(),
+                )
+                .unwrap();
+                let archived =
+                    rkyv::access::<rkyv::Archived<$ty>, rkyv::rancor::Panic>(
+                        &bytes,
+                    )
+                    .unwrap();
+                let access = $access;
+
+                let counter =
+                    $crate::divan::counter::BytesCount::new(bytes.len());
+                bencher.counter(counter).bench_local(|| {
+                    access($crate::divan::black_box(archived))
+                })
+            }
+        )?
+
+        $($(
+            $crate::__bench_format!($format, $ty, $generate);
+        )*)?
+
         fn main() {
+            $crate::print_scratch_stats(&$generate);
             $crate::divan::main();
         }
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bench_format {
+    (bincode, $ty:ty, $generate:expr) => {
+        $crate::__bench_format!(
+            @emit bincode, $ty, $generate,
+            |bytes: &mut Vec<u8>, data: &$ty| {
+                $crate::bincode::serialize_into(bytes, data).unwrap()
+            },
+            |bytes: &[u8]| {
+                $crate::bincode::deserialize::<$ty>(bytes).unwrap()
+            },
+        );
+    };
+    (postcard, $ty:ty, $generate:expr) => {
+        $crate::__bench_format!(
+            @emit postcard, $ty, $generate,
+            |bytes: &mut Vec<u8>, data: &$ty| {
+                *bytes = $crate::postcard::to_extend(
+                    data,
+                    core::mem::take(bytes),
+                )
+                .unwrap();
+            },
+            |bytes: &[u8]| {
+                $crate::postcard::from_bytes::<$ty>(bytes).unwrap()
+            },
+        );
+    };
+    (serde_json, $ty:ty, $generate:expr) => {
+        $crate::__bench_format!(
+            @emit serde_json, $ty, $generate,
+            |bytes: &mut Vec<u8>, data: &$ty| {
+                $crate::serde_json::to_writer(bytes, data).unwrap()
+            },
+            |bytes: &[u8]| {
+                $crate::serde_json::from_slice::<$ty>(bytes).unwrap()
+            },
+        );
+    };
+    (
+        @emit $name:ident, $ty:ty, $generate:expr,
+        $serialize:expr,
+        $deserialize:expr $(,)?
+    ) => {
+        pub mod $name {
+            use super::*;
+
+            #[$crate::divan::bench(
+                min_time = std::time::Duration::from_secs(3),
+            )]
+            pub fn serialize(bencher: $crate::divan::Bencher) {
+                let data = $generate;
+                let serialize = $serialize;
+                let len = {
+                    let mut bytes = Vec::new();
+                    serialize(&mut bytes, &data);
+                    bytes.len()
+                };
+                let mut bytes = Vec::new();
+                if PRETOUCH {
+                    bytes.resize(len, 0);
+                    bytes.clear();
+                }
+
+                let counter = $crate::divan::counter::BytesCount::new(len);
+                bencher.counter(counter).bench_local(|| {
+                    bytes.clear();
+                    serialize(&mut bytes, $crate::divan::black_box(&data));
+                    $crate::divan::black_box(&bytes);
+                });
+            }
+
+            #[$crate::divan::bench(
+                min_time = std::time::Duration::from_secs(3),
+            )]
+            pub fn deserialize(bencher: $crate::divan::Bencher) {
+                let mut bytes = Vec::new();
+                ($serialize)(&mut bytes, &$generate);
+                let deserialize = $deserialize;
+
+                let counter =
+                    $crate::divan::counter::BytesCount::new(bytes.len());
+                bencher.counter(counter).bench_local(|| {
+                    deserialize($crate::divan::black_box(bytes.as_slice()))
+                })
+            }
+        }
+    };
+    ($format:ident, $ty:ty, $generate:expr) => {
+        compile_error!(concat!(
+            "unsupported comparison format `",
+            stringify!($format),
+            "`, expected one of `bincode`, `postcard`, or `serde_json`",
+        ));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __or_default {
+    ($default:expr) => {
+        $default
+    };
+    ($default:expr, $value:expr) => {
+        $value
+    };
+}
+
+/// A serializer which tracks the scratch space used during serialization.
+pub type TrackingSerializer<'a> = Strategy<
+    Serializer<AlignedVec, AllocationTracker<ArenaHandle<'a>>, Share>,
+    Panic,
+>;
+
+/// Serializes the given value and returns statistics about the scratch space
+/// it used.
+pub fn scratch_stats<T>(value: &T) -> AllocationStats
+where
+    T: for<'a> Serialize<TrackingSerializer<'a>>,
+{
+    let mut arena = Arena::new();
+    let serializer = Serializer::new(
+        AlignedVec::new(),
+        AllocationTracker::new(arena.acquire()),
+        Share::new(),
+    );
+    let serializer = serialize_into(value, serializer).unwrap();
+    serializer.into_raw_parts().1.into_stats()
+}
+
+/// Prints the scratch space used to serialize the given value.
+pub fn print_scratch_stats<T>(value: &T)
+where
+    T: for<'a> Serialize<TrackingSerializer<'a>>,
+{
+    let stats = scratch_stats(value);
+    println!(
+        "scratch space: {} bytes peak, {} allocations peak, {} bytes minimum \
+         arena capacity",
+        stats.max_bytes_allocated,
+        stats.max_allocations,
+        stats.min_arena_capacity(),
+    );
+}
+
 pub fn rng() -> Lcg64Xsh32 {
     // nothing up our sleeves, state and stream are first 20 digits of pi
     const STATE: u64 = 3141592653;
@@ -0,0 +1,134 @@
+//! Procedural macros for `benchlib`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Expr, Field,
+    Fields,
+};
+
+/// Derives `Generate` for the labeled type.
+///
+/// Each field is generated with its type's `Generate` implementation, in
+/// declaration order. Enums generate one of their variants, chosen uniformly at
+/// random.
+///
+/// # Attributes
+///
+/// - `#[generate(with = ...)]`: Generates the field by calling the given
+///   function or closure with the random number generator instead (i.e.
+///   `#[generate(with = |rng| generate_vec(rng, 0..10))]`).
+#[proc_macro_derive(Generate, attributes(generate))]
+pub fn derive_generate(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match derive(input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let body = match &input.data {
+        Data::Struct(data) => generate_fields(quote! { Self }, &data.fields)?,
+        Data::Enum(data) => {
+            if data.variants.is_empty() {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "Generate cannot be derived for enums with no variants",
+                ));
+            }
+
+            let len = data.variants.len();
+            let arms = data
+                .variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| {
+                    let name = &variant.ident;
+                    let value = generate_fields(
+                        quote! { Self::#name },
+                        &variant.fields,
+                    )?;
+                    Ok(quote! { #i => #value, })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            quote! {
+                match ::benchlib::Rng::gen_range(rng, 0..#len) {
+                    #(#arms)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Generate cannot be derived for unions",
+            ))
+        }
+    };
+
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote! { ::benchlib::Generate });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::benchlib::Generate for #name #ty_generics
+        #where_clause
+        {
+            fn generate<R: ::benchlib::Rng>(rng: &mut R) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+fn generate_fields(
+    path: TokenStream,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let values = fields
+        .iter()
+        .map(generate_field)
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| &field.ident);
+            quote! { #path { #(#names: #values,)* } }
+        }
+        Fields::Unnamed(_) => quote! { #path(#(#values,)*) },
+        Fields::Unit => path,
+    })
+}
+
+fn generate_field(field: &Field) -> Result<TokenStream, Error> {
+    let mut with = None::<Expr>;
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("generate") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") {
+                with = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unrecognized generate argument"))
+            }
+        })?;
+    }
+
+    let ty = &field.ty;
+    Ok(match with {
+        Some(with) => quote! { (#with)(rng) },
+        None => quote! { <#ty as ::benchlib::Generate>::generate(rng) },
+    })
+}
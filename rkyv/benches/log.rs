@@ -1,7 +1,17 @@
 use benchlib::{bench_dataset, generate_vec, Generate, Rng};
 use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 
-#[derive(Archive, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(
+    Archive,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    SerdeSerialize,
+    SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Address {
     pub x0: u8,
@@ -21,7 +31,15 @@ impl Generate for Address {
     }
 }
 
-#[derive(Archive, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(
+    Archive,
+    Serialize,
+    Deserialize,
+    Clone,
+    PartialEq,
+    SerdeSerialize,
+    SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Log {
     pub address: Address,
@@ -96,7 +114,15 @@ impl Generate for Log {
     }
 }
 
-#[derive(Archive, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(
+    Archive,
+    Serialize,
+    Deserialize,
+    Clone,
+    PartialEq,
+    SerdeSerialize,
+    SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Logs {
     pub logs: Vec<Log>,
@@ -111,4 +137,24 @@ pub fn generate_logs() -> Logs {
     }
 }
 
-bench_dataset!(Logs = generate_logs());
+pub fn access_logs(logs: &ArchivedLogs) -> (u64, usize, usize) {
+    let mut total_size = 0;
+    let mut errors = 0;
+    let mut anonymous = 0;
+    for log in logs.logs.iter() {
+        total_size += log.size.to_native();
+        if log.code.to_native() >= 400 {
+            errors += 1;
+        }
+        if log.userid == "-" {
+            anonymous += 1;
+        }
+    }
+    (total_size, errors, anonymous)
+}
+
+bench_dataset!(
+    Logs = generate_logs();
+    access(access_logs);
+    compare(bincode, postcard, serde_json)
+);
@@ -1,7 +1,18 @@
-use benchlib::{bench_dataset, generate_vec, Generate, Rng};
+use benchlib::{bench_dataset, generate_vec, Generate};
 use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 
-#[derive(Archive, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(
+    Archive,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    SerdeSerialize,
+    SerdeDeserialize,
+    Generate,
+)]
 #[archive(check_bytes)]
 pub struct Vector3 {
     pub x: f32,
@@ -9,17 +20,17 @@ pub struct Vector3 {
     pub z: f32,
 }
 
-impl Generate for Vector3 {
-    fn generate<R: Rng>(rand: &mut R) -> Self {
-        Self {
-            x: rand.gen(),
-            y: rand.gen(),
-            z: rand.gen(),
-        }
-    }
-}
-
-#[derive(Archive, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[derive(
+    Archive,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    SerdeSerialize,
+    SerdeDeserialize,
+    Generate,
+)]
 #[archive(check_bytes)]
 pub struct Triangle {
     pub v0: Vector3,
@@ -28,19 +39,14 @@ pub struct Triangle {
     pub normal: Vector3,
 }
 
-impl Generate for Triangle {
-    fn generate<R: Rng>(rand: &mut R) -> Self {
-        Self {
-            v0: Vector3::generate(rand),
-            v1: Vector3::generate(rand),
-            v2: Vector3::generate(rand),
-            normal: Vector3::generate(rand),
-        }
-    }
-}
-
 #[derive(
-    rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Clone, PartialEq,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    Clone,
+    PartialEq,
+    SerdeSerialize,
+    SerdeDeserialize,
 )]
 #[archive(check_bytes)]
 pub struct Mesh {
@@ -59,4 +65,18 @@ pub fn generate_mesh() -> Mesh {
     }
 }
 
-bench_dataset!(Mesh = generate_mesh());
+pub fn access_mesh(mesh: &ArchivedMesh) -> [f32; 3] {
+    let mut normal = [0.0; 3];
+    for triangle in mesh.triangles.iter() {
+        normal[0] += triangle.normal.x.to_native();
+        normal[1] += triangle.normal.y.to_native();
+        normal[2] += triangle.normal.z.to_native();
+    }
+    normal
+}
+
+bench_dataset!(
+    Mesh = generate_mesh();
+    access(access_mesh);
+    compare(bincode, postcard, serde_json)
+);
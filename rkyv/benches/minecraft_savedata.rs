@@ -1,7 +1,17 @@
 use benchlib::{bench_dataset, generate_vec, Generate, Rng};
 use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 
-#[derive(Archive, Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(
+    Archive,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    SerdeSerialize,
+    SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 #[repr(u8)]
 pub enum GameType {
@@ -23,7 +33,9 @@ impl Generate for GameType {
     }
 }
 
-#[derive(Archive, Serialize, Deserialize, Debug)]
+#[derive(
+    Archive, Serialize, Deserialize, Debug, SerdeSerialize, SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Item {
     count: i8,
@@ -51,7 +63,17 @@ impl Generate for Item {
     }
 }
 
-#[derive(Archive, Serialize, Clone, Copy, Deserialize, Debug)]
+#[derive(
+    Archive,
+    Serialize,
+    Clone,
+    Copy,
+    Deserialize,
+    Debug,
+    SerdeSerialize,
+    SerdeDeserialize,
+    Generate,
+)]
 #[archive(check_bytes)]
 pub struct Abilities {
     walk_speed: f32,
@@ -63,21 +85,9 @@ pub struct Abilities {
     instabuild: bool,
 }
 
-impl Generate for Abilities {
-    fn generate<R: Rng>(rng: &mut R) -> Self {
-        Self {
-            walk_speed: rng.gen(),
-            fly_speed: rng.gen(),
-            may_fly: rng.gen_bool(0.5),
-            flying: rng.gen_bool(0.5),
-            invulnerable: rng.gen_bool(0.5),
-            may_build: rng.gen_bool(0.5),
-            instabuild: rng.gen_bool(0.5),
-        }
-    }
-}
-
-#[derive(Archive, Serialize, Deserialize, Debug)]
+#[derive(
+    Archive, Serialize, Deserialize, Debug, SerdeSerialize, SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Entity {
     id: String,
@@ -132,7 +142,9 @@ impl Generate for Entity {
     }
 }
 
-#[derive(Archive, Serialize, Deserialize, Debug)]
+#[derive(
+    Archive, Serialize, Deserialize, Debug, SerdeSerialize, SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct RecipeBook {
     recipes: Vec<String>,
@@ -185,7 +197,9 @@ impl Generate for RecipeBook {
     }
 }
 
-#[derive(Archive, Serialize, Deserialize, Debug)]
+#[derive(
+    Archive, Serialize, Deserialize, Debug, SerdeSerialize, SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct RootVehicle {
     attach: [u32; 4],
@@ -201,7 +215,9 @@ impl Generate for RootVehicle {
     }
 }
 
-#[derive(Archive, Serialize, Deserialize, Debug)]
+#[derive(
+    Archive, Serialize, Deserialize, Debug, SerdeSerialize, SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Player {
     game_type: GameType,
@@ -234,7 +250,9 @@ pub struct Player {
     recipe_book: RecipeBook,
 }
 
-#[derive(Archive, Serialize, Deserialize, Debug)]
+#[derive(
+    Archive, Serialize, Deserialize, Debug, SerdeSerialize, SerdeDeserialize,
+)]
 #[archive(check_bytes)]
 pub struct Players {
     pub players: Vec<Player>,
@@ -292,4 +310,29 @@ pub fn generate_players() -> Players {
     }
 }
 
-bench_dataset!(Players = generate_players());
+pub fn access_players(players: &ArchivedPlayers) -> (u64, usize, usize) {
+    let mut score = 0;
+    let mut items = 0;
+    let mut riding = 0;
+    for player in players.players.iter() {
+        if let ArchivedGameType::Survival = player.game_type {
+            score += player.score.to_native();
+        }
+        items += player
+            .inventory
+            .iter()
+            .chain(player.ender_items.iter())
+            .filter(|item| item.count > 0)
+            .count();
+        if player.root_vehicle.is_some() {
+            riding += 1;
+        }
+    }
+    (score, items, riding)
+}
+
+bench_dataset!(
+    Players = generate_players();
+    access(access_players);
+    compare(bincode, postcard, serde_json)
+);
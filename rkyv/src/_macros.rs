@@ -20,3 +20,43 @@ macro_rules! match_pointer_width {
         $s64
     };
 }
+
+/// Asserts at compile time that the fields of a type are located at the given
+/// offsets.
+///
+/// This is useful with `#[archive(assert_layout(...))]` to lock down the
+/// layout of archived types which are stored long-term, so that accidental
+/// layout changes fail the build.
+///
+/// # Example
+/// ```
+/// use rkyv::{assert_offsets, Archive};
+///
+/// #[derive(Archive)]
+/// #[archive(assert_layout(size = 8, align = 4))]
+/// struct Example {
+///     id: u32,
+///     flag: bool,
+/// }
+///
+/// assert_offsets!(ArchivedExample { id: 0, flag: 4 });
+/// ```
+#[macro_export]
+macro_rules! assert_offsets {
+    ($ty:ty { $($field:tt: $offset:expr),* $(,)? }) => {
+        const _: () = {
+            $(
+                assert!(
+                    ::core::mem::offset_of!($ty, $field) == $offset,
+                    concat!(
+                        "unexpected offset for field `",
+                        stringify!($field),
+                        "` of `",
+                        stringify!($ty),
+                        "`",
+                    ),
+                );
+            )*
+        };
+    };
+}
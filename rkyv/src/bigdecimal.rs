@@ -0,0 +1,220 @@
+//! Archived versions of `bigdecimal` types.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{cmp::Ordering, fmt};
+
+use bigdecimal::num_bigint::{BigUint, Sign};
+
+use crate::{
+    place::Initialized,
+    primitive::{ArchivedI64, ArchivedU32},
+    vec::{ArchivedVec, VecResolver},
+    Portable,
+};
+
+/// The sign of an [`ArchivedBigDecimal`].
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u8)]
+pub enum ArchivedSign {
+    /// The value is negative.
+    Minus,
+    /// The value is zero.
+    NoSign,
+    /// The value is positive.
+    Plus,
+}
+
+// SAFETY: `ArchivedSign` is `repr(u8)` and so is always initialized.
+unsafe impl Initialized for ArchivedSign {}
+
+impl From<Sign> for ArchivedSign {
+    #[inline]
+    fn from(sign: Sign) -> Self {
+        match sign {
+            Sign::Minus => Self::Minus,
+            Sign::NoSign => Self::NoSign,
+            Sign::Plus => Self::Plus,
+        }
+    }
+}
+
+impl From<ArchivedSign> for Sign {
+    #[inline]
+    fn from(sign: ArchivedSign) -> Self {
+        match sign {
+            ArchivedSign::Minus => Self::Minus,
+            ArchivedSign::NoSign => Self::NoSign,
+            ArchivedSign::Plus => Self::Plus,
+        }
+    }
+}
+
+/// An archived [`BigDecimal`](bigdecimal::BigDecimal).
+///
+/// The value is stored as a sign, the 32-bit digits of its unscaled magnitude
+/// from least to most significant, and a scale. Archived decimals are
+/// compared by value, so `1.0` and `1.00` are equal.
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(C)]
+pub struct ArchivedBigDecimal {
+    pub(crate) digits: ArchivedVec<ArchivedU32>,
+    pub(crate) scale: ArchivedI64,
+    pub(crate) sign: ArchivedSign,
+}
+
+impl ArchivedBigDecimal {
+    /// Returns the sign of this decimal.
+    #[inline]
+    pub fn sign(&self) -> Sign {
+        if self.is_zero() {
+            Sign::NoSign
+        } else {
+            self.sign.into()
+        }
+    }
+
+    /// Returns the 32-bit digits of the unscaled magnitude of this decimal,
+    /// from least to most significant.
+    #[inline]
+    pub fn digits(&self) -> &[ArchivedU32] {
+        self.digits.as_slice()
+    }
+
+    /// Returns the scale of this decimal.
+    ///
+    /// The value of the decimal is its unscaled value multiplied by
+    /// `10^-scale`.
+    #[inline]
+    pub const fn scale(&self) -> i64 {
+        self.scale.to_native()
+    }
+
+    /// Returns `true` if this decimal is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.digits().iter().all(|digit| digit.to_native() == 0)
+    }
+
+    /// Returns the unscaled magnitude of this decimal.
+    pub fn magnitude(&self) -> BigUint {
+        BigUint::from_slice(
+            &self
+                .digits()
+                .iter()
+                .map(|digit| digit.to_native())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    pub(crate) fn parts(
+        &self,
+    ) -> (Sign, impl Clone + DoubleEndedIterator<Item = u32> + '_, i64) {
+        (
+            self.sign(),
+            self.digits().iter().map(|digit| digit.to_native()),
+            self.scale(),
+        )
+    }
+}
+
+/// Compares the magnitudes of two decimals, given their 32-bit digits from
+/// least to most significant and their scales.
+fn cmp_magnitudes<A, B>(a: A, a_scale: i64, b: B, b_scale: i64) -> Ordering
+where
+    A: Clone + DoubleEndedIterator<Item = u32>,
+    B: Clone + DoubleEndedIterator<Item = u32>,
+{
+    if a_scale == b_scale {
+        let a = a.rev().skip_while(|&digit| digit == 0);
+        let b = b.rev().skip_while(|&digit| digit == 0);
+        return a
+            .clone()
+            .count()
+            .cmp(&b.clone().count())
+            .then_with(|| a.cmp(b));
+    }
+
+    let mut a = BigUint::from_slice(&a.collect::<Vec<_>>());
+    let mut b = BigUint::from_slice(&b.collect::<Vec<_>>());
+    let exponent = u32::try_from(a_scale.abs_diff(b_scale))
+        .expect("scale difference is too large to compare decimals");
+    let factor = BigUint::from_slice(&[10]).pow(exponent);
+    if a_scale < b_scale {
+        a *= factor;
+    } else {
+        b *= factor;
+    }
+    a.cmp(&b)
+}
+
+/// Compares two decimals, given their signs, 32-bit digits from least to most
+/// significant, and scales.
+pub(crate) fn cmp_decimals<A, B>(
+    (a_sign, a, a_scale): (Sign, A, i64),
+    (b_sign, b, b_scale): (Sign, B, i64),
+) -> Ordering
+where
+    A: Clone + DoubleEndedIterator<Item = u32>,
+    B: Clone + DoubleEndedIterator<Item = u32>,
+{
+    fn rank(sign: Sign) -> i8 {
+        match sign {
+            Sign::Minus => -1,
+            Sign::NoSign => 0,
+            Sign::Plus => 1,
+        }
+    }
+
+    match (a_sign, b_sign) {
+        (Sign::Plus, Sign::Plus) => cmp_magnitudes(a, a_scale, b, b_scale),
+        (Sign::Minus, Sign::Minus) => cmp_magnitudes(b, b_scale, a, a_scale),
+        _ => rank(a_sign).cmp(&rank(b_sign)),
+    }
+}
+
+impl fmt::Debug for ArchivedBigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedBigDecimal")
+            .field("sign", &self.sign())
+            .field("magnitude", &self.magnitude())
+            .field("scale", &self.scale())
+            .finish()
+    }
+}
+
+impl Eq for ArchivedBigDecimal {}
+
+impl PartialEq for ArchivedBigDecimal {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Ord for ArchivedBigDecimal {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_decimals(self.parts(), other.parts())
+    }
+}
+
+impl PartialOrd for ArchivedBigDecimal {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The resolver for an [`ArchivedBigDecimal`].
+pub struct BigDecimalResolver {
+    pub(crate) digits: VecResolver,
+    pub(crate) digits_len: usize,
+    pub(crate) sign: Sign,
+    pub(crate) scale: i64,
+}
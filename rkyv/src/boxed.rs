@@ -1,18 +1,23 @@
 //! An archived version of `Box`.
 
-use core::{borrow::Borrow, cmp, fmt, hash, ops::Deref, pin::Pin};
+use core::{borrow::Borrow, cmp, fmt, hash, ops::Deref, pin::Pin, ptr};
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 
 use crate::{
-    ArchivePointee, ArchiveUnsized, Place, Portable, RelPtr, SerializeUnsized,
+    endian::SwapEndian,
+    rel_ptr::{DefaultRepr, PointerRepr, RelPtr},
+    relocate::{OffsetMode, Relocate},
+    ArchivePointee, ArchiveUnsized, Place, Portable, SerializeUnsized,
 };
 
 /// An archived [`Box`].
 ///
 /// This is a thin `#[repr(transparent)]` wrapper around a [`RelPtr`] to the
-/// archived type.
+/// archived type. The representation of the relative pointer is selected by
+/// `P`, see [`PointerRepr`] for more details. The metadata of unsized types is
+/// always archived with the types selected by the crate features.
 #[derive(Portable)]
 #[archive(crate)]
 #[cfg_attr(
@@ -21,11 +26,12 @@ use crate::{
     check_bytes(verify)
 )]
 #[repr(transparent)]
-pub struct ArchivedBox<T: ArchivePointee + ?Sized> {
-    ptr: RelPtr<T>,
+pub struct ArchivedBox<T: ArchivePointee + ?Sized, P: PointerRepr = DefaultRepr>
+{
+    ptr: RelPtr<T, P::Offset>,
 }
 
-impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> ArchivedBox<T, P> {
     /// Returns a reference to the value of this archived box.
     pub fn get(&self) -> &T {
         unsafe { &*self.ptr.as_ptr() }
@@ -46,7 +52,22 @@ impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
         Self::resolve_from_raw_parts(resolver, value.archived_metadata(), out)
     }
 
+    /// Resolves an archived box from a [`BoxResolver`] and the raw metadata
+    /// directly.
+    pub fn resolve_from_raw_parts(
+        resolver: BoxResolver,
+        metadata: T::ArchivedMetadata,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedBox { ptr } = out);
+        RelPtr::emplace_unsized(resolver.pos, metadata, ptr);
+    }
+}
+
+impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
     /// Serializes an archived box from the given value and serializer.
+    ///
+    /// The resolver can be used with any pointer representation.
     pub fn serialize_from_ref<U, S>(
         value: &U,
         serializer: &mut S,
@@ -59,41 +80,36 @@ impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
             pos: value.serialize_unsized(serializer)?,
         })
     }
-
-    /// Resolves an archived box from a [`BoxResolver`] and the raw metadata
-    /// directly.
-    pub fn resolve_from_raw_parts(
-        resolver: BoxResolver,
-        metadata: T::ArchivedMetadata,
-        out: Place<Self>,
-    ) {
-        munge!(let ArchivedBox { ptr } = out);
-        RelPtr::emplace_unsized(resolver.pos, metadata, ptr);
-    }
 }
 
-impl<T: ArchivePointee + ?Sized> AsRef<T> for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> AsRef<T>
+    for ArchivedBox<T, P>
+{
     fn as_ref(&self) -> &T {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + ?Sized> Borrow<T> for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> Borrow<T>
+    for ArchivedBox<T, P>
+{
     fn borrow(&self) -> &T {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + ?Sized> fmt::Debug for ArchivedBox<T>
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> fmt::Debug
+    for ArchivedBox<T, P>
 where
     T::ArchivedMetadata: fmt::Debug,
+    P::Offset: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("ArchivedBox").field(&self.ptr).finish()
     }
 }
 
-impl<T: ArchivePointee + ?Sized> Deref for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> Deref for ArchivedBox<T, P> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -101,49 +117,103 @@ impl<T: ArchivePointee + ?Sized> Deref for ArchivedBox<T> {
     }
 }
 
-impl<T: ArchivePointee + fmt::Display + ?Sized> fmt::Display
-    for ArchivedBox<T>
+impl<T: ArchivePointee + fmt::Display + ?Sized, P: PointerRepr> fmt::Display
+    for ArchivedBox<T, P>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.get().fmt(f)
     }
 }
 
-impl<T: ArchivePointee + Eq + ?Sized> Eq for ArchivedBox<T> {}
+impl<T, P> Eq for ArchivedBox<T, P>
+where
+    T: ArchivePointee + Eq + ?Sized,
+    P: PointerRepr,
+{
+}
 
-impl<T: ArchivePointee + hash::Hash + ?Sized> hash::Hash for ArchivedBox<T> {
+impl<T: ArchivePointee + hash::Hash + ?Sized, P: PointerRepr> hash::Hash
+    for ArchivedBox<T, P>
+{
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.get().hash(state);
     }
 }
 
-impl<T: ArchivePointee + Ord + ?Sized> Ord for ArchivedBox<T> {
+impl<T: ArchivePointee + Ord + ?Sized, P: PointerRepr> Ord
+    for ArchivedBox<T, P>
+{
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_ref().cmp(other.as_ref())
     }
 }
 
-impl<T: ArchivePointee + PartialEq<U> + ?Sized, U: ArchivePointee + ?Sized>
-    PartialEq<ArchivedBox<U>> for ArchivedBox<T>
+impl<T, U, P, Q> PartialEq<ArchivedBox<U, Q>> for ArchivedBox<T, P>
+where
+    T: ArchivePointee + PartialEq<U> + ?Sized,
+    U: ArchivePointee + ?Sized,
+    P: PointerRepr,
+    Q: PointerRepr,
 {
-    fn eq(&self, other: &ArchivedBox<U>) -> bool {
+    fn eq(&self, other: &ArchivedBox<U, Q>) -> bool {
         self.get().eq(other.get())
     }
 }
 
-impl<T: ArchivePointee + PartialOrd + ?Sized> PartialOrd for ArchivedBox<T> {
+impl<T: ArchivePointee + PartialOrd + ?Sized, P: PointerRepr> PartialOrd
+    for ArchivedBox<T, P>
+{
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         self.get().partial_cmp(other.get())
     }
 }
 
-impl<T: ArchivePointee + ?Sized> fmt::Pointer for ArchivedBox<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> fmt::Pointer
+    for ArchivedBox<T, P>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let ptr = self.get() as *const T;
         fmt::Pointer::fmt(&ptr, f)
     }
 }
 
+// SAFETY: `ArchivedBox` is a transparent wrapper around a `RelPtr`.
+unsafe impl<T, P> SwapEndian for ArchivedBox<T, P>
+where
+    T: ArchivePointee + SwapEndian + ?Sized,
+    T::ArchivedMetadata: SwapEndian,
+    P: PointerRepr,
+    P::Offset: SwapEndian,
+{
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `swap_endian`, and so does its relative pointer.
+        unsafe {
+            RelPtr::swap_endian(ptr::addr_of_mut!((*this).ptr));
+        }
+    }
+}
+
+// SAFETY: `ArchivedBox` is a transparent wrapper around a `RelPtr`.
+unsafe impl<T, P> Relocate for ArchivedBox<T, P>
+where
+    T: ArchivePointee + Relocate + ?Sized,
+    P: PointerRepr,
+    P::Offset: Portable,
+{
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `relocate`, and so does its relative pointer.
+        unsafe { RelPtr::relocate(ptr::addr_of_mut!((*this).ptr), base, mode) }
+    }
+}
+
 /// The resolver for `Box`.
 pub struct BoxResolver {
     pos: usize,
@@ -168,13 +238,15 @@ mod verify {
 
     use crate::{
         boxed::ArchivedBox,
+        rel_ptr::PointerRepr,
         validation::{ArchiveContext, ArchiveContextExt},
         ArchivePointee, LayoutRaw,
     };
 
-    unsafe impl<T, C> Verify<C> for ArchivedBox<T>
+    unsafe impl<T, P, C> Verify<C> for ArchivedBox<T, P>
     where
         T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized,
+        P: PointerRepr,
         T::ArchivedMetadata: CheckBytes<C>,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
@@ -11,13 +11,13 @@ use core::{
 };
 
 use munge::munge;
-use rancor::{fail, Fallible, Source};
+use rancor::{fail, Failure, Fallible, Source};
 
 use crate::{
     collections::util::IteratorLengthMismatch,
     place::Initialized,
-    primitive::{ArchivedUsize, FixedUsize},
-    ser::{Allocator, Writer, WriterExt as _},
+    primitive::{fixed_usize, ArchivedIsize, ArchivedUsize},
+    ser::{writer::OverflowError, Allocator, Writer, WriterExt as _},
     util::{InlineVec, SerVec},
     Place, Portable, RawRelPtr, Serialize,
 };
@@ -261,7 +261,49 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
             RawRelPtr::emplace(resolver.root_node_pos, root);
         }
 
-        out_len.write(ArchivedUsize::from_native(len as FixedUsize));
+        out_len.write(ArchivedUsize::from_native(fixed_usize(len)));
+    }
+
+    /// Serializes an `ArchivedBTreeMap` from the given unordered iterator and
+    /// serializer.
+    ///
+    /// The entries are sorted by key in serializer scratch space before
+    /// serializing. Returns an error if the iterator yields the same key more
+    /// than once.
+    pub fn serialize_from_iter<'a, I, UK, UV, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<BTreeMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
+        UK: 'a + Serialize<S, Archived = K> + Ord,
+        UV: 'a + Serialize<S, Archived = V>,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::with_capacity(serializer, len, |entries, serializer| {
+            for entry in iter {
+                if entries.len() == len {
+                    fail!(IteratorLengthMismatch {
+                        expected: len,
+                        actual: len + 1,
+                    });
+                }
+                entries.push(entry);
+            }
+
+            entries.as_mut_slice().sort_unstable_by_key(|&(key, _)| key);
+            if entries.as_slice().windows(2).any(|w| w[0].0 == w[1].0) {
+                fail!(DuplicateKey);
+            }
+
+            Self::serialize_from_ordered_iter(
+                entries.as_slice().iter().copied(),
+                serializer,
+            )
+        })?
     }
 
     /// Serializes an `ArchivedBTreeMap` from the given iterator and serializer.
@@ -277,6 +319,7 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
         S::Error: Source,
     {
         let len = iter.len();
+        serializer.check_len(len)?;
 
         if len == 0 {
             let actual = iter.count();
@@ -392,9 +435,10 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
                     });
                 }
 
-                Ok(BTreeMapResolver {
-                    root_node_pos: child_node_pos.unwrap(),
-                })
+                let root_node_pos = child_node_pos.unwrap();
+                serializer.check_offset::<ArchivedIsize>(root_node_pos)?;
+
+                Ok(BTreeMapResolver { root_node_pos })
             },
         )?
     }
@@ -424,7 +468,7 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
 
         munge!(let Node { kind, len, keys, values } = node_place);
         kind.write(NodeKind::Leaf);
-        len.write(ArchivedUsize::from_native(items.len() as FixedUsize));
+        len.write(ArchivedUsize::from_native(fixed_usize(items.len())));
         for (i, ((k, v), (kr, vr))) in
             items.iter().zip(resolvers.drain()).enumerate()
         {
@@ -483,7 +527,7 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
         }
 
         kind.write(NodeKind::Inner);
-        len.write(ArchivedUsize::from_native(items.len() as FixedUsize));
+        len.write(ArchivedUsize::from_native(fixed_usize(items.len())));
         for (i, ((k, v, l), (kr, vr))) in
             items.iter().zip(resolvers.drain()).enumerate()
         {
@@ -495,14 +539,14 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
             let out_lesser_node =
                 unsafe { lesser_nodes.index(i).cast_unchecked() };
             if let Some(lesser_node) = l {
-                RawRelPtr::emplace(*lesser_node, out_lesser_node);
+                emplace_node_ptr(serializer, *lesser_node, out_lesser_node)?;
             } else {
                 RawRelPtr::emplace_invalid(out_lesser_node);
             }
         }
 
         if let Some(greater_node_pos) = greater_node_pos {
-            RawRelPtr::emplace(greater_node_pos, greater_node);
+            emplace_node_ptr(serializer, greater_node_pos, greater_node)?;
         } else {
             RawRelPtr::emplace_invalid(greater_node);
         }
@@ -598,6 +642,39 @@ where
     }
 }
 
+/// An error resulting from serializing a B-tree map from an iterator which
+/// yielded the same key more than once.
+#[derive(Debug)]
+struct DuplicateKey;
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "iterator yielded the same key more than once")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DuplicateKey {}
+
+/// Emplaces a pointer to a child node in an inner node which hasn't been
+/// written yet.
+///
+/// The inner node is resolved before it's written, so the offset is checked
+/// against the position of the pointer instead of the serializer position.
+fn emplace_node_ptr<S: Writer + Fallible + ?Sized>(
+    serializer: &S,
+    to: usize,
+    out: Place<RawRelPtr>,
+) -> Result<(), S::Error> {
+    RawRelPtr::try_emplace::<Failure>(to, out).map_err(|_| {
+        serializer.overflow(OverflowError::Offset {
+            from: out.pos(),
+            to,
+            offset_size: size_of::<ArchivedIsize>(),
+        })
+    })
+}
+
 /// The resolver for [`ArchivedBTreeMap`].
 pub struct BTreeMapResolver {
     root_node_pos: usize,
@@ -64,6 +64,28 @@ impl<K, const E: usize> ArchivedBTreeSet<K, E> {
         ArchivedBTreeMap::<K, (), E>::resolve_from_len(len, resolver.0, inner);
     }
 
+    /// Serializes an `ArchivedBTreeSet` from the given unordered iterator and
+    /// serializer.
+    ///
+    /// The keys are sorted in serializer scratch space before serializing.
+    /// Returns an error if the iterator yields the same key more than once.
+    pub fn serialize_from_iter<'a, I, UK, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<BTreeSetResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = &'a UK>,
+        UK: 'a + Serialize<S, Archived = K> + Ord,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        ArchivedBTreeMap::<K, (), E>::serialize_from_iter(
+            iter.map(|k| (k, &())),
+            serializer,
+        )
+        .map(BTreeSetResolver)
+    }
+
     /// Serializes an `ArchivedBTreeSet` from the given iterator and serializer.
     pub fn serialize_from_ordered_iter<'a, I, UK, S>(
         iter: I,
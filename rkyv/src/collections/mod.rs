@@ -2,5 +2,6 @@
 
 pub mod btree_map;
 pub mod btree_set;
+pub mod str_table;
 pub mod swiss_table;
 pub mod util;
@@ -0,0 +1,573 @@
+//! A front-coded table of sorted strings.
+//!
+//! [`ArchivedSortedStrings`] stores a sorted list of strings with their shared
+//! prefixes removed. The strings are split into blocks of [`BLOCK_LEN`]
+//! strings. The first string of each block is stored in full, and each string
+//! after it only stores the bytes following the prefix it shares with the
+//! string before it. Any string can be rebuilt by decoding at most one block,
+//! and lookups binary search the first strings of each block.
+
+use core::{cmp::Ordering, fmt, iter::FusedIterator, str};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::IteratorLengthMismatch,
+    primitive::{ArchivedIsize, ArchivedUsize},
+    ser::{Allocator, Writer, WriterExt as _},
+    util::{read_varint, write_varint, SerVec},
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Place, Portable,
+};
+
+/// The number of strings in each block of an [`ArchivedSortedStrings`].
+pub const BLOCK_LEN: usize = 16;
+
+/// An archived table of sorted strings which are stored with shared-prefix
+/// compression.
+///
+/// Each entry is stored as a LEB128 varint prefix length, a LEB128 varint
+/// suffix length, and then the bytes of the suffix. The restart offsets point
+/// to the first entry of each block, which always has a prefix length of zero.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct ArchivedSortedStrings {
+    data: ArchivedVec<u8>,
+    restarts: ArchivedVec<ArchivedUsize>,
+    len: ArchivedUsize,
+}
+
+impl ArchivedSortedStrings {
+    /// Returns the number of strings in the table.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the table contains no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the string at the given index, or `None` if the index is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<FrontCodedStr<'_>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut pos = self.block_start(index / BLOCK_LEN);
+        let mut entries = [(0, ""); BLOCK_LEN];
+        let count = index % BLOCK_LEN + 1;
+        for entry in entries[..count].iter_mut() {
+            *entry = self.entry(&mut pos);
+        }
+
+        Some(FrontCodedStr::from_entries(&entries[..count]))
+    }
+
+    /// Binary searches the table for the given string.
+    ///
+    /// If the string is found, returns `Ok` with its index. If there are
+    /// multiple copies of the string, any one of their indices may be
+    /// returned. If the string is not found, returns `Err` with the index
+    /// where it could be inserted while keeping the table sorted.
+    pub fn binary_search(&self, value: &str) -> Result<usize, usize> {
+        // Find the first block which starts with a string greater than `value`
+        let mut lo = 0;
+        let mut hi = self.restarts.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.block_first(mid) <= value {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        if lo == 0 {
+            return Err(0);
+        }
+
+        // `value` can only be in the block before it
+        let block = lo - 1;
+        let start = block * BLOCK_LEN;
+        let count = usize::min(BLOCK_LEN, self.len() - start);
+        let mut pos = self.block_start(block);
+        let mut entries = [(0, ""); BLOCK_LEN];
+        for i in 0..count {
+            entries[i] = self.entry(&mut pos);
+            let string = FrontCodedStr::from_entries(&entries[..=i]);
+            match string.cmp_str(value) {
+                Ordering::Less => (),
+                Ordering::Equal => return Ok(start + i),
+                Ordering::Greater => return Err(start + i),
+            }
+        }
+
+        Err(start + count)
+    }
+
+    /// Returns whether the table contains the given string.
+    pub fn contains(&self, value: &str) -> bool {
+        self.binary_search(value).is_ok()
+    }
+
+    /// Returns an iterator over the strings in the table.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            strings: self,
+            index: 0,
+        }
+    }
+
+    fn block_start(&self, block: usize) -> usize {
+        self.restarts[block].to_native() as usize
+    }
+
+    fn block_first(&self, block: usize) -> &str {
+        let mut pos = self.block_start(block);
+        self.entry(&mut pos).1
+    }
+
+    fn entry(&self, pos: &mut usize) -> (usize, &str) {
+        let (prefix, suffix) = decode_entry(self.data.as_slice(), pos)
+            .expect("invalid front-coded string table");
+        // SAFETY: The table is valid, so every suffix is valid UTF-8.
+        (prefix, unsafe { str::from_utf8_unchecked(suffix) })
+    }
+
+    /// Resolves an archived string table from the number of strings in it.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: SortedStringsResolver,
+        out: Place<Self>,
+    ) {
+        munge! {
+            let ArchivedSortedStrings { data, restarts, len: out_len } = out;
+        }
+        ArchivedVec::resolve_from_len(
+            resolver.data_len,
+            VecResolver::from_pos(resolver.data_pos),
+            data,
+        );
+        ArchivedVec::resolve_from_len(
+            len.div_ceil(BLOCK_LEN),
+            resolver.restarts,
+            restarts,
+        );
+        len.resolve((), out_len);
+    }
+
+    /// Serializes an archived string table from an iterator of sorted strings.
+    ///
+    /// Returns an error if the strings are not sorted. Duplicate strings are
+    /// allowed.
+    pub fn serialize_from_sorted_iter<'a, I, S>(
+        mut iter: I,
+        serializer: &mut S,
+    ) -> Result<SortedStringsResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = &'a str>,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+        serializer.check_len(len)?;
+        let blocks = len.div_ceil(BLOCK_LEN);
+
+        SerVec::with_capacity(serializer, blocks, |restarts, serializer| {
+            let data_pos = serializer.pos();
+            let mut prev: Option<&str> = None;
+            let mut count = 0;
+
+            for value in iter.by_ref().take(len) {
+                let mut prefix = 0;
+                if let Some(prev) = prev {
+                    if prev > value {
+                        fail!(UnsortedStrings);
+                    }
+                    if count % BLOCK_LEN != 0 {
+                        prefix = shared_prefix_len(prev, value);
+                    }
+                }
+
+                if count % BLOCK_LEN == 0 {
+                    restarts.push(serializer.pos() - data_pos);
+                }
+                write_varint(serializer, prefix)?;
+                write_varint(serializer, value.len() - prefix)?;
+                serializer.write(&value.as_bytes()[prefix..])?;
+
+                prev = Some(value);
+                count += 1;
+            }
+
+            let extra = iter.count();
+            if count != len || extra != 0 {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: count + extra,
+                });
+            }
+
+            let data_len = serializer.pos() - data_pos;
+            serializer.check_len(data_len)?;
+            let restarts = ArchivedVec::<ArchivedUsize>::serialize_from_slice(
+                restarts.as_slice(),
+                serializer,
+            )?;
+            serializer.check_offset::<ArchivedIsize>(data_pos)?;
+
+            Ok(SortedStringsResolver {
+                data_pos,
+                data_len,
+                restarts,
+            })
+        })?
+    }
+}
+
+impl fmt::Debug for ArchivedSortedStrings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a ArchivedSortedStrings {
+    type Item = FrontCodedStr<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The resolver for [`ArchivedSortedStrings`].
+pub struct SortedStringsResolver {
+    data_pos: usize,
+    data_len: usize,
+    restarts: VecResolver,
+}
+
+/// A string from an [`ArchivedSortedStrings`].
+///
+/// Strings which share a prefix with the strings before them are not stored
+/// contiguously, so they are made up of several pieces. Strings can be
+/// compared and formatted without allocating.
+#[derive(Clone, Copy)]
+pub struct FrontCodedStr<'a> {
+    pieces: [&'a str; BLOCK_LEN],
+    count: usize,
+    len: usize,
+}
+
+impl<'a> FrontCodedStr<'a> {
+    fn from_entries(entries: &[(usize, &'a str)]) -> Self {
+        let (last, rest) = entries.split_last().unwrap();
+        let mut pieces = [""; BLOCK_LEN];
+        pieces[0] = last.1;
+        let mut count = 1;
+
+        // Walk back through the block, taking the part of each suffix which
+        // is still covered by the prefix of the string.
+        let mut limit = last.0;
+        for &(prefix, suffix) in rest.iter().rev() {
+            if limit == 0 {
+                break;
+            }
+            if limit > prefix {
+                pieces[count] = &suffix[..limit - prefix];
+                count += 1;
+                limit = prefix;
+            }
+        }
+        pieces[..count].reverse();
+
+        Self {
+            pieces,
+            count,
+            len: last.0 + last.1.len(),
+        }
+    }
+
+    /// Returns the length of the string in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the pieces which make up the string, in order.
+    pub fn pieces(&self) -> &[&'a str] {
+        &self.pieces[..self.count]
+    }
+
+    /// Returns the string as a single `str` if it is stored contiguously.
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self.pieces() {
+            [] => Some(""),
+            [piece] => Some(piece),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the bytes of the string.
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.pieces().iter().flat_map(|piece| piece.bytes())
+    }
+
+    /// Returns an iterator over the `char`s of the string.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.pieces().iter().flat_map(|piece| piece.chars())
+    }
+
+    fn cmp_str(&self, other: &str) -> Ordering {
+        self.bytes().cmp(other.bytes())
+    }
+}
+
+impl fmt::Debug for FrontCodedStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Write as _;
+
+        f.write_char('"')?;
+        for piece in self.pieces() {
+            write!(f, "{}", piece.escape_debug())?;
+        }
+        f.write_char('"')
+    }
+}
+
+impl fmt::Display for FrontCodedStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for piece in self.pieces() {
+            f.write_str(piece)?;
+        }
+        Ok(())
+    }
+}
+
+impl Eq for FrontCodedStr<'_> {}
+
+impl Ord for FrontCodedStr<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bytes().cmp(other.bytes())
+    }
+}
+
+impl PartialEq for FrontCodedStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.bytes().eq(other.bytes())
+    }
+}
+
+impl PartialEq<str> for FrontCodedStr<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.len == other.len() && self.bytes().eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for FrontCodedStr<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.eq(*other)
+    }
+}
+
+impl PartialEq<FrontCodedStr<'_>> for str {
+    fn eq(&self, other: &FrontCodedStr<'_>) -> bool {
+        other.eq(self)
+    }
+}
+
+impl PartialEq<FrontCodedStr<'_>> for &str {
+    fn eq(&self, other: &FrontCodedStr<'_>) -> bool {
+        other.eq(*self)
+    }
+}
+
+impl PartialOrd for FrontCodedStr<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialOrd<str> for FrontCodedStr<'_> {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        Some(self.cmp_str(other))
+    }
+}
+
+/// An iterator over the strings of an [`ArchivedSortedStrings`].
+///
+/// This struct is created by [`ArchivedSortedStrings::iter`].
+pub struct Iter<'a> {
+    strings: &'a ArchivedSortedStrings,
+    index: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = FrontCodedStr<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.strings.get(self.index)?;
+        self.index += 1;
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.strings.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+#[derive(Debug)]
+struct UnsortedStrings;
+
+impl fmt::Display for UnsortedStrings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "strings in a sorted string table must be sorted")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsortedStrings {}
+
+fn shared_prefix_len(a: &str, b: &str) -> usize {
+    let mut len = a.bytes().zip(b.bytes()).take_while(|(a, b)| a == b).count();
+    // The prefix must end on a char boundary so that suffixes are valid UTF-8
+    while !b.is_char_boundary(len) {
+        len -= 1;
+    }
+    len
+}
+
+fn decode_entry<'a>(
+    data: &'a [u8],
+    pos: &mut usize,
+) -> Option<(usize, &'a [u8])> {
+    let prefix = read_varint(data, pos)?;
+    let suffix_len = read_varint(data, pos)?;
+    let end = pos.checked_add(suffix_len)?;
+    let suffix = data.get(*pos..end)?;
+    *pos = end;
+    Some((prefix, suffix))
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{fmt, str};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::{decode_entry, ArchivedSortedStrings, BLOCK_LEN};
+    use crate::validation::ArchiveContext;
+
+    #[derive(Debug)]
+    struct InvalidSortedStrings {
+        reason: &'static str,
+    }
+
+    impl fmt::Display for InvalidSortedStrings {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid sorted string table: {}", self.reason)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for InvalidSortedStrings {}
+
+    /// Returns the byte at `index` of the last entry of a block.
+    fn byte_at(entries: &[(usize, &[u8])], index: usize) -> u8 {
+        for &(prefix, suffix) in entries.iter().rev() {
+            if index >= prefix {
+                return suffix[index - prefix];
+            }
+        }
+        unreachable!("the first entry of a block has no prefix")
+    }
+
+    unsafe impl<C> Verify<C> for ArchivedSortedStrings
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            let blocks = len.div_ceil(BLOCK_LEN);
+            if self.restarts.len() != blocks {
+                fail!(InvalidSortedStrings {
+                    reason: "wrong number of restarts",
+                });
+            }
+
+            let data = self.data.as_slice();
+            let mut pos = 0;
+            for block in 0..blocks {
+                if self.block_start(block) != pos {
+                    fail!(InvalidSortedStrings {
+                        reason: "restart does not point to the start of a \
+                                 block",
+                    });
+                }
+
+                let count = usize::min(BLOCK_LEN, len - block * BLOCK_LEN);
+                let mut entries = [(0, &[][..]); BLOCK_LEN];
+                let mut prev_len = 0;
+                for i in 0..count {
+                    let Some((prefix, suffix)) = decode_entry(data, &mut pos)
+                    else {
+                        fail!(InvalidSortedStrings {
+                            reason: "entry is out of bounds",
+                        });
+                    };
+                    if prefix > prev_len {
+                        fail!(InvalidSortedStrings {
+                            reason: "prefix is longer than the previous string",
+                        });
+                    }
+                    if prefix < prev_len
+                        && byte_at(&entries[..i], prefix) & 0xc0 == 0x80
+                    {
+                        fail!(InvalidSortedStrings {
+                            reason: "prefix does not end on a char boundary",
+                        });
+                    }
+                    if str::from_utf8(suffix).is_err() {
+                        fail!(InvalidSortedStrings {
+                            reason: "suffix is not valid UTF-8",
+                        });
+                    }
+
+                    entries[i] = (prefix, suffix);
+                    prev_len = prefix + suffix.len();
+                }
+            }
+
+            if pos != data.len() {
+                fail!(InvalidSortedStrings {
+                    reason: "trailing bytes after the last entry",
+                });
+            }
+
+            Ok(())
+        }
+    }
+}
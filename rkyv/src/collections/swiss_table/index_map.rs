@@ -20,7 +20,7 @@ use crate::{
         util::{Entry, EntryAdapter, EntryResolver},
     },
     hash::{hash_value, FxHasher64},
-    primitive::ArchivedUsize,
+    primitive::{ArchivedIsize, ArchivedUsize},
     ser::{Allocator, Writer, WriterExt as _},
     Place, Portable, RelPtr, Serialize,
 };
@@ -347,6 +347,7 @@ impl<K, V, H: Hasher + Default> ArchivedIndexMap<K, V, H> {
                         )?;
                     }
                 }
+                serializer.check_offset::<ArchivedIsize>(entries_pos)?;
 
                 Ok(IndexMapResolver {
                     table_resolver,
@@ -11,15 +11,16 @@ use core::{
 };
 
 use munge::munge;
-use rancor::{Fallible, Source};
+use rancor::{fail, Fallible, Source};
 
 use crate::{
     collections::{
         swiss_table::table::{ArchivedHashTable, HashTableResolver, RawIter},
-        util::{Entry, EntryAdapter},
+        util::{Entry, EntryAdapter, IteratorLengthMismatch},
     },
     hash::{hash_value, FxHasher64},
     ser::{Allocator, Writer},
+    util::SerVec,
     Place, Portable, Serialize,
 };
 
@@ -202,21 +203,92 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
     }
 
     /// Serializes an iterator of key-value pairs as a hash map.
+    ///
+    /// The iterator is only traversed once, so it can be backed by a streaming
+    /// source. The entries are collected in serializer scratch space.
     pub fn serialize_from_iter<'a, I, KU, VU, S>(
         iter: I,
         load_factor: (usize, usize),
         serializer: &mut S,
     ) -> Result<HashMapResolver, S::Error>
     where
-        I: Clone + ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
         KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
         VU: 'a + Serialize<S, Archived = V>,
         S: Fallible + Writer + Allocator + ?Sized,
         S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::with_capacity(serializer, len, |entries, serializer| {
+            for entry in iter {
+                if entries.len() == len {
+                    fail!(IteratorLengthMismatch {
+                        expected: len,
+                        actual: len + 1,
+                    });
+                }
+                entries.push(entry);
+            }
+
+            Self::serialize_from_entries(entries, load_factor, serializer)
+        })?
+    }
+
+    /// Serializes an iterator of owned key-value pairs as a hash map.
+    ///
+    /// The iterator is only traversed once, so it can be backed by a streaming
+    /// source. The entries are moved into serializer scratch space and dropped
+    /// after they have been serialized.
+    pub fn serialize_from_owned_iter<I, KU, VU, S>(
+        iter: I,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (KU, VU)>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::with_capacity(serializer, len, |entries, serializer| {
+            for entry in iter {
+                if entries.len() == len {
+                    fail!(IteratorLengthMismatch {
+                        expected: len,
+                        actual: len + 1,
+                    });
+                }
+                entries.push(entry);
+            }
+
+            Self::serialize_from_iter(
+                entries.iter().map(|(key, value)| (key, value)),
+                load_factor,
+                serializer,
+            )
+        })?
+    }
+
+    fn serialize_from_entries<KU, VU, S>(
+        entries: &[(&KU, &VU)],
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashMapResolver, S::Error>
+    where
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
     {
         ArchivedHashTable::<Entry<K, V>>::serialize_from_iter(
-            iter.clone().map(|(key, value)| EntryAdapter { key, value }),
-            iter.map(|(key, _)| hash_value::<KU, H>(key)),
+            entries
+                .iter()
+                .map(|&(key, value)| EntryAdapter { key, value }),
+            entries.iter().map(|&(key, _)| hash_value::<KU, H>(key)),
             load_factor,
             serializer,
         )
@@ -83,7 +83,7 @@ impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
         KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
         S: Fallible + Writer + Allocator + ?Sized,
         S::Error: Source,
-        I: Clone + ExactSizeIterator<Item = &'a KU>,
+        I: ExactSizeIterator<Item = &'a KU>,
     {
         Ok(HashSetResolver(
             ArchivedHashMap::<K, (), H>::serialize_from_iter(
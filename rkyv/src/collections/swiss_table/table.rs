@@ -31,11 +31,11 @@ use rancor::{fail, Fallible, OptionExt, Panic, ResultExt as _, Source};
 
 use crate::{
     collections::util::IteratorLengthMismatch,
-    primitive::ArchivedUsize,
+    primitive::{ArchivedIsize, ArchivedUsize},
     ser::{Allocator, Writer, WriterExt},
     simd::{Bitmask, Group, MAX_GROUP_WIDTH},
     util::SerVec,
-    Archive as _, Place, Portable, RawRelPtr, Serialize,
+    Archive, Place, Portable, RawRelPtr, Serialize,
 };
 
 /// A low-level archived SwissTable hash table with explicit hashing.
@@ -327,6 +327,8 @@ impl<T> ArchivedHashTable<T> {
 
             // Allocate scratch space for the hash table storage
             let capacity = Self::capacity_from_len(len, load_factor)?;
+            // The length is never greater than the capacity.
+            serializer.check_len(capacity)?;
             let control_count = Self::control_count(capacity)?;
 
             let (layout, control_offset) =
@@ -413,6 +415,7 @@ impl<T> ArchivedHashTable<T> {
             unsafe {
                 serializer.pop_alloc(alloc, layout)?;
             }
+            serializer.check_offset::<ArchivedIsize>(pos + control_offset)?;
 
             Ok(HashTableResolver {
                 pos: pos + control_offset,
@@ -420,6 +423,48 @@ impl<T> ArchivedHashTable<T> {
         })?
     }
 
+    /// Serializes an iterator of items as a hash table, hashing each item with
+    /// the given hash function.
+    ///
+    /// Items must be looked up in the archived hash table with hashes from the
+    /// same hash function.
+    pub fn serialize_from_iter_with_hasher<'a, I, U, F, S>(
+        items: I,
+        hasher: F,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashTableResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = &'a U>,
+        U: 'a + Serialize<S, Archived = T>,
+        F: Fn(&U) -> u64,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = items.len();
+
+        // Collect the items so they can be iterated over more than once
+        SerVec::with_capacity(serializer, len, |refs, serializer| {
+            for item in items {
+                if refs.len() == len {
+                    fail!(IteratorLengthMismatch {
+                        expected: len,
+                        actual: len + 1,
+                    });
+                }
+
+                refs.push(item);
+            }
+
+            Self::serialize_from_iter(
+                refs.iter().map(|&item| ItemAdapter(item)),
+                refs.iter().map(|&item| hasher(item)),
+                load_factor,
+                serializer,
+            )
+        })?
+    }
+
     /// Resolves an archived hash table from a given length and parameters.
     pub fn resolve_from_len(
         len: usize,
@@ -444,6 +489,32 @@ pub struct HashTableResolver {
     pos: usize,
 }
 
+struct ItemAdapter<'a, U>(&'a U);
+
+impl<U: Archive> Archive for ItemAdapter<'_, U> {
+    type Archived = U::Archived;
+    type Resolver = U::Resolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        self.0.resolve(resolver, out);
+    }
+}
+
+impl<U, S> Serialize<S> for ItemAdapter<'_, U>
+where
+    U: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    #[inline]
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
 struct ControlIter {
     current_mask: Bitmask,
     next_group: *const u8,
@@ -0,0 +1,485 @@
+//! Archived strings and vectors which store their lengths as varints.
+//!
+//! [`ArchivedString`](crate::string::ArchivedString) and
+//! [`ArchivedVec`](crate::vec::ArchivedVec) store their lengths inline as an
+//! [`ArchivedUsize`](crate::primitive::ArchivedUsize) next to a relative
+//! pointer. The types in this module only store the relative pointer inline.
+//! Their lengths are written out-of-line as LEB128 varints just before their
+//! contents, so lengths under 128 only take up a single byte. Empty strings and
+//! vectors don't write anything out-of-line.
+//!
+//! This is most useful for archives which contain many small strings and
+//! vectors, where the length would otherwise be a large part of their size.
+//! Getting the length or contents requires decoding the varint first, so these
+//! types are slightly slower to access. `ArchivedString` also stores strings of
+//! up to 8 bytes inline without any out-of-line data, which is smaller than a
+//! compact string of the same length.
+//!
+//! These types are archived by the [`Compact`](crate::with::Compact) wrapper.
+
+use core::{
+    borrow::Borrow, cmp, fmt, hash, marker::PhantomData, mem::align_of,
+    ops::Deref, ptr::NonNull, slice, str,
+};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::ArchivedIsize,
+    ser::{Allocator, Writer, WriterExt as _},
+    util::{read_varint_unchecked, write_varint, SerVec},
+    Place, Portable, RawRelPtr, Serialize,
+};
+
+/// Returns the number of padding bytes needed to align `ptr` to `align`.
+fn padding(ptr: *const u8, align: usize) -> usize {
+    (ptr as usize).wrapping_neg() & (align - 1)
+}
+
+/// Returns the length of the contents pointed to by `ptr` and a pointer to the
+/// first element.
+///
+/// # Safety
+///
+/// `ptr` must be invalid, or point to a varint length followed by that many
+/// `T`s.
+unsafe fn read_contents<T>(ptr: &RawRelPtr) -> (usize, *const T) {
+    if ptr.is_invalid() {
+        return (0, NonNull::dangling().as_ptr());
+    }
+
+    // SAFETY: The caller has guaranteed that `ptr` points to a varint length.
+    let start = unsafe { ptr.as_ptr().cast::<u8>() };
+    let (len, header_len) = unsafe { read_varint_unchecked(start) };
+    // SAFETY: The contents are located right after the varint length, aligned
+    // for `T`.
+    let data = unsafe { start.add(header_len) };
+    let data = unsafe { data.add(padding(data, align_of::<T>())) };
+    (len, data.cast())
+}
+
+/// The resolver for [`ArchivedCompactString`] and [`ArchivedCompactVec`].
+pub struct CompactResolver {
+    pos: usize,
+}
+
+/// An archived string which stores its length as a varint.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(transparent)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct ArchivedCompactString {
+    ptr: RawRelPtr,
+}
+
+impl ArchivedCompactString {
+    /// Extracts a string slice containing the entire `ArchivedCompactString`.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: The contents of an `ArchivedCompactString` are always valid
+        // UTF-8.
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    /// Returns a byte slice of this string's contents.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: `ptr` is either invalid or points to a varint length followed
+        // by that many bytes.
+        let (len, data) = unsafe { read_contents::<u8>(&self.ptr) };
+        unsafe { slice::from_raw_parts(data, len) }
+    }
+
+    /// Returns the length of the string in bytes.
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// Returns whether the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolves an archived compact string from a given `str`.
+    pub fn resolve_from_str(
+        value: &str,
+        resolver: CompactResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedCompactString { ptr } = out);
+        if value.is_empty() {
+            RawRelPtr::emplace_invalid(ptr);
+        } else {
+            RawRelPtr::emplace(resolver.pos, ptr);
+        }
+    }
+
+    /// Serializes an archived compact string from a given `str`.
+    pub fn serialize_from_str<S>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<CompactResolver, S::Error>
+    where
+        S: Fallible + Writer + ?Sized,
+    {
+        let pos = serializer.pos();
+        if !value.is_empty() {
+            write_varint(serializer, value.len())?;
+            serializer.write(value.as_bytes())?;
+        }
+        serializer.check_offset::<ArchivedIsize>(pos)?;
+        Ok(CompactResolver { pos })
+    }
+}
+
+impl AsRef<str> for ArchivedCompactString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for ArchivedCompactString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for ArchivedCompactString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Deref for ArchivedCompactString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for ArchivedCompactString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Eq for ArchivedCompactString {}
+
+impl hash::Hash for ArchivedCompactString {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl Ord for ArchivedCompactString {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialEq for ArchivedCompactString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<str> for ArchivedCompactString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ArchivedCompactString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<ArchivedCompactString> for str {
+    fn eq(&self, other: &ArchivedCompactString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<ArchivedCompactString> for &str {
+    fn eq(&self, other: &ArchivedCompactString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialOrd for ArchivedCompactString {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialOrd<str> for ArchivedCompactString {
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        self.as_str().partial_cmp(other)
+    }
+}
+
+/// An archived `Vec` which stores its length as a varint.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(transparent)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct ArchivedCompactVec<T> {
+    ptr: RawRelPtr,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedCompactVec<T> {
+    /// Gets the elements of the archived vec as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: `ptr` is either invalid or points to a varint length followed
+        // by that many `T`s.
+        let (len, data) = unsafe { read_contents::<T>(&self.ptr) };
+        unsafe { slice::from_raw_parts(data, len) }
+    }
+
+    /// Returns the number of elements in the archived vec.
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Returns whether the archived vec is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolves an archived compact vec from a given length.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: CompactResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedCompactVec { ptr, .. } = out);
+        if len == 0 {
+            RawRelPtr::emplace_invalid(ptr);
+        } else {
+            RawRelPtr::emplace(resolver.pos, ptr);
+        }
+    }
+
+    /// Serializes an archived compact vec from a given slice.
+    pub fn serialize_from_slice<U, S>(
+        slice: &[U],
+        serializer: &mut S,
+    ) -> Result<CompactResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        if slice.is_empty() {
+            return Ok(CompactResolver {
+                pos: serializer.pos(),
+            });
+        }
+
+        SerVec::with_capacity(
+            serializer,
+            slice.len(),
+            |resolvers, serializer| {
+                for value in slice.iter() {
+                    resolvers.push(value.serialize(serializer)?);
+                }
+
+                let pos = serializer.pos();
+                write_varint(serializer, slice.len())?;
+                serializer.align_for::<T>()?;
+                for (value, resolver) in slice.iter().zip(resolvers.drain()) {
+                    // SAFETY: `resolver` is the result of serializing `value`,
+                    // and the serializer was just aligned for `T`.
+                    unsafe {
+                        serializer.resolve_aligned(value, resolver)?;
+                    }
+                }
+                serializer.check_offset::<ArchivedIsize>(pos)?;
+
+                Ok(CompactResolver { pos })
+            },
+        )?
+    }
+}
+
+impl<T> AsRef<[T]> for ArchivedCompactVec<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> Borrow<[T]> for ArchivedCompactVec<T> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedCompactVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<T> Deref for ArchivedCompactVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for ArchivedCompactVec<T> {}
+
+impl<T: hash::Hash> hash::Hash for ArchivedCompactVec<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: Ord> Ord for ArchivedCompactVec<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<ArchivedCompactVec<U>>
+    for ArchivedCompactVec<T>
+{
+    fn eq(&self, other: &ArchivedCompactVec<U>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedCompactVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for ArchivedCompactVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{alloc::Layout, fmt, mem::align_of, ptr, slice};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        CheckBytes, Verify,
+    };
+    use rancor::fail;
+
+    use super::{padding, ArchivedCompactString, ArchivedCompactVec};
+    use crate::{
+        util::{read_varint, MAX_VARINT_LEN},
+        validation::{ArchiveContext, ArchiveContextExt},
+        RawRelPtr,
+    };
+
+    #[derive(Debug)]
+    struct InvalidCompactLength;
+
+    impl fmt::Display for InvalidCompactLength {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid varint length for compact string or vec")
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for InvalidCompactLength {}
+
+    /// Checks the varint length that `ptr` points to, then claims the length
+    /// and contents as a subtree and checks the contents with `f`.
+    fn check_contents<T, C>(
+        ptr: &RawRelPtr,
+        context: &mut C,
+        f: impl FnOnce(*const [T], &mut C) -> Result<(), C::Error>,
+    ) -> Result<(), C::Error>
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        if ptr.is_invalid() {
+            return Ok(());
+        }
+
+        let start = ptr.as_ptr_wrapping().cast::<u8>();
+        let mut header_len = 0;
+        loop {
+            if header_len == MAX_VARINT_LEN {
+                fail!(InvalidCompactLength);
+            }
+            let byte = start.wrapping_add(header_len);
+            context.check_subtree_ptr(byte, &Layout::new::<u8>())?;
+            header_len += 1;
+            // SAFETY: We just checked that `byte` is located in the archive.
+            if unsafe { *byte } & 0x80 == 0 {
+                break;
+            }
+        }
+
+        // SAFETY: We checked that every byte of the header is located in the
+        // archive.
+        let header = unsafe { slice::from_raw_parts(start, header_len) };
+        let Some(len) = read_varint(header, &mut 0) else {
+            fail!(InvalidCompactLength);
+        };
+
+        let data = start.wrapping_add(header_len);
+        let data = data.wrapping_add(padding(data, align_of::<T>()));
+        let Some(size) = Layout::array::<T>(len)
+            .ok()
+            .and_then(|layout| layout.size().checked_add(data as usize))
+            .map(|end| end - start as usize)
+        else {
+            fail!(InvalidCompactLength);
+        };
+        let Ok(layout) = Layout::from_size_align(size, 1) else {
+            fail!(InvalidCompactLength);
+        };
+
+        let contents = ptr::slice_from_raw_parts(data.cast::<T>(), len);
+        context.in_subtree_raw(start, layout, |context| f(contents, context))
+    }
+
+    unsafe impl<C> Verify<C> for ArchivedCompactString
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            check_contents::<u8, C>(&self.ptr, context, |bytes, context| {
+                // SAFETY: `check_contents` has checked that the bytes are
+                // located in the archive.
+                unsafe { str::check_bytes(bytes as *const str, context) }
+            })
+        }
+    }
+
+    unsafe impl<T, C> Verify<C> for ArchivedCompactVec<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            check_contents::<T, C>(&self.ptr, context, |elements, context| {
+                // SAFETY: `check_contents` has checked that the elements are
+                // properly aligned and located in the archive.
+                unsafe { <[T]>::check_bytes(elements, context) }
+            })
+        }
+    }
+}
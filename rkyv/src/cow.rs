@@ -0,0 +1,230 @@
+//! An archived version of `Cow`.
+
+use core::{borrow::Borrow, cmp, fmt, hash, ops::Deref, pin::Pin};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    place::Initialized,
+    rc::{ArchivedRc, RcResolver},
+    ser::{Sharing, Writer},
+    Archive, Place, Portable, Serialize,
+};
+
+/// The flavor type for the borrowed variant of [`ArchivedCow`].
+pub struct CowFlavor;
+
+/// An archived `Cow`.
+///
+/// A borrowed `Cow` is archived as a relative pointer to a value stored
+/// elsewhere in the same archive. Borrowed values are deduplicated by address,
+/// so many `Cow`s borrowing the same value will all point to a single copy of
+/// it. An owned `Cow` stores its value inline.
+///
+/// Values borrowed by `Cow`s should not also be serialized through an `Rc` or
+/// `Arc`, since they would share the same position but fail validation as
+/// different kinds of shared pointers.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(u8)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub enum ArchivedCow<T> {
+    /// A value borrowed from elsewhere in the archive
+    Borrowed(ArchivedRc<T, CowFlavor>),
+    /// A value stored inline
+    Owned(T),
+}
+
+impl<T> ArchivedCow<T> {
+    /// Gets the value of the `ArchivedCow`.
+    pub fn get(&self) -> &T {
+        match self {
+            ArchivedCow::Borrowed(b) => b.get(),
+            ArchivedCow::Owned(o) => o,
+        }
+    }
+
+    /// Returns `true` if the value is borrowed from elsewhere in the archive.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, ArchivedCow::Borrowed(_))
+    }
+
+    /// Returns `true` if the value is stored inline.
+    pub fn is_owned(&self) -> bool {
+        matches!(self, ArchivedCow::Owned(_))
+    }
+
+    /// Gets the pinned mutable value of this `ArchivedCow` if it is owned.
+    ///
+    /// Returns `None` if the value is borrowed, since other `ArchivedCow`s may
+    /// point to the same value.
+    pub fn owned_pin_mut(self: Pin<&mut Self>) -> Option<Pin<&mut T>> {
+        unsafe {
+            match self.get_unchecked_mut() {
+                ArchivedCow::Borrowed(_) => None,
+                ArchivedCow::Owned(o) => Some(Pin::new_unchecked(o)),
+            }
+        }
+    }
+
+    /// Gets the pinned mutable value of this `ArchivedCow`.
+    ///
+    /// # Safety
+    ///
+    /// If the value is borrowed, any other pointers to the same value must not
+    /// be dereferenced for the duration of the returned borrow.
+    pub unsafe fn get_pin_mut_unchecked(self: Pin<&mut Self>) -> Pin<&mut T> {
+        unsafe {
+            match self.get_unchecked_mut() {
+                ArchivedCow::Borrowed(b) => {
+                    Pin::new_unchecked(b).get_pin_mut_unchecked()
+                }
+                ArchivedCow::Owned(o) => Pin::new_unchecked(o),
+            }
+        }
+    }
+
+    /// Resolves an archived `Cow` from a given value.
+    ///
+    /// `resolver` must be the result of serializing `value` with either
+    /// [`serialize_borrowed`](Self::serialize_borrowed) or
+    /// [`serialize_owned`](Self::serialize_owned).
+    pub fn resolve_from_value<U: Archive<Archived = T>>(
+        value: &U,
+        resolver: CowResolver<U::Resolver>,
+        out: Place<Self>,
+    ) {
+        match resolver {
+            CowResolver::Borrowed(resolver) => {
+                let out = unsafe {
+                    out.cast_unchecked::<ArchivedCowVariantBorrowed<T>>()
+                };
+                munge!(let ArchivedCowVariantBorrowed(tag, rc) = out);
+                tag.write(ArchivedCowTag::Borrowed);
+
+                ArchivedRc::resolve_from_ref(value, resolver, rc);
+            }
+            CowResolver::Owned(resolver) => {
+                let out = unsafe {
+                    out.cast_unchecked::<ArchivedCowVariantOwned<T>>()
+                };
+                munge!(let ArchivedCowVariantOwned(tag, owned) = out);
+                tag.write(ArchivedCowTag::Owned);
+
+                value.resolve(resolver, owned);
+            }
+        }
+    }
+
+    /// Serializes a value which will be borrowed by the archived `Cow`.
+    ///
+    /// If the same value has already been borrowed by another archived `Cow`,
+    /// it is not serialized again.
+    pub fn serialize_borrowed<U, S>(
+        value: &U,
+        serializer: &mut S,
+    ) -> Result<CowResolver<U::Resolver>, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Writer + Sharing + ?Sized,
+    {
+        Ok(CowResolver::Borrowed(
+            ArchivedRc::<T, CowFlavor>::serialize_from_ref(value, serializer)?,
+        ))
+    }
+
+    /// Serializes a value which will be stored inline in the archived `Cow`.
+    pub fn serialize_owned<U, S>(
+        value: &U,
+        serializer: &mut S,
+    ) -> Result<CowResolver<U::Resolver>, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + ?Sized,
+    {
+        Ok(CowResolver::Owned(value.serialize(serializer)?))
+    }
+}
+
+impl<T> AsRef<T> for ArchivedCow<T> {
+    fn as_ref(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T> Borrow<T> for ArchivedCow<T> {
+    fn borrow(&self) -> &T {
+        self.get()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedCow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T> Deref for ArchivedCow<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for ArchivedCow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: Eq> Eq for ArchivedCow<T> {}
+
+impl<T: hash::Hash> hash::Hash for ArchivedCow<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.get().hash(state)
+    }
+}
+
+impl<T: Ord> Ord for ArchivedCow<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.get().cmp(other.get())
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<ArchivedCow<U>> for ArchivedCow<T> {
+    fn eq(&self, other: &ArchivedCow<U>) -> bool {
+        self.get().eq(other.get())
+    }
+}
+
+impl<T: PartialOrd<U>, U> PartialOrd<ArchivedCow<U>> for ArchivedCow<T> {
+    fn partial_cmp(&self, other: &ArchivedCow<U>) -> Option<cmp::Ordering> {
+        self.get().partial_cmp(other.get())
+    }
+}
+
+/// The resolver for [`ArchivedCow`].
+pub enum CowResolver<R> {
+    /// The value is borrowed from elsewhere in the archive
+    Borrowed(RcResolver),
+    /// The value is stored inline
+    Owned(R),
+}
+
+#[allow(dead_code)]
+#[repr(u8)]
+enum ArchivedCowTag {
+    Borrowed,
+    Owned,
+}
+
+// SAFETY: `ArchivedCowTag` is `repr(u8)` and so is always initialized.
+unsafe impl Initialized for ArchivedCowTag {}
+
+#[repr(C)]
+struct ArchivedCowVariantBorrowed<T>(ArchivedCowTag, ArchivedRc<T, CowFlavor>);
+
+#[repr(C)]
+struct ArchivedCowVariantOwned<T>(ArchivedCowTag, T);
@@ -0,0 +1,29 @@
+//! Deserializers that provide the buffer being deserialized from.
+
+use rancor::Strategy;
+
+use crate::{de::Unpool, util::SharedAlignedVec};
+
+/// A deserializer which can provide the buffer it is deserializing from.
+///
+/// This trait is required to deserialize [`Lazy`](crate::lazy::Lazy) values.
+/// Deserializers which don't have a buffer may return `None`, in which case
+/// lazy values are deserialized eagerly instead.
+pub trait SourceBuffer {
+    /// Returns the buffer which contains the archive being deserialized, if
+    /// any.
+    fn source_buffer(&self) -> Option<&SharedAlignedVec>;
+}
+
+impl<T: SourceBuffer, E> SourceBuffer for Strategy<T, E> {
+    fn source_buffer(&self) -> Option<&SharedAlignedVec> {
+        T::source_buffer(self)
+    }
+}
+
+impl SourceBuffer for Unpool {
+    #[inline]
+    fn source_buffer(&self) -> Option<&SharedAlignedVec> {
+        None
+    }
+}
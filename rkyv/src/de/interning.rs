@@ -0,0 +1,37 @@
+//! Deserializers that share the contents of equal strings.
+
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use rancor::Strategy;
+
+use crate::de::Unpool;
+
+/// A string interning strategy.
+///
+/// This trait is required to deserialize fields with the
+/// [`Intern`](crate::with::Intern) wrapper. Interning deserializers return
+/// clones of a single shared string for every archived string with the same
+/// contents.
+pub trait Interning {
+    /// Returns a shared string with the given contents.
+    ///
+    /// This may return a clone of a previously-returned shared string with the
+    /// same contents instead of allocating a new one.
+    fn intern_str(&mut self, value: &str) -> Arc<str>;
+}
+
+impl<T: Interning, E> Interning for Strategy<T, E> {
+    fn intern_str(&mut self, value: &str) -> Arc<str> {
+        T::intern_str(self, value)
+    }
+}
+
+impl Interning for Unpool {
+    #[inline]
+    fn intern_str(&mut self, value: &str) -> Arc<str> {
+        Arc::from(value)
+    }
+}
@@ -1,9 +1,19 @@
 //! Deserialization traits, deserializers, and adapters.
 
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub mod buffer;
+#[cfg(feature = "alloc")]
+pub mod interning;
 pub mod pooling;
 
 use rancor::Strategy;
 
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+#[doc(inline)]
+pub use self::buffer::*;
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use self::interning::*;
 #[doc(inline)]
 pub use self::pooling::*;
 
@@ -1,14 +1,22 @@
 //! Adapters wrap deserializers and add support for deserializer traits.
 
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
 use core::{fmt, mem::size_of};
 #[cfg(feature = "std")]
-use std::collections::hash_map;
+use std::{
+    collections::{hash_map, hash_set},
+    sync::Arc,
+};
 
 #[cfg(not(feature = "std"))]
-use hashbrown::hash_map;
+use hashbrown::{hash_map, hash_set};
 use rancor::{fail, Source};
 
 use super::{ErasedPtr, Pooling};
+use crate::de::Interning;
+#[cfg(target_has_atomic = "ptr")]
+use crate::{de::SourceBuffer, util::SharedAlignedVec};
 
 #[derive(Debug)]
 struct DuplicateSharedPointer {
@@ -45,9 +53,19 @@ impl Drop for SharedPointer {
 
 /// A shared pointer strategy that pools together deserializations of the same
 /// shared pointer.
+///
+/// By default, strings are not interned. Use [`with_string_interning`] to
+/// create a pool which also shares the contents of equal strings deserialized
+/// with the [`Intern`] wrapper.
+///
+/// [`with_string_interning`]: Pool::with_string_interning
+/// [`Intern`]: crate::with::Intern
 #[derive(Default)]
 pub struct Pool {
     shared_pointers: hash_map::HashMap<usize, SharedPointer>,
+    interned_strs: Option<hash_set::HashSet<Arc<str>>>,
+    #[cfg(target_has_atomic = "ptr")]
+    source_buffer: Option<SharedAlignedVec>,
 }
 
 impl Pool {
@@ -62,6 +80,65 @@ impl Pool {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             shared_pointers: hash_map::HashMap::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new shared pointer unifier which also interns strings.
+    ///
+    /// Every string deserialized with the [`Intern`](crate::with::Intern)
+    /// wrapper is deduplicated by its contents, so each distinct string is
+    /// only allocated once.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use rkyv::{
+    ///     access_unchecked, de::Pool, deserialize, rancor::Error, to_bytes,
+    ///     with::Intern, Archive, Deserialize, Serialize,
+    /// };
+    ///
+    /// #[derive(Archive, Serialize, Deserialize)]
+    /// struct Example {
+    ///     #[with(Intern)]
+    ///     a: Arc<str>,
+    ///     #[with(Intern)]
+    ///     b: Arc<str>,
+    /// }
+    ///
+    /// let value = Example {
+    ///     a: Arc::from("hello world"),
+    ///     b: Arc::from("hello world"),
+    /// };
+    /// let bytes = to_bytes::<Error>(&value).unwrap();
+    /// let archived = unsafe { access_unchecked::<ArchivedExample>(&bytes) };
+    ///
+    /// let mut pool = Pool::with_string_interning();
+    /// let deserialized =
+    ///     deserialize::<Example, _, Error>(archived, &mut pool).unwrap();
+    /// assert!(Arc::ptr_eq(&deserialized.a, &deserialized.b));
+    /// ```
+    #[inline]
+    pub fn with_string_interning() -> Self {
+        Self {
+            interned_strs: Some(hash_set::HashSet::new()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new shared pointer unifier which deserializes from the given
+    /// buffer.
+    ///
+    /// [`Lazy`](crate::lazy::Lazy) values deserialized from an archive in
+    /// this buffer keep a clone of it and defer deserialization until they are
+    /// first accessed.
+    #[cfg(target_has_atomic = "ptr")]
+    #[inline]
+    pub fn with_source_buffer(buffer: SharedAlignedVec) -> Self {
+        Self {
+            source_buffer: Some(buffer),
+            ..Self::default()
         }
     }
 }
@@ -94,3 +171,27 @@ impl<E: Source> Pooling<E> for Pool {
         }
     }
 }
+
+impl Interning for Pool {
+    fn intern_str(&mut self, value: &str) -> Arc<str> {
+        let Some(interned_strs) = self.interned_strs.as_mut() else {
+            return Arc::from(value);
+        };
+
+        if let Some(interned) = interned_strs.get(value) {
+            interned.clone()
+        } else {
+            let interned = Arc::<str>::from(value);
+            interned_strs.insert(interned.clone());
+            interned
+        }
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl SourceBuffer for Pool {
+    #[inline]
+    fn source_buffer(&self) -> Option<&SharedAlignedVec> {
+        self.source_buffer.as_ref()
+    }
+}
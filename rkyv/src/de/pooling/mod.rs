@@ -4,14 +4,16 @@
 mod alloc;
 mod core;
 
-use ::core::{alloc::LayoutError, fmt, mem::transmute};
+use ::core::{alloc::LayoutError, fmt, future::Future, mem::transmute};
 use ptr_meta::{from_raw_parts_mut, metadata, DynMetadata, Pointee};
 use rancor::{Fallible, ResultExt as _, Source, Strategy};
 
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
 pub use self::core::*;
-use crate::{ArchiveUnsized, DeserializeUnsized, LayoutRaw};
+use crate::{
+    Archive, ArchiveUnsized, DeserializeAsync, DeserializeUnsized, LayoutRaw,
+};
 
 /// Type-erased pointer metadata.
 #[derive(Clone, Copy)]
@@ -226,6 +228,55 @@ pub trait PoolingExt<E>: Pooling<E> {
             Ok(ptr)
         }
     }
+
+    /// Checks whether the given reference has been deserialized and either uses
+    /// the existing shared pointer to it, or deserializes it asynchronously and
+    /// converts it to a shared pointer of type `P`.
+    ///
+    /// This is the asynchronous counterpart to
+    /// [`deserialize_shared`](PoolingExt::deserialize_shared). Only sized
+    /// values can be deserialized asynchronously.
+    fn deserialize_shared_async<T, P>(
+        &mut self,
+        value: &T::Archived,
+    ) -> impl Future<Output = Result<*mut T, Self::Error>>
+    where
+        T: Archive,
+        T::Archived: DeserializeAsync<T, Self>,
+        P: SharedPointer<T>,
+        Self: Fallible<Error = E>,
+        E: Source,
+    {
+        unsafe fn drop_shared<T, P>(ptr: ErasedPtr)
+        where
+            P: SharedPointer<T>,
+        {
+            unsafe { P::drop(ptr.downcast_unchecked::<T>()) }
+        }
+
+        async move {
+            let address = value as *const T::Archived as *const () as usize;
+
+            if let Some(shared_pointer) = self.get_shared_ptr(address) {
+                return Ok(shared_pointer.data_address.cast::<T>());
+            }
+
+            let deserialized = value.deserialize_async(self).await?;
+            let out = P::alloc(()).into_error()?;
+            unsafe { out.write(deserialized) };
+            let ptr = unsafe { P::from_value(out) };
+
+            unsafe {
+                self.add_shared_ptr(
+                    address,
+                    ErasedPtr::new(ptr),
+                    drop_shared::<T, P>,
+                )?;
+            }
+
+            Ok(ptr)
+        }
+    }
 }
 
 impl<T, E> PoolingExt<E> for T where T: Pooling<E> + ?Sized {}
@@ -0,0 +1,262 @@
+//! Conversion for archives written with the opposite endianness.
+//!
+//! Archives store multibyte primitives in little-endian order by default and
+//! in big-endian order when the `big_endian` feature is enabled. An archive
+//! written with one setting can't be accessed directly by a program built with
+//! the other. [`normalize_endianness`] converts such an archive in place so
+//! that it can be accessed normally afterward.
+
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    mem::size_of,
+    num::{NonZeroI8, NonZeroU8},
+    slice,
+    sync::atomic::{AtomicBool, AtomicI8, AtomicU8},
+};
+
+use crate::{rend::*, Portable};
+
+/// An archived type whose byte order can be converted in place.
+///
+/// Implementations are provided for primitives, arrays, slices, relative
+/// pointers and the most common archived containers. It can be derived for
+/// archived types with `#[archive(swap_endian)]`.
+///
+/// # Safety
+///
+/// `swap_endian` must reverse the byte order of every multibyte primitive that
+/// makes up the value, including any values reachable through its relative
+/// pointers. It must not make any other modifications.
+pub unsafe trait SwapEndian: Portable {
+    /// Converts the value at `this` from the opposite endianness.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must be properly aligned and valid for reads and writes.
+    /// - The value at `this` must be a valid archived value written with the
+    ///   opposite endianness.
+    /// - Every value reachable through the relative pointers of the value must
+    ///   be located in the same allocation as it, and must not be reachable
+    ///   through any other relative pointer. In particular, archives containing
+    ///   shared pointers can't be converted.
+    unsafe fn swap_endian(this: *mut Self);
+}
+
+/// Converts the archive in the given bytes from the opposite endianness in
+/// place.
+///
+/// This calculates the position of the root object using the length of the
+/// byte slice, just like [`access_unchecked`](crate::access_unchecked). After
+/// conversion, the bytes can be accessed or validated normally.
+///
+/// # Safety
+///
+/// - The byte slice must represent an archived `T` written with the opposite
+///   endianness. It is not possible to check this before conversion, so
+///   untrusted data should not be converted.
+/// - The root of the object must be stored at the end of the slice (this is the
+///   default behavior).
+/// - The archive must meet the requirements of [`SwapEndian::swap_endian`].
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{endian::normalize_endianness, Archive};
+///
+/// #[derive(Archive)]
+/// #[archive(swap_endian)]
+/// struct Example {
+///     value: u32,
+/// }
+///
+/// // An archived `Example` with a value of 42, written with the opposite
+/// // endianness.
+/// let mut bytes = if cfg!(feature = "big_endian") {
+///     42u32.to_le_bytes()
+/// } else {
+///     42u32.to_be_bytes()
+/// };
+///
+/// let archived = unsafe {
+///     normalize_endianness::<ArchivedExample>(&mut bytes);
+///     rkyv::access_unchecked::<ArchivedExample>(&bytes)
+/// };
+/// assert_eq!(archived.value, 42);
+/// ```
+pub unsafe fn normalize_endianness<T: SwapEndian>(bytes: &mut [u8]) {
+    let pos = bytes.len() - size_of::<T>();
+    // SAFETY: The caller has guaranteed that a `T` written with the opposite
+    // endianness is located at the root position in the byte slice.
+    unsafe {
+        T::swap_endian(bytes.as_mut_ptr().add(pos).cast());
+    }
+}
+
+macro_rules! impl_swap_endian_noop {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: The type does not contain any multibyte primitives.
+            unsafe impl SwapEndian for $ty {
+                #[inline]
+                unsafe fn swap_endian(_: *mut Self) {}
+            }
+        )*
+    };
+}
+
+impl_swap_endian_noop!(
+    (),
+    bool,
+    i8,
+    u8,
+    NonZeroI8,
+    NonZeroU8,
+    AtomicBool,
+    AtomicI8,
+    AtomicU8,
+    PhantomPinned,
+    str,
+);
+
+// SAFETY: `PhantomData` does not contain any multibyte primitives.
+unsafe impl<T: ?Sized> SwapEndian for PhantomData<T> {
+    #[inline]
+    unsafe fn swap_endian(_: *mut Self) {}
+}
+
+macro_rules! impl_swap_endian_bytes {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: The type is a single multibyte primitive, so reversing
+            // all of its bytes reverses its byte order.
+            unsafe impl SwapEndian for $ty {
+                #[inline]
+                unsafe fn swap_endian(this: *mut Self) {
+                    // SAFETY: The caller has guaranteed that `this` is valid
+                    // for reads and writes.
+                    unsafe {
+                        slice::from_raw_parts_mut(
+                            this.cast::<u8>(),
+                            size_of::<Self>(),
+                        )
+                        .reverse();
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_swap_endian_bytes!(
+    i16_le,
+    i32_le,
+    i64_le,
+    i128_le,
+    u16_le,
+    u32_le,
+    u64_le,
+    u128_le,
+    f32_le,
+    f64_le,
+    char_le,
+    NonZeroI16_le,
+    NonZeroI32_le,
+    NonZeroI64_le,
+    NonZeroI128_le,
+    NonZeroU16_le,
+    NonZeroU32_le,
+    NonZeroU64_le,
+    NonZeroU128_le,
+    i16_be,
+    i32_be,
+    i64_be,
+    i128_be,
+    u16_be,
+    u32_be,
+    u64_be,
+    u128_be,
+    f32_be,
+    f64_be,
+    char_be,
+    NonZeroI16_be,
+    NonZeroI32_be,
+    NonZeroI64_be,
+    NonZeroI128_be,
+    NonZeroU16_be,
+    NonZeroU32_be,
+    NonZeroU64_be,
+    NonZeroU128_be,
+    unaligned::i16_ule,
+    unaligned::i32_ule,
+    unaligned::i64_ule,
+    unaligned::i128_ule,
+    unaligned::u16_ule,
+    unaligned::u32_ule,
+    unaligned::u64_ule,
+    unaligned::u128_ule,
+    unaligned::f32_ule,
+    unaligned::f64_ule,
+    unaligned::char_ule,
+    unaligned::NonZeroI16_ule,
+    unaligned::NonZeroI32_ule,
+    unaligned::NonZeroI64_ule,
+    unaligned::NonZeroI128_ule,
+    unaligned::NonZeroU16_ule,
+    unaligned::NonZeroU32_ule,
+    unaligned::NonZeroU64_ule,
+    unaligned::NonZeroU128_ule,
+    unaligned::i16_ube,
+    unaligned::i32_ube,
+    unaligned::i64_ube,
+    unaligned::i128_ube,
+    unaligned::u16_ube,
+    unaligned::u32_ube,
+    unaligned::u64_ube,
+    unaligned::u128_ube,
+    unaligned::f32_ube,
+    unaligned::f64_ube,
+    unaligned::char_ube,
+    unaligned::NonZeroI16_ube,
+    unaligned::NonZeroI32_ube,
+    unaligned::NonZeroI64_ube,
+    unaligned::NonZeroI128_ube,
+    unaligned::NonZeroU16_ube,
+    unaligned::NonZeroU32_ube,
+    unaligned::NonZeroU64_ube,
+    unaligned::NonZeroU128_ube,
+);
+
+#[cfg(target_has_atomic = "16")]
+impl_swap_endian_bytes!(AtomicI16_le, AtomicI16_be, AtomicU16_le, AtomicU16_be);
+#[cfg(target_has_atomic = "32")]
+impl_swap_endian_bytes!(AtomicI32_le, AtomicI32_be, AtomicU32_le, AtomicU32_be);
+#[cfg(target_has_atomic = "64")]
+impl_swap_endian_bytes!(AtomicI64_le, AtomicI64_be, AtomicU64_le, AtomicU64_be);
+
+// SAFETY: Each element of the array is converted.
+unsafe impl<T: SwapEndian, const N: usize> SwapEndian for [T; N] {
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes, and so is every element of the array.
+        unsafe {
+            <[T]>::swap_endian(this as *mut [T]);
+        }
+    }
+}
+
+// SAFETY: Each element of the slice is converted.
+unsafe impl<T: SwapEndian> SwapEndian for [T] {
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        let len = ptr_meta::metadata(this);
+        let ptr = this.cast::<T>();
+        for i in 0..len {
+            // SAFETY: The caller has guaranteed that `this` is valid for reads
+            // and writes, and so is every element of the slice.
+            unsafe {
+                T::swap_endian(ptr.add(i));
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! References to values stored in other archives.
+
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::size_of,
+};
+#[cfg(feature = "std")]
+use std::{collections::HashMap, hash::BuildHasher};
+
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+use bytecheck::CheckBytes;
+use munge::munge;
+use rancor::Fallible;
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+use rancor::{fail, Source, Strategy};
+
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+use crate::validation::{util::access_pos, validators::DefaultValidator};
+use crate::{
+    primitive::{ArchivedU64, ArchivedUsize, FixedUsize},
+    util::access_pos_unchecked,
+    Archive, Deserialize, Place, Portable, Serialize,
+};
+
+/// A source of archives which [`ExternRef`]s can point into.
+///
+/// Each archive is identified by an ID chosen by the application. An
+/// [`ExternRef`] records the ID of the archive it points into, and the resolver
+/// is asked for the bytes of that archive when the reference is accessed.
+///
+/// Slices of archives are resolvers where each archive's ID is its index.
+pub trait ExternResolver {
+    /// Returns the bytes of the archive with the given ID, or `None` if there
+    /// is no such archive.
+    fn archive_bytes(&self, archive_id: u64) -> Option<&[u8]>;
+}
+
+impl<B: AsRef<[u8]>> ExternResolver for [B] {
+    #[inline]
+    fn archive_bytes(&self, archive_id: u64) -> Option<&[u8]> {
+        let index = usize::try_from(archive_id).ok()?;
+        self.get(index).map(AsRef::as_ref)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<B: AsRef<[u8]>, H: BuildHasher> ExternResolver for HashMap<u64, B, H> {
+    #[inline]
+    fn archive_bytes(&self, archive_id: u64) -> Option<&[u8]> {
+        self.get(&archive_id).map(AsRef::as_ref)
+    }
+}
+
+/// A reference to an archived `T` stored in another archive.
+///
+/// An `ExternRef` stores the ID of the archive the value is stored in and the
+/// position of the value in that archive. It is resolved through an
+/// [`ExternResolver`] when it is accessed, so one archive can refer to values
+/// in another (for example, a shared dictionary) without duplicating them.
+///
+/// `ExternRef` is its own archived type, and is serialized by copying the ID
+/// and position.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     extern_ref::ExternRef, rancor::Error, string::ArchivedString,
+///     vec::ArchivedVec, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Entry {
+///     key: u32,
+///     name: ExternRef<ArchivedString>,
+/// }
+///
+/// // The shared dictionary is archived once.
+/// let words = vec!["hello".to_string(), "world".to_string()];
+/// let dictionary = rkyv::to_bytes::<Error>(&words).unwrap();
+/// let archived_words =
+///     rkyv::access::<ArchivedVec<ArchivedString>, Error>(&dictionary)
+///         .unwrap();
+///
+/// // Other archives refer to values in the dictionary.
+/// let entry = Entry {
+///     key: 1,
+///     name: ExternRef::from_archived(0, &dictionary, &archived_words[1]),
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&entry).unwrap();
+/// let entry = rkyv::access::<ArchivedEntry, Error>(&bytes).unwrap();
+///
+/// let archives = [dictionary];
+/// let name = entry.name.get::<_, Error>(&archives[..]).unwrap();
+/// assert_eq!(name, "world");
+/// ```
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ExternRef<T> {
+    archive_id: ArchivedU64,
+    pos: ArchivedUsize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ExternRef<T> {
+    /// Creates a reference to the value at `pos` in the archive with the
+    /// given ID.
+    ///
+    /// # Panics
+    ///
+    /// - If `pos` exceeds the range of an archived `usize`
+    pub fn new(archive_id: u64, pos: usize) -> Self {
+        let pos = FixedUsize::try_from(pos)
+            .expect("position exceeds the range of an archived usize");
+        Self {
+            archive_id: ArchivedU64::from_native(archive_id),
+            pos: ArchivedUsize::from_native(pos),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a reference to an archived value in `bytes`, which is the
+    /// archive with the given ID.
+    ///
+    /// # Panics
+    ///
+    /// - If `value` is not located within `bytes`
+    /// - If the position of `value` exceeds the range of an archived `usize`
+    pub fn from_archived(archive_id: u64, bytes: &[u8], value: &T) -> Self {
+        let start = bytes.as_ptr() as usize;
+        let address = value as *const T as usize;
+        assert!(
+            address >= start && address + size_of::<T>() <= start + bytes.len(),
+            "archived value is not located within the given archive",
+        );
+        Self::new(archive_id, address - start)
+    }
+
+    /// Returns the ID of the archive the value is stored in.
+    #[inline]
+    pub fn archive_id(&self) -> u64 {
+        self.archive_id.to_native()
+    }
+
+    /// Returns the position of the value in its archive.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos.to_native() as usize
+    }
+}
+
+impl<T: Portable> ExternRef<T> {
+    /// Returns the referenced value without checking it, or `None` if the
+    /// resolver does not have its archive.
+    ///
+    /// # Safety
+    ///
+    /// If the resolver has the archive with this reference's ID, an archived
+    /// `T` must be located at this reference's position in it.
+    pub unsafe fn get_unchecked<'a, R>(&self, resolver: &'a R) -> Option<&'a T>
+    where
+        R: ExternResolver + ?Sized,
+    {
+        let bytes = resolver.archive_bytes(self.archive_id())?;
+        // SAFETY: The caller has guaranteed that an archived `T` is located at
+        // `pos` in the archive.
+        Some(unsafe { access_pos_unchecked::<T>(bytes, self.pos()) })
+    }
+
+    /// Returns the referenced value after checking that it is valid.
+    ///
+    /// The value is checked every time it is accessed. To avoid repeating
+    /// the check, validate the referenced archives up front and use
+    /// [`get_unchecked`](ExternRef::get_unchecked) instead.
+    #[cfg(all(feature = "bytecheck", feature = "alloc"))]
+    pub fn get<'a, R, E>(&self, resolver: &'a R) -> Result<&'a T, E>
+    where
+        R: ExternResolver + ?Sized,
+        T: for<'v> CheckBytes<Strategy<DefaultValidator<'v>, E>>,
+        E: Source,
+    {
+        let archive_id = self.archive_id();
+        match resolver.archive_bytes(archive_id) {
+            Some(bytes) => access_pos::<T, E>(bytes, self.pos()),
+            None => fail!(MissingArchiveError { archive_id }),
+        }
+    }
+}
+
+impl<T> Clone for ExternRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ExternRef<T> {}
+
+impl<T> fmt::Debug for ExternRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternRef")
+            .field("archive_id", &self.archive_id())
+            .field("pos", &self.pos())
+            .finish()
+    }
+}
+
+impl<T> PartialEq for ExternRef<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.archive_id == other.archive_id && self.pos == other.pos
+    }
+}
+
+impl<T> Eq for ExternRef<T> {}
+
+impl<T> Hash for ExternRef<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.archive_id().hash(state);
+        self.pos().hash(state);
+    }
+}
+
+impl<T> Archive for ExternRef<T> {
+    type Archived = ExternRef<T>;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ExternRef { archive_id, pos, _phantom: _ } = out);
+        archive_id.write(self.archive_id);
+        pos.write(self.pos);
+    }
+}
+
+impl<T, S: Fallible + ?Sized> Serialize<S> for ExternRef<T> {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<T, D: Fallible + ?Sized> Deserialize<ExternRef<T>, D> for ExternRef<T> {
+    fn deserialize(&self, _: &mut D) -> Result<ExternRef<T>, D::Error> {
+        Ok(*self)
+    }
+}
+
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+#[derive(Debug)]
+struct MissingArchiveError {
+    archive_id: u64,
+}
+
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+impl fmt::Display for MissingArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "extern reference points into archive {}, which the resolver does \
+             not have",
+            self.archive_id,
+        )
+    }
+}
+
+#[cfg(all(feature = "bytecheck", feature = "std"))]
+impl std::error::Error for MissingArchiveError {}
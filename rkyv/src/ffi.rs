@@ -12,7 +12,9 @@ use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    ser::Writer, ArchiveUnsized, Place, Portable, RelPtr, SerializeUnsized,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    ArchiveUnsized, Place, Portable, RelPtr, SerializeUnsized,
 };
 
 /// An archived [`CString`](std::ffi::CString).
@@ -170,6 +172,143 @@ pub struct CStringResolver {
     pos: usize,
 }
 
+/// An archived [`OsString`](std::ffi::OsString).
+///
+/// The string is stored as bytes so that strings which aren't valid UTF-8
+/// survive a round trip. On Unix, these are the raw bytes of the string. On
+/// Windows, the wide string is converted to [WTF-8], which is identical to
+/// UTF-8 for strings which are valid Unicode. On other platforms, only strings
+/// which are valid UTF-8 can be archived.
+///
+/// Strings which aren't valid UTF-8 can only be deserialized on the same
+/// family of platforms they were serialized on.
+///
+/// [WTF-8]: https://simonsapin.github.io/wtf-8/
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedOsString {
+    bytes: ArchivedVec<u8>,
+}
+
+impl ArchivedOsString {
+    /// Returns the archived bytes of this string.
+    ///
+    /// See [`ArchivedOsString`] for how the bytes are encoded.
+    #[inline]
+    pub fn as_encoded_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Returns the number of archived bytes in this string.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns whether this string is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns this string as a `str` if it is valid UTF-8.
+    #[inline]
+    pub fn to_str(&self) -> Option<&str> {
+        core::str::from_utf8(self.as_encoded_bytes()).ok()
+    }
+
+    /// Returns this string as an `OsStr`.
+    #[cfg(unix)]
+    #[inline]
+    pub fn as_os_str(&self) -> &std::ffi::OsStr {
+        use std::os::unix::ffi::OsStrExt as _;
+
+        std::ffi::OsStr::from_bytes(self.as_encoded_bytes())
+    }
+
+    /// Resolves an archived OS string from the length of its encoded bytes.
+    #[inline]
+    pub fn resolve_from_encoded_len(
+        len: usize,
+        resolver: OsStringResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedOsString { bytes } = out);
+        ArchivedVec::resolve_from_len(len, resolver.bytes, bytes);
+    }
+
+    /// Serializes an OS string from its encoded bytes.
+    ///
+    /// The bytes must be encoded as described in [`ArchivedOsString`].
+    pub fn serialize_from_encoded_bytes<S>(
+        bytes: &[u8],
+        serializer: &mut S,
+    ) -> Result<OsStringResolver, S::Error>
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        Ok(OsStringResolver {
+            bytes: ArchivedVec::serialize_from_slice(bytes, serializer)?,
+        })
+    }
+}
+
+impl fmt::Debug for ArchivedOsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&String::from_utf8_lossy(self.as_encoded_bytes()), f)
+    }
+}
+
+impl Eq for ArchivedOsString {}
+
+impl hash::Hash for ArchivedOsString {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_encoded_bytes().hash(state);
+    }
+}
+
+impl Ord for ArchivedOsString {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_encoded_bytes().cmp(other.as_encoded_bytes())
+    }
+}
+
+impl PartialEq for ArchivedOsString {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_encoded_bytes() == other.as_encoded_bytes()
+    }
+}
+
+impl PartialEq<str> for ArchivedOsString {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_encoded_bytes() == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for ArchivedOsString {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::<str>::eq(self, other)
+    }
+}
+
+impl PartialOrd for ArchivedOsString {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The resolver for `OsString`.
+pub struct OsStringResolver {
+    bytes: VecResolver,
+}
+
 #[cfg(feature = "bytecheck")]
 mod verify {
     use core::ffi::CStr;
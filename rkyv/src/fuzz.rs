@@ -0,0 +1,273 @@
+//! Helpers for fuzzing archived types.
+//!
+//! These helpers use [`arbitrary`] to turn fuzzer input into archives. They
+//! can generate valid archives by serializing arbitrary values, and near-valid
+//! archives by mutating valid archives in ways which are likely to exercise
+//! validation edge cases (like relative pointers which point just out of
+//! bounds). The `fuzz_*` functions are complete fuzz targets which panic if
+//! they find a bug.
+//!
+//! Types containing `usize` or `isize` values which don't fit in the archived
+//! pointer width panic when they are serialized. Enable the `pointer_width_64`
+//! feature when fuzzing those types.
+//!
+//! # Examples
+//!
+//! With [`cargo-fuzz`](https://github.com/rust-fuzz/cargo-fuzz):
+//!
+//! ```ignore
+//! #![no_main]
+//!
+//! use libfuzzer_sys::fuzz_target;
+//!
+//! #[derive(arbitrary::Arbitrary, rkyv::Archive, rkyv::Serialize)]
+//! #[archive(check_bytes)]
+//! struct Inventory {
+//!     owner: String,
+//!     items: Vec<(u32, Option<String>)>,
+//! }
+//!
+//! fuzz_target!(|data: &[u8]| {
+//!     rkyv::fuzz::fuzz_mutated::<Inventory>(data);
+//! });
+//! ```
+
+use arbitrary::{Arbitrary, Unstructured};
+use bytecheck::CheckBytes;
+use rancor::{Error, Strategy};
+
+use crate::{
+    de::Pool,
+    primitive::FixedIsize,
+    ser::DefaultSerializer,
+    util::{to_bytes, AlignedVec},
+    validation::{util::from_bytes, validators::DefaultValidator},
+    Archive, Deserialize, Serialize,
+};
+
+/// Serializes an arbitrary `T` and returns the archive.
+///
+/// Serialization errors are reported as
+/// [`IncorrectFormat`](arbitrary::Error::IncorrectFormat), so fuzzers treat
+/// the input as uninteresting.
+pub fn arbitrary_archive<'a, T>(
+    u: &mut Unstructured<'a>,
+) -> arbitrary::Result<AlignedVec>
+where
+    T: Arbitrary<'a>
+        + for<'b> Serialize<DefaultSerializer<'b, AlignedVec, Error>>,
+{
+    let value = T::arbitrary(u)?;
+    to_bytes::<Error>(&value).map_err(|_| arbitrary::Error::IncorrectFormat)
+}
+
+/// Serializes an arbitrary `T` and mutates the archive.
+///
+/// See [`mutate`] for the kinds of mutations which are applied.
+pub fn arbitrary_mutated_archive<'a, T>(
+    u: &mut Unstructured<'a>,
+) -> arbitrary::Result<AlignedVec>
+where
+    T: Arbitrary<'a>
+        + for<'b> Serialize<DefaultSerializer<'b, AlignedVec, Error>>,
+{
+    let mut bytes = arbitrary_archive::<T>(u)?;
+    mutate(u, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Values which are likely to be on the edge of valid for archived lengths and
+/// relative pointers.
+fn interesting_isize(len: usize) -> [FixedIsize; 7] {
+    let len = FixedIsize::try_from(len).unwrap_or(FixedIsize::MAX);
+    [0, 1, -1, FixedIsize::MAX, FixedIsize::MIN, len, -len]
+}
+
+fn to_archived_bytes(
+    value: FixedIsize,
+) -> [u8; core::mem::size_of::<FixedIsize>()] {
+    if cfg!(feature = "big_endian") {
+        value.to_be_bytes()
+    } else {
+        value.to_le_bytes()
+    }
+}
+
+/// Applies between one and eight random mutations to the given archive.
+///
+/// Each mutation is one of:
+///
+/// - Flipping a single bit
+/// - Overwriting a byte with a random value
+/// - Overwriting a byte with a boundary value like `0x00`, `0x80`, or `0xff`
+/// - Overwriting an aligned archived `isize` with a boundary value, which is
+///   likely to corrupt a length or relative pointer
+/// - Truncating the archive
+pub fn mutate(
+    u: &mut Unstructured<'_>,
+    bytes: &mut AlignedVec,
+) -> arbitrary::Result<()> {
+    const WORD: usize = core::mem::size_of::<FixedIsize>();
+
+    let count = u.int_in_range(1..=8u32)?;
+    for _ in 0..count {
+        if bytes.is_empty() {
+            break;
+        }
+
+        let len = bytes.len();
+        match u.choose_index(5)? {
+            0 => {
+                let index = u.choose_index(len)?;
+                bytes[index] ^= 1 << u.int_in_range(0..=7u32)?;
+            }
+            1 => {
+                let index = u.choose_index(len)?;
+                bytes[index] = u.arbitrary()?;
+            }
+            2 => {
+                let index = u.choose_index(len)?;
+                bytes[index] = *u.choose(&[0x00, 0x01, 0x7f, 0x80, 0xff])?;
+            }
+            3 => {
+                if len < WORD {
+                    continue;
+                }
+                let start = u.choose_index(len / WORD)? * WORD;
+                let value = *u.choose(&interesting_isize(len))?;
+                bytes[start..start + WORD]
+                    .copy_from_slice(&to_archived_bytes(value));
+            }
+            _ => {
+                let new_len = u.choose_index(len)?;
+                bytes.resize(new_len, 0);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the given bytes as an archived `T` and returns whether they are
+/// valid.
+///
+/// The bytes are copied into an aligned buffer before they are checked, so
+/// unaligned fuzzer input can be passed directly. Checking must never panic, no
+/// matter what the bytes are.
+pub fn check_access<T>(bytes: &[u8]) -> bool
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, Error>>,
+{
+    let mut aligned = AlignedVec::<16>::with_capacity(bytes.len());
+    aligned.extend_from_slice(bytes);
+    crate::access::<T::Archived, Error>(&aligned).is_ok()
+}
+
+/// A fuzz target which checks fuzzer input directly as an archived `T`.
+pub fn fuzz_access<T>(data: &[u8])
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, Error>>,
+{
+    check_access::<T>(data);
+}
+
+/// A fuzz target which checks mutated archives of arbitrary `T`s.
+///
+/// Mutated archives are usually invalid, but are close enough to valid that
+/// checking them reaches deep into the validation code for `T`.
+pub fn fuzz_mutated<'a, T>(data: &'a [u8])
+where
+    T: Archive
+        + Arbitrary<'a>
+        + for<'b> Serialize<DefaultSerializer<'b, AlignedVec, Error>>,
+    T::Archived: for<'b> CheckBytes<Strategy<DefaultValidator<'b>, Error>>,
+{
+    let mut u = Unstructured::new(data);
+    if let Ok(bytes) = arbitrary_mutated_archive::<T>(&mut u) {
+        check_access::<T>(&bytes);
+    }
+}
+
+/// A fuzz target which serializes an arbitrary `T`, then checks and
+/// deserializes it.
+///
+/// # Panics
+///
+/// - If the archive fails to check
+/// - If the deserialized value is not equal to the original value
+///
+/// Floating-point values are compared with `PartialEq`, so values containing
+/// `NaN`s will fail the comparison.
+pub fn fuzz_roundtrip<'a, T>(data: &'a [u8])
+where
+    T: Archive
+        + Arbitrary<'a>
+        + PartialEq
+        + core::fmt::Debug
+        + for<'b> Serialize<DefaultSerializer<'b, AlignedVec, Error>>,
+    T::Archived: for<'b> CheckBytes<Strategy<DefaultValidator<'b>, Error>>
+        + Deserialize<T, Strategy<Pool, Error>>,
+{
+    let mut u = Unstructured::new(data);
+    let Ok(value) = T::arbitrary(&mut u) else {
+        return;
+    };
+    let Ok(bytes) = to_bytes::<Error>(&value) else {
+        return;
+    };
+    match from_bytes::<T, Error>(&bytes) {
+        Ok(deserialized) => assert_eq!(deserialized, value),
+        Err(e) => panic!("failed to check a valid archive: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::Unstructured;
+
+    use super::{
+        arbitrary_archive, check_access, fuzz_access, fuzz_mutated,
+        fuzz_roundtrip, mutate,
+    };
+
+    type Value = Vec<(u32, Option<String>)>;
+
+    // A fixed, varied input standing in for fuzzer-generated data.
+    fn input(seed: u8) -> Vec<u8> {
+        (0..512u32)
+            .map(|i| (i.wrapping_mul(31) as u8) ^ seed.rotate_left(i % 8))
+            .collect()
+    }
+
+    #[test]
+    fn arbitrary_archives_are_valid() {
+        for seed in 0..16 {
+            let data = input(seed);
+            let mut u = Unstructured::new(&data);
+            let bytes = arbitrary_archive::<Value>(&mut u).unwrap();
+            assert!(check_access::<Value>(&bytes));
+        }
+    }
+
+    #[test]
+    fn mutations_change_archives() {
+        let data = input(7);
+        let mut u = Unstructured::new(&data);
+        let original = arbitrary_archive::<Value>(&mut u).unwrap();
+        let mut mutated = original.clone();
+        mutate(&mut u, &mut mutated).unwrap();
+        assert_ne!(original.as_slice(), mutated.as_slice());
+    }
+
+    #[test]
+    fn fuzz_targets_do_not_panic() {
+        for seed in 0..=255 {
+            let data = input(seed);
+            fuzz_access::<Value>(&data);
+            fuzz_mutated::<Value>(&data);
+            fuzz_roundtrip::<Value>(&data);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Archived versions of `generational_arena` types.
+
+use core::{fmt, iter::FusedIterator, slice};
+
+use generational_arena::Index;
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    option::ArchivedOption,
+    primitive::{fixed_usize, ArchivedIsize, ArchivedU64, ArchivedUsize},
+    ser::{Allocator, Writer, WriterExt as _},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+/// An archived `Arena`.
+///
+/// The entries of the arena are archived in order, so values can be looked up
+/// by the same indices as the arena that was serialized.
+///
+/// Archived arenas can't be deserialized. An `Arena` can't insert values at
+/// specific indices, and its generation is shared by all of its entries, so
+/// the indices of its values can't be recreated.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedArena<T> {
+    entries: ArchivedVec<ArchivedEntry<T>>,
+    len: ArchivedUsize,
+}
+
+/// An entry of an [`ArchivedArena`].
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedEntry<T> {
+    generation: ArchivedU64,
+    value: ArchivedOption<T>,
+}
+
+impl<T> ArchivedEntry<T> {
+    /// Returns the value in the entry, if it is occupied.
+    #[inline]
+    pub fn value(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+}
+
+impl<T> ArchivedArena<T> {
+    /// Returns the number of elements in the arena.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns `true` if the arena contains no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the archived entries of the arena.
+    ///
+    /// Vacant entries are always archived with a generation of zero.
+    #[inline]
+    pub fn entries(&self) -> &[ArchivedEntry<T>] {
+        self.entries.as_slice()
+    }
+
+    /// Returns `true` if the arena contains a value for the given index.
+    #[inline]
+    pub fn contains(&self, index: Index) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Returns the value corresponding to the given index.
+    pub fn get(&self, index: Index) -> Option<&T> {
+        let (index, generation) = index.into_raw_parts();
+        let entry = self.entries.get(index)?;
+        if entry.generation.to_native() == generation {
+            entry.value()
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the index-value pairs of the arena, in order
+    /// of their indices.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+        }
+    }
+
+    /// Resolves an archived arena from the given length and resolver.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: ArenaResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedArena { entries, len: out_len } = out);
+        ArchivedVec::resolve_from_len(
+            resolver.entries_len,
+            resolver.entries,
+            entries,
+        );
+        out_len.write(ArchivedUsize::from_native(fixed_usize(len)));
+    }
+
+    /// Serializes an iterator of index-value pairs as an arena.
+    ///
+    /// The iterator must yield its indices in order.
+    pub fn serialize_from_iter<'a, I, U, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<ArenaResolver, S::Error>
+    where
+        I: Clone + IntoIterator<Item = (Index, &'a U)>,
+        U: 'a + Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        use crate::util::SerVec;
+
+        let mut len = 0;
+        let mut entries_len = 0;
+        for (index, _) in iter.clone() {
+            len += 1;
+            entries_len = index.into_raw_parts().0 + 1;
+        }
+
+        // The length is never greater than the number of entries.
+        serializer.check_len(entries_len)?;
+
+        SerVec::with_capacity(serializer, len, |resolvers, serializer| {
+            for (_, value) in iter.clone() {
+                resolvers.push(value.serialize(serializer)?);
+            }
+
+            let pos = serializer.align_for::<ArchivedEntry<T>>()?;
+            let mut values = iter.into_iter().zip(resolvers.drain()).peekable();
+            for i in 0..entries_len {
+                let next = values
+                    .next_if(|((index, _), _)| index.into_raw_parts().0 == i);
+                let (entry, resolver) = match next {
+                    Some(((index, value), resolver)) => (
+                        EntryAdapter {
+                            generation: index.into_raw_parts().1,
+                            value: Some(value),
+                        },
+                        Some(resolver),
+                    ),
+                    None => (
+                        EntryAdapter {
+                            generation: 0,
+                            value: None,
+                        },
+                        None,
+                    ),
+                };
+                unsafe {
+                    serializer.resolve_aligned(&entry, resolver)?;
+                }
+            }
+            serializer.check_offset::<ArchivedIsize>(pos)?;
+
+            Ok(ArenaResolver {
+                entries: VecResolver::from_pos(pos),
+                entries_len,
+            })
+        })?
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the index-value pairs of an [`ArchivedArena`].
+pub struct Iter<'a, T> {
+    entries: core::iter::Enumerate<slice::Iter<'a, ArchivedEntry<T>>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.find_map(|(index, entry)| {
+            let value = entry.value()?;
+            let generation = entry.generation.to_native();
+            Some((Index::from_raw_parts(index, generation), value))
+        })
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// The resolver for an [`ArchivedArena`].
+pub struct ArenaResolver {
+    entries: VecResolver,
+    entries_len: usize,
+}
+
+struct EntryAdapter<'a, T> {
+    generation: u64,
+    value: Option<&'a T>,
+}
+
+impl<T: Archive> Archive for EntryAdapter<'_, T> {
+    type Archived = ArchivedEntry<T::Archived>;
+    type Resolver = Option<T::Resolver>;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedEntry { generation, value } = out);
+        self.generation.resolve((), generation);
+        ArchivedOption::resolve_from_option(self.value, resolver, value);
+    }
+}
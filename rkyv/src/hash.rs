@@ -5,8 +5,6 @@ use core::{
     ops::BitXor as _,
 };
 
-use crate::primitive::{FixedIsize, FixedUsize};
-
 /// A cross-platform 64-bit implementation of fxhash.
 #[derive(Default)]
 pub struct FxHasher64 {
@@ -105,12 +103,15 @@ impl Hasher for FxHasher64 {
 
     #[inline]
     fn write_usize(&mut self, i: usize) {
-        self.hash = hash_word(self.hash, i as FixedUsize as u64);
+        // Sizes are widened instead of truncated to the archived pointer
+        // width. Values which fit hash the same as their archived
+        // counterparts, and values which don't fit can't collide with them.
+        self.hash = hash_word(self.hash, i as u64);
     }
 
     #[inline]
     fn write_isize(&mut self, i: isize) {
-        self.write_i64(i as FixedIsize as i64)
+        self.write_i64(i as i64)
     }
 }
 
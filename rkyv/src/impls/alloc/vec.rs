@@ -9,7 +9,8 @@ use rancor::{Fallible, ResultExt as _, Source};
 use crate::{
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
-    Archive, Deserialize, DeserializeUnsized, LayoutRaw, Place, Serialize,
+    Archive, Deserialize, DeserializeBorrowed, DeserializeUnsized, LayoutRaw,
+    Place, Serialize,
 };
 
 impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for ArchivedVec<T> {
@@ -88,3 +89,18 @@ where
         unsafe { Ok(Box::<[T]>::from_raw(out).into()) }
     }
 }
+
+impl<'a, T, U, D> DeserializeBorrowed<'a, Vec<U>, D> for ArchivedVec<T>
+where
+    T: DeserializeBorrowed<'a, U, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_borrowed(
+        &'a self,
+        deserializer: &mut D,
+    ) -> Result<Vec<U>, D::Error> {
+        self.iter()
+            .map(|value| value.deserialize_borrowed(deserializer))
+            .collect()
+    }
+}
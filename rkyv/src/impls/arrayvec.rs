@@ -1,7 +1,8 @@
 use arrayvec::ArrayVec;
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 
 use crate::{
+    impls::capacity::check_capacity,
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
     Archive, Archived, Deserialize, Place, Serialize,
@@ -38,11 +39,13 @@ where
     T: Archive,
     Archived<T>: Deserialize<T, D>,
     D: Fallible + ?Sized,
+    D::Error: Source,
 {
     fn deserialize(
         &self,
         deserializer: &mut D,
     ) -> Result<ArrayVec<T, CAP>, D::Error> {
+        check_capacity(self.len(), CAP)?;
         let mut result = ArrayVec::new();
         for item in self.as_slice() {
             result.push(item.deserialize(deserializer)?);
@@ -54,7 +57,7 @@ where
 #[cfg(test)]
 mod tests {
     use arrayvec::ArrayVec;
-    use rancor::{Error, Infallible};
+    use rancor::Error;
 
     use crate::{access_unchecked, deserialize, to_bytes, Archived};
 
@@ -68,8 +71,19 @@ mod tests {
         assert_eq!(archived.as_slice(), &[10, 20, 40, 80]);
 
         let deserialized =
-            deserialize::<ArrayVec<i32, 4>, _, Infallible>(archived, &mut ())
+            deserialize::<ArrayVec<i32, 4>, _, Error>(archived, &mut ())
                 .unwrap();
         assert_eq!(value, deserialized);
     }
+
+    #[test]
+    fn array_vec_over_capacity() {
+        let value: ArrayVec<i32, 4> = ArrayVec::from([10, 20, 40, 80]);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<ArrayVec<i32, 4>>>(&bytes) };
+        deserialize::<ArrayVec<i32, 2>, _, Error>(archived, &mut ())
+            .expect_err("deserialized more elements than the capacity");
+    }
 }
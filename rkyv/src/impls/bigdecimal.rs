@@ -0,0 +1,194 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use bigdecimal::{
+    num_bigint::{BigInt, Sign},
+    BigDecimal,
+};
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    bigdecimal::{
+        cmp_decimals, ArchivedBigDecimal, ArchivedSign, BigDecimalResolver,
+    },
+    ser::{Allocator, Writer},
+    vec::ArchivedVec,
+    Archive, Deserialize, Place, Serialize,
+};
+
+impl Archive for BigDecimal {
+    type Archived = ArchivedBigDecimal;
+    type Resolver = BigDecimalResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedBigDecimal { digits, scale, sign } = out);
+        ArchivedVec::resolve_from_len(
+            resolver.digits_len,
+            resolver.digits,
+            digits,
+        );
+        resolver.scale.resolve((), scale);
+        sign.write(ArchivedSign::from(resolver.sign));
+    }
+}
+
+impl<S> Serialize<S> for BigDecimal
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let (value, scale) = self.as_bigint_and_exponent();
+        let (sign, digits) = value.to_u32_digits();
+        Ok(BigDecimalResolver {
+            digits: ArchivedVec::serialize_from_slice(&digits, serializer)?,
+            digits_len: digits.len(),
+            sign,
+            scale,
+        })
+    }
+}
+
+impl<D> Deserialize<BigDecimal, D> for ArchivedBigDecimal
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, _: &mut D) -> Result<BigDecimal, D::Error> {
+        let value = BigInt::from_biguint(self.sign(), self.magnitude());
+        Ok(BigDecimal::new(value, self.scale()))
+    }
+}
+
+fn native_parts(value: &BigDecimal) -> (Sign, Vec<u32>, i64) {
+    let (value, scale) = value.as_bigint_and_exponent();
+    let (sign, digits) = value.to_u32_digits();
+    (sign, digits, scale)
+}
+
+impl PartialEq<BigDecimal> for ArchivedBigDecimal {
+    #[inline]
+    fn eq(&self, other: &BigDecimal) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialEq<ArchivedBigDecimal> for BigDecimal {
+    #[inline]
+    fn eq(&self, other: &ArchivedBigDecimal) -> bool {
+        other.eq(self)
+    }
+}
+
+impl PartialOrd<BigDecimal> for ArchivedBigDecimal {
+    fn partial_cmp(&self, other: &BigDecimal) -> Option<Ordering> {
+        let (sign, digits, scale) = native_parts(other);
+        Some(cmp_decimals(
+            self.parts(),
+            (sign, digits.iter().copied(), scale),
+        ))
+    }
+}
+
+impl PartialOrd<ArchivedBigDecimal> for BigDecimal {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedBigDecimal) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    use bigdecimal::{num_bigint::BigInt, BigDecimal};
+    use rancor::{Error, Infallible};
+
+    use crate::{
+        access_unchecked, bigdecimal::ArchivedBigDecimal, deserialize, to_bytes,
+    };
+
+    fn decimal(value: i128, scale: i64) -> BigDecimal {
+        BigDecimal::new(BigInt::from(value), scale)
+    }
+
+    #[test]
+    fn roundtrip_big_decimal() {
+        for value in [
+            decimal(0, 0),
+            decimal(12345, 2),
+            decimal(-12345, -3),
+            decimal(i128::MAX, 40),
+            decimal(i128::MIN, 40),
+        ] {
+            let bytes = to_bytes::<Error>(&value).unwrap();
+            let archived =
+                unsafe { access_unchecked::<ArchivedBigDecimal>(&bytes) };
+            assert_eq!(archived, &value);
+            assert_eq!(archived.scale(), value.as_bigint_and_exponent().1);
+
+            let deserialized =
+                deserialize::<BigDecimal, _, Infallible>(archived, &mut ())
+                    .unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    fn compare_archived_big_decimals() {
+        let values = [
+            decimal(i128::MIN, 0),
+            decimal(-15, 1),
+            decimal(-1, 0),
+            decimal(0, 5),
+            decimal(1, 0),
+            decimal(10, 1),
+            decimal(15, 1),
+            decimal(i128::MAX, 0),
+        ];
+        let bytes = values
+            .iter()
+            .map(|value| to_bytes::<Error>(value).unwrap())
+            .collect::<Vec<_>>();
+        let archived = bytes
+            .iter()
+            .map(|bytes| unsafe {
+                access_unchecked::<ArchivedBigDecimal>(bytes)
+            })
+            .collect::<Vec<_>>();
+
+        for (a, (archived_a, value_a)) in
+            archived.iter().zip(&values).enumerate()
+        {
+            for (b, (archived_b, value_b)) in
+                archived.iter().zip(&values).enumerate()
+            {
+                let expected = value_a.cmp(value_b);
+                assert_eq!(archived_a.cmp(archived_b), expected, "{a} {b}");
+                assert_eq!(
+                    (*archived_a).partial_cmp(value_b),
+                    Some(expected),
+                    "{a} {b}",
+                );
+            }
+        }
+
+        // Decimals are compared by value, not representation.
+        assert_eq!(archived[4], archived[5]);
+        assert_eq!(*archived[0], values[0]);
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn validate_big_decimal() {
+        use crate::access;
+
+        let bytes = to_bytes::<Error>(&decimal(-12345, 2)).unwrap();
+        access::<ArchivedBigDecimal, Error>(&bytes)
+            .expect("failed to validate archived big decimal");
+    }
+}
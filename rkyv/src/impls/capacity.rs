@@ -0,0 +1,37 @@
+use core::fmt;
+
+use rancor::{fail, Source};
+
+/// An error resulting from deserializing an archived collection into a
+/// fixed-capacity collection that is too small to hold it.
+#[derive(Debug)]
+struct CapacityExceeded {
+    len: usize,
+    capacity: usize,
+}
+
+impl fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived collection had {} elements but the deserialized \
+             collection has a capacity of {}",
+            self.len, self.capacity,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityExceeded {}
+
+/// Checks that an archived collection of the given length fits in a
+/// fixed-capacity collection.
+pub(crate) fn check_capacity<E: Source>(
+    len: usize,
+    capacity: usize,
+) -> Result<(), E> {
+    if len > capacity {
+        fail!(CapacityExceeded { len, capacity });
+    }
+    Ok(())
+}
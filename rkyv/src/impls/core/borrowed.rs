@@ -0,0 +1,62 @@
+use rancor::Fallible;
+
+use crate::{
+    boxed::ArchivedBox, option::ArchivedOption, string::ArchivedString,
+    vec::ArchivedVec, ArchivePointee, DeserializeBorrowed, Portable,
+};
+
+impl<'a, T, D> DeserializeBorrowed<'a, &'a T, D> for T
+where
+    T: Portable + ?Sized,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_borrowed(&'a self, _: &mut D) -> Result<&'a T, D::Error> {
+        Ok(self)
+    }
+}
+
+impl<'a, D: Fallible + ?Sized> DeserializeBorrowed<'a, &'a str, D>
+    for ArchivedString
+{
+    #[inline]
+    fn deserialize_borrowed(&'a self, _: &mut D) -> Result<&'a str, D::Error> {
+        Ok(self.as_str())
+    }
+}
+
+impl<'a, T, D> DeserializeBorrowed<'a, &'a [T], D> for ArchivedVec<T>
+where
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_borrowed(&'a self, _: &mut D) -> Result<&'a [T], D::Error> {
+        Ok(self.as_slice())
+    }
+}
+
+impl<'a, T, D> DeserializeBorrowed<'a, &'a T, D> for ArchivedBox<T>
+where
+    T: ArchivePointee + ?Sized,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_borrowed(&'a self, _: &mut D) -> Result<&'a T, D::Error> {
+        Ok(self.get())
+    }
+}
+
+impl<'a, T, U, D> DeserializeBorrowed<'a, Option<U>, D> for ArchivedOption<T>
+where
+    T: DeserializeBorrowed<'a, U, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_borrowed(
+        &'a self,
+        deserializer: &mut D,
+    ) -> Result<Option<U>, D::Error> {
+        self.as_ref()
+            .map(|value| value.deserialize_borrowed(deserializer))
+            .transpose()
+    }
+}
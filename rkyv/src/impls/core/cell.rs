@@ -0,0 +1,109 @@
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+use rancor::{Fallible, ResultExt as _, Source};
+
+use crate::{Archive, CopyOptimization, Deserialize, Place, Serialize};
+
+// Cell
+
+impl<T: Archive + Copy> Archive for Cell<T> {
+    const COPY_OPTIMIZATION: CopyOptimization<Self> = unsafe {
+        CopyOptimization::enable_if(T::COPY_OPTIMIZATION.is_enabled())
+    };
+    const MAX_SERIALIZED_SIZE: Option<usize> = T::MAX_SERIALIZED_SIZE;
+
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        self.get().resolve(resolver, out)
+    }
+}
+
+impl<T, S> Serialize<S> for Cell<T>
+where
+    T: Serialize<S> + Copy,
+    S: Fallible + ?Sized,
+{
+    #[inline]
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+impl<T, D> Deserialize<Cell<T>, D> for T::Archived
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<Cell<T>, D::Error> {
+        T::Archived::deserialize(self, deserializer).map(Cell::new)
+    }
+}
+
+// RefCell
+
+#[derive(Debug)]
+struct RefCellBorrowedMutably;
+
+impl fmt::Display for RefCellBorrowedMutably {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RefCell was mutably borrowed during serialization")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RefCellBorrowedMutably {}
+
+impl<T: Archive> Archive for RefCell<T> {
+    const MAX_SERIALIZED_SIZE: Option<usize> = T::MAX_SERIALIZED_SIZE;
+
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        self.borrow().resolve(resolver, out)
+    }
+}
+
+impl<T, S> Serialize<S> for RefCell<T>
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        self.try_borrow()
+            .map_err(|_| RefCellBorrowedMutably)
+            .into_error()?
+            .serialize(serializer)
+    }
+}
+
+impl<T, D> Deserialize<RefCell<T>, D> for T::Archived
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<RefCell<T>, D::Error> {
+        T::Archived::deserialize(self, deserializer).map(RefCell::new)
+    }
+}
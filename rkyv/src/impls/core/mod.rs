@@ -1,7 +1,8 @@
 use core::{
     alloc::{Layout, LayoutError},
     cell::{Cell, UnsafeCell},
-    mem::ManuallyDrop,
+    mem::{size_of, ManuallyDrop},
+    num::{Saturating, Wrapping},
     ptr::{self, addr_of_mut},
     str,
 };
@@ -10,18 +11,22 @@ use ptr_meta::Pointee;
 use rancor::Fallible;
 
 use crate::{
-    primitive::ArchivedUsize,
+    primitive::{fixed_usize, ArchivedIsize, ArchivedUsize},
     ser::{Allocator, Writer, WriterExt as _},
+    traits::is_fixed_size,
     tuple::*,
     Archive, ArchivePointee, ArchiveUnsized, ArchivedMetadata,
     CopyOptimization, Deserialize, DeserializeUnsized, LayoutRaw, Place,
     Portable, Serialize, SerializeUnsized,
 };
 
+mod borrowed;
+mod cell;
 mod ops;
 mod option;
 mod primitive;
 mod result;
+mod task;
 mod time;
 
 impl<T> LayoutRaw for T {
@@ -70,7 +75,10 @@ where
     S: Fallible + Writer + ?Sized,
 {
     fn serialize_unsized(&self, serializer: &mut S) -> Result<usize, S::Error> {
-        self.serialize_and_resolve(serializer)
+        let result = self.serialize_and_resolve(serializer)?;
+        // Relative pointers to the value are written after it.
+        serializer.check_offset::<ArchivedIsize>(result)?;
+        Ok(result)
     }
 }
 
@@ -106,6 +114,13 @@ macro_rules! impl_tuple {
         where
             $($type: Archive,)*
         {
+            const MAX_SERIALIZED_SIZE: Option<usize> =
+                if $(is_fixed_size::<$type>())&&* {
+                    Some(size_of::<Self::Archived>())
+                } else {
+                    None
+                };
+
             type Archived = $name<$($type::Archived,)*>;
             type Resolver = ($($type::Resolver,)*);
 
@@ -198,6 +213,11 @@ impl<T: Archive, const N: usize> Archive for [T; N] {
     const COPY_OPTIMIZATION: CopyOptimization<Self> = unsafe {
         CopyOptimization::enable_if(T::COPY_OPTIMIZATION.is_enabled())
     };
+    const MAX_SERIALIZED_SIZE: Option<usize> = if is_fixed_size::<T>() {
+        Some(size_of::<Self::Archived>())
+    } else {
+        None
+    };
 
     type Archived = [T::Archived; N];
     type Resolver = [T::Resolver; N];
@@ -252,7 +272,7 @@ impl<T: Archive> ArchiveUnsized for [T] {
     type Archived = [T::Archived];
 
     fn archived_metadata(&self) -> ArchivedMetadata<Self> {
-        ArchivedUsize::from_native(ptr_meta::metadata(self) as _)
+        ArchivedUsize::from_native(fixed_usize(ptr_meta::metadata(self)))
     }
 }
 
@@ -272,7 +292,10 @@ where
     S: Fallible + Allocator + Writer + ?Sized,
 {
     fn serialize_unsized(&self, serializer: &mut S) -> Result<usize, S::Error> {
-        if T::COPY_OPTIMIZATION.is_enabled() {
+        // The length is archived as metadata when this slice is resolved.
+        serializer.check_len(self.len())?;
+
+        let result = if T::COPY_OPTIMIZATION.is_enabled() {
             let result = serializer.align_for::<T::Archived>()?;
             let as_bytes = unsafe {
                 core::slice::from_raw_parts(
@@ -282,7 +305,7 @@ where
             };
             serializer.write(as_bytes)?;
 
-            Ok(result)
+            result
         } else {
             use crate::util::SerVec;
 
@@ -308,8 +331,12 @@ where
 
                     Ok(result)
                 },
-            )?
-        }
+            )??
+        };
+
+        // Relative pointers to the slice are written after its elements.
+        serializer.check_offset::<ArchivedIsize>(result)?;
+        Ok(result)
     }
 }
 
@@ -356,7 +383,7 @@ impl ArchiveUnsized for str {
 
     #[inline]
     fn archived_metadata(&self) -> ArchivedMetadata<Self> {
-        ArchivedUsize::from_native(ptr_meta::metadata(self) as _)
+        ArchivedUsize::from_native(fixed_usize(ptr_meta::metadata(self)))
     }
 }
 
@@ -373,8 +400,11 @@ impl ArchivePointee for str {
 
 impl<S: Fallible + Writer + ?Sized> SerializeUnsized<S> for str {
     fn serialize_unsized(&self, serializer: &mut S) -> Result<usize, S::Error> {
+        serializer.check_len(self.len())?;
+
         let result = serializer.pos();
         serializer.write(self.as_bytes())?;
+        serializer.check_offset::<ArchivedIsize>(result)?;
         Ok(result)
     }
 }
@@ -450,6 +480,63 @@ where
     }
 }
 
+// `Wrapping` and `Saturating`
+
+macro_rules! impl_transparent_num_wrapper {
+    ($wrapper:ident) => {
+        impl<T: Archive> Archive for $wrapper<T> {
+            const COPY_OPTIMIZATION: CopyOptimization<Self> = unsafe {
+                CopyOptimization::enable_if(T::COPY_OPTIMIZATION.is_enabled())
+            };
+            const MAX_SERIALIZED_SIZE: Option<usize> = T::MAX_SERIALIZED_SIZE;
+
+            type Archived = T::Archived;
+            type Resolver = T::Resolver;
+
+            #[inline]
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                self.0.resolve(resolver, out)
+            }
+        }
+
+        impl<T, S> Serialize<S> for $wrapper<T>
+        where
+            T: Serialize<S>,
+            S: Fallible + ?Sized,
+        {
+            #[inline]
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<T, D> Deserialize<$wrapper<T>, D> for T::Archived
+        where
+            T: Archive,
+            T::Archived: Deserialize<T, D>,
+            D: Fallible + ?Sized,
+        {
+            #[inline]
+            fn deserialize(
+                &self,
+                deserializer: &mut D,
+            ) -> Result<$wrapper<T>, D::Error> {
+                T::Archived::deserialize(self, deserializer).map($wrapper)
+            }
+        }
+    };
+}
+
+impl_transparent_num_wrapper!(Wrapping);
+impl_transparent_num_wrapper!(Saturating);
+
 // `Cell`
 
 unsafe impl<T: Portable + ?Sized> Portable for Cell<T> {}
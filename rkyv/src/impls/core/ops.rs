@@ -1,8 +1,9 @@
 use core::{
     hint::unreachable_unchecked,
+    mem::size_of,
     ops::{
-        Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo,
-        RangeToInclusive,
+        Bound, ControlFlow, Range, RangeFrom, RangeFull, RangeInclusive,
+        RangeTo, RangeToInclusive,
     },
 };
 
@@ -11,10 +12,11 @@ use rancor::Fallible;
 
 use crate::{
     ops::{
-        ArchivedBound, ArchivedRange, ArchivedRangeFrom,
+        ArchivedBound, ArchivedControlFlow, ArchivedRange, ArchivedRangeFrom,
         ArchivedRangeInclusive, ArchivedRangeTo, ArchivedRangeToInclusive,
     },
     place::Initialized,
+    traits::is_fixed_size,
     Archive, CopyOptimization, Deserialize, Place, Portable, Serialize,
 };
 
@@ -414,3 +416,140 @@ where
         }
     }
 }
+
+// ControlFlow
+
+#[allow(dead_code)]
+#[repr(u8)]
+enum ArchivedControlFlowTag {
+    Continue,
+    Break,
+}
+
+// SAFETY: `ArchivedControlFlowTag` is `repr(u8)` and so is always initialized.
+unsafe impl Initialized for ArchivedControlFlowTag {}
+
+#[repr(C)]
+struct ArchivedControlFlowVariantContinue<C>(ArchivedControlFlowTag, C);
+
+#[repr(C)]
+struct ArchivedControlFlowVariantBreak<B>(ArchivedControlFlowTag, B);
+
+impl<B: Archive, C: Archive> Archive for ControlFlow<B, C> {
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        if is_fixed_size::<B>() && is_fixed_size::<C>() {
+            Some(size_of::<Self::Archived>())
+        } else {
+            None
+        };
+
+    type Archived = ArchivedControlFlow<B::Archived, C::Archived>;
+    type Resolver = ControlFlow<B::Resolver, C::Resolver>;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        match resolver {
+            ControlFlow::Continue(resolver) => {
+                let out = unsafe {
+                    out.cast_unchecked::<
+                        ArchivedControlFlowVariantContinue<C::Archived>
+                    >()
+                };
+                munge!(
+                    let ArchivedControlFlowVariantContinue(tag, out_value) = out
+                );
+                tag.write(ArchivedControlFlowTag::Continue);
+
+                match self {
+                    ControlFlow::Continue(value) => {
+                        value.resolve(resolver, out_value)
+                    }
+                    ControlFlow::Break(_) => unsafe { unreachable_unchecked() },
+                }
+            }
+            ControlFlow::Break(resolver) => {
+                let out = unsafe {
+                    out.cast_unchecked::<
+                        ArchivedControlFlowVariantBreak<B::Archived>
+                    >()
+                };
+                munge!(
+                    let ArchivedControlFlowVariantBreak(tag, out_value) = out
+                );
+                tag.write(ArchivedControlFlowTag::Break);
+
+                match self {
+                    ControlFlow::Continue(_) => unsafe {
+                        unreachable_unchecked()
+                    },
+                    ControlFlow::Break(value) => {
+                        value.resolve(resolver, out_value)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<B, C, S> Serialize<S> for ControlFlow<B, C>
+where
+    B: Serialize<S>,
+    C: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(match self {
+            ControlFlow::Continue(value) => {
+                ControlFlow::Continue(value.serialize(serializer)?)
+            }
+            ControlFlow::Break(value) => {
+                ControlFlow::Break(value.serialize(serializer)?)
+            }
+        })
+    }
+}
+
+impl<B, C, D> Deserialize<ControlFlow<B, C>, D>
+    for ArchivedControlFlow<B::Archived, C::Archived>
+where
+    B: Archive,
+    C: Archive,
+    B::Archived: Deserialize<B, D>,
+    C::Archived: Deserialize<C, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<ControlFlow<B, C>, D::Error> {
+        Ok(match self {
+            ArchivedControlFlow::Continue(value) => {
+                ControlFlow::Continue(value.deserialize(deserializer)?)
+            }
+            ArchivedControlFlow::Break(value) => {
+                ControlFlow::Break(value.deserialize(deserializer)?)
+            }
+        })
+    }
+}
+
+impl<B, C, AB, AC> PartialEq<ControlFlow<B, C>> for ArchivedControlFlow<AB, AC>
+where
+    AB: PartialEq<B>,
+    AC: PartialEq<C>,
+{
+    fn eq(&self, other: &ControlFlow<B, C>) -> bool {
+        match (self, other) {
+            (
+                ArchivedControlFlow::Continue(this),
+                ControlFlow::Continue(other),
+            ) => this.eq(other),
+            (ArchivedControlFlow::Break(this), ControlFlow::Break(other)) => {
+                this.eq(other)
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,11 +1,11 @@
-use core::hint::unreachable_unchecked;
+use core::{hint::unreachable_unchecked, mem::size_of};
 
 use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    option::ArchivedOption, place::Initialized, Archive, Deserialize, Place,
-    Serialize,
+    option::ArchivedOption, place::Initialized, traits::is_fixed_size, Archive,
+    Deserialize, Place, Serialize,
 };
 
 #[allow(dead_code)]
@@ -25,10 +25,29 @@ struct ArchivedOptionVariantNone(ArchivedOptionTag);
 struct ArchivedOptionVariantSome<T>(ArchivedOptionTag, T);
 
 impl<T: Archive> Archive for Option<T> {
+    const MAX_SERIALIZED_SIZE: Option<usize> = if is_fixed_size::<T>() {
+        Some(size_of::<Self::Archived>())
+    } else {
+        None
+    };
+
     type Archived = ArchivedOption<T::Archived>;
     type Resolver = Option<T::Resolver>;
 
     fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedOption::resolve_from_option(self.as_ref(), resolver, out);
+    }
+}
+
+impl<T> ArchivedOption<T> {
+    /// Resolves an `ArchivedOption<U::Archived>` from an `Option<&U>`.
+    ///
+    /// The value must be `Some` if the resolver is `Some`.
+    pub fn resolve_from_option<U: Archive<Archived = T>>(
+        field: Option<&U>,
+        resolver: Option<U::Resolver>,
+        out: Place<Self>,
+    ) {
         match resolver {
             None => {
                 let out = unsafe {
@@ -39,13 +58,12 @@ impl<T: Archive> Archive for Option<T> {
             }
             Some(resolver) => {
                 let out = unsafe {
-                    out
-                    .cast_unchecked::<ArchivedOptionVariantSome<T::Archived>>()
+                    out.cast_unchecked::<ArchivedOptionVariantSome<T>>()
                 };
                 munge!(let ArchivedOptionVariantSome(tag, out_value) = out);
                 tag.write(ArchivedOptionTag::Some);
 
-                let value = if let Some(value) = self.as_ref() {
+                let value = if let Some(value) = field {
                     value
                 } else {
                     unsafe {
@@ -1,5 +1,6 @@
 use core::{
     marker::{PhantomData, PhantomPinned},
+    mem::size_of,
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
         NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
@@ -12,14 +13,16 @@ use rancor::Fallible;
 use crate::{
     place::Initialized,
     primitive::{
-        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
-        ArchivedI32, ArchivedI64, ArchivedIsize, ArchivedNonZeroI128,
-        ArchivedNonZeroI16, ArchivedNonZeroI32, ArchivedNonZeroI64,
-        ArchivedNonZeroIsize, ArchivedNonZeroU128, ArchivedNonZeroU16,
-        ArchivedNonZeroU32, ArchivedNonZeroU64, ArchivedNonZeroUsize,
-        ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64, ArchivedUsize,
+        fixed_isize, fixed_usize, ArchivedChar, ArchivedF32, ArchivedF64,
+        ArchivedI128, ArchivedI16, ArchivedI32, ArchivedI64, ArchivedIsize,
+        ArchivedNonZeroI128, ArchivedNonZeroI16, ArchivedNonZeroI32,
+        ArchivedNonZeroI64, ArchivedNonZeroIsize, ArchivedNonZeroU128,
+        ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
+        ArchivedNonZeroUsize, ArchivedU128, ArchivedU16, ArchivedU32,
+        ArchivedU64, ArchivedUsize,
     },
-    Archive, CopyOptimization, Deserialize, Place, Portable, Serialize,
+    Archive, CopyOptimization, Deserialize, DeserializeBorrowed, Place,
+    Portable, Serialize,
 };
 
 macro_rules! unsafe_impl_initialized_and_portable {
@@ -134,6 +137,8 @@ macro_rules! impl_archive_self_primitive {
         impl Archive for $type {
             const COPY_OPTIMIZATION: CopyOptimization<Self> =
                 unsafe { CopyOptimization::enable() };
+            const MAX_SERIALIZED_SIZE: Option<usize> =
+                Some(size_of::<Self::Archived>());
 
             type Archived = Self;
             type Resolver = ();
@@ -151,6 +156,15 @@ macro_rules! impl_archive_self_primitive {
                 Ok(*self)
             }
         }
+
+        impl<D: Fallible + ?Sized> DeserializeBorrowed<'_, $type, D> for $type {
+            fn deserialize_borrowed(
+                &self,
+                _: &mut D,
+            ) -> Result<$type, D::Error> {
+                Ok(*self)
+            }
+        }
     };
 }
 
@@ -190,6 +204,8 @@ macro_rules! impl_multibyte_primitive {
                     MULTIBYTE_PRIMITIVES_ARE_TRIVIALLY_COPYABLE,
                 )
             };
+            const MAX_SERIALIZED_SIZE: Option<usize> =
+                Some(size_of::<Self::Archived>());
 
             type Archived = $archived;
             type Resolver = ();
@@ -207,6 +223,18 @@ macro_rules! impl_multibyte_primitive {
                 Ok(self.to_native())
             }
         }
+
+        impl<D> DeserializeBorrowed<'_, $type, D> for $archived
+        where
+            D: Fallible + ?Sized,
+        {
+            fn deserialize_borrowed(
+                &self,
+                _: &mut D,
+            ) -> Result<$type, D::Error> {
+                Ok(self.to_native())
+            }
+        }
     };
 }
 
@@ -247,6 +275,7 @@ unsafe impl<T: ?Sized> Portable for PhantomData<T> {}
 impl<T: ?Sized> Archive for PhantomData<T> {
     const COPY_OPTIMIZATION: CopyOptimization<Self> =
         unsafe { CopyOptimization::enable() };
+    const MAX_SERIALIZED_SIZE: Option<usize> = Some(0);
 
     type Archived = PhantomData<T>;
     type Resolver = ();
@@ -275,6 +304,7 @@ unsafe_impl_initialized_and_portable!(PhantomPinned);
 impl Archive for PhantomPinned {
     const COPY_OPTIMIZATION: CopyOptimization<Self> =
         unsafe { CopyOptimization::enable() };
+    const MAX_SERIALIZED_SIZE: Option<usize> = Some(0);
 
     type Archived = PhantomPinned;
     type Resolver = ();
@@ -323,13 +353,15 @@ impl Archive for usize {
                 && POINTER_WIDTH_EQUALS_ARCHIVED_POINTER_WIDTH,
         )
     };
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
 
     type Archived = ArchivedUsize;
     type Resolver = ();
 
     #[inline]
     fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
-        out.write(ArchivedUsize::from_native(*self as _));
+        out.write(ArchivedUsize::from_native(fixed_usize(*self)));
     }
 }
 
@@ -345,6 +377,12 @@ impl<D: Fallible + ?Sized> Deserialize<usize, D> for ArchivedUsize {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeBorrowed<'_, usize, D> for ArchivedUsize {
+    fn deserialize_borrowed(&self, _: &mut D) -> Result<usize, D::Error> {
+        Ok(self.to_native() as usize)
+    }
+}
+
 // isize
 
 impl Archive for isize {
@@ -354,13 +392,15 @@ impl Archive for isize {
                 && POINTER_WIDTH_EQUALS_ARCHIVED_POINTER_WIDTH,
         )
     };
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
 
     type Archived = ArchivedIsize;
     type Resolver = ();
 
     #[inline]
     fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
-        out.write(ArchivedIsize::from_native(*self as _));
+        out.write(ArchivedIsize::from_native(fixed_isize(*self)));
     }
 }
 
@@ -376,6 +416,12 @@ impl<D: Fallible + ?Sized> Deserialize<isize, D> for ArchivedIsize {
     }
 }
 
+impl<D: Fallible + ?Sized> DeserializeBorrowed<'_, isize, D> for ArchivedIsize {
+    fn deserialize_borrowed(&self, _: &mut D) -> Result<isize, D::Error> {
+        Ok(self.to_native() as isize)
+    }
+}
+
 // NonZeroUsize
 
 impl Archive for NonZeroUsize {
@@ -385,14 +431,19 @@ impl Archive for NonZeroUsize {
                 && POINTER_WIDTH_EQUALS_ARCHIVED_POINTER_WIDTH,
         )
     };
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
 
     type Archived = ArchivedNonZeroUsize;
     type Resolver = ();
 
     #[inline]
     fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
-        let value =
-            unsafe { ArchivedNonZeroUsize::new_unchecked(self.get() as _) };
+        // SAFETY: Converting a nonzero value without truncating it keeps it
+        // nonzero.
+        let value = unsafe {
+            ArchivedNonZeroUsize::new_unchecked(fixed_usize(self.get()))
+        };
         out.write(value);
     }
 }
@@ -421,14 +472,19 @@ impl Archive for NonZeroIsize {
                 && POINTER_WIDTH_EQUALS_ARCHIVED_POINTER_WIDTH,
         )
     };
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
 
     type Archived = ArchivedNonZeroIsize;
     type Resolver = ();
 
     #[inline]
     fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
-        let value =
-            unsafe { ArchivedNonZeroIsize::new_unchecked(self.get() as _) };
+        // SAFETY: Converting a nonzero value without truncating it keeps it
+        // nonzero.
+        let value = unsafe {
+            ArchivedNonZeroIsize::new_unchecked(fixed_isize(self.get()))
+        };
         out.write(value);
     }
 }
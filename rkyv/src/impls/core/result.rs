@@ -1,11 +1,11 @@
-use core::hint::unreachable_unchecked;
+use core::{hint::unreachable_unchecked, mem::size_of};
 
 use munge::munge;
 use rancor::Fallible;
 
 use crate::{
-    place::Initialized, result::ArchivedResult, Archive, Deserialize, Place,
-    Serialize,
+    place::Initialized, result::ArchivedResult, traits::is_fixed_size, Archive,
+    Deserialize, Place, Serialize,
 };
 
 #[allow(dead_code)]
@@ -25,6 +25,13 @@ struct ArchivedResultVariantOk<T>(ArchivedResultTag, T);
 struct ArchivedResultVariantErr<U>(ArchivedResultTag, U);
 
 impl<T: Archive, U: Archive> Archive for Result<T, U> {
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        if is_fixed_size::<T>() && is_fixed_size::<U>() {
+            Some(size_of::<Self::Archived>())
+        } else {
+            None
+        };
+
     type Archived = ArchivedResult<T::Archived, U::Archived>;
     type Resolver = Result<T::Resolver, U::Resolver>;
 
@@ -0,0 +1,99 @@
+use core::{hint::unreachable_unchecked, mem::size_of, task::Poll};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    place::Initialized, task::ArchivedPoll, traits::is_fixed_size, Archive,
+    Deserialize, Place, Serialize,
+};
+
+#[allow(dead_code)]
+#[repr(u8)]
+enum ArchivedPollTag {
+    Ready,
+    Pending,
+}
+
+// SAFETY: `ArchivedPollTag` is `repr(u8)` and so is always initialized.
+unsafe impl Initialized for ArchivedPollTag {}
+
+#[repr(C)]
+struct ArchivedPollVariantReady<T>(ArchivedPollTag, T);
+
+#[repr(C)]
+struct ArchivedPollVariantPending(ArchivedPollTag);
+
+impl<T: Archive> Archive for Poll<T> {
+    const MAX_SERIALIZED_SIZE: Option<usize> = if is_fixed_size::<T>() {
+        Some(size_of::<Self::Archived>())
+    } else {
+        None
+    };
+
+    type Archived = ArchivedPoll<T::Archived>;
+    type Resolver = Poll<T::Resolver>;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        match resolver {
+            Poll::Ready(resolver) => {
+                let out = unsafe {
+                    out.cast_unchecked::<ArchivedPollVariantReady<T::Archived>>(
+                    )
+                };
+                munge!(let ArchivedPollVariantReady(tag, out_value) = out);
+                tag.write(ArchivedPollTag::Ready);
+
+                match self {
+                    Poll::Ready(value) => value.resolve(resolver, out_value),
+                    Poll::Pending => unsafe { unreachable_unchecked() },
+                }
+            }
+            Poll::Pending => {
+                let out = unsafe {
+                    out.cast_unchecked::<ArchivedPollVariantPending>()
+                };
+                munge!(let ArchivedPollVariantPending(tag) = out);
+                tag.write(ArchivedPollTag::Pending);
+            }
+        }
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + ?Sized> Serialize<S> for Poll<T> {
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(match self {
+            Poll::Ready(value) => Poll::Ready(value.serialize(serializer)?),
+            Poll::Pending => Poll::Pending,
+        })
+    }
+}
+
+impl<T, D> Deserialize<Poll<T>, D> for ArchivedPoll<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Poll<T>, D::Error> {
+        Ok(match self {
+            ArchivedPoll::Ready(value) => {
+                Poll::Ready(value.deserialize(deserializer)?)
+            }
+            ArchivedPoll::Pending => Poll::Pending,
+        })
+    }
+}
+
+impl<T, U: PartialEq<T>> PartialEq<Poll<T>> for ArchivedPoll<U> {
+    fn eq(&self, other: &Poll<T>) -> bool {
+        match (self, other) {
+            (ArchivedPoll::Ready(this), Poll::Ready(other)) => this.eq(other),
+            (ArchivedPoll::Pending, Poll::Pending) => true,
+            _ => false,
+        }
+    }
+}
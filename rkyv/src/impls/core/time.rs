@@ -1,10 +1,13 @@
-use core::time::Duration;
+use core::{mem::size_of, time::Duration};
 
 use rancor::Fallible;
 
 use crate::{time::ArchivedDuration, Archive, Deserialize, Place, Serialize};
 
 impl Archive for Duration {
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
+
     type Archived = ArchivedDuration;
     type Resolver = ();
 
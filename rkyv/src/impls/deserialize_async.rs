@@ -0,0 +1,164 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::{boxed::Box, rc, string::String, sync, vec::Vec};
+use core::future::{ready, Future};
+#[cfg(feature = "std")]
+use std::{rc, sync};
+
+use rancor::Fallible;
+#[cfg(feature = "alloc")]
+use rancor::Source;
+
+#[cfg(feature = "alloc")]
+use crate::{
+    boxed::ArchivedBox,
+    de::{Pooling, PoolingExt as _},
+    rc::{ArcFlavor, ArchivedRc, RcFlavor},
+    vec::ArchivedVec,
+};
+use crate::{
+    option::ArchivedOption, Archive, Archived, Deserialize, DeserializeAsync,
+};
+
+// Types which don't contain other archived values are deserialized
+// synchronously.
+
+macro_rules! impl_deserialize_async_sync {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<D> DeserializeAsync<$ty, D> for Archived<$ty>
+            where
+                D: Fallible + ?Sized,
+                Archived<$ty>: Deserialize<$ty, D>,
+            {
+                fn deserialize_async(
+                    &self,
+                    deserializer: &mut D,
+                ) -> impl Future<Output = Result<$ty, D::Error>> {
+                    ready(self.deserialize(deserializer))
+                }
+            }
+        )*
+    };
+}
+
+impl_deserialize_async_sync! {
+    (),
+    bool,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+    f32, f64,
+    char,
+}
+
+#[cfg(feature = "alloc")]
+impl_deserialize_async_sync! {
+    String,
+}
+
+// Option
+
+impl<T, D> DeserializeAsync<Option<T>, D> for ArchivedOption<T::Archived>
+where
+    T: Archive,
+    T::Archived: DeserializeAsync<T, D>,
+    D: Fallible + ?Sized,
+{
+    async fn deserialize_async(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Option<T>, D::Error> {
+        Ok(match self {
+            ArchivedOption::Some(value) => {
+                Some(value.deserialize_async(deserializer).await?)
+            }
+            ArchivedOption::None => None,
+        })
+    }
+}
+
+// Box
+
+#[cfg(feature = "alloc")]
+impl<T, D> DeserializeAsync<Box<T>, D> for ArchivedBox<T::Archived>
+where
+    T: Archive,
+    T::Archived: DeserializeAsync<T, D>,
+    D: Fallible + ?Sized,
+{
+    async fn deserialize_async(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Box<T>, D::Error> {
+        Ok(Box::new(self.get().deserialize_async(deserializer).await?))
+    }
+}
+
+// Vec
+
+#[cfg(feature = "alloc")]
+impl<T, D> DeserializeAsync<Vec<T>, D> for ArchivedVec<T::Archived>
+where
+    T: Archive,
+    T::Archived: DeserializeAsync<T, D>,
+    D: Fallible + ?Sized,
+{
+    async fn deserialize_async(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let mut result = Vec::with_capacity(self.len());
+        for value in self.iter() {
+            result.push(value.deserialize_async(deserializer).await?);
+        }
+        Ok(result)
+    }
+}
+
+// Rc
+
+#[cfg(feature = "alloc")]
+impl<T, D> DeserializeAsync<rc::Rc<T>, D> for ArchivedRc<T::Archived, RcFlavor>
+where
+    T: Archive + 'static,
+    T::Archived: DeserializeAsync<T, D>,
+    D: Fallible + Pooling + ?Sized,
+    D::Error: Source,
+{
+    async fn deserialize_async(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<rc::Rc<T>, D::Error> {
+        let raw_shared_ptr = deserializer
+            .deserialize_shared_async::<T, rc::Rc<T>>(self.get())
+            .await?;
+        unsafe {
+            rc::Rc::<T>::increment_strong_count(raw_shared_ptr);
+        }
+        unsafe { Ok(rc::Rc::<T>::from_raw(raw_shared_ptr)) }
+    }
+}
+
+// Arc
+
+#[cfg(feature = "alloc")]
+impl<T, D> DeserializeAsync<sync::Arc<T>, D>
+    for ArchivedRc<T::Archived, ArcFlavor>
+where
+    T: Archive + 'static,
+    T::Archived: DeserializeAsync<T, D>,
+    D: Fallible + Pooling + ?Sized,
+    D::Error: Source,
+{
+    async fn deserialize_async(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<sync::Arc<T>, D::Error> {
+        let raw_shared_ptr = deserializer
+            .deserialize_shared_async::<T, sync::Arc<T>>(self.get())
+            .await?;
+        unsafe {
+            sync::Arc::<T>::increment_strong_count(raw_shared_ptr);
+        }
+        unsafe { Ok(sync::Arc::<T>::from_raw(raw_shared_ptr)) }
+    }
+}
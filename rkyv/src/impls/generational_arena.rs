@@ -0,0 +1,82 @@
+use generational_arena::Arena;
+use rancor::Fallible;
+
+use crate::{
+    generational_arena::{ArchivedArena, ArenaResolver},
+    ser::{Allocator, Writer},
+    Archive, Place, Serialize,
+};
+
+impl<T: Archive> Archive for Arena<T> {
+    type Archived = ArchivedArena<T::Archived>;
+    type Resolver = ArenaResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedArena::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for Arena<T>
+where
+    T: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedArena::serialize_from_iter(self, serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::string::{String, ToString};
+
+    use generational_arena::Arena;
+    use rancor::Error;
+
+    use crate::{
+        access_unchecked, generational_arena::ArchivedArena,
+        string::ArchivedString, to_bytes,
+    };
+
+    fn arena() -> (Arena<String>, [generational_arena::Index; 3]) {
+        let mut arena = Arena::new();
+        let a = arena.insert("a".to_string());
+        let b = arena.insert("b".to_string());
+        arena.remove(a);
+        let c = arena.insert("c".to_string());
+        (arena, [a, b, c])
+    }
+
+    #[test]
+    fn archive_arena() {
+        let (value, [removed, b, c]) = arena();
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<ArchivedArena<ArchivedString>>(&bytes)
+        };
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived.get(b).unwrap(), "b");
+        assert_eq!(archived.get(c).unwrap(), "c");
+        assert!(!archived.contains(removed));
+        assert!(archived
+            .iter()
+            .map(|(index, value)| (index, value.as_str()))
+            .eq(value.iter().map(|(index, value)| (index, value.as_str()))));
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn validate_arena() {
+        use crate::access;
+
+        let (value, _) = arena();
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        access::<ArchivedArena<ArchivedString>, Error>(&bytes)
+            .expect("failed to validate archived arena");
+    }
+}
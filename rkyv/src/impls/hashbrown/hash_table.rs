@@ -0,0 +1,206 @@
+use core::hash::Hash;
+
+use hashbrown::HashTable;
+use rancor::{Fallible, Source};
+
+use crate::{
+    collections::swiss_table::table::{ArchivedHashTable, HashTableResolver},
+    hash::{hash_value, FxHasher64},
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+// `HashTable` doesn't store a hasher, so items are hashed with the same
+// default hasher as archived hash maps. To archive a table with a different
+// hash function, use `ArchivedHashTable::serialize_from_iter_with_hasher`.
+
+impl<T: Archive> Archive for HashTable<T> {
+    type Archived = ArchivedHashTable<T::Archived>;
+    type Resolver = HashTableResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedHashTable::<T::Archived>::resolve_from_len(
+            self.len(),
+            (7, 8),
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<T, S> Serialize<S> for HashTable<T>
+where
+    T: Serialize<S> + Hash,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedHashTable::<T::Archived>::serialize_from_iter_with_hasher(
+            self.iter(),
+            hash_value::<T, FxHasher64>,
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> Deserialize<HashTable<T>, D> for ArchivedHashTable<T::Archived>
+where
+    T: Archive + Hash,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<HashTable<T>, D::Error> {
+        let mut result = HashTable::with_capacity(self.len());
+        for item in self.raw_iter() {
+            let item = unsafe { item.as_ref() }.deserialize(deserializer)?;
+            result.insert_unique(
+                hash_value::<T, FxHasher64>(&item),
+                item,
+                hash_value::<T, FxHasher64>,
+            );
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    use alloc::string::{String, ToString};
+
+    use hashbrown::HashTable;
+    use rancor::Error;
+
+    use crate::{
+        access_unchecked,
+        collections::swiss_table::ArchivedHashTable,
+        deserialize,
+        hash::{hash_value, FxHasher64},
+        string::ArchivedString,
+        to_bytes,
+    };
+
+    fn table() -> HashTable<String> {
+        let mut table = HashTable::new();
+        for value in ["foo", "bar", "baz", "bat"] {
+            table.insert_unique(
+                hash_value::<str, FxHasher64>(value),
+                value.to_string(),
+                hash_value::<String, FxHasher64>,
+            );
+        }
+        table
+    }
+
+    #[test]
+    fn roundtrip_hash_table() {
+        let value = table();
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<ArchivedHashTable<ArchivedString>>(&bytes)
+        };
+        assert_eq!(archived.len(), value.len());
+        for item in value.iter() {
+            let hash = hash_value::<str, FxHasher64>(item);
+            let archived_item = archived
+                .get_with(hash, |archived| archived == item)
+                .unwrap();
+            assert_eq!(archived_item, item);
+        }
+        assert!(archived
+            .get_with(hash_value::<str, FxHasher64>("qux"), |_| true)
+            .is_none());
+
+        let deserialized =
+            deserialize::<HashTable<String>, _, Error>(archived, &mut ())
+                .unwrap();
+        assert_eq!(deserialized.len(), value.len());
+        for item in value.iter() {
+            let hash = hash_value::<str, FxHasher64>(item);
+            assert!(deserialized.find(hash, |other| other == item).is_some());
+        }
+    }
+
+    #[test]
+    fn custom_hasher() {
+        use rancor::{Fallible, Source};
+
+        use crate::{
+            collections::swiss_table::HashTableResolver,
+            ser::{Allocator, Writer},
+            Archive, Place, Serialize,
+        };
+
+        fn hasher(value: &str) -> u64 {
+            value.len() as u64
+        }
+
+        struct ByLength([String; 3]);
+
+        impl Archive for ByLength {
+            type Archived = ArchivedHashTable<ArchivedString>;
+            type Resolver = HashTableResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedHashTable::resolve_from_len(
+                    self.0.len(),
+                    (7, 8),
+                    resolver,
+                    out,
+                );
+            }
+        }
+
+        impl<S> Serialize<S> for ByLength
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedHashTable::serialize_from_iter_with_hasher(
+                    self.0.iter(),
+                    |value: &String| hasher(value),
+                    (7, 8),
+                    serializer,
+                )
+            }
+        }
+
+        let value = ByLength(["a", "bb", "ccc"].map(String::from));
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<ArchivedHashTable<ArchivedString>>(&bytes)
+        };
+        for item in value.0.iter() {
+            let archived_item = archived
+                .get_with(hasher(item), |archived| archived == item)
+                .unwrap();
+            assert_eq!(archived_item, item);
+        }
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn validate_hash_table() {
+        use crate::access;
+
+        let bytes = to_bytes::<Error>(&table()).unwrap();
+        access::<ArchivedHashTable<ArchivedString>, Error>(&bytes)
+            .expect("failed to validate archived hash table");
+    }
+}
@@ -0,0 +1,361 @@
+use core::{cmp, fmt, mem::size_of};
+
+use jiff::{civil::DateTime, Span, Timestamp};
+use munge::munge;
+use rancor::{Fallible, ResultExt as _, Source};
+
+use crate::{
+    jiff::{ArchivedDateTime, ArchivedSpan, ArchivedTimestamp},
+    Archive, Deserialize, Place, Serialize,
+};
+
+/// An error resulting from an archived `jiff` value which is out of range.
+#[derive(Debug)]
+struct JiffError(jiff::Error);
+
+impl fmt::Display for JiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid archived jiff value: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JiffError {}
+
+// Timestamp
+
+impl Archive for Timestamp {
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
+
+    type Archived = ArchivedTimestamp;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedTimestamp { second, nanosecond } = out);
+        self.as_second().resolve((), second);
+        self.subsec_nanosecond().resolve((), nanosecond);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Timestamp {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D> Deserialize<Timestamp, D> for ArchivedTimestamp
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<Timestamp, D::Error> {
+        Timestamp::new(self.as_second(), self.subsec_nanosecond())
+            .map_err(JiffError)
+            .into_error()
+    }
+}
+
+impl PartialEq<Timestamp> for ArchivedTimestamp {
+    #[inline]
+    fn eq(&self, other: &Timestamp) -> bool {
+        self.as_second() == other.as_second()
+            && self.subsec_nanosecond() == other.subsec_nanosecond()
+    }
+}
+
+impl PartialEq<ArchivedTimestamp> for Timestamp {
+    #[inline]
+    fn eq(&self, other: &ArchivedTimestamp) -> bool {
+        other.eq(self)
+    }
+}
+
+impl PartialOrd<Timestamp> for ArchivedTimestamp {
+    #[inline]
+    fn partial_cmp(&self, other: &Timestamp) -> Option<cmp::Ordering> {
+        Some(
+            self.as_second()
+                .cmp(&other.as_second())
+                .then(self.subsec_nanosecond().cmp(&other.subsec_nanosecond())),
+        )
+    }
+}
+
+impl PartialOrd<ArchivedTimestamp> for Timestamp {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedTimestamp) -> Option<cmp::Ordering> {
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+// DateTime
+
+impl Archive for DateTime {
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
+
+    type Archived = ArchivedDateTime;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge! {
+            let ArchivedDateTime {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                subsec_nanosecond,
+            } = out;
+        }
+        self.year().resolve((), year);
+        self.month().resolve((), month);
+        self.day().resolve((), day);
+        self.hour().resolve((), hour);
+        self.minute().resolve((), minute);
+        self.second().resolve((), second);
+        self.subsec_nanosecond().resolve((), subsec_nanosecond);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for DateTime {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D> Deserialize<DateTime, D> for ArchivedDateTime
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<DateTime, D::Error> {
+        DateTime::new(
+            self.year(),
+            self.month(),
+            self.day(),
+            self.hour(),
+            self.minute(),
+            self.second(),
+            self.subsec_nanosecond(),
+        )
+        .map_err(JiffError)
+        .into_error()
+    }
+}
+
+impl ArchivedDateTime {
+    #[inline]
+    fn as_tuple(&self) -> (i16, i8, i8, i8, i8, i8, i32) {
+        (
+            self.year(),
+            self.month(),
+            self.day(),
+            self.hour(),
+            self.minute(),
+            self.second(),
+            self.subsec_nanosecond(),
+        )
+    }
+}
+
+#[inline]
+fn date_time_tuple(dt: &DateTime) -> (i16, i8, i8, i8, i8, i8, i32) {
+    (
+        dt.year(),
+        dt.month(),
+        dt.day(),
+        dt.hour(),
+        dt.minute(),
+        dt.second(),
+        dt.subsec_nanosecond(),
+    )
+}
+
+impl PartialEq<DateTime> for ArchivedDateTime {
+    #[inline]
+    fn eq(&self, other: &DateTime) -> bool {
+        self.as_tuple() == date_time_tuple(other)
+    }
+}
+
+impl PartialEq<ArchivedDateTime> for DateTime {
+    #[inline]
+    fn eq(&self, other: &ArchivedDateTime) -> bool {
+        other.eq(self)
+    }
+}
+
+impl PartialOrd<DateTime> for ArchivedDateTime {
+    #[inline]
+    fn partial_cmp(&self, other: &DateTime) -> Option<cmp::Ordering> {
+        Some(self.as_tuple().cmp(&date_time_tuple(other)))
+    }
+}
+
+impl PartialOrd<ArchivedDateTime> for DateTime {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedDateTime) -> Option<cmp::Ordering> {
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+// Span
+
+impl Archive for Span {
+    const MAX_SERIALIZED_SIZE: Option<usize> =
+        Some(size_of::<Self::Archived>());
+
+    type Archived = ArchivedSpan;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge! {
+            let ArchivedSpan {
+                years,
+                months,
+                weeks,
+                days,
+                hours,
+                minutes,
+                seconds,
+                milliseconds,
+                microseconds,
+                nanoseconds,
+            } = out;
+        }
+        self.get_years().resolve((), years);
+        self.get_months().resolve((), months);
+        self.get_weeks().resolve((), weeks);
+        self.get_days().resolve((), days);
+        self.get_hours().resolve((), hours);
+        self.get_minutes().resolve((), minutes);
+        self.get_seconds().resolve((), seconds);
+        self.get_milliseconds().resolve((), milliseconds);
+        self.get_microseconds().resolve((), microseconds);
+        self.get_nanoseconds().resolve((), nanoseconds);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Span {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D> Deserialize<Span, D> for ArchivedSpan
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<Span, D::Error> {
+        let span = || {
+            Span::new()
+                .try_years(self.get_years())?
+                .try_months(self.get_months())?
+                .try_weeks(self.get_weeks())?
+                .try_days(self.get_days())?
+                .try_hours(self.get_hours())?
+                .try_minutes(self.get_minutes())?
+                .try_seconds(self.get_seconds())?
+                .try_milliseconds(self.get_milliseconds())?
+                .try_microseconds(self.get_microseconds())?
+                .try_nanoseconds(self.get_nanoseconds())
+        };
+        span().map_err(JiffError).into_error()
+    }
+}
+
+impl PartialEq<Span> for ArchivedSpan {
+    #[inline]
+    fn eq(&self, other: &Span) -> bool {
+        self.get_years() == other.get_years()
+            && self.get_months() == other.get_months()
+            && self.get_weeks() == other.get_weeks()
+            && self.get_days() == other.get_days()
+            && self.get_hours() == other.get_hours()
+            && self.get_minutes() == other.get_minutes()
+            && self.get_seconds() == other.get_seconds()
+            && self.get_milliseconds() == other.get_milliseconds()
+            && self.get_microseconds() == other.get_microseconds()
+            && self.get_nanoseconds() == other.get_nanoseconds()
+    }
+}
+
+impl PartialEq<ArchivedSpan> for Span {
+    #[inline]
+    fn eq(&self, other: &ArchivedSpan) -> bool {
+        other.eq(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt;
+
+    use jiff::{civil::DateTime, Span, Timestamp};
+    use rancor::{Error, Strategy};
+
+    use crate::{
+        access_unchecked, deserialize, jiff::ArchivedTimestamp,
+        ser::DefaultSerializer, to_bytes, util::AlignedVec, Deserialize,
+        Serialize,
+    };
+
+    fn roundtrip<T>(value: &T)
+    where
+        T: fmt::Debug
+            + PartialEq
+            + PartialEq<T::Archived>
+            + for<'a> Serialize<DefaultSerializer<'a, AlignedVec, Error>>,
+        T::Archived: Deserialize<T, Strategy<(), Error>>,
+    {
+        let bytes = to_bytes::<Error>(value).unwrap();
+        let archived = unsafe { access_unchecked::<T::Archived>(&bytes) };
+        assert!(value == archived);
+
+        let deserialized =
+            deserialize::<T, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(value, &deserialized);
+    }
+
+    #[test]
+    fn roundtrip_timestamp() {
+        roundtrip(&Timestamp::UNIX_EPOCH);
+        roundtrip(&Timestamp::new(1_700_000_000, 123_456_789).unwrap());
+        roundtrip(&Timestamp::new(-1_700_000_000, -123_456_789).unwrap());
+        roundtrip(&Timestamp::MIN);
+        roundtrip(&Timestamp::MAX);
+    }
+
+    #[test]
+    fn roundtrip_date_time() {
+        roundtrip(&DateTime::new(2024, 7, 21, 13, 45, 10, 500).unwrap());
+        roundtrip(&DateTime::MIN);
+        roundtrip(&DateTime::MAX);
+    }
+
+    #[test]
+    fn roundtrip_span() {
+        roundtrip(&Span::new());
+        roundtrip(&Span::new().years(1).months(2).days(3).hours(4));
+        roundtrip(&Span::new().minutes(-5).seconds(-6).nanoseconds(-7));
+    }
+
+    #[test]
+    fn compare_timestamp() {
+        let value = Timestamp::new(1_700_000_000, 0).unwrap();
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTimestamp>(&bytes) };
+
+        assert!(*archived < Timestamp::new(1_700_000_000, 1).unwrap());
+        assert!(*archived > Timestamp::UNIX_EPOCH);
+        assert!(Timestamp::MAX > *archived);
+        assert_eq!(archived.as_nanosecond(), value.as_nanosecond());
+    }
+}
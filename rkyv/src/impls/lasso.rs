@@ -0,0 +1,256 @@
+use core::{fmt, hash::BuildHasher};
+
+use lasso::{Key, Rodeo, RodeoReader, Spur};
+use munge::munge;
+use rancor::{fail, Fallible, ResultExt as _, Source};
+
+use crate::{
+    lasso::{ArchivedRodeo, ArchivedSpur, RodeoResolver},
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+/// An error resulting from an archived interner that contains the same string
+/// more than once.
+#[derive(Debug)]
+struct DuplicateString;
+
+impl fmt::Display for DuplicateString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "archived interner contained a duplicate string")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DuplicateString {}
+
+/// Returns the key for the string at the given position of an interner.
+fn key_at<K: Key>(index: usize) -> K {
+    // Interners assign keys in order, so every index less than the length of
+    // an interner is a valid key.
+    K::try_from_usize(index).expect("interner index was not a valid key")
+}
+
+// Spur
+
+impl Archive for Spur {
+    type Archived = ArchivedSpur;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedSpur { key } = out);
+        self.into_inner().resolve((), key);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Spur {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<Spur, D> for ArchivedSpur {
+    fn deserialize(&self, _: &mut D) -> Result<Spur, D::Error> {
+        // Archived spurs are nonzero 32-bit integers, the same as spurs.
+        Ok(key_at(self.into_usize()))
+    }
+}
+
+impl PartialEq<ArchivedSpur> for Spur {
+    #[inline]
+    fn eq(&self, other: &ArchivedSpur) -> bool {
+        other.eq(self)
+    }
+}
+
+// Rodeo
+
+impl<K: Key, H: BuildHasher + Clone> Archive for Rodeo<K, H> {
+    type Archived = ArchivedRodeo;
+    type Resolver = RodeoResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedRodeo::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<K, H, S> Serialize<S> for Rodeo<K, H>
+where
+    K: Key,
+    H: BuildHasher + Clone,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRodeo::serialize_from_strings(
+            (0..self.len()).map(|index| self.resolve(&key_at(index))),
+            serializer,
+        )
+    }
+}
+
+impl<K, H, D> Deserialize<Rodeo<K, H>, D> for ArchivedRodeo
+where
+    K: Key,
+    H: BuildHasher + Clone + Default,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<Rodeo<K, H>, D::Error> {
+        let mut result = Rodeo::with_hasher(H::default());
+        for (index, string) in self.strings().iter().enumerate() {
+            let key: K =
+                result.try_get_or_intern(string.as_str()).into_error()?;
+            if key.into_usize() != index {
+                fail!(DuplicateString);
+            }
+        }
+        Ok(result)
+    }
+}
+
+// RodeoReader
+
+impl<K: Key, H: BuildHasher + Clone> Archive for RodeoReader<K, H> {
+    type Archived = ArchivedRodeo;
+    type Resolver = RodeoResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedRodeo::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<K, H, S> Serialize<S> for RodeoReader<K, H>
+where
+    K: Key,
+    H: BuildHasher + Clone,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRodeo::serialize_from_strings(
+            (0..self.len()).map(|index| self.resolve(&key_at(index))),
+            serializer,
+        )
+    }
+}
+
+impl<K, H, D> Deserialize<RodeoReader<K, H>, D> for ArchivedRodeo
+where
+    K: Key,
+    H: BuildHasher + Clone + Default,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<RodeoReader<K, H>, D::Error> {
+        let rodeo: Rodeo<K, H> = self.deserialize(deserializer)?;
+        Ok(rodeo.into_reader())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::String, vec, vec::Vec};
+
+    use lasso::{Key as _, Rodeo, RodeoReader, Spur};
+    use rancor::Error;
+
+    use crate::{
+        access_unchecked, deserialize,
+        lasso::{ArchivedRodeo, ArchivedSpur},
+        to_bytes,
+        tuple::ArchivedTuple2,
+        vec::ArchivedVec,
+    };
+
+    type Document = (Rodeo, Vec<Spur>);
+    type ArchivedDocument =
+        ArchivedTuple2<ArchivedRodeo, ArchivedVec<ArchivedSpur>>;
+
+    fn document() -> Document {
+        let mut interner = Rodeo::new();
+        let words = "the quick brown fox jumps over the lazy dog"
+            .split(' ')
+            .map(|word| interner.get_or_intern(word))
+            .collect();
+        (interner, words)
+    }
+
+    #[test]
+    fn roundtrip_rodeo() {
+        let value = document();
+        let (interner, words) = &value;
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDocument>(&bytes) };
+        let (archived_interner, archived_words) = (&archived.0, &archived.1);
+        assert_eq!(archived_interner.len(), interner.len());
+        for (word, archived_word) in words.iter().zip(archived_words.iter()) {
+            assert_eq!(archived_word, word);
+            assert_eq!(
+                archived_interner.resolve(archived_word),
+                interner.resolve(word),
+            );
+            assert_eq!(archived_interner.resolve(word), interner.resolve(word));
+        }
+        assert!(archived_interner
+            .iter::<Spur>()
+            .all(|(key, string)| interner.resolve(&key) == string));
+
+        let (deserialized_interner, deserialized_words) =
+            deserialize::<Document, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(&deserialized_words, words);
+        for word in deserialized_words.iter() {
+            assert_eq!(
+                deserialized_interner.resolve(word),
+                interner.resolve(word),
+            );
+        }
+    }
+
+    #[test]
+    fn roundtrip_rodeo_reader() {
+        let value = document().0.into_reader();
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedRodeo>(&bytes) };
+        let the = value.get("the").unwrap();
+        assert_eq!(archived.resolve(&the), "the");
+        assert!(!archived.contains_key(&Spur::try_from_usize(100).unwrap()));
+
+        let deserialized =
+            deserialize::<RodeoReader, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized.len(), value.len());
+        assert_eq!(deserialized.get("the"), Some(the));
+    }
+
+    #[test]
+    fn duplicate_strings() {
+        let strings: Vec<String> = vec!["a".into(), "b".into(), "a".into()];
+        let bytes = to_bytes::<Error>(&strings).unwrap();
+        // An interner is archived the same way as a vector of its strings.
+        let archived = unsafe { access_unchecked::<ArchivedRodeo>(&bytes) };
+        assert!(deserialize::<Rodeo, _, Error>(archived, &mut ()).is_err());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn validate_rodeo() {
+        use crate::access;
+
+        let bytes = to_bytes::<Error>(&document()).unwrap();
+        access::<ArchivedDocument, Error>(&bytes)
+            .expect("failed to validate archived interner");
+    }
+}
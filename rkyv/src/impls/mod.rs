@@ -1,10 +1,18 @@
 #[cfg(feature = "alloc")]
 mod alloc;
+#[cfg(any(feature = "arrayvec", feature = "tinyvec"))]
+mod capacity;
 mod core;
+mod deserialize_async;
 mod niche;
 mod rend;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "alloc")]
+mod splice;
 #[cfg(feature = "std")]
 mod std;
+mod walk;
 
 // Support for various common crates. These are primarily to get users off the
 // ground and build some momentum.
@@ -16,14 +24,26 @@ mod std;
 
 #[cfg(feature = "arrayvec")]
 mod arrayvec;
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal;
 #[cfg(feature = "bitvec")]
 mod bitvec;
 #[cfg(feature = "bytes")]
 mod bytes;
+#[cfg(feature = "generational-arena")]
+mod generational_arena;
 #[cfg(feature = "hashbrown")]
 mod hashbrown;
 #[cfg(feature = "indexmap")]
 mod indexmap;
+#[cfg(feature = "jiff")]
+mod jiff;
+#[cfg(feature = "lasso")]
+mod lasso;
+#[cfg(feature = "roaring")]
+mod roaring;
+#[cfg(feature = "slotmap")]
+mod slotmap;
 #[cfg(feature = "smallvec")]
 mod smallvec;
 #[cfg(feature = "smol_str")]
@@ -1,3 +1,5 @@
+use core::mem::size_of;
+
 use rancor::Fallible;
 
 use crate::{
@@ -9,6 +11,8 @@ macro_rules! impl_rend_primitive {
         impl Archive for $type {
             const COPY_OPTIMIZATION: CopyOptimization<Self> =
                 unsafe { CopyOptimization::enable() };
+            const MAX_SERIALIZED_SIZE: Option<usize> =
+                Some(size_of::<Self::Archived>());
 
             type Archived = Self;
             type Resolver = ();
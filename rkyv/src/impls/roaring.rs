@@ -0,0 +1,151 @@
+use core::fmt;
+
+use rancor::{fail, Fallible, Source};
+use roaring::RoaringBitmap;
+
+use crate::{
+    roaring::{ArchivedRoaringBitmap, RoaringBitmapResolver},
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+/// An error resulting from an archived roaring bitmap whose values are not
+/// sorted.
+#[derive(Debug)]
+struct UnsortedValues;
+
+impl fmt::Display for UnsortedValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "archived roaring bitmap values were not sorted")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsortedValues {}
+
+impl Archive for RoaringBitmap {
+    type Archived = ArchivedRoaringBitmap;
+    type Resolver = RoaringBitmapResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedRoaringBitmap::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<S> Serialize<S> for RoaringBitmap
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRoaringBitmap::serialize_from_sorted_iter(self, serializer)
+    }
+}
+
+impl<D> Deserialize<RoaringBitmap, D> for ArchivedRoaringBitmap
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<RoaringBitmap, D::Error> {
+        let mut result = RoaringBitmap::new();
+        for value in self.iter() {
+            if !result.push(value) {
+                fail!(UnsortedValues);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl PartialEq<RoaringBitmap> for ArchivedRoaringBitmap {
+    fn eq(&self, other: &RoaringBitmap) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl PartialEq<ArchivedRoaringBitmap> for RoaringBitmap {
+    #[inline]
+    fn eq(&self, other: &ArchivedRoaringBitmap) -> bool {
+        other.eq(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rancor::Error;
+    use roaring::RoaringBitmap;
+
+    use crate::{
+        access_unchecked, deserialize, roaring::ArchivedRoaringBitmap, to_bytes,
+    };
+
+    fn bitmap() -> RoaringBitmap {
+        let mut bitmap = RoaringBitmap::new();
+        // An array container
+        bitmap.extend([1, 5, 9, 1000]);
+        // A bitmap container
+        bitmap.extend((1 << 16)..(1 << 16) + 10_000);
+        // A container with a single value
+        bitmap.insert(u32::MAX);
+        bitmap
+    }
+
+    #[test]
+    fn roundtrip_roaring_bitmap() {
+        let value = bitmap();
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<ArchivedRoaringBitmap>(&bytes) };
+        assert_eq!(archived, &value);
+        assert_eq!(archived.containers().len(), 3);
+        assert!(!archived.containers()[0].is_bitmap());
+        assert!(archived.containers()[1].is_bitmap());
+
+        for v in [0, 1, 5, 6, 1000, 1 << 16, (1 << 16) + 9_999, u32::MAX] {
+            assert_eq!(archived.contains(v), value.contains(v), "{v}");
+            assert_eq!(archived.rank(v), value.rank(v), "{v}");
+        }
+
+        let deserialized =
+            deserialize::<RoaringBitmap, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    fn archived_intersection() {
+        let a = bitmap();
+        let mut b = RoaringBitmap::new();
+        b.extend([5, 6, 1000]);
+        b.extend(((1 << 16) + 5_000)..(1 << 16) + 20_000);
+        b.extend((2 << 16)..(2 << 16) + 100);
+
+        let a_bytes = to_bytes::<Error>(&a).unwrap();
+        let b_bytes = to_bytes::<Error>(&b).unwrap();
+        let (archived_a, archived_b) = unsafe {
+            (
+                access_unchecked::<ArchivedRoaringBitmap>(&a_bytes),
+                access_unchecked::<ArchivedRoaringBitmap>(&b_bytes),
+            )
+        };
+
+        let expected = &a & &b;
+        assert_eq!(archived_a.intersection_len(archived_b), expected.len());
+        assert_eq!(archived_a.intersection(archived_b), expected);
+        assert_eq!(archived_b.intersection(archived_a), expected);
+        assert!(!archived_a.is_disjoint(archived_b));
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn validate_roaring_bitmap() {
+        use crate::access;
+
+        let bytes = to_bytes::<Error>(&bitmap()).unwrap();
+        access::<ArchivedRoaringBitmap, Error>(&bytes)
+            .expect("failed to validate archived roaring bitmap");
+    }
+}
@@ -0,0 +1,361 @@
+use core::{
+    marker::PhantomData,
+    num::{NonZeroI8, NonZeroU8},
+    ops::ControlFlow,
+};
+
+use serde::ser::{SerializeMap, SerializeSeq, SerializeTuple, Serializer};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_map::ArchivedBTreeMap,
+        btree_set::ArchivedBTreeSet,
+        swiss_table::{
+            ArchivedHashMap, ArchivedHashSet, ArchivedIndexMap,
+            ArchivedIndexSet,
+        },
+    },
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedNonZeroI128, ArchivedNonZeroI16,
+        ArchivedNonZeroI32, ArchivedNonZeroI64, ArchivedNonZeroU128,
+        ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
+        ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64,
+    },
+    rc::ArchivedRc,
+    result::ArchivedResult,
+    serde::{AsSerde, SerializeArchived},
+    string::ArchivedString,
+    tuple::*,
+    vec::ArchivedVec,
+    ArchivePointee,
+};
+
+// Primitives
+
+macro_rules! impl_serialize_archived {
+    ($($ty:ty => |$value:ident| $method:ident($expr:expr)),* $(,)?) => {
+        $(
+            impl SerializeArchived for $ty {
+                #[inline]
+                fn serialize_archived<S: Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    let $value = self;
+                    serializer.$method($expr)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize_archived! {
+    bool => |value| serialize_bool(*value),
+    i8 => |value| serialize_i8(*value),
+    u8 => |value| serialize_u8(*value),
+    NonZeroI8 => |value| serialize_i8(value.get()),
+    NonZeroU8 => |value| serialize_u8(value.get()),
+    ArchivedI16 => |value| serialize_i16(value.to_native()),
+    ArchivedI32 => |value| serialize_i32(value.to_native()),
+    ArchivedI64 => |value| serialize_i64(value.to_native()),
+    ArchivedI128 => |value| serialize_i128(value.to_native()),
+    ArchivedU16 => |value| serialize_u16(value.to_native()),
+    ArchivedU32 => |value| serialize_u32(value.to_native()),
+    ArchivedU64 => |value| serialize_u64(value.to_native()),
+    ArchivedU128 => |value| serialize_u128(value.to_native()),
+    ArchivedF32 => |value| serialize_f32(value.to_native()),
+    ArchivedF64 => |value| serialize_f64(value.to_native()),
+    ArchivedChar => |value| serialize_char(value.to_native()),
+    ArchivedNonZeroI16 => |value| serialize_i16(value.get()),
+    ArchivedNonZeroI32 => |value| serialize_i32(value.get()),
+    ArchivedNonZeroI64 => |value| serialize_i64(value.get()),
+    ArchivedNonZeroI128 => |value| serialize_i128(value.get()),
+    ArchivedNonZeroU16 => |value| serialize_u16(value.get()),
+    ArchivedNonZeroU32 => |value| serialize_u32(value.get()),
+    ArchivedNonZeroU64 => |value| serialize_u64(value.get()),
+    ArchivedNonZeroU128 => |value| serialize_u128(value.get()),
+    str => |value| serialize_str(value),
+    ArchivedString => |value| serialize_str(value.as_str()),
+}
+
+impl SerializeArchived for () {
+    #[inline]
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl<T: ?Sized> SerializeArchived for PhantomData<T> {
+    #[inline]
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit_struct("PhantomData")
+    }
+}
+
+// Sequences
+
+impl<T: SerializeArchived> SerializeArchived for [T] {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for element in self.iter() {
+            seq.serialize_element(&AsSerde(element))?;
+        }
+        seq.end()
+    }
+}
+
+impl<T: SerializeArchived, const N: usize> SerializeArchived for [T; N] {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for element in self.iter() {
+            tuple.serialize_element(&AsSerde(element))?;
+        }
+        tuple.end()
+    }
+}
+
+impl<T: SerializeArchived> SerializeArchived for ArchivedVec<T> {
+    #[inline]
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize_archived(serializer)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($name:ident, $n:tt, $($type:ident $index:tt),*) => {
+        impl<$($type: SerializeArchived),*> SerializeArchived
+            for $name<$($type),*>
+        {
+            fn serialize_archived<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                let mut tuple = serializer.serialize_tuple($n)?;
+                $(tuple.serialize_element(&AsSerde(&self.$index))?;)*
+                tuple.end()
+            }
+        }
+    };
+}
+
+impl_tuple!(ArchivedTuple1, 1, T0 0);
+impl_tuple!(ArchivedTuple2, 2, T0 0, T1 1);
+impl_tuple!(ArchivedTuple3, 3, T0 0, T1 1, T2 2);
+impl_tuple!(ArchivedTuple4, 4, T0 0, T1 1, T2 2, T3 3);
+impl_tuple!(ArchivedTuple5, 5, T0 0, T1 1, T2 2, T3 3, T4 4);
+impl_tuple!(ArchivedTuple6, 6, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+impl_tuple!(ArchivedTuple7, 7, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+impl_tuple!(ArchivedTuple8, 8, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+impl_tuple!(
+    ArchivedTuple9, 9, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8
+);
+impl_tuple!(
+    ArchivedTuple10, 10, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9
+);
+impl_tuple!(
+    ArchivedTuple11, 11, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10
+);
+impl_tuple!(
+    ArchivedTuple12, 12, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10, T11 11
+);
+impl_tuple!(
+    ArchivedTuple13, 13, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10, T11 11, T12 12
+);
+
+// Pointers
+
+impl<T> SerializeArchived for ArchivedBox<T>
+where
+    T: ArchivePointee + SerializeArchived + ?Sized,
+{
+    #[inline]
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.get().serialize_archived(serializer)
+    }
+}
+
+impl<T, F> SerializeArchived for ArchivedRc<T, F>
+where
+    T: ArchivePointee + SerializeArchived + ?Sized,
+{
+    #[inline]
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.get().serialize_archived(serializer)
+    }
+}
+
+// Option and Result
+
+impl<T: SerializeArchived> SerializeArchived for ArchivedOption<T> {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            ArchivedOption::None => serializer.serialize_none(),
+            ArchivedOption::Some(value) => {
+                serializer.serialize_some(&AsSerde(value))
+            }
+        }
+    }
+}
+
+impl<T, E> SerializeArchived for ArchivedResult<T, E>
+where
+    T: SerializeArchived,
+    E: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            ArchivedResult::Ok(value) => serializer.serialize_newtype_variant(
+                "Result",
+                0,
+                "Ok",
+                &AsSerde(value),
+            ),
+            ArchivedResult::Err(error) => serializer.serialize_newtype_variant(
+                "Result",
+                1,
+                "Err",
+                &AsSerde(error),
+            ),
+        }
+    }
+}
+
+// Collections
+
+impl<K, V, H> SerializeArchived for ArchivedHashMap<K, V, H>
+where
+    K: SerializeArchived,
+    V: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(&AsSerde(key), &AsSerde(value))?;
+        }
+        map.end()
+    }
+}
+
+impl<K: SerializeArchived, H> SerializeArchived for ArchivedHashSet<K, H> {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for key in self.iter() {
+            seq.serialize_element(&AsSerde(key))?;
+        }
+        seq.end()
+    }
+}
+
+impl<K, V, H> SerializeArchived for ArchivedIndexMap<K, V, H>
+where
+    K: SerializeArchived,
+    V: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(&AsSerde(key), &AsSerde(value))?;
+        }
+        map.end()
+    }
+}
+
+impl<K: SerializeArchived, H> SerializeArchived for ArchivedIndexSet<K, H> {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for key in self.iter() {
+            seq.serialize_element(&AsSerde(key))?;
+        }
+        seq.end()
+    }
+}
+
+impl<K, V, const E: usize> SerializeArchived for ArchivedBTreeMap<K, V, E>
+where
+    K: SerializeArchived,
+    V: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        let error = self.visit(|key, value| {
+            match map.serialize_entry(&AsSerde(key), &AsSerde(value)) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => ControlFlow::Break(e),
+            }
+        });
+        match error {
+            Some(e) => Err(e),
+            None => map.end(),
+        }
+    }
+}
+
+impl<K, const E: usize> SerializeArchived for ArchivedBTreeSet<K, E>
+where
+    K: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        let error =
+            self.visit(|key| match seq.serialize_element(&AsSerde(key)) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => ControlFlow::Break(e),
+            });
+        match error {
+            Some(e) => Err(e),
+            None => seq.end(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use rancor::Fallible;
+use slotmap::{Key, SecondaryMap, SlotMap};
+
+use crate::{
+    ser::{Allocator, Writer},
+    slotmap::{ArchivedSlotMap, SlotMapResolver},
+    Archive, Deserialize, Place, Serialize,
+};
+
+// SlotMap
+
+impl<K: Key, V: Archive> Archive for SlotMap<K, V> {
+    type Archived = ArchivedSlotMap<K, V::Archived>;
+    type Resolver = SlotMapResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedSlotMap::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<K, V, S> Serialize<S> for SlotMap<K, V>
+where
+    K: Key,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSlotMap::serialize_from_iter(self, serializer)
+    }
+}
+
+impl<K, V, D> Deserialize<SlotMap<K, V>, D> for ArchivedSlotMap<K, V::Archived>
+where
+    K: Key,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<SlotMap<K, V>, D::Error> {
+        let slots = self.slots();
+        let mut result = SlotMap::with_capacity_and_key(slots.len());
+
+        // `SlotMap` can't insert a value with a specific key, so the keys are
+        // recreated by replaying insertions and removals. Every slot is
+        // inserted first, with vacant slots holding a placeholder value.
+        let placeholder = match slots.iter().find_map(|slot| slot.value()) {
+            Some(placeholder) => placeholder,
+            None => return Ok(result),
+        };
+        let mut keys = Vec::with_capacity(slots.len());
+        for slot in slots {
+            let value = slot.value().unwrap_or(placeholder);
+            keys.push(result.insert(value.deserialize(deserializer)?));
+        }
+
+        // Removing and reinserting a value reuses its slot and bumps the
+        // version of its key.
+        let occupied = keys
+            .iter_mut()
+            .zip(slots)
+            .filter(|(_, slot)| slot.value().is_some());
+        for ((key, _), (target, _)) in occupied.zip(self.iter()) {
+            while *key != target {
+                let value = result.remove(*key).unwrap();
+                *key = result.insert(value);
+            }
+        }
+
+        for (slot, key) in slots.iter().zip(keys) {
+            if slot.value().is_none() {
+                result.remove(key);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+// SecondaryMap
+
+impl<K: Key, V: Archive> Archive for SecondaryMap<K, V> {
+    type Archived = ArchivedSlotMap<K, V::Archived>;
+    type Resolver = SlotMapResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedSlotMap::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<K, V, S> Serialize<S> for SecondaryMap<K, V>
+where
+    K: Key,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSlotMap::serialize_from_iter(self, serializer)
+    }
+}
+
+impl<K, V, D> Deserialize<SecondaryMap<K, V>, D>
+    for ArchivedSlotMap<K, V::Archived>
+where
+    K: Key,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<SecondaryMap<K, V>, D::Error> {
+        let mut result = SecondaryMap::with_capacity(self.slots().len());
+        for (key, value) in self.iter() {
+            result.insert(key, value.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::string::{String, ToString};
+
+    use rancor::{Error, Infallible};
+    use slotmap::{DefaultKey, SecondaryMap, SlotMap};
+
+    use crate::{
+        access_unchecked, deserialize, slotmap::ArchivedSlotMap,
+        string::ArchivedString, to_bytes, Archived,
+    };
+
+    type ArchivedMap = ArchivedSlotMap<DefaultKey, ArchivedString>;
+
+    fn slot_map() -> (SlotMap<DefaultKey, String>, [DefaultKey; 3]) {
+        let mut map = SlotMap::new();
+        let a = map.insert("a".to_string());
+        let b = map.insert("b".to_string());
+        let c = map.insert("c".to_string());
+        map.remove(a);
+        map.remove(b);
+        let d = map.insert("d".to_string());
+        let e = map.insert("e".to_string());
+        map.remove(e);
+        (map, [a, c, d])
+    }
+
+    #[test]
+    fn roundtrip_slot_map() {
+        let (value, [removed, c, d]) = slot_map();
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedMap>(&bytes) };
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived.get(c).unwrap(), "c");
+        assert_eq!(archived.get(d).unwrap(), "d");
+        assert!(!archived.contains_key(removed));
+        assert!(archived
+            .iter()
+            .map(|(key, value)| (key, value.as_str()))
+            .eq(value.iter().map(|(key, value)| (key, value.as_str()))));
+
+        let mut deserialized = deserialize::<
+            SlotMap<DefaultKey, String>,
+            _,
+            Infallible,
+        >(archived, &mut ())
+        .unwrap();
+        assert!(deserialized.iter().eq(value.iter()));
+        assert!(!deserialized.contains_key(removed));
+
+        // New keys must not collide with the original keys.
+        let new = deserialized.insert("f".to_string());
+        assert!(new != removed && new != c && new != d);
+    }
+
+    #[test]
+    fn roundtrip_secondary_map() {
+        let (slots, [removed, c, d]) = slot_map();
+        let mut value = SecondaryMap::new();
+        value.insert(c, 1u32);
+        value.insert(d, 2u32);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<ArchivedSlotMap<DefaultKey, Archived<u32>>>(
+                &bytes,
+            )
+        };
+        assert_eq!(archived.get(c).unwrap().to_native(), 1);
+        assert_eq!(archived.get(d).unwrap().to_native(), 2);
+        assert!(archived.get(removed).is_none());
+
+        let deserialized = deserialize::<
+            SecondaryMap<DefaultKey, u32>,
+            _,
+            Infallible,
+        >(archived, &mut ())
+        .unwrap();
+        assert!(deserialized.iter().eq(value.iter()));
+
+        // Archived slot maps can also be deserialized as secondary maps.
+        let bytes = to_bytes::<Error>(&slots).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedMap>(&bytes) };
+        let deserialized = deserialize::<
+            SecondaryMap<DefaultKey, String>,
+            _,
+            Infallible,
+        >(archived, &mut ())
+        .unwrap();
+        assert_eq!(deserialized[c], "c");
+        assert_eq!(deserialized[d], "d");
+    }
+
+    #[test]
+    fn empty_slot_map() {
+        let value = SlotMap::<DefaultKey, String>::new();
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedMap>(&bytes) };
+        assert!(archived.is_empty());
+
+        let deserialized = deserialize::<
+            SlotMap<DefaultKey, String>,
+            _,
+            Infallible,
+        >(archived, &mut ())
+        .unwrap();
+        assert!(deserialized.is_empty());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn validate_slot_map() {
+        use crate::access;
+
+        let (value, _) = slot_map();
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        access::<ArchivedMap, Error>(&bytes)
+            .expect("failed to validate archived slot map");
+    }
+}
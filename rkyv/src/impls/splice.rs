@@ -0,0 +1,220 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::{mem::size_of, slice};
+
+use rancor::Fallible;
+
+use crate::{
+    boxed::{ArchivedBox, BoxResolver},
+    option::ArchivedOption,
+    primitive::ArchivedIsize,
+    ser::{Writer, WriterExt as _},
+    splice::{Edits, Splice, Spliced},
+    string::{ArchivedString, StringResolver},
+    vec::{ArchivedVec, VecResolver},
+    Place,
+};
+
+// Types which don't contain pointers are copied verbatim.
+
+macro_rules! impl_splice_copy {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<S: Fallible + ?Sized> Splice<S> for $ty {
+                type SpliceResolver = ();
+
+                fn splice(
+                    _: &Self::Archived,
+                    _: &mut S,
+                    _: &mut Edits<'_, S>,
+                ) -> Result<Self::SpliceResolver, S::Error> {
+                    Ok(())
+                }
+
+                fn resolve_spliced(
+                    archived: &Self::Archived,
+                    _: Self::SpliceResolver,
+                    out: Place<Self::Archived>,
+                    _: &mut Edits<'_, S>,
+                ) {
+                    // SAFETY: `out` is properly aligned and valid for writes,
+                    // and the archived value contains no pointers.
+                    unsafe {
+                        out.ptr().copy_from_nonoverlapping(archived, 1);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_splice_copy! {
+    (),
+    bool,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+    f32, f64,
+    char,
+}
+
+// String
+
+impl<S: Fallible + Writer + ?Sized> Splice<S> for String {
+    type SpliceResolver = StringResolver;
+
+    fn splice(
+        archived: &Self::Archived,
+        serializer: &mut S,
+        _: &mut Edits<'_, S>,
+    ) -> Result<Self::SpliceResolver, S::Error> {
+        ArchivedString::serialize_from_str(archived.as_str(), serializer)
+    }
+
+    fn resolve_spliced(
+        archived: &Self::Archived,
+        resolver: Self::SpliceResolver,
+        out: Place<Self::Archived>,
+        _: &mut Edits<'_, S>,
+    ) {
+        ArchivedString::resolve_from_str(archived.as_str(), resolver, out);
+    }
+}
+
+// Box
+
+impl<T, S> Splice<S> for Box<T>
+where
+    T: Splice<S>,
+    S: Fallible + Writer + ?Sized,
+{
+    type SpliceResolver = BoxResolver;
+
+    fn splice(
+        archived: &Self::Archived,
+        serializer: &mut S,
+        edits: &mut Edits<'_, S>,
+    ) -> Result<Self::SpliceResolver, S::Error> {
+        let value = archived.get();
+        let resolver = edits.splice::<T>(value, serializer)?;
+        serializer.align_for::<T::Archived>()?;
+        let pos = edits.resolve_aligned::<T>(value, resolver, serializer)?;
+        serializer.check_offset::<ArchivedIsize>(pos)?;
+        Ok(BoxResolver::from_pos(pos))
+    }
+
+    fn resolve_spliced(
+        _: &Self::Archived,
+        resolver: Self::SpliceResolver,
+        out: Place<Self::Archived>,
+        _: &mut Edits<'_, S>,
+    ) {
+        ArchivedBox::resolve_from_raw_parts(resolver, (), out);
+    }
+}
+
+// Vec
+
+impl<T, S> Splice<S> for Vec<T>
+where
+    T: Splice<S>,
+    S: Fallible + Writer + ?Sized,
+{
+    type SpliceResolver = VecResolver;
+
+    fn splice(
+        archived: &Self::Archived,
+        serializer: &mut S,
+        edits: &mut Edits<'_, S>,
+    ) -> Result<Self::SpliceResolver, S::Error> {
+        // SAFETY: The elements of the archived vector are initialized and
+        // contiguous.
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                archived.as_ptr().cast::<u8>(),
+                archived.len() * size_of::<T::Archived>(),
+            )
+        };
+
+        if T::COPY_OPTIMIZATION.is_enabled() && !edits.has_edits_within(bytes) {
+            // The archived elements contain no pointers, so they can be
+            // copied verbatim.
+            let pos = serializer.align_for::<T::Archived>()?;
+            serializer.write(bytes)?;
+            serializer.check_offset::<ArchivedIsize>(pos)?;
+            return Ok(VecResolver::from_pos(pos));
+        }
+
+        let resolvers = archived
+            .iter()
+            .map(|value| edits.splice::<T>(value, serializer))
+            .collect::<Result<Vec<Spliced<T::SpliceResolver>>, _>>()?;
+
+        let pos = serializer.align_for::<T::Archived>()?;
+        for (value, resolver) in archived.iter().zip(resolvers) {
+            edits.resolve_aligned::<T>(value, resolver, serializer)?;
+        }
+        serializer.check_offset::<ArchivedIsize>(pos)?;
+
+        Ok(VecResolver::from_pos(pos))
+    }
+
+    fn resolve_spliced(
+        archived: &Self::Archived,
+        resolver: Self::SpliceResolver,
+        out: Place<Self::Archived>,
+        _: &mut Edits<'_, S>,
+    ) {
+        ArchivedVec::resolve_from_len(archived.len(), resolver, out);
+    }
+}
+
+// Option
+
+impl<T, S> Splice<S> for Option<T>
+where
+    T: Splice<S>,
+    S: Fallible + ?Sized,
+{
+    type SpliceResolver = Option<Spliced<T::SpliceResolver>>;
+
+    fn splice(
+        archived: &Self::Archived,
+        serializer: &mut S,
+        edits: &mut Edits<'_, S>,
+    ) -> Result<Self::SpliceResolver, S::Error> {
+        match archived {
+            ArchivedOption::Some(value) => {
+                Ok(Some(edits.splice::<T>(value, serializer)?))
+            }
+            ArchivedOption::None => Ok(None),
+        }
+    }
+
+    fn resolve_spliced(
+        archived: &Self::Archived,
+        resolver: Self::SpliceResolver,
+        out: Place<Self::Archived>,
+        edits: &mut Edits<'_, S>,
+    ) {
+        // SAFETY: `out` is properly aligned and valid for writes. Copying the
+        // archived option writes its tag, and the value is resolved over the
+        // copy.
+        unsafe {
+            out.ptr().copy_from_nonoverlapping(archived, 1);
+        }
+
+        if let (ArchivedOption::Some(value), Some(resolver)) =
+            (archived, resolver)
+        {
+            let offset = value as *const T::Archived as usize
+                - archived as *const Self::Archived as usize;
+            // SAFETY: The value is at the same offset in the copy of the
+            // archived option as in the original.
+            let out_value = unsafe {
+                let ptr = out.ptr().cast::<u8>().add(offset);
+                Place::from_field_unchecked(out, ptr.cast::<T::Archived>())
+            };
+            edits.resolve::<T>(value, resolver, out_value);
+        }
+    }
+}
@@ -1,19 +1,22 @@
 use core::{
     alloc::{Layout, LayoutError},
-    ptr,
+    fmt, ptr,
 };
 use std::{
     alloc,
-    ffi::{CStr, CString},
+    borrow::Cow,
+    ffi::{CStr, CString, OsStr, OsString},
 };
 
 use ptr_meta::Pointee;
-use rancor::{Fallible, ResultExt, Source};
+use rancor::{Fallible, OptionExt, ResultExt, Source};
 
 use crate::{
-    ffi::{ArchivedCString, CStringResolver},
-    primitive::ArchivedUsize,
-    ser::Writer,
+    ffi::{
+        ArchivedCString, ArchivedOsString, CStringResolver, OsStringResolver,
+    },
+    primitive::{fixed_usize, ArchivedIsize, ArchivedUsize},
+    ser::{Allocator, Writer, WriterExt as _},
     Archive, ArchivePointee, ArchiveUnsized, ArchivedMetadata, Deserialize,
     DeserializeUnsized, LayoutRaw, Place, Portable, Serialize,
     SerializeUnsized,
@@ -37,7 +40,7 @@ impl ArchiveUnsized for CStr {
 
     #[inline]
     fn archived_metadata(&self) -> ArchivedMetadata<Self> {
-        ArchivedUsize::from_native(ptr_meta::metadata(self) as _)
+        ArchivedUsize::from_native(fixed_usize(ptr_meta::metadata(self)))
     }
 }
 
@@ -54,8 +57,11 @@ impl ArchivePointee for CStr {
 
 impl<S: Fallible + Writer + ?Sized> SerializeUnsized<S> for CStr {
     fn serialize_unsized(&self, serializer: &mut S) -> Result<usize, S::Error> {
+        serializer.check_len(ptr_meta::metadata(self))?;
+
         let result = serializer.pos();
         serializer.write(self.to_bytes_with_nul())?;
+        serializer.check_offset::<ArchivedIsize>(result)?;
         Ok(result)
     }
 }
@@ -149,3 +155,252 @@ where
         Ok(CString::from(boxed))
     }
 }
+
+// OsString
+
+#[derive(Debug)]
+pub(super) struct InvalidOsString;
+
+impl fmt::Display for InvalidOsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OS string is not valid on this platform")
+    }
+}
+
+impl std::error::Error for InvalidOsString {}
+
+#[cfg(unix)]
+pub(super) fn encode_os_str(os_str: &OsStr) -> Option<Cow<'_, [u8]>> {
+    use std::os::unix::ffi::OsStrExt as _;
+
+    Some(Cow::Borrowed(os_str.as_bytes()))
+}
+
+#[cfg(unix)]
+pub(super) fn encoded_len(os_str: &OsStr) -> usize {
+    use std::os::unix::ffi::OsStrExt as _;
+
+    os_str.as_bytes().len()
+}
+
+#[cfg(unix)]
+pub(super) fn decode_os_string(bytes: &[u8]) -> Option<OsString> {
+    use std::os::unix::ffi::OsStringExt as _;
+
+    Some(OsString::from_vec(bytes.to_vec()))
+}
+
+#[cfg(windows)]
+pub(super) fn encode_os_str(os_str: &OsStr) -> Option<Cow<'_, [u8]>> {
+    use std::os::windows::ffi::OsStrExt as _;
+
+    Some(Cow::Owned(wtf8::encode(os_str.encode_wide())))
+}
+
+#[cfg(windows)]
+pub(super) fn encoded_len(os_str: &OsStr) -> usize {
+    use std::os::windows::ffi::OsStrExt as _;
+
+    wtf8::encoded_len(os_str.encode_wide())
+}
+
+#[cfg(windows)]
+pub(super) fn decode_os_string(bytes: &[u8]) -> Option<OsString> {
+    use std::os::windows::ffi::OsStringExt as _;
+
+    wtf8::decode(bytes).map(|wide| OsString::from_wide(&wide))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(super) fn encode_os_str(os_str: &OsStr) -> Option<Cow<'_, [u8]>> {
+    os_str.to_str().map(|s| Cow::Borrowed(s.as_bytes()))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(super) fn encoded_len(os_str: &OsStr) -> usize {
+    // It's safe to unwrap here because if the OsStr wasn't valid UTF-8 it
+    // would have failed to serialize
+    os_str.to_str().unwrap().len()
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(super) fn decode_os_string(bytes: &[u8]) -> Option<OsString> {
+    core::str::from_utf8(bytes).ok().map(OsString::from)
+}
+
+/// Conversions between potentially ill-formed UTF-16 and WTF-8.
+#[cfg(any(windows, test))]
+mod wtf8 {
+    fn code_points(
+        wide: impl Iterator<Item = u16>,
+    ) -> impl Iterator<Item = u32> {
+        char::decode_utf16(wide).map(|c| match c {
+            Ok(c) => c as u32,
+            Err(e) => e.unpaired_surrogate() as u32,
+        })
+    }
+
+    fn len_utf8(code_point: u32) -> usize {
+        match code_point {
+            0..=0x7f => 1,
+            0x80..=0x7ff => 2,
+            0x800..=0xffff => 3,
+            _ => 4,
+        }
+    }
+
+    pub fn encoded_len(wide: impl Iterator<Item = u16>) -> usize {
+        code_points(wide).map(len_utf8).sum()
+    }
+
+    pub fn encode(wide: impl Iterator<Item = u16>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for c in code_points(wide) {
+            match len_utf8(c) {
+                1 => bytes.push(c as u8),
+                2 => bytes.extend_from_slice(&[
+                    0xc0 | (c >> 6) as u8,
+                    0x80 | (c & 0x3f) as u8,
+                ]),
+                3 => bytes.extend_from_slice(&[
+                    0xe0 | (c >> 12) as u8,
+                    0x80 | ((c >> 6) & 0x3f) as u8,
+                    0x80 | (c & 0x3f) as u8,
+                ]),
+                _ => bytes.extend_from_slice(&[
+                    0xf0 | (c >> 18) as u8,
+                    0x80 | ((c >> 12) & 0x3f) as u8,
+                    0x80 | ((c >> 6) & 0x3f) as u8,
+                    0x80 | (c & 0x3f) as u8,
+                ]),
+            }
+        }
+        bytes
+    }
+
+    pub fn decode(mut bytes: &[u8]) -> Option<Vec<u16>> {
+        let mut wide = Vec::with_capacity(bytes.len());
+        while let Some(&first) = bytes.first() {
+            let (len, min) = match first {
+                0x00..=0x7f => (1, 0),
+                0xc0..=0xdf => (2, 0x80),
+                0xe0..=0xef => (3, 0x800),
+                0xf0..=0xf7 => (4, 0x10000),
+                _ => return None,
+            };
+            if bytes.len() < len {
+                return None;
+            }
+
+            let mut c = if len == 1 {
+                first as u32
+            } else {
+                (first & (0x7f >> len)) as u32
+            };
+            for &byte in &bytes[1..len] {
+                if byte & 0xc0 != 0x80 {
+                    return None;
+                }
+                c = (c << 6) | (byte & 0x3f) as u32;
+            }
+            if c < min || c > 0x10ffff {
+                return None;
+            }
+
+            if c < 0x10000 {
+                wide.push(c as u16);
+            } else {
+                let c = c - 0x10000;
+                wide.push(0xd800 | (c >> 10) as u16);
+                wide.push(0xdc00 | (c & 0x3ff) as u16);
+            }
+            bytes = &bytes[len..];
+        }
+        Some(wide)
+    }
+}
+
+impl Archive for OsString {
+    type Archived = ArchivedOsString;
+    type Resolver = OsStringResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedOsString::resolve_from_encoded_len(
+            encoded_len(self),
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<S> Serialize<S> for OsString
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let bytes = encode_os_str(self).into_trace(InvalidOsString)?;
+        ArchivedOsString::serialize_from_encoded_bytes(&bytes, serializer)
+    }
+}
+
+impl<D> Deserialize<OsString, D> for ArchivedOsString
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<OsString, D::Error> {
+        decode_os_string(self.as_encoded_bytes()).into_trace(InvalidOsString)
+    }
+}
+
+impl PartialEq<OsString> for ArchivedOsString {
+    #[inline]
+    fn eq(&self, other: &OsString) -> bool {
+        encode_os_str(other)
+            .is_some_and(|bytes| *bytes == *self.as_encoded_bytes())
+    }
+}
+
+impl PartialEq<ArchivedOsString> for OsString {
+    #[inline]
+    fn eq(&self, other: &ArchivedOsString) -> bool {
+        other.eq(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wtf8;
+
+    #[test]
+    fn wtf8_roundtrip() {
+        let wide = [
+            0x0061, // 'a'
+            0x00e9, // 'é'
+            0x20ac, // '€'
+            0xd83d, 0xde00, // '😀'
+            0xd800, // unpaired lead surrogate
+            0x0062, // 'b'
+            0xdc00, // unpaired trail surrogate
+        ];
+
+        let bytes = wtf8::encode(wide.iter().copied());
+        assert_eq!(bytes.len(), wtf8::encoded_len(wide.iter().copied()));
+        assert_eq!(&bytes[..10], "aé€😀".as_bytes());
+        assert_eq!(wtf8::decode(&bytes).unwrap(), wide);
+    }
+
+    #[test]
+    fn wtf8_rejects_invalid_bytes() {
+        assert!(wtf8::decode(&[0xff]).is_none());
+        assert!(wtf8::decode(&[0x80]).is_none());
+        assert!(wtf8::decode(&[0xc0, 0x80]).is_none());
+        assert!(wtf8::decode(&[0xe2, 0x82]).is_none());
+        assert!(wtf8::decode(&[0xf4, 0x90, 0x80, 0x80]).is_none());
+    }
+}
@@ -1,4 +1,4 @@
 mod collections;
 mod ffi;
 mod net;
-mod time;
+mod path;
@@ -1,4 +1,4 @@
-use core::cmp;
+use core::{cmp, fmt};
 use std::{
     io,
     net::{
@@ -132,7 +132,14 @@ impl PartialOrd<Ipv4Addr> for ArchivedIpv4Addr {
 impl PartialOrd<ArchivedIpv4Addr> for Ipv4Addr {
     #[inline]
     fn partial_cmp(&self, other: &ArchivedIpv4Addr) -> Option<cmp::Ordering> {
-        other.partial_cmp(self)
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+impl fmt::Display for ArchivedIpv4Addr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_ipv4(), f)
     }
 }
 
@@ -245,7 +252,14 @@ impl PartialOrd<Ipv6Addr> for ArchivedIpv6Addr {
 impl PartialOrd<ArchivedIpv6Addr> for Ipv6Addr {
     #[inline]
     fn partial_cmp(&self, other: &ArchivedIpv6Addr) -> Option<cmp::Ordering> {
-        other.partial_cmp(self)
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+impl fmt::Display for ArchivedIpv6Addr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_ipv6(), f)
     }
 }
 
@@ -360,7 +374,14 @@ impl PartialOrd<IpAddr> for ArchivedIpAddr {
 impl PartialOrd<ArchivedIpAddr> for IpAddr {
     #[inline]
     fn partial_cmp(&self, other: &ArchivedIpAddr) -> Option<cmp::Ordering> {
-        other.partial_cmp(self)
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+impl fmt::Display for ArchivedIpAddr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_ipaddr(), f)
     }
 }
 
@@ -475,7 +496,14 @@ impl PartialOrd<ArchivedSocketAddrV4> for SocketAddrV4 {
         &self,
         other: &ArchivedSocketAddrV4,
     ) -> Option<cmp::Ordering> {
-        other.partial_cmp(self)
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+impl fmt::Display for ArchivedSocketAddrV4 {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_socket_addr_v4(), f)
     }
 }
 
@@ -560,7 +588,14 @@ impl PartialOrd<ArchivedSocketAddrV6> for SocketAddrV6 {
         &self,
         other: &ArchivedSocketAddrV6,
     ) -> Option<cmp::Ordering> {
-        other.partial_cmp(self)
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+impl fmt::Display for ArchivedSocketAddrV6 {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_socket_addr_v6(), f)
     }
 }
 
@@ -659,7 +694,14 @@ impl PartialOrd<SocketAddr> for ArchivedSocketAddr {
 impl PartialOrd<ArchivedSocketAddr> for SocketAddr {
     #[inline]
     fn partial_cmp(&self, other: &ArchivedSocketAddr) -> Option<cmp::Ordering> {
-        other.partial_cmp(self)
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
+
+impl fmt::Display for ArchivedSocketAddr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_socket_addr(), f)
     }
 }
 
@@ -0,0 +1,115 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use rancor::{Fallible, OptionExt, Source};
+
+use super::ffi::{
+    decode_os_string, encode_os_str, encoded_len, InvalidOsString,
+};
+use crate::{
+    path::{ArchivedPath, ArchivedPathBuf, PathBufResolver},
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+fn replace_separators(bytes: Cow<'_, [u8]>, from: u8, to: u8) -> Cow<'_, [u8]> {
+    if cfg!(windows) && bytes.contains(&from) {
+        bytes
+            .iter()
+            .map(|&b| if b == from { to } else { b })
+            .collect::<Vec<_>>()
+            .into()
+    } else {
+        bytes
+    }
+}
+
+fn encode_path(path: &Path) -> Option<Cow<'_, [u8]>> {
+    encode_os_str(path.as_os_str())
+        .map(|bytes| replace_separators(bytes, b'\\', b'/'))
+}
+
+impl ArchivedPath {
+    /// Converts this archived path to a `PathBuf`.
+    ///
+    /// Returns `None` if the path can't be represented on this platform. This
+    /// only happens for paths which aren't valid UTF-8, and were serialized on
+    /// a different family of platforms.
+    pub fn to_path_buf(&self) -> Option<PathBuf> {
+        let bytes = replace_separators(
+            Cow::Borrowed(self.as_encoded_bytes()),
+            b'/',
+            b'\\',
+        );
+        decode_os_string(&bytes).map(PathBuf::from)
+    }
+}
+
+impl Archive for PathBuf {
+    type Archived = ArchivedPathBuf;
+    type Resolver = PathBufResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedPathBuf::resolve_from_encoded_len(
+            encoded_len(self.as_os_str()),
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<S> Serialize<S> for PathBuf
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let bytes = encode_path(self).into_trace(InvalidOsString)?;
+        ArchivedPathBuf::serialize_from_encoded_bytes(&bytes, serializer)
+    }
+}
+
+impl<D> Deserialize<PathBuf, D> for ArchivedPathBuf
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<PathBuf, D::Error> {
+        self.to_path_buf().into_trace(InvalidOsString)
+    }
+}
+
+impl PartialEq<Path> for ArchivedPath {
+    #[inline]
+    fn eq(&self, other: &Path) -> bool {
+        encode_path(other)
+            .is_some_and(|bytes| *bytes == *self.as_encoded_bytes())
+    }
+}
+
+impl PartialEq<ArchivedPath> for Path {
+    #[inline]
+    fn eq(&self, other: &ArchivedPath) -> bool {
+        other.eq(self)
+    }
+}
+
+impl PartialEq<PathBuf> for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &PathBuf) -> bool {
+        self.as_path().eq(other.as_path())
+    }
+}
+
+impl PartialEq<ArchivedPathBuf> for PathBuf {
+    #[inline]
+    fn eq(&self, other: &ArchivedPathBuf) -> bool {
+        other.eq(self)
+    }
+}
@@ -1,9 +1,10 @@
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 #[cfg(all(feature = "tinyvec", feature = "alloc"))]
 use tinyvec::TinyVec;
 use tinyvec::{Array, ArrayVec, SliceVec};
 
 use crate::{
+    impls::capacity::check_capacity,
     ser::{Allocator, Writer},
     vec::{ArchivedVec, VecResolver},
     Archive, Archived, Deserialize, Place, Serialize,
@@ -43,11 +44,13 @@ where
     A::Item: Archive,
     Archived<A::Item>: Deserialize<A::Item, D>,
     D: Fallible + ?Sized,
+    D::Error: Source,
 {
     fn deserialize(
         &self,
         deserializer: &mut D,
     ) -> Result<ArrayVec<A>, D::Error> {
+        check_capacity(self.len(), A::CAPACITY)?;
         let mut result = ArrayVec::new();
         for item in self.as_slice() {
             result.push(item.deserialize(deserializer)?);
@@ -134,7 +137,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use rancor::{Error, Infallible};
+    use rancor::Error;
     use tinyvec::{array_vec, Array, ArrayVec, SliceVec};
 
     use crate::{
@@ -150,14 +153,23 @@ mod tests {
             unsafe { access_unchecked::<ArchivedVec<Archived<i32>>>(&bytes) };
         assert_eq!(archived.as_slice(), &[10, 20, 40, 80]);
 
-        let deserialized = deserialize::<ArrayVec<[i32; 10]>, _, Infallible>(
-            archived,
-            &mut (),
-        )
-        .unwrap();
+        let deserialized =
+            deserialize::<ArrayVec<[i32; 10]>, _, Error>(archived, &mut ())
+                .unwrap();
         assert_eq!(value, deserialized);
     }
 
+    #[test]
+    fn array_vec_over_capacity() {
+        let value = array_vec!([i32; 10] => 10, 20, 40, 80);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<ArchivedVec<Archived<i32>>>(&bytes) };
+        deserialize::<ArrayVec<[i32; 2]>, _, Error>(archived, &mut ())
+            .expect_err("deserialized more elements than the capacity");
+    }
+
     #[test]
     fn slice_vec() {
         let mut backing = [0i32; 10];
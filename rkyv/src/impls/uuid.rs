@@ -37,7 +37,7 @@ impl<D: Fallible + ?Sized> Deserialize<Uuid, D> for Uuid {
 
 #[cfg(test)]
 mod rkyv_tests {
-    use rancor::Infallible;
+    use rancor::{Error, Infallible};
     use uuid::Uuid;
 
     use crate::{access_unchecked, deserialize, util::AlignedVec};
@@ -47,7 +47,7 @@ mod rkyv_tests {
         let uuid_str = "f9168c5e-ceb2-4faa-b6bf-329bf39fa1e4";
         let u = Uuid::parse_str(uuid_str).unwrap();
 
-        let buf = crate::util::serialize_into::<_, Infallible>(
+        let buf = crate::util::serialize_into::<_, Error>(
             &u,
             AlignedVec::<16>::new(),
         )
@@ -0,0 +1,268 @@
+use core::{
+    marker::PhantomData,
+    num::{NonZeroI8, NonZeroU8},
+    ops::ControlFlow,
+};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_map::ArchivedBTreeMap,
+        btree_set::ArchivedBTreeSet,
+        swiss_table::{
+            ArchivedHashMap, ArchivedHashSet, ArchivedIndexMap,
+            ArchivedIndexSet,
+        },
+    },
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedNonZeroI128, ArchivedNonZeroI16,
+        ArchivedNonZeroI32, ArchivedNonZeroI64, ArchivedNonZeroU128,
+        ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
+        ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64,
+    },
+    rc::ArchivedRc,
+    result::ArchivedResult,
+    string::ArchivedString,
+    tuple::*,
+    vec::ArchivedVec,
+    walk::{Visitor, Walk},
+    ArchivePointee,
+};
+
+// Primitives
+
+macro_rules! impl_walk {
+    ($($ty:ty => |$value:ident| $method:ident($expr:expr)),* $(,)?) => {
+        $(
+            impl Walk for $ty {
+                #[inline]
+                fn walk(&self, visitor: &mut dyn Visitor) {
+                    let $value = self;
+                    visitor.$method($expr);
+                }
+            }
+        )*
+    };
+}
+
+impl_walk! {
+    bool => |value| visit_bool(*value),
+    i8 => |value| visit_signed((*value).into()),
+    u8 => |value| visit_unsigned((*value).into()),
+    NonZeroI8 => |value| visit_signed(value.get().into()),
+    NonZeroU8 => |value| visit_unsigned(value.get().into()),
+    ArchivedI16 => |value| visit_signed(value.to_native().into()),
+    ArchivedI32 => |value| visit_signed(value.to_native().into()),
+    ArchivedI64 => |value| visit_signed(value.to_native().into()),
+    ArchivedI128 => |value| visit_signed(value.to_native()),
+    ArchivedU16 => |value| visit_unsigned(value.to_native().into()),
+    ArchivedU32 => |value| visit_unsigned(value.to_native().into()),
+    ArchivedU64 => |value| visit_unsigned(value.to_native().into()),
+    ArchivedU128 => |value| visit_unsigned(value.to_native()),
+    ArchivedF32 => |value| visit_float(value.to_native().into()),
+    ArchivedF64 => |value| visit_float(value.to_native()),
+    ArchivedChar => |value| visit_char(value.to_native()),
+    ArchivedNonZeroI16 => |value| visit_signed(value.get().into()),
+    ArchivedNonZeroI32 => |value| visit_signed(value.get().into()),
+    ArchivedNonZeroI64 => |value| visit_signed(value.get().into()),
+    ArchivedNonZeroI128 => |value| visit_signed(value.get()),
+    ArchivedNonZeroU16 => |value| visit_unsigned(value.get().into()),
+    ArchivedNonZeroU32 => |value| visit_unsigned(value.get().into()),
+    ArchivedNonZeroU64 => |value| visit_unsigned(value.get().into()),
+    ArchivedNonZeroU128 => |value| visit_unsigned(value.get()),
+    str => |value| visit_str(value),
+    ArchivedString => |value| visit_str(value.as_str()),
+}
+
+impl Walk for () {
+    #[inline]
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_unit();
+    }
+}
+
+impl<T: ?Sized> Walk for PhantomData<T> {
+    #[inline]
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_unit();
+    }
+}
+
+// Sequences
+
+impl<T: Walk> Walk for [T] {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_seq(self.len());
+        for element in self.iter() {
+            element.walk(visitor);
+        }
+        visitor.end_seq();
+    }
+}
+
+impl<T: Walk, const N: usize> Walk for [T; N] {
+    #[inline]
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        self.as_slice().walk(visitor);
+    }
+}
+
+impl<T: Walk> Walk for ArchivedVec<T> {
+    #[inline]
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        self.as_slice().walk(visitor);
+    }
+}
+
+macro_rules! impl_tuple {
+    ($name:ident, $n:tt, $($type:ident $index:tt),*) => {
+        impl<$($type: Walk),*> Walk for $name<$($type),*> {
+            fn walk(&self, visitor: &mut dyn Visitor) {
+                visitor.begin_seq($n);
+                $(self.$index.walk(visitor);)*
+                visitor.end_seq();
+            }
+        }
+    };
+}
+
+impl_tuple!(ArchivedTuple1, 1, T0 0);
+impl_tuple!(ArchivedTuple2, 2, T0 0, T1 1);
+impl_tuple!(ArchivedTuple3, 3, T0 0, T1 1, T2 2);
+impl_tuple!(ArchivedTuple4, 4, T0 0, T1 1, T2 2, T3 3);
+impl_tuple!(ArchivedTuple5, 5, T0 0, T1 1, T2 2, T3 3, T4 4);
+impl_tuple!(ArchivedTuple6, 6, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+impl_tuple!(ArchivedTuple7, 7, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+impl_tuple!(ArchivedTuple8, 8, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+impl_tuple!(
+    ArchivedTuple9, 9, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8
+);
+impl_tuple!(
+    ArchivedTuple10, 10, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9
+);
+impl_tuple!(
+    ArchivedTuple11, 11, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10
+);
+impl_tuple!(
+    ArchivedTuple12, 12, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10, T11 11
+);
+impl_tuple!(
+    ArchivedTuple13, 13, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10, T11 11, T12 12
+);
+
+// Pointers
+
+impl<T: ArchivePointee + Walk + ?Sized> Walk for ArchivedBox<T> {
+    #[inline]
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        self.get().walk(visitor);
+    }
+}
+
+impl<T: ArchivePointee + Walk + ?Sized, F> Walk for ArchivedRc<T, F> {
+    #[inline]
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        self.get().walk(visitor);
+    }
+}
+
+// Option and Result
+
+impl<T: Walk> Walk for ArchivedOption<T> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        match self {
+            ArchivedOption::None => visitor.visit_none(),
+            ArchivedOption::Some(value) => {
+                visitor.begin_some();
+                value.walk(visitor);
+                visitor.end_some();
+            }
+        }
+    }
+}
+
+impl<T: Walk, E: Walk> Walk for ArchivedResult<T, E> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        let (variant, value): (_, &dyn Walk) = match self {
+            ArchivedResult::Ok(value) => ("Ok", value),
+            ArchivedResult::Err(error) => ("Err", error),
+        };
+        visitor.begin_variant("Result", variant);
+        visitor.visit_field("0");
+        value.walk(visitor);
+        visitor.end_variant();
+    }
+}
+
+// Collections
+
+impl<K: Walk, V: Walk, H> Walk for ArchivedHashMap<K, V, H> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_map(self.len());
+        for (key, value) in self.iter() {
+            key.walk(visitor);
+            value.walk(visitor);
+        }
+        visitor.end_map();
+    }
+}
+
+impl<K: Walk, H> Walk for ArchivedHashSet<K, H> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_seq(self.len());
+        for key in self.iter() {
+            key.walk(visitor);
+        }
+        visitor.end_seq();
+    }
+}
+
+impl<K: Walk, V: Walk, H> Walk for ArchivedIndexMap<K, V, H> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_map(self.len());
+        for (key, value) in self.iter() {
+            key.walk(visitor);
+            value.walk(visitor);
+        }
+        visitor.end_map();
+    }
+}
+
+impl<K: Walk, H> Walk for ArchivedIndexSet<K, H> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_seq(self.len());
+        for key in self.iter() {
+            key.walk(visitor);
+        }
+        visitor.end_seq();
+    }
+}
+
+impl<K: Walk, V: Walk, const E: usize> Walk for ArchivedBTreeMap<K, V, E> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_map(self.len());
+        self.visit(|key, value| {
+            key.walk(visitor);
+            value.walk(visitor);
+            ControlFlow::<()>::Continue(())
+        });
+        visitor.end_map();
+    }
+}
+
+impl<K: Walk, const E: usize> Walk for ArchivedBTreeSet<K, E> {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        visitor.begin_seq(self.len());
+        self.visit(|key| {
+            key.walk(visitor);
+            ControlFlow::<()>::Continue(())
+        });
+        visitor.end_seq();
+    }
+}
@@ -0,0 +1,202 @@
+//! Archived versions of `jiff` types.
+
+use crate::{
+    primitive::{ArchivedI16, ArchivedI32, ArchivedI64},
+    Portable,
+};
+
+/// An archived [`Timestamp`](jiff::Timestamp).
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct ArchivedTimestamp {
+    pub(crate) second: ArchivedI64,
+    pub(crate) nanosecond: ArchivedI32,
+}
+
+impl ArchivedTimestamp {
+    /// Returns the number of whole seconds since the Unix epoch.
+    ///
+    /// Timestamps before the Unix epoch have a negative number of seconds.
+    #[inline]
+    pub const fn as_second(&self) -> i64 {
+        self.second.to_native()
+    }
+
+    /// Returns the fractional second component of this timestamp, in
+    /// nanoseconds.
+    ///
+    /// The fractional component has the same sign as the whole seconds.
+    #[inline]
+    pub const fn subsec_nanosecond(&self) -> i32 {
+        self.nanosecond.to_native()
+    }
+
+    /// Returns the number of nanoseconds since the Unix epoch.
+    #[inline]
+    pub const fn as_nanosecond(&self) -> i128 {
+        self.as_second() as i128 * 1_000_000_000
+            + self.subsec_nanosecond() as i128
+    }
+}
+
+/// An archived [`DateTime`](jiff::civil::DateTime).
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct ArchivedDateTime {
+    pub(crate) year: ArchivedI16,
+    pub(crate) month: i8,
+    pub(crate) day: i8,
+    pub(crate) hour: i8,
+    pub(crate) minute: i8,
+    pub(crate) second: i8,
+    pub(crate) subsec_nanosecond: ArchivedI32,
+}
+
+impl ArchivedDateTime {
+    /// Returns the year of this datetime.
+    #[inline]
+    pub const fn year(&self) -> i16 {
+        self.year.to_native()
+    }
+
+    /// Returns the month of this datetime, from 1 to 12.
+    #[inline]
+    pub const fn month(&self) -> i8 {
+        self.month
+    }
+
+    /// Returns the day of the month of this datetime, starting at 1.
+    #[inline]
+    pub const fn day(&self) -> i8 {
+        self.day
+    }
+
+    /// Returns the hour of this datetime, from 0 to 23.
+    #[inline]
+    pub const fn hour(&self) -> i8 {
+        self.hour
+    }
+
+    /// Returns the minute of this datetime, from 0 to 59.
+    #[inline]
+    pub const fn minute(&self) -> i8 {
+        self.minute
+    }
+
+    /// Returns the second of this datetime, from 0 to 59.
+    #[inline]
+    pub const fn second(&self) -> i8 {
+        self.second
+    }
+
+    /// Returns the fractional part of the second of this datetime, in
+    /// nanoseconds.
+    #[inline]
+    pub const fn subsec_nanosecond(&self) -> i32 {
+        self.subsec_nanosecond.to_native()
+    }
+}
+
+/// An archived [`Span`](jiff::Span).
+///
+/// Like a `Span`, every non-zero unit of an archived span has the same sign.
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[repr(C)]
+pub struct ArchivedSpan {
+    pub(crate) years: ArchivedI16,
+    pub(crate) months: ArchivedI32,
+    pub(crate) weeks: ArchivedI32,
+    pub(crate) days: ArchivedI32,
+    pub(crate) hours: ArchivedI32,
+    pub(crate) minutes: ArchivedI64,
+    pub(crate) seconds: ArchivedI64,
+    pub(crate) milliseconds: ArchivedI64,
+    pub(crate) microseconds: ArchivedI64,
+    pub(crate) nanoseconds: ArchivedI64,
+}
+
+impl ArchivedSpan {
+    /// Returns the number of years in this span.
+    #[inline]
+    pub const fn get_years(&self) -> i16 {
+        self.years.to_native()
+    }
+
+    /// Returns the number of months in this span.
+    #[inline]
+    pub const fn get_months(&self) -> i32 {
+        self.months.to_native()
+    }
+
+    /// Returns the number of weeks in this span.
+    #[inline]
+    pub const fn get_weeks(&self) -> i32 {
+        self.weeks.to_native()
+    }
+
+    /// Returns the number of days in this span.
+    #[inline]
+    pub const fn get_days(&self) -> i32 {
+        self.days.to_native()
+    }
+
+    /// Returns the number of hours in this span.
+    #[inline]
+    pub const fn get_hours(&self) -> i32 {
+        self.hours.to_native()
+    }
+
+    /// Returns the number of minutes in this span.
+    #[inline]
+    pub const fn get_minutes(&self) -> i64 {
+        self.minutes.to_native()
+    }
+
+    /// Returns the number of seconds in this span.
+    #[inline]
+    pub const fn get_seconds(&self) -> i64 {
+        self.seconds.to_native()
+    }
+
+    /// Returns the number of milliseconds in this span.
+    #[inline]
+    pub const fn get_milliseconds(&self) -> i64 {
+        self.milliseconds.to_native()
+    }
+
+    /// Returns the number of microseconds in this span.
+    #[inline]
+    pub const fn get_microseconds(&self) -> i64 {
+        self.microseconds.to_native()
+    }
+
+    /// Returns the number of nanoseconds in this span.
+    #[inline]
+    pub const fn get_nanoseconds(&self) -> i64 {
+        self.nanoseconds.to_native()
+    }
+
+    /// Returns `true` if every unit of this span is zero.
+    #[inline]
+    pub const fn is_zero(&self) -> bool {
+        self.get_years() == 0
+            && self.get_months() == 0
+            && self.get_weeks() == 0
+            && self.get_days() == 0
+            && self.get_hours() == 0
+            && self.get_minutes() == 0
+            && self.get_seconds() == 0
+            && self.get_milliseconds() == 0
+            && self.get_microseconds() == 0
+            && self.get_nanoseconds() == 0
+    }
+}
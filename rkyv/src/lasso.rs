@@ -0,0 +1,199 @@
+//! Archived versions of `lasso` types.
+
+use core::{fmt, num::NonZeroU32};
+
+use lasso::{Key, Spur};
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::ArchivedNonZeroU32,
+    ser::{Allocator, Writer},
+    string::{ArchivedString, StringResolver},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+/// An archived [`Spur`].
+///
+/// Archived spurs implement [`Key`], so they can be used to resolve strings
+/// from an [`ArchivedRodeo`] in the same archive.
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct ArchivedSpur {
+    pub(crate) key: ArchivedNonZeroU32,
+}
+
+impl ArchivedSpur {
+    /// Returns the underlying key of this spur.
+    #[inline]
+    pub fn into_inner(self) -> NonZeroU32 {
+        self.key.to_native()
+    }
+}
+
+// SAFETY: `into_usize` and `try_from_usize` are inverses, the same as they
+// are for `Spur`.
+unsafe impl Key for ArchivedSpur {
+    #[inline]
+    fn into_usize(self) -> usize {
+        self.into_inner().get() as usize - 1
+    }
+
+    #[inline]
+    fn try_from_usize(int: usize) -> Option<Self> {
+        let key = u32::try_from(int.checked_add(1)?).ok()?;
+        Some(Self {
+            key: ArchivedNonZeroU32::from_native(NonZeroU32::new(key)?),
+        })
+    }
+}
+
+impl PartialEq<Spur> for ArchivedSpur {
+    #[inline]
+    fn eq(&self, other: &Spur) -> bool {
+        self.into_inner() == other.into_inner()
+    }
+}
+
+/// An archived string interner.
+///
+/// This is the archived form of both [`Rodeo`](lasso::Rodeo) and
+/// [`RodeoReader`](lasso::RodeoReader). The interned strings are stored in
+/// order of their keys, so keys can be resolved without deserializing the
+/// interner. Any [`Key`] type can be used to resolve strings, including
+/// [`ArchivedSpur`].
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedRodeo {
+    strings: ArchivedVec<ArchivedString>,
+}
+
+impl ArchivedRodeo {
+    /// Returns the number of strings in the interner.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if the interner contains no strings.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the interned strings, in order of their keys.
+    #[inline]
+    pub fn strings(&self) -> &[ArchivedString] {
+        self.strings.as_slice()
+    }
+
+    /// Returns `true` if the given key was produced by the interner.
+    #[inline]
+    pub fn contains_key<K: Key>(&self, key: &K) -> bool {
+        key.into_usize() < self.len()
+    }
+
+    /// Resolves a key to its string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key was not produced by the interner.
+    #[inline]
+    pub fn resolve<K: Key>(&self, key: &K) -> &str {
+        self.try_resolve(key)
+            .expect("key is out of bounds for the archived interner")
+    }
+
+    /// Resolves a key to its string, returning `None` if the key was not
+    /// produced by the interner.
+    #[inline]
+    pub fn try_resolve<K: Key>(&self, key: &K) -> Option<&str> {
+        self.strings()
+            .get(key.into_usize())
+            .map(ArchivedString::as_str)
+    }
+
+    /// Returns an iterator over the keys and strings of the interner, in
+    /// order of their keys.
+    pub fn iter<K: Key>(&self) -> impl Iterator<Item = (K, &str)> {
+        self.strings()
+            .iter()
+            .enumerate()
+            .map_while(|(index, string)| {
+                Some((K::try_from_usize(index)?, string.as_str()))
+            })
+    }
+
+    /// Resolves an archived interner from the given number of strings and
+    /// resolver.
+    #[inline]
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: RodeoResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedRodeo { strings } = out);
+        ArchivedVec::resolve_from_len(len, resolver.strings, strings);
+    }
+
+    /// Serializes an iterator of strings as an interner.
+    ///
+    /// The iterator must yield the strings in order of their keys.
+    pub fn serialize_from_strings<'a, I, S>(
+        strings: I,
+        serializer: &mut S,
+    ) -> Result<RodeoResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = &'a str> + Clone,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        Ok(RodeoResolver {
+            strings: ArchivedVec::<ArchivedString>::serialize_from_iter::<
+                InternedStr<'a>,
+                _,
+                _,
+            >(strings.map(InternedStr), serializer)?,
+        })
+    }
+}
+
+impl fmt::Debug for ArchivedRodeo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.strings()).finish()
+    }
+}
+
+/// The resolver for an [`ArchivedRodeo`].
+pub struct RodeoResolver {
+    strings: VecResolver,
+}
+
+struct InternedStr<'a>(&'a str);
+
+impl Archive for InternedStr<'_> {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedString::resolve_from_str(self.0, resolver, out);
+    }
+}
+
+impl<S> Serialize<S> for InternedStr<'_>
+where
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(self.0, serializer)
+    }
+}
@@ -0,0 +1,283 @@
+//! Values which are deserialized on first access.
+
+use core::{cell::OnceCell, fmt, mem::size_of, ops::Deref};
+
+use munge::munge;
+use rancor::{Fallible, Source, Strategy};
+
+use crate::{
+    de::{Pool, SourceBuffer},
+    util::{deserialize, SharedAlignedVec},
+    Archive, Deserialize, Place, Portable, Serialize,
+};
+
+/// A value which is deserialized on first access.
+///
+/// When deserialized with a [`SourceBuffer`] deserializer like
+/// [`Pool::with_source_buffer`], a `Lazy` keeps a clone of the buffer and the
+/// position of its archived value instead of deserializing it. The value is
+/// deserialized the first time it is accessed with [`get`](Lazy::get), and
+/// the result is kept for later accesses. This allows the small, frequently
+/// used parts of a message to be deserialized eagerly while the rest are only
+/// deserialized if they are needed.
+///
+/// Deserializers which do not provide a source buffer deserialize the value
+/// eagerly.
+///
+/// Lazy values are deserialized with a new [`Pool`], so shared pointers in a
+/// lazy value are not shared with the rest of the archive.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     de::Pool,
+///     lazy::Lazy,
+///     rancor::Error,
+///     util::{deserialize, SharedAlignedVec},
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Message {
+///     id: u32,
+///     payload: Lazy<Vec<u8>>,
+/// }
+///
+/// let message = Message {
+///     id: 42,
+///     payload: Lazy::new(vec![0; 1024]),
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&message).unwrap();
+/// let bytes = SharedAlignedVec::from(bytes);
+///
+/// let archived = unsafe { rkyv::access_unchecked::<ArchivedMessage>(&bytes) };
+/// let mut pool = Pool::with_source_buffer(bytes.clone());
+/// let message =
+///     deserialize::<Message, _, Error>(archived, &mut pool).unwrap();
+///
+/// assert_eq!(message.id, 42);
+/// assert!(!message.payload.is_loaded());
+/// assert_eq!(message.payload.get::<Error>().unwrap().len(), 1024);
+/// assert!(message.payload.is_loaded());
+/// ```
+pub struct Lazy<T: Archive> {
+    inner: LazyInner<T>,
+}
+
+enum LazyInner<T> {
+    Loaded(T),
+    Deferred {
+        buffer: SharedAlignedVec,
+        pos: usize,
+        value: OnceCell<T>,
+    },
+}
+
+impl<T: Archive> Lazy<T> {
+    /// Creates a new `Lazy` which has already been loaded with the given
+    /// value.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: LazyInner::Loaded(value),
+        }
+    }
+
+    /// Returns whether the value has been deserialized.
+    #[inline]
+    pub fn is_loaded(&self) -> bool {
+        self.loaded().is_some()
+    }
+
+    /// Returns the value if it has been deserialized.
+    #[inline]
+    pub fn loaded(&self) -> Option<&T> {
+        match &self.inner {
+            LazyInner::Loaded(value) => Some(value),
+            LazyInner::Deferred { value, .. } => value.get(),
+        }
+    }
+
+    /// Returns the archived value if this `Lazy` was deserialized from a
+    /// source buffer.
+    #[inline]
+    pub fn archived(&self) -> Option<&T::Archived> {
+        match &self.inner {
+            LazyInner::Loaded(_) => None,
+            LazyInner::Deferred { buffer, pos, .. } => {
+                // SAFETY: `Deferred` is only created by deserializing an
+                // archived value located at `pos` in `buffer`.
+                Some(unsafe { archived_at::<T>(buffer, *pos) })
+            }
+        }
+    }
+
+    /// Returns the value, deserializing it if it has not been deserialized
+    /// yet.
+    pub fn get<E>(&self) -> Result<&T, E>
+    where
+        T::Archived: Deserialize<T, Strategy<Pool, E>>,
+        E: Source,
+    {
+        match &self.inner {
+            LazyInner::Loaded(value) => Ok(value),
+            LazyInner::Deferred { buffer, pos, value } => {
+                if let Some(value) = value.get() {
+                    return Ok(value);
+                }
+                // SAFETY: `Deferred` is only created by deserializing an
+                // archived value located at `pos` in `buffer`.
+                let loaded = unsafe { load(buffer, *pos)? };
+                Ok(value.get_or_init(|| loaded))
+            }
+        }
+    }
+
+    /// Returns the value, deserializing it if it has not been deserialized
+    /// yet.
+    pub fn into_inner<E>(self) -> Result<T, E>
+    where
+        T::Archived: Deserialize<T, Strategy<Pool, E>>,
+        E: Source,
+    {
+        match self.inner {
+            LazyInner::Loaded(value) => Ok(value),
+            LazyInner::Deferred { buffer, pos, value } => {
+                match value.into_inner() {
+                    Some(value) => Ok(value),
+                    // SAFETY: `Deferred` is only created by deserializing an
+                    // archived value located at `pos` in `buffer`.
+                    None => unsafe { load(&buffer, pos) },
+                }
+            }
+        }
+    }
+}
+
+/// # Safety
+///
+/// An archived `T` must be located at `pos` in `buffer`.
+unsafe fn archived_at<T: Archive>(
+    buffer: &SharedAlignedVec,
+    pos: usize,
+) -> &T::Archived {
+    // SAFETY: The caller has guaranteed that an archived `T` is located at
+    // `pos` in `buffer`. The buffer is immutable, and its bytes are not moved
+    // while it is borrowed.
+    unsafe { &*buffer.as_ptr().add(pos).cast::<T::Archived>() }
+}
+
+/// # Safety
+///
+/// An archived `T` must be located at `pos` in `buffer`.
+unsafe fn load<T, E>(buffer: &SharedAlignedVec, pos: usize) -> Result<T, E>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, Strategy<Pool, E>>,
+    E: Source,
+{
+    // SAFETY: The caller has guaranteed that an archived `T` is located at
+    // `pos` in `buffer`.
+    let archived = unsafe { archived_at::<T>(buffer, pos) };
+    let mut pool = Pool::with_source_buffer(buffer.clone());
+    deserialize(archived, &mut pool)
+}
+
+impl<T: Archive + fmt::Debug> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.loaded() {
+            Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+            None => f.write_str("Lazy(<deferred>)"),
+        }
+    }
+}
+
+/// An archived [`Lazy`].
+///
+/// This has the same layout as the archived value, and dereferences to it.
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedLazy<T: Archive> {
+    inner: T::Archived,
+}
+
+impl<T: Archive> ArchivedLazy<T> {
+    /// Returns the archived value.
+    #[inline]
+    pub fn get(&self) -> &T::Archived {
+        &self.inner
+    }
+}
+
+impl<T: Archive> Deref for ArchivedLazy<T> {
+    type Target = T::Archived;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: Archive> fmt::Debug for ArchivedLazy<T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T: Archive> Archive for Lazy<T> {
+    type Archived = ArchivedLazy<T>;
+    type Resolver = T::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // Serializing a `Lazy` always loads its value.
+        let value = self
+            .loaded()
+            .expect("lazy values must be serialized before being resolved");
+        munge!(let ArchivedLazy { inner } = out);
+        value.resolve(resolver, inner);
+    }
+}
+
+impl<T, S> Serialize<S> for Lazy<T>
+where
+    T: Serialize<S>,
+    T::Archived: Deserialize<T, Strategy<Pool, S::Error>>,
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<T::Resolver, S::Error> {
+        self.get::<S::Error>()?.serialize(serializer)
+    }
+}
+
+impl<T, D> Deserialize<Lazy<T>, D> for ArchivedLazy<T>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + SourceBuffer + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Lazy<T>, D::Error> {
+        if let Some(buffer) = deserializer.source_buffer() {
+            let start = buffer.as_ptr() as usize;
+            let address = &self.inner as *const T::Archived as usize;
+            let in_buffer = address >= start
+                && address + size_of::<T::Archived>() <= start + buffer.len();
+            if in_buffer {
+                return Ok(Lazy {
+                    inner: LazyInner::Deferred {
+                        buffer: buffer.clone(),
+                        pos: address - start,
+                        value: OnceCell::new(),
+                    },
+                });
+            }
+        }
+
+        Ok(Lazy::new(self.inner.deserialize(deserializer)?))
+    }
+}
@@ -67,16 +67,39 @@
 //!   architectures.
 //! - `big_endian`: Forces archives into a big-endian format. This guarantees
 //!   cross-endian compatibility optimized for big-endian architectures.
-//! - `size_16`: Archives integral `*size` types as 16-bit integers. This is
-//!   intended to be used only for small archives and may not handle large, more
-//!   general data.
-//! - `size_32`: Archives integral `*size` types as 32-bit integers. Enabled by
+//! - `pointer_width_16`: Archives integral `*size` types and relative pointers
+//!   as 16-bit integers. This is intended to be used only for small archives
+//!   and may not handle large, more general data.
+//! - `pointer_width_32`: Archives integral `*size` types and relative pointers
+//!   as 32-bit integers. Archives are limited to about 2 GiB. Enabled by
 //!   default.
-//! - `size_64`: Archives integral `*size` types as 64-bit integers. This is
-//!   intended to be used only for very large archives and may cause unnecessary
-//!   data bloat.
+//! - `pointer_width_64`: Archives integral `*size` types and relative pointers
+//!   as 64-bit integers. Use this for archives larger than 2 GiB. Archived
+//!   lengths and pointers take up more space.
 //! - `std`: Enables standard library support. Enabled by default.
 //! - `bytecheck`: Enables validation support through `bytecheck`.
+//! - `serde`: Enables serializing archived types with serde. See [`serde`] for
+//!   more details.
+//! - `fuzz`: Enables helpers for fuzzing archived types with `arbitrary`. See
+//!   [`fuzz`] for more details.
+//!
+//! ## Archive size limits
+//!
+//! Relative pointers are resolved after their targets have been written, and
+//! `resolve` can't return errors. Instead, serialize implementations check that
+//! the offsets to their targets fit in the configured pointer width with
+//! [`WriterExt::check_offset`](ser::WriterExt::check_offset), and return the
+//! error from [`Writer::overflow`](ser::Writer::overflow) if they don't.
+//! Serialization only panics if an archive grows past the limit between
+//! serializing a target and writing the relative pointer to it. The `try_*`
+//! constructors on [`RawRelPtr`] and [`RelPtr`] return these errors for custom
+//! resolvers.
+//!
+//! Values which don't fit in the configured pointer width are never truncated.
+//! Serializing a length which is out of range returns the error from
+//! [`Writer::overflow`](ser::Writer::overflow), which the writers provided by
+//! rkyv return as an error. Serializing a `usize` or `isize` which is out of
+//! range panics.
 //!
 //! ## Crate support
 //!
@@ -88,9 +111,15 @@
 //!
 //! Crates supported by rkyv:
 //!
+//! - [`bigdecimal`](https://docs.rs/bigdecimal)
+//! - [`generational-arena`](https://docs.rs/generational-arena)
 //! - [`indexmap`](https://docs.rs/indexmap)
+//! - [`jiff`](https://docs.rs/jiff)
+//! - [`lasso`](https://docs.rs/lasso)
 //! - [`rend`](https://docs.rs/rend) *Enabled automatically when using
 //!   endian-specific archive features.*
+//! - [`roaring`](https://docs.rs/roaring)
+//! - [`slotmap`](https://docs.rs/slotmap)
 //! - [`tinyvec`](https://docs.rs/tinyvec)
 //! - [`uuid`](https://docs.rs/uuid)
 //!
@@ -145,39 +174,74 @@ pub use ::munge;
 pub use ::ptr_meta;
 pub use ::rancor;
 pub use ::rend;
-pub use ::rkyv_derive::{Archive, Deserialize, Portable, Serialize};
+pub use ::rkyv_derive::{
+    Archive, Deserialize, DeserializeAsync, DeserializeBorrowed, Portable,
+    Serialize,
+};
 
 // Modules
 
 mod alias;
 #[macro_use]
 mod _macros;
+#[cfg(feature = "bigdecimal")]
+pub mod bigdecimal;
 #[cfg(feature = "bitvec")]
 pub mod bitvec;
 pub mod boxed;
 pub mod collections;
+pub mod compact;
+pub mod cow;
 pub mod de;
+pub mod endian;
+pub mod extern_ref;
 mod fmt;
+#[cfg(feature = "fuzz")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "fuzz")))]
+pub mod fuzz;
 // This is pretty unfortunate. CStr doesn't rely on the rest of std, but it's
 // not in core. If CStr ever gets moved into `core` then this module will no
 // longer need cfg(feature = "std")
 #[cfg(feature = "std")]
 pub mod ffi;
+#[cfg(feature = "generational-arena")]
+pub mod generational_arena;
 pub mod hash;
 mod impls;
+#[cfg(feature = "jiff")]
+pub mod jiff;
+#[cfg(feature = "lasso")]
+pub mod lasso;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub mod lazy;
 pub mod net;
 pub mod niche;
 pub mod ops;
 pub mod option;
+#[cfg(feature = "std")]
+pub mod path;
 pub mod place;
 mod polyfill;
+pub mod prefixed;
 pub mod primitive;
 pub mod rc;
 pub mod rel_ptr;
+pub mod relocate;
 pub mod result;
+#[cfg(feature = "roaring")]
+pub mod roaring;
+pub mod schema;
 pub mod ser;
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+pub mod serde;
 mod simd;
+#[cfg(feature = "slotmap")]
+pub mod slotmap;
+#[cfg(feature = "alloc")]
+pub mod splice;
 pub mod string;
+pub mod task;
 pub mod time;
 pub mod traits;
 pub mod tuple;
@@ -185,6 +249,7 @@ pub mod util;
 #[cfg(feature = "bytecheck")]
 pub mod validation;
 pub mod vec;
+pub mod walk;
 pub mod with;
 
 // Exports
@@ -193,24 +258,28 @@ pub mod with;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
 #[doc(inline)]
 pub use util::{from_bytes_unchecked, to_bytes, to_bytes_in};
+#[cfg(feature = "bytecheck")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bytecheck")))]
+#[doc(inline)]
+pub use validation::util::{access, access_mut};
 #[cfg(all(feature = "bytecheck", feature = "alloc"))]
 #[cfg_attr(
     doc_cfg,
     doc(cfg(all(feature = "bytecheck", feature = "alloc")))
 )]
 #[doc(inline)]
-pub use validation::util::from_bytes;
-#[cfg(feature = "bytecheck")]
-#[cfg_attr(doc_cfg, doc(cfg(feature = "bytecheck")))]
-#[doc(inline)]
-pub use validation::util::{access, access_mut};
+pub use validation::util::{from_bytes, from_bytes_async};
 
 #[doc(inline)]
 pub use crate::{
     alias::*,
     place::Place,
     traits::*,
-    util::{access_unchecked, access_unchecked_mut, deserialize, serialize},
+    util::{
+        access_unchecked, access_unchecked_mut, deserialize, deserialize_async,
+        deserialize_borrowed, deserialize_projection, serialize,
+        to_bytes_in_slice, to_bytes_into, to_bytes_noalloc,
+    },
 };
 
 // Check endianness feature flag settings
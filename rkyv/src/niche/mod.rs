@@ -2,3 +2,5 @@
 
 pub mod option_box;
 pub mod option_nonzero;
+pub mod option_string;
+pub mod option_vec;
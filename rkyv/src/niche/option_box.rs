@@ -6,34 +6,46 @@ use core::{
 };
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
+    endian::SwapEndian,
+    rel_ptr::{DefaultRepr, PointerRepr, RelPtr},
+    relocate::{OffsetMode, Relocate},
     ser::Writer,
-    ArchivePointee, ArchiveUnsized, Place, Portable, RelPtr, SerializeUnsized,
+    ArchivePointee, ArchiveUnsized, Place, Portable, SerializeUnsized,
 };
 
 /// A niched archived `Option<Box<T>>`.
 ///
-/// It uses less space by storing the `None` variant as a null pointer.
+/// It uses less space by storing the `None` variant as a null pointer. This is
+/// the archived type of `Option<Box<T>>` fields using the
+/// [`Niche`](crate::with::Niche) wrapper, and can be used in custom `Archive`
+/// implementations with [`resolve_from_option`](Self::resolve_from_option) and
+/// [`serialize_from_option`](Self::serialize_from_option). The representation
+/// of the relative pointer is selected by `P`, see [`PointerRepr`] for more
+/// details.
 #[derive(Portable)]
 #[archive(crate)]
 #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
 #[repr(transparent)]
-pub struct ArchivedOptionBox<T: ArchivePointee + ?Sized> {
-    repr: Repr<T>,
+pub struct ArchivedOptionBox<
+    T: ArchivePointee + ?Sized,
+    P: PointerRepr = DefaultRepr,
+> {
+    repr: Repr<T, P>,
 }
 
 #[derive(Portable)]
 #[archive(crate)]
 #[repr(C)]
-union Repr<T: ArchivePointee + ?Sized> {
-    boxed: ManuallyDrop<ArchivedBox<T>>,
-    ptr: ManuallyDrop<RelPtr<T>>,
+union Repr<T: ArchivePointee + ?Sized, P: PointerRepr> {
+    boxed: ManuallyDrop<ArchivedBox<T, P>>,
+    ptr: ManuallyDrop<RelPtr<T, P::Offset>>,
 }
 
-impl<T: ArchivePointee + ?Sized> Repr<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> Repr<T, P> {
     fn is_invalid(&self) -> bool {
         unsafe { self.ptr.is_invalid() }
     }
@@ -48,22 +60,27 @@ const _: () = {
         LayoutRaw,
     };
 
-    unsafe impl<T, C> CheckBytes<C> for Repr<T>
+    unsafe impl<T, P, C> CheckBytes<C> for Repr<T, P>
     where
         T: ArchivePointee + ?Sized,
+        P: PointerRepr,
         C: Fallible + ?Sized,
-        RelPtr<T>: CheckBytes<C>,
+        RelPtr<T, P::Offset>: CheckBytes<C>,
         Self: Verify<C>,
     {
         unsafe fn check_bytes(
             value: *const Self,
             context: &mut C,
         ) -> Result<(), C::Error> {
-            // SAFETY: `Repr<T>` is a `#[repr(C)]` union of an `ArchivedBox<T>`
-            // and a `RelPtr<T>`, and so is guaranteed to be aligned and point
-            // to enough bytes for a `RelPtr<T>`.
+            // SAFETY: `Repr<T, P>` is a `#[repr(C)]` union of an
+            // `ArchivedBox<T, P>` and a `RelPtr<T, P::Offset>`, and so is
+            // guaranteed to be aligned and point to enough bytes for a
+            // `RelPtr<T, P::Offset>`.
             unsafe {
-                RelPtr::check_bytes(value.cast::<RelPtr<T>>(), context)?;
+                RelPtr::check_bytes(
+                    value.cast::<RelPtr<T, P::Offset>>(),
+                    context,
+                )?;
             }
 
             // verify with null check
@@ -71,9 +88,10 @@ const _: () = {
         }
     }
 
-    unsafe impl<T, C> Verify<C> for Repr<T>
+    unsafe impl<T, P, C> Verify<C> for Repr<T, P>
     where
         T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized,
+        P: PointerRepr,
         T::ArchivedMetadata: CheckBytes<C>,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
@@ -90,7 +108,7 @@ const _: () = {
     }
 };
 
-impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T> {
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> ArchivedOptionBox<T, P> {
     /// Returns `true` if the option box is a `None` value.
     pub fn is_none(&self) -> bool {
         self.as_ref().is_none()
@@ -101,8 +119,8 @@ impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T> {
         self.as_ref().is_some()
     }
 
-    /// Converts to an `Option<&ArchivedBox<T>>`.
-    pub fn as_ref(&self) -> Option<&ArchivedBox<T>> {
+    /// Converts to an `Option<&ArchivedBox<T, P>>`.
+    pub fn as_ref(&self) -> Option<&ArchivedBox<T, P>> {
         if self.repr.is_invalid() {
             None
         } else {
@@ -110,8 +128,8 @@ impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T> {
         }
     }
 
-    /// Converts to an `Option<&mut ArchivedBox<T>>`.
-    pub fn as_mut(&mut self) -> Option<&mut ArchivedBox<T>> {
+    /// Converts to an `Option<&mut ArchivedBox<T, P>>`.
+    pub fn as_mut(&mut self) -> Option<&mut ArchivedBox<T, P>> {
         if self.repr.is_invalid() {
             None
         } else {
@@ -119,17 +137,17 @@ impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T> {
         }
     }
 
-    /// Converts from `Pin<&ArchivedOptionBox<T>>` to
-    /// `Option<Pin<&ArchivedBox<T>>>`.
-    pub fn as_pin_ref(self: Pin<&Self>) -> Option<Pin<&ArchivedBox<T>>> {
+    /// Converts from `Pin<&ArchivedOptionBox<T, P>>` to
+    /// `Option<Pin<&ArchivedBox<T, P>>>`.
+    pub fn as_pin_ref(self: Pin<&Self>) -> Option<Pin<&ArchivedBox<T, P>>> {
         unsafe { Pin::get_ref(self).as_ref().map(|x| Pin::new_unchecked(x)) }
     }
 
     /// Converts from `Pin<&mut ArchivedOption<T>>` to `Option<Pin<&mut
-    /// ArchivedBox<T>>>`.
+    /// ArchivedBox<T, P>>>`.
     pub fn as_pin_mut(
         self: Pin<&mut Self>,
-    ) -> Option<Pin<&mut ArchivedBox<T>>> {
+    ) -> Option<Pin<&mut ArchivedBox<T, P>>> {
         unsafe {
             Pin::get_unchecked_mut(self)
                 .as_mut()
@@ -138,29 +156,37 @@ impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T> {
     }
 
     /// Returns an iterator over the possibly contained value.
-    pub fn iter(&self) -> Iter<'_, ArchivedBox<T>> {
+    pub fn iter(&self) -> Iter<'_, ArchivedBox<T, P>> {
         Iter {
             inner: self.as_ref(),
         }
     }
 
     /// Returns a mutable iterator over the possibly contained value.
-    pub fn iter_mut(&mut self) -> IterMut<'_, ArchivedBox<T>> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, ArchivedBox<T, P>> {
         IterMut {
             inner: self.as_mut(),
         }
     }
 
-    /// Converts from `&ArchivedOptionBox<T>` to `Option<&T>`.
+    /// Converts from `&ArchivedOptionBox<T, P>` to `Option<&T>`.
     ///
     /// Leaves the original `ArchivedOptionBox` in-place, creating a new one
     /// with a reference to the original one.
     pub fn as_deref(&self) -> Option<&T> {
         self.as_ref().map(|x| (*x).deref())
     }
+
+    /// Converts from `Pin<&mut ArchivedOptionBox<T, P>>` to
+    /// `Option<Pin<&mut T>>`.
+    ///
+    /// This allows the boxed value to be mutated in-place.
+    pub fn as_pin_deref_mut(self: Pin<&mut Self>) -> Option<Pin<&mut T>> {
+        self.as_pin_mut().map(ArchivedBox::get_pin_mut)
+    }
 }
 
-impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T>
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> ArchivedOptionBox<T, P>
 where
     T::ArchivedMetadata: Default,
 {
@@ -181,15 +207,38 @@ where
                     }
                 };
 
-            let out = unsafe { repr.cast_unchecked::<ArchivedBox<T>>() };
+            let out = unsafe { repr.cast_unchecked::<ArchivedBox<T, P>>() };
             ArchivedBox::resolve_from_ref(value, resolver, out)
         } else {
-            let out = unsafe { repr.cast_unchecked::<RelPtr<T>>() };
+            let out = unsafe { repr.cast_unchecked::<RelPtr<T, P::Offset>>() };
             RelPtr::emplace_invalid(out);
         }
     }
 
+    /// Resolves an `ArchivedOptionBox` from a resolver and the archived
+    /// metadata of its value.
+    ///
+    /// The metadata is ignored if the resolver is `None`.
+    pub fn resolve_from_raw_parts(
+        resolver: OptionBoxResolver,
+        metadata: T::ArchivedMetadata,
+        out: Place<Self>,
+    ) {
+        munge!(let Self { repr } = out);
+        if let OptionBoxResolver::Some(resolver) = resolver {
+            let out = unsafe { repr.cast_unchecked::<ArchivedBox<T, P>>() };
+            ArchivedBox::resolve_from_raw_parts(resolver, metadata, out);
+        } else {
+            let out = unsafe { repr.cast_unchecked::<RelPtr<T, P::Offset>>() };
+            RelPtr::emplace_invalid(out);
+        }
+    }
+}
+
+impl<T: ArchivePointee + ?Sized> ArchivedOptionBox<T> {
     /// Serializes an `ArchivedOptionBox<T::Archived>` from an `Option<&T>`.
+    ///
+    /// The resolver can be used with any pointer representation.
     pub fn serialize_from_option<U, S>(
         field: Option<&U>,
         serializer: &mut S,
@@ -208,7 +257,8 @@ where
     }
 }
 
-impl<T: ArchivePointee + ?Sized> fmt::Debug for ArchivedOptionBox<T>
+impl<T: ArchivePointee + ?Sized, P: PointerRepr> fmt::Debug
+    for ArchivedOptionBox<T, P>
 where
     T::ArchivedMetadata: fmt::Debug,
 {
@@ -220,38 +270,106 @@ where
     }
 }
 
-impl<T: ArchivePointee + Eq + ?Sized> Eq for ArchivedOptionBox<T> {}
+impl<T: ArchivePointee + Eq + ?Sized, P: PointerRepr> Eq
+    for ArchivedOptionBox<T, P>
+{
+}
 
-impl<T: ArchivePointee + hash::Hash + ?Sized> hash::Hash
-    for ArchivedOptionBox<T>
+impl<T: ArchivePointee + hash::Hash + ?Sized, P: PointerRepr> hash::Hash
+    for ArchivedOptionBox<T, P>
 {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.as_ref().hash(state)
     }
 }
 
-impl<T: ArchivePointee + Ord + ?Sized> Ord for ArchivedOptionBox<T> {
+impl<T: ArchivePointee + Ord + ?Sized, P: PointerRepr> Ord
+    for ArchivedOptionBox<T, P>
+{
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_ref().cmp(&other.as_ref())
     }
 }
 
-impl<T: ArchivePointee + PartialEq + ?Sized> PartialEq
-    for ArchivedOptionBox<T>
+impl<T: ArchivePointee + PartialEq + ?Sized, P: PointerRepr> PartialEq
+    for ArchivedOptionBox<T, P>
 {
     fn eq(&self, other: &Self) -> bool {
         self.as_ref().eq(&other.as_ref())
     }
 }
 
-impl<T: ArchivePointee + PartialOrd + ?Sized> PartialOrd
-    for ArchivedOptionBox<T>
+impl<T: ArchivePointee + PartialOrd + ?Sized, P: PointerRepr> PartialOrd
+    for ArchivedOptionBox<T, P>
 {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         self.as_ref().partial_cmp(&other.as_ref())
     }
 }
 
+impl<'a, T: ArchivePointee + ?Sized, P: PointerRepr> IntoIterator
+    for &'a ArchivedOptionBox<T, P>
+{
+    type Item = &'a ArchivedBox<T, P>;
+    type IntoIter = Iter<'a, ArchivedBox<T, P>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: ArchivePointee + ?Sized, P: PointerRepr> IntoIterator
+    for &'a mut ArchivedOptionBox<T, P>
+{
+    type Item = &'a mut ArchivedBox<T, P>;
+    type IntoIter = IterMut<'a, ArchivedBox<T, P>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// SAFETY: `ArchivedOptionBox` is a transparent wrapper around a `RelPtr`. The
+// `None` variant is an invalid relative pointer, which is converted without
+// converting its target.
+unsafe impl<T, P> SwapEndian for ArchivedOptionBox<T, P>
+where
+    T: ArchivePointee + SwapEndian + ?Sized,
+    T::ArchivedMetadata: SwapEndian,
+    P: PointerRepr,
+    P::Offset: SwapEndian,
+{
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `swap_endian`, and so does its relative pointer.
+        unsafe {
+            RelPtr::<T>::swap_endian(this.cast());
+        }
+    }
+}
+
+// SAFETY: `ArchivedOptionBox` is a transparent wrapper around a `RelPtr`. The
+// `None` variant is an invalid relative pointer, which stays invalid and whose
+// target is not converted.
+unsafe impl<T, P> Relocate for ArchivedOptionBox<T, P>
+where
+    T: ArchivePointee + Relocate + ?Sized,
+    P: PointerRepr,
+    P::Offset: Portable,
+{
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `relocate`, and so does its relative pointer.
+        unsafe { RelPtr::<T>::relocate(this.cast(), base, mode) }
+    }
+}
+
 /// An iterator over a reference to the `Some` variant of an
 /// `ArchivedOptionBox`.
 ///
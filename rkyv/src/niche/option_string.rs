@@ -0,0 +1,170 @@
+//! A niched archived `Option<String>` that uses less space.
+
+use core::{cmp, fmt, hash, pin::Pin};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    string::{ArchivedString, StringResolver},
+    Place, Portable, SerializeUnsized,
+};
+
+/// A niched archived `Option<String>`.
+///
+/// It uses less space by storing the `None` variant as a string representation
+/// which no valid string uses.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(transparent)]
+pub struct ArchivedOptionString {
+    inner: ArchivedString,
+}
+
+#[cfg(feature = "bytecheck")]
+const _: () = {
+    use crate::{
+        bytecheck::CheckBytes, rancor::Source, validation::ArchiveContext,
+    };
+
+    unsafe impl<C> CheckBytes<C> for ArchivedOptionString
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // SAFETY: `ArchivedOptionString` is a `#[repr(transparent)]`
+            // wrapper around an `ArchivedString`, and every bit pattern is a
+            // valid string representation.
+            let inner = unsafe { &*value.cast::<ArchivedString>() };
+            if inner.is_niche() {
+                // This is a `None` and doesn't need to be checked further
+                Ok(())
+            } else {
+                unsafe { ArchivedString::check_bytes(value.cast(), context) }
+            }
+        }
+    }
+};
+
+impl ArchivedOptionString {
+    /// Returns `true` if the option string is a `None` value.
+    pub fn is_none(&self) -> bool {
+        self.inner.is_niche()
+    }
+
+    /// Returns `true` if the option string is a `Some` value.
+    pub fn is_some(&self) -> bool {
+        !self.is_none()
+    }
+
+    /// Converts to an `Option<&ArchivedString>`.
+    pub fn as_ref(&self) -> Option<&ArchivedString> {
+        if self.is_none() {
+            None
+        } else {
+            Some(&self.inner)
+        }
+    }
+
+    /// Converts from `Pin<&mut ArchivedOptionString>` to
+    /// `Option<Pin<&mut ArchivedString>>`.
+    pub fn as_pin_mut(
+        self: Pin<&mut Self>,
+    ) -> Option<Pin<&mut ArchivedString>> {
+        if self.is_none() {
+            None
+        } else {
+            unsafe { Some(self.map_unchecked_mut(|s| &mut s.inner)) }
+        }
+    }
+
+    /// Returns an iterator over the possibly contained value.
+    pub fn iter(&self) -> Iter<'_, ArchivedString> {
+        Iter {
+            inner: self.as_ref(),
+        }
+    }
+
+    /// Converts from `&ArchivedOptionString` to `Option<&str>`.
+    pub fn as_deref(&self) -> Option<&str> {
+        self.as_ref().map(ArchivedString::as_str)
+    }
+
+    /// Resolves an `ArchivedOptionString` from an `Option<&str>`.
+    ///
+    /// The value must be `Some` if the resolver is `Some`.
+    pub fn resolve_from_option(
+        field: Option<&str>,
+        resolver: Option<StringResolver>,
+        out: Place<Self>,
+    ) {
+        munge!(let Self { inner } = out);
+        match (field, resolver) {
+            (Some(value), Some(resolver)) => {
+                ArchivedString::resolve_from_str(value, resolver, inner)
+            }
+            _ => ArchivedString::emplace_niche(inner),
+        }
+    }
+
+    /// Serializes an `ArchivedOptionString` from an `Option<&str>`.
+    pub fn serialize_from_option<S: Fallible + ?Sized>(
+        field: Option<&str>,
+        serializer: &mut S,
+    ) -> Result<Option<StringResolver>, S::Error>
+    where
+        str: SerializeUnsized<S>,
+    {
+        field
+            .map(|value| ArchivedString::serialize_from_str(value, serializer))
+            .transpose()
+    }
+}
+
+impl fmt::Debug for ArchivedOptionString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_ref() {
+            Some(inner) => inner.fmt(f),
+            None => f.debug_tuple("None").finish(),
+        }
+    }
+}
+
+impl Eq for ArchivedOptionString {}
+
+impl hash::Hash for ArchivedOptionString {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state)
+    }
+}
+
+impl Ord for ArchivedOptionString {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_ref().cmp(&other.as_ref())
+    }
+}
+
+impl PartialEq for ArchivedOptionString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref().eq(&other.as_ref())
+    }
+}
+
+impl PartialOrd for ArchivedOptionString {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// An iterator over a reference to the `Some` variant of an
+/// `ArchivedOptionString`.
+///
+/// This iterator yields one value if the `ArchivedOptionString` is a `Some`,
+/// otherwise none.
+///
+/// This `struct` is created by the [`ArchivedOptionString::iter`] function.
+pub type Iter<'a, T> = crate::option::Iter<'a, T>;
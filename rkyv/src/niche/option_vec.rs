@@ -0,0 +1,173 @@
+//! A niched archived `Option<Vec<T>>` that uses less space.
+
+use core::{cmp, fmt, hash, pin::Pin};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable, Serialize,
+};
+
+/// A niched archived `Option<Vec<T>>`.
+///
+/// It uses less space by storing the `None` variant as a null pointer.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(transparent)]
+pub struct ArchivedOptionVec<T> {
+    inner: ArchivedVec<T>,
+}
+
+#[cfg(feature = "bytecheck")]
+const _: () = {
+    use crate::{
+        bytecheck::CheckBytes, rancor::Source, validation::ArchiveContext,
+    };
+
+    unsafe impl<T, C> CheckBytes<C> for ArchivedOptionVec<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // SAFETY: `ArchivedOptionVec<T>` is a `#[repr(transparent)]`
+            // wrapper around an `ArchivedVec<T>`, and the pointer and length of
+            // an `ArchivedVec<T>` are valid for every bit pattern.
+            let inner = unsafe { &*value.cast::<ArchivedVec<T>>() };
+            if inner.is_niche() {
+                // This is a `None` and doesn't need to be checked further
+                Ok(())
+            } else {
+                unsafe { ArchivedVec::<T>::check_bytes(value.cast(), context) }
+            }
+        }
+    }
+};
+
+impl<T> ArchivedOptionVec<T> {
+    /// Returns `true` if the option vec is a `None` value.
+    pub fn is_none(&self) -> bool {
+        self.inner.is_niche()
+    }
+
+    /// Returns `true` if the option vec is a `Some` value.
+    pub fn is_some(&self) -> bool {
+        !self.is_none()
+    }
+
+    /// Converts to an `Option<&ArchivedVec<T>>`.
+    pub fn as_ref(&self) -> Option<&ArchivedVec<T>> {
+        if self.is_none() {
+            None
+        } else {
+            Some(&self.inner)
+        }
+    }
+
+    /// Converts from `Pin<&mut ArchivedOptionVec<T>>` to
+    /// `Option<Pin<&mut ArchivedVec<T>>>`.
+    pub fn as_pin_mut(
+        self: Pin<&mut Self>,
+    ) -> Option<Pin<&mut ArchivedVec<T>>> {
+        if self.is_none() {
+            None
+        } else {
+            unsafe { Some(self.map_unchecked_mut(|s| &mut s.inner)) }
+        }
+    }
+
+    /// Returns an iterator over the possibly contained value.
+    pub fn iter(&self) -> Iter<'_, ArchivedVec<T>> {
+        Iter {
+            inner: self.as_ref(),
+        }
+    }
+
+    /// Converts from `&ArchivedOptionVec<T>` to `Option<&[T]>`.
+    pub fn as_deref(&self) -> Option<&[T]> {
+        self.as_ref().map(ArchivedVec::as_slice)
+    }
+
+    /// Resolves an `ArchivedOptionVec<T>` from the length of an
+    /// `Option<&[U]>`.
+    ///
+    /// The length must be `Some` if the resolver is `Some`.
+    pub fn resolve_from_len(
+        len: Option<usize>,
+        resolver: Option<VecResolver>,
+        out: Place<Self>,
+    ) {
+        munge!(let Self { inner } = out);
+        match (len, resolver) {
+            (Some(len), Some(resolver)) => {
+                ArchivedVec::resolve_from_len(len, resolver, inner)
+            }
+            _ => ArchivedVec::emplace_niche(inner),
+        }
+    }
+
+    /// Serializes an `ArchivedOptionVec<T>` from an `Option<&[U]>`.
+    pub fn serialize_from_option<U, S>(
+        field: Option<&[U]>,
+        serializer: &mut S,
+    ) -> Result<Option<VecResolver>, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        field
+            .map(|slice| ArchivedVec::serialize_from_slice(slice, serializer))
+            .transpose()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedOptionVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_ref() {
+            Some(inner) => inner.fmt(f),
+            None => f.debug_tuple("None").finish(),
+        }
+    }
+}
+
+impl<T: Eq> Eq for ArchivedOptionVec<T> {}
+
+impl<T: hash::Hash> hash::Hash for ArchivedOptionVec<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state)
+    }
+}
+
+impl<T: Ord> Ord for ArchivedOptionVec<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_ref().cmp(&other.as_ref())
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArchivedOptionVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref().eq(&other.as_ref())
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for ArchivedOptionVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.as_ref().partial_cmp(&other.as_ref())
+    }
+}
+
+/// An iterator over a reference to the `Some` variant of an
+/// `ArchivedOptionVec`.
+///
+/// This iterator yields one value if the `ArchivedOptionVec` is a `Some`,
+/// otherwise none.
+///
+/// This `struct` is created by the [`ArchivedOptionVec::iter`] function.
+pub type Iter<'a, T> = crate::option::Iter<'a, T>;
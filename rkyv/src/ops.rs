@@ -2,10 +2,19 @@
 
 use core::{
     cmp, fmt,
-    ops::{Bound, RangeBounds},
+    ops::{
+        Bound, ControlFlow, Range, RangeBounds, RangeFrom, RangeInclusive,
+        RangeTo, RangeToInclusive,
+    },
 };
 
-use crate::Portable;
+use crate::{
+    primitive::{
+        ArchivedI128, ArchivedI16, ArchivedI32, ArchivedI64, ArchivedU128,
+        ArchivedU16, ArchivedU32, ArchivedU64,
+    },
+    Portable,
+};
 
 /// An archived [`Range`](::core::ops::Range).
 #[derive(Clone, Default, PartialEq, Eq, Hash, Portable)]
@@ -226,6 +235,128 @@ impl<T> RangeBounds<T> for ArchivedRangeToInclusive<T> {
     }
 }
 
+macro_rules! impl_integer_ranges {
+    ($($archived:ty => $prim:ty),* $(,)?) => { $(
+        impl ArchivedRange<$archived> {
+            /// Returns a [`Range`] with the same bounds.
+            ///
+            /// This can be passed to APIs which take native ranges, like
+            /// `BTreeMap::range`.
+            #[inline]
+            pub fn as_range(&self) -> Range<$prim> {
+                <$prim>::from(self.start)..<$prim>::from(self.end)
+            }
+
+            /// Returns an iterator over the values in the range.
+            #[inline]
+            pub fn iter(&self) -> Range<$prim> {
+                self.as_range()
+            }
+        }
+
+        impl IntoIterator for &ArchivedRange<$archived> {
+            type Item = $prim;
+            type IntoIter = Range<$prim>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl ArchivedRangeInclusive<$archived> {
+            /// Returns a [`RangeInclusive`] with the same bounds.
+            ///
+            /// This can be passed to APIs which take native ranges, like
+            /// `BTreeMap::range`.
+            #[inline]
+            pub fn as_range_inclusive(&self) -> RangeInclusive<$prim> {
+                <$prim>::from(self.start)..=<$prim>::from(self.end)
+            }
+
+            /// Returns an iterator over the values in the range.
+            #[inline]
+            pub fn iter(&self) -> RangeInclusive<$prim> {
+                self.as_range_inclusive()
+            }
+        }
+
+        impl IntoIterator for &ArchivedRangeInclusive<$archived> {
+            type Item = $prim;
+            type IntoIter = RangeInclusive<$prim>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl ArchivedRangeFrom<$archived> {
+            /// Returns a [`RangeFrom`] with the same bounds.
+            ///
+            /// This can be passed to APIs which take native ranges, like
+            /// `BTreeMap::range`.
+            #[inline]
+            pub fn as_range_from(&self) -> RangeFrom<$prim> {
+                <$prim>::from(self.start)..
+            }
+
+            /// Returns an iterator over the values in the range.
+            #[inline]
+            pub fn iter(&self) -> RangeFrom<$prim> {
+                self.as_range_from()
+            }
+        }
+
+        impl IntoIterator for &ArchivedRangeFrom<$archived> {
+            type Item = $prim;
+            type IntoIter = RangeFrom<$prim>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl ArchivedRangeTo<$archived> {
+            /// Returns a [`RangeTo`] with the same bounds.
+            ///
+            /// This can be passed to APIs which take native ranges, like
+            /// `BTreeMap::range`.
+            #[inline]
+            pub fn as_range_to(&self) -> RangeTo<$prim> {
+                ..<$prim>::from(self.end)
+            }
+        }
+
+        impl ArchivedRangeToInclusive<$archived> {
+            /// Returns a [`RangeToInclusive`] with the same bounds.
+            ///
+            /// This can be passed to APIs which take native ranges, like
+            /// `BTreeMap::range`.
+            #[inline]
+            pub fn as_range_to_inclusive(&self) -> RangeToInclusive<$prim> {
+                ..=<$prim>::from(self.end)
+            }
+        }
+    )* };
+}
+
+// `usize` and `isize` are archived as one of the fixed-size integers, so their
+// archived ranges convert to ranges of that integer type.
+impl_integer_ranges! {
+    u8 => u8,
+    i8 => i8,
+    ArchivedU16 => u16,
+    ArchivedI16 => i16,
+    ArchivedU32 => u32,
+    ArchivedI32 => i32,
+    ArchivedU64 => u64,
+    ArchivedI64 => i64,
+    ArchivedU128 => u128,
+    ArchivedI128 => i128,
+}
+
 /// An archived [`Bound`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Portable)]
 #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
@@ -260,3 +391,55 @@ impl<T> ArchivedBound<T> {
         }
     }
 }
+
+/// An archived [`ControlFlow`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+#[archive(crate)]
+pub enum ArchivedControlFlow<B, C = ()> {
+    /// Move on to the next phase of the operation as normal.
+    Continue(C),
+    /// Exit the operation without running subsequent phases.
+    Break(B),
+}
+
+impl<B, C> ArchivedControlFlow<B, C> {
+    /// Returns `true` if this is a [`Break`](ArchivedControlFlow::Break)
+    /// variant.
+    pub const fn is_break(&self) -> bool {
+        matches!(self, ArchivedControlFlow::Break(_))
+    }
+
+    /// Returns `true` if this is a [`Continue`](ArchivedControlFlow::Continue)
+    /// variant.
+    pub const fn is_continue(&self) -> bool {
+        matches!(self, ArchivedControlFlow::Continue(_))
+    }
+
+    /// Returns the [`Break`](ArchivedControlFlow::Break) value if this is a
+    /// `Break` variant, and `None` otherwise.
+    pub fn break_value(&self) -> Option<&B> {
+        match self {
+            ArchivedControlFlow::Continue(_) => None,
+            ArchivedControlFlow::Break(x) => Some(x),
+        }
+    }
+
+    /// Returns the [`Continue`](ArchivedControlFlow::Continue) value if this
+    /// is a `Continue` variant, and `None` otherwise.
+    pub fn continue_value(&self) -> Option<&C> {
+        match self {
+            ArchivedControlFlow::Continue(x) => Some(x),
+            ArchivedControlFlow::Break(_) => None,
+        }
+    }
+
+    /// Converts from `&ArchivedControlFlow<B, C>` to `ControlFlow<&B, &C>`.
+    pub fn as_ref(&self) -> ControlFlow<&B, &C> {
+        match self {
+            ArchivedControlFlow::Continue(x) => ControlFlow::Continue(x),
+            ArchivedControlFlow::Break(x) => ControlFlow::Break(x),
+        }
+    }
+}
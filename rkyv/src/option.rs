@@ -4,9 +4,16 @@ use core::{
     cmp, hash, mem,
     ops::{Deref, DerefMut},
     pin::Pin,
+    ptr,
 };
 
-use crate::Portable;
+use rancor::Source;
+
+use crate::{
+    endian::SwapEndian,
+    relocate::{OffsetMode, Relocate},
+    Portable,
+};
 
 /// An archived [`Option`].
 ///
@@ -244,6 +251,53 @@ impl<T> From<T> for ArchivedOption<T> {
     }
 }
 
+// SAFETY: The value of a `Some` is converted. The tag is a single byte and does
+// not need to be converted.
+unsafe impl<T: SwapEndian> SwapEndian for ArchivedOption<T> {
+    unsafe fn swap_endian(this: *mut Self) {
+        // `ArchivedOption` is `repr(u8)`, so the `Some` variant has the same
+        // layout as this struct.
+        #[repr(C)]
+        struct ArchivedOptionVariantSome<T>(u8, T);
+
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes and that it points to a valid `ArchivedOption`. The tag of
+        // `ArchivedOption::None` is zero.
+        unsafe {
+            if this.cast::<u8>().read() != 0 {
+                let some = this.cast::<ArchivedOptionVariantSome<T>>();
+                T::swap_endian(ptr::addr_of_mut!((*some).1));
+            }
+        }
+    }
+}
+
+// SAFETY: The value of a `Some` is converted. The tag does not contain any
+// relative pointers.
+unsafe impl<T: Relocate> Relocate for ArchivedOption<T> {
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // `ArchivedOption` is `repr(u8)`, so the `Some` variant has the same
+        // layout as this struct.
+        #[repr(C)]
+        struct ArchivedOptionVariantSome<T>(u8, T);
+
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `relocate` and that it points to a valid `ArchivedOption`. The tag
+        // of `ArchivedOption::None` is zero.
+        unsafe {
+            if this.cast::<u8>().read() != 0 {
+                let some = this.cast::<ArchivedOptionVariantSome<T>>();
+                T::relocate(ptr::addr_of_mut!((*some).1), base, mode)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// An iterator over a reference to the `Some` variant of an `ArchivedOption`.
 ///
 /// This iterator yields one value if the `ArchivedOption` is a `Some`,
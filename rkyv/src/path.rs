@@ -0,0 +1,354 @@
+//! Archived versions of path types.
+//!
+//! Archived paths use a platform-neutral encoding so that they can be read on
+//! any platform, regardless of where they were serialized:
+//!
+//! - The path is stored as bytes. On Unix, these are the raw bytes of the path.
+//!   On Windows, the wide path is converted to [WTF-8], which is identical to
+//!   UTF-8 for paths which are valid Unicode. On other platforms, only paths
+//!   which are valid UTF-8 can be archived.
+//! - Separators are always stored as `/`. On Windows, `\` separators are
+//!   converted to `/` when serializing, and back to `\` when deserializing.
+//!
+//! Archived paths are parsed the same way on every platform: `/` is the only
+//! separator, and Windows prefixes like `C:` are treated as normal components.
+//!
+//! [WTF-8]: https://simonsapin.github.io/wtf-8/
+
+use core::{
+    borrow::Borrow, cmp, fmt, hash, iter::FusedIterator, ops::Deref, str,
+};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable,
+};
+
+/// A slice of an archived path.
+///
+/// This is the archived counterpart of [`Path`](std::path::Path). See the
+/// [module docs](crate::path) for how archived paths are encoded.
+#[repr(transparent)]
+pub struct ArchivedPath {
+    bytes: [u8],
+}
+
+impl ArchivedPath {
+    /// Wraps the given encoded bytes as an archived path.
+    #[inline]
+    pub fn from_encoded_bytes(bytes: &[u8]) -> &Self {
+        // SAFETY: `ArchivedPath` is `repr(transparent)` over `[u8]`.
+        unsafe { &*(bytes as *const [u8] as *const Self) }
+    }
+
+    /// Returns the encoded bytes of this path.
+    #[inline]
+    pub fn as_encoded_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns this path as a `str` if it is valid UTF-8.
+    #[inline]
+    pub fn to_str(&self) -> Option<&str> {
+        str::from_utf8(&self.bytes).ok()
+    }
+
+    /// Returns whether this path begins with a root separator.
+    #[inline]
+    pub fn has_root(&self) -> bool {
+        self.bytes.first() == Some(&b'/')
+    }
+
+    /// Returns an iterator over the components of this path.
+    ///
+    /// Like [`Path::components`](std::path::Path::components), repeated
+    /// separators and `.` components which aren't at the beginning of the path
+    /// are skipped.
+    #[inline]
+    pub fn components(&self) -> Components<'_> {
+        Components {
+            rest: &self.bytes,
+            at_start: true,
+        }
+    }
+
+    /// Returns the final component of this path, if it is a normal component.
+    pub fn file_name(&self) -> Option<&[u8]> {
+        match self.components().last()? {
+            ArchivedComponent::Normal(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the file name of this path without its extension.
+    ///
+    /// See [`Path::file_stem`](std::path::Path::file_stem) for how the stem
+    /// and extension are split.
+    pub fn file_stem(&self) -> Option<&[u8]> {
+        self.file_name().map(|name| split_extension(name).0)
+    }
+
+    /// Returns the extension of the file name of this path, if any.
+    ///
+    /// See [`Path::extension`](std::path::Path::extension) for how the stem
+    /// and extension are split.
+    pub fn extension(&self) -> Option<&[u8]> {
+        self.file_name().and_then(|name| split_extension(name).1)
+    }
+}
+
+fn split_extension(name: &[u8]) -> (&[u8], Option<&[u8]>) {
+    match name.iter().rposition(|&b| b == b'.') {
+        None | Some(0) => (name, None),
+        Some(i) => (&name[..i], Some(&name[i + 1..])),
+    }
+}
+
+impl AsRef<ArchivedPath> for ArchivedPath {
+    #[inline]
+    fn as_ref(&self) -> &ArchivedPath {
+        self
+    }
+}
+
+impl fmt::Debug for ArchivedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&String::from_utf8_lossy(&self.bytes), f)
+    }
+}
+
+impl Eq for ArchivedPath {}
+
+impl hash::Hash for ArchivedPath {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state);
+    }
+}
+
+impl Ord for ArchivedPath {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+impl PartialEq for ArchivedPath {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl PartialEq<str> for ArchivedPath {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.bytes == *other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for ArchivedPath {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::<str>::eq(self, other)
+    }
+}
+
+impl PartialOrd for ArchivedPath {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A single component of an [`ArchivedPath`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArchivedComponent<'a> {
+    /// The root separator at the beginning of the path.
+    RootDir,
+    /// A `.` at the beginning of the path.
+    CurDir,
+    /// A `..` component.
+    ParentDir,
+    /// A normal component, like `a` and `b` in `a/b`.
+    Normal(&'a [u8]),
+}
+
+/// An iterator over the [components](ArchivedComponent) of an
+/// [`ArchivedPath`].
+#[derive(Clone, Debug)]
+pub struct Components<'a> {
+    rest: &'a [u8],
+    at_start: bool,
+}
+
+impl<'a> Components<'a> {
+    /// Returns the rest of the path which hasn't been iterated over yet.
+    #[inline]
+    pub fn as_path(&self) -> &'a ArchivedPath {
+        ArchivedPath::from_encoded_bytes(self.rest)
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = ArchivedComponent<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.at_start {
+            self.at_start = false;
+            if let Some(rest) = self.rest.strip_prefix(b"/") {
+                self.rest = rest;
+                return Some(ArchivedComponent::RootDir);
+            }
+            if self.rest == b"." || self.rest.starts_with(b"./") {
+                self.rest = &self.rest[1..];
+                return Some(ArchivedComponent::CurDir);
+            }
+        }
+
+        loop {
+            let start = self.rest.iter().position(|&b| b != b'/')?;
+            let rest = &self.rest[start..];
+            let end =
+                rest.iter().position(|&b| b == b'/').unwrap_or(rest.len());
+            let (component, rest) = rest.split_at(end);
+            self.rest = rest;
+
+            match component {
+                b"." => continue,
+                b".." => return Some(ArchivedComponent::ParentDir),
+                _ => return Some(ArchivedComponent::Normal(component)),
+            }
+        }
+    }
+}
+
+impl FusedIterator for Components<'_> {}
+
+/// An archived [`PathBuf`](std::path::PathBuf).
+///
+/// Dereferences to an [`ArchivedPath`]. See the [module docs](crate::path) for
+/// how archived paths are encoded.
+#[derive(Portable)]
+#[archive(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(transparent)]
+pub struct ArchivedPathBuf {
+    bytes: ArchivedVec<u8>,
+}
+
+impl ArchivedPathBuf {
+    /// Returns this path as an [`ArchivedPath`].
+    #[inline]
+    pub fn as_path(&self) -> &ArchivedPath {
+        ArchivedPath::from_encoded_bytes(self.bytes.as_slice())
+    }
+
+    /// Resolves an archived path from the length of its encoded bytes.
+    #[inline]
+    pub fn resolve_from_encoded_len(
+        len: usize,
+        resolver: PathBufResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedPathBuf { bytes } = out);
+        ArchivedVec::resolve_from_len(len, resolver.bytes, bytes);
+    }
+
+    /// Serializes a path from its encoded bytes.
+    ///
+    /// The bytes must be encoded as described in the
+    /// [module docs](crate::path).
+    pub fn serialize_from_encoded_bytes<S>(
+        bytes: &[u8],
+        serializer: &mut S,
+    ) -> Result<PathBufResolver, S::Error>
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        Ok(PathBufResolver {
+            bytes: ArchivedVec::serialize_from_slice(bytes, serializer)?,
+        })
+    }
+}
+
+impl AsRef<ArchivedPath> for ArchivedPathBuf {
+    #[inline]
+    fn as_ref(&self) -> &ArchivedPath {
+        self.as_path()
+    }
+}
+
+impl Borrow<ArchivedPath> for ArchivedPathBuf {
+    #[inline]
+    fn borrow(&self) -> &ArchivedPath {
+        self.as_path()
+    }
+}
+
+impl fmt::Debug for ArchivedPathBuf {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_path().fmt(f)
+    }
+}
+
+impl Deref for ArchivedPathBuf {
+    type Target = ArchivedPath;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_path()
+    }
+}
+
+impl Eq for ArchivedPathBuf {}
+
+impl hash::Hash for ArchivedPathBuf {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_path().hash(state);
+    }
+}
+
+impl Ord for ArchivedPathBuf {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_path().cmp(other.as_path())
+    }
+}
+
+impl PartialEq for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_path() == other.as_path()
+    }
+}
+
+impl PartialEq<str> for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        PartialEq::<str>::eq(self.as_path(), other)
+    }
+}
+
+impl PartialEq<&str> for ArchivedPathBuf {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        PartialEq::<str>::eq(self.as_path(), other)
+    }
+}
+
+impl PartialOrd for ArchivedPathBuf {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The resolver for `PathBuf`.
+pub struct PathBufResolver {
+    bytes: VecResolver,
+}
@@ -0,0 +1,301 @@
+//! Length-prefixed structs for forward-compatible archives.
+//!
+//! Structs derived with `#[archive(length_prefixed)]` are archived out-of-line
+//! along with the number of bytes that were written for them. When a field is
+//! added to the end of the struct, archives written by the older definition
+//! are shorter than the newer definition expects. Fields which have a default
+//! (`#[rkyv(default)]` or `#[rkyv(default = "...")]`) can be missing from the
+//! archive, and are filled in with their defaults when deserialized.
+//! Likewise, older definitions ignore any fields which were added after them.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{rancor::Error, Archive, Deserialize, Serialize};
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! #[archive(check_bytes, length_prefixed)]
+//! struct ConfigV1 {
+//!     name: String,
+//! }
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! #[archive(check_bytes, length_prefixed)]
+//! struct ConfigV2 {
+//!     name: String,
+//!     #[rkyv(default = "8080")]
+//!     port: u16,
+//! }
+//!
+//! let bytes = rkyv::to_bytes::<Error>(&ConfigV1 {
+//!     name: "example".to_string(),
+//! })
+//! .unwrap();
+//! let config = rkyv::from_bytes::<ConfigV2, Error>(&bytes).unwrap();
+//! assert_eq!(config.name, "example");
+//! assert_eq!(config.port, 8080);
+//! ```
+
+use core::{
+    fmt,
+    mem::{size_of, MaybeUninit},
+};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::{fixed_usize, ArchivedIsize, ArchivedUsize},
+    ser::{Writer, WriterExt as _},
+    Place, Portable, RelPtr,
+};
+
+/// A struct which is archived with a length prefix.
+///
+/// This is implemented by `#[archive(length_prefixed)]`, and provides the
+/// archived fields of the struct and a way to write them.
+pub trait ArchiveFields {
+    /// The archived fields of the struct.
+    type Fields: Portable;
+    /// The resolver for the fields of the struct.
+    type FieldsResolver;
+
+    /// Creates the archived fields of the struct in the given output location.
+    fn resolve_fields(
+        &self,
+        resolver: Self::FieldsResolver,
+        out: Place<Self::Fields>,
+    );
+}
+
+/// An archived struct which is stored along with the number of bytes that were
+/// written for it.
+///
+/// Only the first [`len`](LengthPrefixed::len) bytes of the struct are present
+/// in the archive. If the struct was archived by an older definition, the
+/// fields at the end of it may be missing.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+pub struct LengthPrefixed<T> {
+    ptr: RelPtr<T>,
+    len: ArchivedUsize,
+}
+
+impl<T> LengthPrefixed<T> {
+    /// Returns the number of bytes that were written for the struct.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether no bytes were written for the struct.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether every field of the struct is present.
+    pub fn is_complete(&self) -> bool {
+        self.len() >= size_of::<T>()
+    }
+
+    /// Returns a pointer to the archived struct.
+    ///
+    /// Only the first [`len`](LengthPrefixed::len) bytes of the struct may be
+    /// read.
+    pub fn as_ptr(&self) -> *const T {
+        unsafe { self.ptr.as_ptr() }
+    }
+
+    /// Returns a reference to the archived struct if every field of it is
+    /// present.
+    pub fn get(&self) -> Option<&T> {
+        if self.is_complete() {
+            Some(unsafe { &*self.as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the field of the struct at the given offset if
+    /// it is present.
+    ///
+    /// # Safety
+    ///
+    /// `offset` must be the offset of a field of type `U` in `T`.
+    pub unsafe fn field<U>(&self, offset: usize) -> Option<&U> {
+        if offset + size_of::<U>() <= self.len() {
+            // SAFETY: The caller has guaranteed that `offset` is the offset of
+            // a field of type `U`, and we checked that it was written.
+            Some(unsafe { self.field_unchecked(offset) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the field of the struct at the given offset
+    /// without checking whether it is present.
+    ///
+    /// # Safety
+    ///
+    /// - `offset` must be the offset of a field of type `U` in `T`.
+    /// - The field must be present in the archive.
+    pub unsafe fn field_unchecked<U>(&self, offset: usize) -> &U {
+        // SAFETY: The caller has guaranteed that the field is present.
+        unsafe { &*self.as_ptr().cast::<u8>().add(offset).cast::<U>() }
+    }
+
+    /// Resolves a length-prefixed struct from the given resolver.
+    pub fn resolve_from_resolver(
+        resolver: LengthPrefixedResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let LengthPrefixed { ptr, len } = out);
+        RelPtr::emplace(resolver.pos, ptr);
+        len.write(ArchivedUsize::from_native(fixed_usize(size_of::<T>())));
+    }
+
+    /// Serializes the fields of a length-prefixed struct.
+    pub fn serialize_fields<U, S>(
+        value: &U,
+        resolver: U::FieldsResolver,
+        serializer: &mut S,
+    ) -> Result<LengthPrefixedResolver, S::Error>
+    where
+        U: ArchiveFields<Fields = T> + ?Sized,
+        S: Fallible + Writer + ?Sized,
+    {
+        // The size of the fields is archived as the length when this struct
+        // is resolved.
+        serializer.check_len(size_of::<T>())?;
+        let pos = serializer.align_for::<T>()?;
+
+        let mut resolved = MaybeUninit::<T>::zeroed();
+        // SAFETY: `resolved.as_mut_ptr()` points to a local zeroed
+        // `MaybeUninit`, and so is properly aligned, dereferenceable, and all
+        // of its bytes are initialized.
+        let out = unsafe { Place::new_unchecked(pos, resolved.as_mut_ptr()) };
+        value.resolve_fields(resolver, out);
+        serializer.write(out.as_slice())?;
+        serializer.check_offset::<ArchivedIsize>(pos)?;
+
+        Ok(LengthPrefixedResolver { pos })
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LengthPrefixed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(fields) => fields.fmt(f),
+            None => f
+                .debug_struct("LengthPrefixed")
+                .field("len", &self.len())
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// The resolver for [`LengthPrefixed`].
+pub struct LengthPrefixedResolver {
+    pos: usize,
+}
+
+impl LengthPrefixedResolver {
+    /// Creates a new `LengthPrefixedResolver` from a position in the output
+    /// buffer where the fields of the struct are stored.
+    pub fn from_pos(pos: usize) -> Self {
+        Self { pos }
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::verify::*;
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{alloc::Layout, fmt, mem::align_of};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::{fail, ResultExt as _};
+
+    use super::LengthPrefixed;
+    use crate::validation::{ArchiveContext, ArchiveContextExt};
+
+    /// The archived fields of a length-prefixed struct, which can be checked
+    /// when only some of them are present.
+    ///
+    /// # Safety
+    ///
+    /// `MIN_LEN` must include every field which doesn't have a default, and
+    /// `check_prefix` must check every field which lies completely within the
+    /// first `len` bytes.
+    pub unsafe trait CheckPrefix<C: Fallible + ?Sized> {
+        /// The minimum number of bytes which must be present.
+        const MIN_LEN: usize;
+
+        /// Checks the fields which lie completely within the first `len` bytes
+        /// of `value`.
+        ///
+        /// # Safety
+        ///
+        /// The first `len` bytes of `value` must be located in the archive.
+        unsafe fn check_prefix(
+            value: *const Self,
+            len: usize,
+            context: &mut C,
+        ) -> Result<(), C::Error>;
+    }
+
+    /// An error resulting from a length-prefixed struct which is missing
+    /// fields that don't have defaults.
+    #[derive(Debug)]
+    pub struct MissingFieldsError {
+        len: usize,
+        min_len: usize,
+    }
+
+    impl fmt::Display for MissingFieldsError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "length-prefixed struct has {} bytes but fields without \
+                 defaults require at least {} bytes",
+                self.len, self.min_len,
+            )
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for MissingFieldsError {}
+
+    unsafe impl<T, C> Verify<C> for LengthPrefixed<T>
+    where
+        T: CheckPrefix<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            if len < T::MIN_LEN {
+                fail!(MissingFieldsError {
+                    len,
+                    min_len: T::MIN_LEN,
+                });
+            }
+
+            let ptr = self.ptr.as_ptr_wrapping();
+            let layout =
+                Layout::from_size_align(len, align_of::<T>()).into_error()?;
+            context.in_subtree_raw(ptr.cast(), layout, |context| unsafe {
+                T::check_prefix(ptr, len, context)
+            })
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Archived 128-bit integers that are only 8-byte aligned.
+
+use core::{cmp, fmt, hash, mem::size_of, slice};
+
+use rancor::Source;
+
+use crate::{
+    endian::SwapEndian,
+    place::Initialized,
+    primitive::ArchivedU64,
+    relocate::{OffsetMode, Relocate},
+    Portable,
+};
+
+// The words are stored in the same order as the halves of an archived 128-bit
+// integer, so the bytes of both are the same.
+#[cfg(not(feature = "big_endian"))]
+const LOW: usize = 0;
+#[cfg(feature = "big_endian")]
+const LOW: usize = 1;
+const HIGH: usize = 1 - LOW;
+
+macro_rules! define_align8_int {
+    ($name:ident, $prim:ty) => {
+        #[rustfmt::skip]
+        #[doc = concat!(
+            "An archived `",
+            stringify!($prim),
+            "` which is stored as two 64-bit words.",
+        )]
+        ///
+        /// This has the same size and bytes as an archived 128-bit integer, but
+        /// is at most 8-byte aligned. It is the archived type of integers
+        /// archived with [`Align8`](crate::with::Align8).
+        #[derive(Clone, Copy, Portable)]
+        #[archive(crate)]
+        #[repr(transparent)]
+        #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+        pub struct $name {
+            words: [ArchivedU64; 2],
+        }
+
+        impl $name {
+            #[doc = concat!(
+                "Creates an archived integer from a native `",
+                stringify!($prim),
+                "`.",
+            )]
+            #[inline]
+            pub fn from_native(value: $prim) -> Self {
+                let value = value as u128;
+                let mut words = [ArchivedU64::from_native(0); 2];
+                words[LOW] = ArchivedU64::from_native(value as u64);
+                words[HIGH] = ArchivedU64::from_native((value >> 64) as u64);
+                Self { words }
+            }
+
+            #[doc = concat!(
+                "Returns the value of this integer as a native `",
+                stringify!($prim),
+                "`.",
+            )]
+            #[inline]
+            pub fn to_native(self) -> $prim {
+                let low = self.words[LOW].to_native() as u128;
+                let high = self.words[HIGH].to_native() as u128;
+                ((high << 64) | low) as $prim
+            }
+        }
+
+        impl From<$prim> for $name {
+            #[inline]
+            fn from(value: $prim) -> Self {
+                Self::from_native(value)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.to_native().fmt(f)
+            }
+        }
+
+        impl fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.to_native().fmt(f)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl hash::Hash for $name {
+            #[inline]
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.to_native().hash(state);
+            }
+        }
+
+        impl Ord for $name {
+            #[inline]
+            fn cmp(&self, other: &Self) -> cmp::Ordering {
+                self.to_native().cmp(&other.to_native())
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.words == other.words
+            }
+        }
+
+        impl PartialEq<$prim> for $name {
+            #[inline]
+            fn eq(&self, other: &$prim) -> bool {
+                self.to_native() == *other
+            }
+        }
+
+        impl PartialEq<$name> for $prim {
+            #[inline]
+            fn eq(&self, other: &$name) -> bool {
+                other.eq(self)
+            }
+        }
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialOrd<$prim> for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &$prim) -> Option<cmp::Ordering> {
+                self.to_native().partial_cmp(other)
+            }
+        }
+
+        // SAFETY: This type is a transparent wrapper around an array of
+        // archived integers, which is always fully-initialized.
+        unsafe impl Initialized for $name {}
+
+        // SAFETY: This type does not contain any relative pointers.
+        unsafe impl Relocate for $name {
+            #[inline]
+            unsafe fn relocate<E: Source>(
+                _: *mut Self,
+                _: *mut u8,
+                _: OffsetMode,
+            ) -> Result<(), E> {
+                Ok(())
+            }
+        }
+
+        // SAFETY: This type represents a single 128-bit integer, so reversing
+        // all of its bytes reverses its byte order.
+        unsafe impl SwapEndian for $name {
+            #[inline]
+            unsafe fn swap_endian(this: *mut Self) {
+                // SAFETY: The caller has guaranteed that `this` is valid for
+                // reads and writes.
+                unsafe {
+                    slice::from_raw_parts_mut(
+                        this.cast::<u8>(),
+                        size_of::<Self>(),
+                    )
+                    .reverse();
+                }
+            }
+        }
+    };
+}
+
+define_align8_int!(ArchivedAlign8U128, u128);
+define_align8_int!(ArchivedAlign8I128, i128);
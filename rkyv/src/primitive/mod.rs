@@ -3,12 +3,15 @@
 
 #[macro_use]
 mod _macros;
+mod align8;
 #[cfg(not(feature = "unaligned"))]
 mod atomic;
+mod total_ord;
 
 // Aligned little-endian
 #[cfg(not(feature = "unaligned"))]
 pub use self::atomic::*;
+pub use self::{align8::*, total_ord::*};
 // Unaligned big-endian
 #[cfg(all(feature = "unaligned", feature = "big_endian"))]
 use crate::rend::unaligned::{
@@ -97,6 +100,85 @@ pub type FixedUsize = match_pointer_width!(u16, u32, u64);
 pub type ArchivedUsize =
     match_pointer_width!(ArchivedU16, ArchivedU32, ArchivedU64);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PointerWidthOverflow<T> {
+    value: T,
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for PointerWidthOverflow<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} exceeds the archived pointer width of {} bits; enable the \
+             `pointer_width_64` feature to archive larger values",
+            self.value,
+            core::mem::size_of::<FixedUsize>() * 8,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: core::fmt::Debug + core::fmt::Display> std::error::Error
+    for PointerWidthOverflow<T>
+{
+}
+
+/// Converts a `usize` to a [`FixedUsize`], returning an error if it exceeds
+/// the archived pointer width.
+///
+/// `resolve` can't return errors, so serialize implementations which can
+/// construct errors call this to check values before [`fixed_usize`] converts
+/// them again while resolving.
+#[inline]
+pub(crate) fn try_fixed_usize<E: rancor::Source>(
+    value: usize,
+) -> Result<FixedUsize, E> {
+    match FixedUsize::try_from(value) {
+        Ok(value) => Ok(value),
+        Err(_) => rancor::fail!(PointerWidthOverflow { value }),
+    }
+}
+
+/// Converts an `isize` to a [`FixedIsize`], returning an error if it exceeds
+/// the archived pointer width.
+///
+/// See [`try_fixed_usize`] for how this is used.
+#[inline]
+pub(crate) fn try_fixed_isize<E: rancor::Source>(
+    value: isize,
+) -> Result<FixedIsize, E> {
+    match FixedIsize::try_from(value) {
+        Ok(value) => Ok(value),
+        Err(_) => rancor::fail!(PointerWidthOverflow { value }),
+    }
+}
+
+/// Converts a `usize` to a [`FixedUsize`].
+///
+/// # Panics
+///
+/// - If `value` exceeds the range of a `FixedUsize`
+#[inline]
+pub(crate) fn fixed_usize(value: usize) -> FixedUsize {
+    FixedUsize::try_from(value).expect(
+        "usize value exceeds the archived pointer width; enable the \
+         `pointer_width_64` feature to archive larger values",
+    )
+}
+
+/// Converts an `isize` to a [`FixedIsize`].
+///
+/// # Panics
+///
+/// - If `value` exceeds the range of a `FixedIsize`
+#[inline]
+pub(crate) fn fixed_isize(value: isize) -> FixedIsize {
+    FixedIsize::try_from(value).expect(
+        "isize value exceeds the archived pointer width; enable the \
+         `pointer_width_64` feature to archive larger values",
+    )
+}
+
 define_multibyte_primitives! {
     ArchivedNonZeroI16:
         NonZeroI16,
@@ -194,3 +276,29 @@ pub type ArchivedNonZeroUsize = match_pointer_width!(
     ArchivedNonZeroU32,
     ArchivedNonZeroU64
 );
+
+macro_rules! define_enum_tag {
+    ($name:ident : $ty:ty) => {
+        /// Converts between the value of a multibyte archived enum tag and its
+        /// native representation.
+        ///
+        /// Enums with more than 256 variants are archived with a tag that is
+        /// stored in the byte order of the archive. This function is its own
+        /// inverse.
+        #[doc(hidden)]
+        #[inline]
+        pub const fn $name(value: $ty) -> $ty {
+            #[cfg(not(feature = "big_endian"))]
+            {
+                <$ty>::from_le(value)
+            }
+            #[cfg(feature = "big_endian")]
+            {
+                <$ty>::from_be(value)
+            }
+        }
+    };
+}
+
+define_enum_tag!(enum_tag_u16: u16);
+define_enum_tag!(enum_tag_u32: u32);
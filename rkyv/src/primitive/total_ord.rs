@@ -0,0 +1,171 @@
+//! Archived floats that are totally ordered.
+
+use core::{cmp, fmt, hash};
+
+use rancor::Source;
+
+use crate::{
+    endian::SwapEndian,
+    place::Initialized,
+    primitive::{ArchivedF32, ArchivedF64},
+    relocate::{OffsetMode, Relocate},
+    Portable,
+};
+
+macro_rules! define_total_ord_float {
+    ($name:ident, $archived:ident, $prim:ty) => {
+        #[rustfmt::skip]
+        #[doc = concat!(
+            "An archived `",
+            stringify!($prim),
+            "` which is ordered with [`",
+            stringify!($prim),
+            "::total_cmp`].",
+        )]
+        ///
+        /// Unlike archived floats, this type implements `Eq`, `Ord` and `Hash`,
+        /// so it can be used as a key in archived maps and sets. Values are
+        /// equal only if they have the same bit pattern, so `-0.0` and `0.0`
+        /// are distinct and every `NaN` is equal to itself.
+        ///
+        /// This is the archived type of floats archived with
+        /// [`TotalOrdFloat`](crate::with::TotalOrdFloat).
+        #[derive(Clone, Copy, Portable)]
+        #[archive(crate)]
+        #[repr(transparent)]
+        #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+        pub struct $name {
+            inner: $archived,
+        }
+
+        impl $name {
+            #[doc = concat!(
+                "Creates a totally-ordered float from a native `",
+                stringify!($prim),
+                "`.",
+            )]
+            #[inline]
+            pub fn from_native(value: $prim) -> Self {
+                Self {
+                    inner: <$archived>::from_native(value),
+                }
+            }
+
+            #[doc = concat!(
+                "Returns the value of this float as a native `",
+                stringify!($prim),
+                "`.",
+            )]
+            #[inline]
+            pub fn to_native(self) -> $prim {
+                self.inner.to_native()
+            }
+        }
+
+        impl From<$prim> for $name {
+            #[inline]
+            fn from(value: $prim) -> Self {
+                Self::from_native(value)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.to_native().fmt(f)
+            }
+        }
+
+        impl fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.to_native().fmt(f)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl hash::Hash for $name {
+            #[inline]
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.to_native().to_bits().hash(state);
+            }
+        }
+
+        impl Ord for $name {
+            #[inline]
+            fn cmp(&self, other: &Self) -> cmp::Ordering {
+                self.to_native().total_cmp(&other.to_native())
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == cmp::Ordering::Equal
+            }
+        }
+
+        impl PartialEq<$prim> for $name {
+            #[inline]
+            fn eq(&self, other: &$prim) -> bool {
+                self.to_native().total_cmp(other) == cmp::Ordering::Equal
+            }
+        }
+
+        impl PartialEq<$name> for $prim {
+            #[inline]
+            fn eq(&self, other: &$name) -> bool {
+                other.eq(self)
+            }
+        }
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialOrd<$prim> for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &$prim) -> Option<cmp::Ordering> {
+                Some(self.to_native().total_cmp(other))
+            }
+        }
+
+        // SAFETY: This type is a transparent wrapper around an archived float,
+        // which is always fully-initialized.
+        unsafe impl Initialized for $name {}
+
+        // SAFETY: This type does not contain any relative pointers.
+        unsafe impl Relocate for $name {
+            #[inline]
+            unsafe fn relocate<E: Source>(
+                _: *mut Self,
+                _: *mut u8,
+                _: OffsetMode,
+            ) -> Result<(), E> {
+                Ok(())
+            }
+        }
+
+        // SAFETY: The only multibyte primitive in this type is its inner float,
+        // and `swap_endian` is forwarded to it.
+        unsafe impl SwapEndian for $name {
+            #[inline]
+            unsafe fn swap_endian(this: *mut Self) {
+                // SAFETY: The caller has guaranteed that `this` is valid for
+                // reads and writes, so its inner float is too.
+                unsafe {
+                    <$archived>::swap_endian(::core::ptr::addr_of_mut!(
+                        (*this).inner
+                    ));
+                }
+            }
+        }
+    };
+}
+
+define_total_ord_float!(ArchivedTotalF32, ArchivedF32, f32);
+define_total_ord_float!(ArchivedTotalF64, ArchivedF64, f64);
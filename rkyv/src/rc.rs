@@ -9,8 +9,10 @@ use rancor::Fallible;
 
 use crate::{
     place::Initialized,
+    primitive::ArchivedIsize,
+    rel_ptr::{DefaultRepr, PointerRepr, RelPtr},
     ser::{Sharing, SharingExt, Writer, WriterExt as _},
-    ArchivePointee, ArchiveUnsized, Place, Portable, RelPtr, SerializeUnsized,
+    ArchivePointee, ArchiveUnsized, Place, Portable, SerializeUnsized,
 };
 
 /// The flavor type for [`Rc`](std::rc::Rc).
@@ -24,7 +26,8 @@ pub struct ArcFlavor;
 /// This is a thin wrapper around a [`RelPtr`] to the archived type paired with
 /// a "flavor" type. Because there may be many varieties of shared pointers and
 /// they may not be used together, the flavor helps check that memory is not
-/// being shared incorrectly during validation.
+/// being shared incorrectly during validation. The representation of the
+/// relative pointer is selected by `P`, see [`PointerRepr`] for more details.
 #[derive(Portable)]
 #[archive(crate)]
 #[repr(transparent)]
@@ -33,12 +36,16 @@ pub struct ArcFlavor;
     derive(bytecheck::CheckBytes),
     check_bytes(verify)
 )]
-pub struct ArchivedRc<T: ArchivePointee + ?Sized, F> {
-    ptr: RelPtr<T>,
+pub struct ArchivedRc<
+    T: ArchivePointee + ?Sized,
+    F,
+    P: PointerRepr = DefaultRepr,
+> {
+    ptr: RelPtr<T, P::Offset>,
     _phantom: PhantomData<F>,
 }
 
-impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
+impl<T: ArchivePointee + ?Sized, F, P: PointerRepr> ArchivedRc<T, F, P> {
     /// Gets the value of the `ArchivedRc`.
     pub fn get(&self) -> &T {
         unsafe { &*self.ptr.as_ptr() }
@@ -64,8 +71,12 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
         munge!(let ArchivedRc { ptr, .. } = out);
         RelPtr::emplace_unsized(resolver.pos, value.archived_metadata(), ptr);
     }
+}
 
+impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
     /// Serializes an archived `Rc` from a given reference.
+    ///
+    /// The resolver can be used with any pointer representation.
     pub fn serialize_from_ref<U, S>(
         value: &U,
         serializer: &mut S,
@@ -82,83 +93,130 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
         if serializer.pos() == pos {
             serializer.pad(1)?;
         }
+        serializer.check_offset::<ArchivedIsize>(pos)?;
+
+        Ok(RcResolver { pos })
+    }
+
+    /// Serializes an archived `Rc` from a given reference without sharing the
+    /// serialized value.
+    ///
+    /// The value is always serialized again, even if it has already been
+    /// serialized through another shared pointer. The resulting `ArchivedRc`
+    /// points to its own copy of the value.
+    pub fn serialize_unshared_from_ref<U, S>(
+        value: &U,
+        serializer: &mut S,
+    ) -> Result<RcResolver, S::Error>
+    where
+        U: SerializeUnsized<S> + ?Sized,
+        S: Fallible + Writer + ?Sized,
+    {
+        let pos = value.serialize_unsized(serializer)?;
+
+        // See `serialize_from_ref`: the position of the copy must be unique
+        // even if serializing it did not write any data.
+        if serializer.pos() == pos {
+            serializer.pad(1)?;
+        }
+        serializer.check_offset::<ArchivedIsize>(pos)?;
 
         Ok(RcResolver { pos })
     }
 }
 
-impl<T: ArchivePointee + ?Sized, F> AsRef<T> for ArchivedRc<T, F> {
+impl<T: ArchivePointee + ?Sized, F, P: PointerRepr> AsRef<T>
+    for ArchivedRc<T, F, P>
+{
     fn as_ref(&self) -> &T {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + ?Sized, F> Borrow<T> for ArchivedRc<T, F> {
+impl<T: ArchivePointee + ?Sized, F, P: PointerRepr> Borrow<T>
+    for ArchivedRc<T, F, P>
+{
     fn borrow(&self) -> &T {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + fmt::Debug + ?Sized, F> fmt::Debug
-    for ArchivedRc<T, F>
+impl<T: ArchivePointee + fmt::Debug + ?Sized, F, P: PointerRepr> fmt::Debug
+    for ArchivedRc<T, F, P>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.get().fmt(f)
     }
 }
 
-impl<T: ArchivePointee + ?Sized, F> Deref for ArchivedRc<T, F> {
+impl<T: ArchivePointee + ?Sized, F, P: PointerRepr> Deref
+    for ArchivedRc<T, F, P>
+{
     type Target = T;
     fn deref(&self) -> &Self::Target {
         self.get()
     }
 }
 
-impl<T: ArchivePointee + fmt::Display + ?Sized, F> fmt::Display
-    for ArchivedRc<T, F>
+impl<T: ArchivePointee + fmt::Display + ?Sized, F, P: PointerRepr> fmt::Display
+    for ArchivedRc<T, F, P>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.get().fmt(f)
     }
 }
 
-impl<T: ArchivePointee + Eq + ?Sized, F> Eq for ArchivedRc<T, F> {}
+impl<T: ArchivePointee + Eq + ?Sized, F, P: PointerRepr> Eq
+    for ArchivedRc<T, F, P>
+{
+}
 
-impl<T: ArchivePointee + hash::Hash + ?Sized, F> hash::Hash
-    for ArchivedRc<T, F>
+impl<T: ArchivePointee + hash::Hash + ?Sized, F, P: PointerRepr> hash::Hash
+    for ArchivedRc<T, F, P>
 {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.get().hash(state)
     }
 }
 
-impl<T: ArchivePointee + Ord + ?Sized, F> Ord for ArchivedRc<T, F> {
+impl<T: ArchivePointee + Ord + ?Sized, F, P: PointerRepr> Ord
+    for ArchivedRc<T, F, P>
+{
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.get().cmp(other.get())
     }
 }
 
-impl<T, TF, U, UF> PartialEq<ArchivedRc<U, UF>> for ArchivedRc<T, TF>
+impl<T, TF, TP, U, UF, UP> PartialEq<ArchivedRc<U, UF, UP>>
+    for ArchivedRc<T, TF, TP>
 where
     T: ArchivePointee + PartialEq<U> + ?Sized,
+    TP: PointerRepr,
     U: ArchivePointee + ?Sized,
+    UP: PointerRepr,
 {
-    fn eq(&self, other: &ArchivedRc<U, UF>) -> bool {
+    fn eq(&self, other: &ArchivedRc<U, UF, UP>) -> bool {
         self.get().eq(other.get())
     }
 }
 
-impl<T, TF, U, UF> PartialOrd<ArchivedRc<U, UF>> for ArchivedRc<T, TF>
+impl<T, TF, TP, U, UF, UP> PartialOrd<ArchivedRc<U, UF, UP>>
+    for ArchivedRc<T, TF, TP>
 where
     T: ArchivePointee + PartialOrd<U> + ?Sized,
+    TP: PointerRepr,
     U: ArchivePointee + ?Sized,
+    UP: PointerRepr,
 {
-    fn partial_cmp(&self, other: &ArchivedRc<U, UF>) -> Option<cmp::Ordering> {
+    fn partial_cmp(
+        &self,
+        other: &ArchivedRc<U, UF, UP>,
+    ) -> Option<cmp::Ordering> {
         self.get().partial_cmp(other.get())
     }
 }
 
-impl<T, F> fmt::Pointer for ArchivedRc<T, F> {
+impl<T, F, P: PointerRepr> fmt::Pointer for ArchivedRc<T, F, P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Pointer::fmt(&self.ptr.base(), f)
     }
@@ -176,18 +234,22 @@ pub struct RcResolver {
 #[archive(crate)]
 #[repr(u8)]
 #[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
-pub enum ArchivedRcWeak<T: ArchivePointee + ?Sized, F> {
+pub enum ArchivedRcWeak<
+    T: ArchivePointee + ?Sized,
+    F,
+    P: PointerRepr = DefaultRepr,
+> {
     /// A null weak pointer
     None,
     /// A weak pointer to some shared pointer
-    Some(ArchivedRc<T, F>),
+    Some(ArchivedRc<T, F, P>),
 }
 
-impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
+impl<T: ArchivePointee + ?Sized, F, P: PointerRepr> ArchivedRcWeak<T, F, P> {
     /// Attempts to upgrade the weak pointer to an `ArchivedArc`.
     ///
     /// Returns `None` if a null weak pointer was serialized.
-    pub fn upgrade(&self) -> Option<&ArchivedRc<T, F>> {
+    pub fn upgrade(&self) -> Option<&ArchivedRc<T, F, P>> {
         match self {
             ArchivedRcWeak::None => None,
             ArchivedRcWeak::Some(r) => Some(r),
@@ -197,7 +259,7 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
     /// Attempts to upgrade a pinned mutable weak pointer.
     pub fn upgrade_pin_mut(
         self: Pin<&mut Self>,
-    ) -> Option<Pin<&mut ArchivedRc<T, F>>> {
+    ) -> Option<Pin<&mut ArchivedRc<T, F, P>>> {
         unsafe {
             match self.get_unchecked_mut() {
                 ArchivedRcWeak::None => None,
@@ -222,7 +284,7 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
             }
             RcWeakResolver::Some(resolver) => {
                 let out = unsafe {
-                    out.cast_unchecked::<ArchivedRcWeakVariantSome<T, F>>()
+                    out.cast_unchecked::<ArchivedRcWeakVariantSome<T, F, P>>()
                 };
                 munge!(let ArchivedRcWeakVariantSome(tag, rc) = out);
                 tag.write(ArchivedRcWeakTag::Some);
@@ -231,8 +293,12 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
             }
         }
     }
+}
 
+impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
     /// Serializes an archived `Weak` from a given optional reference.
+    ///
+    /// The resolver can be used with any pointer representation.
     pub fn serialize_from_ref<U, S>(
         value: Option<&U>,
         serializer: &mut S,
@@ -250,8 +316,8 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRcWeak<T, F> {
     }
 }
 
-impl<T: ArchivePointee + fmt::Debug + ?Sized, F> fmt::Debug
-    for ArchivedRcWeak<T, F>
+impl<T: ArchivePointee + fmt::Debug + ?Sized, F, P: PointerRepr> fmt::Debug
+    for ArchivedRcWeak<T, F, P>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "(Weak)")
@@ -280,9 +346,9 @@ unsafe impl Initialized for ArchivedRcWeakTag {}
 struct ArchivedRcWeakVariantNone(ArchivedRcWeakTag);
 
 #[repr(C)]
-struct ArchivedRcWeakVariantSome<T: ArchivePointee + ?Sized, F>(
+struct ArchivedRcWeakVariantSome<T: ArchivePointee + ?Sized, F, P: PointerRepr>(
     ArchivedRcWeakTag,
-    ArchivedRc<T, F>,
+    ArchivedRc<T, F, P>,
 );
 
 #[cfg(feature = "bytecheck")]
@@ -296,21 +362,23 @@ mod verify {
 
     use super::ArchivedRc;
     use crate::{
+        rel_ptr::PointerRepr,
         validation::{ArchiveContext, ArchiveContextExt, SharedContext},
         ArchivePointee, LayoutRaw,
     };
 
-    unsafe impl<T, F, C> Verify<C> for ArchivedRc<T, F>
+    unsafe impl<T, F, P, C> Verify<C> for ArchivedRc<T, F, P>
     where
         T: ArchivePointee + CheckBytes<C> + LayoutRaw + ?Sized + 'static,
         T::ArchivedMetadata: CheckBytes<C>,
         F: 'static,
+        P: PointerRepr + 'static,
         C: Fallible + ArchiveContext + SharedContext + ?Sized,
         C::Error: Source,
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr = self.ptr.as_ptr_wrapping();
-            let type_id = TypeId::of::<ArchivedRc<T, F>>();
+            let type_id = TypeId::of::<ArchivedRc<T, F, P>>();
 
             let is_new = context
                 .register_shared_ptr(ptr as *const u8 as usize, type_id)?;
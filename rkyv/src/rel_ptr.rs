@@ -3,17 +3,29 @@
 use core::{
     fmt,
     marker::{PhantomData, PhantomPinned},
+    mem::size_of,
     pin::Pin,
+    ptr,
 };
 
 use munge::munge;
-use rancor::{fail, Panic, ResultExt as _, Source};
+use rancor::{fail, Failure, Panic, ResultExt as _, Source};
 
 use crate::{
+    endian::SwapEndian,
     place::Initialized,
     primitive::{
-        ArchivedI16, ArchivedI32, ArchivedI64, ArchivedU16, ArchivedU32,
-        ArchivedU64,
+        ArchivedI16, ArchivedI32, ArchivedI64, ArchivedIsize, ArchivedU16,
+        ArchivedU32, ArchivedU64, ArchivedUsize,
+    },
+    relocate::{OffsetMode, Relocate},
+    rend::{
+        i16_be, i16_le, i32_be, i32_le, i64_be, i64_le, u16_be, u16_le, u32_be,
+        u32_le, u64_be, u64_le,
+        unaligned::{
+            i16_ube, i16_ule, i32_ube, i32_ule, i64_ube, i64_ule, u16_ube,
+            u16_ule, u32_ube, u32_ule, u64_ube, u64_ule,
+        },
     },
     ArchivePointee, Place, Portable,
 };
@@ -30,6 +42,50 @@ impl fmt::Display for IsizeOverflow {
 #[cfg(feature = "std")]
 impl std::error::Error for IsizeOverflow {}
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct OffsetOverflow {
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) offset_size: usize,
+}
+
+impl fmt::Display for OffsetOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the relative pointer offset from position {} to position {} does \
+             not fit in a {}-bit offset; the archive may be too large for the \
+             configured pointer width",
+            self.from,
+            self.to,
+            self.offset_size * 8,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OffsetOverflow {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct AbsoluteOffsetCollision {
+    pos: usize,
+}
+
+impl fmt::Display for AbsoluteOffsetCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the relative pointer at position {} points to the byte \
+             immediately after it, which can't be represented with a relative \
+             offset because an offset of 1 marks invalid relative pointers",
+            self.pos,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AbsoluteOffsetCollision {}
+
 /// A offset that can be used with [`RawRelPtr`].
 pub trait Offset: Copy + Initialized {
     /// Creates a new offset between a `from` position and a `to` position.
@@ -86,17 +142,115 @@ macro_rules! impl_offset_multi_byte {
     };
 }
 
-impl_offset_multi_byte!(i16, ArchivedI16);
+macro_rules! impl_offset_multi_bytes {
+    ($ty:ty: $($archived:ty),* $(,)?) => {
+        $(
+            impl_offset_multi_byte!($ty, $archived);
+        )*
+    };
+}
+
+impl_offset_multi_bytes!(i16: i16_le, i16_be, i16_ule, i16_ube);
 #[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
-impl_offset_multi_byte!(i32, ArchivedI32);
+impl_offset_multi_bytes!(i32: i32_le, i32_be, i32_ule, i32_ube);
 #[cfg(target_pointer_width = "64")]
-impl_offset_multi_byte!(i64, ArchivedI64);
+impl_offset_multi_bytes!(i64: i64_le, i64_be, i64_ule, i64_ube);
 
-impl_offset_multi_byte!(u16, ArchivedU16);
+impl_offset_multi_bytes!(u16: u16_le, u16_be, u16_ule, u16_ube);
 #[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
-impl_offset_multi_byte!(u32, ArchivedU32);
+impl_offset_multi_bytes!(u32: u32_le, u32_be, u32_ule, u32_ube);
 #[cfg(target_pointer_width = "64")]
-impl_offset_multi_byte!(u64, ArchivedU64);
+impl_offset_multi_bytes!(u64: u64_le, u64_be, u64_ule, u64_ube);
+
+/// A representation for relative pointers and the lengths stored next to them.
+///
+/// [`ArchivedBox`](crate::boxed::ArchivedBox),
+/// [`ArchivedVec`](crate::vec::ArchivedVec),
+/// [`ArchivedRc`](crate::rc::ArchivedRc),
+/// [`ArchivedRcWeak`](crate::rc::ArchivedRcWeak), and
+/// [`ArchivedOptionBox`](crate::niche::option_box::ArchivedOptionBox) take a
+/// pointer representation as a type parameter. It defaults to [`DefaultRepr`],
+/// which uses the offset and length types selected by the crate features. Other
+/// representations can be used to produce and consume these types with a
+/// different offset size, length size, or endianness in the same binary.
+///
+/// The length of an `ArchivedVec` uses the length type of its representation.
+/// The metadata of unsized types, like the length of an `ArchivedBox<[T]>`, is
+/// archived by [`ArchivePointee`] and always uses
+/// [`ArchivedUsize`](crate::primitive::ArchivedUsize). Strings, hash maps,
+/// B-trees, and the other archived collections don't take a representation and
+/// always use the types selected by the crate features, including when they
+/// are nested inside a type with a different representation.
+///
+/// Serializers check offsets and lengths against the types selected by the
+/// crate features. Resolving a value with a narrower representation panics if
+/// an offset or length doesn't fit in it.
+pub trait PointerRepr {
+    /// The offset type of relative pointers with this representation.
+    type Offset: Offset;
+    /// The type of the lengths stored next to relative pointers with this
+    /// representation.
+    ///
+    /// Lengths are never negative, so unsigned types can be used to hold them.
+    type Len: Offset;
+}
+
+/// The pointer representation selected by the crate features.
+///
+/// This uses an archived [`FixedIsize`](crate::primitive::FixedIsize) to hold
+/// offsets and an archived [`FixedUsize`](crate::primitive::FixedUsize) to
+/// hold lengths.
+#[derive(Debug)]
+pub struct DefaultRepr;
+
+impl PointerRepr for DefaultRepr {
+    type Offset = ArchivedIsize;
+    type Len = ArchivedUsize;
+}
+
+/// A pointer representation which uses the offset type `O` and the length type
+/// `L`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     rel_ptr::Repr,
+///     rend::{i16_le, i64_be, u16_le, u64_be},
+///     vec::ArchivedVec,
+/// };
+///
+/// // A vector with a big-endian 64-bit offset and length.
+/// type ArchivedVecBe64<T> = ArchivedVec<T, Repr<i64_be, u64_be>>;
+/// // A vector with a little-endian 16-bit offset and length.
+/// type ArchivedVecLe16<T> = ArchivedVec<T, Repr<i16_le, u16_le>>;
+/// ```
+pub struct Repr<O, L = ArchivedUsize> {
+    _phantom: PhantomData<(O, L)>,
+}
+
+impl<O: Offset, L: Offset> PointerRepr for Repr<O, L> {
+    type Offset = O;
+    type Len = L;
+}
+
+impl<O, L> fmt::Debug for Repr<O, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Repr")
+    }
+}
+
+/// Converts a length to the length type of a pointer representation.
+///
+/// # Panics
+///
+/// - If `len` exceeds the range of `P::Len`
+pub(crate) fn repr_len<P: PointerRepr>(len: usize) -> P::Len {
+    isize::try_from(len)
+        .into_error::<Panic>()
+        .and_then(<P::Len as Offset>::from_isize::<Panic>)
+        .always_ok()
+}
 
 /// An untyped pointer which resolves relative to its position in memory.
 ///
@@ -175,11 +329,24 @@ impl<O: Offset> RawRelPtr<O> {
 
     /// Attempts to create a new `RawRelPtr` in-place between the given `from`
     /// and `to` positions.
+    ///
+    /// Returns an error containing the `from` and `to` positions if the offset
+    /// between them exceeds the offset storage.
     pub fn try_emplace<E: Source>(
         to: usize,
         out: Place<Self>,
     ) -> Result<(), E> {
-        let offset = O::from_isize(signed_offset(out.pos(), to)?)?;
+        let from = out.pos();
+        let offset = match signed_offset::<Failure>(from, to)
+            .and_then(O::from_isize::<Failure>)
+        {
+            Ok(offset) => offset,
+            Err(_) => fail!(OffsetOverflow {
+                from,
+                to,
+                offset_size: size_of::<O>(),
+            }),
+        };
         munge!(let Self { offset: out_offset, _phantom: _ } = out);
         out_offset.write(offset);
         Ok(())
@@ -192,6 +359,14 @@ impl<O: Offset> RawRelPtr<O> {
     ///
     /// - If the offset between `out` and `to` does not fit in an `isize`
     /// - If the offset between `out` and `to` exceeds the offset storage
+    ///
+    /// `resolve` can't return errors, so serialize implementations check the
+    /// offset to their targets with
+    /// [`WriterExt::check_offset`](crate::ser::WriterExt::check_offset) first.
+    /// The resolvers provided by rkyv only panic if the archive grows past the
+    /// limit between serializing a target and writing the pointer to it. Use
+    /// [`try_emplace`](Self::try_emplace) to get an error with the offending
+    /// positions instead.
     pub fn emplace(to: usize, out: Place<Self>) {
         Self::try_emplace::<Panic>(to, out).always_ok()
     }
@@ -271,6 +446,129 @@ impl<O: Offset> fmt::Pointer for RawRelPtr<O> {
     }
 }
 
+// SAFETY: The offset is the only multibyte primitive in a `RawRelPtr`. Its
+// target is untyped, so it is not converted.
+unsafe impl<O: SwapEndian> SwapEndian for RawRelPtr<O> {
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes.
+        unsafe {
+            O::swap_endian(ptr::addr_of_mut!((*this).offset));
+        }
+    }
+}
+
+impl<O: Offset + SwapEndian> RawRelPtr<O> {
+    /// Converts the offset of the relative pointer at `this` from the opposite
+    /// endianness and returns a pointer to its target, or `None` if the
+    /// relative pointer is invalid.
+    ///
+    /// # Safety
+    ///
+    /// `this` must meet the requirements of [`SwapEndian::swap_endian`].
+    unsafe fn swap_endian_target(this: *mut Self) -> Option<*mut u8> {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes, and that its target is located in the same allocation.
+        unsafe {
+            Self::swap_endian(this);
+            let raw_ptr = &*this;
+            if raw_ptr.is_invalid() {
+                None
+            } else {
+                Some(this.cast::<u8>().offset(raw_ptr.offset()))
+            }
+        }
+    }
+}
+
+// SAFETY: The offset is the only relative pointer in a `RawRelPtr`. Its target
+// is untyped, so it is not converted.
+unsafe impl<O: Offset + Portable> Relocate for RawRelPtr<O> {
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` and `base` meet the
+        // requirements of `relocate`.
+        unsafe { Self::relocate_target::<E>(this, base, mode).map(|_| ()) }
+    }
+}
+
+/// The offset that invalid relative pointers hold in absolute mode.
+///
+/// Valid absolute offsets are positions in the archive, so they are never
+/// negative.
+const INVALID_ABSOLUTE_OFFSET: isize = -1;
+
+impl<O: Offset> RawRelPtr<O> {
+    /// Converts the offset of the relative pointer at `this` to the given
+    /// mode and returns a pointer to its target, or `None` if the relative
+    /// pointer is invalid or its target must not be converted.
+    ///
+    /// Invalid relative pointers hold an offset of `1` in relative mode and
+    /// `-1` in absolute mode. Converting a valid pointer to the byte right
+    /// after it from absolute mode returns an error, since its relative offset
+    /// would mark it as invalid.
+    ///
+    /// # Safety
+    ///
+    /// `this` and `base` must meet the requirements of
+    /// [`Relocate::relocate`].
+    unsafe fn relocate_target<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<Option<*mut u8>, E> {
+        let pos = (this as usize).wrapping_sub(base as usize);
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes.
+        let offset = unsafe { (*this).offset() };
+        let (mode, follow) = match mode {
+            OffsetMode::AbsoluteShallow => (OffsetMode::Absolute, false),
+            mode => (mode, true),
+        };
+        let (relative, new_offset) = match mode {
+            OffsetMode::Absolute if offset == 1 => {
+                (None, INVALID_ABSOLUTE_OFFSET)
+            }
+            OffsetMode::Absolute => (Some(offset), pos as isize + offset),
+            OffsetMode::Relative if offset == INVALID_ABSOLUTE_OFFSET => {
+                (None, 1)
+            }
+            OffsetMode::Relative => {
+                let relative = offset - pos as isize;
+                if relative == 1 {
+                    fail!(AbsoluteOffsetCollision { pos });
+                }
+                (Some(relative), relative)
+            }
+        };
+        let new_offset = match O::from_isize::<Failure>(new_offset) {
+            Ok(new_offset) => new_offset,
+            Err(_) => fail!(OffsetOverflow {
+                from: pos,
+                to: pos.wrapping_add_signed(relative.unwrap_or(1)),
+                offset_size: size_of::<O>(),
+            }),
+        };
+        // SAFETY: The caller has guaranteed that `this` is valid for writes,
+        // and that its target is located in the same archive unless it's not
+        // followed.
+        unsafe {
+            ptr::addr_of_mut!((*this).offset).write(new_offset);
+            match relative {
+                Some(relative) if follow => {
+                    Ok(Some(this.cast::<u8>().offset(relative)))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+}
+
 /// A raw relative pointer that uses an archived `i8` as the underlying offset.
 pub type RawRelPtrI8 = RawRelPtr<i8>;
 /// A raw relative pointer that uses an archived `i16` as the underlying offset.
@@ -334,6 +632,9 @@ impl<T, O: Offset> RelPtr<T, O> {
     ///
     /// - If the offset between `from` and `to` does not fit in an `isize`
     /// - If the offset between `from` and `to` exceeds the offset storage
+    ///
+    /// See [`RawRelPtr::emplace`] for when this can happen. Use
+    /// [`try_emplace`](Self::try_emplace) to get an error instead.
     pub fn emplace(to: usize, out: Place<Self>) {
         Self::try_emplace::<Panic>(to, out).always_ok()
     }
@@ -381,6 +682,10 @@ impl<T: ArchivePointee + ?Sized, O: Offset> RelPtr<T, O> {
     ///
     /// - If the offset between `from` and `to` does not fit in an `isize`
     /// - If the offset between `from` and `to` exceeds the offset storage
+    ///
+    /// See [`RawRelPtr::emplace`] for when this can happen. Use
+    /// [`try_emplace_unsized`](Self::try_emplace_unsized) to get an error
+    /// instead.
     pub fn emplace_unsized(
         to: usize,
         metadata: T::ArchivedMetadata,
@@ -489,3 +794,129 @@ impl<T: ArchivePointee + ?Sized, O: Offset> fmt::Pointer for RelPtr<T, O> {
         fmt::Pointer::fmt(&self.as_ptr_wrapping(), f)
     }
 }
+
+// SAFETY: The offset and metadata are converted, followed by the target of the
+// relative pointer.
+unsafe impl<T, O> SwapEndian for RelPtr<T, O>
+where
+    T: ArchivePointee + SwapEndian + ?Sized,
+    T::ArchivedMetadata: SwapEndian,
+    O: Offset + SwapEndian,
+{
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes, and that its target is a valid `T` written with the opposite
+        // endianness.
+        unsafe {
+            let metadata = ptr::addr_of_mut!((*this).metadata);
+            T::ArchivedMetadata::swap_endian(metadata);
+            let target = RawRelPtr::swap_endian_target(ptr::addr_of_mut!(
+                (*this).raw_ptr
+            ));
+            if let Some(target) = target {
+                T::swap_endian(ptr_meta::from_raw_parts_mut(
+                    target.cast(),
+                    T::pointer_metadata(&*metadata),
+                ));
+            }
+        }
+    }
+}
+
+// SAFETY: The offset is converted, followed by the target of the relative
+// pointer. The metadata does not contain any relative pointers.
+unsafe impl<T, O> Relocate for RelPtr<T, O>
+where
+    T: ArchivePointee + Relocate + ?Sized,
+    O: Offset + Portable,
+{
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` and `base` meet the
+        // requirements of `relocate`, and that its target is a valid `T` with
+        // offsets in the opposite mode.
+        unsafe {
+            let target = RawRelPtr::relocate_target::<E>(
+                ptr::addr_of_mut!((*this).raw_ptr),
+                base,
+                mode,
+            )?;
+            if let Some(target) = target {
+                T::relocate(
+                    ptr_meta::from_raw_parts_mut(
+                        target.cast(),
+                        T::pointer_metadata(&(*this).metadata),
+                    ),
+                    base,
+                    mode,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Relocate, O: Offset> RelPtr<T, O> {
+    /// Converts the relative pointer at `this` and the `len` consecutive
+    /// values it points to to the given offset mode.
+    ///
+    /// # Safety
+    ///
+    /// `this` and `base` must meet the requirements of
+    /// [`Relocate::relocate`] for a relative pointer to a `[T]` with a length
+    /// of `len`.
+    pub(crate) unsafe fn relocate_slice<E: Source>(
+        this: *mut Self,
+        len: usize,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` and `base` meet the
+        // requirements of `relocate`, and that it points to `len` valid `T`s
+        // with offsets in the opposite mode.
+        unsafe {
+            let target = RawRelPtr::relocate_target::<E>(
+                ptr::addr_of_mut!((*this).raw_ptr),
+                base,
+                mode,
+            )?;
+            if let Some(target) = target {
+                <[T]>::relocate(
+                    ptr_meta::from_raw_parts_mut(target.cast(), len),
+                    base,
+                    mode,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: SwapEndian, O: Offset + SwapEndian> RelPtr<T, O> {
+    /// Converts the relative pointer at `this` and the `len` consecutive
+    /// values it points to from the opposite endianness.
+    ///
+    /// # Safety
+    ///
+    /// `this` must meet the requirements of [`SwapEndian::swap_endian`] for a
+    /// relative pointer to a `[T]` with a length of `len`.
+    pub(crate) unsafe fn swap_endian_slice(this: *mut Self, len: usize) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes, and that it points to `len` valid `T`s written with the
+        // opposite endianness.
+        unsafe {
+            let target = RawRelPtr::swap_endian_target(ptr::addr_of_mut!(
+                (*this).raw_ptr
+            ));
+            if let Some(target) = target {
+                <[T]>::swap_endian(ptr_meta::from_raw_parts_mut(
+                    target.cast(),
+                    len,
+                ));
+            }
+        }
+    }
+}
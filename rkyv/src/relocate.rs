@@ -0,0 +1,339 @@
+//! Conversion between relative and absolute pointer offsets.
+//!
+//! Archives store relative pointers as offsets from the pointer to its target,
+//! so that they stay valid wherever the archive is loaded. Some tools find it
+//! easier to work with archives where every pointer holds the position of its
+//! target from the start of the archive instead. [`to_absolute_offsets`]
+//! converts an archive to this absolute mode in place, and
+//! [`to_relative_offsets`] converts it back so that it can be accessed
+//! normally.
+//!
+//! Archives are always serialized with relative offsets. Absolute mode is only
+//! reached by converting a serialized archive, and archives in absolute mode
+//! can't be accessed or validated until they are converted back.
+//!
+//! Invalid relative pointers have an offset of `1` in relative mode and hold
+//! `-1` in absolute mode, since valid absolute offsets are never negative. A
+//! relative offset of `1` can't point to a valid target, so converting an
+//! archive with a pointer to the byte immediately after it back to relative
+//! offsets returns an error.
+//!
+//! Out-of-line [`ArchivedString`](crate::string::ArchivedString)s store the
+//! bitwise complement of their absolute offset, since the sign bit of their
+//! offset distinguishes them from inline strings.
+
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    mem::size_of,
+    num::{NonZeroI8, NonZeroU8},
+    sync::atomic::{AtomicBool, AtomicI8, AtomicU8},
+};
+
+use rancor::Source;
+
+use crate::{rend::*, Portable};
+
+/// The mode of the offsets of an archive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OffsetMode {
+    /// Offsets are relative to the position of the pointer which holds them.
+    Relative,
+    /// Offsets are relative to the start of the archive.
+    Absolute,
+    /// Offsets are relative to the start of the archive, but only the relative
+    /// pointers that make up the value itself are converted.
+    ///
+    /// The values reachable through its relative pointers are neither read nor
+    /// converted, so this can convert a copy of a value which is outside of
+    /// its archive. The value must have relative offsets.
+    AbsoluteShallow,
+}
+
+/// An archived type whose pointer offsets can be converted in place.
+///
+/// Implementations are provided for primitives, arrays, slices, relative
+/// pointers and the most common archived containers. It can be derived for
+/// archived types with `#[archive(relocate)]`.
+///
+/// # Safety
+///
+/// `relocate` must convert the offset of every relative pointer that makes up
+/// the value, including any values reachable through its relative pointers, to
+/// the given mode. In [`AbsoluteShallow`](OffsetMode::AbsoluteShallow) mode,
+/// it must not read or convert the values reachable through its relative
+/// pointers. It must not make any other modifications.
+pub unsafe trait Relocate: Portable {
+    /// Converts the offsets of the value at `this` to the given mode.
+    ///
+    /// If an offset can't be represented in the new mode, an error is returned
+    /// and the value is left partially converted.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must be properly aligned and valid for reads and writes.
+    /// - `base` must point to the start of the archive containing `this`. In
+    ///   [`AbsoluteShallow`](OffsetMode::AbsoluteShallow) mode, `base` is only
+    ///   used to calculate the position of `this` and may be dangling.
+    /// - The value at `this` must be a valid archived value with offsets in the
+    ///   opposite mode.
+    /// - Every value reachable through the relative pointers of the value must
+    ///   be located in the same archive as it, and must not be reachable
+    ///   through any other relative pointer. In particular, archives containing
+    ///   shared pointers can't be converted. This does not apply in
+    ///   [`AbsoluteShallow`](OffsetMode::AbsoluteShallow) mode.
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E>;
+}
+
+/// Converts the archive in the given bytes to absolute offsets in place.
+///
+/// This calculates the position of the root object using the length of the
+/// byte slice, just like [`access_unchecked`](crate::access_unchecked).
+///
+/// # Safety
+///
+/// - The byte slice must represent an archived `T` with relative offsets.
+/// - The root of the object must be stored at the end of the slice (this is the
+///   default behavior).
+/// - The archive must meet the requirements of [`Relocate::relocate`].
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     relocate::{to_absolute_offsets, to_relative_offsets},
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(relocate)]
+/// struct Example {
+///     name: String,
+///     values: Vec<u32>,
+/// }
+///
+/// let value = Example {
+///     name: "a string which is too long to be inlined".to_string(),
+///     values: vec![1, 2, 3],
+/// };
+/// let mut bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+///
+/// unsafe {
+///     to_absolute_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+///     // The archive can now be processed by tools which expect absolute
+///     // offsets.
+///     to_relative_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+/// }
+///
+/// let archived = unsafe { rkyv::access_unchecked::<ArchivedExample>(&bytes) };
+/// assert_eq!(archived.values, [1, 2, 3]);
+/// ```
+pub unsafe fn to_absolute_offsets<T: Relocate, E: Source>(
+    bytes: &mut [u8],
+) -> Result<(), E> {
+    // SAFETY: The caller has upheld the safety requirements of
+    // `to_absolute_offsets`, which are the same as those of `relocate_root`.
+    unsafe { relocate_root::<T, E>(bytes, OffsetMode::Absolute) }
+}
+
+/// Converts the archive in the given bytes to relative offsets in place.
+///
+/// After conversion, the bytes can be accessed or validated normally.
+///
+/// # Safety
+///
+/// - The byte slice must represent an archived `T` which was converted with
+///   [`to_absolute_offsets`].
+/// - The root of the object must be stored at the end of the slice (this is the
+///   default behavior).
+/// - The archive must meet the requirements of [`Relocate::relocate`].
+pub unsafe fn to_relative_offsets<T: Relocate, E: Source>(
+    bytes: &mut [u8],
+) -> Result<(), E> {
+    // SAFETY: The caller has upheld the safety requirements of
+    // `to_relative_offsets`, which are the same as those of `relocate_root`.
+    unsafe { relocate_root::<T, E>(bytes, OffsetMode::Relative) }
+}
+
+/// # Safety
+///
+/// The byte slice must contain an archived `T` at its root position with
+/// offsets in the opposite mode, and it must meet the requirements of
+/// [`Relocate::relocate`].
+unsafe fn relocate_root<T: Relocate, E: Source>(
+    bytes: &mut [u8],
+    mode: OffsetMode,
+) -> Result<(), E> {
+    let pos = bytes.len() - size_of::<T>();
+    let base = bytes.as_mut_ptr();
+    // SAFETY: The caller has guaranteed that a `T` with offsets in the opposite
+    // mode is located at the root position in the byte slice.
+    unsafe { T::relocate(base.add(pos).cast(), base, mode) }
+}
+
+macro_rules! impl_relocate_noop {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: The type does not contain any relative pointers.
+            unsafe impl Relocate for $ty {
+                #[inline]
+                unsafe fn relocate<E: Source>(
+                    _: *mut Self,
+                    _: *mut u8,
+                    _: OffsetMode,
+                ) -> Result<(), E> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_relocate_noop!(
+    (),
+    bool,
+    i8,
+    u8,
+    NonZeroI8,
+    NonZeroU8,
+    AtomicBool,
+    AtomicI8,
+    AtomicU8,
+    PhantomPinned,
+    str,
+    i16_le,
+    i32_le,
+    i64_le,
+    i128_le,
+    u16_le,
+    u32_le,
+    u64_le,
+    u128_le,
+    f32_le,
+    f64_le,
+    char_le,
+    NonZeroI16_le,
+    NonZeroI32_le,
+    NonZeroI64_le,
+    NonZeroI128_le,
+    NonZeroU16_le,
+    NonZeroU32_le,
+    NonZeroU64_le,
+    NonZeroU128_le,
+    i16_be,
+    i32_be,
+    i64_be,
+    i128_be,
+    u16_be,
+    u32_be,
+    u64_be,
+    u128_be,
+    f32_be,
+    f64_be,
+    char_be,
+    NonZeroI16_be,
+    NonZeroI32_be,
+    NonZeroI64_be,
+    NonZeroI128_be,
+    NonZeroU16_be,
+    NonZeroU32_be,
+    NonZeroU64_be,
+    NonZeroU128_be,
+    unaligned::i16_ule,
+    unaligned::i32_ule,
+    unaligned::i64_ule,
+    unaligned::i128_ule,
+    unaligned::u16_ule,
+    unaligned::u32_ule,
+    unaligned::u64_ule,
+    unaligned::u128_ule,
+    unaligned::f32_ule,
+    unaligned::f64_ule,
+    unaligned::char_ule,
+    unaligned::NonZeroI16_ule,
+    unaligned::NonZeroI32_ule,
+    unaligned::NonZeroI64_ule,
+    unaligned::NonZeroI128_ule,
+    unaligned::NonZeroU16_ule,
+    unaligned::NonZeroU32_ule,
+    unaligned::NonZeroU64_ule,
+    unaligned::NonZeroU128_ule,
+    unaligned::i16_ube,
+    unaligned::i32_ube,
+    unaligned::i64_ube,
+    unaligned::i128_ube,
+    unaligned::u16_ube,
+    unaligned::u32_ube,
+    unaligned::u64_ube,
+    unaligned::u128_ube,
+    unaligned::f32_ube,
+    unaligned::f64_ube,
+    unaligned::char_ube,
+    unaligned::NonZeroI16_ube,
+    unaligned::NonZeroI32_ube,
+    unaligned::NonZeroI64_ube,
+    unaligned::NonZeroI128_ube,
+    unaligned::NonZeroU16_ube,
+    unaligned::NonZeroU32_ube,
+    unaligned::NonZeroU64_ube,
+    unaligned::NonZeroU128_ube,
+);
+
+#[cfg(target_has_atomic = "16")]
+impl_relocate_noop!(AtomicI16_le, AtomicI16_be, AtomicU16_le, AtomicU16_be);
+#[cfg(target_has_atomic = "32")]
+impl_relocate_noop!(AtomicI32_le, AtomicI32_be, AtomicU32_le, AtomicU32_be);
+#[cfg(target_has_atomic = "64")]
+impl_relocate_noop!(AtomicI64_le, AtomicI64_be, AtomicU64_le, AtomicU64_be);
+
+// SAFETY: `PhantomData` does not contain any relative pointers.
+unsafe impl<T: ?Sized> Relocate for PhantomData<T> {
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        _: *mut Self,
+        _: *mut u8,
+        _: OffsetMode,
+    ) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+// SAFETY: Each element of the array is converted.
+unsafe impl<T: Relocate, const N: usize> Relocate for [T; N] {
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `relocate`, and so does every element of the array.
+        unsafe { <[T]>::relocate(this as *mut [T], base, mode) }
+    }
+}
+
+// SAFETY: Each element of the slice is converted.
+unsafe impl<T: Relocate> Relocate for [T] {
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        let len = ptr_meta::metadata(this);
+        let ptr = this.cast::<T>();
+        for i in 0..len {
+            // SAFETY: The caller has guaranteed that `this` meets the
+            // requirements of `relocate`, and so does every element of the
+            // slice.
+            unsafe {
+                T::relocate(ptr.add(i), base, mode)?;
+            }
+        }
+        Ok(())
+    }
+}
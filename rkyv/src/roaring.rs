@@ -0,0 +1,492 @@
+//! Archived versions of `roaring` types.
+
+use core::{cmp::Ordering, fmt, iter::FusedIterator, slice};
+
+use munge::munge;
+use rancor::Fallible;
+use roaring::RoaringBitmap;
+
+use crate::{
+    primitive::{ArchivedIsize, ArchivedU16, ArchivedU32, ArchivedU64},
+    ser::{Allocator, Writer, WriterExt as _},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable,
+};
+
+/// The largest number of values stored in an array container.
+const ARRAY_LIMIT: u32 = 4096;
+/// The number of 16-bit words in a bitmap container.
+const BITMAP_WORDS: usize = 4096;
+
+/// An archived `RoaringBitmap`.
+///
+/// Archived bitmaps keep the container structure of roaring bitmaps, so
+/// membership, rank, and intersection queries don't need to deserialize the
+/// bitmap.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedRoaringBitmap {
+    containers: ArchivedVec<ArchivedContainer>,
+    len: ArchivedU64,
+}
+
+/// A container of an [`ArchivedRoaringBitmap`], holding all of the values with
+/// the same high 16 bits.
+///
+/// Containers with at most 4096 values store them as a sorted array. Larger
+/// containers store them as a bitmap of 4096 16-bit words.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedContainer {
+    values: ArchivedVec<ArchivedU16>,
+    rank: ArchivedU64,
+    len: ArchivedU32,
+    key: ArchivedU16,
+}
+
+impl ArchivedContainer {
+    /// Returns the high 16 bits of the values in the container.
+    #[inline]
+    pub const fn key(&self) -> u16 {
+        self.key.to_native()
+    }
+
+    /// Returns the number of values in the container.
+    #[inline]
+    pub const fn len(&self) -> u32 {
+        self.len.to_native()
+    }
+
+    /// Returns `true` if the container contains no values.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the values of the container are stored as a bitmap.
+    #[inline]
+    pub const fn is_bitmap(&self) -> bool {
+        self.len() > ARRAY_LIMIT
+    }
+
+    fn word(&self, index: usize) -> u16 {
+        self.values
+            .as_slice()
+            .get(index)
+            .map_or(0, |word| word.to_native())
+    }
+
+    /// Returns `true` if the container contains the given low 16 bits.
+    pub fn contains(&self, low: u16) -> bool {
+        if self.is_bitmap() {
+            self.word(low as usize / 16) & (1 << (low % 16)) != 0
+        } else {
+            self.values
+                .as_slice()
+                .binary_search_by(|value| value.to_native().cmp(&low))
+                .is_ok()
+        }
+    }
+
+    /// Returns the number of values in the container which are less than or
+    /// equal to the given low 16 bits.
+    pub fn rank(&self, low: u16) -> u32 {
+        if self.is_bitmap() {
+            let index = low as usize / 16;
+            let mask = u16::MAX >> (15 - low % 16);
+            let before = self.values.as_slice()[..index.min(self.values.len())]
+                .iter()
+                .map(|word| word.to_native().count_ones())
+                .sum::<u32>();
+            before + (self.word(index) & mask).count_ones()
+        } else {
+            self.values
+                .as_slice()
+                .partition_point(|value| value.to_native() <= low)
+                as u32
+        }
+    }
+
+    /// Returns an iterator over the low 16 bits of the values in the
+    /// container, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> ContainerIter<'_> {
+        let inner = if self.is_bitmap() {
+            ContainerIterInner::Bitmap {
+                words: self.values.as_slice().iter().enumerate(),
+                base: 0,
+                word: 0,
+            }
+        } else {
+            ContainerIterInner::Array(self.values.as_slice().iter())
+        };
+        ContainerIter { inner }
+    }
+
+    /// Calls `f` with the low 16 bits of every value in both this container
+    /// and `other`, in ascending order.
+    fn for_each_intersection(&self, other: &Self, mut f: impl FnMut(u16)) {
+        match (self.is_bitmap(), other.is_bitmap()) {
+            (true, true) => {
+                for (index, (a, b)) in self
+                    .values
+                    .as_slice()
+                    .iter()
+                    .zip(other.values.as_slice())
+                    .enumerate()
+                {
+                    let mut word = a.to_native() & b.to_native();
+                    while word != 0 {
+                        f(index as u16 * 16 + word.trailing_zeros() as u16);
+                        word &= word - 1;
+                    }
+                }
+            }
+            (false, _) => {
+                self.iter().filter(|&low| other.contains(low)).for_each(f)
+            }
+            (true, false) => {
+                other.iter().filter(|&low| self.contains(low)).for_each(f)
+            }
+        }
+    }
+
+    fn intersection_len(&self, other: &Self) -> u64 {
+        if self.is_bitmap() && other.is_bitmap() {
+            self.values
+                .as_slice()
+                .iter()
+                .zip(other.values.as_slice())
+                .map(|(a, b)| (a.to_native() & b.to_native()).count_ones())
+                .sum::<u32>() as u64
+        } else {
+            let mut len = 0;
+            self.for_each_intersection(other, |_| len += 1);
+            len
+        }
+    }
+}
+
+impl fmt::Debug for ArchivedContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedContainer")
+            .field("key", &self.key())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// An iterator over the low 16 bits of the values in an [`ArchivedContainer`].
+pub struct ContainerIter<'a> {
+    inner: ContainerIterInner<'a>,
+}
+
+enum ContainerIterInner<'a> {
+    Array(slice::Iter<'a, ArchivedU16>),
+    Bitmap {
+        words: core::iter::Enumerate<slice::Iter<'a, ArchivedU16>>,
+        base: u16,
+        word: u16,
+    },
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        match &mut self.inner {
+            ContainerIterInner::Array(values) => {
+                values.next().map(|value| value.to_native())
+            }
+            ContainerIterInner::Bitmap { words, base, word } => {
+                while *word == 0 {
+                    let (index, next) = words.next()?;
+                    *base = index as u16 * 16;
+                    *word = next.to_native();
+                }
+                let low = *base + word.trailing_zeros() as u16;
+                *word &= *word - 1;
+                Some(low)
+            }
+        }
+    }
+}
+
+impl FusedIterator for ContainerIter<'_> {}
+
+impl ArchivedRoaringBitmap {
+    /// Returns the number of values in the bitmap.
+    #[inline]
+    pub const fn len(&self) -> u64 {
+        self.len.to_native()
+    }
+
+    /// Returns `true` if the bitmap contains no values.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the containers of the bitmap, sorted by key.
+    #[inline]
+    pub fn containers(&self) -> &[ArchivedContainer] {
+        self.containers.as_slice()
+    }
+
+    fn find(&self, key: u16) -> Result<usize, usize> {
+        self.containers()
+            .binary_search_by(|container| container.key().cmp(&key))
+    }
+
+    /// Returns `true` if the bitmap contains the given value.
+    pub fn contains(&self, value: u32) -> bool {
+        match self.find((value >> 16) as u16) {
+            Ok(index) => self.containers()[index].contains(value as u16),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the number of values in the bitmap which are less than or equal
+    /// to the given value.
+    pub fn rank(&self, value: u32) -> u64 {
+        let containers = self.containers();
+        match self.find((value >> 16) as u16) {
+            Ok(index) => {
+                let container = &containers[index];
+                container.rank.to_native() + container.rank(value as u16) as u64
+            }
+            Err(0) => 0,
+            Err(index) => {
+                let container = &containers[index - 1];
+                container.rank.to_native() + container.len() as u64
+            }
+        }
+    }
+
+    /// Returns an iterator over the values in the bitmap, in ascending order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            containers: self.containers().iter(),
+            current: None,
+        }
+    }
+
+    /// Calls `f` with every pair of containers in this bitmap and `other` which
+    /// have the same key, in ascending order.
+    fn for_each_intersection(
+        &self,
+        other: &Self,
+        mut f: impl FnMut(&ArchivedContainer, &ArchivedContainer),
+    ) {
+        let mut a = self.containers().iter().peekable();
+        let mut b = other.containers().iter().peekable();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            match x.key().cmp(&y.key()) {
+                Ordering::Less => {
+                    a.next();
+                }
+                Ordering::Greater => {
+                    b.next();
+                }
+                Ordering::Equal => {
+                    f(x, y);
+                    a.next();
+                    b.next();
+                }
+            }
+        }
+    }
+
+    /// Returns the number of values in both this bitmap and `other`.
+    pub fn intersection_len(&self, other: &Self) -> u64 {
+        let mut len = 0;
+        self.for_each_intersection(other, |a, b| {
+            len += a.intersection_len(b);
+        });
+        len
+    }
+
+    /// Returns `true` if this bitmap and `other` have no values in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection_len(other) == 0
+    }
+
+    /// Returns a bitmap of the values in both this bitmap and `other`.
+    pub fn intersection(&self, other: &Self) -> RoaringBitmap {
+        let mut result = RoaringBitmap::new();
+        self.for_each_intersection(other, |a, b| {
+            let high = (a.key() as u32) << 16;
+            a.for_each_intersection(b, |low| {
+                result.push(high | low as u32);
+            });
+        });
+        result
+    }
+
+    /// Resolves an archived bitmap from the given length and resolver.
+    pub fn resolve_from_len(
+        len: u64,
+        resolver: RoaringBitmapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedRoaringBitmap { containers, len: out_len } = out);
+        ArchivedVec::resolve_from_len(
+            resolver.containers_len,
+            resolver.containers,
+            containers,
+        );
+        out_len.write(ArchivedU64::from_native(len));
+    }
+
+    /// Serializes an iterator of sorted values as a roaring bitmap.
+    pub fn serialize_from_sorted_iter<I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<RoaringBitmapResolver, S::Error>
+    where
+        I: Clone + IntoIterator<Item = u32>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        use crate::util::SerVec;
+
+        let mut containers_len = 0;
+        let mut last_key = None;
+        for value in iter.clone() {
+            let key = (value >> 16) as u16;
+            if last_key != Some(key) {
+                containers_len += 1;
+                last_key = Some(key);
+            }
+        }
+        serializer.check_len(containers_len)?;
+
+        SerVec::with_capacity(
+            serializer,
+            containers_len,
+            |resolvers, serializer| {
+                let mut values = iter.into_iter().peekable();
+                let mut rank = 0;
+                let mut words = [0u16; BITMAP_WORDS];
+                let mut array = [0u16; ARRAY_LIMIT as usize];
+                while let Some(&first) = values.peek() {
+                    let key = (first >> 16) as u16;
+                    let mut len = 0;
+                    words.fill(0);
+                    while let Some(value) =
+                        values.next_if(|value| (value >> 16) as u16 == key)
+                    {
+                        let low = value as u16;
+                        words[low as usize / 16] |= 1 << (low % 16);
+                        if len < ARRAY_LIMIT {
+                            array[len as usize] = low;
+                        }
+                        len += 1;
+                    }
+
+                    let values = if len > ARRAY_LIMIT {
+                        &words[..]
+                    } else {
+                        &array[..len as usize]
+                    };
+                    let resolver =
+                        ArchivedVec::<ArchivedU16>::serialize_from_slice(
+                            values, serializer,
+                        )?;
+                    resolvers.push((
+                        ContainerAdapter {
+                            key,
+                            len,
+                            rank,
+                            values_len: values.len(),
+                        },
+                        resolver,
+                    ));
+                    rank += len as u64;
+                }
+
+                let pos = serializer.align_for::<ArchivedContainer>()?;
+                for (container, resolver) in resolvers.drain() {
+                    unsafe {
+                        serializer.resolve_aligned(&container, resolver)?;
+                    }
+                }
+                serializer.check_offset::<ArchivedIsize>(pos)?;
+
+                Ok(RoaringBitmapResolver {
+                    containers: VecResolver::from_pos(pos),
+                    containers_len,
+                })
+            },
+        )?
+    }
+}
+
+impl fmt::Debug for ArchivedRoaringBitmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for ArchivedRoaringBitmap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for ArchivedRoaringBitmap {}
+
+/// An iterator over the values in an [`ArchivedRoaringBitmap`].
+pub struct Iter<'a> {
+    containers: slice::Iter<'a, ArchivedContainer>,
+    current: Option<(u32, ContainerIter<'a>)>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((high, values)) = &mut self.current {
+                if let Some(low) = values.next() {
+                    return Some(*high | low as u32);
+                }
+            }
+            let container = self.containers.next()?;
+            self.current =
+                Some(((container.key() as u32) << 16, container.iter()));
+        }
+    }
+}
+
+impl FusedIterator for Iter<'_> {}
+
+/// The resolver for an [`ArchivedRoaringBitmap`].
+pub struct RoaringBitmapResolver {
+    containers: VecResolver,
+    containers_len: usize,
+}
+
+struct ContainerAdapter {
+    key: u16,
+    len: u32,
+    rank: u64,
+    values_len: usize,
+}
+
+impl Archive for ContainerAdapter {
+    type Archived = ArchivedContainer;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedContainer { values, rank, len, key } = out);
+        ArchivedVec::resolve_from_len(self.values_len, resolver, values);
+        self.rank.resolve((), rank);
+        self.len.resolve((), len);
+        self.key.resolve((), key);
+    }
+}
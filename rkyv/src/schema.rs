@@ -0,0 +1,169 @@
+//! Runtime descriptions of the layout of archived types.
+//!
+//! Types which derive `Archive` with `#[archive(schema)]` implement [`Schema`]
+//! on their archived types. The schema describes the name, archived type,
+//! offset, and size of each field, as well as the tags of enum variants. It can
+//! be queried at runtime by generic tools like archive viewers, diffing tools,
+//! and binding generators for other languages.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     schema::{Schema, SchemaKind},
+//!     Archive, Archived,
+//! };
+//!
+//! #[derive(Archive)]
+//! #[archive(schema)]
+//! struct Point {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! let schema = Archived::<Point>::SCHEMA;
+//! assert_eq!(schema.size, 8);
+//!
+//! let SchemaKind::Struct(fields) = schema.kind else {
+//!     panic!("expected a struct");
+//! };
+//! assert_eq!(fields[1].name, "y");
+//! assert_eq!(fields[1].offset, 4);
+//! assert_eq!(fields[1].size, 4);
+//! ```
+
+use core::fmt;
+
+/// An archived type with a runtime description of its layout.
+///
+/// This can be derived with `#[archive(schema)]`.
+///
+/// # Safety
+///
+/// `SCHEMA` must accurately describe the layout of `Self`.
+pub unsafe trait Schema {
+    /// The schema of this type.
+    const SCHEMA: TypeSchema;
+}
+
+/// A description of the layout of an archived type.
+#[derive(Clone, Copy)]
+pub struct TypeSchema {
+    /// Returns the name of the archived type.
+    pub type_name: fn() -> &'static str,
+    /// The size of the archived type.
+    pub size: usize,
+    /// The alignment of the archived type.
+    pub align: usize,
+    /// The kind of the archived type.
+    pub kind: SchemaKind,
+}
+
+impl TypeSchema {
+    /// Returns the field with the given name if this is the schema of a
+    /// struct.
+    pub fn field(&self, name: &str) -> Option<&'static FieldSchema> {
+        match self.kind {
+            SchemaKind::Struct(fields) => find_field(fields, name),
+            SchemaKind::Enum(_) => None,
+        }
+    }
+}
+
+impl fmt::Debug for TypeSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeSchema")
+            .field("type_name", &(self.type_name)())
+            .field("size", &self.size)
+            .field("align", &self.align)
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+/// The kind of an archived type.
+#[derive(Clone, Copy, Debug)]
+pub enum SchemaKind {
+    /// A struct with the given fields.
+    ///
+    /// Tuple structs have fields named `0`, `1`, and so on.
+    Struct(&'static [FieldSchema]),
+    /// An enum.
+    Enum(EnumSchema),
+}
+
+/// A description of a field of an archived struct or enum variant.
+#[derive(Clone, Copy)]
+pub struct FieldSchema {
+    /// The name of the field.
+    pub name: &'static str,
+    /// Returns the name of the archived type of the field.
+    pub type_name: fn() -> &'static str,
+    /// The offset of the field from the start of the archived type.
+    pub offset: usize,
+    /// The size of the archived type of the field.
+    pub size: usize,
+}
+
+impl fmt::Debug for FieldSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldSchema")
+            .field("name", &self.name)
+            .field("type_name", &(self.type_name)())
+            .field("offset", &self.offset)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+/// A description of an archived enum.
+///
+/// The tag of the enum is stored at the start of the archived type. Tags
+/// larger than one byte are stored in the byte order of the archive.
+#[derive(Clone, Copy, Debug)]
+pub struct EnumSchema {
+    /// The size of the tag of the enum.
+    pub tag_size: usize,
+    /// The variants of the enum.
+    pub variants: &'static [VariantSchema],
+}
+
+impl EnumSchema {
+    /// Returns the variant with the given tag.
+    pub fn variant_for_tag(&self, tag: u64) -> Option<&'static VariantSchema> {
+        self.variants.iter().find(|variant| variant.tag == tag)
+    }
+
+    /// Returns the variant with the given name.
+    pub fn variant(&self, name: &str) -> Option<&'static VariantSchema> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+}
+
+/// A description of a variant of an archived enum.
+#[derive(Clone, Copy, Debug)]
+pub struct VariantSchema {
+    /// The name of the variant.
+    pub name: &'static str,
+    /// The native value of the tag of the variant.
+    pub tag: u64,
+    /// The fields of the variant.
+    ///
+    /// Field offsets are from the start of the archived enum, and include the
+    /// tag.
+    pub fields: &'static [FieldSchema],
+}
+
+impl VariantSchema {
+    /// Returns the field with the given name.
+    pub fn field(&self, name: &str) -> Option<&'static FieldSchema> {
+        find_field(self.fields, name)
+    }
+}
+
+fn find_field(
+    fields: &'static [FieldSchema],
+    name: &str,
+) -> Option<&'static FieldSchema> {
+    fields.iter().find(|field| field.name == name)
+}
@@ -6,6 +6,7 @@ use alloc::{
 };
 use core::{
     alloc::Layout,
+    fmt,
     marker::PhantomData,
     mem::{align_of, size_of, ManuallyDrop},
     ptr::{slice_from_raw_parts_mut, NonNull},
@@ -14,8 +15,46 @@ use std::alloc::handle_alloc_error;
 #[cfg(feature = "std")]
 use std::alloc::{alloc, dealloc};
 
+use rancor::{fail, Source};
+
 use crate::ser::Allocator;
 
+/// The error returned when memory for serialization could not be allocated.
+///
+/// Serializers which write to [`AlignedVec`](crate::util::AlignedVec)s or
+/// `Vec<u8>`s, or allocate scratch space from an [`ArenaHandle`], return this
+/// error instead of aborting the process when the global allocator fails or the
+/// requested size is too large. Creating an [`Arena`] with [`Arena::new`] or
+/// [`Arena::with_capacity`] still aborts, use [`Arena::try_new`] or
+/// [`Arena::try_with_capacity`] to handle that failure too.
+///
+/// Because they can fail, the [`Writer`](crate::ser::Writer) impls for
+/// `AlignedVec` and `Vec<u8>` and the [`Allocator`] impl for `ArenaHandle`
+/// require that the error type implements [`Source`]. Serializers which used an
+/// error type that can't be constructed from another error, like
+/// `rancor::Infallible`, should switch to an error type like
+/// [`rancor::Error`], or [`rancor::Panic`] to keep panicking on failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfMemory {
+    /// The size of the allocation that failed.
+    pub size: usize,
+    /// The alignment of the allocation that failed.
+    pub align: usize,
+}
+
+impl fmt::Display for OutOfMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "out of memory: failed to allocate {} bytes with alignment {}",
+            self.size, self.align,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfMemory {}
+
 struct Block {
     next_ptr: NonNull<Block>,
     next_size: usize,
@@ -23,11 +62,26 @@ struct Block {
 
 impl Block {
     fn alloc(size: usize) -> NonNull<Self> {
-        debug_assert!(size >= size_of::<Self>());
         let layout = Layout::from_size_align(size, align_of::<Self>()).unwrap();
+        match Self::try_alloc(size) {
+            Ok(ptr) => ptr,
+            Err(_) => handle_alloc_error(layout),
+        }
+    }
+
+    fn try_alloc(size: usize) -> Result<NonNull<Self>, OutOfMemory> {
+        debug_assert!(size >= size_of::<Self>());
+        let error = OutOfMemory {
+            size,
+            align: align_of::<Self>(),
+        };
+        let Ok(layout) = Layout::from_size_align(size, align_of::<Self>())
+        else {
+            return Err(error);
+        };
         let ptr = unsafe { alloc(layout).cast::<Self>() };
         let Some(ptr) = NonNull::new(ptr) else {
-            handle_alloc_error(layout)
+            return Err(error);
         };
 
         unsafe {
@@ -37,7 +91,7 @@ impl Block {
             });
         }
 
-        ptr
+        Ok(ptr)
     }
 
     unsafe fn dealloc(ptr: NonNull<Self>, size: usize) {
@@ -94,17 +148,46 @@ impl Arena {
     pub const DEFAULT_CAPACITY: usize = 1024;
 
     /// Creates a new `Arena` with the default capacity.
+    ///
+    /// This aborts the process if the arena can't be allocated. Use
+    /// [`try_new`](Self::try_new) to handle allocation failures.
     pub fn new() -> Self {
         Self::with_capacity(Self::DEFAULT_CAPACITY)
     }
 
     /// Creates a new `Arena` with at least the requested capacity.
+    ///
+    /// This aborts the process if the arena can't be allocated. Use
+    /// [`try_with_capacity`](Self::try_with_capacity) to handle allocation
+    /// failures.
     pub fn with_capacity(cap: usize) -> Self {
         let head_size = (cap + size_of::<Block>()).next_power_of_two();
         let head_ptr = Block::alloc(head_size);
         Self { head_ptr }
     }
 
+    /// Creates a new `Arena` with the default capacity, returning an error if
+    /// it can't be allocated.
+    pub fn try_new() -> Result<Self, OutOfMemory> {
+        Self::try_with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Creates a new `Arena` with at least the requested capacity, returning an
+    /// error if it can't be allocated.
+    pub fn try_with_capacity(cap: usize) -> Result<Self, OutOfMemory> {
+        let Some(head_size) = cap
+            .checked_add(size_of::<Block>())
+            .and_then(usize::checked_next_power_of_two)
+        else {
+            return Err(OutOfMemory {
+                size: cap,
+                align: align_of::<Block>(),
+            });
+        };
+        let head_ptr = Block::try_alloc(head_size)?;
+        Ok(Self { head_ptr })
+    }
+
     /// Cleans up allocated blocks which are no longer in use.
     ///
     /// The arena is automatically shrunk by [`acquire`](Self::acquire).
@@ -210,7 +293,7 @@ pub struct ArenaHandle<'a> {
     _phantom: PhantomData<&'a mut Arena>,
 }
 
-unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
+unsafe impl<E: Source> Allocator<E> for ArenaHandle<'_> {
     unsafe fn push_alloc(
         &mut self,
         layout: Layout,
@@ -221,12 +304,20 @@ unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
             self.used += pad;
         } else {
             // Allocation request is too large, allocate a new block
-            let size = usize::max(
-                2 * self.tail_size,
-                (size_of::<Block>() + layout.size() + layout.align())
-                    .next_power_of_two(),
-            );
-            let next = Block::alloc(size);
+            let Some(needed) = (size_of::<Block>() + layout.align())
+                .checked_add(layout.size())
+                .and_then(usize::checked_next_power_of_two)
+            else {
+                fail!(OutOfMemory {
+                    size: layout.size(),
+                    align: layout.align(),
+                });
+            };
+            let size = usize::max(2 * self.tail_size, needed);
+            let next = match Block::try_alloc(size) {
+                Ok(next) => next,
+                Err(e) => fail!(e),
+            };
             unsafe {
                 Block::push_next(self.tail_ptr, next);
             }
@@ -253,8 +344,20 @@ unsafe impl<E> Allocator<E> for ArenaHandle<'_> {
         ptr: NonNull<u8>,
         _: Layout,
     ) -> Result<(), E> {
-        let bytes = self.tail_ptr.as_ptr().cast::<u8>();
-        self.used = ptr.as_ptr() as usize - bytes as usize;
+        let addr = ptr.as_ptr() as usize;
+        // Allocations made before the arena grew may live in an earlier block,
+        // so walk the loop of blocks until finding the one containing `ptr`.
+        loop {
+            let base = self.tail_ptr.as_ptr() as usize;
+            if base < addr && addr <= base + self.tail_size {
+                self.used = addr - base;
+                break;
+            }
+            // SAFETY: `tail_ptr` always points to a valid block in the loop.
+            let tail = unsafe { self.tail_ptr.as_ref() };
+            self.tail_size = tail.next_size;
+            self.tail_ptr = tail.next_ptr;
+        }
 
         Ok(())
     }
@@ -98,3 +98,10 @@ where
         Ok(())
     }
 }
+
+/// An allocator placeholder for serializers which must never allocate.
+///
+/// `NoAllocator` does not implement [`Allocator`], so serializing a type which
+/// needs scratch space with it fails to compile instead of failing at runtime.
+#[derive(Debug, Default)]
+pub struct NoAllocator;
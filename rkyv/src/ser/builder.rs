@@ -0,0 +1,184 @@
+//! Builders which serialize collections from elements pushed one at a time.
+
+use core::{fmt, hash::Hash};
+
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::swiss_table::{ArchivedHashMap, HashMapResolver},
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Serialize,
+};
+
+/// An error resulting from a builder receiving a different number of elements
+/// than it was created for.
+#[derive(Debug)]
+struct BuilderLengthMismatch {
+    expected: usize,
+    actual: usize,
+}
+
+impl fmt::Display for BuilderLengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "builder was created for {} elements, but {} elements were pushed",
+            self.expected, self.actual,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuilderLengthMismatch {}
+
+fn check_len<E: Source>(expected: usize, actual: usize) -> Result<(), E> {
+    if expected != actual {
+        fail!(BuilderLengthMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+/// A builder for an [`ArchivedVec`].
+///
+/// This is created by [`BuilderExt::build_vec`].
+pub struct VecBuilder<'a, T> {
+    items: &'a mut SerVec<T>,
+}
+
+impl<T> VecBuilder<'_, T> {
+    /// Returns the number of elements the builder was created for.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns the number of elements pushed to the builder.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if no elements have been pushed to the builder.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Appends an element to the vector.
+    ///
+    /// Returns an error if the builder is already full.
+    pub fn push<E: Source>(&mut self, value: T) -> Result<(), E> {
+        if self.len() == self.capacity() {
+            fail!(BuilderLengthMismatch {
+                expected: self.capacity(),
+                actual: self.capacity() + 1,
+            });
+        }
+        self.items.push(value);
+        Ok(())
+    }
+}
+
+/// A builder for an [`ArchivedHashMap`].
+///
+/// This is created by [`BuilderExt::build_map`].
+pub struct MapBuilder<'a, K, V> {
+    entries: &'a mut SerVec<(K, V)>,
+}
+
+impl<K, V> MapBuilder<'_, K, V> {
+    /// Returns the number of entries the builder was created for.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns the number of entries pushed to the builder.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries have been pushed to the builder.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// Returns an error if the builder is already full. Keys must be unique.
+    pub fn push<E: Source>(&mut self, key: K, value: V) -> Result<(), E> {
+        if self.len() == self.capacity() {
+            fail!(BuilderLengthMismatch {
+                expected: self.capacity(),
+                actual: self.capacity() + 1,
+            });
+        }
+        self.entries.push((key, value));
+        Ok(())
+    }
+}
+
+/// Helper methods for building collections during serialization.
+///
+/// Builders let custom [`Serialize`] implementations produce collections from
+/// streaming or generated sources. Pushed elements are held in serializer
+/// scratch space until the builder finishes, then they are serialized and
+/// dropped.
+pub trait BuilderExt: Fallible + Allocator + Writer {
+    /// Builds an archived vector with exactly `len` elements.
+    ///
+    /// `f` pushes the elements of the vector to the builder, and returns an
+    /// error if a different number of elements were pushed. The vector should
+    /// be resolved with [`ArchivedVec::resolve_from_len`].
+    fn build_vec<T, F>(
+        &mut self,
+        len: usize,
+        f: F,
+    ) -> Result<VecResolver, Self::Error>
+    where
+        T: Serialize<Self>,
+        F: FnOnce(&mut VecBuilder<'_, T>) -> Result<(), Self::Error>,
+        Self::Error: Source,
+    {
+        SerVec::with_capacity(self, len, |items, serializer| {
+            f(&mut VecBuilder { items })?;
+            check_len(len, items.len())?;
+            ArchivedVec::<T::Archived>::serialize_from_slice(items, serializer)
+        })?
+    }
+
+    /// Builds an archived hash map with exactly `len` entries.
+    ///
+    /// `f` pushes the entries of the map to the builder, and returns an error
+    /// if a different number of entries were pushed. The map should be
+    /// resolved with [`ArchivedHashMap::resolve_from_len`] using the same load
+    /// factor.
+    fn build_map<K, V, F>(
+        &mut self,
+        len: usize,
+        load_factor: (usize, usize),
+        f: F,
+    ) -> Result<HashMapResolver, Self::Error>
+    where
+        K: Serialize<Self> + Hash + Eq,
+        V: Serialize<Self>,
+        F: FnOnce(&mut MapBuilder<'_, K, V>) -> Result<(), Self::Error>,
+        Self::Error: Source,
+    {
+        SerVec::with_capacity(self, len, |entries, serializer| {
+            f(&mut MapBuilder { entries })?;
+            check_len(len, entries.len())?;
+            ArchivedHashMap::<K::Archived, V::Archived>::serialize_from_iter(
+                entries.iter().map(|(key, value)| (key, value)),
+                load_factor,
+                serializer,
+            )
+        })?
+    }
+}
+
+impl<S> BuilderExt for S where S: Fallible + Allocator + Writer + ?Sized {}
@@ -1,6 +1,7 @@
 //! Serialization traits and adapters.
 
 pub mod allocator;
+pub mod builder;
 pub mod sharing;
 pub mod writer;
 
@@ -10,12 +11,20 @@ use rancor::Strategy;
 #[doc(inline)]
 pub use self::{
     allocator::Allocator,
+    builder::BuilderExt,
     sharing::{Sharing, SharingExt},
     writer::{Positional, Writer, WriterExt},
 };
 #[cfg(feature = "alloc")]
 use crate::ser::{allocator::ArenaHandle, sharing::Share};
-use crate::ser::{allocator::SubAllocator, sharing::Unshare};
+use crate::{
+    ser::{
+        allocator::{NoAllocator, SubAllocator},
+        sharing::Unshare,
+        writer::OverflowError,
+    },
+    Serialize,
+};
 
 /// A serializer built from composeable pieces.
 #[derive(Debug, Default)]
@@ -61,6 +70,10 @@ impl<W: Writer<E>, A, S, E> Writer<E> for Serializer<W, A, S> {
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
         self.writer.write(bytes)
     }
+
+    fn overflow(&self, error: OverflowError) -> E {
+        self.writer.overflow(error)
+    }
 }
 
 unsafe impl<W, A: Allocator<E>, S, E> Allocator<E> for Serializer<W, A, S> {
@@ -92,12 +105,70 @@ impl<W, A, S: Sharing<E>, E> Sharing<E> for Serializer<W, A, S> {
     fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E> {
         self.sharing.add_shared_ptr(address, pos)
     }
+
+    fn get_shared_bytes(&self, bytes: &[u8]) -> Option<usize> {
+        self.sharing.get_shared_bytes(bytes)
+    }
+
+    fn add_shared_bytes(&mut self, bytes: &[u8], pos: usize) -> Result<(), E> {
+        self.sharing.add_shared_bytes(bytes, pos)
+    }
+
+    fn get_shared_range(&self, address: usize, len: usize) -> Option<usize> {
+        self.sharing.get_shared_range(address, len)
+    }
+
+    fn add_shared_range(
+        &mut self,
+        address: usize,
+        len: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        self.sharing.add_shared_range(address, len, pos)
+    }
+
+    fn shares_subtrees(&self) -> bool {
+        self.sharing.shares_subtrees()
+    }
+
+    fn get_shared_subtree(&self, key: &[u8], align: usize) -> Option<usize> {
+        self.sharing.get_shared_subtree(key, align)
+    }
+
+    fn add_shared_subtree(
+        &mut self,
+        key: &[u8],
+        align: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        self.sharing.add_shared_subtree(key, align, pos)
+    }
 }
 
 /// A serializer suitable for environments where allocations cannot be made.
 pub type CoreSerializer<'a, W, E> =
     Strategy<Serializer<W, SubAllocator<'a>, Unshare>, E>;
 
+/// A serializer which statically cannot allocate scratch space.
+///
+/// Types which serialize with this serializer are proven not to touch an
+/// [`Allocator`] during serialization. See [`SerializeNoAlloc`].
+pub type NoAllocSerializer<W, E> =
+    Strategy<Serializer<W, NoAllocator, Unshare>, E>;
+
+/// A type which can be serialized without any scratch space.
+///
+/// This is implemented for every type which implements [`Serialize`] for a
+/// [`NoAllocSerializer`]. That includes primitives, strings, and types built
+/// from them, but not collections or unsized types which need scratch space
+/// while serializing their elements.
+pub trait SerializeNoAlloc<W, E>: Serialize<NoAllocSerializer<W, E>> {}
+
+impl<T, W, E> SerializeNoAlloc<W, E> for T where
+    T: Serialize<NoAllocSerializer<W, E>> + ?Sized
+{
+}
+
 /// A general-purpose serializer suitable for environments where allocations can
 /// be made.
 #[cfg(feature = "alloc")]
@@ -1,6 +1,8 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, collections::BTreeMap};
 use core::{fmt, mem::size_of};
 #[cfg(feature = "std")]
-use std::collections::hash_map;
+use std::collections::{hash_map, BTreeMap};
 
 #[cfg(not(feature = "std"))]
 use hashbrown::hash_map;
@@ -27,11 +29,42 @@ impl fmt::Display for DuplicateSharedPointer {
 #[cfg(feature = "std")]
 impl std::error::Error for DuplicateSharedPointer {}
 
+// Maps the alignment and key of each shared subtree to its position.
+type SharedSubtrees =
+    hash_map::HashMap<usize, hash_map::HashMap<Box<[u8]>, usize>>;
+
 /// A shared pointer strategy that shares serializations of the same shared
 /// pointer.
+///
+/// Content deduplication can be enabled with
+/// [`with_content_dedup`](Share::with_content_dedup). In that mode, bytes with
+/// identical contents (such as strings serialized with
+/// [`Dedup`](crate::with::Dedup)) are only written once, even if they come from
+/// distinct allocations. Archives with shared bytes can't be validated, so this
+/// mode is `unsafe` to enable.
+///
+/// Subslice sharing can be enabled with
+/// [`with_subslice_sharing`](Share::with_subslice_sharing). In that mode, byte
+/// slices (such as those serialized with [`Subslice`](crate::with::Subslice))
+/// which lie within the memory of a previously-written slice point into its
+/// serialized bytes instead of being written again. Like content
+/// deduplication, this mode is `unsafe` to enable.
+///
+/// Subtree deduplication can be enabled with
+/// [`with_subtree_dedup`](Share::with_subtree_dedup). In that mode, values
+/// (such as boxes and vectors serialized with [`Dedup`](crate::with::Dedup))
+/// whose archived bytes and pointers are identical to those of a
+/// previously-written value are only written once. This mode also deduplicates
+/// bytes by their contents, and is `unsafe` to enable.
 #[derive(Debug, Default)]
 pub struct Share {
     shared_address_to_pos: hash_map::HashMap<usize, usize>,
+    shared_bytes_to_pos: Option<hash_map::HashMap<Box<[u8]>, usize>>,
+    shared_subtrees: Option<SharedSubtrees>,
+    // Maps the start address of each shared range to its end address and
+    // position. No range in the map contains another, so the ranges are sorted
+    // by both their start and end addresses.
+    shared_ranges: Option<BTreeMap<usize, (usize, usize)>>,
 }
 
 impl Share {
@@ -40,6 +73,9 @@ impl Share {
     pub fn new() -> Self {
         Self {
             shared_address_to_pos: hash_map::HashMap::new(),
+            shared_bytes_to_pos: None,
+            shared_ranges: None,
+            shared_subtrees: None,
         }
     }
 
@@ -48,6 +84,79 @@ impl Share {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             shared_address_to_pos: hash_map::HashMap::with_capacity(capacity),
+            shared_bytes_to_pos: None,
+            shared_ranges: None,
+            shared_subtrees: None,
+        }
+    }
+
+    /// Creates a new shared pointer unifier which also deduplicates bytes by
+    /// their contents.
+    ///
+    /// A copy of each distinct byte payload is kept until the unifier is
+    /// dropped.
+    ///
+    /// # Safety
+    ///
+    /// Archives serialized with content deduplication may point multiple
+    /// archived values at the same bytes. These archives fail validation, so
+    /// they may only be accessed with
+    /// [`access_unchecked`](crate::access_unchecked). Mutable references must
+    /// not be created to any of the shared bytes, since they may alias.
+    #[inline]
+    pub unsafe fn with_content_dedup() -> Self {
+        Self {
+            shared_address_to_pos: hash_map::HashMap::new(),
+            shared_bytes_to_pos: Some(hash_map::HashMap::new()),
+            shared_ranges: None,
+            shared_subtrees: None,
+        }
+    }
+
+    /// Creates a new shared pointer unifier which also shares the bytes of
+    /// slices which lie within previously-written slices.
+    ///
+    /// Slices are compared by address, so this is most useful for overlapping
+    /// views into the same buffer, like the windows of a rope.
+    ///
+    /// # Safety
+    ///
+    /// Archives serialized with subslice sharing may point multiple archived
+    /// values at the same bytes. These archives fail validation, so they may
+    /// only be accessed with [`access_unchecked`](crate::access_unchecked).
+    /// Mutable references must not be created to any of the shared bytes,
+    /// since they may alias.
+    #[inline]
+    pub unsafe fn with_subslice_sharing() -> Self {
+        Self {
+            shared_address_to_pos: hash_map::HashMap::new(),
+            shared_bytes_to_pos: None,
+            shared_ranges: Some(BTreeMap::new()),
+            shared_subtrees: None,
+        }
+    }
+
+    /// Creates a new shared pointer unifier which also deduplicates subtrees
+    /// and bytes by their contents.
+    ///
+    /// Subtrees are only shared if they are identical all the way down, so
+    /// their shared dependencies must also be identical. A key for each
+    /// distinct subtree is kept until the unifier is dropped.
+    ///
+    /// # Safety
+    ///
+    /// Archives serialized with subtree deduplication may point multiple
+    /// archived values at the same bytes. These archives fail validation, so
+    /// they may only be accessed with
+    /// [`access_unchecked`](crate::access_unchecked). Mutable references must
+    /// not be created to any of the shared bytes, since they may alias.
+    #[inline]
+    pub unsafe fn with_subtree_dedup() -> Self {
+        Self {
+            shared_address_to_pos: hash_map::HashMap::new(),
+            shared_bytes_to_pos: Some(hash_map::HashMap::new()),
+            shared_ranges: None,
+            shared_subtrees: Some(hash_map::HashMap::new()),
         }
     }
 }
@@ -68,4 +177,92 @@ impl<E: Source> Sharing<E> for Share {
             }
         }
     }
+
+    fn get_shared_bytes(&self, bytes: &[u8]) -> Option<usize> {
+        self.shared_bytes_to_pos.as_ref()?.get(bytes).copied()
+    }
+
+    fn add_shared_bytes(&mut self, bytes: &[u8], pos: usize) -> Result<(), E> {
+        if let Some(shared_bytes_to_pos) = self.shared_bytes_to_pos.as_mut() {
+            if !shared_bytes_to_pos.contains_key(bytes) {
+                shared_bytes_to_pos.insert(bytes.into(), pos);
+            }
+        }
+        Ok(())
+    }
+
+    fn get_shared_range(&self, address: usize, len: usize) -> Option<usize> {
+        let shared_ranges = self.shared_ranges.as_ref()?;
+        // Because no range contains another, the range with the greatest start
+        // address before `address` also has the greatest end address.
+        let (start, (end, pos)) =
+            shared_ranges.range(..=address).next_back()?;
+        if address.checked_add(len)? <= *end {
+            Some(pos + (address - start))
+        } else {
+            None
+        }
+    }
+
+    fn add_shared_range(
+        &mut self,
+        address: usize,
+        len: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        let Some(shared_ranges) = self.shared_ranges.as_mut() else {
+            return Ok(());
+        };
+        let end = address.saturating_add(len);
+        if shared_ranges
+            .range(..=address)
+            .next_back()
+            .is_some_and(|(_, (range_end, _))| end <= *range_end)
+        {
+            // The new range is already covered by a shared range.
+            return Ok(());
+        }
+
+        // Remove the ranges contained in the new range so that no range
+        // contains another.
+        while let Some((&start, &(range_end, _))) =
+            shared_ranges.range(address..).next()
+        {
+            if range_end > end {
+                break;
+            }
+            shared_ranges.remove(&start);
+        }
+        shared_ranges.insert(address, (end, pos));
+
+        Ok(())
+    }
+
+    fn shares_subtrees(&self) -> bool {
+        self.shared_subtrees.is_some()
+    }
+
+    fn get_shared_subtree(&self, key: &[u8], align: usize) -> Option<usize> {
+        self.shared_subtrees
+            .as_ref()?
+            .get(&align)?
+            .get(key)
+            .copied()
+    }
+
+    fn add_shared_subtree(
+        &mut self,
+        key: &[u8],
+        align: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        if let Some(shared_subtrees) = self.shared_subtrees.as_mut() {
+            shared_subtrees
+                .entry(align)
+                .or_default()
+                .entry(key.into())
+                .or_insert(pos);
+        }
+        Ok(())
+    }
 }
@@ -4,12 +4,22 @@
 mod alloc;
 mod core;
 
-use rancor::{Fallible, Strategy};
+use ::core::{
+    mem::{align_of, size_of, MaybeUninit},
+    ptr, slice,
+};
+use rancor::{Failure, Fallible, Strategy};
 
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
 pub use self::core::*;
-use crate::SerializeUnsized;
+use crate::{
+    primitive::ArchivedIsize,
+    relocate::{OffsetMode, Relocate},
+    ser::{Allocator, Writer, WriterExt as _},
+    util::SerVec,
+    Archive, Place, Serialize, SerializeUnsized,
+};
 
 /// A shared pointer serialization strategy.
 ///
@@ -22,6 +32,80 @@ pub trait Sharing<E = <Self as Fallible>::Error> {
 
     /// Adds the serialized position of a shared pointer.
     fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E>;
+
+    /// Gets the position of previously-serialized bytes with the same contents
+    /// as the given bytes.
+    ///
+    /// Returns `None` if no bytes with the same contents have been added, or
+    /// if content deduplication is not supported.
+    fn get_shared_bytes(&self, bytes: &[u8]) -> Option<usize> {
+        let _ = bytes;
+        None
+    }
+
+    /// Adds the serialized position of some bytes, so that later bytes with
+    /// the same contents can share them.
+    ///
+    /// Strategies which don't support content deduplication may ignore this.
+    fn add_shared_bytes(&mut self, bytes: &[u8], pos: usize) -> Result<(), E> {
+        let _ = (bytes, pos);
+        Ok(())
+    }
+
+    /// Gets the position of the serialized bytes for the memory range starting
+    /// at `address` and spanning `len` bytes.
+    ///
+    /// This returns a position if the range lies entirely within a range that
+    /// has already been added, even if it does not start at the same address.
+    /// Returns `None` if no such range has been added, or if subslice sharing
+    /// is not supported.
+    fn get_shared_range(&self, address: usize, len: usize) -> Option<usize> {
+        let _ = (address, len);
+        None
+    }
+
+    /// Adds the serialized position of a memory range, so that later ranges
+    /// which lie within it can share its bytes.
+    ///
+    /// Strategies which don't support subslice sharing may ignore this.
+    fn add_shared_range(
+        &mut self,
+        address: usize,
+        len: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        let _ = (address, len, pos);
+        Ok(())
+    }
+
+    /// Returns whether this strategy deduplicates subtrees by their contents.
+    fn shares_subtrees(&self) -> bool {
+        false
+    }
+
+    /// Gets the position of a previously-added subtree with the given key and
+    /// alignment.
+    ///
+    /// Returns `None` if no subtree with the same key and alignment has been
+    /// added, or if subtree sharing is not supported.
+    fn get_shared_subtree(&self, key: &[u8], align: usize) -> Option<usize> {
+        let _ = (key, align);
+        None
+    }
+
+    /// Adds the serialized position of a subtree with the given key and
+    /// alignment, so that later identical subtrees can share it.
+    ///
+    /// Strategies which don't support subtree sharing may ignore this.
+    fn add_shared_subtree(
+        &mut self,
+        key: &[u8],
+        align: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        let _ = (key, align, pos);
+        Ok(())
+    }
 }
 
 impl<T, E> Sharing<E> for Strategy<T, E>
@@ -35,6 +119,44 @@ where
     fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E> {
         T::add_shared_ptr(self, address, pos)
     }
+
+    fn get_shared_bytes(&self, bytes: &[u8]) -> Option<usize> {
+        T::get_shared_bytes(self, bytes)
+    }
+
+    fn add_shared_bytes(&mut self, bytes: &[u8], pos: usize) -> Result<(), E> {
+        T::add_shared_bytes(self, bytes, pos)
+    }
+
+    fn get_shared_range(&self, address: usize, len: usize) -> Option<usize> {
+        T::get_shared_range(self, address, len)
+    }
+
+    fn add_shared_range(
+        &mut self,
+        address: usize,
+        len: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        T::add_shared_range(self, address, len, pos)
+    }
+
+    fn shares_subtrees(&self) -> bool {
+        T::shares_subtrees(self)
+    }
+
+    fn get_shared_subtree(&self, key: &[u8], align: usize) -> Option<usize> {
+        T::get_shared_subtree(self, key, align)
+    }
+
+    fn add_shared_subtree(
+        &mut self,
+        key: &[u8],
+        align: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        T::add_shared_subtree(self, key, align, pos)
+    }
 }
 
 /// TODO: Document this
@@ -73,6 +195,316 @@ pub trait SharingExt<E>: Sharing<E> {
             Ok(pos)
         }
     }
+
+    /// Writes the given bytes and returns their position. If bytes with the
+    /// same contents have already been added then it returns the position of
+    /// the previously added bytes instead.
+    fn serialize_shared_bytes(&mut self, bytes: &[u8]) -> Result<usize, E>
+    where
+        Self: Writer<E>,
+    {
+        let pos = if let Some(pos) = self.get_shared_bytes(bytes) {
+            pos
+        } else {
+            let pos = self.pos();
+            self.write(bytes)?;
+            self.add_shared_bytes(bytes, pos)?;
+            pos
+        };
+        self.check_offset::<ArchivedIsize>(pos)?;
+        Ok(pos)
+    }
+
+    /// Writes the given bytes and returns their position. If the bytes lie
+    /// within a range of memory which has already been added, then it returns
+    /// the position of the matching bytes in that range instead.
+    ///
+    /// Ranges are compared by address, so this only shares bytes between
+    /// slices which borrow from the same underlying buffer.
+    fn serialize_shared_subslice(&mut self, bytes: &[u8]) -> Result<usize, E>
+    where
+        Self: Writer<E>,
+    {
+        let address = bytes.as_ptr() as usize;
+        if bytes.is_empty() {
+            Ok(self.pos())
+        } else if let Some(pos) = self.get_shared_range(address, bytes.len()) {
+            self.check_offset::<ArchivedIsize>(pos)?;
+            Ok(pos)
+        } else {
+            let pos = self.pos();
+            self.write(bytes)?;
+            self.add_shared_range(address, bytes.len(), pos)?;
+            self.check_offset::<ArchivedIsize>(pos)?;
+            Ok(pos)
+        }
+    }
+
+    /// Serializes the given value as a subtree and returns the position of its
+    /// archived root.
+    ///
+    /// If the strategy shares subtrees and an identical subtree has already
+    /// been added, then it returns the position of that subtree instead.
+    /// Subtrees are identical if their archived roots have the same bytes and
+    /// all of their relative pointers point to the same positions.
+    ///
+    /// The value is only serialized once. Its archived root is resolved before
+    /// it's written, and a copy of it is converted to absolute offsets with
+    /// [`Relocate`] to compare it to previously-added subtrees. When an
+    /// identical subtree is found, the dependencies of the value which are not
+    /// themselves shared are left unused in the archive.
+    fn serialize_shared_subtree<T>(&mut self, value: &T) -> Result<usize, E>
+    where
+        T: Serialize<Self>,
+        T::Archived: Relocate,
+        Self: Fallible<Error = E> + Writer<E>,
+    {
+        let is_empty = size_of::<T::Archived>() == 0;
+        if !is_empty {
+            if let Some(pos) = self.get_shared(value) {
+                self.check_offset::<ArchivedIsize>(pos)?;
+                return Ok(pos);
+            }
+        }
+
+        let resolver = value.serialize(self)?;
+        let pos = if !is_empty && self.shares_subtrees() {
+            let pos = self.align_for::<T::Archived>()?;
+            let mut resolved = MaybeUninit::<T::Archived>::zeroed();
+            // SAFETY: `resolved.as_mut_ptr()` points to a local zeroed
+            // `MaybeUninit`, and so is properly aligned, dereferenceable, and
+            // all of its bytes are initialized.
+            let out =
+                unsafe { Place::new_unchecked(pos, resolved.as_mut_ptr()) };
+            value.resolve(resolver, out);
+            let mut key = MaybeUninit::<T::Archived>::uninit();
+            // SAFETY: `resolved` was resolved at `pos`, which the serializer
+            // is at. `key` is a separate local `MaybeUninit`, and so is
+            // properly aligned and valid for writes.
+            unsafe {
+                write_subtree(
+                    self,
+                    resolved.as_ptr(),
+                    key.as_mut_ptr(),
+                    1,
+                    pos,
+                )?
+            }
+        } else {
+            self.align_for::<T::Archived>()?;
+            // SAFETY: `resolver` is the result of serializing `value`, and the
+            // serializer was just aligned for `T::Archived`.
+            unsafe { self.resolve_aligned(value, resolver)? }
+        };
+
+        if !is_empty {
+            self.add_shared(value, pos)?;
+        }
+        self.check_offset::<ArchivedIsize>(pos)?;
+        Ok(pos)
+    }
+
+    /// Serializes the given slice as a subtree and returns the position of its
+    /// archived elements.
+    ///
+    /// This behaves like
+    /// [`serialize_shared_subtree`](SharingExt::serialize_shared_subtree), but
+    /// the archived elements of the slice are shared together.
+    fn serialize_shared_subtree_slice<T>(
+        &mut self,
+        values: &[T],
+    ) -> Result<usize, E>
+    where
+        T: Serialize<Self>,
+        T::Archived: Relocate,
+        Self: Fallible<Error = E> + Writer<E> + Allocator<E>,
+    {
+        let size = size_of::<T::Archived>() * values.len();
+        if size != 0 {
+            if let Some(pos) = self.get_shared(values) {
+                self.check_offset::<ArchivedIsize>(pos)?;
+                return Ok(pos);
+            }
+        }
+
+        let len = values.len();
+        let pos = SerVec::<T::Resolver>::with_capacity(
+            self,
+            len,
+            |resolvers, serializer| {
+                for value in values.iter() {
+                    // SAFETY: `resolvers` has a capacity of `values.len()`.
+                    unsafe {
+                        resolvers.push_unchecked(value.serialize(serializer)?);
+                    }
+                }
+
+                if size != 0 && serializer.shares_subtrees() {
+                    SerVec::with_capacity(
+                        serializer,
+                        len,
+                        |resolved, serializer| {
+                            SerVec::with_capacity(
+                                serializer,
+                                len,
+                                |key, serializer| {
+                                    share_slice(
+                                        serializer, values, resolvers,
+                                        resolved, key,
+                                    )
+                                },
+                            )?
+                        },
+                    )?
+                } else {
+                    resolve_slice(serializer, values, resolvers)
+                }
+            },
+        )??;
+
+        if size != 0 {
+            self.add_shared(values, pos)?;
+        }
+        self.check_offset::<ArchivedIsize>(pos)?;
+        Ok(pos)
+    }
 }
 
 impl<S, E> SharingExt<E> for S where S: Sharing<E> + ?Sized {}
+
+/// Writes `len` resolved values as a subtree unless the strategy has already
+/// added an identical one, and returns the position of the subtree.
+///
+/// A copy of the values is converted to absolute offsets in `key`. Absolute
+/// offsets don't depend on where the subtree is written, so the copy can be
+/// compared to the keys of previously-added subtrees. Invalid relative pointers
+/// hold `-1` in absolute mode, so they can't be mistaken for valid pointers.
+///
+/// # Safety
+///
+/// - `resolved` must point to `len` values which were resolved at `pos`.
+/// - `key` must be properly aligned and valid for writes of `len` values, and
+///   must not overlap `resolved`.
+/// - The serializer must be at `pos`.
+unsafe fn write_subtree<T, S, E>(
+    serializer: &mut S,
+    resolved: *const T,
+    key: *mut T,
+    len: usize,
+    pos: usize,
+) -> Result<usize, E>
+where
+    T: Relocate,
+    S: Writer<E> + Sharing<E> + ?Sized,
+{
+    let size = len * size_of::<T>();
+    // SAFETY: The caller has guaranteed that `resolved` points to `len`
+    // resolved values and that `key` is valid for writes of `len` values which
+    // don't overlap them.
+    unsafe {
+        ptr::copy_nonoverlapping(resolved, key, len);
+    }
+    // Only the copy is converted, so `base` is only used to calculate its
+    // position.
+    let base = key.cast::<u8>().wrapping_sub(pos);
+    // SAFETY: `key` holds a copy of values which were resolved at `pos`, and so
+    // have relative offsets. Their targets aren't read in `AbsoluteShallow`
+    // mode.
+    let relocated = unsafe {
+        <[T]>::relocate::<Failure>(
+            ptr::slice_from_raw_parts_mut(key, len),
+            base,
+            OffsetMode::AbsoluteShallow,
+        )
+    };
+    // SAFETY: `resolved` and `key` both point to `len` initialized values.
+    let (bytes, key) = unsafe {
+        (
+            slice::from_raw_parts(resolved.cast::<u8>(), size),
+            slice::from_raw_parts(key.cast::<u8>(), size),
+        )
+    };
+
+    // A subtree whose offsets can't be converted is written without sharing.
+    let align = align_of::<T>();
+    if relocated.is_ok() {
+        if let Some(shared_pos) = serializer.get_shared_subtree(key, align) {
+            return Ok(shared_pos);
+        }
+    }
+    serializer.write(bytes)?;
+    if relocated.is_ok() {
+        serializer.add_shared_subtree(key, align, pos)?;
+    }
+    Ok(pos)
+}
+
+fn share_slice<T, S, E>(
+    serializer: &mut S,
+    values: &[T],
+    resolvers: &mut SerVec<T::Resolver>,
+    resolved: &mut SerVec<MaybeUninit<T::Archived>>,
+    key: &mut SerVec<MaybeUninit<T::Archived>>,
+) -> Result<usize, E>
+where
+    T: Archive,
+    T::Archived: Relocate,
+    S: Writer<E> + Sharing<E> + ?Sized,
+{
+    let pos = serializer.align_for::<T::Archived>()?;
+    let resolved_ptr = resolved.as_mut_ptr().cast::<T::Archived>();
+    // SAFETY: `resolved` has a capacity of `values.len()`, so it is valid for
+    // writes of `values.len()` archived values.
+    unsafe {
+        ptr::write_bytes(resolved_ptr, 0, values.len());
+    }
+    for (i, (value, resolver)) in
+        values.iter().zip(resolvers.drain()).enumerate()
+    {
+        // SAFETY: `resolved_ptr.add(i)` points to a zeroed `T::Archived` in
+        // `resolved`, and so is properly aligned, dereferenceable, and all of
+        // its bytes are initialized.
+        let out = unsafe {
+            Place::new_unchecked(
+                pos + i * size_of::<T::Archived>(),
+                resolved_ptr.add(i),
+            )
+        };
+        value.resolve(resolver, out);
+    }
+
+    // SAFETY: Every element of `resolved` was resolved at its position after
+    // `pos`, which the serializer is at. `key` is a separate buffer with a
+    // capacity of `values.len()`.
+    unsafe {
+        write_subtree(
+            serializer,
+            resolved_ptr,
+            key.as_mut_ptr().cast(),
+            values.len(),
+            pos,
+        )
+    }
+}
+
+fn resolve_slice<T, S, E>(
+    serializer: &mut S,
+    values: &[T],
+    resolvers: &mut SerVec<T::Resolver>,
+) -> Result<usize, E>
+where
+    T: Archive,
+    S: Writer<E> + ?Sized,
+{
+    let pos = serializer.align_for::<T::Archived>()?;
+    for (value, resolver) in values.iter().zip(resolvers.drain()) {
+        // SAFETY: `resolver` is the result of serializing `value`, and the
+        // serializer is aligned for `T::Archived` because it was aligned
+        // before the first element and each element is a multiple of the
+        // alignment in size.
+        unsafe {
+            serializer.resolve_aligned(value, resolver)?;
+        }
+    }
+    Ok(pos)
+}
@@ -1,8 +1,10 @@
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+use rancor::{fail, Source};
+
 use crate::{
-    ser::{Positional, Writer},
+    ser::{allocator::OutOfMemory, writer::OverflowError, Positional, Writer},
     util::AlignedVec,
 };
 
@@ -13,11 +15,21 @@ impl Positional for Vec<u8> {
     }
 }
 
-impl<E> Writer<E> for Vec<u8> {
+impl<E: Source> Writer<E> for Vec<u8> {
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if self.try_reserve(bytes.len()).is_err() {
+            fail!(OutOfMemory {
+                size: self.len().saturating_add(bytes.len()),
+                align: 1,
+            });
+        }
         self.extend_from_slice(bytes);
         Ok(())
     }
+
+    fn overflow(&self, error: OverflowError) -> E {
+        E::new(error)
+    }
 }
 
 impl<const A: usize> Positional for AlignedVec<A> {
@@ -27,9 +39,15 @@ impl<const A: usize> Positional for AlignedVec<A> {
     }
 }
 
-impl<E, const A: usize> Writer<E> for AlignedVec<A> {
+impl<E: Source, const A: usize> Writer<E> for AlignedVec<A> {
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
-        self.extend_from_slice(bytes);
-        Ok(())
+        match self.try_extend_from_slice(bytes) {
+            Ok(()) => Ok(()),
+            Err(e) => fail!(e),
+        }
+    }
+
+    fn overflow(&self, error: OverflowError) -> E {
+        E::new(error)
     }
 }
@@ -9,7 +9,7 @@ use core::{
 
 use rancor::{fail, Source};
 
-use crate::ser::{Positional, Writer};
+use crate::ser::{writer::OverflowError, Positional, Writer};
 
 #[derive(Debug)]
 struct BufferOverflow {
@@ -124,6 +124,18 @@ impl<'a> From<&'a mut [MaybeUninit<u8>]> for Buffer<'a> {
     }
 }
 
+impl<'a> Buffer<'a> {
+    /// Consumes the buffer and returns the bytes that have been written to it.
+    ///
+    /// Unlike dereferencing the buffer, the returned slice borrows from the
+    /// underlying bytes instead of the `Buffer`.
+    pub fn into_slice(self) -> &'a mut [u8] {
+        // SAFETY: The first `len` bytes of the buffer have been initialized by
+        // writes, and the buffer mutably borrows them for `'a`.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
 impl Deref for Buffer<'_> {
     type Target = [u8];
 
@@ -165,4 +177,123 @@ impl<E: Source> Writer<E> for Buffer<'_> {
             Ok(())
         }
     }
+
+    fn overflow(&self, error: OverflowError) -> E {
+        E::new(error)
+    }
+}
+
+/// The error returned when a [`SizingBuffer`] is too small to hold an archive.
+#[derive(Debug)]
+pub struct BufferTooSmall {
+    /// The number of bytes needed to hold the archive.
+    pub needed: usize,
+    /// The capacity of the buffer.
+    pub capacity: usize,
+}
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer too small (needed {} bytes, capacity is {})",
+            self.needed, self.capacity,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BufferTooSmall {}
+
+/// Wraps a byte buffer and equips it with [`Writer`], measuring the total
+/// size of the output even if it does not fit.
+///
+/// Once a write overflows the buffer, later writes only advance the position
+/// of the writer. This allows serialization to complete so that
+/// [`finish`](SizingBuffer::finish) can report how large the buffer needs to
+/// be.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     ser::writer::SizingBuffer,
+///     util::{serialize_into, Align},
+/// };
+///
+/// let mut bytes = Align([0u8; 4]);
+/// let writer = serialize_into::<_, Error>(
+///     &[1u32, 2, 3],
+///     SizingBuffer::from(&mut *bytes),
+/// )
+/// .unwrap();
+/// let error = writer.finish().unwrap_err();
+/// assert_eq!(error.needed, 12);
+/// assert_eq!(error.capacity, 4);
+/// ```
+#[derive(Debug)]
+pub struct SizingBuffer<'a> {
+    buffer: Buffer<'a>,
+    pos: usize,
+}
+
+impl<'a> SizingBuffer<'a> {
+    /// Returns whether the output has overflowed the buffer.
+    #[inline]
+    pub fn is_overflowed(&self) -> bool {
+        self.pos > self.buffer.len
+    }
+
+    /// Consumes the writer and returns the bytes that were written to it, or
+    /// an error containing the size of the output if it did not fit.
+    pub fn finish(self) -> Result<&'a mut [u8], BufferTooSmall> {
+        if self.is_overflowed() {
+            Err(BufferTooSmall {
+                needed: self.pos,
+                capacity: self.buffer.cap,
+            })
+        } else {
+            Ok(self.buffer.into_slice())
+        }
+    }
+}
+
+impl<'a, T> From<T> for SizingBuffer<'a>
+where
+    Buffer<'a>: From<T>,
+{
+    fn from(bytes: T) -> Self {
+        Self {
+            buffer: Buffer::from(bytes),
+            pos: 0,
+        }
+    }
+}
+
+impl Positional for SizingBuffer<'_> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E> Writer<E> for SizingBuffer<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if !self.is_overflowed()
+            && bytes.len() <= self.buffer.cap - self.buffer.len
+        {
+            // SAFETY: We just checked that there is enough capacity remaining
+            // in the buffer for `bytes`.
+            unsafe {
+                copy_nonoverlapping(
+                    bytes.as_ptr(),
+                    self.buffer.ptr.as_ptr().add(self.buffer.len),
+                    bytes.len(),
+                );
+            }
+            self.buffer.len += bytes.len();
+        }
+        self.pos += bytes.len();
+        Ok(())
+    }
 }
@@ -6,13 +6,69 @@ mod core;
 #[cfg(feature = "std")]
 mod std;
 
-use ::core::mem;
-use rancor::{Fallible, Strategy};
+use ::core::{fmt, mem};
+use rancor::{Failure, Fallible, Strategy};
 
 pub use self::core::*;
 #[cfg(feature = "std")]
 pub use self::std::*;
-use crate::{Archive, ArchiveUnsized, Place, RelPtr};
+use crate::{
+    primitive::{ArchivedIsize, FixedUsize},
+    rel_ptr::{signed_offset, Offset},
+    Archive, ArchiveUnsized, Place, RelPtr,
+};
+
+/// A value which doesn't fit in the archived pointer width.
+///
+/// Serialize implementations report these through [`Writer::overflow`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverflowError {
+    /// A length exceeds the range of a [`FixedUsize`].
+    Length(usize),
+    /// The offset from a relative pointer to its target doesn't fit in the
+    /// offset type of the relative pointer.
+    Offset {
+        /// The position of the relative pointer, or the earliest position it
+        /// can be written at.
+        from: usize,
+        /// The position of the target.
+        to: usize,
+        /// The size of the offset type in bytes.
+        offset_size: usize,
+    },
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverflowError::Length(len) => write!(
+                f,
+                "length {} exceeds the archived pointer width of {} bits; \
+                 enable the `pointer_width_64` feature to archive longer \
+                 values",
+                len,
+                mem::size_of::<FixedUsize>() * 8,
+            ),
+            OverflowError::Offset {
+                from,
+                to,
+                offset_size,
+            } => write!(
+                f,
+                "the relative pointer offset from position {} to position {} \
+                 does not fit in a {}-bit offset; the archive may be too \
+                 large for the configured pointer width",
+                from,
+                to,
+                offset_size * 8,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for OverflowError {}
 
 /// A writer that knows its current position.
 pub trait Positional {
@@ -41,6 +97,17 @@ where
 pub trait Writer<E = <Self as Fallible>::Error>: Positional {
     /// Attempts to write the given bytes to the serializer.
     fn write(&mut self, bytes: &[u8]) -> Result<(), E>;
+
+    /// Returns the error to report for a value which doesn't fit in the
+    /// archived pointer width.
+    ///
+    /// `resolve` can't return errors, so serialize implementations check
+    /// lengths and relative pointer offsets ahead of time and report overflows
+    /// through the writer. The default implementation panics with `error`. The
+    /// writers provided by rkyv return it as an error instead.
+    fn overflow(&self, error: OverflowError) -> E {
+        panic!("{}", error)
+    }
 }
 
 impl<T, E> Writer<E> for Strategy<T, E>
@@ -50,16 +117,55 @@ where
     fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
         T::write(self, bytes)
     }
+
+    fn overflow(&self, error: OverflowError) -> E {
+        T::overflow(self, error)
+    }
 }
 
 /// TODO: Document
 pub trait WriterExt<E>: Writer<E> {
+    /// Checks that the given length fits in the archived pointer width.
+    ///
+    /// Returns the error from [`Writer::overflow`] if it doesn't.
+    fn check_len(&self, len: usize) -> Result<(), E> {
+        if FixedUsize::try_from(len).is_err() {
+            return Err(self.overflow(OverflowError::Length(len)));
+        }
+        Ok(())
+    }
+
+    /// Checks that a relative pointer with an offset of type `O` written at or
+    /// after the current position can point to `to`.
+    ///
+    /// Targets are written before the relative pointers to them, so the offset
+    /// only grows as more bytes are written. Returns the error from
+    /// [`Writer::overflow`] if the offset from the current position already
+    /// doesn't fit.
+    fn check_offset<O: Offset>(&self, to: usize) -> Result<(), E> {
+        let from = self.pos();
+        if signed_offset::<Failure>(from, to)
+            .and_then(O::from_isize::<Failure>)
+            .is_err()
+        {
+            return Err(self.overflow(OverflowError::Offset {
+                from,
+                to,
+                offset_size: mem::size_of::<O>(),
+            }));
+        }
+        Ok(())
+    }
+
     /// Advances the given number of bytes as padding.
-    fn pad(&mut self, padding: usize) -> Result<(), E> {
+    fn pad(&mut self, mut padding: usize) -> Result<(), E> {
         const MAX_ZEROES: usize = 32;
         const ZEROES: [u8; MAX_ZEROES] = [0; MAX_ZEROES];
-        debug_assert!(padding < MAX_ZEROES);
 
+        while padding > MAX_ZEROES {
+            self.write(&ZEROES)?;
+            padding -= MAX_ZEROES;
+        }
         self.write(&ZEROES[0..padding])
     }
 
@@ -122,6 +228,7 @@ pub trait WriterExt<E>: Writer<E> {
             from & (mem::align_of::<RelPtr<T::Archived>>() - 1),
             0
         );
+        self.check_offset::<ArchivedIsize>(to)?;
 
         let mut resolved = mem::MaybeUninit::<RelPtr<T::Archived>>::zeroed();
         // SAFETY: `resolved.as_mut_ptr()` points to a local zeroed
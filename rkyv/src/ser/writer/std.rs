@@ -2,7 +2,7 @@ use std::io;
 
 use rancor::{ResultExt as _, Source};
 
-use crate::ser::{Positional, Writer};
+use crate::ser::{writer::OverflowError, Positional, Writer};
 
 /// Wraps a type that implements [`io::Write`](std::io::Write) and equips it
 /// with [`Writer`].
@@ -59,4 +59,8 @@ impl<W: io::Write, E: Source> Writer<E> for IoWriter<W> {
         self.pos += bytes.len();
         Ok(())
     }
+
+    fn overflow(&self, error: OverflowError) -> E {
+        E::new(error)
+    }
 }
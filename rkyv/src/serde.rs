@@ -0,0 +1,68 @@
+//! Serializing archived types with serde.
+//!
+//! Archived types can be serialized with serde without deserializing them into
+//! their native types first. This is useful for dumping the contents of an
+//! archive to a human-readable format like JSON for debugging.
+//!
+//! Many archived types contain primitives from [`rend`](crate::rend), which
+//! can't implement `serde::Serialize` from rkyv. Instead, archived types
+//! implement [`SerializeArchived`], and can be serialized with serde by
+//! wrapping them in [`AsSerde`]. Types which derive `Archive` can also
+//! implement `serde::Serialize` directly on their archived types with
+//! `#[archive(derive_serde)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{rancor::Error, serde::AsSerde, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(derive_serde)]
+//! struct Example {
+//!     id: u32,
+//!     tags: Vec<String>,
+//! }
+//!
+//! let value = Example {
+//!     id: 42,
+//!     tags: vec!["a".to_string(), "b".to_string()],
+//! };
+//! let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+//! let archived = unsafe { rkyv::access_unchecked::<ArchivedExample>(&bytes) };
+//!
+//! # fn check<T: rkyv::serde::Serialize>(_: &T) {}
+//! // `ArchivedExample` implements `serde::Serialize`
+//! check(archived);
+//! // Other archived types can be wrapped in `AsSerde`
+//! check(&AsSerde(&archived.tags));
+//! ```
+
+#[doc(no_inline)]
+pub use ::serde::{ser, Serialize, Serializer};
+
+/// An archived type which can be serialized with serde.
+///
+/// This is implemented for archived types instead of `serde::Serialize` so
+/// that it can be implemented for archived primitives. Wrap a value in
+/// [`AsSerde`] to serialize it with serde.
+pub trait SerializeArchived {
+    /// Serializes this archived value with the given serializer.
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+/// A wrapper which implements `serde::Serialize` for archived types.
+#[derive(Debug)]
+pub struct AsSerde<'a, T: ?Sized>(pub &'a T);
+
+impl<T: SerializeArchived + ?Sized> Serialize for AsSerde<'_, T> {
+    #[inline]
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_archived(serializer)
+    }
+}
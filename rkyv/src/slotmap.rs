@@ -0,0 +1,246 @@
+//! Archived versions of `slotmap` types.
+
+use core::{fmt, iter::FusedIterator, marker::PhantomData, slice};
+
+use munge::munge;
+use rancor::Fallible;
+use slotmap::{Key, KeyData};
+
+use crate::{
+    option::ArchivedOption,
+    primitive::{fixed_usize, ArchivedIsize, ArchivedU32, ArchivedUsize},
+    ser::{Allocator, Writer, WriterExt as _},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+/// An archived `SlotMap` or `SecondaryMap`.
+///
+/// The slots of the map are archived in order, so values can be looked up by
+/// the same keys as the map that was serialized. Deserializing preserves the
+/// keys of all values in the map, but not the versions of vacant slots. Keys
+/// which were removed before serializing may be reused by the deserialized
+/// map.
+///
+/// `SlotMap` can't insert values with specific keys, so deserializing one
+/// replays the insertions and removals needed to recreate its keys. This takes
+/// time proportional to the versions of the keys. Deserializing a
+/// `SecondaryMap` doesn't have this cost, and archived slot maps can be
+/// deserialized as either type.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedSlotMap<K, V> {
+    slots: ArchivedVec<ArchivedSlot<V>>,
+    len: ArchivedUsize,
+    _phantom: PhantomData<K>,
+}
+
+/// A slot of an [`ArchivedSlotMap`].
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+pub struct ArchivedSlot<V> {
+    version: ArchivedU32,
+    value: ArchivedOption<V>,
+}
+
+impl<V> ArchivedSlot<V> {
+    /// Returns the value in the slot, if it is occupied.
+    #[inline]
+    pub fn value(&self) -> Option<&V> {
+        self.value.as_ref()
+    }
+}
+
+/// Splits a key into its slot index and version.
+fn split_key(key: KeyData) -> (u32, u32) {
+    let ffi = key.as_ffi();
+    (ffi as u32, (ffi >> 32) as u32)
+}
+
+/// Returns the key for the slot at the given position.
+fn slot_key<K: Key>(position: usize, version: u32) -> K {
+    // Slot indices start at 1, since the slot at index 0 is a sentinel.
+    let ffi = (u64::from(version) << 32) | (position as u64 + 1);
+    KeyData::from_ffi(ffi).into()
+}
+
+impl<K: Key, V> ArchivedSlotMap<K, V> {
+    /// Returns the number of elements in the map.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns `true` if the map contains no elements.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the archived slots of the map.
+    ///
+    /// The slot at position `i` is the slot at index `i + 1` of the original
+    /// map. Vacant slots are always archived with a version of zero.
+    #[inline]
+    pub fn slots(&self) -> &[ArchivedSlot<V>] {
+        self.slots.as_slice()
+    }
+
+    /// Returns `true` if the map contains a value for the given key.
+    #[inline]
+    pub fn contains_key(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the value corresponding to the given key.
+    pub fn get(&self, key: K) -> Option<&V> {
+        let (index, version) = split_key(key.data());
+        let slot = self.slots.get((index as usize).checked_sub(1)?)?;
+        if slot.version.to_native() == version {
+            slot.value()
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the key-value pairs of the map, in order of
+    /// their slots.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            slots: self.slots.iter().enumerate(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Resolves an archived slot map from the given length and resolver.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: SlotMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedSlotMap { slots, len: out_len, _phantom: _ } = out);
+        ArchivedVec::resolve_from_len(
+            resolver.slots_len,
+            resolver.slots,
+            slots,
+        );
+        out_len.write(ArchivedUsize::from_native(fixed_usize(len)));
+    }
+
+    /// Serializes an iterator of key-value pairs as a slot map.
+    ///
+    /// The iterator must yield its keys in order of their slot indices.
+    pub fn serialize_from_iter<'a, I, UV, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<SlotMapResolver, S::Error>
+    where
+        I: Clone + IntoIterator<Item = (K, &'a UV)>,
+        UV: 'a + Serialize<S, Archived = V>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        use crate::util::SerVec;
+
+        let mut len = 0;
+        let mut slots_len = 0;
+        for (key, _) in iter.clone() {
+            len += 1;
+            slots_len = split_key(key.data()).0 as usize;
+        }
+
+        // The length is never greater than the number of slots.
+        serializer.check_len(slots_len)?;
+
+        SerVec::with_capacity(serializer, len, |resolvers, serializer| {
+            for (_, value) in iter.clone() {
+                resolvers.push(value.serialize(serializer)?);
+            }
+
+            let pos = serializer.align_for::<ArchivedSlot<V>>()?;
+            let mut entries =
+                iter.into_iter().zip(resolvers.drain()).peekable();
+            for index in 1..=slots_len {
+                let next = entries.next_if(|((key, _), _)| {
+                    split_key(key.data()).0 as usize == index
+                });
+                let (slot, resolver) = match next {
+                    Some(((key, value), resolver)) => (
+                        SlotAdapter {
+                            version: split_key(key.data()).1,
+                            value: Some(value),
+                        },
+                        Some(resolver),
+                    ),
+                    None => (
+                        SlotAdapter {
+                            version: 0,
+                            value: None,
+                        },
+                        None,
+                    ),
+                };
+                unsafe {
+                    serializer.resolve_aligned(&slot, resolver)?;
+                }
+            }
+            serializer.check_offset::<ArchivedIsize>(pos)?;
+
+            Ok(SlotMapResolver {
+                slots: VecResolver::from_pos(pos),
+                slots_len,
+            })
+        })?
+    }
+}
+
+impl<K: Key, V: fmt::Debug> fmt::Debug for ArchivedSlotMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the key-value pairs of an [`ArchivedSlotMap`].
+pub struct Iter<'a, K, V> {
+    slots: core::iter::Enumerate<slice::Iter<'a, ArchivedSlot<V>>>,
+    _phantom: PhantomData<K>,
+}
+
+impl<'a, K: Key, V> Iterator for Iter<'a, K, V> {
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots.find_map(|(position, slot)| {
+            let value = slot.value()?;
+            Some((slot_key(position, slot.version.to_native()), value))
+        })
+    }
+}
+
+impl<K: Key, V> FusedIterator for Iter<'_, K, V> {}
+
+/// The resolver for an [`ArchivedSlotMap`].
+pub struct SlotMapResolver {
+    slots: VecResolver,
+    slots_len: usize,
+}
+
+struct SlotAdapter<'a, V> {
+    version: u32,
+    value: Option<&'a V>,
+}
+
+impl<V: Archive> Archive for SlotAdapter<'_, V> {
+    type Archived = ArchivedSlot<V::Archived>;
+    type Resolver = Option<V::Resolver>;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedSlot { version, value } = out);
+        self.version.resolve((), version);
+        ArchivedOption::resolve_from_option(self.value, resolver, value);
+    }
+}
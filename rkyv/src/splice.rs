@@ -0,0 +1,376 @@
+//! Re-serializing archived values without deserializing them.
+//!
+//! [`Splice`] writes a copy of an archived value into a new archive. Bytes
+//! which don't contain pointers, like the contents of strings and vectors of
+//! primitives, are copied verbatim from the original archive, and only the
+//! pointers which refer to other parts of the archive are rewritten. This is
+//! much cheaper than deserializing a value and serializing it again.
+//!
+//! [`ArchiveEditor`] uses this to edit existing archives: selected subtrees are
+//! replaced with new values, and everything else is spliced from the original
+//! archive. Only the replaced subtrees are ever deserialized or serialized.
+//!
+//! `Splice` is implemented for primitives, strings, boxes, vectors, and
+//! options, and can be implemented for structs by deriving `Archive` with
+//! `#[archive(splice)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     access_unchecked, rancor::Error, splice::ArchiveEditor, to_bytes,
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(splice)]
+//! struct Document {
+//!     title: String,
+//!     revision: u32,
+//!     pages: Vec<String>,
+//! }
+//!
+//! let value = Document {
+//!     title: "First draft".to_string(),
+//!     revision: 1,
+//!     pages: vec!["Lorem ipsum dolor sit amet".to_string(); 100],
+//! };
+//! let bytes = to_bytes::<Error>(&value).unwrap();
+//! let archived = unsafe { access_unchecked::<ArchivedDocument>(&bytes) };
+//!
+//! let mut editor = ArchiveEditor::<Document, Error>::new(archived);
+//! editor
+//!     .replace(|doc| &doc.title, "Second draft".to_string())
+//!     .update(|doc| &doc.revision, |revision: &mut u32| *revision += 1)
+//!     .unwrap();
+//! let edited = editor.finish().unwrap();
+//!
+//! let archived = unsafe { access_unchecked::<ArchivedDocument>(&edited) };
+//! assert_eq!(archived.title, "Second draft");
+//! assert_eq!(archived.revision, 2);
+//! assert_eq!(archived.pages.len(), 100);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt,
+    mem::{size_of, MaybeUninit},
+};
+
+use rancor::{fail, Fallible, Source, Strategy};
+
+use crate::{
+    de::Pool,
+    ser::{
+        sharing::Share, DefaultSerializer, Serializer, Writer, WriterExt as _,
+    },
+    util::{deserialize, with_arena, AlignedVec},
+    Archive, Deserialize, Place, Serialize,
+};
+
+/// A type whose archived values can be re-serialized without deserializing
+/// them.
+///
+/// Implementations should splice their children with [`Edits::splice`] and
+/// resolve them with [`Edits::resolve`] so that any of them can be replaced by
+/// an [`ArchiveEditor`].
+///
+/// This can be implemented for structs by deriving `Archive` with
+/// `#[archive(splice)]`.
+pub trait Splice<S: Fallible + ?Sized>: Archive {
+    /// The resolver for a spliced value.
+    type SpliceResolver;
+
+    /// Writes the dependencies of the archived value to the serializer and
+    /// returns a resolver which can create a copy of it.
+    fn splice(
+        archived: &Self::Archived,
+        serializer: &mut S,
+        edits: &mut Edits<'_, S>,
+    ) -> Result<Self::SpliceResolver, S::Error>;
+
+    /// Creates a copy of the archived value at the output location using the
+    /// resolver returned by [`splice`](Splice::splice).
+    fn resolve_spliced(
+        archived: &Self::Archived,
+        resolver: Self::SpliceResolver,
+        out: Place<Self::Archived>,
+        edits: &mut Edits<'_, S>,
+    );
+}
+
+/// A replacement value which can be serialized in place of an archived value.
+trait Replace<S: Fallible + ?Sized> {
+    fn serialize(&mut self, serializer: &mut S) -> Result<(), S::Error>;
+
+    /// # Safety
+    ///
+    /// `out` must be properly aligned and valid for writes of the archived
+    /// replacement, and `serialize` must have been called first.
+    unsafe fn resolve(&mut self, pos: usize, out: *mut u8);
+}
+
+struct Replacement<U: Archive> {
+    value: U,
+    resolver: Option<U::Resolver>,
+}
+
+impl<U: Serialize<S>, S: Fallible + ?Sized> Replace<S> for Replacement<U> {
+    fn serialize(&mut self, serializer: &mut S) -> Result<(), S::Error> {
+        self.resolver = Some(self.value.serialize(serializer)?);
+        Ok(())
+    }
+
+    unsafe fn resolve(&mut self, pos: usize, out: *mut u8) {
+        let resolver = self.resolver.take().unwrap();
+        // SAFETY: The caller has guaranteed that `out` is properly aligned
+        // and valid for writes of a `U::Archived`. It points into a copy of
+        // the original archived value, so all of its bytes are initialized.
+        let out = unsafe { Place::new_unchecked(pos, out.cast()) };
+        self.value.resolve(resolver, out);
+    }
+}
+
+struct Edit<'a, S: Fallible + ?Sized> {
+    address: usize,
+    size: usize,
+    replacement: &'a mut dyn Replace<S>,
+    used: bool,
+}
+
+/// The replacements to make while splicing an archived value.
+pub struct Edits<'a, S: Fallible + ?Sized> {
+    edits: Vec<Edit<'a, S>>,
+}
+
+/// The resolver for a value spliced with [`Edits::splice`].
+pub struct Spliced<R>(SplicedResolver<R>);
+
+enum SplicedResolver<R> {
+    Spliced(R),
+    Replaced(usize),
+}
+
+impl<S: Fallible + ?Sized> Edits<'_, S> {
+    /// Returns an empty set of edits, which splices values without changing
+    /// them.
+    pub fn none() -> Self {
+        Self { edits: Vec::new() }
+    }
+
+    fn find(&self, address: usize, size: usize) -> Option<usize> {
+        self.edits.iter().position(|edit| {
+            !edit.used && edit.address == address && edit.size == size
+        })
+    }
+
+    /// Returns whether any of the bytes in the given range will be replaced.
+    ///
+    /// Types can use this to copy values which contain no pointers verbatim
+    /// when none of their contents are replaced.
+    pub fn has_edits_within(&self, bytes: &[u8]) -> bool {
+        let range = bytes.as_ptr_range();
+        self.edits.iter().any(|edit| {
+            !edit.used
+                && edit.address >= range.start as usize
+                && edit.address + edit.size <= range.end as usize
+        })
+    }
+
+    /// Splices an archived value, or serializes its replacement if it has
+    /// one.
+    pub fn splice<T: Splice<S>>(
+        &mut self,
+        archived: &T::Archived,
+        serializer: &mut S,
+    ) -> Result<Spliced<T::SpliceResolver>, S::Error> {
+        let address = archived as *const T::Archived as usize;
+        if let Some(index) = self.find(address, size_of::<T::Archived>()) {
+            let edit = &mut self.edits[index];
+            edit.used = true;
+            edit.replacement.serialize(serializer)?;
+            Ok(Spliced(SplicedResolver::Replaced(index)))
+        } else {
+            let resolver = T::splice(archived, serializer, self)?;
+            Ok(Spliced(SplicedResolver::Spliced(resolver)))
+        }
+    }
+
+    /// Resolves a value spliced with [`splice`](Edits::splice) at the output
+    /// location.
+    pub fn resolve<T: Splice<S>>(
+        &mut self,
+        archived: &T::Archived,
+        resolver: Spliced<T::SpliceResolver>,
+        out: Place<T::Archived>,
+    ) {
+        match resolver.0 {
+            SplicedResolver::Spliced(resolver) => {
+                T::resolve_spliced(archived, resolver, out, self)
+            }
+            SplicedResolver::Replaced(index) => {
+                // SAFETY: The replacement was found at the address of a
+                // `T::Archived` with the same size, and `out` is a place for
+                // a `T::Archived`.
+                unsafe {
+                    self.edits[index]
+                        .replacement
+                        .resolve(out.pos(), out.ptr().cast());
+                }
+            }
+        }
+    }
+
+    /// Resolves a value spliced with [`splice`](Edits::splice) and writes it
+    /// to the serializer.
+    ///
+    /// Returns the position of the written value. The serializer must be
+    /// aligned for a `T::Archived`.
+    pub fn resolve_aligned<T: Splice<S>>(
+        &mut self,
+        archived: &T::Archived,
+        resolver: Spliced<T::SpliceResolver>,
+        serializer: &mut S,
+    ) -> Result<usize, S::Error>
+    where
+        S: Writer,
+    {
+        let pos = serializer.pos();
+        let mut resolved = MaybeUninit::<T::Archived>::uninit();
+        // SAFETY: `archived` is a valid `T::Archived`, so copying it
+        // initializes all of the bytes of `resolved`.
+        unsafe {
+            resolved.as_mut_ptr().copy_from_nonoverlapping(archived, 1);
+        }
+        // SAFETY: `resolved` is a local `MaybeUninit`, so it is properly
+        // aligned and dereferenceable. All of its bytes were initialized.
+        let out = unsafe { Place::new_unchecked(pos, resolved.as_mut_ptr()) };
+        self.resolve::<T>(archived, resolver, out);
+        serializer.write(out.as_slice())?;
+        Ok(pos)
+    }
+}
+
+/// An editor which replaces parts of an existing archive.
+///
+/// Replacements are selected by returning a reference to the archived value
+/// to replace. When the editor is [finished](ArchiveEditor::finish), the
+/// archive is spliced into a new buffer with the selected values replaced.
+/// Everything else is copied from the original archive without being
+/// deserialized.
+///
+/// Only values which are spliced with [`Edits::splice`] can be replaced. This
+/// includes the fields of derived structs and the elements of vectors, boxes,
+/// and options.
+pub struct ArchiveEditor<'a, T: Archive, E> {
+    archived: &'a T::Archived,
+    replacements: Vec<(usize, usize, BoxedReplace<'a, E>)>,
+}
+
+type BoxedReplace<'a, E> =
+    Box<dyn for<'b> Replace<DefaultSerializer<'b, AlignedVec, E>> + 'a>;
+
+impl<'a, T: Archive, E: Source> ArchiveEditor<'a, T, E> {
+    /// Creates a new editor for the given archived value.
+    pub fn new(archived: &'a T::Archived) -> Self {
+        Self {
+            archived,
+            replacements: Vec::new(),
+        }
+    }
+
+    /// Replaces the selected archived value with a new value.
+    pub fn replace<U>(
+        &mut self,
+        select: impl FnOnce(&'a T::Archived) -> &'a U::Archived,
+        value: U,
+    ) -> &mut Self
+    where
+        U: for<'b> Serialize<DefaultSerializer<'b, AlignedVec, E>> + 'a,
+    {
+        let address = select(self.archived) as *const U::Archived as usize;
+        self.replacements.push((
+            address,
+            size_of::<U::Archived>(),
+            Box::new(Replacement {
+                value,
+                resolver: None,
+            }),
+        ));
+        self
+    }
+
+    /// Deserializes the selected archived value, updates it, and replaces the
+    /// archived value with the result.
+    pub fn update<U>(
+        &mut self,
+        select: impl FnOnce(&'a T::Archived) -> &'a U::Archived,
+        f: impl FnOnce(&mut U),
+    ) -> Result<&mut Self, E>
+    where
+        U: for<'b> Serialize<DefaultSerializer<'b, AlignedVec, E>> + 'a,
+        U::Archived: Deserialize<U, Strategy<Pool, E>>,
+    {
+        let archived = select(self.archived);
+        let mut value = deserialize::<U, _, E>(archived, &mut Pool::new())?;
+        f(&mut value);
+        Ok(self.replace(|_| archived, value))
+    }
+
+    /// Splices the archive with the replacements and returns the new bytes.
+    ///
+    /// Returns an error if any of the selected values were not found while
+    /// splicing the archive.
+    pub fn finish(mut self) -> Result<AlignedVec, E>
+    where
+        T: for<'b> Splice<DefaultSerializer<'b, AlignedVec, E>>,
+    {
+        with_arena(|arena| {
+            let mut edits = Edits {
+                edits: self
+                    .replacements
+                    .iter_mut()
+                    .map(|(address, size, replacement)| Edit {
+                        address: *address,
+                        size: *size,
+                        replacement: &mut **replacement,
+                        used: false,
+                    })
+                    .collect(),
+            };
+
+            let mut serializer = Serializer::new(
+                AlignedVec::new(),
+                arena.acquire(),
+                Share::new(),
+            );
+            let strategy = Strategy::<_, E>::wrap(&mut serializer);
+            let resolver = edits.splice::<T>(self.archived, strategy)?;
+            strategy.align_for::<T::Archived>()?;
+            edits.resolve_aligned::<T>(self.archived, resolver, strategy)?;
+
+            if edits.edits.iter().any(|edit| !edit.used) {
+                fail!(UnusedReplacementError);
+            }
+
+            Ok(serializer.into_writer())
+        })
+    }
+}
+
+#[derive(Debug)]
+struct UnusedReplacementError;
+
+impl fmt::Display for UnusedReplacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a replaced value was not found while splicing the archive; only \
+             values spliced with `Edits::splice` can be replaced"
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnusedReplacementError {}
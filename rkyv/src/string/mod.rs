@@ -10,14 +10,19 @@ use core::{
         RangeToInclusive,
     },
     pin::Pin,
-    str,
+    ptr, str,
 };
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 use repr::{ArchivedStringRepr, INLINE_CAPACITY};
 
-use crate::{Place, Portable, SerializeUnsized};
+use crate::{
+    endian::SwapEndian,
+    relocate::{OffsetMode, Relocate},
+    ser::{Sharing, SharingExt as _, Writer, WriterExt as _},
+    Place, Portable, SerializeUnsized,
+};
 
 /// An archived [`String`].
 ///
@@ -73,6 +78,19 @@ impl ArchivedString {
         }
     }
 
+    /// Returns whether the string is a niche which no valid string uses.
+    #[inline]
+    pub(crate) fn is_niche(&self) -> bool {
+        self.repr.is_niche()
+    }
+
+    /// Emplaces a niche which no valid string uses.
+    #[inline]
+    pub(crate) fn emplace_niche(out: Place<Self>) {
+        munge!(let ArchivedString { repr } = out);
+        ArchivedStringRepr::emplace_niche(repr);
+    }
+
     /// Serializes an archived string from a given `str`.
     pub fn serialize_from_str<S: Fallible + ?Sized>(
         value: &str,
@@ -89,6 +107,50 @@ impl ArchivedString {
             })
         }
     }
+
+    /// Serializes an archived string from a given `str`, sharing its bytes
+    /// with any previously-serialized string with the same contents.
+    ///
+    /// Whether bytes are actually shared depends on the [`Sharing`]
+    /// implementation of the serializer.
+    pub fn serialize_from_str_shared<S>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<StringResolver, S::Error>
+    where
+        S: Fallible + Writer + Sharing + ?Sized,
+    {
+        if value.len() <= INLINE_CAPACITY {
+            Ok(StringResolver { pos: 0 })
+        } else {
+            serializer.check_len(value.len())?;
+            Ok(StringResolver {
+                pos: serializer.serialize_shared_bytes(value.as_bytes())?,
+            })
+        }
+    }
+
+    /// Serializes an archived string from a given `str`, pointing into the
+    /// bytes of a previously-serialized string if `value` lies within it.
+    ///
+    /// Whether bytes are actually shared depends on the [`Sharing`]
+    /// implementation of the serializer.
+    pub fn serialize_from_str_subslice<S>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<StringResolver, S::Error>
+    where
+        S: Fallible + Writer + Sharing + ?Sized,
+    {
+        if value.len() <= INLINE_CAPACITY {
+            Ok(StringResolver { pos: 0 })
+        } else {
+            serializer.check_len(value.len())?;
+            Ok(StringResolver {
+                pos: serializer.serialize_shared_subslice(value.as_bytes())?,
+            })
+        }
+    }
 }
 
 impl AsRef<str> for ArchivedString {
@@ -233,6 +295,38 @@ impl PartialOrd<ArchivedString> for str {
     }
 }
 
+// SAFETY: `ArchivedString` is a wrapper around an `ArchivedStringRepr`.
+unsafe impl SwapEndian for ArchivedString {
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `swap_endian`, and so does its representation.
+        unsafe {
+            ArchivedStringRepr::swap_endian(ptr::addr_of_mut!((*this).repr));
+        }
+    }
+}
+
+// SAFETY: `ArchivedString` is a wrapper around an `ArchivedStringRepr`.
+unsafe impl Relocate for ArchivedString {
+    #[inline]
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `relocate`, and so does its representation.
+        unsafe {
+            ArchivedStringRepr::relocate(
+                ptr::addr_of_mut!((*this).repr),
+                base,
+                mode,
+            )
+        }
+    }
+}
+
 /// The resolver for `String`.
 pub struct StringResolver {
     pos: usize,
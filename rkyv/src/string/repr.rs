@@ -3,10 +3,13 @@
 use core::{marker::PhantomPinned, mem, ptr, slice, str};
 
 use munge::munge;
-use rancor::{Panic, ResultExt as _, Source};
+use rancor::{fail, Failure, Panic, ResultExt as _, Source};
 
 use crate::{
+    endian::SwapEndian,
     primitive::{ArchivedUsize, FixedIsize},
+    rel_ptr::{signed_offset, OffsetOverflow},
+    relocate::{OffsetMode, Relocate},
     Place, Portable,
 };
 
@@ -35,6 +38,10 @@ struct InlineRepr {
     len: u8,
 }
 
+// Inline lengths are never greater than `INLINE_CAPACITY`, so this marks a
+// representation which no valid string uses.
+const NICHE_LEN: u8 = 0x7f;
+
 /// An archived string representation that can inline short strings.
 #[derive(Portable)]
 #[archive(crate)]
@@ -51,6 +58,13 @@ impl ArchivedStringRepr {
         unsafe { self.inline.len & 0x80 == 0 }
     }
 
+    /// Returns whether the representation is the niche written by
+    /// [`emplace_niche`](Self::emplace_niche).
+    #[inline]
+    pub fn is_niche(&self) -> bool {
+        unsafe { self.inline.len == NICHE_LEN }
+    }
+
     /// Returns the offset of the representation.
     ///
     /// # Safety
@@ -180,8 +194,29 @@ impl ArchivedStringRepr {
         }
     }
 
+    /// Emplaces a niche representation which no valid string uses.
+    ///
+    /// The niche is an inline representation with a length greater than
+    /// [`INLINE_CAPACITY`]. This function is guaranteed not to write any
+    /// uninitialized bytes to `out`.
+    #[inline]
+    pub fn emplace_niche(out: Place<Self>) {
+        // SAFETY: Every field of the union is located at the start of it.
+        let out = unsafe { out.cast_unchecked::<InlineRepr>() };
+        munge!(let InlineRepr { bytes, len } = out);
+        bytes.write([0; INLINE_CAPACITY]);
+        len.write(NICHE_LEN);
+    }
+
     /// Emplaces a new out-of-line representation for the given `str`.
     ///
+    /// # Errors
+    ///
+    /// - If the offset between `out` and `target` does not fit in an `isize` or
+    ///   exceeds the offset storage, the error reports both positions.
+    /// - If the length of `value` exceeds the range of an [`ArchivedUsize`],
+    ///   the error is the `TryFromIntError` of the failed conversion.
+    ///
     /// # Safety
     ///
     /// The length of `str` must be greater than [`INLINE_CAPACITY`].
@@ -199,8 +234,18 @@ impl ArchivedStringRepr {
             value.len().try_into().into_error()?,
         ));
 
-        let off = crate::rel_ptr::signed_offset(out.pos(), target)?;
-        offset.write((off as FixedIsize).to_le_bytes());
+        let from = out.pos();
+        let off = match signed_offset::<Failure>(from, target)
+            .and_then(|off| FixedIsize::try_from(off).into_error::<Failure>())
+        {
+            Ok(off) => off,
+            Err(_) => fail!(OffsetOverflow {
+                from,
+                to: target,
+                offset_size: OFFSET_BYTES,
+            }),
+        };
+        offset.write(off.to_le_bytes());
 
         Ok(())
     }
@@ -230,6 +275,68 @@ impl ArchivedStringRepr {
     }
 }
 
+// SAFETY: Inline strings only contain single bytes, and the offset of an
+// out-of-line string is always stored in little-endian format. The only
+// multibyte primitive which needs to be converted is the out-of-line length.
+unsafe impl SwapEndian for ArchivedStringRepr {
+    #[inline]
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes. Whether the representation is inline does not depend on
+        // endianness.
+        unsafe {
+            if !(*this).is_inline() {
+                ArchivedUsize::swap_endian(ptr::addr_of_mut!(
+                    (*this).out_of_line.len
+                ));
+            }
+        }
+    }
+}
+
+// SAFETY: Inline strings don't contain any relative pointers, and the offset
+// of an out-of-line string is converted. Absolute offsets are stored as their
+// bitwise complement so that the representation stays out-of-line.
+unsafe impl Relocate for ArchivedStringRepr {
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes.
+        unsafe {
+            if (*this).is_inline() {
+                return Ok(());
+            }
+
+            let pos = (this as usize).wrapping_sub(base as usize);
+            let offset = (*this).out_of_line_offset();
+            let (target, new_offset) = match mode {
+                OffsetMode::Absolute | OffsetMode::AbsoluteShallow => {
+                    let target = pos.wrapping_add_signed(offset);
+                    (target, !(target as isize))
+                }
+                OffsetMode::Relative => {
+                    let target = !offset as usize;
+                    (target, signed_offset::<E>(pos, target)?)
+                }
+            };
+            let new_offset = match FixedIsize::try_from(new_offset) {
+                Ok(new_offset) => new_offset,
+                Err(_) => fail!(OffsetOverflow {
+                    from: pos,
+                    to: target,
+                    offset_size: OFFSET_BYTES,
+                }),
+            };
+            ptr::addr_of_mut!((*this).out_of_line.offset)
+                .write(new_offset.to_le_bytes());
+        }
+        Ok(())
+    }
+}
+
 #[cfg(feature = "bytecheck")]
 const _: () = {
     use core::fmt;
@@ -0,0 +1,37 @@
+//! Archived versions of `task` types.
+
+use core::task::Poll;
+
+use crate::Portable;
+
+/// An archived [`Poll`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+#[archive(crate)]
+pub enum ArchivedPoll<T> {
+    /// Represents that a value is immediately ready.
+    Ready(T),
+    /// Represents that a value is not ready yet.
+    Pending,
+}
+
+impl<T> ArchivedPoll<T> {
+    /// Returns `true` if this is a [`Ready`](ArchivedPoll::Ready) value.
+    pub const fn is_ready(&self) -> bool {
+        matches!(self, ArchivedPoll::Ready(_))
+    }
+
+    /// Returns `true` if this is a [`Pending`](ArchivedPoll::Pending) value.
+    pub const fn is_pending(&self) -> bool {
+        !self.is_ready()
+    }
+
+    /// Converts from `&ArchivedPoll<T>` to `Poll<&T>`.
+    pub fn as_ref(&self) -> Poll<&T> {
+        match self {
+            ArchivedPoll::Ready(value) => Poll::Ready(value),
+            ArchivedPoll::Pending => Poll::Pending,
+        }
+    }
+}
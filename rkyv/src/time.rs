@@ -1,5 +1,7 @@
 //! Archived versions of `time` types.
 
+use core::{cmp, time::Duration};
+
 use crate::{
     primitive::{ArchivedU32, ArchivedU64},
     Portable,
@@ -28,6 +30,12 @@ const MILLIS_PER_SEC: u64 = 1_000;
 const MICROS_PER_SEC: u64 = 1_000_000;
 
 impl ArchivedDuration {
+    /// Returns true if this `ArchivedDuration` spans no time.
+    #[inline]
+    pub const fn is_zero(&self) -> bool {
+        self.as_secs() == 0 && self.subsec_nanos() == 0
+    }
+
     /// Returns the number of _whole_ seconds contained by this
     /// `ArchivedDuration`.
     ///
@@ -118,6 +126,48 @@ impl ArchivedDuration {
             + (self.subsec_nanos() as f32) / (NANOS_PER_SEC as f32)
     }
 
+    /// Returns the number of milliseconds contained by this `ArchivedDuration`
+    /// as `f64`.
+    ///
+    /// The returned value does include the fractional (nanosecond) part of the
+    /// duration.
+    #[inline]
+    pub fn as_millis_f64(&self) -> f64 {
+        (self.as_secs() as f64) * (MILLIS_PER_SEC as f64)
+            + (self.subsec_nanos() as f64) / (NANOS_PER_MILLI as f64)
+    }
+
+    /// Returns the number of milliseconds contained by this `ArchivedDuration`
+    /// as `f32`.
+    ///
+    /// The returned value does include the fractional (nanosecond) part of the
+    /// duration.
+    #[inline]
+    pub fn as_millis_f32(&self) -> f32 {
+        (self.as_secs() as f32) * (MILLIS_PER_SEC as f32)
+            + (self.subsec_nanos() as f32) / (NANOS_PER_MILLI as f32)
+    }
+
+    /// Returns this `ArchivedDuration` as a [`Duration`].
+    #[inline]
+    pub const fn to_duration(&self) -> Duration {
+        Duration::new(self.as_secs(), self.subsec_nanos())
+    }
+
+    /// Checked addition with a [`Duration`]. Computes `self + rhs`, returning
+    /// `None` if overflow occurred.
+    #[inline]
+    pub const fn checked_add(&self, rhs: Duration) -> Option<Duration> {
+        self.to_duration().checked_add(rhs)
+    }
+
+    /// Checked subtraction of a [`Duration`]. Computes `self - rhs`, returning
+    /// `None` if the result would be negative or if overflow occurred.
+    #[inline]
+    pub const fn checked_sub(&self, rhs: Duration) -> Option<Duration> {
+        self.to_duration().checked_sub(rhs)
+    }
+
     /// Constructs an archived duration at the given position.
     ///
     /// This function is guaranteed not to write any uninitialized bytes to
@@ -141,6 +191,35 @@ impl ArchivedDuration {
     }
 }
 
+impl PartialEq<Duration> for ArchivedDuration {
+    #[inline]
+    fn eq(&self, other: &Duration) -> bool {
+        self.as_secs() == other.as_secs()
+            && self.subsec_nanos() == other.subsec_nanos()
+    }
+}
+
+impl PartialEq<ArchivedDuration> for Duration {
+    #[inline]
+    fn eq(&self, other: &ArchivedDuration) -> bool {
+        other.eq(self)
+    }
+}
+
+impl PartialOrd<Duration> for ArchivedDuration {
+    #[inline]
+    fn partial_cmp(&self, other: &Duration) -> Option<cmp::Ordering> {
+        Some(self.to_duration().cmp(other))
+    }
+}
+
+impl PartialOrd<ArchivedDuration> for Duration {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedDuration) -> Option<cmp::Ordering> {
+        Some(self.cmp(&other.to_duration()))
+    }
+}
+
 #[cfg(feature = "bytecheck")]
 mod verify {
     use core::fmt;
@@ -189,3 +268,59 @@ mod verify {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::{cmp::Ordering, time::Duration};
+
+    use rancor::Error;
+
+    use super::ArchivedDuration;
+    use crate::{access_unchecked, to_bytes};
+
+    #[test]
+    fn checked_arithmetic() {
+        let bytes = to_bytes::<Error>(&Duration::new(5, 750_000_000)).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDuration>(&bytes) };
+
+        assert_eq!(
+            archived.checked_add(Duration::from_millis(500)),
+            Some(Duration::new(6, 250_000_000)),
+        );
+        assert_eq!(archived.checked_add(Duration::MAX), None);
+        assert_eq!(
+            archived.checked_sub(Duration::new(1, 800_000_000)),
+            Some(Duration::new(3, 950_000_000)),
+        );
+        assert_eq!(archived.checked_sub(Duration::from_secs(6)), None);
+    }
+
+    #[test]
+    fn compare_with_duration() {
+        let bytes = to_bytes::<Error>(&Duration::new(5, 750_000_000)).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDuration>(&bytes) };
+
+        assert_eq!(*archived, Duration::new(5, 750_000_000));
+        assert_eq!(Duration::new(5, 750_000_000), *archived);
+        assert!(*archived < Duration::new(6, 0));
+        assert!(*archived > Duration::new(5, 749_999_999));
+        assert!(Duration::from_secs(5) < *archived);
+        assert_eq!(
+            Duration::from_secs(10).partial_cmp(archived),
+            Some(Ordering::Greater),
+        );
+    }
+
+    #[test]
+    fn float_accessors() {
+        let bytes = to_bytes::<Error>(&Duration::new(2, 500_000_000)).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDuration>(&bytes) };
+
+        assert_eq!(archived.as_secs_f64(), 2.5);
+        assert_eq!(archived.as_secs_f32(), 2.5);
+        assert_eq!(archived.as_millis_f64(), 2500.0);
+        assert_eq!(archived.as_millis_f32(), 2500.0);
+        assert!(!archived.is_zero());
+        assert!(ArchivedDuration::default().is_zero());
+    }
+}
@@ -2,8 +2,10 @@
 
 use core::{
     alloc::{Layout, LayoutError},
+    future::Future,
     hash::Hash,
     marker::PhantomData,
+    mem::size_of,
 };
 
 use crate::{
@@ -266,6 +268,45 @@ pub trait Archive {
     const COPY_OPTIMIZATION: CopyOptimization<Self> =
         CopyOptimization::disable();
 
+    /// The maximum number of bytes written when serializing a value of this
+    /// type as the root of an archive, or `None` if it is unbounded.
+    ///
+    /// Types which only write their archived representation set this to the
+    /// size of their archived type (see [`is_fixed_size`]). Derived types do
+    /// this automatically when all of their fields are fixed-size. Fields
+    /// archived with a wrapper are never considered fixed-size.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::mem::MaybeUninit;
+    ///
+    /// use rkyv::{
+    ///     rancor::Error, to_bytes_into, util::Align, Archive, Serialize,
+    /// };
+    ///
+    /// #[derive(Archive, Serialize)]
+    /// struct Reading {
+    ///     sensor: u16,
+    ///     value: f32,
+    ///     calibrated: Option<bool>,
+    /// }
+    ///
+    /// const SIZE: usize = match Reading::MAX_SERIALIZED_SIZE {
+    ///     Some(size) => size,
+    ///     None => panic!("readings must have a bounded size"),
+    /// };
+    ///
+    /// let reading = Reading {
+    ///     sensor: 3,
+    ///     value: 21.5,
+    ///     calibrated: Some(true),
+    /// };
+    /// let mut buffer = Align([MaybeUninit::<u8>::uninit(); SIZE]);
+    /// let bytes = to_bytes_into::<Error>(&reading, &mut *buffer).unwrap();
+    /// assert_eq!(bytes.len(), SIZE);
+    /// ```
+    const MAX_SERIALIZED_SIZE: Option<usize> = None;
+
     /// The archived representation of this type.
     ///
     /// In this form, the data can be used with zero-copy deserialization.
@@ -291,6 +332,18 @@ pub trait Archive {
     fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>);
 }
 
+/// Returns whether serializing a value of type `T` only writes its archived
+/// representation.
+///
+/// This is the case when [`Archive::MAX_SERIALIZED_SIZE`] is the size of the
+/// archived type.
+pub const fn is_fixed_size<T: Archive>() -> bool {
+    match T::MAX_SERIALIZED_SIZE {
+        Some(size) => size == size_of::<T::Archived>(),
+        None => false,
+    }
+}
+
 /// Converts a type to its archived form.
 ///
 /// Objects perform any supportive serialization during
@@ -334,6 +387,169 @@ pub trait Deserialize<T, D: Fallible + ?Sized> {
     fn deserialize(&self, deserializer: &mut D) -> Result<T, D::Error>;
 }
 
+/// Converts a type back from its archived form asynchronously.
+///
+/// This is the asynchronous counterpart to [`Deserialize`]. Deserializers can
+/// provide capabilities which resolve resources asynchronously (for example,
+/// fetching blobs which are referenced by ID), and types which need them can
+/// bound `D` to implement those capabilities the same way as they would for
+/// `Deserialize`.
+///
+/// This can be derived with
+/// [`DeserializeAsync`](macro@crate::DeserializeAsync).
+///
+/// # Examples
+/// ```
+/// use std::future::Future;
+///
+/// use rkyv::{
+///     rancor::Fallible, with::Skip, Archive, DeserializeAsync, Serialize,
+/// };
+///
+/// /// A deserializer which can fetch the contents of blobs.
+/// trait FetchBlob: Fallible {
+///     fn fetch_blob(
+///         &mut self,
+///         id: u64,
+///     ) -> impl Future<Output = Result<Vec<u8>, Self::Error>>;
+/// }
+///
+/// /// A blob which is archived as just its ID.
+/// #[derive(Archive, Serialize)]
+/// struct Blob {
+///     id: u64,
+///     #[with(Skip)]
+///     data: Vec<u8>,
+/// }
+///
+/// impl<D: FetchBlob + ?Sized> DeserializeAsync<Blob, D> for ArchivedBlob {
+///     async fn deserialize_async(
+///         &self,
+///         deserializer: &mut D,
+///     ) -> Result<Blob, D::Error> {
+///         let id = self.id.to_native();
+///         let data = deserializer.fetch_blob(id).await?;
+///         Ok(Blob { id, data })
+///     }
+/// }
+///
+/// #[derive(Archive, Serialize, DeserializeAsync)]
+/// struct Document {
+///     title: String,
+///     attachments: Vec<Blob>,
+/// }
+/// ```
+pub trait DeserializeAsync<T, D: Fallible + ?Sized> {
+    /// Deserializes using the given deserializer.
+    fn deserialize_async(
+        &self,
+        deserializer: &mut D,
+    ) -> impl Future<Output = Result<T, D::Error>>;
+}
+
+/// Deserializes some of the fields of an archived type into a projection type.
+///
+/// A projection is a companion struct containing a subset of the fields of
+/// another struct. Deserializing a projection only deserializes those fields,
+/// which can be much cheaper than deserializing the entire value.
+///
+/// Projections can be generated with the `#[archive(project(...))]` attribute
+/// of [`Deserialize`](macro@crate::Deserialize).
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, deserialize_projection, rancor::Error, to_bytes,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// #[archive(project(Summary(id, name)))]
+/// struct Record {
+///     id: u64,
+///     name: String,
+///     history: Vec<String>,
+/// }
+///
+/// let value = Record {
+///     id: 7,
+///     name: "rkyv".to_string(),
+///     history: vec!["a".to_string(); 100],
+/// };
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let archived = unsafe { access_unchecked::<ArchivedRecord>(&bytes) };
+///
+/// let summary =
+///     deserialize_projection::<Summary, _, _, Error>(archived, &mut ())
+///         .unwrap();
+/// assert_eq!(summary.id, 7);
+/// assert_eq!(summary.name, "rkyv");
+/// ```
+pub trait DeserializeProjection<P, D: Fallible + ?Sized> {
+    /// Deserializes the projection using the given deserializer.
+    fn deserialize_projection(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<P, D::Error>;
+}
+
+/// Converts a type back from its archived form into a type which borrows from
+/// the archive.
+///
+/// Unlike [`Deserialize`], the deserialized value may borrow from the archived
+/// value instead of copying it. For example, an archived `String` can be
+/// deserialized into a `&str` and an archived `Vec<u8>` can be deserialized
+/// into a `&[u8]` without allocating. Primitives are deserialized into their
+/// native types, and any archived value can be borrowed as itself.
+///
+/// This can be derived for structs of borrowed fields with
+/// [`DeserializeBorrowed`](macro@crate::DeserializeBorrowed).
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, deserialize_borrowed, rancor::Error, to_bytes,
+///     Archive, DeserializeBorrowed, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Message {
+///     id: u32,
+///     name: String,
+///     payload: Vec<u8>,
+/// }
+///
+/// #[derive(DeserializeBorrowed)]
+/// #[archive(borrow_from = Message)]
+/// struct MessageRef<'a> {
+///     id: u32,
+///     name: &'a str,
+///     payload: &'a [u8],
+/// }
+///
+/// let value = Message {
+///     id: 1,
+///     name: "hello".to_string(),
+///     payload: vec![1, 2, 3],
+/// };
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let archived = unsafe { access_unchecked::<ArchivedMessage>(&bytes) };
+///
+/// let borrowed =
+///     deserialize_borrowed::<MessageRef, _, _, Error>(archived, &mut ())
+///         .unwrap();
+/// assert_eq!(borrowed.id, 1);
+/// assert_eq!(borrowed.name, "hello");
+/// assert_eq!(borrowed.payload, &[1, 2, 3]);
+/// ```
+pub trait DeserializeBorrowed<'a, T, D: Fallible + ?Sized> {
+    /// Deserializes using the given deserializer, borrowing from `self`.
+    fn deserialize_borrowed(
+        &'a self,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error>;
+}
+
 /// A counterpart of [`Archive`] that's suitable for unsized types.
 ///
 /// Unlike `Archive`, types that implement `ArchiveUnsized` must be serialized
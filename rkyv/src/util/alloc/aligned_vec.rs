@@ -11,6 +11,8 @@ use std::{alloc, io};
 #[cfg(not(feature = "std"))]
 use ::alloc::{alloc, boxed::Box, vec::Vec};
 
+use crate::ser::allocator::OutOfMemory;
+
 /// A vector of bytes that aligns its memory to 16 bytes.
 ///
 /// The alignment also applies to `ArchivedAlignedVec`, which is useful for
@@ -179,59 +181,56 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
     ///   [`MAX_CAPACITY`](AlignedVec::MAX_CAPACITY)
     /// - `new_cap` must be greater than or equal to [`len()`](AlignedVec::len)
     pub unsafe fn change_capacity(&mut self, new_cap: usize) {
+        // SAFETY: The caller has guaranteed that `new_cap` is less than or
+        // equal to `MAX_CAPACITY` and greater than or equal to `len`.
+        if let Err(layout) = unsafe { self.try_change_capacity(new_cap) } {
+            alloc::handle_alloc_error(layout);
+        }
+    }
+
+    /// Changes the capacity of the vector to exactly `new_cap`, returning the
+    /// layout that could not be allocated if the allocator fails.
+    ///
+    /// # Safety
+    ///
+    /// `new_cap` must be less than or equal to `MAX_CAPACITY` and greater than
+    /// or equal to `len`.
+    unsafe fn try_change_capacity(
+        &mut self,
+        new_cap: usize,
+    ) -> Result<(), alloc::Layout> {
         debug_assert!(new_cap <= Self::MAX_CAPACITY);
         debug_assert!(new_cap >= self.len);
 
         if new_cap > 0 {
+            // SAFETY:
+            // - `ALIGNMENT` is always guaranteed to be a nonzero power of two.
+            // - The caller has guaranteed that `new_cap` doesn't overflow
+            //   `isize` when rounded up to the nearest multiple of `ALIGNMENT`.
+            let new_layout = unsafe {
+                alloc::Layout::from_size_align_unchecked(
+                    new_cap,
+                    Self::ALIGNMENT,
+                )
+            };
             let new_ptr = if self.cap > 0 {
-                // TODO: also verify that `new_cap` does not overflow `isize`
-                // when rounded up to the nearest multiple of `ALIGNMENT`.
                 // SAFETY:
                 // - `self.ptr` is currently allocated because `self.cap` is
                 //   greater than zero.
                 // - `self.layout()` always matches the layout used to allocate
                 //   the current block of memory.
                 // - We checked that `new_cap` is greater than zero.
-                let new_ptr = unsafe {
+                unsafe {
                     alloc::realloc(self.ptr.as_ptr(), self.layout(), new_cap)
-                };
-                if new_ptr.is_null() {
-                    // SAFETY:
-                    // - `ALIGNMENT` is always guaranteed to be a nonzero power
-                    //   of two.
-                    // - We checked that `new_cap` doesn't overflow `isize` when
-                    //   rounded up to the nearest power of two.
-                    let layout = unsafe {
-                        alloc::Layout::from_size_align_unchecked(
-                            new_cap,
-                            Self::ALIGNMENT,
-                        )
-                    };
-                    alloc::handle_alloc_error(layout);
                 }
-                new_ptr
             } else {
-                // SAFETY:
-                // - `ALIGNMENT` is always guaranteed to be a nonzero power of
-                //   two.
-                // - We checked that `new_cap` doesn't overflow `isize` when
-                //   rounded up to the nearest power of two.
-                let layout = unsafe {
-                    alloc::Layout::from_size_align_unchecked(
-                        new_cap,
-                        Self::ALIGNMENT,
-                    )
-                };
                 // SAFETY: We checked that `new_cap` has non-zero size.
-                let new_ptr = unsafe { alloc::alloc(layout) };
-                if new_ptr.is_null() {
-                    alloc::handle_alloc_error(layout);
-                }
-                new_ptr
+                unsafe { alloc::alloc(new_layout) }
+            };
+            let Some(new_ptr) = NonNull::new(new_ptr) else {
+                return Err(new_layout);
             };
-            // SAFETY: We checked that `new_ptr` is non-null in each of the
-            // branches.
-            self.ptr = unsafe { NonNull::new_unchecked(new_ptr) };
+            self.ptr = new_ptr;
             self.cap = new_cap;
         } else if self.cap > 0 {
             //
@@ -241,6 +240,8 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
             self.ptr = NonNull::dangling();
             self.cap = 0;
         }
+
+        Ok(())
     }
 
     /// Shrinks the capacity of the vector as much as possible.
@@ -421,6 +422,74 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
         unsafe { self.grow_capacity_to(new_cap) };
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes to be
+    /// inserted into the given `AlignedVec`.
+    ///
+    /// This uses the same growth strategy as [`reserve`](AlignedVec::reserve),
+    /// but returns an error instead of panicking if the new capacity exceeds
+    /// `Self::MAX_CAPACITY` bytes, or aborting if the allocator fails. The
+    /// vector is left unchanged if an error is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    ///
+    /// let mut vec = AlignedVec::<16>::new();
+    /// vec.push(1);
+    /// vec.try_reserve(10).unwrap();
+    /// assert!(vec.capacity() >= 11);
+    ///
+    /// assert!(vec.try_reserve(usize::MAX).is_err());
+    /// assert_eq!(vec.as_slice(), &[1]);
+    /// ```
+    pub fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), OutOfMemory> {
+        let remaining = self.cap.wrapping_sub(self.len);
+        if additional > remaining {
+            self.do_try_reserve(additional)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cold]
+    fn do_try_reserve(&mut self, additional: usize) -> Result<(), OutOfMemory> {
+        let new_cap = match self.len.checked_add(additional) {
+            Some(new_cap) if new_cap <= Self::MAX_CAPACITY => new_cap,
+            _ => {
+                return Err(OutOfMemory {
+                    size: self.len.saturating_add(additional),
+                    align: Self::ALIGNMENT,
+                })
+            }
+        };
+        // SAFETY: `grown_capacity` always returns a capacity greater than or
+        // equal to `new_cap` and less than or equal to `MAX_CAPACITY`.
+        unsafe { self.try_change_capacity(Self::grown_capacity(new_cap)) }
+            .map_err(|layout| OutOfMemory {
+                size: layout.size(),
+                align: layout.align(),
+            })
+    }
+
+    /// Returns the capacity to grow to when at least `new_cap` bytes are
+    /// needed.
+    ///
+    /// `new_cap` must be less than or equal to `MAX_CAPACITY`.
+    fn grown_capacity(new_cap: usize) -> usize {
+        if new_cap > (isize::MAX as usize + 1) >> 1 {
+            // Rounding up to next power of 2 would result in `isize::MAX + 1`
+            // or higher, which exceeds max capacity. So cap at max
+            // instead.
+            Self::MAX_CAPACITY
+        } else {
+            // Cannot overflow due to check above
+            new_cap.next_power_of_two()
+        }
+    }
+
     /// Grows total capacity of vector to `new_cap` or more.
     ///
     /// Capacity after this call will be `new_cap` rounded up to next power of
@@ -460,19 +529,11 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
     pub unsafe fn grow_capacity_to(&mut self, new_cap: usize) {
         debug_assert!(new_cap > self.cap);
 
-        let new_cap = if new_cap > (isize::MAX as usize + 1) >> 1 {
-            // Rounding up to next power of 2 would result in `isize::MAX + 1`
-            // or higher, which exceeds max capacity. So cap at max
-            // instead.
-            assert!(
-                new_cap <= Self::MAX_CAPACITY,
-                "cannot reserve a larger AlignedVec"
-            );
-            Self::MAX_CAPACITY
-        } else {
-            // Cannot overflow due to check above
-            new_cap.next_power_of_two()
-        };
+        assert!(
+            new_cap <= Self::MAX_CAPACITY,
+            "cannot reserve a larger AlignedVec"
+        );
+        let new_cap = Self::grown_capacity(new_cap);
         // SAFETY: We just checked that `new_cap` is greater than or equal to
         // `len` and less than or equal to `MAX_CAPACITY`.
         unsafe {
@@ -579,6 +640,37 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
         }
     }
 
+    /// Tries to copy and append all bytes in a slice to the `AlignedVec`.
+    ///
+    /// Unlike [`extend_from_slice`](AlignedVec::extend_from_slice), this
+    /// returns an error instead of panicking or aborting if the vector can't be
+    /// grown to hold the bytes. The vector is left unchanged if an error is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    ///
+    /// let mut vec = AlignedVec::<16>::new();
+    /// vec.try_extend_from_slice(&[1, 2, 3]).unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn try_extend_from_slice(
+        &mut self,
+        other: &[u8],
+    ) -> Result<(), OutOfMemory> {
+        self.try_reserve(other.len())?;
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                other.as_ptr(),
+                self.as_mut_ptr().add(self.len()),
+                other.len(),
+            );
+        }
+        self.len += other.len();
+        Ok(())
+    }
+
     /// Removes the last element from a vector and returns it, or `None` if it
     /// is empty.
     ///
@@ -716,8 +808,9 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
 
     /// Converts the vector into `Box<[u8]>`. The returned slice is 1-aligned.
     ///
-    /// This method reallocates and copies the underlying bytes. Any excess
-    /// capacity is dropped.
+    /// Any excess capacity is dropped. This reuses the allocation of the
+    /// vector if `ALIGNMENT` is 1, and otherwise reallocates and copies the
+    /// underlying bytes.
     ///
     /// # Examples
     /// ```
@@ -745,8 +838,11 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
 
     /// Converts the vector into `Vec<u8>`.
     ///
-    /// This method reallocates and copies the underlying bytes. Any excess
-    /// capacity is dropped.
+    /// The global allocator requires memory to be deallocated with the same
+    /// alignment it was allocated with, and `Vec<u8>` always deallocates with
+    /// an alignment of 1. So this only reuses the allocation of the vector if
+    /// `ALIGNMENT` is 1. Otherwise, it reallocates and copies the underlying
+    /// bytes and any excess capacity is dropped.
     ///
     /// # Examples
     /// ```
@@ -758,14 +854,113 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
     /// assert_eq!(vec.len(), 3);
     /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
     /// ```
+    ///
+    /// With an alignment of 1, the allocation is reused:
+    ///
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    /// let mut v = AlignedVec::<1>::with_capacity(10);
+    /// v.extend_from_slice(&[1, 2, 3]);
+    /// let ptr = v.as_ptr();
+    ///
+    /// let vec = v.into_vec();
+    /// assert_eq!(vec.as_ptr(), ptr);
+    /// assert_eq!(vec.capacity(), 10);
+    /// ```
     pub fn into_vec(self) -> Vec<u8> {
-        Vec::from(self.as_ref())
+        if ALIGNMENT == 1 {
+            let this = core::mem::ManuallyDrop::new(self);
+            // SAFETY: With an alignment of 1, the memory of the vector was
+            // allocated with the same layout that `Vec<u8>` uses for the same
+            // capacity. If the capacity is 0, the pointer is dangling and will
+            // not be deallocated. The first `len` bytes are initialized, and
+            // `this` will not be dropped so ownership of the allocation is
+            // transferred.
+            unsafe {
+                Vec::from_raw_parts(this.ptr.as_ptr(), this.len, this.cap)
+            }
+        } else {
+            Vec::from(self.as_ref())
+        }
+    }
+
+    /// Attempts to convert a `Vec<u8>` into an `AlignedVec` without copying.
+    ///
+    /// This only succeeds if `ALIGNMENT` is 1, because the global allocator
+    /// requires memory to be deallocated with the same alignment it was
+    /// allocated with. Even if the bytes of the `Vec<u8>` happen to be
+    /// sufficiently aligned, its allocation can't be taken over for larger
+    /// alignments. If the conversion can't be performed, the original vector
+    /// is returned. Use [`from_vec`](Self::from_vec) to fall back to copying
+    /// instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    /// let vec = vec![1, 2, 3];
+    /// let ptr = vec.as_ptr();
+    ///
+    /// let aligned = AlignedVec::<1>::try_from_vec(vec).unwrap();
+    /// assert_eq!(aligned.as_ptr(), ptr);
+    /// assert_eq!(aligned.as_slice(), &[1, 2, 3]);
+    ///
+    /// let vec = vec![1, 2, 3];
+    /// assert!(AlignedVec::<16>::try_from_vec(vec).is_err());
+    /// ```
+    pub fn try_from_vec(vec: Vec<u8>) -> Result<Self, Vec<u8>> {
+        if ALIGNMENT == 1 {
+            let mut vec = core::mem::ManuallyDrop::new(vec);
+            let cap = vec.capacity();
+            let ptr = if cap == 0 {
+                NonNull::dangling()
+            } else {
+                // SAFETY: Vectors with a nonzero capacity always have a
+                // non-null pointer.
+                unsafe { NonNull::new_unchecked(vec.as_mut_ptr()) }
+            };
+            // SAFETY: The allocation of the vector was made with a layout of
+            // `cap` bytes with an alignment of 1, which is the same layout
+            // that `AlignedVec<1>` uses. `vec` will not be dropped so
+            // ownership of the allocation is transferred.
+            Ok(Self {
+                ptr,
+                cap,
+                len: vec.len(),
+            })
+        } else {
+            Err(vec)
+        }
+    }
+
+    /// Converts a `Vec<u8>` into an `AlignedVec`.
+    ///
+    /// This takes ownership of the allocation of the vector when possible (see
+    /// [`try_from_vec`](Self::try_from_vec)), and otherwise copies its bytes
+    /// into a new allocation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    /// let aligned = AlignedVec::<16>::from_vec(vec![1, 2, 3]);
+    /// assert_eq!(aligned.as_ptr() as usize % 16, 0);
+    /// assert_eq!(aligned.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn from_vec(vec: Vec<u8>) -> Self {
+        Self::try_from_vec(vec).unwrap_or_else(|vec| {
+            let mut result = Self::with_capacity(vec.len());
+            result.extend_from_slice(&vec);
+            result
+        })
     }
 }
 
 #[cfg(feature = "std")]
 const _: () = {
-    use std::io::{ErrorKind, Read};
+    use std::{
+        fs::File,
+        io::{ErrorKind, Read},
+        path::Path,
+    };
 
     impl<const A: usize> AlignedVec<A> {
         /// Reads all bytes until EOF from `r` and appends them to this
@@ -870,12 +1065,72 @@ const _: () = {
                 }
             }
         }
+
+        /// Reads all bytes until EOF from `r` into a new `AlignedVec`.
+        ///
+        /// # Examples
+        /// ```
+        /// # use rkyv::util::AlignedVec;
+        /// let source = [1u8, 2, 3, 4];
+        /// let bytes = AlignedVec::<16>::read_from(source.as_slice()).unwrap();
+        ///
+        /// assert_eq!(bytes.as_ptr() as usize % 16, 0);
+        /// assert_eq!(bytes.as_slice(), &[1, 2, 3, 4]);
+        /// ```
+        pub fn read_from<R: Read>(mut r: R) -> std::io::Result<Self> {
+            let mut result = Self::new();
+            result.extend_from_reader(&mut r)?;
+            Ok(result)
+        }
+
+        /// Reads the entire contents of a file into a new `AlignedVec`.
+        ///
+        /// The vector is allocated up front using the length of the file, so
+        /// the contents are read without any intermediate copies. Returns an
+        /// error if the file's metadata can't be read, or if the file is too
+        /// large to fit in memory.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// use rkyv::{rancor::Error, util::AlignedVec, Archived};
+        ///
+        /// let bytes = AlignedVec::<16>::from_file("data.bin").unwrap();
+        /// let archived = rkyv::access::<Archived<Vec<u32>>, Error>(&bytes);
+        /// assert!(archived.is_ok());
+        /// ```
+        pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+            let mut file = File::open(path)?;
+            // Files which don't report a size, like pipes, have a length of
+            // zero and are read until the end without a capacity hint.
+            let len =
+                usize::try_from(file.metadata()?.len()).map_err(|_| {
+                    std::io::Error::new(
+                        ErrorKind::OutOfMemory,
+                        "file is too large to read into memory",
+                    )
+                })?;
+            let mut result = Self::with_capacity(len);
+            result.extend_from_reader(&mut file)?;
+            Ok(result)
+        }
     }
 };
 
 impl<const A: usize> From<AlignedVec<A>> for Vec<u8> {
     fn from(aligned: AlignedVec<A>) -> Self {
-        aligned.to_vec()
+        aligned.into_vec()
+    }
+}
+
+impl<const A: usize> From<AlignedVec<A>> for Box<[u8]> {
+    fn from(aligned: AlignedVec<A>) -> Self {
+        aligned.into_boxed_slice()
+    }
+}
+
+impl<const A: usize> From<Vec<u8>> for AlignedVec<A> {
+    fn from(vec: Vec<u8>) -> Self {
+        Self::from_vec(vec)
     }
 }
 
@@ -1,8 +1,12 @@
 mod aligned_vec;
+#[cfg(target_has_atomic = "ptr")]
+mod shared_aligned_vec;
 
 use rancor::Strategy;
 
 pub use self::aligned_vec::*;
+#[cfg(target_has_atomic = "ptr")]
+pub use self::shared_aligned_vec::*;
 use crate::{
     access_unchecked,
     de::pooling::Pool,
@@ -123,6 +127,9 @@ pub fn clear_arena() {
 /// Serializes the given value and returns the resulting bytes in an
 /// [`AlignedVec`].
 ///
+/// The bytes are aligned to 16 bytes. To use a different alignment, serialize
+/// into an `AlignedVec` with that alignment using [`to_bytes_in`].
+///
 /// # Examples
 /// ```
 /// use rkyv::rancor::Error;
@@ -151,6 +158,26 @@ where
 }
 
 /// Serializes the given value and writes the bytes to the given `writer`.
+///
+/// The archive is written starting at the current position of `writer`, and
+/// the output is only as aligned as the writer's buffer. Serializing into an
+/// [`AlignedVec`] with a larger alignment can be used to align archived types
+/// to cache lines or pages. Padding the writer to that alignment first keeps
+/// the archive itself aligned when it follows other data.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Error, util::AlignedVec};
+///
+/// let mut bytes = AlignedVec::<4096>::new();
+/// bytes.extend_from_slice(&[1, 2, 3]);
+/// // Start the archive on the next page.
+/// bytes.resize(4096, 0);
+///
+/// let bytes = rkyv::to_bytes_in::<_, Error>(&42u32, bytes).unwrap();
+/// assert_eq!(bytes.as_ptr() as usize % 4096, 0);
+/// assert_eq!(bytes.len(), 4096 + 4);
+/// ```
 pub fn to_bytes_in<W, E>(
     value: &impl for<'a> Serialize<DefaultSerializer<'a, W, E>>,
     writer: W,
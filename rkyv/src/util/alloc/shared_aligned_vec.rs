@@ -0,0 +1,153 @@
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+use core::{borrow::Borrow, fmt, ops::Deref};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use crate::util::AlignedVec;
+
+/// An immutable, reference-counted buffer of aligned bytes.
+///
+/// A `SharedAlignedVec` can be cheaply cloned and sent between threads, with
+/// every clone pointing to the same aligned bytes. This allows multiple readers
+/// to hold the same archive without copying it.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     util::{AlignedVec, SharedAlignedVec},
+///     Archived,
+/// };
+///
+/// let bytes = rkyv::to_bytes::<Error>(&vec![1, 2, 3, 4]).unwrap();
+/// let shared = SharedAlignedVec::from(bytes);
+///
+/// let handles = (0..4)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         std::thread::spawn(move || {
+///             let archived =
+///                 rkyv::access::<Archived<Vec<i32>>, Error>(&shared).unwrap();
+///             archived.iter().map(|x| x.to_native()).sum::<i32>()
+///         })
+///     })
+///     .collect::<Vec<_>>();
+///
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap(), 10);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedAlignedVec<const ALIGNMENT: usize = 16> {
+    inner: Arc<AlignedVec<ALIGNMENT>>,
+}
+
+impl<const ALIGNMENT: usize> SharedAlignedVec<ALIGNMENT> {
+    /// The alignment of the buffer
+    pub const ALIGNMENT: usize = ALIGNMENT;
+
+    /// Creates a new `SharedAlignedVec` from the given `AlignedVec` without
+    /// copying its bytes.
+    #[inline]
+    pub fn new(vec: AlignedVec<ALIGNMENT>) -> Self {
+        Self {
+            inner: Arc::new(vec),
+        }
+    }
+
+    /// Extracts a slice containing the entire buffer.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        self.inner.as_slice()
+    }
+
+    /// Returns a raw pointer to the buffer.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.inner.as_ptr()
+    }
+
+    /// Returns the number of bytes in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the buffer contains no bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns `true` if the two buffers point to the same bytes.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.inner, &other.inner)
+    }
+
+    /// Returns the underlying `AlignedVec` if this is the only reference to
+    /// it. Otherwise, the same `SharedAlignedVec` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::{AlignedVec, SharedAlignedVec};
+    /// let mut vec = AlignedVec::<16>::new();
+    /// vec.extend_from_slice(&[1, 2, 3]);
+    ///
+    /// let shared = SharedAlignedVec::from(vec);
+    /// let other = shared.clone();
+    ///
+    /// let shared = shared.try_into_aligned_vec().unwrap_err();
+    /// drop(other);
+    /// let vec = shared.try_into_aligned_vec().unwrap();
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn try_into_aligned_vec(self) -> Result<AlignedVec<ALIGNMENT>, Self> {
+        Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+    }
+}
+
+impl<const A: usize> From<AlignedVec<A>> for SharedAlignedVec<A> {
+    #[inline]
+    fn from(vec: AlignedVec<A>) -> Self {
+        Self::new(vec)
+    }
+}
+
+impl<const A: usize> AsRef<[u8]> for SharedAlignedVec<A> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const A: usize> Borrow<[u8]> for SharedAlignedVec<A> {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const A: usize> fmt::Debug for SharedAlignedVec<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<const A: usize> Default for SharedAlignedVec<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(AlignedVec::new())
+    }
+}
+
+impl<const A: usize> Deref for SharedAlignedVec<A> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
@@ -0,0 +1,169 @@
+use core::{marker::PhantomData, pin::Pin};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Seek as _, SeekFrom, Write as _},
+    path::Path,
+};
+
+use bytecheck::CheckBytes;
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use rancor::{Source, Strategy};
+
+use crate::{
+    util::{access_unchecked, access_unchecked_mut},
+    validation::validators::DefaultValidator,
+    Archive,
+};
+
+/// The size of the pages which are compared and written when edits are
+/// committed.
+const PAGE_SIZE: usize = 4096;
+
+/// An archive file which can be edited in place.
+///
+/// The file is memory-mapped twice: once read-only, and once copy-on-write.
+/// Edits made through [`get_mut`](ArchiveFile::get_mut) only modify the
+/// copy-on-write mapping, so the file is left unchanged until the edits are
+/// [committed](ArchiveFile::commit). Committing checks that the edited archive
+/// is still valid, and then writes only the pages which were changed back to
+/// the file.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Error, util::ArchiveFile, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Counter {
+///     name: String,
+///     count: u32,
+/// }
+///
+/// let path = std::env::temp_dir().join("rkyv_archive_file_doc");
+/// let value = Counter {
+///     name: "visits".to_string(),
+///     count: 41,
+/// };
+/// std::fs::write(&path, rkyv::to_bytes::<Error>(&value).unwrap()).unwrap();
+///
+/// let mut file =
+///     unsafe { ArchiveFile::<Counter>::open::<Error>(&path) }.unwrap();
+/// let mut counter = file.get_mut();
+/// // SAFETY: `count` is never moved out of the pinned counter.
+/// let count = unsafe { &mut counter.as_mut().get_unchecked_mut().count };
+/// *count = (count.to_native() + 1).into();
+/// assert_eq!(file.commit::<Error>().unwrap(), 1);
+///
+/// let bytes = std::fs::read(&path).unwrap();
+/// let counter = rkyv::access::<ArchivedCounter, Error>(&bytes).unwrap();
+/// assert_eq!(counter.count, 42);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct ArchiveFile<T: Archive> {
+    file: File,
+    original: Mmap,
+    edited: MmapMut,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Archive> ArchiveFile<T> {
+    /// Opens the archive file at the given path for editing and checks that
+    /// it contains a valid archived `T`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by anything other than this
+    /// `ArchiveFile` while it is open, including by other processes.
+    pub unsafe fn open<E>(path: impl AsRef<Path>) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+        E: Source,
+    {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(E::new)?;
+        // SAFETY: The caller has guaranteed that the file will only be
+        // modified through this `ArchiveFile`.
+        let original = unsafe { Mmap::map(&file).map_err(E::new)? };
+        let edited = Self::map_edited(&file)?;
+        crate::access::<T::Archived, E>(&original)?;
+
+        Ok(Self {
+            file,
+            original,
+            edited,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn map_edited<E: Source>(file: &File) -> Result<MmapMut, E> {
+        // SAFETY: The caller of `open` has guaranteed that the file will only
+        // be modified through this `ArchiveFile`.
+        unsafe { MmapOptions::new().map_copy(file).map_err(E::new) }
+    }
+
+    /// Returns the archived value, including any edits which have not been
+    /// committed yet.
+    pub fn get(&self) -> &T::Archived {
+        // SAFETY: The file was checked when it was opened, and edits can only
+        // be made to it through pinned mutable references.
+        unsafe { access_unchecked::<T::Archived>(&self.edited) }
+    }
+
+    /// Returns a pinned mutable reference to the archived value.
+    ///
+    /// Edits are not written to the file until they are
+    /// [committed](ArchiveFile::commit).
+    pub fn get_mut(&mut self) -> Pin<&mut T::Archived> {
+        // SAFETY: The file was checked when it was opened, and edits can only
+        // be made to it through pinned mutable references.
+        unsafe { access_unchecked_mut::<T::Archived>(&mut self.edited) }
+    }
+
+    /// Returns whether any edits have been made which have not been committed
+    /// yet.
+    pub fn is_dirty(&self) -> bool {
+        *self.original != *self.edited
+    }
+
+    /// Checks that the edited archive is valid, and then writes the pages
+    /// which were changed back to the file.
+    ///
+    /// Returns the number of pages which were written. If the edited archive
+    /// is not valid, the file is left unchanged and the edits are kept so
+    /// that they can be fixed or [discarded](ArchiveFile::discard).
+    pub fn commit<E>(&mut self) -> Result<usize, E>
+    where
+        T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+        E: Source,
+    {
+        crate::access::<T::Archived, E>(&self.edited)?;
+
+        let mut written = 0;
+        let pages = self
+            .original
+            .chunks(PAGE_SIZE)
+            .zip(self.edited.chunks(PAGE_SIZE));
+        for (index, (original, edited)) in pages.enumerate() {
+            if original != edited {
+                let offset = (index * PAGE_SIZE) as u64;
+                self.file.seek(SeekFrom::Start(offset)).map_err(E::new)?;
+                self.file.write_all(edited).map_err(E::new)?;
+                written += 1;
+            }
+        }
+        if written != 0 {
+            self.file.sync_data().map_err(E::new)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Discards any edits which have not been committed yet.
+    pub fn discard<E: Source>(&mut self) -> Result<(), E> {
+        self.edited = Self::map_edited(&self.file)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+use core::{fmt, marker::PhantomData, ops::Range};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Seek as _, SeekFrom, Write as _},
+    path::Path,
+};
+
+use bytecheck::CheckBytes;
+use rancor::{fail, Source, Strategy};
+
+use crate::{
+    ser::DefaultSerializer,
+    util::{frame::checksum, to_bytes, AlignedVec},
+    validation::validators::DefaultValidator,
+    Archive, Serialize,
+};
+
+/// The alignment of every record in an archive log.
+const RECORD_ALIGN: usize = 16;
+/// The size of the header written before every record.
+const HEADER_SIZE: usize = 16;
+/// The value that every record header starts with.
+const MAGIC: u32 = u32::from_le_bytes(*b"rkyv");
+
+/// An append-only file of archived records.
+///
+/// Each record is written with a header containing its length and a checksum
+/// of its bytes, and is padded so that every record is aligned to 16 bytes.
+/// When a log is opened, any incomplete or corrupted records at the end of the
+/// file (for example, from a crash in the middle of an append) are removed so
+/// that new records can be appended after the last complete record.
+///
+/// The contents of the log are kept in memory so that records can be accessed
+/// without copying them.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Error, util::ArchiveLog, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Event {
+///     id: u32,
+///     message: String,
+/// }
+///
+/// let path = std::env::temp_dir().join("rkyv_archive_log_doc");
+/// # std::fs::remove_file(&path).ok();
+/// let mut log = ArchiveLog::open::<Error>(&path).unwrap();
+/// for (id, message) in [(1, "started"), (2, "stopped")] {
+///     let message = message.to_string();
+///     log.append::<_, Error>(&Event { id, message }).unwrap();
+/// }
+/// drop(log);
+///
+/// let log = ArchiveLog::open::<Error>(&path).unwrap();
+/// let events = log
+///     .iter::<Event, Error>()
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(events.len(), 2);
+/// assert_eq!(events[1].message, "stopped");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct ArchiveLog {
+    file: File,
+    bytes: AlignedVec<RECORD_ALIGN>,
+    len: usize,
+}
+
+impl ArchiveLog {
+    /// Opens the archive log at the given path, creating it if it doesn't
+    /// exist.
+    ///
+    /// Any incomplete or corrupted records at the end of the file are
+    /// truncated.
+    pub fn open<E: Source>(path: impl AsRef<Path>) -> Result<Self, E> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(E::new)?;
+
+        let mut bytes = AlignedVec::new();
+        bytes.extend_from_reader(&mut file).map_err(E::new)?;
+
+        let mut len = 0;
+        let mut end = 0;
+        while let Some((_, next)) = next_record(&bytes, end) {
+            len += 1;
+            end = next;
+        }
+
+        if end != bytes.len() {
+            bytes.resize(end, 0);
+            file.set_len(end as u64).map_err(E::new)?;
+        }
+        file.seek(SeekFrom::End(0)).map_err(E::new)?;
+
+        Ok(Self { file, bytes, len })
+    }
+
+    /// Returns the number of records in the log.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the log has no records.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Serializes a value and appends it to the log as a new record.
+    ///
+    /// The record is written to the file, but may not be durable until
+    /// [`sync`](ArchiveLog::sync) is called.
+    pub fn append<T, E>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>,
+        E: Source,
+    {
+        let payload = to_bytes::<E>(value)?;
+        if u32::try_from(payload.len()).is_err() {
+            fail!(RecordTooLargeError { len: payload.len() });
+        }
+
+        let mut record = AlignedVec::<RECORD_ALIGN>::with_capacity(
+            HEADER_SIZE + payload.len() + RECORD_ALIGN,
+        );
+        record.extend_from_slice(&MAGIC.to_le_bytes());
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&checksum(&payload).to_le_bytes());
+        record.extend_from_slice(&[0; 4]);
+        record.extend_from_slice(&payload);
+        record.resize(padded(record.len()), 0);
+
+        self.file.write_all(&record).map_err(E::new)?;
+        self.bytes.extend_from_slice(&record);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Flushes all appended records to disk.
+    pub fn sync<E: Source>(&self) -> Result<(), E> {
+        self.file.sync_data().map_err(E::new)
+    }
+
+    /// Returns an iterator over the records in the log.
+    ///
+    /// Each record is checked before it is returned.
+    pub fn iter<T, E>(&self) -> ArchiveLogIter<'_, T, E>
+    where
+        T: Archive,
+        T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+        E: Source,
+    {
+        ArchiveLogIter {
+            bytes: &self.bytes,
+            pos: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the records of an [`ArchiveLog`].
+pub struct ArchiveLogIter<'a, T, E> {
+    bytes: &'a [u8],
+    pos: usize,
+    _phantom: PhantomData<(T, E)>,
+}
+
+impl<'a, T, E> Iterator for ArchiveLogIter<'a, T, E>
+where
+    T: Archive,
+    T::Archived: 'a + for<'b> CheckBytes<Strategy<DefaultValidator<'b>, E>>,
+    E: Source,
+{
+    type Item = Result<&'a T::Archived, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (payload, next) = next_record(self.bytes, self.pos)?;
+        self.pos = next;
+        Some(crate::access::<T::Archived, E>(&self.bytes[payload]))
+    }
+}
+
+/// An error resulting from a record which is too large to be written to an
+/// archive log.
+#[derive(Debug)]
+pub struct RecordTooLargeError {
+    len: usize,
+}
+
+impl fmt::Display for RecordTooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "record of {} bytes is too large for an archive log, records may \
+             be at most {} bytes",
+            self.len,
+            u32::MAX,
+        )
+    }
+}
+
+impl std::error::Error for RecordTooLargeError {}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+/// Returns `len` rounded up to the alignment of records.
+fn padded(len: usize) -> usize {
+    (len + RECORD_ALIGN - 1) & !(RECORD_ALIGN - 1)
+}
+
+/// Returns the range of the payload of the record with its header at `pos`
+/// and the position of the next record, or `None` if there is no complete and
+/// uncorrupted record at `pos`.
+fn next_record(bytes: &[u8], pos: usize) -> Option<(Range<usize>, usize)> {
+    if bytes.len() - pos < HEADER_SIZE || read_u32(bytes, pos) != MAGIC {
+        return None;
+    }
+
+    let start = pos + HEADER_SIZE;
+    let end = start.checked_add(read_u32(bytes, pos + 4) as usize)?;
+    if end > bytes.len() || padded(end) > bytes.len() {
+        return None;
+    }
+    if checksum(&bytes[start..end]) != read_u32(bytes, pos + 8) {
+        return None;
+    }
+
+    Some((start..end, padded(end)))
+}
@@ -0,0 +1,293 @@
+use core::{fmt, marker::PhantomData};
+use std::io;
+
+use bytecheck::CheckBytes;
+use bytes::{Buf as _, BufMut as _, BytesMut};
+use rancor::{Error, Strategy};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    ser::DefaultSerializer,
+    util::{
+        frame::{
+            checksum, ChecksumMismatchError, FrameTooLargeError, CHECKSUM_FLAG,
+            MAX_FRAME_LEN,
+        },
+        to_bytes, AlignedVec, OwnedArchive,
+    },
+    validation::validators::DefaultValidator,
+    Archive, Serialize,
+};
+
+/// The default maximum length of a frame for an [`RkyvCodec`].
+pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+/// A codec which encodes and decodes archives as length-prefixed frames.
+///
+/// Frames use the same format as the [`frame`](crate::util::frame) module, so
+/// frames written with [`write_frame`](crate::util::frame::write_frame) or
+/// [`write_frame_checksummed`](crate::util::frame::write_frame_checksummed)
+/// can be decoded by an `RkyvCodec` and vice versa.
+///
+/// Values are encoded by reference, and decoded into an [`OwnedArchive`] so
+/// they can be accessed without deserializing them. Decoded frames are checked
+/// by default, and frames longer than the maximum frame length are rejected
+/// with an error before they are buffered.
+///
+/// # Examples
+/// ```
+/// use rkyv::{util::RkyvCodec, Archive, Serialize};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Message {
+///     id: u32,
+///     body: String,
+/// }
+///
+/// let mut codec = RkyvCodec::<Message>::new().with_max_frame_len(1024);
+/// let mut buffer = bytes::BytesMut::new();
+///
+/// let message = Message {
+///     id: 1,
+///     body: "hello".to_string(),
+/// };
+/// codec.encode(&message, &mut buffer).unwrap();
+///
+/// let decoded = codec.decode(&mut buffer).unwrap().unwrap();
+/// assert_eq!(decoded.id, 1);
+/// assert_eq!(decoded.body, "hello");
+/// assert!(buffer.is_empty());
+/// ```
+pub struct RkyvCodec<T> {
+    max_frame_len: usize,
+    checked: bool,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> RkyvCodec<T> {
+    /// Creates a new codec which checks the frames it decodes and accepts
+    /// frames up to [`DEFAULT_MAX_FRAME_LEN`] bytes long.
+    pub fn new() -> Self {
+        Self {
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            checked: true,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length of the frames encoded and decoded by the
+    /// codec.
+    ///
+    /// The maximum length is capped at [`MAX_FRAME_LEN`].
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len.min(MAX_FRAME_LEN);
+        self
+    }
+
+    /// Returns the maximum length of the frames encoded and decoded by the
+    /// codec.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Disables checking the frames decoded by the codec.
+    ///
+    /// # Safety
+    ///
+    /// Every frame decoded by the codec must contain a valid archived `T`.
+    /// This is generally only true when the other end of the connection is
+    /// trusted.
+    pub unsafe fn unchecked(mut self) -> Self {
+        self.checked = false;
+        self
+    }
+
+    /// Returns whether the codec checks the frames it decodes.
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+}
+
+impl<T> Default for RkyvCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for RkyvCodec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            max_frame_len: self.max_frame_len,
+            checked: self.checked,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for RkyvCodec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RkyvCodec")
+            .field("max_frame_len", &self.max_frame_len)
+            .field("checked", &self.checked)
+            .finish()
+    }
+}
+
+impl<'a, T> Encoder<&'a T> for RkyvCodec<T>
+where
+    T: for<'b> Serialize<DefaultSerializer<'b, AlignedVec, Error>>,
+{
+    type Error = io::Error;
+
+    fn encode(
+        &mut self,
+        item: &'a T,
+        dst: &mut BytesMut,
+    ) -> Result<(), Self::Error> {
+        let bytes = to_bytes::<Error>(item).map_err(io::Error::other)?;
+        if bytes.len() > self.max_frame_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                FrameTooLargeError {
+                    len: bytes.len(),
+                    max_len: self.max_frame_len,
+                },
+            ));
+        }
+
+        dst.reserve(4 + bytes.len());
+        dst.put_u32_le(bytes.len() as u32);
+        dst.extend_from_slice(&bytes);
+
+        Ok(())
+    }
+}
+
+impl<T> Decoder for RkyvCodec<T>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, Error>>,
+{
+    type Item = OwnedArchive<T>;
+    type Error = io::Error;
+
+    fn decode(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < 4 {
+            return Ok(None);
+        }
+
+        let prefix = read_u32(src, 0);
+        let header_len = if prefix & CHECKSUM_FLAG != 0 { 8 } else { 4 };
+        let len = (prefix & !CHECKSUM_FLAG) as usize;
+        if len > self.max_frame_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                FrameTooLargeError {
+                    len,
+                    max_len: self.max_frame_len,
+                },
+            ));
+        }
+
+        if src.len() < header_len + len {
+            src.reserve(header_len + len - src.len());
+            return Ok(None);
+        }
+
+        let expected_checksum = (header_len == 8).then(|| read_u32(src, 4));
+        src.advance(header_len);
+        let frame = src.split_to(len);
+
+        if let Some(expected) = expected_checksum {
+            let actual = checksum(&frame);
+            if actual != expected {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    ChecksumMismatchError { expected, actual },
+                ));
+            }
+        }
+
+        let mut bytes = AlignedVec::with_capacity(len);
+        bytes.extend_from_slice(&frame);
+
+        if self.checked {
+            OwnedArchive::new::<Error>(bytes)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        } else {
+            // SAFETY: The caller of `unchecked` has guaranteed that every
+            // frame decoded by the codec contains a valid archived `T`.
+            unsafe { Ok(Some(OwnedArchive::new_unchecked(bytes))) }
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use rancor::Error;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::RkyvCodec;
+    use crate::util::frame::write_frame_checksummed;
+
+    #[test]
+    fn round_trip() {
+        let mut codec = RkyvCodec::<Vec<u32>>::new();
+        let mut buffer = BytesMut::new();
+
+        codec.encode(&vec![1, 2, 3], &mut buffer).unwrap();
+        codec.encode(&vec![4, 5], &mut buffer).unwrap();
+
+        // Partial frames are buffered until the rest of the frame arrives.
+        let mut partial = buffer.split_to(6);
+        assert!(codec.decode(&mut partial).unwrap().is_none());
+        partial.unsplit(buffer);
+        let mut buffer = partial;
+
+        let first = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(first.as_slice(), &[1, 2, 3]);
+        let second = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(second.as_slice(), &[4, 5]);
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+    }
+
+    #[test]
+    fn checksummed_frames() {
+        let value = vec![1u32, 2, 3];
+        let bytes = crate::to_bytes::<Error>(&value).unwrap();
+        let mut stream = Vec::new();
+        write_frame_checksummed::<_, Error>(&mut stream, &bytes).unwrap();
+
+        let mut codec = RkyvCodec::<Vec<u32>>::new();
+        let mut buffer = BytesMut::from(stream.as_slice());
+        let decoded = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(decoded.as_slice(), &[1, 2, 3]);
+
+        let last = stream.len() - 1;
+        stream[last] ^= 0xff;
+        let mut buffer = BytesMut::from(stream.as_slice());
+        assert!(codec.decode(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn max_frame_len() {
+        let mut codec = RkyvCodec::<Vec<u32>>::new().with_max_frame_len(16);
+        let mut buffer = BytesMut::new();
+        assert!(codec.encode(&vec![0; 16], &mut buffer).is_err());
+        assert!(buffer.is_empty());
+
+        buffer.extend_from_slice(&1024u32.to_le_bytes());
+        assert!(codec.decode(&mut buffer).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+
+use rancor::{fail, Source};
+
+use crate::{
+    ser::DefaultSerializer,
+    util::{to_bytes, AlignedVec},
+    Serialize,
+};
+
+/// The value that every compressed archive header starts with.
+const MAGIC: u32 = u32::from_le_bytes(*b"rkyc");
+/// The size of the header written before every compressed archive.
+const HEADER_SIZE: usize = 16;
+
+/// A compression algorithm for whole archives.
+///
+/// Compressed archives start with a header containing the ID of the algorithm
+/// and the length of the uncompressed archive, followed by the compressed
+/// bytes.
+pub trait Compression {
+    /// A unique identifier for the algorithm, which is written to the header
+    /// of compressed archives.
+    const ID: u32;
+
+    /// Compresses some bytes and appends them to `out`.
+    fn compress<E: Source>(bytes: &[u8], out: &mut Vec<u8>) -> Result<(), E>;
+
+    /// Decompresses some bytes into `out` and returns the number of bytes
+    /// written.
+    ///
+    /// `out` is exactly as long as the original uncompressed bytes.
+    fn decompress<E: Source>(bytes: &[u8], out: &mut [u8]) -> Result<usize, E>;
+}
+
+/// Zstandard compression.
+///
+/// Archives are compressed with the default compression level.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     util::{decompress_to_aligned, to_bytes_compressed, Zstd},
+/// };
+///
+/// let value = vec![42u32; 1024];
+/// let compressed = to_bytes_compressed::<Zstd, Error>(&value).unwrap();
+/// assert!(compressed.len() < 1024);
+///
+/// let bytes = decompress_to_aligned::<Zstd, Error>(&compressed).unwrap();
+/// let archived =
+///     rkyv::access::<rkyv::Archived<Vec<u32>>, Error>(&bytes).unwrap();
+/// assert_eq!(archived.len(), 1024);
+/// ```
+#[cfg(feature = "zstd")]
+pub struct Zstd;
+
+#[cfg(feature = "zstd")]
+impl Compression for Zstd {
+    const ID: u32 = u32::from_le_bytes(*b"zstd");
+
+    fn compress<E: Source>(bytes: &[u8], out: &mut Vec<u8>) -> Result<(), E> {
+        zstd::stream::copy_encode(bytes, out, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .map_err(E::new)
+    }
+
+    fn decompress<E: Source>(bytes: &[u8], out: &mut [u8]) -> Result<usize, E> {
+        zstd::bulk::decompress_to_buffer(bytes, out).map_err(E::new)
+    }
+}
+
+/// Serializes the given value and compresses the resulting bytes with `C`.
+///
+/// The compressed bytes start with a header which records the length of the
+/// uncompressed archive, so that [`decompress_to_aligned`] can allocate its
+/// output buffer up front.
+pub fn to_bytes_compressed<C, E>(
+    value: &impl for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>,
+) -> Result<Vec<u8>, E>
+where
+    C: Compression,
+    E: Source,
+{
+    let bytes = to_bytes::<E>(value)?;
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + bytes.len() / 2);
+    result.extend_from_slice(&MAGIC.to_le_bytes());
+    result.extend_from_slice(&C::ID.to_le_bytes());
+    result.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    C::compress::<E>(&bytes, &mut result)?;
+
+    Ok(result)
+}
+
+/// Decompresses bytes written by [`to_bytes_compressed`] into an
+/// [`AlignedVec`].
+///
+/// The buffer is allocated with the uncompressed length recorded in the header,
+/// and the archive is decompressed directly into it so that it is properly
+/// aligned to be accessed.
+pub fn decompress_to_aligned<C, E>(bytes: &[u8]) -> Result<AlignedVec, E>
+where
+    C: Compression,
+    E: Source,
+{
+    if bytes.len() < HEADER_SIZE || read_u32(bytes, 0) != MAGIC {
+        fail!(InvalidHeaderError);
+    }
+    let id = read_u32(bytes, 4);
+    if id != C::ID {
+        fail!(AlgorithmMismatchError {
+            expected: C::ID,
+            found: id,
+        });
+    }
+    let len = match usize::try_from(read_u64(bytes, 8)) {
+        Ok(len) if len <= AlignedVec::<16>::MAX_CAPACITY => len,
+        _ => fail!(InvalidHeaderError),
+    };
+
+    let mut result = AlignedVec::with_capacity(len);
+    result.resize(len, 0);
+    let decompressed = C::decompress::<E>(&bytes[HEADER_SIZE..], &mut result)?;
+    if decompressed != len {
+        fail!(LengthMismatchError {
+            expected: len,
+            actual: decompressed,
+        });
+    }
+
+    Ok(result)
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], pos: usize) -> u64 {
+    u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap())
+}
+
+#[derive(Debug)]
+struct InvalidHeaderError;
+
+impl fmt::Display for InvalidHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "compressed archive header is missing or invalid")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidHeaderError {}
+
+#[derive(Debug)]
+struct AlgorithmMismatchError {
+    expected: u32,
+    found: u32,
+}
+
+impl fmt::Display for AlgorithmMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive was compressed with algorithm {:#010x} but expected \
+             {:#010x}",
+            self.found, self.expected,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AlgorithmMismatchError {}
+
+#[derive(Debug)]
+struct LengthMismatchError {
+    expected: usize,
+    actual: usize,
+}
+
+impl fmt::Display for LengthMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "decompressed archive was {} bytes but the header recorded {} \
+             bytes",
+            self.actual, self.expected,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LengthMismatchError {}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use core::fmt;
+
+    use rancor::{fail, Error, Source};
+
+    use super::{decompress_to_aligned, to_bytes_compressed, Compression};
+    use crate::{access, Archived};
+
+    struct Identity;
+
+    impl Compression for Identity {
+        const ID: u32 = u32::from_le_bytes(*b"iden");
+
+        fn compress<E: Source>(
+            bytes: &[u8],
+            out: &mut Vec<u8>,
+        ) -> Result<(), E> {
+            out.extend_from_slice(bytes);
+            Ok(())
+        }
+
+        fn decompress<E: Source>(
+            bytes: &[u8],
+            out: &mut [u8],
+        ) -> Result<usize, E> {
+            let len = bytes.len().min(out.len());
+            out[..len].copy_from_slice(&bytes[..len]);
+            Ok(len)
+        }
+    }
+
+    struct Other;
+
+    #[derive(Debug)]
+    struct UnsupportedError;
+
+    impl fmt::Display for UnsupportedError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "the test codec does not support this operation")
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for UnsupportedError {}
+
+    impl Compression for Other {
+        const ID: u32 = u32::from_le_bytes(*b"othr");
+
+        fn compress<E: Source>(_: &[u8], _: &mut Vec<u8>) -> Result<(), E> {
+            fail!(UnsupportedError);
+        }
+
+        fn decompress<E: Source>(_: &[u8], _: &mut [u8]) -> Result<usize, E> {
+            fail!(UnsupportedError);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = vec![1u32, 2, 3];
+        let compressed =
+            to_bytes_compressed::<Identity, Error>(&value).unwrap();
+        let bytes =
+            decompress_to_aligned::<Identity, Error>(&compressed).unwrap();
+        let archived = access::<Archived<Vec<u32>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert!(decompress_to_aligned::<Identity, Error>(&[]).is_err());
+
+        let mut compressed =
+            to_bytes_compressed::<Identity, Error>(&vec![1u32]).unwrap();
+        assert!(decompress_to_aligned::<Other, Error>(&compressed).is_err());
+
+        compressed[0] ^= 0xff;
+        assert!(decompress_to_aligned::<Identity, Error>(&compressed).is_err());
+    }
+
+    #[test]
+    fn truncated() {
+        let compressed =
+            to_bytes_compressed::<Identity, Error>(&vec![1u32, 2, 3]).unwrap();
+        let truncated = &compressed[..compressed.len() - 4];
+        assert!(decompress_to_aligned::<Identity, Error>(truncated).is_err());
+    }
+}
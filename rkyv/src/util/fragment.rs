@@ -0,0 +1,185 @@
+use core::{fmt, marker::PhantomData, mem::size_of};
+
+#[cfg(feature = "bytecheck")]
+use bytecheck::CheckBytes;
+#[cfg(feature = "bytecheck")]
+use rancor::Strategy;
+use rancor::{Fallible, Source};
+
+#[cfg(feature = "bytecheck")]
+use crate::validation::validators::DefaultValidator;
+use crate::{
+    boxed::{ArchivedBox, BoxResolver},
+    primitive::ArchivedIsize,
+    ser::{DefaultSerializer, Writer, WriterExt as _},
+    util::{access_unchecked, to_bytes, AlignedVec, StableBytes},
+    Archive, Place, Serialize,
+};
+
+/// A value serialized into a detached buffer, which can be spliced into other
+/// archives later.
+///
+/// Relative pointers only depend on the distance between a pointer and its
+/// target, so the bytes of a fragment stay valid when they are copied
+/// anywhere with the same alignment. Serializing a fragment copies its bytes
+/// into the parent archive and relocates its root, without serializing the
+/// value again. This allows serialized values to be cached and reused, and
+/// archives to be assembled from parts which were serialized at different
+/// times.
+///
+/// A `Fragment<T>` is archived as an [`ArchivedBox`] which points to the
+/// spliced root. Fragments are always spliced at positions aligned to
+/// [`AlignedVec::ALIGNMENT`], so archived values with a greater alignment are
+/// not supported. Shared pointers inside a fragment are not shared with the
+/// rest of the archive.
+///
+/// Fragments can be backed by any [`StableBytes`] buffer. Fragments backed by
+/// a [`SharedAlignedVec`](crate::util::SharedAlignedVec) can be cloned
+/// without copying their bytes.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked, rancor::Error, to_bytes, util::Fragment, Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Response {
+///     id: u32,
+///     dictionary: Fragment<Vec<String>>,
+/// }
+///
+/// let words = vec!["hello".to_string(), "world".to_string()];
+/// let dictionary = Fragment::new::<Error>(&words).unwrap();
+///
+/// let response = Response { id: 1, dictionary };
+/// let bytes = to_bytes::<Error>(&response).unwrap();
+/// let archived = unsafe { access_unchecked::<ArchivedResponse>(&bytes) };
+/// assert_eq!(archived.id, 1);
+/// assert_eq!(archived.dictionary[1], "world");
+/// ```
+pub struct Fragment<T: Archive, B = AlignedVec> {
+    bytes: B,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Archive> Fragment<T> {
+    /// Serializes the given value into a new fragment.
+    pub fn new<E>(value: &T) -> Result<Self, E>
+    where
+        T: for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>,
+        E: Source,
+    {
+        let bytes = to_bytes::<E>(value)?;
+        // SAFETY: The bytes were just produced by serializing a `T`, with its
+        // root at the end of the buffer.
+        unsafe { Ok(Self::from_bytes_unchecked(bytes)) }
+    }
+}
+
+impl<T: Archive, B: StableBytes> Fragment<T, B> {
+    /// Creates a fragment from the given bytes without checking them.
+    ///
+    /// # Safety
+    ///
+    /// - The bytes must represent an archived `T`.
+    /// - The root of the object must be stored at the end of the bytes (this is
+    ///   the default behavior).
+    #[inline]
+    pub unsafe fn from_bytes_unchecked(bytes: B) -> Self {
+        Self {
+            bytes,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a fragment from the given bytes after checking their validity.
+    #[cfg(feature = "bytecheck")]
+    pub fn from_bytes<E>(bytes: B) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+        E: Source,
+    {
+        crate::access::<T::Archived, E>(&bytes)?;
+        // SAFETY: We just checked that the bytes contain a valid archived `T`
+        // at their root position.
+        unsafe { Ok(Self::from_bytes_unchecked(bytes)) }
+    }
+
+    /// Returns the bytes of the fragment.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the backing buffer of the fragment.
+    #[inline]
+    pub fn into_bytes(self) -> B {
+        self.bytes
+    }
+
+    /// Returns the archived value of the fragment.
+    #[inline]
+    pub fn archived(&self) -> &T::Archived {
+        // SAFETY: The fragment was checked (or guaranteed by the caller) to
+        // contain a valid archived `T` at its root position when it was
+        // created.
+        unsafe { access_unchecked::<T::Archived>(&self.bytes) }
+    }
+
+    /// Writes the bytes of the fragment to the given writer and returns the
+    /// position of its root.
+    pub fn write_to<W, E>(&self, writer: &mut W) -> Result<usize, E>
+    where
+        W: Writer<E> + ?Sized,
+    {
+        let pos = writer.align(<AlignedVec>::ALIGNMENT)?;
+        writer.write(&self.bytes)?;
+        Ok(pos + self.bytes.len() - size_of::<T::Archived>())
+    }
+}
+
+impl<T: Archive, B: StableBytes + Clone> Clone for Fragment<T, B> {
+    fn clone(&self) -> Self {
+        // SAFETY: The bytes are a copy of a valid fragment.
+        unsafe { Self::from_bytes_unchecked(self.bytes.clone()) }
+    }
+}
+
+impl<T, B> fmt::Debug for Fragment<T, B>
+where
+    T: Archive,
+    B: StableBytes,
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.archived().fmt(f)
+    }
+}
+
+impl<T: Archive, B> Archive for Fragment<T, B> {
+    type Archived = ArchivedBox<T::Archived>;
+    type Resolver = BoxResolver;
+
+    #[inline]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedBox::resolve_from_raw_parts(resolver, (), out);
+    }
+}
+
+impl<T, B, S> Serialize<S> for Fragment<T, B>
+where
+    T: Archive,
+    B: StableBytes,
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let pos = self.write_to(serializer)?;
+        serializer.check_offset::<ArchivedIsize>(pos)?;
+        Ok(BoxResolver::from_pos(pos))
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+use core::{
+    any::{Any, TypeId},
+    fmt,
+    hash::Hash,
+};
+#[cfg(feature = "std")]
+use std::{collections::hash_map, sync::Arc};
+
+#[cfg(not(feature = "std"))]
+use hashbrown::hash_map;
+use rancor::Source;
+
+use crate::{
+    ser::DefaultSerializer,
+    util::{to_bytes, AlignedVec, Fragment, SharedAlignedVec},
+    Archive, Serialize,
+};
+
+/// A cache of serialized values which can be reused across serializations.
+///
+/// Values are serialized into a [`Fragment`] the first time they are
+/// requested, and the same bytes are returned for every later request.
+/// Splicing the returned fragments into archives copies their bytes instead of
+/// serializing the values again, so a large value which appears in every
+/// message is only serialized once per cache.
+///
+/// Values in [`Arc`]s are identified by their allocation, and the cache keeps
+/// them alive so that their addresses are not reused. Other values are
+/// identified by a key of type `K` which describes their contents. Cached
+/// fragments are backed by a [`SharedAlignedVec`], so they are cheap to clone.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv::{
+///     access_unchecked,
+///     rancor::Error,
+///     to_bytes,
+///     util::{Fragment, FragmentCache, SharedAlignedVec},
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Message {
+///     id: u32,
+///     dictionary: Fragment<Vec<String>, SharedAlignedVec>,
+/// }
+///
+/// let dictionary = Arc::new(vec!["hello".to_string(), "world".to_string()]);
+/// let mut cache = FragmentCache::<()>::new();
+///
+/// for id in 0..3 {
+///     let message = Message {
+///         id,
+///         dictionary: cache
+///             .get_or_insert_arc::<_, Error>(&dictionary)
+///             .unwrap(),
+///     };
+///     let bytes = to_bytes::<Error>(&message).unwrap();
+///     let archived = unsafe { access_unchecked::<ArchivedMessage>(&bytes) };
+///     assert_eq!(archived.dictionary[0], "hello");
+/// }
+/// assert_eq!(cache.len(), 1);
+/// ```
+pub struct FragmentCache<K> {
+    by_arc:
+        hash_map::HashMap<(usize, TypeId), (Arc<dyn Any>, SharedAlignedVec)>,
+    by_key: hash_map::HashMap<(TypeId, K), SharedAlignedVec>,
+}
+
+impl<K> FragmentCache<K> {
+    /// Creates a new, empty `FragmentCache`.
+    pub fn new() -> Self {
+        Self {
+            by_arc: hash_map::HashMap::new(),
+            by_key: hash_map::HashMap::new(),
+        }
+    }
+
+    /// Returns the number of cached fragments.
+    pub fn len(&self) -> usize {
+        self.by_arc.len() + self.by_key.len()
+    }
+
+    /// Returns `true` if the cache contains no fragments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all of the cached fragments and releases the cached `Arc`s.
+    pub fn clear(&mut self) {
+        self.by_arc.clear();
+        self.by_key.clear();
+    }
+}
+
+impl<K: Hash + Eq> FragmentCache<K> {
+    /// Returns the fragment for the value in the given `Arc`, serializing it
+    /// if it has not been cached yet.
+    pub fn get_or_insert_arc<T, E>(
+        &mut self,
+        value: &Arc<T>,
+    ) -> Result<Fragment<T, SharedAlignedVec>, E>
+    where
+        T: Archive
+            + for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>
+            + 'static,
+        E: Source,
+    {
+        let key = (Arc::as_ptr(value) as usize, TypeId::of::<T>());
+        let bytes = match self.by_arc.entry(key) {
+            hash_map::Entry::Occupied(entry) => entry.get().1.clone(),
+            hash_map::Entry::Vacant(entry) => {
+                let bytes = SharedAlignedVec::from(to_bytes::<E>(&**value)?);
+                let value: Arc<dyn Any> = value.clone();
+                entry.insert((value, bytes.clone()));
+                bytes
+            }
+        };
+        // SAFETY: The bytes were produced by serializing a `T`, with its root
+        // at the end of the buffer.
+        unsafe { Ok(Fragment::from_bytes_unchecked(bytes)) }
+    }
+
+    /// Returns the fragment for the given key, serializing `value` if the key
+    /// has not been cached yet.
+    ///
+    /// Keys are cached separately for each type `T`, so the same key can be
+    /// used for values of different types.
+    pub fn get_or_insert<T, E>(
+        &mut self,
+        key: K,
+        value: &T,
+    ) -> Result<Fragment<T, SharedAlignedVec>, E>
+    where
+        T: Archive
+            + for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>
+            + 'static,
+        E: Source,
+    {
+        let bytes = match self.by_key.entry((TypeId::of::<T>(), key)) {
+            hash_map::Entry::Occupied(entry) => entry.get().clone(),
+            hash_map::Entry::Vacant(entry) => {
+                let bytes = SharedAlignedVec::from(to_bytes::<E>(value)?);
+                entry.insert(bytes.clone());
+                bytes
+            }
+        };
+        // SAFETY: The bytes were produced by serializing a `T`, with its root
+        // at the end of the buffer.
+        unsafe { Ok(Fragment::from_bytes_unchecked(bytes)) }
+    }
+}
+
+impl<K> Default for FragmentCache<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> fmt::Debug for FragmentCache<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FragmentCache")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
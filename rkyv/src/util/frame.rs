@@ -0,0 +1,215 @@
+//! Length-prefixed framing for sending archives over streams.
+//!
+//! Streams like TCP sockets and Unix domain sockets don't preserve message
+//! boundaries, and bytes read from them are not aligned. [`write_frame`]
+//! writes an archive prefixed with its length so that [`read_frame`] can read
+//! exactly one archive back into an [`AlignedVec`], where it is properly
+//! aligned to be accessed.
+//!
+//! Frames written with [`write_frame_checksummed`] also include a checksum of
+//! their bytes, which `read_frame` checks automatically.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     rancor::Error,
+//!     util::{
+//!         frame::{read_frame, write_frame},
+//!         AlignedVec,
+//!     },
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(check_bytes)]
+//! struct Message {
+//!     id: u32,
+//!     body: String,
+//! }
+//!
+//! let mut stream = Vec::new();
+//! for (id, body) in [(1, "hello"), (2, "world")] {
+//!     let message = Message {
+//!         id,
+//!         body: body.to_string(),
+//!     };
+//!     let bytes = rkyv::to_bytes::<Error>(&message).unwrap();
+//!     write_frame::<_, Error>(&mut stream, &bytes).unwrap();
+//! }
+//!
+//! let mut reader = stream.as_slice();
+//! let mut buffer = AlignedVec::<16>::new();
+//! while read_frame::<_, _, Error>(&mut reader, &mut buffer).unwrap() {
+//!     let message = rkyv::access::<ArchivedMessage, Error>(&buffer).unwrap();
+//!     println!("{}: {}", message.id, message.body);
+//! }
+//! ```
+
+use core::fmt;
+use std::io::{self, Read, Write};
+
+use rancor::{fail, Source};
+
+use crate::util::AlignedVec;
+
+/// The bit of the length prefix which is set when the frame is checksummed.
+pub(crate) const CHECKSUM_FLAG: u32 = 1 << 31;
+
+/// The maximum length of a frame.
+pub const MAX_FRAME_LEN: usize = (CHECKSUM_FLAG - 1) as usize;
+
+/// Writes some bytes to a stream as a single frame.
+pub fn write_frame<W, E>(writer: &mut W, bytes: &[u8]) -> Result<(), E>
+where
+    W: Write + ?Sized,
+    E: Source,
+{
+    let len = frame_len::<E>(bytes)?;
+    writer.write_all(&len.to_le_bytes()).map_err(E::new)?;
+    writer.write_all(bytes).map_err(E::new)
+}
+
+/// Writes some bytes to a stream as a single frame along with a checksum of
+/// them.
+pub fn write_frame_checksummed<W, E>(
+    writer: &mut W,
+    bytes: &[u8],
+) -> Result<(), E>
+where
+    W: Write + ?Sized,
+    E: Source,
+{
+    let len = frame_len::<E>(bytes)? | CHECKSUM_FLAG;
+    writer.write_all(&len.to_le_bytes()).map_err(E::new)?;
+    writer
+        .write_all(&checksum(bytes).to_le_bytes())
+        .map_err(E::new)?;
+    writer.write_all(bytes).map_err(E::new)
+}
+
+fn frame_len<E: Source>(bytes: &[u8]) -> Result<u32, E> {
+    if bytes.len() > MAX_FRAME_LEN {
+        fail!(FrameTooLargeError {
+            len: bytes.len(),
+            max_len: MAX_FRAME_LEN,
+        });
+    }
+    Ok(bytes.len() as u32)
+}
+
+/// Reads a single frame from a stream into an aligned buffer, replacing the
+/// contents of the buffer.
+///
+/// Returns `false` if the stream ended before the start of a frame, and
+/// returns an error if it ended in the middle of one. If the frame has a
+/// checksum, it is checked before returning.
+///
+/// The buffer only grows as bytes are received, so a corrupted length prefix
+/// can't cause a large allocation on its own.
+pub fn read_frame<R, const A: usize, E>(
+    reader: &mut R,
+    buffer: &mut AlignedVec<A>,
+) -> Result<bool, E>
+where
+    R: Read + ?Sized,
+    E: Source,
+{
+    buffer.clear();
+
+    let mut prefix = [0; 4];
+    if !read_prefix(reader, &mut prefix).map_err(E::new)? {
+        return Ok(false);
+    }
+    let prefix = u32::from_le_bytes(prefix);
+
+    let expected_checksum = if prefix & CHECKSUM_FLAG != 0 {
+        let mut checksum = [0; 4];
+        reader.read_exact(&mut checksum).map_err(E::new)?;
+        Some(u32::from_le_bytes(checksum))
+    } else {
+        None
+    };
+
+    let len = (prefix & !CHECKSUM_FLAG) as u64;
+    let read = buffer
+        .extend_from_reader(&mut Read::take(&mut *reader, len))
+        .map_err(E::new)?;
+    if read as u64 != len {
+        fail!(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+
+    if let Some(expected) = expected_checksum {
+        let actual = checksum(buffer);
+        if actual != expected {
+            fail!(ChecksumMismatchError { expected, actual });
+        }
+    }
+
+    Ok(true)
+}
+
+/// Fills `prefix` from the reader, returning `false` if the reader ended
+/// before any bytes were read.
+fn read_prefix<R: Read + ?Sized>(
+    reader: &mut R,
+    prefix: &mut [u8; 4],
+) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < prefix.len() {
+        match reader.read(&mut prefix[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+/// Computes the 32-bit FNV-1a hash of some bytes.
+pub(crate) fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+/// An error resulting from a frame which is larger than the maximum frame
+/// length.
+#[derive(Debug)]
+pub struct FrameTooLargeError {
+    pub(crate) len: usize,
+    pub(crate) max_len: usize,
+}
+
+impl fmt::Display for FrameTooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame of {} bytes is too large, frames may be at most {} bytes",
+            self.len, self.max_len,
+        )
+    }
+}
+
+impl std::error::Error for FrameTooLargeError {}
+
+/// An error resulting from a frame whose bytes don't match its checksum.
+#[derive(Debug)]
+pub struct ChecksumMismatchError {
+    pub(crate) expected: u32,
+    pub(crate) actual: u32,
+}
+
+impl fmt::Display for ChecksumMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame checksum mismatch: expected {:#010x} but found {:#010x}",
+            self.expected, self.actual,
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatchError {}
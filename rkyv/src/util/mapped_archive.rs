@@ -0,0 +1,131 @@
+use core::{marker::PhantomData, pin::Pin};
+use std::{fs::OpenOptions, path::Path};
+
+use bytecheck::CheckBytes;
+use memmap2::MmapMut;
+use rancor::{Source, Strategy};
+
+use crate::{
+    util::{access_unchecked, access_unchecked_mut},
+    validation::validators::DefaultValidator,
+    Archive,
+};
+
+/// An archive file which is memory-mapped read-write.
+///
+/// Unlike an [`ArchiveFile`](crate::util::ArchiveFile), edits made through
+/// [`get_mut`](MappedArchiveMut::get_mut) modify the mapped file directly. The
+/// operating system writes modified pages back to the file eventually, and
+/// [`flush`](MappedArchiveMut::flush) and
+/// [`flush_range`](MappedArchiveMut::flush_range) can be used to write them
+/// back immediately.
+///
+/// Edits are not checked, so they must keep the archive valid.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Error, util::MappedArchiveMut, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Counter {
+///     name: String,
+///     count: u32,
+/// }
+///
+/// let path = std::env::temp_dir().join("rkyv_mapped_archive_mut_doc");
+/// let value = Counter {
+///     name: "visits".to_string(),
+///     count: 41,
+/// };
+/// std::fs::write(&path, rkyv::to_bytes::<Error>(&value).unwrap()).unwrap();
+///
+/// let mut map =
+///     unsafe { MappedArchiveMut::<Counter>::open::<Error>(&path) }.unwrap();
+/// let mut counter = map.get_mut();
+/// // SAFETY: `count` is never moved out of the pinned counter.
+/// let count = unsafe { &mut counter.as_mut().get_unchecked_mut().count };
+/// *count = (count.to_native() + 1).into();
+/// map.flush::<Error>().unwrap();
+///
+/// let bytes = std::fs::read(&path).unwrap();
+/// let counter = rkyv::access::<ArchivedCounter, Error>(&bytes).unwrap();
+/// assert_eq!(counter.count, 42);
+/// # drop(map);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MappedArchiveMut<T: Archive> {
+    map: MmapMut,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Archive> MappedArchiveMut<T> {
+    /// Memory-maps the archive file at the given path read-write and checks
+    /// that it contains a valid archived `T`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by anything other than this
+    /// `MappedArchiveMut` while it is mapped, including by other processes.
+    pub unsafe fn open<E>(path: impl AsRef<Path>) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+        E: Source,
+    {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(E::new)?;
+        // SAFETY: The caller has guaranteed that the file will only be
+        // modified through this `MappedArchiveMut`.
+        let map = unsafe { MmapMut::map_mut(&file).map_err(E::new)? };
+        crate::access::<T::Archived, E>(&map)?;
+
+        Ok(Self {
+            map,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the archived value.
+    pub fn get(&self) -> &T::Archived {
+        // SAFETY: The file was checked when it was mapped, and edits can only
+        // be made to it through pinned mutable references.
+        unsafe { access_unchecked::<T::Archived>(&self.map) }
+    }
+
+    /// Returns a pinned mutable reference to the archived value.
+    ///
+    /// Edits are made to the mapped file directly.
+    pub fn get_mut(&mut self) -> Pin<&mut T::Archived> {
+        // SAFETY: The file was checked when it was mapped, and edits can only
+        // be made to it through pinned mutable references.
+        unsafe { access_unchecked_mut::<T::Archived>(&mut self.map) }
+    }
+
+    /// Returns the bytes of the mapped file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Writes all modified pages back to the file.
+    ///
+    /// This blocks until the pages have been written.
+    pub fn flush<E: Source>(&self) -> Result<(), E> {
+        self.map.flush().map_err(E::new)
+    }
+
+    /// Writes the modified pages in the given byte range back to the file.
+    ///
+    /// The offset of an archived value can be found by subtracting the address
+    /// of [`as_bytes`](MappedArchiveMut::as_bytes) from the address of the
+    /// value. This blocks until the pages have been written.
+    pub fn flush_range<E: Source>(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> Result<(), E> {
+        self.map.flush_range(offset, len).map_err(E::new)
+    }
+}
@@ -12,23 +12,97 @@
 
 #[cfg(feature = "alloc")]
 mod alloc;
+#[cfg(all(feature = "memmap2", feature = "bytecheck"))]
+mod archive_file;
+#[cfg(all(feature = "std", feature = "bytecheck"))]
+mod archive_log;
+#[cfg(all(feature = "tokio-util", feature = "bytecheck"))]
+mod codec;
+#[cfg(feature = "alloc")]
+mod compression;
+#[cfg(feature = "alloc")]
+mod fragment;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+mod fragment_cache;
+#[cfg(feature = "std")]
+pub mod frame;
 mod inline_vec;
+#[cfg(all(feature = "memmap2", feature = "bytecheck"))]
+mod mapped_archive;
+#[cfg(feature = "alloc")]
+mod owned_archive;
+mod root;
+#[cfg(feature = "alloc")]
+mod sealed;
 mod ser_vec;
+mod varint;
 
 use core::{
-    mem,
+    fmt,
+    mem::MaybeUninit,
     ops::{Deref, DerefMut},
     pin::Pin,
 };
 
-use rancor::Strategy;
+use rancor::{fail, Strategy, Trace};
 
 #[doc(inline)]
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
 #[doc(inline)]
-pub use self::{inline_vec::InlineVec, ser_vec::SerVec};
-use crate::{ser::Writer, Archive, Deserialize, Portable, Serialize};
+#[cfg(all(feature = "memmap2", feature = "bytecheck"))]
+pub use self::archive_file::ArchiveFile;
+#[doc(inline)]
+#[cfg(all(feature = "std", feature = "bytecheck"))]
+pub use self::archive_log::{ArchiveLog, ArchiveLogIter, RecordTooLargeError};
+#[doc(inline)]
+#[cfg(all(feature = "tokio-util", feature = "bytecheck"))]
+pub use self::codec::{RkyvCodec, DEFAULT_MAX_FRAME_LEN};
+#[doc(inline)]
+#[cfg(feature = "zstd")]
+pub use self::compression::Zstd;
+#[doc(inline)]
+#[cfg(feature = "alloc")]
+pub use self::compression::{
+    decompress_to_aligned, to_bytes_compressed, Compression,
+};
+#[doc(inline)]
+#[cfg(feature = "alloc")]
+pub use self::fragment::Fragment;
+#[doc(inline)]
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use self::fragment_cache::FragmentCache;
+#[doc(inline)]
+#[cfg(all(feature = "memmap2", feature = "bytecheck"))]
+pub use self::mapped_archive::MappedArchiveMut;
+#[doc(inline)]
+#[cfg(feature = "alloc")]
+pub use self::owned_archive::{OwnedArchive, StableBytes};
+#[doc(inline)]
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+pub use self::sealed::open_archive;
+#[doc(inline)]
+#[cfg(feature = "alloc")]
+pub use self::sealed::{open_to_aligned, to_bytes_sealed, SealingKey};
+pub(crate) use self::varint::{
+    read_varint, read_varint_unchecked, write_varint, MAX_VARINT_LEN,
+};
+#[doc(inline)]
+pub use self::{
+    inline_vec::InlineVec,
+    root::{root_position, serialize_root, PendingRoot},
+    ser_vec::SerVec,
+};
+use crate::{
+    ser::{
+        allocator::{NoAllocator, SubAllocator},
+        sharing::Unshare,
+        writer::{Buffer, SizingBuffer},
+        CoreSerializer, NoAllocSerializer, Serializer, Writer,
+    },
+    Archive, Deserialize, DeserializeAsync, DeserializeBorrowed,
+    DeserializeProjection, Portable, Serialize,
+};
 
 #[cfg(debug_assertions)]
 fn check_alignment<T: Portable>(ptr: *const u8) {
@@ -102,7 +176,8 @@ pub unsafe fn access_pos_unchecked_mut<T: Portable>(
 /// of the root object using the length of the byte slice. If your byte slice is
 /// not guaranteed to end immediately after the root object, you may need to
 /// store the position of the root object returned from
-/// [`serialize_and_resolve`](crate::Serialize::serialize_and_resolve).
+/// [`serialize_and_resolve`](crate::Serialize::serialize_and_resolve) or
+/// [`PendingRoot::resolve_at`].
 ///
 /// # Safety
 ///
@@ -111,9 +186,10 @@ pub unsafe fn access_pos_unchecked_mut<T: Portable>(
 ///   default behavior).
 pub unsafe fn access_unchecked<T: Portable>(bytes: &[u8]) -> &T {
     // SAFETY: The caller has guaranteed that a valid `T` is located at the root
-    // position in the byte slice.
+    // position in the byte slice, so the byte slice is at least as long as `T`.
     unsafe {
-        access_pos_unchecked::<T>(bytes, bytes.len() - mem::size_of::<T>())
+        let pos = root_position::<T>(bytes.len()).unwrap_unchecked();
+        access_pos_unchecked::<T>(bytes, pos)
     }
 }
 
@@ -124,7 +200,8 @@ pub unsafe fn access_unchecked<T: Portable>(bytes: &[u8]) -> &T {
 /// position of the root object using the length of the byte slice. If your byte
 /// slice is not guaranteed to end immediately after the root object, you may
 /// need to store the position of the root object returned from
-/// [`serialize_and_resolve`](crate::Serialize::serialize_and_resolve).
+/// [`serialize_and_resolve`](crate::Serialize::serialize_and_resolve) or
+/// [`PendingRoot::resolve_at`].
 ///
 /// # Safety
 ///
@@ -134,10 +211,12 @@ pub unsafe fn access_unchecked<T: Portable>(bytes: &[u8]) -> &T {
 pub unsafe fn access_unchecked_mut<T: Portable>(
     bytes: &mut [u8],
 ) -> Pin<&mut T> {
-    let pos = bytes.len() - mem::size_of::<T>();
     // SAFETY: The caller has guaranteed that a valid `T` is located at the root
-    // position in the byte slice.
-    unsafe { access_pos_unchecked_mut::<T>(bytes, pos) }
+    // position in the byte slice, so the byte slice is at least as long as `T`.
+    unsafe {
+        let pos = root_position::<T>(bytes.len()).unwrap_unchecked();
+        access_pos_unchecked_mut::<T>(bytes, pos)
+    }
 }
 
 /// A wrapper which aligns its inner value to 16 bytes.
@@ -164,29 +243,215 @@ impl<T> DerefMut for Align<T> {
 
 /// Serializes the given value into the given serializer and then returns the
 /// serializer.
+///
+/// See [`serialize`] for details on the errors returned.
 pub fn serialize_into<S, E>(
     value: &impl Serialize<Strategy<S, E>>,
     mut serializer: S,
 ) -> Result<S, E>
 where
     S: Writer<E>,
+    E: Trace,
 {
     serialize(value, &mut serializer)?;
     Ok(serializer)
 }
 
 /// Serializes the given value into the given serializer.
+///
+/// If serialization fails, the position of the serializer at the time of the
+/// failure is added to the error as a trace.
 pub fn serialize<S, E>(
     value: &impl Serialize<Strategy<S, E>>,
     serializer: &mut S,
 ) -> Result<(), E>
 where
     S: Writer<E> + ?Sized,
+    E: Trace,
 {
-    value.serialize_and_resolve(Strategy::wrap(serializer))?;
+    value
+        .serialize_and_resolve(Strategy::wrap(serializer))
+        .map_err(|e| {
+            e.trace(SerializerPosition {
+                pos: serializer.pos(),
+            })
+        })?;
     Ok(())
 }
 
+#[derive(Debug)]
+struct SerializerPosition {
+    pos: usize,
+}
+
+impl fmt::Display for SerializerPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "while serializing at byte position {}", self.pos)
+    }
+}
+
+/// Serializes the given value into the given uninitialized buffer and returns
+/// the initialized bytes.
+///
+/// This does not allocate, so it can be used to serialize directly into
+/// pre-allocated memory like ring buffers and shared memory segments. The
+/// archive is written starting at the beginning of the buffer, so the buffer
+/// must be suitably aligned for the archived value to be accessed in place.
+///
+/// No scratch space is provided to the serializer, so types which require
+/// scratch space to serialize (like vectors of strings) will fail to
+/// serialize. Use [`serialize_into`] with a [`SubAllocator`] for those
+/// instead.
+///
+/// # Examples
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     access_unchecked, rancor::Error, to_bytes_into, util::Align, Archived,
+/// };
+///
+/// let mut output = Align([MaybeUninit::<u8>::uninit(); 64]);
+/// let bytes = to_bytes_into::<Error>(&(1u32, 2u32), &mut *output).unwrap();
+/// assert_eq!(bytes.len(), 8);
+///
+/// let archived = unsafe { access_unchecked::<Archived<(u32, u32)>>(bytes) };
+/// assert_eq!(archived.0, 1);
+/// assert_eq!(archived.1, 2);
+/// ```
+pub fn to_bytes_into<'a, E>(
+    value: &impl for<'b> Serialize<CoreSerializer<'b, Buffer<'a>, E>>,
+    out: &'a mut [MaybeUninit<u8>],
+) -> Result<&'a [u8], E>
+where
+    E: rancor::Source,
+{
+    let serializer =
+        Serializer::new(Buffer::from(out), SubAllocator::empty(), Unshare);
+    let buffer = serialize_into(value, serializer)?.into_writer();
+    Ok(buffer.into_slice())
+}
+
+/// Serializes the given value into the given uninitialized buffer without any
+/// scratch space and returns the initialized bytes.
+///
+/// Unlike [`to_bytes_into`], this statically requires the value to serialize
+/// without allocating scratch space, so it cannot fail because scratch space
+/// ran out. It only fails if the buffer is too small or the value returns an
+/// error. This makes it suitable for hard real-time code where no arena is
+/// available. The archive is written starting at the beginning of the buffer,
+/// so the buffer must be suitably aligned for the archived value to be accessed
+/// in place.
+///
+/// # Examples
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     access_unchecked, rancor::Error, to_bytes_noalloc, util::Align,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Sample {
+///     channel: u8,
+///     values: [f32; 4],
+///     label: Option<u32>,
+/// }
+///
+/// let sample = Sample {
+///     channel: 3,
+///     values: [1.0, 2.0, 3.0, 4.0],
+///     label: Some(42),
+/// };
+///
+/// let mut output = Align([MaybeUninit::<u8>::uninit(); 64]);
+/// let bytes = to_bytes_noalloc::<Error>(&sample, &mut *output).unwrap();
+///
+/// let archived = unsafe { access_unchecked::<ArchivedSample>(bytes) };
+/// assert_eq!(archived.channel, 3);
+/// assert_eq!(archived.values[2].to_native(), 3.0);
+/// assert_eq!(archived.label.as_ref().map(|l| l.to_native()), Some(42));
+/// ```
+///
+/// Types which need scratch space do not compile:
+///
+/// ```compile_fail
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{rancor::Error, to_bytes_noalloc, util::Align};
+///
+/// let value = vec!["hello".to_string()];
+/// let mut output = Align([MaybeUninit::<u8>::uninit(); 64]);
+/// to_bytes_noalloc::<Error>(&value, &mut *output).unwrap();
+/// ```
+pub fn to_bytes_noalloc<'a, E>(
+    value: &impl Serialize<NoAllocSerializer<Buffer<'a>, E>>,
+    out: &'a mut [MaybeUninit<u8>],
+) -> Result<&'a [u8], E>
+where
+    E: rancor::Source,
+{
+    let serializer = Serializer::new(Buffer::from(out), NoAllocator, Unshare);
+    let buffer = serialize_into(value, serializer)?.into_writer();
+    Ok(buffer.into_slice())
+}
+
+/// The amount of stack space used as scratch space by [`to_bytes_in_slice`].
+pub const SLICE_SCRATCH_SIZE: usize = 1024;
+
+/// Serializes the given value into the given byte slice and returns the
+/// written bytes.
+///
+/// This does not allocate. The serializer uses [`SLICE_SCRATCH_SIZE`] bytes of
+/// stack space as scratch space. The archive is written starting at the
+/// beginning of the slice, so the slice must be suitably aligned for the
+/// archived value to be accessed in place.
+///
+/// If the slice is too small to hold the archive, this fails with a
+/// [`BufferTooSmall`](crate::ser::writer::BufferTooSmall) error containing the
+/// number of bytes needed.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     access_unchecked,
+///     rancor::Error,
+///     util::{to_bytes_in_slice, Align},
+///     Archived,
+/// };
+///
+/// let value = vec!["hello".to_string(), "world".to_string()];
+///
+/// let mut output = Align([0u8; 64]);
+/// let bytes = to_bytes_in_slice::<Error>(&value, &mut *output).unwrap();
+/// let archived = unsafe { access_unchecked::<Archived<Vec<String>>>(bytes) };
+/// assert_eq!(archived[0], "hello");
+/// assert_eq!(archived[1], "world");
+///
+/// let mut output = Align([0u8; 8]);
+/// assert!(to_bytes_in_slice::<Error>(&value, &mut *output).is_err());
+/// ```
+pub fn to_bytes_in_slice<'a, E>(
+    value: &impl for<'b> Serialize<CoreSerializer<'b, SizingBuffer<'a>, E>>,
+    out: &'a mut [u8],
+) -> Result<&'a [u8], E>
+where
+    E: rancor::Source,
+{
+    let mut scratch = [MaybeUninit::<u8>::uninit(); SLICE_SCRATCH_SIZE];
+    let serializer = Serializer::new(
+        SizingBuffer::from(out),
+        SubAllocator::new(&mut scratch),
+        Unshare,
+    );
+    let writer = serialize_into(value, serializer)?.into_writer();
+    match writer.finish() {
+        Ok(bytes) => Ok(bytes),
+        Err(e) => fail!(e),
+    }
+}
+
 /// Deserailizes a value from the given archived value using the provided
 /// deserializer.
 pub fn deserialize<T, D, E>(
@@ -199,3 +464,40 @@ where
 {
     value.deserialize(Strategy::wrap(deserializer))
 }
+
+/// Deserializes a value asynchronously from the given archived value using the
+/// provided deserializer.
+pub async fn deserialize_async<T, D, E>(
+    value: &T::Archived,
+    deserializer: &mut D,
+) -> Result<T, E>
+where
+    T: Archive,
+    T::Archived: DeserializeAsync<T, Strategy<D, E>>,
+{
+    value.deserialize_async(Strategy::wrap(deserializer)).await
+}
+
+/// Deserializes a value which borrows from the given archived value using the
+/// provided deserializer.
+pub fn deserialize_borrowed<'a, T, A, D, E>(
+    value: &'a A,
+    deserializer: &mut D,
+) -> Result<T, E>
+where
+    A: DeserializeBorrowed<'a, T, Strategy<D, E>> + ?Sized,
+{
+    value.deserialize_borrowed(Strategy::wrap(deserializer))
+}
+
+/// Deserializes a projection from the given archived value using the provided
+/// deserializer.
+pub fn deserialize_projection<P, A, D, E>(
+    value: &A,
+    deserializer: &mut D,
+) -> Result<P, E>
+where
+    A: DeserializeProjection<P, Strategy<D, E>> + ?Sized,
+{
+    value.deserialize_projection(Strategy::wrap(deserializer))
+}
@@ -0,0 +1,230 @@
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, marker::PhantomData, ops::Deref};
+
+#[cfg(feature = "bytecheck")]
+use bytecheck::CheckBytes;
+use rancor::Source;
+#[cfg(feature = "bytecheck")]
+use rancor::Strategy;
+
+#[cfg(feature = "bytecheck")]
+use crate::validation::validators::DefaultValidator;
+use crate::{
+    ser::DefaultSerializer,
+    util::{access_unchecked, to_bytes, AlignedVec},
+    Archive, Serialize,
+};
+
+/// A byte buffer which keeps its contents at the same address when it is
+/// moved.
+///
+/// # Safety
+///
+/// The slice returned by `deref` must have the same address and length every
+/// time it is called, including after the buffer has been moved. The bytes in
+/// the slice must not be modified while the buffer is alive.
+pub unsafe trait StableBytes: Deref<Target = [u8]> {}
+
+// SAFETY: The bytes of an `AlignedVec` are heap-allocated and are not moved
+// when the `AlignedVec` is moved. `OwnedArchive` never exposes the vector
+// mutably.
+unsafe impl<const A: usize> StableBytes for AlignedVec<A> {}
+
+// SAFETY: The bytes of a `SharedAlignedVec` are heap-allocated and immutable.
+#[cfg(target_has_atomic = "ptr")]
+unsafe impl<const A: usize> StableBytes for crate::util::SharedAlignedVec<A> {}
+
+// SAFETY: The bytes of a `Vec<u8>` are heap-allocated and are not moved when
+// the `Vec` is moved. `OwnedArchive` never exposes the vector mutably.
+unsafe impl StableBytes for Vec<u8> {}
+
+// SAFETY: The bytes of a `Box<[u8]>` are heap-allocated and are not moved when
+// the `Box` is moved. `OwnedArchive` never exposes the box mutably.
+unsafe impl StableBytes for Box<[u8]> {}
+
+/// An archive which owns its backing buffer.
+///
+/// `OwnedArchive` dereferences to the archived root object of its buffer. This
+/// allows archived values to be returned from functions and stored in structs
+/// alongside the bytes they borrow from.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Error, util::OwnedArchive, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     name: String,
+///     values: Vec<u32>,
+/// }
+///
+/// fn load(bytes: rkyv::util::AlignedVec) -> OwnedArchive<Example> {
+///     OwnedArchive::new::<Error>(bytes).unwrap()
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     values: vec![3, 1, 4, 1, 5],
+/// };
+/// let archive = load(rkyv::to_bytes::<Error>(&value).unwrap());
+///
+/// assert_eq!(archive.name, "pi");
+/// assert_eq!(archive.values.len(), 5);
+/// ```
+pub struct OwnedArchive<T: Archive, B = AlignedVec> {
+    buffer: B,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Archive, B: StableBytes> OwnedArchive<T, B> {
+    /// Creates a new `OwnedArchive` from the given buffer without checking
+    /// it.
+    ///
+    /// # Safety
+    ///
+    /// - The buffer must represent an archived `T`.
+    /// - The root of the object must be stored at the end of the buffer (this
+    ///   is the default behavior).
+    #[inline]
+    pub unsafe fn new_unchecked(buffer: B) -> Self {
+        Self {
+            buffer,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new `OwnedArchive` from the given buffer after checking its
+    /// validity.
+    #[cfg(feature = "bytecheck")]
+    pub fn new<E>(buffer: B) -> Result<Self, E>
+    where
+        T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+        E: Source,
+    {
+        crate::access::<T::Archived, E>(&buffer)?;
+        // SAFETY: We just checked that the buffer contains a valid archived
+        // `T` at its root position.
+        unsafe { Ok(Self::new_unchecked(buffer)) }
+    }
+
+    /// Returns the bytes of the archive.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the backing buffer of the archive.
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+}
+
+impl<T: Archive> OwnedArchive<T> {
+    /// Serializes the given value into a new `OwnedArchive`.
+    ///
+    /// The serialized bytes are not checked, since they were just produced
+    /// from a valid value.
+    ///
+    /// # Examples
+    /// ```
+    /// use rkyv::{rancor::Error, util::OwnedArchive};
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let archive =
+    ///     OwnedArchive::<Vec<i32>>::from_value::<Error>(&value).unwrap();
+    /// assert_eq!(archive.as_slice(), &[1, 2, 3]);
+    /// ```
+    pub fn from_value<E>(value: &T) -> Result<Self, E>
+    where
+        T: for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>,
+        E: Source,
+    {
+        let bytes = to_bytes::<E>(value)?;
+        // SAFETY: The bytes were just produced by serializing a `T`, with its
+        // root at the end of the buffer.
+        unsafe { Ok(Self::new_unchecked(bytes)) }
+    }
+}
+
+impl<T: Archive, B: StableBytes> Deref for OwnedArchive<T, B> {
+    type Target = T::Archived;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The buffer was checked (or guaranteed by the caller) to
+        // contain a valid archived `T` at its root position when this archive
+        // was created, and `StableBytes` guarantees that it has not changed
+        // since.
+        unsafe { access_unchecked::<T::Archived>(&self.buffer) }
+    }
+}
+
+impl<T, B> fmt::Debug for OwnedArchive<T, B>
+where
+    T: Archive,
+    T::Archived: fmt::Debug,
+    B: StableBytes,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(feature = "memmap2")]
+const _: () = {
+    #[cfg(feature = "bytecheck")]
+    use std::{fs::File, path::Path};
+
+    use memmap2::Mmap;
+
+    // SAFETY: The memory of a `Mmap` is not moved when the `Mmap` is moved,
+    // and it is mapped read-only.
+    unsafe impl StableBytes for Mmap {}
+
+    #[cfg(feature = "bytecheck")]
+    impl<T: Archive> OwnedArchive<T, Mmap> {
+        /// Memory-maps the file at the given path and checks that it contains
+        /// a valid archived `T`.
+        ///
+        /// Memory maps are always page-aligned, so the archive is properly
+        /// aligned as long as the root object is placed at a suitable offset
+        /// in the file. The file is only checked once, after which accessing
+        /// the archive is free.
+        ///
+        /// # Safety
+        ///
+        /// The file must not be modified or truncated while it is mapped,
+        /// including by other processes. Modifying the file could change the
+        /// contents of the archive after it has been checked.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// use rkyv::{rancor::Error, util::OwnedArchive, Archive};
+        ///
+        /// #[derive(Archive)]
+        /// #[archive(check_bytes)]
+        /// struct Example {
+        ///     name: String,
+        /// }
+        ///
+        /// let path = "example.bin";
+        /// let archive =
+        ///     unsafe { OwnedArchive::<Example, _>::map_file::<Error>(path) };
+        /// println!("{}", archive.unwrap().name);
+        /// ```
+        pub unsafe fn map_file<E>(path: impl AsRef<Path>) -> Result<Self, E>
+        where
+            T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+            E: Source,
+        {
+            let file = File::open(path).map_err(E::new)?;
+            // SAFETY: The caller has guaranteed that the file will not be
+            // modified while it is mapped.
+            let map = unsafe { Mmap::map(&file).map_err(E::new)? };
+            Self::new(map)
+        }
+    }
+};
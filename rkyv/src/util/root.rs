@@ -0,0 +1,156 @@
+use core::{
+    fmt,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+};
+
+use rancor::{fail, Source, Strategy};
+
+use crate::{
+    ser::{Writer, WriterExt as _},
+    Archive, Portable, Serialize,
+};
+
+/// Returns the position of the root object in an archive of `len` bytes.
+///
+/// By default, the root object of an archive is written last, so it ends at
+/// the end of the archive. This is the position that
+/// [`access_unchecked`](crate::access_unchecked) and
+/// [`access`](crate::access) read the root from. Returns `None` if the archive
+/// is too short to contain a `T`.
+///
+/// # Examples
+/// ```
+/// use rkyv::{rancor::Error, util::root_position, Archived};
+///
+/// let bytes = rkyv::to_bytes::<Error>(&(1u32, 2u32)).unwrap();
+/// let pos = root_position::<Archived<(u32, u32)>>(bytes.len()).unwrap();
+/// assert_eq!(pos, bytes.len() - 8);
+/// assert!(root_position::<Archived<(u32, u32)>>(4).is_none());
+/// ```
+#[inline]
+pub const fn root_position<T: Portable>(len: usize) -> Option<usize> {
+    len.checked_sub(size_of::<T>())
+}
+
+/// A value whose dependencies have been serialized, but which has not been
+/// resolved as the root of an archive yet.
+///
+/// This is returned by [`serialize_root`]. Between serializing the
+/// dependencies and resolving the root, arbitrary bytes like page headers can
+/// be written to the serializer. The root can then be resolved at the current
+/// position with [`resolve`](PendingRoot::resolve), or at a chosen position
+/// with [`resolve_at`](PendingRoot::resolve_at). This allows archived values
+/// to be embedded in formats which control where their roots are located.
+pub struct PendingRoot<'a, T: Archive + ?Sized, E> {
+    value: &'a T,
+    resolver: T::Resolver,
+    _phantom: PhantomData<E>,
+}
+
+/// Serializes the dependencies of the given value and returns a
+/// [`PendingRoot`] which can write the root object later.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     ser::{
+///         allocator::NoAllocator, sharing::Unshare, writer::Buffer,
+///         Serializer, Writer,
+///     },
+///     util::{access_pos_unchecked, serialize_root, Align},
+///     Archived,
+/// };
+///
+/// let value = "a string which is too long to be inlined".to_string();
+///
+/// let mut page = Align([0u8; 64]);
+/// let mut serializer =
+///     Serializer::new(Buffer::from(&mut *page), NoAllocator, Unshare);
+/// let pending =
+///     serialize_root::<_, _, Error>(&value, &mut serializer).unwrap();
+/// // Write some page metadata, then place the root at a fixed position.
+/// Writer::<Error>::write(&mut serializer, b"metadata").unwrap();
+/// let root = pending.resolve_at(&mut serializer, 48).unwrap();
+/// assert_eq!(root, 48);
+///
+/// let archived =
+///     unsafe { access_pos_unchecked::<Archived<String>>(&*page, root) };
+/// assert_eq!(archived, "a string which is too long to be inlined");
+/// ```
+pub fn serialize_root<'a, T, S, E>(
+    value: &'a T,
+    serializer: &mut S,
+) -> Result<PendingRoot<'a, T, E>, E>
+where
+    T: Serialize<Strategy<S, E>> + ?Sized,
+    S: Writer<E> + ?Sized,
+{
+    let resolver = value.serialize(Strategy::wrap(serializer))?;
+    Ok(PendingRoot {
+        value,
+        resolver,
+        _phantom: PhantomData,
+    })
+}
+
+impl<T: Archive + ?Sized, E> PendingRoot<'_, T, E> {
+    /// Writes the root object at the next position in the serializer which is
+    /// suitably aligned for it and returns that position.
+    pub fn resolve<W>(self, writer: &mut W) -> Result<usize, E>
+    where
+        W: Writer<E> + ?Sized,
+    {
+        writer.align_for::<T::Archived>()?;
+        // SAFETY: `resolver` is the result of serializing `value`, and the
+        // writer was just aligned for `T::Archived`.
+        unsafe { writer.resolve_aligned(self.value, self.resolver) }
+    }
+
+    /// Pads the serializer up to `pos`, writes the root object there, and
+    /// returns `pos`.
+    ///
+    /// Returns an error if the serializer is already past `pos`, or if `pos`
+    /// is not suitably aligned for the archived type.
+    pub fn resolve_at<W>(self, writer: &mut W, pos: usize) -> Result<usize, E>
+    where
+        W: Writer<E> + ?Sized,
+        E: Source,
+    {
+        let current = writer.pos();
+        let align = align_of::<T::Archived>();
+        if pos < current || pos & (align - 1) != 0 {
+            fail!(RootPositionError {
+                pos,
+                current,
+                align,
+            });
+        }
+        writer.pad(pos - current)?;
+        // SAFETY: `resolver` is the result of serializing `value`, and the
+        // writer is at `pos`, which is aligned for `T::Archived`.
+        unsafe { writer.resolve_aligned(self.value, self.resolver) }
+    }
+}
+
+#[derive(Debug)]
+struct RootPositionError {
+    pos: usize,
+    current: usize,
+    align: usize,
+}
+
+impl fmt::Display for RootPositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "root can't be written at position {}: the serializer is at \
+             position {} and the root must be aligned to {} bytes",
+            self.pos, self.current, self.align,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RootPositionError {}
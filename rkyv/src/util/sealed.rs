@@ -0,0 +1,317 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "bytecheck")]
+use bytecheck::CheckBytes;
+#[cfg(feature = "bytecheck")]
+use rancor::Strategy;
+use rancor::{fail, Source};
+
+use crate::{
+    ser::DefaultSerializer,
+    util::{to_bytes, AlignedVec},
+    Serialize,
+};
+#[cfg(feature = "bytecheck")]
+use crate::{util::OwnedArchive, validation::validators::DefaultValidator};
+
+/// The value that every sealed archive header starts with.
+const MAGIC: u32 = u32::from_le_bytes(*b"rkys");
+/// The size of the header written before every sealed archive.
+const HEADER_SIZE: usize = 16;
+
+/// A key for an authenticated encryption algorithm which can seal archives.
+///
+/// Sealed archives start with a header containing the ID of the algorithm and
+/// the length of the plaintext archive, followed by the output of
+/// [`seal`](SealingKey::seal). The header is passed to the key as associated
+/// data so that it is authenticated along with the archive.
+///
+/// Implementations are responsible for generating nonces and writing them
+/// along with the ciphertext and authentication tag.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::{Error, Source},
+///     util::{open_to_aligned, to_bytes_sealed, SealingKey},
+/// };
+///
+/// // Not a real cipher, only for illustration.
+/// struct XorKey(u8);
+///
+/// impl SealingKey for XorKey {
+///     const ID: u32 = u32::from_le_bytes(*b"xor!");
+///
+///     fn seal<E: Source>(
+///         &self,
+///         _: &[u8],
+///         plaintext: &[u8],
+///         out: &mut Vec<u8>,
+///     ) -> Result<(), E> {
+///         out.extend(plaintext.iter().map(|b| b ^ self.0));
+///         Ok(())
+///     }
+///
+///     fn open<E: Source>(
+///         &self,
+///         _: &[u8],
+///         ciphertext: &[u8],
+///         out: &mut [u8],
+///     ) -> Result<(), E> {
+///         for (o, b) in out.iter_mut().zip(ciphertext) {
+///             *o = b ^ self.0;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let key = XorKey(0x5a);
+/// let sealed = to_bytes_sealed::<_, Error>(&vec![1u32, 2, 3], &key).unwrap();
+/// let bytes = open_to_aligned::<_, Error>(&sealed, &key).unwrap();
+/// let archived =
+///     rkyv::access::<rkyv::Archived<Vec<u32>>, Error>(&bytes).unwrap();
+/// assert_eq!(archived.as_slice(), &[1, 2, 3]);
+/// ```
+pub trait SealingKey {
+    /// A unique identifier for the algorithm, which is written to the header
+    /// of sealed archives.
+    const ID: u32;
+
+    /// Encrypts and authenticates `plaintext` and `associated_data`, and
+    /// appends the result to `out`.
+    fn seal<E: Source>(
+        &self,
+        associated_data: &[u8],
+        plaintext: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), E>;
+
+    /// Verifies and decrypts `ciphertext` into `out`.
+    ///
+    /// `out` is exactly as long as the original plaintext. This must return an
+    /// error if `ciphertext` or `associated_data` fail to authenticate.
+    fn open<E: Source>(
+        &self,
+        associated_data: &[u8],
+        ciphertext: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), E>;
+}
+
+/// Serializes the given value and seals the resulting bytes with `key`.
+///
+/// The sealed bytes start with a header which records the length of the
+/// plaintext archive, so that [`open_to_aligned`] can allocate its output
+/// buffer up front.
+pub fn to_bytes_sealed<K, E>(
+    value: &impl for<'a> Serialize<DefaultSerializer<'a, AlignedVec, E>>,
+    key: &K,
+) -> Result<Vec<u8>, E>
+where
+    K: SealingKey + ?Sized,
+    E: Source,
+{
+    let bytes = to_bytes::<E>(value)?;
+
+    let mut header = [0; HEADER_SIZE];
+    header[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+    header[4..8].copy_from_slice(&K::ID.to_le_bytes());
+    header[8..16].copy_from_slice(&(bytes.len() as u64).to_le_bytes());
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + bytes.len());
+    result.extend_from_slice(&header);
+    key.seal::<E>(&header, &bytes, &mut result)?;
+
+    Ok(result)
+}
+
+/// Verifies and decrypts bytes written by [`to_bytes_sealed`] into an
+/// [`AlignedVec`].
+///
+/// The archive is decrypted directly into a buffer which is properly aligned to
+/// be accessed. If authentication fails, the decrypted bytes are discarded and
+/// an error is returned.
+pub fn open_to_aligned<K, E>(bytes: &[u8], key: &K) -> Result<AlignedVec, E>
+where
+    K: SealingKey + ?Sized,
+    E: Source,
+{
+    if bytes.len() < HEADER_SIZE || read_u32(bytes, 0) != MAGIC {
+        fail!(InvalidHeaderError);
+    }
+    let id = read_u32(bytes, 4);
+    if id != K::ID {
+        fail!(AlgorithmMismatchError {
+            expected: K::ID,
+            found: id,
+        });
+    }
+    let len = match usize::try_from(read_u64(bytes, 8)) {
+        Ok(len) if len <= AlignedVec::<16>::MAX_CAPACITY => len,
+        _ => fail!(InvalidHeaderError),
+    };
+
+    let (header, ciphertext) = bytes.split_at(HEADER_SIZE);
+    let mut result = AlignedVec::with_capacity(len);
+    result.resize(len, 0);
+    key.open::<E>(header, ciphertext, &mut result)?;
+
+    Ok(result)
+}
+
+/// Verifies and decrypts bytes written by [`to_bytes_sealed`], then checks
+/// that they contain a valid archived `T`.
+#[cfg(feature = "bytecheck")]
+pub fn open_archive<T, K, E>(
+    bytes: &[u8],
+    key: &K,
+) -> Result<OwnedArchive<T>, E>
+where
+    T: crate::Archive,
+    T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+    K: SealingKey + ?Sized,
+    E: Source,
+{
+    OwnedArchive::new(open_to_aligned::<K, E>(bytes, key)?)
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], pos: usize) -> u64 {
+    u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap())
+}
+
+#[derive(Debug)]
+struct InvalidHeaderError;
+
+impl fmt::Display for InvalidHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sealed archive header is missing or invalid")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidHeaderError {}
+
+#[derive(Debug)]
+struct AlgorithmMismatchError {
+    expected: u32,
+    found: u32,
+}
+
+impl fmt::Display for AlgorithmMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive was sealed with algorithm {:#010x} but expected {:#010x}",
+            self.found, self.expected,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AlgorithmMismatchError {}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use core::fmt;
+
+    use rancor::{fail, Error, Source};
+
+    use super::{open_archive, open_to_aligned, to_bytes_sealed, SealingKey};
+
+    #[derive(Debug)]
+    struct TagMismatch;
+
+    impl fmt::Display for TagMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "tag mismatch")
+        }
+    }
+
+    impl std::error::Error for TagMismatch {}
+
+    /// XORs bytes with the key and appends a simple tag over the associated
+    /// data and plaintext.
+    struct TestKey(u8);
+
+    impl TestKey {
+        fn tag(&self, associated_data: &[u8], plaintext: &[u8]) -> u8 {
+            associated_data
+                .iter()
+                .chain(plaintext)
+                .fold(self.0, |tag, b| tag.rotate_left(3) ^ b)
+        }
+    }
+
+    impl SealingKey for TestKey {
+        const ID: u32 = u32::from_le_bytes(*b"test");
+
+        fn seal<E: Source>(
+            &self,
+            associated_data: &[u8],
+            plaintext: &[u8],
+            out: &mut Vec<u8>,
+        ) -> Result<(), E> {
+            out.extend(plaintext.iter().map(|b| b ^ self.0));
+            out.push(self.tag(associated_data, plaintext));
+            Ok(())
+        }
+
+        fn open<E: Source>(
+            &self,
+            associated_data: &[u8],
+            ciphertext: &[u8],
+            out: &mut [u8],
+        ) -> Result<(), E> {
+            if ciphertext.len() != out.len() + 1 {
+                fail!(TagMismatch);
+            }
+            for (o, b) in out.iter_mut().zip(ciphertext) {
+                *o = b ^ self.0;
+            }
+            if ciphertext[out.len()] != self.tag(associated_data, out) {
+                fail!(TagMismatch);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let key = TestKey(0x5a);
+        let sealed =
+            to_bytes_sealed::<_, Error>(&vec![1u32, 2, 3], &key).unwrap();
+        let bytes = open_to_aligned::<_, Error>(&sealed, &key).unwrap();
+        let archived =
+            crate::access::<crate::Archived<Vec<u32>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.as_slice(), &[1, 2, 3]);
+
+        let archive =
+            open_archive::<Vec<u32>, _, Error>(&sealed, &key).unwrap();
+        assert_eq!(archive.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn tampered() {
+        let key = TestKey(0x5a);
+        let sealed =
+            to_bytes_sealed::<_, Error>(&vec![1u32, 2, 3], &key).unwrap();
+
+        // Tampering with the header is detected by the key.
+        let mut tampered = sealed.clone();
+        tampered[8] ^= 1;
+        assert!(open_to_aligned::<_, Error>(&tampered, &key).is_err());
+
+        let mut tampered = sealed.clone();
+        tampered[20] ^= 1;
+        assert!(open_to_aligned::<_, Error>(&tampered, &key).is_err());
+
+        assert!(open_to_aligned::<_, Error>(&sealed[..10], &key).is_err());
+        assert!(open_to_aligned::<_, Error>(&sealed, &TestKey(0)).is_err());
+    }
+}
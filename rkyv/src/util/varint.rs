@@ -0,0 +1,75 @@
+//! LEB128 variable-length integers.
+
+use rancor::Fallible;
+
+use crate::ser::Writer;
+
+/// The maximum number of bytes in an encoded `usize`.
+pub(crate) const MAX_VARINT_LEN: usize = (usize::BITS as usize).div_ceil(7);
+
+/// Writes `value` as a varint.
+pub(crate) fn write_varint<S>(
+    serializer: &mut S,
+    mut value: usize,
+) -> Result<(), S::Error>
+where
+    S: Fallible + Writer + ?Sized,
+{
+    let mut buf = [0u8; MAX_VARINT_LEN];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    serializer.write(&buf[..len])
+}
+
+/// Reads a varint from `bytes` at `pos`, and advances `pos` past it.
+///
+/// Returns `None` if the varint runs past the end of `bytes` or overflows a
+/// `usize`.
+pub(crate) fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut value = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        let bits = (byte & 0x7f) as usize;
+        if shift >= usize::BITS || (bits << shift) >> shift != bits {
+            return None;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Reads a varint from `ptr`, returning its value and the number of bytes it
+/// took up.
+///
+/// # Safety
+///
+/// `ptr` must point to a valid varint which was written by [`write_varint`].
+pub(crate) unsafe fn read_varint_unchecked(ptr: *const u8) -> (usize, usize) {
+    let mut value = 0usize;
+    let mut len = 0;
+    loop {
+        // SAFETY: The caller has guaranteed that `ptr` points to a valid
+        // varint, so every byte up to and including the last one is readable.
+        let byte = unsafe { *ptr.add(len) };
+        value |= ((byte & 0x7f) as usize) << (7 * len);
+        len += 1;
+        if byte & 0x80 == 0 {
+            return (value, len);
+        }
+    }
+}
@@ -3,7 +3,7 @@
 pub mod util;
 pub mod validators;
 
-use core::{alloc::Layout, any::TypeId, ops::Range};
+use core::{alloc::Layout, any::TypeId, fmt, ops::Range};
 
 use bytecheck::rancor::{Fallible, Source, Strategy};
 use rancor::ResultExt as _;
@@ -52,6 +52,15 @@ pub unsafe trait ArchiveContext<E = <Self as Fallible>::Error> {
         &mut self,
         range: Range<usize>,
     ) -> Result<(), E>;
+
+    /// Returns the position of the given pointer from the start of the
+    /// archive, if the context knows where the archive starts.
+    ///
+    /// This is used to add byte positions to validation errors.
+    fn archive_pos(&self, ptr: *const u8) -> Option<usize> {
+        let _ = ptr;
+        None
+    }
 }
 
 unsafe impl<T, E> ArchiveContext<E> for Strategy<T, E>
@@ -84,6 +93,10 @@ where
         // has the same safety requirements.
         unsafe { T::pop_subtree_range(self, range) }
     }
+
+    fn archive_pos(&self, ptr: *const u8) -> Option<usize> {
+        T::archive_pos(self, ptr)
+    }
 }
 
 /// Helper methods for `ArchiveContext`s.
@@ -123,7 +136,11 @@ impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
         let range =
             unsafe { self.push_subtree_range(ptr, ptr.add(layout.size()))? };
 
-        let result = f(self)?;
+        let pos = self.archive_pos(ptr);
+        let result = f(self).map_err(|e| match pos {
+            Some(pos) => e.trace(SubtreePosition { pos }),
+            None => e,
+        })?;
 
         // SAFETY: `range` was returned from `push_subtree_range`.
         unsafe {
@@ -146,6 +163,17 @@ impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
     }
 }
 
+#[derive(Debug)]
+struct SubtreePosition {
+    pos: usize,
+}
+
+impl fmt::Display for SubtreePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "while checking subtree at byte position {}", self.pos)
+    }
+}
+
 /// A context that can validate shared archive memory.
 ///
 /// Shared pointers require this kind of context to validate.
@@ -1,23 +1,47 @@
 //! Utility methods for accessing and deserializing safely.
 
-use core::{mem::size_of, pin::Pin};
+#[cfg(not(feature = "alloc"))]
+use core::mem::MaybeUninit;
+use core::pin::Pin;
 
 use bytecheck::CheckBytes;
 use ptr_meta::Pointee;
 use rancor::{Source, Strategy};
 
+#[cfg(not(feature = "alloc"))]
+use crate::validation::validators::DEFAULT_SHARED_CAPACITY;
+#[cfg(feature = "alloc")]
 use crate::{
-    de::pooling::Pool,
-    deserialize,
-    util::{access_pos_unchecked, access_pos_unchecked_mut},
+    de::pooling::Pool, deserialize, deserialize_async, Archive, Deserialize,
+    DeserializeAsync,
+};
+use crate::{
+    util::{self, access_pos_unchecked, access_pos_unchecked_mut},
     validation::{
         validators::DefaultValidator, ArchiveContext, ArchiveContextExt,
     },
-    Archive, Deserialize, Portable,
+    Portable,
 };
 
 fn root_position<T: Portable>(bytes: &[u8]) -> usize {
-    bytes.len().saturating_sub(size_of::<T>())
+    util::root_position::<T>(bytes.len()).unwrap_or(0)
+}
+
+/// Checks a byte slice for a valid instance of the given archived type at the
+/// given position with the default validator.
+fn check_pos<T, E>(bytes: &[u8], pos: usize) -> Result<(), E>
+where
+    T: Portable + for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
+    E: Source,
+{
+    #[cfg(feature = "alloc")]
+    let mut validator = DefaultValidator::new(bytes);
+    #[cfg(not(feature = "alloc"))]
+    let mut shared = [MaybeUninit::uninit(); DEFAULT_SHARED_CAPACITY];
+    #[cfg(not(feature = "alloc"))]
+    let mut validator = DefaultValidator::new(bytes, &mut shared);
+
+    check_pos_with_context::<T, DefaultValidator, E>(bytes, pos, &mut validator)
 }
 
 /// Checks a byte slice for a valid instance of the given archived type at the
@@ -94,12 +118,8 @@ where
     T: Portable + for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
     E: Source,
 {
-    let mut validator = DefaultValidator::new(bytes);
-    access_pos_with_context::<T, DefaultValidator, E>(
-        bytes,
-        pos,
-        &mut validator,
-    )
+    check_pos::<T, E>(bytes, pos)?;
+    unsafe { Ok(access_pos_unchecked::<T>(bytes, pos)) }
 }
 
 /// Accesses an archived value from the given byte slice by calculating the root
@@ -139,8 +159,7 @@ where
     T: Portable + for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
     E: Source,
 {
-    let mut validator = DefaultValidator::new(bytes);
-    access_with_context::<T, DefaultValidator, E>(bytes, &mut validator)
+    access_pos::<T, E>(bytes, root_position::<T>(bytes))
 }
 
 // TODO: `Pin` is not technically correct for the return type. `Pin` requires
@@ -203,8 +222,7 @@ where
     T: Portable + for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
     E: Source,
 {
-    let mut context = DefaultValidator::new(bytes);
-    check_pos_with_context::<T, _, E>(bytes, pos, &mut context)?;
+    check_pos::<T, E>(bytes, pos)?;
     unsafe { Ok(access_pos_unchecked_mut::<T>(bytes, pos)) }
 }
 
@@ -219,10 +237,8 @@ where
     T: Portable + for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>,
     E: Source,
 {
-    let mut context = DefaultValidator::new(bytes);
     let pos = root_position::<T>(bytes);
-    check_pos_with_context::<T, _, E>(bytes, pos, &mut context)?;
-    unsafe { Ok(access_pos_unchecked_mut::<T>(bytes, pos)) }
+    access_pos_mut::<T, E>(bytes, pos)
 }
 
 /// Checks and deserializes a value from the given bytes.
@@ -250,6 +266,7 @@ where
 ///
 /// assert_eq!(deserialized, value);
 /// ```
+#[cfg(feature = "alloc")]
 pub fn from_bytes<T, E>(bytes: &[u8]) -> Result<T, E>
 where
     T: Archive,
@@ -260,3 +277,30 @@ where
     let mut deserializer = Pool::default();
     deserialize(access::<T::Archived, E>(bytes)?, &mut deserializer)
 }
+
+/// Checks and deserializes a value from the given bytes asynchronously.
+///
+/// This is the asynchronous counterpart to [`from_bytes`]. The bytes are
+/// checked before they are deserialized.
+///
+/// # Examples
+/// ```
+/// use rkyv::rancor::Error;
+///
+/// async fn load(bytes: &[u8]) -> Vec<i32> {
+///     rkyv::from_bytes_async::<Vec<i32>, Error>(bytes)
+///         .await
+///         .expect("failed to deserialize vec")
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+pub async fn from_bytes_async<T, E>(bytes: &[u8]) -> Result<T, E>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<Strategy<DefaultValidator<'a>, E>>
+        + DeserializeAsync<T, Strategy<Pool, E>>,
+    E: Source,
+{
+    let mut deserializer = Pool::default();
+    deserialize_async(access::<T::Archived, E>(bytes)?, &mut deserializer).await
+}
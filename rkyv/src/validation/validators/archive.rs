@@ -11,6 +11,7 @@ use crate::{fmt::Pointer, validation::ArchiveContext};
 #[derive(Debug)]
 struct UnalignedPointer {
     address: usize,
+    pos: usize,
     align: usize,
 }
 
@@ -18,8 +19,10 @@ impl fmt::Display for UnalignedPointer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "unaligned pointer: ptr {} unaligned for alignment {}",
+            "unaligned pointer: ptr {} (byte position {}) unaligned for \
+             alignment {}",
             Pointer(self.address),
+            self.pos,
             self.align,
         )
     }
@@ -33,17 +36,22 @@ struct InvalidSubtreePointer {
     address: usize,
     size: usize,
     subtree_range: Range<usize>,
+    base: usize,
 }
 
 impl fmt::Display for InvalidSubtreePointer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "subtree pointer overran range: ptr {} size {} in range {}..{}",
+            "subtree pointer overran range: ptr {} (byte position {}) size {} \
+             in range {}..{} (byte positions {}..{})",
             Pointer(self.address),
+            self.address.wrapping_sub(self.base) as isize,
             self.size,
             Pointer(self.subtree_range.start),
             Pointer(self.subtree_range.end),
+            self.subtree_range.start - self.base,
+            self.subtree_range.end - self.base,
         )
     }
 }
@@ -93,6 +101,7 @@ impl std::error::Error for RangePoppedOutOfOrder {}
 /// A validator that can verify archives with nonlocal memory.
 #[derive(Debug)]
 pub struct ArchiveValidator<'a> {
+    base: usize,
     subtree_range: Range<usize>,
     max_subtree_depth: Option<NonZeroUsize>,
     _phantom: PhantomData<&'a [u8]>,
@@ -124,6 +133,7 @@ impl<'a> ArchiveValidator<'a> {
     ) -> Self {
         let Range { start, end } = bytes.as_ptr_range();
         Self {
+            base: start as usize,
             subtree_range: Range {
                 start: start as usize,
                 end: end as usize,
@@ -147,10 +157,12 @@ unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
                 address: start,
                 size: layout.size(),
                 subtree_range: self.subtree_range.clone(),
+                base: self.base,
             });
         } else if start & (layout.align() - 1) != 0 {
             fail!(UnalignedPointer {
-                address: ptr as usize,
+                address: start,
+                pos: start - self.base,
                 align: layout.align(),
             });
         } else {
@@ -191,4 +203,8 @@ unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
         }
         Ok(())
     }
+
+    fn archive_pos(&self, ptr: *const u8) -> Option<usize> {
+        (ptr as usize).checked_sub(self.base)
+    }
 }
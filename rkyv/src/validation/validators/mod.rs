@@ -3,20 +3,125 @@
 mod archive;
 mod shared;
 
-use core::{any::TypeId, ops::Range};
+use core::{any::TypeId, mem::MaybeUninit, num::NonZeroUsize, ops::Range};
 
 pub use archive::*;
 pub use shared::*;
 
 use crate::validation::{ArchiveContext, SharedContext};
 
+/// A validator suitable for environments where allocations cannot be made.
+///
+/// Shared pointers are tracked in caller-provided storage. See
+/// [`BufferSharedValidator`] for more details.
+#[derive(Debug)]
+pub struct CoreValidator<'a, 'b> {
+    archive: ArchiveValidator<'a>,
+    shared: BufferSharedValidator<'b>,
+}
+
+impl<'a, 'b> CoreValidator<'a, 'b> {
+    /// Creates a new validator from a byte range, using the given buffer to
+    /// track shared pointers.
+    #[inline]
+    pub fn new(
+        bytes: &'a [u8],
+        shared: &'b mut [MaybeUninit<(usize, TypeId)>],
+    ) -> Self {
+        Self {
+            archive: ArchiveValidator::new(bytes),
+            shared: BufferSharedValidator::new(shared),
+        }
+    }
+
+    /// Creates a new validator from a byte range with a maximum validation
+    /// depth, using the given buffer to track shared pointers.
+    #[inline]
+    pub fn with_max_depth(
+        bytes: &'a [u8],
+        max_subtree_depth: Option<NonZeroUsize>,
+        shared: &'b mut [MaybeUninit<(usize, TypeId)>],
+    ) -> Self {
+        Self {
+            archive: ArchiveValidator::with_max_depth(bytes, max_subtree_depth),
+            shared: BufferSharedValidator::new(shared),
+        }
+    }
+}
+
+unsafe impl<'a, E> ArchiveContext<E> for CoreValidator<'a, '_>
+where
+    ArchiveValidator<'a>: ArchiveContext<E>,
+{
+    fn check_subtree_ptr(
+        &mut self,
+        ptr: *const u8,
+        layout: &core::alloc::Layout,
+    ) -> Result<(), E> {
+        self.archive.check_subtree_ptr(ptr, layout)
+    }
+
+    unsafe fn push_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, E> {
+        // SAFETY: This just forwards the call to the underlying
+        // `ArchiveValidator`, which has the same safety requirements.
+        unsafe { self.archive.push_subtree_range(root, end) }
+    }
+
+    unsafe fn pop_subtree_range(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), E> {
+        // SAFETY: This just forwards the call to the underlying
+        // `ArchiveValidator`, which has the same safety requirements.
+        unsafe { self.archive.pop_subtree_range(range) }
+    }
+
+    fn archive_pos(&self, ptr: *const u8) -> Option<usize> {
+        <ArchiveValidator<'a> as ArchiveContext<E>>::archive_pos(
+            &self.archive,
+            ptr,
+        )
+    }
+}
+
+impl<'b, E> SharedContext<E> for CoreValidator<'_, 'b>
+where
+    BufferSharedValidator<'b>: SharedContext<E>,
+{
+    fn register_shared_ptr(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<bool, E> {
+        self.shared.register_shared_ptr(address, type_id)
+    }
+}
+
+/// The number of shared pointers that [`DefaultValidator`] can track when the
+/// `alloc` feature is disabled.
+#[cfg(not(feature = "alloc"))]
+pub const DEFAULT_SHARED_CAPACITY: usize = 32;
+
 /// The default validator.
+///
+/// Without the `alloc` feature, this is a [`CoreValidator`] which tracks up to
+/// [`DEFAULT_SHARED_CAPACITY`] shared pointers in a buffer on the stack.
+#[cfg(not(feature = "alloc"))]
+pub type DefaultValidator<'a> = CoreValidator<'a, 'a>;
+
+/// The default validator.
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 pub struct DefaultValidator<'a> {
     archive: ArchiveValidator<'a>,
     shared: SharedValidator,
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> DefaultValidator<'a> {
     /// Creates a new validator from a byte range.
     #[inline]
@@ -37,6 +142,7 @@ impl<'a> DefaultValidator<'a> {
     }
 }
 
+#[cfg(feature = "alloc")]
 unsafe impl<'a, E> ArchiveContext<E> for DefaultValidator<'a>
 where
     ArchiveValidator<'a>: ArchiveContext<E>,
@@ -67,8 +173,16 @@ where
         // `ArchiveValidator`, which has the same safety requirements.
         unsafe { self.archive.pop_subtree_range(range) }
     }
+
+    fn archive_pos(&self, ptr: *const u8) -> Option<usize> {
+        <ArchiveValidator<'a> as ArchiveContext<E>>::archive_pos(
+            &self.archive,
+            ptr,
+        )
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl<E> SharedContext<E> for DefaultValidator<'_>
 where
     SharedValidator: SharedContext<E>,
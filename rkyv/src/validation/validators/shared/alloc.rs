@@ -1,7 +1,4 @@
-//! Validators add validation capabilities by wrapping and extending basic
-//! validators.
-
-use core::{any::TypeId, fmt};
+use core::any::TypeId;
 #[cfg(feature = "std")]
 use std::collections::HashMap;
 
@@ -9,41 +6,7 @@ use std::collections::HashMap;
 use hashbrown::HashMap;
 use rancor::{fail, Source};
 
-use crate::validation::SharedContext;
-
-/// Errors that can occur when checking shared memory.
-#[derive(Debug)]
-pub enum SharedError {
-    /// Multiple pointers exist to the same location with different types
-    TypeMismatch {
-        /// A previous type that the location was checked as
-        previous: TypeId,
-        /// The current type that the location is checked as
-        current: TypeId,
-    },
-}
-
-impl fmt::Display for SharedError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            SharedError::TypeMismatch { previous, current } => write!(
-                f,
-                "the same memory region has been claimed as two different \
-                 types ({:?} and {:?})",
-                previous, current
-            ),
-        }
-    }
-}
-
-#[cfg(feature = "std")]
-impl std::error::Error for SharedError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            SharedError::TypeMismatch { .. } => None,
-        }
-    }
-}
+use crate::validation::{validators::SharedError, SharedContext};
 
 /// A validator that can verify shared memory.
 #[derive(Debug, Default)]
@@ -0,0 +1,136 @@
+use core::{any::TypeId, fmt, mem::MaybeUninit};
+
+use rancor::{fail, Source};
+
+use crate::validation::{validators::SharedError, SharedContext};
+
+#[derive(Debug)]
+struct SharedBufferFull {
+    capacity: usize,
+}
+
+impl fmt::Display for SharedBufferFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "not enough space to register another shared pointer (capacity is \
+             {})",
+            self.capacity,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SharedBufferFull {}
+
+/// A validator that can verify shared memory using a fixed-size buffer.
+///
+/// Registered shared pointers are stored in caller-provided memory, so this
+/// validator can be used in environments where allocations cannot be made.
+/// Lookups are linear in the number of registered shared pointers.
+///
+/// # Examples
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     rancor::Error,
+///     to_bytes_noalloc,
+///     util::Align,
+///     validation::{util::access_with_context, validators::CoreValidator},
+///     Archived,
+/// };
+///
+/// let value = (1u32, [2u8, 3, 4, 5], Some(-6i16));
+///
+/// let mut output = Align([MaybeUninit::<u8>::uninit(); 64]);
+/// let bytes = to_bytes_noalloc::<Error>(&value, &mut *output).unwrap();
+///
+/// let mut shared = [MaybeUninit::uninit(); 16];
+/// let mut validator = CoreValidator::new(bytes, &mut shared);
+/// let archived = access_with_context::<
+///     Archived<(u32, [u8; 4], Option<i16>)>,
+///     _,
+///     Error,
+/// >(bytes, &mut validator)
+/// .unwrap();
+/// assert_eq!(archived.0, 1);
+/// assert_eq!(archived.1, [2, 3, 4, 5]);
+/// assert_eq!(archived.2, Some(-6));
+/// ```
+#[derive(Debug)]
+pub struct BufferSharedValidator<'a> {
+    entries: &'a mut [MaybeUninit<(usize, TypeId)>],
+    len: usize,
+}
+
+impl<'a> BufferSharedValidator<'a> {
+    /// Creates a new shared memory validator which stores registered shared
+    /// pointers in the given buffer.
+    #[inline]
+    pub fn new(entries: &'a mut [MaybeUninit<(usize, TypeId)>]) -> Self {
+        Self { entries, len: 0 }
+    }
+
+    /// Returns the maximum number of shared pointers that can be registered.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the number of shared pointers that have been registered.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no shared pointers have been registered.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn registered(&self) -> &[(usize, TypeId)] {
+        let entries = &self.entries[..self.len];
+        // SAFETY: The first `self.len` entries are always initialized, and
+        // `MaybeUninit<T>` has the same layout as `T`.
+        unsafe {
+            core::slice::from_raw_parts(
+                entries.as_ptr().cast::<(usize, TypeId)>(),
+                entries.len(),
+            )
+        }
+    }
+}
+
+impl<E: Source> SharedContext<E> for BufferSharedValidator<'_> {
+    fn register_shared_ptr(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<bool, E> {
+        let previous = self
+            .registered()
+            .iter()
+            .find(|(registered, _)| *registered == address);
+
+        if let Some((_, previous_type_id)) = previous {
+            if previous_type_id != &type_id {
+                fail!(SharedError::TypeMismatch {
+                    previous: *previous_type_id,
+                    current: type_id,
+                })
+            } else {
+                Ok(false)
+            }
+        } else if self.len == self.entries.len() {
+            fail!(SharedBufferFull {
+                capacity: self.entries.len(),
+            })
+        } else {
+            self.entries[self.len] = MaybeUninit::new((address, type_id));
+            self.len += 1;
+            Ok(true)
+        }
+    }
+}
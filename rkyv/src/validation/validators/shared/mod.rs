@@ -0,0 +1,46 @@
+//! Validators add validation capabilities by wrapping and extending basic
+//! validators.
+
+#[cfg(feature = "alloc")]
+mod alloc;
+mod core;
+
+use ::core::{any::TypeId, fmt};
+
+#[cfg(feature = "alloc")]
+pub use self::alloc::*;
+pub use self::core::*;
+
+/// Errors that can occur when checking shared memory.
+#[derive(Debug)]
+pub enum SharedError {
+    /// Multiple pointers exist to the same location with different types
+    TypeMismatch {
+        /// A previous type that the location was checked as
+        previous: TypeId,
+        /// The current type that the location is checked as
+        current: TypeId,
+    },
+}
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SharedError::TypeMismatch { previous, current } => write!(
+                f,
+                "the same memory region has been claimed as two different \
+                 types ({:?} and {:?})",
+                previous, current
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SharedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SharedError::TypeMismatch { .. } => None,
+        }
+    }
+}
@@ -1,20 +1,26 @@
 //! An archived version of `Vec`.
 
+pub mod small;
+
 use core::{
     borrow::Borrow,
     cmp, fmt, hash,
     ops::{Deref, Index, IndexMut},
     pin::Pin,
+    ptr,
     slice::SliceIndex,
 };
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 
 use crate::{
-    primitive::ArchivedUsize,
+    endian::SwapEndian,
+    primitive::ArchivedIsize,
+    rel_ptr::{repr_len, DefaultRepr, Offset as _, PointerRepr, RelPtr},
+    relocate::{OffsetMode, Relocate},
     ser::{Allocator, Writer, WriterExt as _},
-    Archive, Place, Portable, RelPtr, Serialize, SerializeUnsized,
+    Archive, Place, Portable, Serialize, SerializeUnsized,
 };
 
 // pub use self::raw::*;
@@ -23,7 +29,8 @@ use crate::{
 ///
 /// This uses a [`RelPtr`] to a `[T]` under the hood. Unlike
 /// [`ArchivedString`](crate::string::ArchivedString), it does not have an
-/// inline representation.
+/// inline representation. The representation of the relative pointer is
+/// and length are selected by `P`, see [`PointerRepr`] for more details.
 #[derive(Portable)]
 #[archive(crate)]
 #[repr(C)]
@@ -32,12 +39,12 @@ use crate::{
     derive(bytecheck::CheckBytes),
     check_bytes(verify)
 )]
-pub struct ArchivedVec<T> {
-    ptr: RelPtr<T>,
-    len: ArchivedUsize,
+pub struct ArchivedVec<T, P: PointerRepr = DefaultRepr> {
+    ptr: RelPtr<T, P::Offset>,
+    len: P::Len,
 }
 
-impl<T> ArchivedVec<T> {
+impl<T, P: PointerRepr> ArchivedVec<T, P> {
     /// Returns a pointer to the first element of the archived vec.
     pub fn as_ptr(&self) -> *const T {
         unsafe { self.ptr.as_ptr() }
@@ -45,7 +52,7 @@ impl<T> ArchivedVec<T> {
 
     /// Returns the number of elements in the archived vec.
     pub fn len(&self) -> usize {
-        self.len.to_native() as usize
+        self.len.to_isize() as usize
     }
 
     /// Returns whether the archived vec is empty.
@@ -95,6 +102,10 @@ impl<T> ArchivedVec<T> {
     }
 
     /// Resolves an archived `Vec` from a given length.
+    ///
+    /// # Panics
+    ///
+    /// - If `len` exceeds the range of the length type of `P`
     pub fn resolve_from_len(
         len: usize,
         resolver: VecResolver,
@@ -102,10 +113,26 @@ impl<T> ArchivedVec<T> {
     ) {
         munge!(let ArchivedVec { ptr, len: out_len } = out);
         RelPtr::emplace(resolver.pos, ptr);
-        usize::resolve(&len, (), out_len);
+        out_len.write(repr_len::<P>(len));
+    }
+
+    /// Returns whether the archived vec is a niche which no valid vec uses.
+    pub(crate) fn is_niche(&self) -> bool {
+        self.ptr.is_invalid()
     }
 
+    /// Emplaces a niche which no valid vec uses.
+    pub(crate) fn emplace_niche(out: Place<Self>) {
+        munge!(let ArchivedVec { ptr, len } = out);
+        RelPtr::emplace_invalid(ptr);
+        len.write(repr_len::<P>(0));
+    }
+}
+
+impl<T> ArchivedVec<T> {
     /// Serializes an archived `Vec` from a given slice.
+    ///
+    /// The resolver can be used with any pointer representation.
     pub fn serialize_from_slice<
         U: Serialize<S, Archived = T>,
         S: Fallible + Allocator + Writer + ?Sized,
@@ -136,6 +163,8 @@ impl<T> ArchivedVec<T> {
     {
         use crate::util::SerVec;
 
+        serializer.check_len(iter.len())?;
+
         SerVec::with_capacity(
             serializer,
             iter.len(),
@@ -151,6 +180,7 @@ impl<T> ArchivedVec<T> {
                         serializer.resolve_aligned(value.borrow(), resolver)?;
                     }
                 }
+                serializer.check_offset::<ArchivedIsize>(pos)?;
 
                 Ok(VecResolver { pos })
             },
@@ -176,37 +206,41 @@ impl<T> ArchivedVec<T> {
         unsafe {
             let pos = serializer.align_for::<T>()?;
 
+            let mut len = 0;
             for value in iter {
                 let pos_cached = serializer.pos();
                 let resolver = value.serialize(serializer)?;
                 assert!(serializer.pos() == pos_cached);
                 serializer.resolve_aligned(value.borrow(), resolver)?;
+                len += 1;
             }
+            serializer.check_len(len)?;
+            serializer.check_offset::<ArchivedIsize>(pos)?;
 
             Ok(VecResolver { pos })
         }
     }
 }
 
-impl<T> AsRef<[T]> for ArchivedVec<T> {
+impl<T, P: PointerRepr> AsRef<[T]> for ArchivedVec<T, P> {
     fn as_ref(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T> Borrow<[T]> for ArchivedVec<T> {
+impl<T, P: PointerRepr> Borrow<[T]> for ArchivedVec<T, P> {
     fn borrow(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for ArchivedVec<T> {
+impl<T: fmt::Debug, P: PointerRepr> fmt::Debug for ArchivedVec<T, P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.as_slice()).finish()
     }
 }
 
-impl<T> Deref for ArchivedVec<T> {
+impl<T, P: PointerRepr> Deref for ArchivedVec<T, P> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
@@ -214,15 +248,15 @@ impl<T> Deref for ArchivedVec<T> {
     }
 }
 
-impl<T: Eq> Eq for ArchivedVec<T> {}
+impl<T: Eq, P: PointerRepr> Eq for ArchivedVec<T, P> {}
 
-impl<T: hash::Hash> hash::Hash for ArchivedVec<T> {
+impl<T: hash::Hash, P: PointerRepr> hash::Hash for ArchivedVec<T, P> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.as_slice().hash(state)
     }
 }
 
-impl<T, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T> {
+impl<T, P: PointerRepr, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T, P> {
     type Output = <[T] as Index<I>>::Output;
 
     fn index(&self, index: I) -> &Self::Output {
@@ -230,60 +264,127 @@ impl<T, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T> {
     }
 }
 
-impl<T: Ord> Ord for ArchivedVec<T> {
+impl<T: Ord, P: PointerRepr> Ord for ArchivedVec<T, P> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_slice().cmp(other.as_slice())
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<ArchivedVec<U>> for ArchivedVec<T> {
-    fn eq(&self, other: &ArchivedVec<U>) -> bool {
+impl<T, U, P, Q> PartialEq<ArchivedVec<U, Q>> for ArchivedVec<T, P>
+where
+    T: PartialEq<U>,
+    P: PointerRepr,
+    Q: PointerRepr,
+{
+    fn eq(&self, other: &ArchivedVec<U, Q>) -> bool {
         self.as_slice().eq(other.as_slice())
     }
 }
 
-impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for ArchivedVec<T> {
+impl<T, U, P, const N: usize> PartialEq<[U; N]> for ArchivedVec<T, P>
+where
+    T: PartialEq<U>,
+    P: PointerRepr,
+{
     fn eq(&self, other: &[U; N]) -> bool {
         self.as_slice().eq(&other[..])
     }
 }
 
-impl<T: PartialEq<U>, U, const N: usize> PartialEq<ArchivedVec<T>> for [U; N] {
-    fn eq(&self, other: &ArchivedVec<T>) -> bool {
+impl<T, U, P, const N: usize> PartialEq<ArchivedVec<T, P>> for [U; N]
+where
+    T: PartialEq<U>,
+    P: PointerRepr,
+{
+    fn eq(&self, other: &ArchivedVec<T, P>) -> bool {
         other.eq(self)
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedVec<T> {
+impl<T: PartialEq<U>, U, P: PointerRepr> PartialEq<[U]> for ArchivedVec<T, P> {
     fn eq(&self, other: &[U]) -> bool {
         self.as_slice().eq(other)
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<ArchivedVec<U>> for [T] {
-    fn eq(&self, other: &ArchivedVec<U>) -> bool {
+impl<T: PartialEq<U>, U, P: PointerRepr> PartialEq<ArchivedVec<U, P>>
+    for [T]
+{
+    fn eq(&self, other: &ArchivedVec<U, P>) -> bool {
         self.eq(other.as_slice())
     }
 }
 
-impl<T: PartialOrd> PartialOrd<ArchivedVec<T>> for ArchivedVec<T> {
-    fn partial_cmp(&self, other: &ArchivedVec<T>) -> Option<cmp::Ordering> {
+impl<T: PartialOrd, P: PointerRepr> PartialOrd<ArchivedVec<T, P>>
+    for ArchivedVec<T, P>
+{
+    fn partial_cmp(&self, other: &ArchivedVec<T, P>) -> Option<cmp::Ordering> {
         self.as_slice().partial_cmp(other.as_slice())
     }
 }
 
-impl<T: PartialOrd> PartialOrd<[T]> for ArchivedVec<T> {
+impl<T: PartialOrd, P: PointerRepr> PartialOrd<[T]> for ArchivedVec<T, P> {
     fn partial_cmp(&self, other: &[T]) -> Option<cmp::Ordering> {
         self.as_slice().partial_cmp(other)
     }
 }
 
-impl<T: PartialOrd> PartialOrd<ArchivedVec<T>> for [T] {
-    fn partial_cmp(&self, other: &ArchivedVec<T>) -> Option<cmp::Ordering> {
+impl<T: PartialOrd, P: PointerRepr> PartialOrd<ArchivedVec<T, P>> for [T] {
+    fn partial_cmp(&self, other: &ArchivedVec<T, P>) -> Option<cmp::Ordering> {
         self.partial_cmp(other.as_slice())
     }
 }
 
+// SAFETY: The length is converted, followed by the pointer and each element of
+// the archived vec.
+unsafe impl<T, P> SwapEndian for ArchivedVec<T, P>
+where
+    T: SwapEndian,
+    P: PointerRepr,
+    P::Offset: SwapEndian,
+    P::Len: SwapEndian,
+{
+    unsafe fn swap_endian(this: *mut Self) {
+        // SAFETY: The caller has guaranteed that `this` is valid for reads and
+        // writes, and that its elements were written with the opposite
+        // endianness.
+        unsafe {
+            let len = ptr::addr_of_mut!((*this).len);
+            P::Len::swap_endian(len);
+            let len = (*len).to_isize() as usize;
+            RelPtr::swap_endian_slice(ptr::addr_of_mut!((*this).ptr), len);
+        }
+    }
+}
+
+// SAFETY: The pointer and each element of the archived vec are converted.
+unsafe impl<T, P> Relocate for ArchivedVec<T, P>
+where
+    T: Relocate,
+    P: PointerRepr,
+    P::Offset: Portable,
+    P::Len: Portable,
+{
+    unsafe fn relocate<E: Source>(
+        this: *mut Self,
+        base: *mut u8,
+        mode: OffsetMode,
+    ) -> Result<(), E> {
+        // SAFETY: The caller has guaranteed that `this` meets the requirements
+        // of `relocate`, and that its elements have offsets in the opposite
+        // mode.
+        unsafe {
+            let len = (*this).len();
+            RelPtr::relocate_slice(
+                ptr::addr_of_mut!((*this).ptr),
+                len,
+                base,
+                mode,
+            )
+        }
+    }
+}
+
 /// The resolver for [`ArchivedVec`].
 pub struct VecResolver {
     pos: usize,
@@ -305,20 +406,22 @@ mod verify {
     };
 
     use crate::{
+        rel_ptr::PointerRepr,
         validation::{ArchiveContext, ArchiveContextExt},
         vec::ArchivedVec,
     };
 
-    unsafe impl<T, C> Verify<C> for ArchivedVec<T>
+    unsafe impl<T, P, C> Verify<C> for ArchivedVec<T, P>
     where
         T: CheckBytes<C>,
+        P: PointerRepr,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr = core::ptr::slice_from_raw_parts(
                 self.ptr.as_ptr_wrapping(),
-                self.len.to_native() as usize,
+                self.len(),
             );
 
             context.in_subtree(ptr, |context| unsafe {
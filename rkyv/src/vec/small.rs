@@ -0,0 +1,307 @@
+//! An archived vector which stores short vectors inline.
+
+use core::{
+    borrow::Borrow,
+    cmp, fmt, hash,
+    mem::ManuallyDrop,
+    ops::{Deref, Index, IndexMut},
+    pin::Pin,
+    slice::{self, SliceIndex},
+};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::ArchivedUsize,
+    ser::{Allocator, Writer},
+    util::InlineVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, RelPtr, Serialize,
+};
+
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+union SmallVecStorage<T, const N: usize> {
+    inline: ManuallyDrop<[T; N]>,
+    out_of_line: ManuallyDrop<RelPtr<T>>,
+}
+
+/// An archived `Vec` which stores up to `N` elements inline.
+///
+/// Like [`ArchivedString`](crate::string::ArchivedString), vectors which are
+/// short enough are stored inside the `ArchivedSmallVec` itself. Longer vectors
+/// are stored out-of-line like an [`ArchivedVec`]. Accessing an inline vector
+/// doesn't have to follow a pointer, which can be faster for vectors which are
+/// usually short. In exchange, an `ArchivedSmallVec` always takes up enough
+/// space to store `N` elements.
+#[derive(Portable)]
+#[archive(crate)]
+#[repr(C)]
+pub struct ArchivedSmallVec<T, const N: usize> {
+    len: ArchivedUsize,
+    storage: SmallVecStorage<T, N>,
+}
+
+impl<T, const N: usize> ArchivedSmallVec<T, N> {
+    /// Returns whether the elements are stored inline.
+    pub fn is_inline(&self) -> bool {
+        self.len() <= N
+    }
+
+    /// Returns a pointer to the first element of the archived vec.
+    pub fn as_ptr(&self) -> *const T {
+        if self.is_inline() {
+            // SAFETY: Inline vecs store their elements in `inline`.
+            unsafe { self.storage.inline.as_ptr() }
+        } else {
+            // SAFETY: Out-of-line vecs store a pointer to their elements in
+            // `out_of_line`.
+            unsafe { self.storage.out_of_line.as_ptr() }
+        }
+    }
+
+    /// Returns the number of elements in the archived vec.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the archived vec is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the elements of the archived vec as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    /// Gets the elements of the archived vec as a pinned mutable slice.
+    pub fn pin_mut_slice(self: Pin<&mut Self>) -> Pin<&mut [T]> {
+        // SAFETY: The elements are not moved out of the archived vec.
+        let this = unsafe { self.get_unchecked_mut() };
+        let len = this.len();
+        let ptr = if this.is_inline() {
+            // SAFETY: Inline vecs store their elements in `inline`.
+            unsafe { (*this.storage.inline).as_mut_ptr() }
+        } else {
+            // SAFETY: Out-of-line vecs store a pointer to their elements in
+            // `out_of_line`, and it is not moved.
+            unsafe {
+                Pin::new_unchecked(&mut *this.storage.out_of_line).as_mut_ptr()
+            }
+        };
+        unsafe { Pin::new_unchecked(slice::from_raw_parts_mut(ptr, len)) }
+    }
+
+    /// Gets the element at the given index to this archived vec as a pinned
+    /// mutable reference.
+    pub fn index_pin<I>(
+        self: Pin<&mut Self>,
+        index: I,
+    ) -> Pin<&mut <[T] as Index<I>>::Output>
+    where
+        [T]: IndexMut<I>,
+    {
+        unsafe { self.pin_mut_slice().map_unchecked_mut(|s| &mut s[index]) }
+    }
+
+    /// Resolves an archived small vec from a given slice.
+    pub fn resolve_from_slice<U: Archive<Archived = T>>(
+        slice: &[U],
+        resolver: SmallVecResolver<U::Resolver, N>,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedSmallVec { len, storage } = out);
+        slice.len().resolve((), len);
+
+        match resolver {
+            SmallVecResolver::Inline(mut resolvers) => {
+                // SAFETY: `inline` is the first field of `SmallVecStorage`,
+                // which is `repr(C)`.
+                let inline = unsafe { storage.cast_unchecked::<[T; N]>() };
+                for (i, (value, resolver)) in
+                    slice.iter().zip(resolvers.drain()).enumerate()
+                {
+                    // SAFETY: `i` is less than the length of the slice, which
+                    // is at most `N`.
+                    value.resolve(resolver, unsafe { inline.index(i) });
+                }
+            }
+            SmallVecResolver::OutOfLine(resolver) => {
+                // SAFETY: `out_of_line` is the first field of
+                // `SmallVecStorage`, which is `repr(C)`.
+                let ptr = unsafe { storage.cast_unchecked::<RelPtr<T>>() };
+                RelPtr::emplace(resolver.pos, ptr);
+            }
+        }
+    }
+
+    /// Serializes an archived small vec from a given slice.
+    ///
+    /// If the slice has more than `N` elements, they are serialized
+    /// out-of-line.
+    pub fn serialize_from_slice<U, S>(
+        slice: &[U],
+        serializer: &mut S,
+    ) -> Result<SmallVecResolver<U::Resolver, N>, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        if slice.len() <= N {
+            let mut resolvers = InlineVec::new();
+            for value in slice.iter() {
+                resolvers.push(value.serialize(serializer)?);
+            }
+            Ok(SmallVecResolver::Inline(resolvers))
+        } else {
+            Ok(SmallVecResolver::OutOfLine(
+                ArchivedVec::<T>::serialize_from_slice(slice, serializer)?,
+            ))
+        }
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArchivedSmallVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for ArchivedSmallVec<T, N> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArchivedSmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Deref for ArchivedSmallVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArchivedSmallVec<T, N> {}
+
+impl<T: hash::Hash, const N: usize> hash::Hash for ArchivedSmallVec<T, N> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, const N: usize> Index<I>
+    for ArchivedSmallVec<T, N>
+{
+    type Output = <[T] as Index<I>>::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ArchivedSmallVec<T, N> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<ArchivedSmallVec<U, M>>
+    for ArchivedSmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &ArchivedSmallVec<U, M>) -> bool {
+        self.as_slice().eq(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]>
+    for ArchivedSmallVec<T, N>
+{
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice().eq(other)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<ArchivedSmallVec<U, N>>
+    for [T]
+{
+    fn eq(&self, other: &ArchivedSmallVec<U, N>) -> bool {
+        self.eq(other.as_slice())
+    }
+}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ArchivedSmallVec<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+/// The resolver for [`ArchivedSmallVec`].
+pub enum SmallVecResolver<R, const N: usize> {
+    /// The elements are stored inline, and these are their resolvers.
+    Inline(InlineVec<R, N>),
+    /// The elements are stored out-of-line.
+    OutOfLine(VecResolver),
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::ptr::{self, addr_of};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        CheckBytes,
+    };
+
+    use super::ArchivedSmallVec;
+    use crate::{
+        validation::{ArchiveContext, ArchiveContextExt},
+        RelPtr,
+    };
+
+    unsafe impl<T, C, const N: usize> CheckBytes<C> for ArchivedSmallVec<T, N>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // SAFETY: The caller has guaranteed that `value` is aligned and
+            // points to enough bytes for an `ArchivedSmallVec`, and every bit
+            // pattern is a valid length.
+            let len = unsafe { (*value).len.to_native() as usize };
+            let storage = unsafe { addr_of!((*value).storage) };
+
+            if len <= N {
+                let elements =
+                    ptr::slice_from_raw_parts(storage.cast::<T>(), len);
+                // SAFETY: The first `len` elements of the inline storage are
+                // located within `value`.
+                unsafe { <[T]>::check_bytes(elements, context) }
+            } else {
+                // SAFETY: Every bit pattern is a valid relative pointer.
+                let rel_ptr = unsafe { &*storage.cast::<RelPtr<T>>() };
+                let elements =
+                    ptr::slice_from_raw_parts(rel_ptr.as_ptr_wrapping(), len);
+                context.in_subtree(elements, |context| {
+                    // SAFETY: `in_subtree` has checked that `elements` is
+                    // aligned and located in the archive.
+                    unsafe { <[T]>::check_bytes(elements, context) }
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,477 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::walk::{to_json_value, JsonValue, Visitor, Walk};
+
+/// A path to a value inside of an archived value.
+///
+/// Paths are displayed with `.name` for fields, map keys, and enum variants,
+/// and with `[index]` for elements of sequences. The root value is displayed
+/// as `.`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    /// Returns the segments of the path, starting from the root value.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    fn with(&self, segment: PathSegment) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(segment);
+        Self { segments }
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return f.write_str(".");
+        }
+        for segment in self.segments.iter() {
+            match segment {
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A segment of a [`Path`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A field, map key, or enum variant.
+    Key(String),
+    /// An element of a sequence.
+    Index(usize),
+}
+
+/// A difference between two archived values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference {
+    /// The path to the value which differs.
+    pub path: Path,
+    /// How the value differs.
+    pub kind: DifferenceKind,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DifferenceKind::Changed { old, new } => {
+                write!(f, "{}: {} -> {}", self.path, old, new)
+            }
+            DifferenceKind::Added(value) => {
+                write!(f, "{}: added {}", self.path, value)
+            }
+            DifferenceKind::Removed(value) => {
+                write!(f, "{}: removed {}", self.path, value)
+            }
+        }
+    }
+}
+
+/// The way that a value differs between two archived values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DifferenceKind {
+    /// The value was changed.
+    Changed {
+        /// The old value.
+        old: JsonValue,
+        /// The new value.
+        new: JsonValue,
+    },
+    /// The value is only present in the new archived value.
+    Added(JsonValue),
+    /// The value is only present in the old archived value.
+    Removed(JsonValue),
+}
+
+/// Compares two walkable archived values and returns the differences between
+/// them.
+///
+/// Structs are compared by field and maps are compared by key. Sequences are
+/// compared element by element, and elements past the end of the shorter
+/// sequence are added or removed. Enums which have the same variant are
+/// compared by their fields, and enums which have different variants are
+/// changed as a whole. Values in differences are converted with
+/// [`to_json_value`].
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, walk::diff, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(schema)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let old = rkyv::to_bytes::<Error>(&Config {
+///     name: "server".to_string(),
+///     ports: vec![80, 443],
+/// })
+/// .unwrap();
+/// let new = rkyv::to_bytes::<Error>(&Config {
+///     name: "server".to_string(),
+///     ports: vec![8080, 443, 8443],
+/// })
+/// .unwrap();
+///
+/// let differences = unsafe {
+///     diff(
+///         rkyv::access_unchecked::<ArchivedConfig>(&old),
+///         rkyv::access_unchecked::<ArchivedConfig>(&new),
+///     )
+/// };
+/// let differences = differences
+///     .iter()
+///     .map(|d| d.to_string())
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     differences,
+///     [".ports[0]: 80 -> 8080", ".ports[2]: added 8443"]
+/// );
+/// ```
+pub fn diff<T: Walk + ?Sized>(old: &T, new: &T) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    diff_nodes(
+        &Path::default(),
+        &Node::record(old),
+        &Node::record(new),
+        &mut differences,
+    );
+    differences
+}
+
+fn diff_nodes(
+    path: &Path,
+    old: &Node,
+    new: &Node,
+    differences: &mut Vec<Difference>,
+) {
+    match (old, new) {
+        (Node::Some(old), Node::Some(new)) => {
+            diff_nodes(path, old, new, differences);
+        }
+        (Node::Seq(old), Node::Seq(new)) => {
+            let index = |i| path.with(PathSegment::Index(i));
+            for (i, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+                diff_nodes(&index(i), old, new, differences);
+            }
+            for (i, old) in old.iter().enumerate().skip(new.len()) {
+                differences.push(Difference::removed(index(i), old));
+            }
+            for (i, new) in new.iter().enumerate().skip(old.len()) {
+                differences.push(Difference::added(index(i), new));
+            }
+        }
+        (Node::Map(old), Node::Map(new)) => {
+            let key = |k: &Node| {
+                path.with(PathSegment::Key(match to_json_value(k) {
+                    JsonValue::String(key) => key,
+                    key => key.to_string(),
+                }))
+            };
+            for (k, old) in old.iter() {
+                match find_entry(new, k) {
+                    Some(new) => diff_nodes(&key(k), old, new, differences),
+                    None => differences.push(Difference::removed(key(k), old)),
+                }
+            }
+            for (k, new) in new.iter() {
+                if find_entry(old, k).is_none() {
+                    differences.push(Difference::added(key(k), new));
+                }
+            }
+        }
+        (Node::Struct(old_name, old), Node::Struct(new_name, new))
+            if old_name == new_name =>
+        {
+            diff_fields(path, old, new, differences);
+        }
+        (
+            Node::Variant(old_name, old_variant, old),
+            Node::Variant(new_name, new_variant, new),
+        ) if old_name == new_name && old_variant == new_variant => {
+            let path = path.with(PathSegment::Key(old_variant.clone()));
+            diff_fields(&path, old, new, differences);
+        }
+        (old, new) if old != new => differences.push(Difference {
+            path: path.clone(),
+            kind: DifferenceKind::Changed {
+                old: to_json_value(old),
+                new: to_json_value(new),
+            },
+        }),
+        _ => (),
+    }
+}
+
+fn diff_fields(
+    path: &Path,
+    old: &[(String, Node)],
+    new: &[(String, Node)],
+    differences: &mut Vec<Difference>,
+) {
+    for (name, old) in old.iter() {
+        let path = path.with(PathSegment::Key(name.clone()));
+        match find_entry(new, name.as_str()) {
+            Some(new) => diff_nodes(&path, old, new, differences),
+            None => differences.push(Difference::removed(path, old)),
+        }
+    }
+    for (name, new) in new.iter() {
+        if find_entry(old, name.as_str()).is_none() {
+            let path = path.with(PathSegment::Key(name.clone()));
+            differences.push(Difference::added(path, new));
+        }
+    }
+}
+
+fn find_entry<'a, K, Q>(entries: &'a [(K, Node)], key: &Q) -> Option<&'a Node>
+where
+    K: PartialEq<Q>,
+    Q: ?Sized,
+{
+    entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+impl Difference {
+    fn added(path: Path, value: &Node) -> Self {
+        Self {
+            path,
+            kind: DifferenceKind::Added(to_json_value(value)),
+        }
+    }
+
+    fn removed(path: Path, value: &Node) -> Self {
+        Self {
+            path,
+            kind: DifferenceKind::Removed(to_json_value(value)),
+        }
+    }
+}
+
+/// A recorded walk of an archived value.
+#[derive(PartialEq)]
+enum Node {
+    Unit,
+    Bool(bool),
+    Unsigned(u128),
+    Signed(i128),
+    Float(f64),
+    Char(char),
+    Str(String),
+    None,
+    Some(Box<Node>),
+    Seq(Vec<Node>),
+    Map(Vec<(Node, Node)>),
+    Struct(String, Vec<(String, Node)>),
+    Variant(String, String, Vec<(String, Node)>),
+}
+
+impl Node {
+    fn record<T: Walk + ?Sized>(value: &T) -> Self {
+        let mut recorder = Recorder {
+            stack: Vec::new(),
+            result: None,
+        };
+        value.walk(&mut recorder);
+        recorder.result.unwrap_or(Node::Unit)
+    }
+}
+
+impl Walk for Node {
+    fn walk(&self, visitor: &mut dyn Visitor) {
+        fn walk_fields(fields: &[(String, Node)], visitor: &mut dyn Visitor) {
+            for (name, value) in fields.iter() {
+                visitor.visit_field(name);
+                value.walk(visitor);
+            }
+        }
+
+        match self {
+            Node::Unit => visitor.visit_unit(),
+            Node::Bool(value) => visitor.visit_bool(*value),
+            Node::Unsigned(value) => visitor.visit_unsigned(*value),
+            Node::Signed(value) => visitor.visit_signed(*value),
+            Node::Float(value) => visitor.visit_float(*value),
+            Node::Char(value) => visitor.visit_char(*value),
+            Node::Str(value) => visitor.visit_str(value),
+            Node::None => visitor.visit_none(),
+            Node::Some(value) => {
+                visitor.begin_some();
+                value.walk(visitor);
+                visitor.end_some();
+            }
+            Node::Seq(elements) => {
+                visitor.begin_seq(elements.len());
+                for element in elements.iter() {
+                    element.walk(visitor);
+                }
+                visitor.end_seq();
+            }
+            Node::Map(entries) => {
+                visitor.begin_map(entries.len());
+                for (key, value) in entries.iter() {
+                    key.walk(visitor);
+                    value.walk(visitor);
+                }
+                visitor.end_map();
+            }
+            Node::Struct(name, fields) => {
+                visitor.begin_struct(name);
+                walk_fields(fields, visitor);
+                visitor.end_struct();
+            }
+            Node::Variant(enum_name, variant, fields) => {
+                visitor.begin_variant(enum_name, variant);
+                walk_fields(fields, visitor);
+                visitor.end_variant();
+            }
+        }
+    }
+}
+
+enum Frame {
+    Some,
+    Seq(Vec<Node>),
+    Map(Vec<(Node, Node)>, Option<Node>),
+    Fields(Node, Option<String>),
+}
+
+struct Recorder {
+    stack: Vec<Frame>,
+    result: Option<Node>,
+}
+
+impl Recorder {
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            None => self.result = Some(node),
+            Some(Frame::Some) => {
+                self.stack.pop();
+                self.push(Node::Some(Box::new(node)));
+            }
+            Some(Frame::Seq(elements)) => elements.push(node),
+            Some(Frame::Map(entries, key)) => match key.take() {
+                Some(key) => entries.push((key, node)),
+                None => *key = Some(node),
+            },
+            Some(Frame::Fields(
+                Node::Struct(_, fields) | Node::Variant(_, _, fields),
+                field,
+            )) => fields.push((field.take().unwrap_or_default(), node)),
+            Some(Frame::Fields(..)) => unreachable!(),
+        }
+    }
+
+    fn end_fields(&mut self) {
+        let Some(Frame::Fields(node, _)) = self.stack.pop() else {
+            panic!("unbalanced calls to begin and end a struct or variant");
+        };
+        self.push(node);
+    }
+}
+
+impl Visitor for Recorder {
+    fn visit_unit(&mut self) {
+        self.push(Node::Unit);
+    }
+
+    fn visit_bool(&mut self, value: bool) {
+        self.push(Node::Bool(value));
+    }
+
+    fn visit_unsigned(&mut self, value: u128) {
+        self.push(Node::Unsigned(value));
+    }
+
+    fn visit_signed(&mut self, value: i128) {
+        self.push(Node::Signed(value));
+    }
+
+    fn visit_float(&mut self, value: f64) {
+        self.push(Node::Float(value));
+    }
+
+    fn visit_char(&mut self, value: char) {
+        self.push(Node::Char(value));
+    }
+
+    fn visit_str(&mut self, value: &str) {
+        self.push(Node::Str(value.to_string()));
+    }
+
+    fn visit_none(&mut self) {
+        self.push(Node::None);
+    }
+
+    fn begin_some(&mut self) {
+        self.stack.push(Frame::Some);
+    }
+
+    fn end_some(&mut self) {}
+
+    fn begin_seq(&mut self, len: usize) {
+        self.stack.push(Frame::Seq(Vec::with_capacity(len)));
+    }
+
+    fn end_seq(&mut self) {
+        let Some(Frame::Seq(elements)) = self.stack.pop() else {
+            panic!("unbalanced calls to begin_seq and end_seq");
+        };
+        self.push(Node::Seq(elements));
+    }
+
+    fn begin_map(&mut self, len: usize) {
+        self.stack.push(Frame::Map(Vec::with_capacity(len), None));
+    }
+
+    fn end_map(&mut self) {
+        let Some(Frame::Map(entries, _)) = self.stack.pop() else {
+            panic!("unbalanced calls to begin_map and end_map");
+        };
+        self.push(Node::Map(entries));
+    }
+
+    fn begin_struct(&mut self, name: &str) {
+        let node = Node::Struct(name.to_string(), Vec::new());
+        self.stack.push(Frame::Fields(node, None));
+    }
+
+    fn end_struct(&mut self) {
+        self.end_fields();
+    }
+
+    fn begin_variant(&mut self, enum_name: &str, variant: &str) {
+        let node = Node::Variant(
+            enum_name.to_string(),
+            variant.to_string(),
+            Vec::new(),
+        );
+        self.stack.push(Frame::Fields(node, None));
+    }
+
+    fn end_variant(&mut self) {
+        self.end_fields();
+    }
+
+    fn visit_field(&mut self, name: &str) {
+        if let Some(Frame::Fields(_, field)) = self.stack.last_mut() {
+            *field = Some(name.to_string());
+        }
+    }
+}
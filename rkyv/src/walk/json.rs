@@ -0,0 +1,316 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{self, Write as _};
+
+use crate::walk::{Visitor, Walk};
+
+/// A JSON value built from a walkable archived value.
+///
+/// Formatting a `JsonValue` with `{}` prints compact JSON, and formatting it
+/// with `{:#}` pretty-prints it.
+///
+/// Archived values are converted to JSON as follows:
+///
+/// - Unit values and `None` are `null`, and `Some` values are the value they
+///   contain.
+/// - Sequences are arrays, and maps are objects. Map keys which aren't strings
+///   are converted to their JSON text.
+/// - Structs with named fields are objects. Tuple structs are arrays, except
+///   for those with a single field which are the value of that field.
+/// - Unit variants are the name of the variant, and other variants are an
+///   object with the name of the variant as their only key.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    /// A `null` value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// An unsigned integer.
+    Unsigned(u128),
+    /// A signed integer.
+    Signed(i128),
+    /// A floating-point number. Non-finite numbers are printed as `null`.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// An array of values.
+    Array(Vec<JsonValue>),
+    /// An object with its entries in the order they were visited.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the value of the entry with the given key if this is an
+    /// object.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => {
+                entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match self {
+            JsonValue::Null => f.write_str("null"),
+            JsonValue::Bool(value) => write!(f, "{}", value),
+            JsonValue::Unsigned(value) => write!(f, "{}", value),
+            JsonValue::Signed(value) => write!(f, "{}", value),
+            JsonValue::Float(value) if value.is_finite() => {
+                write!(f, "{:?}", value)
+            }
+            JsonValue::Float(_) => f.write_str("null"),
+            JsonValue::String(value) => write_str(f, value),
+            JsonValue::Array(elements) => {
+                write_compound(f, depth, '[', ']', elements, |f, e| {
+                    e.write(f, depth + 1)
+                })
+            }
+            JsonValue::Object(entries) => {
+                write_compound(f, depth, '{', '}', entries, |f, (k, v)| {
+                    write_str(f, k)?;
+                    f.write_str(if f.alternate() { ": " } else { ":" })?;
+                    v.write(f, depth + 1)
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn write_compound<T>(
+    f: &mut fmt::Formatter<'_>,
+    depth: usize,
+    open: char,
+    close: char,
+    items: &[T],
+    mut write_item: impl FnMut(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    fn newline(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        f.write_char('\n')?;
+        for _ in 0..depth {
+            f.write_str("  ")?;
+        }
+        Ok(())
+    }
+
+    f.write_char(open)?;
+    for (i, item) in items.iter().enumerate() {
+        if i != 0 {
+            f.write_char(',')?;
+        }
+        if f.alternate() {
+            newline(f, depth + 1)?;
+        }
+        write_item(f, item)?;
+    }
+    if f.alternate() && !items.is_empty() {
+        newline(f, depth)?;
+    }
+    f.write_char(close)
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => {
+                let mut buf = [0; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    write!(f, "\\u{:04x}", unit)?;
+                }
+            }
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Converts a walkable archived value into a [`JsonValue`].
+pub fn to_json_value<T: Walk + ?Sized>(value: &T) -> JsonValue {
+    let mut builder = JsonBuilder {
+        stack: Vec::new(),
+        result: None,
+    };
+    value.walk(&mut builder);
+    builder.result.unwrap_or(JsonValue::Null)
+}
+
+enum Frame {
+    Seq(Vec<JsonValue>),
+    Map {
+        entries: Vec<(String, JsonValue)>,
+        key: Option<String>,
+    },
+    Fields {
+        variant: Option<String>,
+        fields: Vec<(String, JsonValue)>,
+        field: Option<String>,
+    },
+}
+
+struct JsonBuilder {
+    stack: Vec<Frame>,
+    result: Option<JsonValue>,
+}
+
+impl JsonBuilder {
+    fn push(&mut self, value: JsonValue) {
+        match self.stack.last_mut() {
+            None => self.result = Some(value),
+            Some(Frame::Seq(elements)) => elements.push(value),
+            Some(Frame::Map { entries, key }) => match key.take() {
+                Some(key) => entries.push((key, value)),
+                None => {
+                    *key = Some(match value {
+                        JsonValue::String(key) => key,
+                        key => key.to_string(),
+                    });
+                }
+            },
+            Some(Frame::Fields { fields, field, .. }) => {
+                fields.push((field.take().unwrap_or_default(), value));
+            }
+        }
+    }
+
+    fn pop_fields(&mut self) -> (Option<String>, JsonValue) {
+        let Some(Frame::Fields {
+            variant, fields, ..
+        }) = self.stack.pop()
+        else {
+            panic!("unbalanced calls to begin and end a struct or variant");
+        };
+
+        let is_tuple = !fields.is_empty()
+            && fields
+                .iter()
+                .enumerate()
+                .all(|(i, (name, _))| *name == i.to_string());
+        let value = if !is_tuple {
+            JsonValue::Object(fields)
+        } else if fields.len() == 1 {
+            fields.into_iter().next().unwrap().1
+        } else {
+            JsonValue::Array(fields.into_iter().map(|(_, v)| v).collect())
+        };
+
+        (variant, value)
+    }
+}
+
+impl Visitor for JsonBuilder {
+    fn visit_unit(&mut self) {
+        self.push(JsonValue::Null);
+    }
+
+    fn visit_bool(&mut self, value: bool) {
+        self.push(JsonValue::Bool(value));
+    }
+
+    fn visit_unsigned(&mut self, value: u128) {
+        self.push(JsonValue::Unsigned(value));
+    }
+
+    fn visit_signed(&mut self, value: i128) {
+        self.push(JsonValue::Signed(value));
+    }
+
+    fn visit_float(&mut self, value: f64) {
+        self.push(JsonValue::Float(value));
+    }
+
+    fn visit_char(&mut self, value: char) {
+        self.push(JsonValue::String(value.to_string()));
+    }
+
+    fn visit_str(&mut self, value: &str) {
+        self.push(JsonValue::String(value.to_string()));
+    }
+
+    fn visit_none(&mut self) {
+        self.push(JsonValue::Null);
+    }
+
+    fn begin_some(&mut self) {}
+
+    fn end_some(&mut self) {}
+
+    fn begin_seq(&mut self, len: usize) {
+        self.stack.push(Frame::Seq(Vec::with_capacity(len)));
+    }
+
+    fn end_seq(&mut self) {
+        let Some(Frame::Seq(elements)) = self.stack.pop() else {
+            panic!("unbalanced calls to begin_seq and end_seq");
+        };
+        self.push(JsonValue::Array(elements));
+    }
+
+    fn begin_map(&mut self, len: usize) {
+        self.stack.push(Frame::Map {
+            entries: Vec::with_capacity(len),
+            key: None,
+        });
+    }
+
+    fn end_map(&mut self) {
+        let Some(Frame::Map { entries, .. }) = self.stack.pop() else {
+            panic!("unbalanced calls to begin_map and end_map");
+        };
+        self.push(JsonValue::Object(entries));
+    }
+
+    fn begin_struct(&mut self, _: &str) {
+        self.stack.push(Frame::Fields {
+            variant: None,
+            fields: Vec::new(),
+            field: None,
+        });
+    }
+
+    fn end_struct(&mut self) {
+        let (_, value) = self.pop_fields();
+        self.push(value);
+    }
+
+    fn begin_variant(&mut self, _: &str, variant: &str) {
+        self.stack.push(Frame::Fields {
+            variant: Some(variant.to_string()),
+            fields: Vec::new(),
+            field: None,
+        });
+    }
+
+    fn end_variant(&mut self) {
+        let (variant, value) = self.pop_fields();
+        let variant = variant.unwrap_or_default();
+        match value {
+            JsonValue::Object(fields) if fields.is_empty() => {
+                self.push(JsonValue::String(variant));
+            }
+            value => self.push(JsonValue::Object(vec![(variant, value)])),
+        }
+    }
+
+    fn visit_field(&mut self, name: &str) {
+        if let Some(Frame::Fields { field, .. }) = self.stack.last_mut() {
+            *field = Some(name.to_string());
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Walking the structure of archived values.
+//!
+//! Archived types which implement [`Walk`] can be traversed by a [`Visitor`]
+//! without knowing their concrete types. Both traits are object-safe, so
+//! generic tools like archive viewers and debuggers can work with
+//! `&dyn Walk` values.
+//!
+//! Types which derive `Archive` with `#[archive(schema)]` implement `Walk` on
+//! their archived types whenever all of their archived fields implement it.
+//! Fields are named the same as in their [schema](crate::schema).
+//!
+//! With the `alloc` feature, [`to_json_value`] converts any walkable value
+//! into a [`JsonValue`] which can be printed as JSON, and [`diff`] compares two
+//! walkable values and reports the paths of the values which differ.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{rancor::Error, walk::to_json_value, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(schema)]
+//! struct Example {
+//!     id: u32,
+//!     tags: Vec<String>,
+//!     parent: Option<u32>,
+//! }
+//!
+//! let value = Example {
+//!     id: 42,
+//!     tags: vec!["a".to_string(), "b".to_string()],
+//!     parent: None,
+//! };
+//! let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+//! let archived = unsafe { rkyv::access_unchecked::<ArchivedExample>(&bytes) };
+//!
+//! assert_eq!(
+//!     to_json_value(archived).to_string(),
+//!     r#"{"id":42,"tags":["a","b"],"parent":null}"#,
+//! );
+//! ```
+
+#[cfg(feature = "alloc")]
+mod diff;
+#[cfg(feature = "alloc")]
+mod json;
+
+#[cfg(feature = "alloc")]
+pub use self::{diff::*, json::*};
+
+/// An archived value which can be traversed by a [`Visitor`].
+pub trait Walk {
+    /// Calls the methods of `visitor` that describe this value.
+    fn walk(&self, visitor: &mut dyn Visitor);
+}
+
+/// A visitor for the structure of archived values.
+///
+/// Values are described by a sequence of calls to the methods of the visitor.
+/// Primitive values call a single `visit_*` method, while compound values
+/// call a `begin_*` method, walk their contents, and then call the matching
+/// `end_*` method:
+///
+/// - Sequences walk each of their elements.
+/// - Maps walk each key followed by its value.
+/// - Structs and enum variants call [`visit_field`](Visitor::visit_field)
+///   before walking each field. Tuple fields are named `0`, `1`, and so on.
+/// - `Some` values walk the value they contain.
+pub trait Visitor {
+    /// Visits a unit value.
+    fn visit_unit(&mut self);
+
+    /// Visits a `bool`.
+    fn visit_bool(&mut self, value: bool);
+
+    /// Visits an unsigned integer.
+    fn visit_unsigned(&mut self, value: u128);
+
+    /// Visits a signed integer.
+    fn visit_signed(&mut self, value: i128);
+
+    /// Visits a floating-point number.
+    fn visit_float(&mut self, value: f64);
+
+    /// Visits a `char`.
+    fn visit_char(&mut self, value: char);
+
+    /// Visits a string.
+    fn visit_str(&mut self, value: &str);
+
+    /// Visits a `None` value.
+    fn visit_none(&mut self);
+
+    /// Begins a `Some` value.
+    fn begin_some(&mut self);
+
+    /// Ends a `Some` value.
+    fn end_some(&mut self);
+
+    /// Begins a sequence with the given number of elements.
+    fn begin_seq(&mut self, len: usize);
+
+    /// Ends a sequence.
+    fn end_seq(&mut self);
+
+    /// Begins a map with the given number of entries.
+    fn begin_map(&mut self, len: usize);
+
+    /// Ends a map.
+    fn end_map(&mut self);
+
+    /// Begins a struct with the given name.
+    fn begin_struct(&mut self, name: &str);
+
+    /// Ends a struct.
+    fn end_struct(&mut self);
+
+    /// Begins a variant of the enum with the given name.
+    fn begin_variant(&mut self, enum_name: &str, variant: &str);
+
+    /// Ends a variant of an enum.
+    fn end_variant(&mut self);
+
+    /// Visits the name of the next field of a struct or enum variant.
+    fn visit_field(&mut self, name: &str);
+}
@@ -4,10 +4,13 @@ use alloc::{
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
     rc::Rc,
+    string::String,
     sync::Arc,
     vec::Vec,
 };
 use core::marker::PhantomData;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::AtomicPtr;
 #[cfg(feature = "std")]
 use std::{
     borrow::Cow,
@@ -17,17 +20,38 @@ use std::{
 };
 
 use ptr_meta::Pointee;
-use rancor::{Fallible, Source};
+use rancor::{fail, Fallible, OptionExt as _, Source};
 
 use crate::{
-    collections::util::{Entry, EntryAdapter},
-    niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
-    ser::{Allocator, Writer},
+    boxed::{ArchivedBox, BoxResolver},
+    collections::{
+        str_table::{
+            ArchivedSortedStrings, FrontCodedStr, SortedStringsResolver,
+        },
+        util::{Entry, EntryAdapter},
+    },
+    compact::{ArchivedCompactString, ArchivedCompactVec, CompactResolver},
+    cow::{ArchivedCow, CowResolver},
+    de::Interning,
+    niche::{
+        option_box::{ArchivedOptionBox, OptionBoxResolver},
+        option_string::ArchivedOptionString,
+        option_vec::ArchivedOptionVec,
+    },
+    rc::{ArcFlavor, ArchivedRc, RcFlavor, RcResolver},
+    relocate::Relocate,
+    ser::{Allocator, Sharing, SharingExt as _, Writer, WriterExt as _},
     string::{ArchivedString, StringResolver},
-    vec::{ArchivedVec, VecResolver},
+    util::SerVec,
+    vec::{
+        small::{ArchivedSmallVec, SmallVecResolver},
+        ArchivedVec, VecResolver,
+    },
     with::{
-        ArchiveWith, AsOwned, AsVec, Cloned, DeserializeWith, Map, Niche,
-        SerializeWith,
+        impls::atomic::LoadOrdering, ArchiveWith, AsCow, AsFrontCoded, AsOwned,
+        AsSmallVec, AsVec, AtomicPtrAsBox, CStrArray, CStrArrayError, Cloned,
+        Compact, Dedup, DeserializeWith, Intern, InvalidStr, Map, Niche,
+        SerializeWith, Subslice, Unshare,
     },
     Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized,
     LayoutRaw, Place, Serialize, SerializeUnsized,
@@ -114,6 +138,56 @@ where
     }
 }
 
+// AsCow
+
+impl<'a, F: Archive + Clone> ArchiveWith<Cow<'a, F>> for AsCow {
+    type Archived = ArchivedCow<F::Archived>;
+    type Resolver = CowResolver<F::Resolver>;
+
+    fn resolve_with(
+        field: &Cow<'a, F>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedCow::resolve_from_value(field.as_ref(), resolver, out);
+    }
+}
+
+impl<'a, F, S> SerializeWith<Cow<'a, F>, S> for AsCow
+where
+    F: Serialize<S> + Clone,
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &Cow<'a, F>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        match field {
+            Cow::Borrowed(value) => {
+                ArchivedCow::serialize_borrowed(*value, serializer)
+            }
+            Cow::Owned(value) => {
+                ArchivedCow::serialize_owned(value, serializer)
+            }
+        }
+    }
+}
+
+impl<'a, F, D> DeserializeWith<ArchivedCow<F::Archived>, Cow<'a, F>, D>
+    for AsCow
+where
+    F: Archive + Clone,
+    F::Archived: Deserialize<F, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedCow<F::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Cow<'a, F>, D::Error> {
+        Ok(Cow::Owned(field.get().deserialize(deserializer)?))
+    }
+}
+
 // AsOwned
 
 impl<'a, F: Archive + Clone> ArchiveWith<Cow<'a, F>> for AsOwned {
@@ -342,6 +416,239 @@ where
     }
 }
 
+// AsFrontCoded
+
+fn front_coded_to_string(value: FrontCodedStr<'_>) -> String {
+    let mut result = String::with_capacity(value.len());
+    for piece in value.pieces() {
+        result.push_str(piece);
+    }
+    result
+}
+
+impl ArchiveWith<Vec<String>> for AsFrontCoded {
+    type Archived = ArchivedSortedStrings;
+    type Resolver = SortedStringsResolver;
+
+    fn resolve_with(
+        field: &Vec<String>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedSortedStrings::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Vec<String>, S> for AsFrontCoded
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Vec<String>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        SerVec::with_capacity(
+            serializer,
+            field.len(),
+            |strings, serializer| {
+                for value in field.iter() {
+                    strings.push(value.as_str());
+                }
+                strings.sort_unstable();
+                ArchivedSortedStrings::serialize_from_sorted_iter(
+                    strings.iter().copied(),
+                    serializer,
+                )
+            },
+        )?
+    }
+}
+
+impl<D> DeserializeWith<ArchivedSortedStrings, Vec<String>, D> for AsFrontCoded
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedStrings,
+        _: &mut D,
+    ) -> Result<Vec<String>, D::Error> {
+        Ok(field.iter().map(front_coded_to_string).collect())
+    }
+}
+
+impl ArchiveWith<BTreeSet<String>> for AsFrontCoded {
+    type Archived = ArchivedSortedStrings;
+    type Resolver = SortedStringsResolver;
+
+    fn resolve_with(
+        field: &BTreeSet<String>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedSortedStrings::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<S> SerializeWith<BTreeSet<String>, S> for AsFrontCoded
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &BTreeSet<String>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedStrings::serialize_from_sorted_iter(
+            field.iter().map(String::as_str),
+            serializer,
+        )
+    }
+}
+
+impl<D> DeserializeWith<ArchivedSortedStrings, BTreeSet<String>, D>
+    for AsFrontCoded
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedStrings,
+        _: &mut D,
+    ) -> Result<BTreeSet<String>, D::Error> {
+        Ok(field.iter().map(front_coded_to_string).collect())
+    }
+}
+
+// Compact
+
+impl ArchiveWith<String> for Compact {
+    type Archived = ArchivedCompactString;
+    type Resolver = CompactResolver;
+
+    fn resolve_with(
+        field: &String,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedCompactString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<S> SerializeWith<String, S> for Compact
+where
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &String,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedCompactString::serialize_from_str(field, serializer)
+    }
+}
+
+impl<D> DeserializeWith<ArchivedCompactString, String, D> for Compact
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedCompactString,
+        _: &mut D,
+    ) -> Result<String, D::Error> {
+        Ok(String::from(field.as_str()))
+    }
+}
+
+impl<T: Archive> ArchiveWith<Vec<T>> for Compact {
+    type Archived = ArchivedCompactVec<T::Archived>;
+    type Resolver = CompactResolver;
+
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedCompactVec::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for Compact
+where
+    T: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedCompactVec::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedCompactVec<T::Archived>, Vec<T>, D>
+    for Compact
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedCompactVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+// AsSmallVec
+
+impl<T: Archive, const N: usize> ArchiveWith<Vec<T>> for AsSmallVec<N> {
+    type Archived = ArchivedSmallVec<T::Archived, N>;
+    type Resolver = SmallVecResolver<T::Resolver, N>;
+
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedSmallVec::resolve_from_slice(field, resolver, out);
+    }
+}
+
+impl<T, S, const N: usize> SerializeWith<Vec<T>, S> for AsSmallVec<N>
+where
+    T: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSmallVec::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<T, D, const N: usize>
+    DeserializeWith<ArchivedSmallVec<T::Archived, N>, Vec<T>, D>
+    for AsSmallVec<N>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSmallVec<T::Archived, N>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
 // Niche
 
 impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Option<Box<T>>> for Niche
@@ -394,66 +701,664 @@ where
     }
 }
 
-// Cloned
+impl ArchiveWith<Option<String>> for Niche {
+    type Archived = ArchivedOptionString;
+    type Resolver = Option<StringResolver>;
 
-impl<T: Archive> ArchiveWith<Arc<T>> for Cloned {
-    type Archived = T::Archived;
-    type Resolver = T::Resolver;
+    fn resolve_with(
+        field: &Option<String>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedOptionString::resolve_from_option(
+            field.as_deref(),
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<S> SerializeWith<Option<String>, S> for Niche
+where
+    S: Fallible + ?Sized,
+    str: SerializeUnsized<S>,
+{
+    fn serialize_with(
+        field: &Option<String>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedOptionString::serialize_from_option(
+            field.as_deref(),
+            serializer,
+        )
+    }
+}
+
+impl<D> DeserializeWith<ArchivedOptionString, Option<String>, D> for Niche
+where
+    D: Fallible + ?Sized,
+    str: DeserializeUnsized<str, D>,
+{
+    fn deserialize_with(
+        field: &ArchivedOptionString,
+        deserializer: &mut D,
+    ) -> Result<Option<String>, D::Error> {
+        field
+            .as_ref()
+            .map(|value| value.deserialize(deserializer))
+            .transpose()
+    }
+}
+
+impl<T: Archive> ArchiveWith<Option<Vec<T>>> for Niche {
+    type Archived = ArchivedOptionVec<T::Archived>;
+    type Resolver = Option<VecResolver>;
 
     fn resolve_with(
-        x: &Arc<T>,
+        field: &Option<Vec<T>>,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        x.as_ref().resolve(resolver, out)
+        ArchivedOptionVec::resolve_from_len(
+            field.as_ref().map(Vec::len),
+            resolver,
+            out,
+        );
     }
 }
 
-impl<T: Serialize<S>, S: Fallible + ?Sized> SerializeWith<Arc<T>, S>
-    for Cloned
+impl<T, S> SerializeWith<Option<Vec<T>>, S> for Niche
+where
+    T: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
 {
     fn serialize_with(
-        x: &Arc<T>,
-        s: &mut S,
+        field: &Option<Vec<T>>,
+        serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        x.as_ref().serialize(s)
+        ArchivedOptionVec::serialize_from_option(field.as_deref(), serializer)
     }
 }
 
-impl<A: Deserialize<T, D>, T, D: Fallible + ?Sized>
-    DeserializeWith<A, Arc<T>, D> for Cloned
+impl<T, D> DeserializeWith<ArchivedOptionVec<T::Archived>, Option<Vec<T>>, D>
+    for Niche
+where
+    T: Archive,
+    ArchivedVec<T::Archived>: Deserialize<Vec<T>, D>,
+    D: Fallible + ?Sized,
 {
-    fn deserialize_with(x: &A, d: &mut D) -> Result<Arc<T>, D::Error> {
-        Ok(Arc::new(A::deserialize(x, d)?))
+    fn deserialize_with(
+        field: &ArchivedOptionVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Option<Vec<T>>, D::Error> {
+        field
+            .as_ref()
+            .map(|value| value.deserialize(deserializer))
+            .transpose()
     }
 }
 
-impl<T: Archive> ArchiveWith<Rc<T>> for Cloned {
-    type Archived = T::Archived;
-    type Resolver = T::Resolver;
+// AtomicPtrAsBox
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: Archive, SO> ArchiveWith<AtomicPtr<T>> for AtomicPtrAsBox<SO> {
+    type Archived = ArchivedOptionBox<T::Archived>;
+    type Resolver = OptionBoxResolver;
 
     fn resolve_with(
-        x: &Rc<T>,
+        _: &AtomicPtr<T>,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        x.as_ref().resolve(resolver, out)
+        // The pointer isn't loaded again because it may have changed since it
+        // was serialized.
+        ArchivedOptionBox::resolve_from_raw_parts(resolver, (), out);
     }
 }
 
-impl<T: Serialize<S>, S: Fallible + ?Sized> SerializeWith<Rc<T>, S> for Cloned {
+#[cfg(target_has_atomic = "ptr")]
+impl<T, S, SO> SerializeWith<AtomicPtr<T>, S> for AtomicPtrAsBox<SO>
+where
+    T: Serialize<S>,
+    S: Fallible + Writer + ?Sized,
+    SO: LoadOrdering,
+{
     fn serialize_with(
-        x: &Rc<T>,
-        s: &mut S,
+        field: &AtomicPtr<T>,
+        serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        x.as_ref().serialize(s)
+        // SAFETY: The user has guaranteed that the loaded pointer is either
+        // null or points to a valid `T`.
+        let value = unsafe { field.load(SO::ORDERING).as_ref() };
+        ArchivedOptionBox::serialize_from_option(value, serializer)
     }
 }
 
-impl<A: Deserialize<T, D>, T, D: Fallible + ?Sized> DeserializeWith<A, Rc<T>, D>
-    for Cloned
+#[cfg(target_has_atomic = "ptr")]
+impl<T, D, SO> DeserializeWith<ArchivedOptionBox<T::Archived>, AtomicPtr<T>, D>
+    for AtomicPtrAsBox<SO>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
 {
-    fn deserialize_with(x: &A, d: &mut D) -> Result<Rc<T>, D::Error> {
-        Ok(Rc::new(A::deserialize(x, d)?))
+    fn deserialize_with(
+        field: &ArchivedOptionBox<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<AtomicPtr<T>, D::Error> {
+        let ptr = match field.as_deref() {
+            Some(value) => {
+                Box::into_raw(Box::new(value.deserialize(deserializer)?))
+            }
+            None => core::ptr::null_mut(),
+        };
+        Ok(AtomicPtr::new(ptr))
+    }
+}
+
+// Cloned
+
+impl<T: Archive> ArchiveWith<Arc<T>> for Cloned {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve_with(
+        x: &Arc<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        x.as_ref().resolve(resolver, out)
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + ?Sized> SerializeWith<Arc<T>, S>
+    for Cloned
+{
+    fn serialize_with(
+        x: &Arc<T>,
+        s: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        x.as_ref().serialize(s)
+    }
+}
+
+impl<A: Deserialize<T, D>, T, D: Fallible + ?Sized>
+    DeserializeWith<A, Arc<T>, D> for Cloned
+{
+    fn deserialize_with(x: &A, d: &mut D) -> Result<Arc<T>, D::Error> {
+        Ok(Arc::new(A::deserialize(x, d)?))
+    }
+}
+
+impl<T: Archive> ArchiveWith<Rc<T>> for Cloned {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve_with(
+        x: &Rc<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        x.as_ref().resolve(resolver, out)
+    }
+}
+
+impl<T: Serialize<S>, S: Fallible + ?Sized> SerializeWith<Rc<T>, S> for Cloned {
+    fn serialize_with(
+        x: &Rc<T>,
+        s: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        x.as_ref().serialize(s)
+    }
+}
+
+impl<A: Deserialize<T, D>, T, D: Fallible + ?Sized> DeserializeWith<A, Rc<T>, D>
+    for Cloned
+{
+    fn deserialize_with(x: &A, d: &mut D) -> Result<Rc<T>, D::Error> {
+        Ok(Rc::new(A::deserialize(x, d)?))
+    }
+}
+
+// Intern
+
+impl ArchiveWith<Arc<str>> for Intern {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &Arc<str>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Arc<str>, S> for Intern
+where
+    S: Fallible + ?Sized,
+    str: SerializeUnsized<S>,
+{
+    fn serialize_with(
+        field: &Arc<str>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(field, serializer)
+    }
+}
+
+impl<D> DeserializeWith<ArchivedString, Arc<str>, D> for Intern
+where
+    D: Fallible + Interning + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedString,
+        deserializer: &mut D,
+    ) -> Result<Arc<str>, D::Error> {
+        Ok(deserializer.intern_str(field.as_str()))
+    }
+}
+
+// Unshare
+
+impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Arc<T>> for Unshare {
+    type Archived = ArchivedRc<T::Archived, ArcFlavor>;
+    type Resolver = RcResolver;
+
+    fn resolve_with(
+        field: &Arc<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedRc::resolve_from_ref(field.as_ref(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Arc<T>, S> for Unshare
+where
+    T: SerializeUnsized<S> + ?Sized,
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Arc<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRc::<T::Archived, ArcFlavor>::serialize_unshared_from_ref(
+            field.as_ref(),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedRc<T::Archived, ArcFlavor>, Arc<T>, D>
+    for Unshare
+where
+    T: ArchiveUnsized + ?Sized,
+    ArchivedRc<T::Archived, ArcFlavor>: Deserialize<Arc<T>, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRc<T::Archived, ArcFlavor>,
+        deserializer: &mut D,
+    ) -> Result<Arc<T>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+impl<T: ArchiveUnsized + ?Sized> ArchiveWith<Rc<T>> for Unshare {
+    type Archived = ArchivedRc<T::Archived, RcFlavor>;
+    type Resolver = RcResolver;
+
+    fn resolve_with(
+        field: &Rc<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedRc::resolve_from_ref(field.as_ref(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Rc<T>, S> for Unshare
+where
+    T: SerializeUnsized<S> + ?Sized,
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Rc<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRc::<T::Archived, RcFlavor>::serialize_unshared_from_ref(
+            field.as_ref(),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedRc<T::Archived, RcFlavor>, Rc<T>, D>
+    for Unshare
+where
+    T: ArchiveUnsized + ?Sized,
+    ArchivedRc<T::Archived, RcFlavor>: Deserialize<Rc<T>, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRc<T::Archived, RcFlavor>,
+        deserializer: &mut D,
+    ) -> Result<Rc<T>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// Dedup
+
+impl ArchiveWith<String> for Dedup {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &String,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<S> SerializeWith<String, S> for Dedup
+where
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &String,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str_shared(field, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, String, D>
+    for Dedup
+{
+    fn deserialize_with(
+        field: &ArchivedString,
+        deserializer: &mut D,
+    ) -> Result<String, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// Subslice
+
+impl<'a> ArchiveWith<Cow<'a, [u8]>> for Subslice {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(
+        field: &Cow<'a, [u8]>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVec::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<'a, S> SerializeWith<Cow<'a, [u8]>, S> for Subslice
+where
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &Cow<'a, [u8]>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        serializer.check_len(field.len())?;
+        Ok(VecResolver::from_pos(
+            serializer.serialize_shared_subslice(field)?,
+        ))
+    }
+}
+
+impl<'a, D> DeserializeWith<ArchivedVec<u8>, Cow<'a, [u8]>, D> for Subslice
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<u8>,
+        _: &mut D,
+    ) -> Result<Cow<'a, [u8]>, D::Error> {
+        Ok(Cow::Owned(field.as_slice().to_vec()))
+    }
+}
+
+impl<'a> ArchiveWith<Cow<'a, str>> for Subslice {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &Cow<'a, str>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<'a, S> SerializeWith<Cow<'a, str>, S> for Subslice
+where
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &Cow<'a, str>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str_subslice(field, serializer)
+    }
+}
+
+impl<'a, D> DeserializeWith<ArchivedString, Cow<'a, str>, D> for Subslice
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedString,
+        _: &mut D,
+    ) -> Result<Cow<'a, str>, D::Error> {
+        Ok(Cow::Owned(field.as_str().into()))
+    }
+}
+
+macro_rules! impl_dedup_box {
+    ($ty:ty, $as_bytes:ident) => {
+        impl ArchiveWith<Box<$ty>> for Dedup {
+            type Archived = ArchivedBox<$ty>;
+            type Resolver = BoxResolver;
+
+            fn resolve_with(
+                field: &Box<$ty>,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedBox::resolve_from_ref(field.as_ref(), resolver, out);
+            }
+        }
+
+        impl<S> SerializeWith<Box<$ty>, S> for Dedup
+        where
+            S: Fallible + Writer + Sharing + ?Sized,
+        {
+            fn serialize_with(
+                field: &Box<$ty>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                let bytes = field.$as_bytes();
+                serializer.check_len(bytes.len())?;
+                Ok(BoxResolver::from_pos(
+                    serializer.serialize_shared_bytes(bytes)?,
+                ))
+            }
+        }
+
+        impl<D> DeserializeWith<ArchivedBox<$ty>, Box<$ty>, D> for Dedup
+        where
+            D: Fallible + ?Sized,
+            D::Error: Source,
+        {
+            fn deserialize_with(
+                field: &ArchivedBox<$ty>,
+                deserializer: &mut D,
+            ) -> Result<Box<$ty>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+    };
+}
+
+impl_dedup_box!(str, as_bytes);
+impl_dedup_box!([u8], as_ref);
+
+impl<T: Archive> ArchiveWith<Box<T>> for Dedup {
+    type Archived = ArchivedBox<T::Archived>;
+    type Resolver = BoxResolver;
+
+    fn resolve_with(
+        field: &Box<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBox::resolve_from_ref(field.as_ref(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Box<T>, S> for Dedup
+where
+    T: Serialize<S>,
+    T::Archived: Relocate,
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &Box<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(BoxResolver::from_pos(
+            serializer.serialize_shared_subtree(field.as_ref())?,
+        ))
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedBox<T::Archived>, Box<T>, D> for Dedup
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedBox<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Box<T>, D::Error> {
+        Ok(Box::new(field.get().deserialize(deserializer)?))
+    }
+}
+
+impl<T: Archive> ArchiveWith<Vec<T>> for Dedup {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVec::resolve_from_slice(field, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for Dedup
+where
+    T: Serialize<S>,
+    T::Archived: Relocate,
+    S: Fallible + Allocator + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        serializer.check_len(field.len())?;
+        Ok(VecResolver::from_pos(
+            serializer.serialize_shared_subtree_slice(field)?,
+        ))
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedVec<T::Archived>, Vec<T>, D> for Dedup
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+// CStrArray
+
+impl<const N: usize> ArchiveWith<String> for CStrArray<N> {
+    type Archived = [u8; N];
+    type Resolver = ();
+
+    #[inline]
+    fn resolve_with(
+        field: &String,
+        _: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        out.write(Self::to_array(field.as_bytes()));
+    }
+}
+
+impl<S, const N: usize> SerializeWith<String, S> for CStrArray<N>
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &String,
+        _: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        if field.as_bytes().contains(&0) {
+            fail!(CStrArrayError::InteriorNul);
+        }
+        if field.len() >= N {
+            fail!(CStrArrayError::TooLong {
+                len: field.len(),
+                capacity: N,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<D, const N: usize> DeserializeWith<[u8; N], String, D> for CStrArray<N>
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &[u8; N],
+        _: &mut D,
+    ) -> Result<String, D::Error> {
+        let Some(len) = field.iter().position(|&b| b == 0) else {
+            fail!(CStrArrayError::MissingNul);
+        };
+        let s = core::str::from_utf8(&field[..len])
+            .ok()
+            .into_trace(InvalidStr)?;
+        Ok(String::from(s))
     }
 }
@@ -17,7 +17,7 @@ use crate::{
     Place,
 };
 
-trait LoadOrdering {
+pub(super) trait LoadOrdering {
     const ORDERING: Ordering;
 }
 
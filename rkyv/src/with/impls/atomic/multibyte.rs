@@ -1,38 +1,53 @@
+#[cfg(any(
+    all(target_has_atomic = "16", feature = "pointer_width_16"),
+    all(
+        target_has_atomic = "32",
+        not(any(feature = "pointer_width_16", feature = "pointer_width_64")),
+    ),
+    all(target_has_atomic = "64", feature = "pointer_width_64"),
+))]
+use {
+    crate::{
+        primitive::{
+            try_fixed_isize, try_fixed_usize, ArchivedAtomicIsize,
+            ArchivedAtomicUsize, ArchivedIsize, ArchivedUsize, FixedIsize,
+            FixedUsize,
+        },
+        rancor::Source,
+        with::SerializeWith,
+    },
+    core::sync::atomic::{AtomicIsize, AtomicUsize},
+};
 #[cfg(target_has_atomic = "16")]
 use {
-    crate::primitive::{
-        ArchivedAtomicI16, ArchivedAtomicU16, ArchivedI16, ArchivedU16,
+    crate::{
+        primitive::{
+            ArchivedAtomicI16, ArchivedAtomicU16, ArchivedI16, ArchivedU16,
+        },
+        rend::{AtomicI16_be, AtomicI16_le, AtomicU16_be, AtomicU16_le},
     },
     core::sync::atomic::{AtomicI16, AtomicU16},
 };
 #[cfg(target_has_atomic = "32")]
 use {
-    crate::primitive::{
-        ArchivedAtomicI32, ArchivedAtomicU32, ArchivedI32, ArchivedU32,
+    crate::{
+        primitive::{
+            ArchivedAtomicI32, ArchivedAtomicU32, ArchivedI32, ArchivedU32,
+        },
+        rend::{AtomicI32_be, AtomicI32_le, AtomicU32_be, AtomicU32_le},
     },
     core::sync::atomic::{AtomicI32, AtomicU32},
 };
 #[cfg(target_has_atomic = "64")]
 use {
-    crate::primitive::{
-        ArchivedAtomicI64, ArchivedAtomicU64, ArchivedI64, ArchivedU64,
+    crate::{
+        primitive::{
+            ArchivedAtomicI64, ArchivedAtomicU64, ArchivedI64, ArchivedU64,
+        },
+        rend::{AtomicI64_be, AtomicI64_le, AtomicU64_be, AtomicU64_le},
     },
     core::sync::atomic::{AtomicI64, AtomicU64},
 };
-#[cfg(any(
-    all(target_has_atomic = "16", feature = "pointer_width_16"),
-    all(
-        target_has_atomic = "32",
-        not(any(feature = "pointer_width_16", feature = "pointer_width_64")),
-    ),
-    all(target_has_atomic = "64", feature = "pointer_width_64"),
-))]
-use {
-    crate::primitive::{
-        ArchivedAtomicIsize, ArchivedAtomicUsize, ArchivedIsize, ArchivedUsize,
-    },
-    core::sync::atomic::{AtomicIsize, AtomicUsize},
-};
 
 use crate::{
     rancor::Fallible,
@@ -134,19 +149,23 @@ impl_multi_byte_atomics! {
 // AtomicUsize
 
 macro_rules! impl_atomic_size_type {
-    ($atomic:ty, $archived:ty, $archived_non_atomic:ty) => {
+    (
+        $atomic:ty,
+        $archived:ty,
+        $archived_non_atomic:ty,
+        $fixed:ty,
+        $try_fixed:ident
+    ) => {
         impl<SO: LoadOrdering> ArchiveWith<$atomic> for AtomicLoad<SO> {
             type Archived = $archived_non_atomic;
-            type Resolver = ();
+            type Resolver = $fixed;
 
             fn resolve_with(
-                field: &$atomic,
-                _: Self::Resolver,
+                _: &$atomic,
+                resolver: Self::Resolver,
                 out: Place<Self::Archived>,
             ) {
-                out.write(<$archived_non_atomic>::from_native(
-                    field.load(SO::ORDERING) as _,
-                ));
+                out.write(<$archived_non_atomic>::from_native(resolver));
             }
         }
 
@@ -155,18 +174,47 @@ macro_rules! impl_atomic_size_type {
             SO: LoadOrdering,
         {
             type Archived = $archived;
-            type Resolver = ();
+            type Resolver = $fixed;
 
             fn resolve_with(
-                field: &$atomic,
-                _: Self::Resolver,
+                _: &$atomic,
+                resolver: Self::Resolver,
                 out: Place<Self::Archived>,
             ) {
-                out.write(<$archived>::new(field.load(SO::ORDERING) as _));
+                out.write(<$archived>::new(resolver));
             }
         }
 
-        impl_serialize_with_atomic!($atomic);
+        // The loaded value may not fit in the archived pointer width, so it is
+        // loaded and converted while serializing instead of while resolving.
+
+        impl<S, SO> SerializeWith<$atomic, S> for AtomicLoad<SO>
+        where
+            S: Fallible + ?Sized,
+            S::Error: Source,
+            SO: LoadOrdering,
+        {
+            fn serialize_with(
+                field: &$atomic,
+                _: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                $try_fixed(field.load(SO::ORDERING))
+            }
+        }
+
+        impl<S, SO, DO> SerializeWith<$atomic, S> for AsAtomic<SO, DO>
+        where
+            S: Fallible + ?Sized,
+            S::Error: Source,
+            SO: LoadOrdering,
+        {
+            fn serialize_with(
+                field: &$atomic,
+                _: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                $try_fixed(field.load(SO::ORDERING))
+            }
+        }
 
         impl<D, SO> DeserializeWith<$archived_non_atomic, $atomic, D>
             for AtomicLoad<SO>
@@ -198,9 +246,21 @@ macro_rules! impl_atomic_size_type {
 }
 
 macro_rules! impl_atomic_size_types {
-    ($($atomic:ty, $archived:ty, $archived_non_atomic:ty;)*) => {
+    ($(
+        $atomic:ty,
+        $archived:ty,
+        $archived_non_atomic:ty,
+        $fixed:ty,
+        $try_fixed:ident;
+    )*) => {
         $(
-            impl_atomic_size_type!($atomic, $archived, $archived_non_atomic);
+            impl_atomic_size_type!(
+                $atomic,
+                $archived,
+                $archived_non_atomic,
+                $fixed,
+                $try_fixed
+            );
         )*
     }
 }
@@ -214,8 +274,32 @@ macro_rules! impl_atomic_size_types {
     all(target_has_atomic = "64", feature = "pointer_width_64"),
 ))]
 impl_atomic_size_types! {
-    AtomicIsize, ArchivedAtomicIsize, ArchivedIsize;
-    AtomicUsize, ArchivedAtomicUsize, ArchivedUsize;
+    AtomicIsize, ArchivedAtomicIsize, ArchivedIsize,
+        FixedIsize, try_fixed_isize;
+    AtomicUsize, ArchivedAtomicUsize, ArchivedUsize,
+        FixedUsize, try_fixed_usize;
 }
 
-// TODO: provide impls for rend atomics
+// rend atomics
+
+#[cfg(target_has_atomic = "16")]
+impl_multi_byte_atomics! {
+    AtomicI16_le, ArchivedAtomicI16, ArchivedI16;
+    AtomicI16_be, ArchivedAtomicI16, ArchivedI16;
+    AtomicU16_le, ArchivedAtomicU16, ArchivedU16;
+    AtomicU16_be, ArchivedAtomicU16, ArchivedU16;
+}
+#[cfg(target_has_atomic = "32")]
+impl_multi_byte_atomics! {
+    AtomicI32_le, ArchivedAtomicI32, ArchivedI32;
+    AtomicI32_be, ArchivedAtomicI32, ArchivedI32;
+    AtomicU32_le, ArchivedAtomicU32, ArchivedU32;
+    AtomicU32_be, ArchivedAtomicU32, ArchivedU32;
+}
+#[cfg(target_has_atomic = "64")]
+impl_multi_byte_atomics! {
+    AtomicI64_le, ArchivedAtomicI64, ArchivedI64;
+    AtomicI64_be, ArchivedAtomicI64, ArchivedI64;
+    AtomicU64_le, ArchivedAtomicU64, ArchivedU64;
+    AtomicU64_be, ArchivedAtomicU64, ArchivedU64;
+}
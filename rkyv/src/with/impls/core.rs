@@ -1,11 +1,11 @@
 use core::{
-    cell::{Cell, UnsafeCell},
+    cell::{Cell, OnceCell, UnsafeCell},
     hint::unreachable_unchecked,
     num::{NonZeroIsize, NonZeroUsize},
 };
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{fail, Fallible, ResultExt as _, Source};
 
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
@@ -14,10 +14,17 @@ use crate::{
     },
     option::ArchivedOption,
     place::Initialized,
-    primitive::{FixedNonZeroIsize, FixedNonZeroUsize},
+    primitive::{
+        ArchivedAlign8I128, ArchivedAlign8U128, ArchivedTotalF32,
+        ArchivedTotalF64, FixedNonZeroIsize, FixedNonZeroUsize,
+    },
+    ser::{Sharing, SharingExt as _, Writer},
+    string::{ArchivedString, StringResolver},
+    vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, Boxed, BoxedInline, DeserializeWith, Inline, Map, Niche,
-        SerializeWith, Skip, Unsafe,
+        Align8, ArchiveWith, AsOption, Boxed, BoxedInline, CStrArray,
+        CStrArrayError, DeserializeWith, Inline, Map, Niche, SerializeWith,
+        Skip, Subslice, TotalOrdFloat, Unsafe,
     },
     Archive, ArchiveUnsized, Deserialize, Place, Serialize, SerializeUnsized,
 };
@@ -122,6 +129,55 @@ struct ArchivedOptionVariantNone(ArchivedOptionTag);
 #[repr(C)]
 struct ArchivedOptionVariantSome<T>(ArchivedOptionTag, T);
 
+// AsOption
+
+impl<T: Archive> ArchiveWith<OnceCell<T>> for AsOption {
+    type Archived = ArchivedOption<T::Archived>;
+    type Resolver = Option<T::Resolver>;
+
+    fn resolve_with(
+        field: &OnceCell<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedOption::resolve_from_option(field.get(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<OnceCell<T>, S> for AsOption
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize_with(
+        field: &OnceCell<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        field
+            .get()
+            .map(|value| value.serialize(serializer))
+            .transpose()
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedOption<T::Archived>, OnceCell<T>, D>
+    for AsOption
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedOption<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<OnceCell<T>, D::Error> {
+        match field.as_ref() {
+            Some(value) => Ok(OnceCell::from(value.deserialize(deserializer)?)),
+            None => Ok(OnceCell::new()),
+        }
+    }
+}
+
 // Inline
 
 impl<F: Archive> ArchiveWith<&F> for Inline {
@@ -211,29 +267,91 @@ where
     }
 }
 
+// Subslice
+
+impl<'a> ArchiveWith<&'a [u8]> for Subslice {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(
+        field: &&'a [u8],
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVec::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<'a, S> SerializeWith<&'a [u8], S> for Subslice
+where
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &&'a [u8],
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(VecResolver::from_pos(
+            serializer.serialize_shared_subslice(field)?,
+        ))
+    }
+}
+
+impl<'a> ArchiveWith<&'a str> for Subslice {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &&'a str,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<'a, S> SerializeWith<&'a str, S> for Subslice
+where
+    S: Fallible + Writer + Sharing + ?Sized,
+{
+    fn serialize_with(
+        field: &&'a str,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str_subslice(field, serializer)
+    }
+}
+
 // Niche
 
 impl ArchiveWith<Option<NonZeroIsize>> for Niche {
     type Archived = ArchivedOptionNonZeroIsize;
-    type Resolver = ();
+    type Resolver = Option<FixedNonZeroIsize>;
 
     #[inline]
     fn resolve_with(
-        field: &Option<NonZeroIsize>,
-        _: Self::Resolver,
+        _: &Option<NonZeroIsize>,
+        resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        let f = field.as_ref().map(|&x| x.try_into().unwrap());
-        ArchivedOptionNonZeroIsize::resolve_from_option(f, out);
+        ArchivedOptionNonZeroIsize::resolve_from_option(resolver, out);
     }
 }
 
-impl<S: Fallible + ?Sized> SerializeWith<Option<NonZeroIsize>, S> for Niche {
+impl<S> SerializeWith<Option<NonZeroIsize>, S> for Niche
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
     fn serialize_with(
-        _: &Option<NonZeroIsize>,
+        field: &Option<NonZeroIsize>,
         _: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        Ok(())
+        // The value may not fit in the archived pointer width, so convert it
+        // here where the error can be returned instead of in resolve_with.
+        field
+            .map(FixedNonZeroIsize::try_from)
+            .transpose()
+            .into_error()
     }
 }
 
@@ -255,25 +373,33 @@ impl<D: Fallible + ?Sized>
 
 impl ArchiveWith<Option<NonZeroUsize>> for Niche {
     type Archived = ArchivedOptionNonZeroUsize;
-    type Resolver = ();
+    type Resolver = Option<FixedNonZeroUsize>;
 
     #[inline]
     fn resolve_with(
-        field: &Option<NonZeroUsize>,
-        _: Self::Resolver,
+        _: &Option<NonZeroUsize>,
+        resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        let f = field.as_ref().map(|&x| x.try_into().unwrap());
-        ArchivedOptionNonZeroUsize::resolve_from_option(f, out);
+        ArchivedOptionNonZeroUsize::resolve_from_option(resolver, out);
     }
 }
 
-impl<S: Fallible + ?Sized> SerializeWith<Option<NonZeroUsize>, S> for Niche {
+impl<S> SerializeWith<Option<NonZeroUsize>, S> for Niche
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
     fn serialize_with(
-        _: &Option<NonZeroUsize>,
+        field: &Option<NonZeroUsize>,
         _: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        Ok(())
+        // The value may not fit in the archived pointer width, so convert it
+        // here where the error can be returned instead of in resolve_with.
+        field
+            .map(FixedNonZeroUsize::try_from)
+            .transpose()
+            .into_error()
     }
 }
 
@@ -293,6 +419,58 @@ impl<D: Fallible + ?Sized>
     }
 }
 
+// Wrappers which archive a primitive as a different archived type
+
+macro_rules! impl_native_wrapper {
+    ($wrapper:ty, $prim:ty, $archived:ty) => {
+        impl ArchiveWith<$prim> for $wrapper {
+            type Archived = $archived;
+            type Resolver = ();
+
+            #[inline]
+            fn resolve_with(
+                field: &$prim,
+                _: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                out.write(<$archived>::from_native(*field));
+            }
+        }
+
+        impl<S: Fallible + ?Sized> SerializeWith<$prim, S> for $wrapper {
+            #[inline]
+            fn serialize_with(
+                _: &$prim,
+                _: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> DeserializeWith<$archived, $prim, D>
+            for $wrapper
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &$archived,
+                _: &mut D,
+            ) -> Result<$prim, D::Error> {
+                Ok(field.to_native())
+            }
+        }
+    };
+}
+
+// Align8
+
+impl_native_wrapper!(Align8, u128, ArchivedAlign8U128);
+impl_native_wrapper!(Align8, i128, ArchivedAlign8I128);
+
+// TotalOrdFloat
+
+impl_native_wrapper!(TotalOrdFloat, f32, ArchivedTotalF32);
+impl_native_wrapper!(TotalOrdFloat, f64, ArchivedTotalF64);
+
 // Unsafe
 
 impl<F: Archive> ArchiveWith<UnsafeCell<F>> for Unsafe {
@@ -401,3 +579,47 @@ impl<F: Default, D: Fallible + ?Sized> DeserializeWith<(), F, D> for Skip {
         Ok(Default::default())
     }
 }
+
+// CStrArray
+
+impl<const N: usize> ArchiveWith<[u8; N]> for CStrArray<N> {
+    type Archived = [u8; N];
+    type Resolver = ();
+
+    #[inline]
+    fn resolve_with(
+        field: &[u8; N],
+        _: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        out.write(Self::to_array(field));
+    }
+}
+
+impl<S, const N: usize> SerializeWith<[u8; N], S> for CStrArray<N>
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &[u8; N],
+        _: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        if !field.contains(&0) {
+            fail!(CStrArrayError::MissingNul);
+        }
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized, const N: usize> DeserializeWith<[u8; N], [u8; N], D>
+    for CStrArray<N>
+{
+    #[inline]
+    fn deserialize_with(
+        field: &[u8; N],
+        _: &mut D,
+    ) -> Result<[u8; N], D::Error> {
+        Ok(*field)
+    }
+}
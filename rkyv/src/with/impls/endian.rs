@@ -0,0 +1,93 @@
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64,
+};
+
+use rancor::Fallible;
+
+use crate::{
+    with::{
+        ArchiveWith, BigEndian, DeserializeWith, LittleEndian, SerializeWith,
+    },
+    Place,
+};
+
+macro_rules! impl_endian_wrapper {
+    ($wrapper:ident, $prim:ty, $aligned:ident, $unaligned:ident) => {
+        impl ArchiveWith<$prim> for $wrapper {
+            #[cfg(not(feature = "unaligned"))]
+            type Archived = crate::rend::$aligned;
+            #[cfg(feature = "unaligned")]
+            type Archived = crate::rend::unaligned::$unaligned;
+            type Resolver = ();
+
+            #[inline]
+            fn resolve_with(
+                field: &$prim,
+                _: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                out.write(<Self::Archived>::from_native(*field));
+            }
+        }
+
+        impl<S: Fallible + ?Sized> SerializeWith<$prim, S> for $wrapper {
+            #[inline]
+            fn serialize_with(
+                _: &$prim,
+                _: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized>
+            DeserializeWith<
+                <$wrapper as ArchiveWith<$prim>>::Archived,
+                $prim,
+                D,
+            > for $wrapper
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &<$wrapper as ArchiveWith<$prim>>::Archived,
+                _: &mut D,
+            ) -> Result<$prim, D::Error> {
+                Ok(field.to_native())
+            }
+        }
+    };
+}
+
+macro_rules! impl_endian_wrappers {
+    ($($prim:ty: $le:ident, $ule:ident, $be:ident, $ube:ident);* $(;)?) => {
+        $(
+            impl_endian_wrapper!(LittleEndian, $prim, $le, $ule);
+            impl_endian_wrapper!(BigEndian, $prim, $be, $ube);
+        )*
+    };
+}
+
+impl_endian_wrappers! {
+    i16: i16_le, i16_ule, i16_be, i16_ube;
+    i32: i32_le, i32_ule, i32_be, i32_ube;
+    i64: i64_le, i64_ule, i64_be, i64_ube;
+    i128: i128_le, i128_ule, i128_be, i128_ube;
+    u16: u16_le, u16_ule, u16_be, u16_ube;
+    u32: u32_le, u32_ule, u32_be, u32_ube;
+    u64: u64_le, u64_ule, u64_be, u64_ube;
+    u128: u128_le, u128_ule, u128_be, u128_ube;
+    f32: f32_le, f32_ule, f32_be, f32_ube;
+    f64: f64_le, f64_ule, f64_be, f64_ube;
+    char: char_le, char_ule, char_be, char_ube;
+    NonZeroI16: NonZeroI16_le, NonZeroI16_ule, NonZeroI16_be, NonZeroI16_ube;
+    NonZeroI32: NonZeroI32_le, NonZeroI32_ule, NonZeroI32_be, NonZeroI32_ube;
+    NonZeroI64: NonZeroI64_le, NonZeroI64_ule, NonZeroI64_be, NonZeroI64_ube;
+    NonZeroI128:
+        NonZeroI128_le, NonZeroI128_ule, NonZeroI128_be, NonZeroI128_ube;
+    NonZeroU16: NonZeroU16_le, NonZeroU16_ule, NonZeroU16_be, NonZeroU16_ube;
+    NonZeroU32: NonZeroU32_le, NonZeroU32_ule, NonZeroU32_be, NonZeroU32_ube;
+    NonZeroU64: NonZeroU64_le, NonZeroU64_ule, NonZeroU64_be, NonZeroU64_ube;
+    NonZeroU128:
+        NonZeroU128_le, NonZeroU128_ule, NonZeroU128_be, NonZeroU128_ube;
+}
@@ -1,6 +1,9 @@
-#[cfg(feature = "alloc")]
-mod alloc;
-mod atomic;
-mod core;
-#[cfg(feature = "std")]
-mod std;
+#[cfg(feature = "alloc")]
+mod alloc;
+mod atomic;
+mod core;
+mod endian;
+#[cfg(feature = "std")]
+mod std;
+#[cfg(feature = "tokio")]
+mod tokio;
@@ -1,32 +1,47 @@
+use core::{alloc::Layout, ops::Deref, ptr::NonNull};
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
-    ffi::{CStr, OsString},
+    ffi::{CStr, OsStr, OsString},
     hash::Hash,
     path::{Path, PathBuf},
     str::FromStr,
-    sync::{Mutex, RwLock},
-    time::{SystemTime, UNIX_EPOCH},
+    sync::{Arc, LazyLock, LockResult, Mutex, OnceLock, PoisonError, RwLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use rancor::{Fallible, OptionExt, ResultExt, Source};
+use rancor::{fail, Fallible, OptionExt, ResultExt, Source, Strategy};
 
 use crate::{
     collections::util::{Entry, EntryAdapter},
+    de::{ErasedPtr, Interning, Pooling},
     ffi::{ArchivedCString, CStringResolver},
-    ser::{Allocator, Writer},
+    option::ArchivedOption,
+    primitive::ArchivedI64,
+    ser::{writer::OverflowError, Allocator, Positional, Sharing, Writer},
     string::{ArchivedString, StringResolver},
     time::ArchivedDuration,
     vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsOwned, AsString, AsVec, DeserializeWith, Immutable,
-        InvalidStr, Lock, Poisoned, SerializeWith, UnixTimestamp,
+        Anchored, ArchiveWith, AsOption, AsOwned, AsString, AsVec,
+        DefaultOnPoison, DeserializeWith, ErrorOnPoison, IgnorePoison,
+        Immutable, InstantAnchor, InstantOutOfRange, InstantSince, InvalidStr,
+        Lock, LockResolver, Poisoned, SerializeWith, UnixTimestamp,
     },
     Archive, Deserialize, Place, Serialize, SerializeUnsized,
 };
 
 // AsString
 
+/// Returns an `OsStr` which was checked to be valid UTF-8 in `serialize_with`.
+fn checked_str(field: &OsStr) -> &str {
+    // The field is borrowed immutably from serializing through resolving, so
+    // it can't have changed since it was checked.
+    field.to_str().unwrap_or_else(|| {
+        unreachable!("string was checked to be valid UTF-8 while serializing")
+    })
+}
+
 impl ArchiveWith<OsString> for AsString {
     type Archived = ArchivedString;
     type Resolver = StringResolver;
@@ -37,10 +52,8 @@ impl ArchiveWith<OsString> for AsString {
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        // It's safe to unwrap here because if the OsString wasn't valid UTF-8
-        // it would have failed to serialize
         ArchivedString::resolve_from_str(
-            field.to_str().unwrap(),
+            checked_str(field.as_ref()),
             resolver,
             out,
         );
@@ -84,10 +97,8 @@ impl ArchiveWith<PathBuf> for AsString {
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        // It's safe to unwrap here because if the OsString wasn't valid UTF-8
-        // it would have failed to serialize
         ArchivedString::resolve_from_str(
-            field.to_str().unwrap(),
+            checked_str(field.as_ref()),
             resolver,
             out,
         );
@@ -123,33 +134,100 @@ impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, PathBuf, D>
 
 // Lock
 
-impl<F: Archive> ArchiveWith<Mutex<F>> for Lock {
+trait PoisonPolicy<F> {
+    /// Returns the value to serialize in place of the value in a poisoned lock,
+    /// or `None` to serialize the value in the lock anyway.
+    fn on_poison<E: Source>() -> Result<Option<F>, E>;
+}
+
+impl<F> PoisonPolicy<F> for ErrorOnPoison {
+    fn on_poison<E: Source>() -> Result<Option<F>, E> {
+        fail!(Poisoned);
+    }
+}
+
+impl<F> PoisonPolicy<F> for IgnorePoison {
+    fn on_poison<E: Source>() -> Result<Option<F>, E> {
+        Ok(None)
+    }
+}
+
+impl<F: Default> PoisonPolicy<F> for DefaultOnPoison {
+    fn on_poison<E: Source>() -> Result<Option<F>, E> {
+        Ok(Some(F::default()))
+    }
+}
+
+fn serialize_locked<F, P, G, S>(
+    locked: LockResult<G>,
+    serializer: &mut S,
+) -> Result<LockResolver<F>, S::Error>
+where
+    F: Serialize<S>,
+    P: PoisonPolicy<F>,
+    G: Deref<Target = F>,
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    let guard = match locked {
+        Ok(guard) => guard,
+        Err(poisoned) => match P::on_poison()? {
+            Some(default) => {
+                return Ok(LockResolver {
+                    resolver: default.serialize(serializer)?,
+                    default: Some(default),
+                });
+            }
+            None => poisoned.into_inner(),
+        },
+    };
+    Ok(LockResolver {
+        resolver: guard.serialize(serializer)?,
+        default: None,
+    })
+}
+
+fn resolve_locked<F, G>(
+    lock: impl FnOnce() -> LockResult<G>,
+    resolver: LockResolver<F>,
+    out: Place<Immutable<F::Archived>>,
+) where
+    F: Archive,
+    G: Deref<Target = F>,
+{
+    // SAFETY: `Immutable<T>` is `repr(transparent)` over `T`.
+    let out = unsafe { out.cast_unchecked() };
+    if let Some(default) = resolver.default {
+        default.resolve(resolver.resolver, out);
+    } else {
+        // Resolve must be infallible, so the lock can't be held in the
+        // resolver: serializing two Arcs to the same lock would deadlock.
+        // Poisoning was already handled during serialize_with, so recover
+        // the guard if the lock was poisoned between serializing and
+        // resolving.
+        lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .resolve(resolver.resolver, out);
+    }
+}
+
+impl<F: Archive, P> ArchiveWith<Mutex<F>> for Lock<P> {
     type Archived = Immutable<F::Archived>;
-    type Resolver = F::Resolver;
+    type Resolver = LockResolver<F>;
 
     fn resolve_with(
         field: &Mutex<F>,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        let out = unsafe { out.cast_unchecked() };
-        // Unfortunately, we have to unwrap here because resolve must be
-        // infallible
-        //
-        // An alternative would be to only implement ArchiveWith for
-        // Arc<Mutex<F>>, copy an Arc into the resolver, and hold the
-        // guard in there as well (as a reference to the internal mutex).
-        // This unfortunately will cause a deadlock if two Arcs to the same
-        // Mutex are serialized before the first is resolved. The
-        // compromise is, unfortunately, to just unwrap poison
-        // errors here and document it.
-        field.lock().unwrap().resolve(resolver, out);
-    }
-}
-
-impl<F, S> SerializeWith<Mutex<F>, S> for Lock
+        resolve_locked(|| field.lock(), resolver, out);
+    }
+}
+
+impl<F, P, S> SerializeWith<Mutex<F>, S> for Lock<P>
 where
     F: Serialize<S>,
+    P: PoisonPolicy<F>,
     S: Fallible + ?Sized,
     S::Error: Source,
 {
@@ -157,15 +235,11 @@ where
         field: &Mutex<F>,
         serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        field
-            .lock()
-            .ok()
-            .into_trace(Poisoned)?
-            .serialize(serializer)
+        serialize_locked::<F, P, _, S>(field.lock(), serializer)
     }
 }
 
-impl<F, T, D> DeserializeWith<Immutable<F>, Mutex<T>, D> for Lock
+impl<F, T, P, D> DeserializeWith<Immutable<F>, Mutex<T>, D> for Lock<P>
 where
     F: Deserialize<T, D>,
     D: Fallible + ?Sized,
@@ -178,33 +252,23 @@ where
     }
 }
 
-impl<F: Archive> ArchiveWith<RwLock<F>> for Lock {
+impl<F: Archive, P> ArchiveWith<RwLock<F>> for Lock<P> {
     type Archived = Immutable<F::Archived>;
-    type Resolver = F::Resolver;
+    type Resolver = LockResolver<F>;
 
     fn resolve_with(
         field: &RwLock<F>,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        let out = unsafe { out.cast_unchecked() };
-        // Unfortunately, we have to unwrap here because resolve must be
-        // infallible
-        //
-        // An alternative would be to only implement ArchiveWith for
-        // Arc<Mutex<F>>, copy a an Arc into the resolver, and hold the
-        // guard in there as well (as a reference to the internal
-        // mutex). This unfortunately will cause a deadlock if two Arcs to the
-        // same Mutex are serialized before the first is resolved. The
-        // compromise is, unfortunately, to just unwrap poison errors
-        // here and document it.
-        field.read().unwrap().resolve(resolver, out);
-    }
-}
-
-impl<F, S> SerializeWith<RwLock<F>, S> for Lock
+        resolve_locked(|| field.read(), resolver, out);
+    }
+}
+
+impl<F, P, S> SerializeWith<RwLock<F>, S> for Lock<P>
 where
     F: Serialize<S>,
+    P: PoisonPolicy<F>,
     S: Fallible + ?Sized,
     S::Error: Source,
 {
@@ -212,15 +276,11 @@ where
         field: &RwLock<F>,
         serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        field
-            .read()
-            .ok()
-            .into_trace(Poisoned)?
-            .serialize(serializer)
+        serialize_locked::<F, P, _, S>(field.read(), serializer)
     }
 }
 
-impl<F, T, D> DeserializeWith<Immutable<F>, RwLock<T>, D> for Lock
+impl<F, T, P, D> DeserializeWith<Immutable<F>, RwLock<T>, D> for Lock<P>
 where
     F: Deserialize<T, D>,
     D: Fallible + ?Sized,
@@ -233,6 +293,90 @@ where
     }
 }
 
+// AsOption
+
+impl<T: Archive> ArchiveWith<OnceLock<T>> for AsOption {
+    type Archived = ArchivedOption<T::Archived>;
+    type Resolver = Option<T::Resolver>;
+
+    fn resolve_with(
+        field: &OnceLock<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedOption::resolve_from_option(field.get(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<OnceLock<T>, S> for AsOption
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize_with(
+        field: &OnceLock<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        field
+            .get()
+            .map(|value| value.serialize(serializer))
+            .transpose()
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedOption<T::Archived>, OnceLock<T>, D>
+    for AsOption
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedOption<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<OnceLock<T>, D::Error> {
+        match field.as_ref() {
+            Some(value) => Ok(OnceLock::from(value.deserialize(deserializer)?)),
+            None => Ok(OnceLock::new()),
+        }
+    }
+}
+
+impl<T, F> ArchiveWith<LazyLock<T, F>> for AsOption
+where
+    T: Archive,
+    F: FnOnce() -> T,
+{
+    type Archived = ArchivedOption<T::Archived>;
+    type Resolver = Option<T::Resolver>;
+
+    fn resolve_with(
+        field: &LazyLock<T, F>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedOption::resolve_from_option(
+            Some(LazyLock::force(field)),
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<T, F, S> SerializeWith<LazyLock<T, F>, S> for AsOption
+where
+    T: Serialize<S>,
+    F: FnOnce() -> T,
+    S: Fallible + ?Sized,
+{
+    fn serialize_with(
+        field: &LazyLock<T, F>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(Some(LazyLock::force(field).serialize(serializer)?))
+    }
+}
+
 // AsVec
 
 impl<K: Archive, V: Archive> ArchiveWith<HashMap<K, V>> for AsVec {
@@ -344,17 +488,16 @@ where
 
 impl ArchiveWith<SystemTime> for UnixTimestamp {
     type Archived = ArchivedDuration;
-    type Resolver = ();
+    type Resolver = Duration;
 
     #[inline]
     fn resolve_with(
-        field: &SystemTime,
+        _: &SystemTime,
         resolver: Self::Resolver,
         out: Place<Self::Archived>,
     ) {
-        // We already checked the duration during serialize_with
-        let duration = field.duration_since(UNIX_EPOCH).unwrap();
-        Archive::resolve(&duration, resolver, out);
+        // The duration was computed and checked during serialize_with
+        Archive::resolve(&resolver, (), out);
     }
 }
 
@@ -367,8 +510,7 @@ where
         field: &SystemTime,
         _: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        field.duration_since(UNIX_EPOCH).into_error()?;
-        Ok(())
+        field.duration_since(UNIX_EPOCH).into_error()
     }
 }
 
@@ -422,3 +564,198 @@ where
         Ok(Cow::Owned(field.deserialize(deserializer)?))
     }
 }
+
+// InstantSince
+
+impl InstantAnchor for Instant {
+    #[inline]
+    fn instant_anchor(&self) -> Instant {
+        *self
+    }
+}
+
+impl<T: InstantAnchor, E> InstantAnchor for Strategy<T, E> {
+    #[inline]
+    fn instant_anchor(&self) -> Instant {
+        T::instant_anchor(self)
+    }
+}
+
+impl<T> InstantAnchor for Anchored<T> {
+    #[inline]
+    fn instant_anchor(&self) -> Instant {
+        self.anchor
+    }
+}
+
+impl<T: Positional> Positional for Anchored<T> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+impl<T: Writer<E>, E> Writer<E> for Anchored<T> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.inner.write(bytes)
+    }
+
+    #[inline]
+    fn overflow(&self, error: OverflowError) -> E {
+        self.inner.overflow(error)
+    }
+}
+
+unsafe impl<T: Allocator<E>, E> Allocator<E> for Anchored<T> {
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        // SAFETY: The safety requirements for `T::push_alloc()` are the same as
+        // the safety requirements for `push_alloc()`.
+        unsafe { self.inner.push_alloc(layout) }
+    }
+
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `T::pop_alloc()` are the same as
+        // the safety requirements for `pop_alloc()`.
+        unsafe { self.inner.pop_alloc(ptr, layout) }
+    }
+}
+
+impl<T: Sharing<E>, E> Sharing<E> for Anchored<T> {
+    fn get_shared_ptr(&self, address: usize) -> Option<usize> {
+        self.inner.get_shared_ptr(address)
+    }
+
+    fn add_shared_ptr(&mut self, address: usize, pos: usize) -> Result<(), E> {
+        self.inner.add_shared_ptr(address, pos)
+    }
+
+    fn get_shared_bytes(&self, bytes: &[u8]) -> Option<usize> {
+        self.inner.get_shared_bytes(bytes)
+    }
+
+    fn add_shared_bytes(&mut self, bytes: &[u8], pos: usize) -> Result<(), E> {
+        self.inner.add_shared_bytes(bytes, pos)
+    }
+
+    fn get_shared_range(&self, address: usize, len: usize) -> Option<usize> {
+        self.inner.get_shared_range(address, len)
+    }
+
+    fn add_shared_range(
+        &mut self,
+        address: usize,
+        len: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        self.inner.add_shared_range(address, len, pos)
+    }
+
+    fn shares_subtrees(&self) -> bool {
+        self.inner.shares_subtrees()
+    }
+
+    fn get_shared_subtree(&self, key: &[u8], align: usize) -> Option<usize> {
+        self.inner.get_shared_subtree(key, align)
+    }
+
+    fn add_shared_subtree(
+        &mut self,
+        key: &[u8],
+        align: usize,
+        pos: usize,
+    ) -> Result<(), E> {
+        self.inner.add_shared_subtree(key, align, pos)
+    }
+}
+
+impl<T: Pooling<E>, E> Pooling<E> for Anchored<T> {
+    fn get_shared_ptr(&mut self, address: usize) -> Option<ErasedPtr> {
+        self.inner.get_shared_ptr(address)
+    }
+
+    unsafe fn add_shared_ptr(
+        &mut self,
+        address: usize,
+        ptr: ErasedPtr,
+        drop: unsafe fn(ErasedPtr),
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `T::add_shared_ptr()` are the
+        // same as the safety requirements for `add_shared_ptr()`.
+        unsafe { self.inner.add_shared_ptr(address, ptr, drop) }
+    }
+}
+
+impl<T: Interning> Interning for Anchored<T> {
+    fn intern_str(&mut self, value: &str) -> Arc<str> {
+        self.inner.intern_str(value)
+    }
+}
+
+fn nanos_since(instant: Instant, anchor: Instant) -> Option<i64> {
+    if instant >= anchor {
+        i64::try_from((instant - anchor).as_nanos()).ok()
+    } else {
+        i64::try_from((anchor - instant).as_nanos())
+            .ok()
+            .map(|nanos| -nanos)
+    }
+}
+
+fn instant_from_nanos(anchor: Instant, nanos: i64) -> Option<Instant> {
+    let offset = Duration::from_nanos(nanos.unsigned_abs());
+    if nanos >= 0 {
+        anchor.checked_add(offset)
+    } else {
+        anchor.checked_sub(offset)
+    }
+}
+
+impl ArchiveWith<Instant> for InstantSince {
+    type Archived = ArchivedI64;
+    type Resolver = i64;
+
+    #[inline]
+    fn resolve_with(
+        _: &Instant,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        resolver.resolve((), out);
+    }
+}
+
+impl<S> SerializeWith<Instant, S> for InstantSince
+where
+    S: Fallible + InstantAnchor + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Instant,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        nanos_since(*field, serializer.instant_anchor())
+            .into_trace(InstantOutOfRange)
+    }
+}
+
+impl<D> DeserializeWith<ArchivedI64, Instant, D> for InstantSince
+where
+    D: Fallible + InstantAnchor + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedI64,
+        deserializer: &mut D,
+    ) -> Result<Instant, D::Error> {
+        instant_from_nanos(deserializer.instant_anchor(), field.to_native())
+            .into_trace(InstantOutOfRange)
+    }
+}
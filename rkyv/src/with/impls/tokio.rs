@@ -0,0 +1,188 @@
+use core::fmt;
+
+use rancor::{Fallible, ResultExt as _, Source};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::{
+    with::{ArchiveWith, DeserializeWith, Immutable, Lock, SerializeWith},
+    Archive, Deserialize, Place, Serialize,
+};
+
+/// An error resulting from serializing a `tokio` lock which is already locked.
+#[derive(Debug)]
+struct Contended;
+
+impl fmt::Display for Contended {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lock was already held during serialization")
+    }
+}
+
+impl std::error::Error for Contended {}
+
+/// Spins until `try_lock` acquires the lock again while resolving.
+///
+/// Resolve must be infallible, so the guard can't be held in the resolver:
+/// serializing two `Arc`s to the same lock would deadlock. Contention was
+/// already reported as an error during `serialize_with`, so anything holding
+/// the lock now acquired it on another thread in the meantime and will release
+/// it. Unlike `blocking_lock`, this doesn't panic inside of an async runtime.
+fn relock<G, E>(mut try_lock: impl FnMut() -> Result<G, E>) -> G {
+    loop {
+        match try_lock() {
+            Ok(guard) => return guard,
+            Err(_) => std::thread::yield_now(),
+        }
+    }
+}
+
+// Mutex
+
+impl<F: Archive, P> ArchiveWith<Mutex<F>> for Lock<P> {
+    type Archived = Immutable<F::Archived>;
+    type Resolver = F::Resolver;
+
+    fn resolve_with(
+        field: &Mutex<F>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        let out = unsafe { out.cast_unchecked() };
+        relock(|| field.try_lock()).resolve(resolver, out);
+    }
+}
+
+impl<F, P, S> SerializeWith<Mutex<F>, S> for Lock<P>
+where
+    F: Serialize<S>,
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Mutex<F>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        field
+            .try_lock()
+            .map_err(|_| Contended)
+            .into_error()?
+            .serialize(serializer)
+    }
+}
+
+impl<F, T, P, D> DeserializeWith<Immutable<F>, Mutex<T>, D> for Lock<P>
+where
+    F: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &Immutable<F>,
+        deserializer: &mut D,
+    ) -> Result<Mutex<T>, D::Error> {
+        Ok(Mutex::new(field.value().deserialize(deserializer)?))
+    }
+}
+
+// RwLock
+
+impl<F: Archive, P> ArchiveWith<RwLock<F>> for Lock<P> {
+    type Archived = Immutable<F::Archived>;
+    type Resolver = F::Resolver;
+
+    fn resolve_with(
+        field: &RwLock<F>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        let out = unsafe { out.cast_unchecked() };
+        relock(|| field.try_read()).resolve(resolver, out);
+    }
+}
+
+impl<F, P, S> SerializeWith<RwLock<F>, S> for Lock<P>
+where
+    F: Serialize<S>,
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &RwLock<F>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        field
+            .try_read()
+            .map_err(|_| Contended)
+            .into_error()?
+            .serialize(serializer)
+    }
+}
+
+impl<F, T, P, D> DeserializeWith<Immutable<F>, RwLock<T>, D> for Lock<P>
+where
+    F: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &Immutable<F>,
+        deserializer: &mut D,
+    ) -> Result<RwLock<T>, D::Error> {
+        Ok(RwLock::new(field.value().deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rancor::Error;
+    use tokio::sync::{Mutex, RwLock};
+
+    use crate::{
+        access_unchecked, deserialize, to_bytes, with::Lock, Archive,
+        Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize)]
+    #[archive(crate)]
+    struct State {
+        #[with(Lock)]
+        counter: Mutex<u32>,
+        #[with(Lock)]
+        names: RwLock<Vec<String>>,
+    }
+
+    fn state() -> State {
+        State {
+            counter: Mutex::new(42),
+            names: RwLock::new(vec!["a".to_string(), "b".to_string()]),
+        }
+    }
+
+    #[test]
+    fn roundtrip_locks() {
+        let value = state();
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedState>(&bytes) };
+        assert_eq!(*archived.counter.value(), 42);
+        assert_eq!(archived.names.value().as_slice(), ["a", "b"]);
+
+        let deserialized =
+            deserialize::<State, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized.counter.into_inner(), 42);
+        assert_eq!(deserialized.names.into_inner(), ["a", "b"]);
+    }
+
+    #[test]
+    fn contended_locks() {
+        let value = state();
+        let guard = value.counter.try_lock().unwrap();
+        assert!(to_bytes::<Error>(&value).is_err());
+        drop(guard);
+
+        let guard = value.names.try_write().unwrap();
+        assert!(to_bytes::<Error>(&value).is_err());
+        drop(guard);
+
+        // Shared readers don't block serialization.
+        let _guard = value.names.try_read().unwrap();
+        assert!(to_bytes::<Error>(&value).is_ok());
+    }
+}
@@ -1,7 +1,8 @@
 //! Wrapper type support and commonly used wrappers.
 //!
-//! Wrappers can be applied with the `#[with(...)]` attribute in the
-//! [`Archive`](macro@crate::Archive) macro.
+//! Wrappers can be applied to fields with the `#[with(...)]` attribute in the
+//! [`Archive`](macro@crate::Archive) macro, or to entire types with
+//! `#[archive(with = ...)]`.
 
 mod impls;
 
@@ -219,7 +220,8 @@ pub struct AtomicLoad<SO> {
 /// A wrapper that archives an atomic with an underlying atomic.
 ///
 /// When serializing and deserializing, the specified ordering will be used to
-/// load the value from the source atomic.
+/// load the value from the source atomic. Endian-aware atomics from `rend` can
+/// also be used, and are archived in the byte order of the archive.
 ///
 /// See [`AtomicLoad`] for a safe alternative.
 ///
@@ -251,6 +253,189 @@ pub struct AsAtomic<SO, DO> {
     _phantom: PhantomData<(SO, DO)>,
 }
 
+/// A wrapper that archives an `AtomicPtr` by boxing its pointee.
+///
+/// When serializing, the specified ordering will be used to load the pointer
+/// from the source atomic. A null pointer is archived as `None`, and any other
+/// pointer has its pointee archived as if it were boxed. When deserializing,
+/// the pointee is deserialized into a new `Box` and the resulting atomic points
+/// to it. The caller is responsible for freeing that allocation, for example
+/// with `Box::from_raw`.
+///
+/// # Safety
+///
+/// This wrapper is only safe to use when the loaded pointer is either null or
+/// points to a valid value which isn't modified or freed while serializing.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(target_has_atomic = "ptr")]
+/// use core::sync::atomic::AtomicPtr;
+///
+/// use rkyv::{
+///     with::{Acquire, AtomicPtrAsBox},
+///     Archive,
+/// };
+///
+/// # #[cfg(target_has_atomic = "ptr")]
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AtomicPtrAsBox<Acquire>)]
+///     head: AtomicPtr<u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AtomicPtrAsBox<SO> {
+    _phantom: PhantomData<SO>,
+}
+
+/// A wrapper that archives a multibyte primitive in little-endian byte order.
+///
+/// The byte order of the field is fixed regardless of the `little_endian` and
+/// `big_endian` features. This is useful for archives that must match an
+/// externally-specified layout. The archived field is a `rend` type, which can
+/// be converted to its native value with `to_native`.
+///
+/// Fields archived with this wrapper are left as-is when converting archives
+/// with [`normalize_endianness`](crate::endian::normalize_endianness).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::LittleEndian, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(LittleEndian)]
+///     a: u32,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LittleEndian;
+
+/// A wrapper that archives a multibyte primitive in big-endian byte order.
+///
+/// The byte order of the field is fixed regardless of the `little_endian` and
+/// `big_endian` features. This is useful for archives that must match an
+/// externally-specified layout, such as network protocol headers. The archived
+/// field is a `rend` type, which can be converted to its native value with
+/// `to_native`.
+///
+/// Fields archived with this wrapper are left as-is when converting archives
+/// with [`normalize_endianness`](crate::endian::normalize_endianness).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::BigEndian, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(BigEndian)]
+///     a: u32,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BigEndian;
+
+/// A wrapper that archives a 128-bit integer with 8-byte alignment.
+///
+/// Archived `u128` and `i128` are 16-byte aligned, which forces every archived
+/// type that contains them to be 16-byte aligned as well and can add padding.
+/// This wrapper archives them as an
+/// [`ArchivedAlign8U128`](crate::primitive::ArchivedAlign8U128) or
+/// [`ArchivedAlign8I128`](crate::primitive::ArchivedAlign8I128) instead, which
+/// store the integer as a pair of 64-bit words. These have the same size and
+/// bytes as archived 128-bit integers, but are at most 8-byte aligned.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::align_of;
+///
+/// use rkyv::{with::Align8, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(Align8)]
+///     id: u128,
+///     #[with(Align8)]
+///     delta: i128,
+/// }
+///
+/// assert!(align_of::<ArchivedExample>() <= 8);
+/// ```
+#[derive(Debug)]
+pub struct Align8;
+
+/// A wrapper that archives a float so that it is totally ordered.
+///
+/// This can be used with `f32` and `f64` fields, which are archived as an
+/// [`ArchivedTotalF32`](crate::primitive::ArchivedTotalF32) or
+/// [`ArchivedTotalF64`](crate::primitive::ArchivedTotalF64) respectively. These
+/// are ordered with `total_cmp` and implement `Eq`, `Ord` and `Hash`, so types
+/// which contain them can be used as keys in archived maps and sets.
+///
+/// # Example
+///
+/// ```
+/// use std::{cmp::Ordering, collections::BTreeMap, ops::ControlFlow};
+///
+/// use rkyv::{
+///     primitive::ArchivedTotalF64, rancor::Error, with::TotalOrdFloat,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, Clone, Copy)]
+/// #[archive_attr(derive(PartialEq, Eq, PartialOrd, Ord))]
+/// struct Score(#[with(TotalOrdFloat)] f64);
+///
+/// impl Ord for Score {
+///     fn cmp(&self, other: &Self) -> Ordering {
+///         self.0.total_cmp(&other.0)
+///     }
+/// }
+///
+/// impl PartialOrd for Score {
+///     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+///         Some(self.cmp(other))
+///     }
+/// }
+///
+/// impl PartialEq for Score {
+///     fn eq(&self, other: &Self) -> bool {
+///         self.cmp(other) == Ordering::Equal
+///     }
+/// }
+///
+/// impl Eq for Score {}
+///
+/// let mut scores = BTreeMap::new();
+/// scores.insert(Score(0.5), "half".to_string());
+/// scores.insert(Score(-1.0), "negative".to_string());
+/// scores.insert(Score(f64::INFINITY), "infinite".to_string());
+///
+/// let bytes = rkyv::to_bytes::<Error>(&scores).unwrap();
+/// let archived = unsafe {
+///     rkyv::access_unchecked::<rkyv::Archived<BTreeMap<Score, String>>>(
+///         &bytes,
+///     )
+/// };
+///
+/// let key = ArchivedScore(ArchivedTotalF64::from_native(0.5));
+/// assert_eq!(archived.get(&key).unwrap(), "half");
+///
+/// let mut keys = Vec::new();
+/// archived.visit(|key, _| {
+///     keys.push(key.0.to_native());
+///     ControlFlow::<()>::Continue(())
+/// });
+/// assert_eq!(keys, [-1.0, 0.5, f64::INFINITY]);
+/// ```
+#[derive(Debug)]
+pub struct TotalOrdFloat;
+
 /// A wrapper that serializes a reference inline.
 ///
 /// References serialized with `Inline` cannot be deserialized because the
@@ -314,6 +499,46 @@ pub struct Boxed;
 #[derive(Debug)]
 pub struct BoxedInline;
 
+/// A wrapper that archives a lazily-initialized cell as an optional value.
+///
+/// This can be used with `OnceCell`, and with `OnceLock` and `LazyLock` when
+/// the `std` feature is enabled. The value of the cell is archived as an
+/// [`ArchivedOption`](crate::option::ArchivedOption) which is `Some` if the
+/// cell was initialized and `None` otherwise. When deserializing, the cell is
+/// initialized with the value if there was one.
+///
+/// `LazyLock`s are always initialized when they are serialized, and can't be
+/// deserialized because their initialization function can't be archived.
+///
+/// # Example
+///
+/// ```
+/// use core::cell::OnceCell;
+///
+/// use rkyv::{
+///     rancor::Error, with::AsOption, Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example {
+///     #[with(AsOption)]
+///     cached: OnceCell<u32>,
+/// }
+///
+/// let value = Example {
+///     cached: OnceCell::from(42),
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = unsafe { rkyv::access_unchecked::<ArchivedExample>(&bytes) };
+/// assert_eq!(archived.cached.as_ref().unwrap(), &42);
+///
+/// let deserialized =
+///     rkyv::deserialize::<Example, _, Error>(archived, &mut ()).unwrap();
+/// assert_eq!(deserialized.cached.get(), Some(&42));
+/// ```
+#[derive(Debug)]
+pub struct AsOption;
+
 /// A wrapper that attempts to convert a type to and from UTF-8.
 ///
 /// Types like `OsString` and `PathBuf` aren't guaranteed to be encoded as
@@ -354,18 +579,84 @@ impl fmt::Display for InvalidStr {
 #[cfg(feature = "std")]
 impl ::std::error::Error for InvalidStr {}
 
+/// A wrapper that archives a C string as a fixed-size, NUL-terminated buffer.
+///
+/// This can be used with `[u8; N]` and `String` fields, which are archived as a
+/// `[u8; N]` containing the bytes of the string followed by a NUL terminator.
+/// The rest of the buffer is filled with zeroes. This matches the layout of a
+/// `char name[N]` field in a C struct, so archived structs can be passed to C
+/// code which expects that layout.
+///
+/// Serialization fails if a `[u8; N]` doesn't contain a NUL terminator, or if a
+/// `String` contains a NUL byte or is too long to fit in the buffer with its
+/// NUL terminator. Deserializing a `String` fails if the archived buffer
+/// doesn't contain a NUL terminator or isn't valid UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::CStrArray, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive_attr(repr(C))]
+/// struct Device {
+///     id: u32,
+///     #[with(CStrArray<16>)]
+///     name: String,
+///     #[with(CStrArray<8>)]
+///     serial: [u8; 8],
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CStrArray<const N: usize>;
+
+impl<const N: usize> CStrArray<N> {
+    /// Copies the bytes of a C string into a zero-filled buffer.
+    ///
+    /// Any bytes after the first NUL byte are not copied.
+    fn to_array(bytes: &[u8]) -> [u8; N] {
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let mut result = [0; N];
+        result[..len].copy_from_slice(&bytes[..len]);
+        result
+    }
+}
+
+#[derive(Debug)]
+enum CStrArrayError {
+    MissingNul,
+    InteriorNul,
+    TooLong { len: usize, capacity: usize },
+}
+
+impl fmt::Display for CStrArrayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingNul => write!(f, "C string is not NUL-terminated"),
+            Self::InteriorNul => write!(f, "string contains a NUL byte"),
+            Self::TooLong { len, capacity } => write!(
+                f,
+                "string of length {len} does not fit in a {capacity}-byte C \
+                 string buffer",
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for CStrArrayError {}
+
 /// A wrapper that locks a lock and serializes the value immutably.
 ///
-/// This wrapper can panic under very specific circumstances when:
+/// The type parameter chooses what happens when serializing a poisoned lock:
 ///
-/// 1. `serialize_with` is called and succeeds in locking the value to serialize
-///    it.
-/// 2. Another thread locks the value and panics, poisoning the lock
-/// 3. `resolve_with` is called and gets a poisoned value.
+/// - [`ErrorOnPoison`] (the default) fails serialization with an error.
+/// - [`IgnorePoison`] serializes the value in the lock anyway.
+/// - [`DefaultOnPoison`] serializes the default value of the locked type
+///   instead.
 ///
-/// Unfortunately, it's not possible to work around this issue. If your code
-/// absolutely must not panic under any circumstances, it's recommended that you
-/// lock your values and then serialize them while locked.
+/// If the lock is poisoned after the value is serialized but before it is
+/// resolved, the value in the lock is resolved anyway.
 ///
 /// Additionally, mutating the data protected by a mutex between the serialize
 /// and resolve steps may cause undefined behavior in the resolve step. **Uses
@@ -376,21 +667,55 @@ impl ::std::error::Error for InvalidStr {}
 /// type by default. To use this wrapper, a custom serializer with an error type
 /// satisfying `<S as Fallible>::Error: From<LockError>` must be provided.
 ///
+/// With the `tokio` feature enabled, this wrapper can also be used with
+/// `tokio::sync::Mutex` and `tokio::sync::RwLock`. These are locked without
+/// waiting, so serialization fails if they are already locked for writing.
+/// Tokio locks can't be poisoned, so the poisoning policy has no effect on
+/// them.
+///
 /// # Example
 ///
 /// ```
-/// use std::sync::Mutex;
+/// use std::sync::{Mutex, RwLock};
 ///
-/// use rkyv::{with::Lock, Archive};
+/// use rkyv::{
+///     with::{DefaultOnPoison, Lock},
+///     Archive,
+/// };
 ///
 /// #[derive(Archive)]
 /// struct Example {
 ///     #[with(Lock)]
 ///     a: Mutex<i32>,
+///     #[with(Lock<DefaultOnPoison>)]
+///     b: RwLock<Vec<i32>>,
 /// }
 /// ```
 #[derive(Debug)]
-pub struct Lock;
+pub struct Lock<P = ErrorOnPoison> {
+    _phantom: PhantomData<P>,
+}
+
+/// A poisoning policy for [`Lock`] which fails to serialize poisoned locks.
+#[derive(Debug)]
+pub struct ErrorOnPoison;
+
+/// A poisoning policy for [`Lock`] which serializes the values in poisoned
+/// locks as if they weren't poisoned.
+#[derive(Debug)]
+pub struct IgnorePoison;
+
+/// A poisoning policy for [`Lock`] which serializes the default value in place
+/// of the values in poisoned locks.
+#[derive(Debug)]
+pub struct DefaultOnPoison;
+
+/// The resolver for [`Lock`] when wrapping a `std` lock.
+#[cfg(feature = "std")]
+pub struct LockResolver<F: crate::Archive> {
+    resolver: F::Resolver,
+    default: Option<F>,
+}
 
 #[derive(Debug)]
 struct Poisoned;
@@ -404,6 +729,44 @@ impl fmt::Display for Poisoned {
 #[cfg(feature = "std")]
 impl ::std::error::Error for Poisoned {}
 
+/// A wrapper that serializes a `Cow` as an [`ArchivedCow`].
+///
+/// Borrowed values are serialized once and shared by every `Cow` that borrows
+/// them, while owned values are stored inline. This lets repeated large values
+/// reference a single copy in the archive. Deserializing always produces an
+/// owned `Cow`.
+///
+/// [`ArchivedCow`]: crate::cow::ArchivedCow
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use rkyv::{rancor::Error, with::AsCow, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example<'a> {
+///     #[with(AsCow)]
+///     a: Cow<'a, String>,
+///     #[with(AsCow)]
+///     b: Cow<'a, String>,
+/// }
+///
+/// let payload = "a large payload which is stored only once".to_string();
+/// let value = Example {
+///     a: Cow::Borrowed(&payload),
+///     b: Cow::Borrowed(&payload),
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert!(archived.a.is_borrowed());
+/// assert_eq!(archived.a.get() as *const _, archived.b.get() as *const _);
+/// ```
+#[derive(Debug)]
+pub struct AsCow;
+
 /// A wrapper that serializes a `Cow` as if it were owned.
 ///
 /// # Example
@@ -445,10 +808,118 @@ pub struct AsOwned;
 #[derive(Debug)]
 pub struct AsVec;
 
+/// A wrapper that archives strings as a sorted, front-coded string table.
+///
+/// This can be used with `Vec<String>` and `BTreeSet<String>`. Strings which
+/// share prefixes with each other, like the keys of a dictionary, take up much
+/// less space while still supporting indexing and binary search. See the
+/// [`str_table`](crate::collections::str_table) module for details of the
+/// format. A `Vec` is sorted in serializer scratch space before it is
+/// archived, so it deserializes in sorted order.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::AsFrontCoded, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     #[with(AsFrontCoded)]
+///     words: Vec<String>,
+/// }
+///
+/// let value = Example {
+///     words: vec![
+///         "compress".to_string(),
+///         "compression".to_string(),
+///         "compact".to_string(),
+///     ],
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.words.get(0).unwrap(), "compact");
+/// assert_eq!(archived.words.binary_search("compression"), Ok(2));
+/// ```
+#[derive(Debug)]
+pub struct AsFrontCoded;
+
+/// A wrapper that archives strings and vectors with their lengths stored as
+/// varints.
+///
+/// This can be used with `String` and `Vec<T>` fields, which are archived as an
+/// [`ArchivedCompactString`](crate::compact::ArchivedCompactString) and an
+/// [`ArchivedCompactVec`](crate::compact::ArchivedCompactVec). These only store
+/// a relative pointer inline, and write their length before their contents as
+/// a varint. See the [`compact`](crate::compact) module for details.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::Compact, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     #[with(Compact)]
+///     name: String,
+///     #[with(Compact)]
+///     values: Vec<u32>,
+/// }
+///
+/// let value = Example {
+///     name: "a name that doesn't fit inline".to_string(),
+///     values: vec![1, 2, 3],
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.name, "a name that doesn't fit inline");
+/// assert_eq!(archived.values.as_slice(), &[1, 2, 3]);
+/// ```
+#[derive(Debug)]
+pub struct Compact;
+
+/// A wrapper that archives a `Vec` with up to `N` elements stored inline.
+///
+/// The `Vec` is archived as an
+/// [`ArchivedSmallVec`](crate::vec::small::ArchivedSmallVec), which stores
+/// short vectors inside of itself instead of behind a relative pointer. Vectors
+/// with more than `N` elements are stored out-of-line.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::AsSmallVec, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     #[with(AsSmallVec<4>)]
+///     short: Vec<u32>,
+///     #[with(AsSmallVec<4>)]
+///     long: Vec<u32>,
+/// }
+///
+/// let value = Example {
+///     short: vec![1, 2],
+///     long: vec![1, 2, 3, 4, 5, 6],
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert!(archived.short.is_inline());
+/// assert_eq!(archived.short.as_slice(), &[1, 2]);
+/// assert!(!archived.long.is_inline());
+/// assert_eq!(archived.long.as_slice(), &[1, 2, 3, 4, 5, 6]);
+/// ```
+#[derive(Debug)]
+pub struct AsSmallVec<const N: usize>;
+
 /// A wrapper that niches some type combinations.
 ///
 /// A common type combination is `Option<Box<T>>`. By using a null pointer, the
-/// archived version can save some space on-disk.
+/// archived version can save some space on-disk. `Option<String>` and
+/// `Option<Vec<T>>` are niched the same way, and `Option`s of nonzero integers
+/// use zero to represent `None`.
 ///
 /// # Example
 ///
@@ -481,15 +952,10 @@ pub struct Niche;
 /// [`UNIX_EPOCH`](::std::time::UNIX_EPOCH).
 ///
 /// If the serialized time occurs before the UNIX epoch, serialization will
-/// panic during `resolve`. The resulting archived time will be an
+/// fail with an error. The resulting archived time will be an
 /// [`ArchivedDuration`](crate::time::ArchivedDuration) relative to the UNIX
 /// epoch.
 ///
-/// Regular serializers don't support the custom error handling needed for this
-/// type by default. To use this wrapper, a custom serializer with an error type
-/// satisfying `<S as Fallible>::Error: From<UnixTimestampError>` must be
-/// provided.
-///
 /// # Example
 ///
 /// ```
@@ -520,6 +986,123 @@ impl fmt::Display for UnixTimestampError {
 #[cfg(feature = "std")]
 impl ::std::error::Error for UnixTimestampError {}
 
+/// A wrapper that archives an [`Instant`](::std::time::Instant) relative to an
+/// anchor.
+///
+/// `Instant`s are opaque and can't be archived directly. This wrapper archives
+/// an `Instant` as a signed number of nanoseconds since an anchor `Instant`
+/// provided by the serializer, and adds it to an anchor provided by the
+/// deserializer. Serializers and deserializers provide anchors by implementing
+/// [`InstantAnchor`], and [`Anchored`] can add an anchor to any existing
+/// serializer or deserializer.
+///
+/// Deserialized instants are only as accurate as the anchors used. To preserve
+/// the relative timing between archived instants, it's enough to deserialize
+/// them all with the same anchor. Serialization and deserialization fail if an
+/// instant is too far from the anchor to be represented.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use rkyv::{
+///     rancor::Error,
+///     ser::{allocator::Arena, sharing::Share, Serializer},
+///     util::{deserialize, serialize_into, AlignedVec},
+///     with::{Anchored, InstantSince},
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Span {
+///     #[with(InstantSince)]
+///     start: Instant,
+///     #[with(InstantSince)]
+///     end: Instant,
+/// }
+///
+/// let anchor = Instant::now();
+/// let span = Span {
+///     start: anchor + Duration::from_millis(5),
+///     end: anchor + Duration::from_millis(12),
+/// };
+///
+/// let mut arena = Arena::new();
+/// let serializer = Anchored::new(
+///     Serializer::new(AlignedVec::<16>::new(), arena.acquire(), Share::new()),
+///     anchor,
+/// );
+/// let bytes = serialize_into::<_, Error>(&span, serializer)
+///     .unwrap()
+///     .into_inner()
+///     .into_writer();
+///
+/// let archived = unsafe { rkyv::access_unchecked::<ArchivedSpan>(&bytes) };
+/// assert_eq!(archived.start, 5_000_000);
+/// assert_eq!(archived.end, 12_000_000);
+///
+/// let mut later = Instant::now();
+/// let deserialized =
+///     deserialize::<Span, _, Error>(archived, &mut later).unwrap();
+/// assert_eq!(deserialized.start, later + Duration::from_millis(5));
+/// assert_eq!(deserialized.end - deserialized.start, span.end - span.start);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct InstantSince;
+
+/// A serializer or deserializer which provides an anchor for archiving
+/// [`Instant`](::std::time::Instant)s with [`InstantSince`].
+///
+/// An `Instant` can be used directly as a deserializer which only provides an
+/// anchor.
+#[cfg(feature = "std")]
+pub trait InstantAnchor {
+    /// Returns the anchor instant.
+    fn instant_anchor(&self) -> ::std::time::Instant;
+}
+
+/// An adapter which adds an [`InstantAnchor`] to a serializer or deserializer.
+///
+/// All of the serializer and deserializer traits implemented by the inner value
+/// are also implemented by `Anchored`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Anchored<T> {
+    /// The inner serializer or deserializer.
+    pub inner: T,
+    /// The anchor instant.
+    pub anchor: ::std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl<T> Anchored<T> {
+    /// Wraps a serializer or deserializer with the given anchor.
+    pub fn new(inner: T, anchor: ::std::time::Instant) -> Self {
+        Self { inner, anchor }
+    }
+
+    /// Consumes the adapter and returns the inner serializer or deserializer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct InstantOutOfRange;
+
+#[cfg(feature = "std")]
+impl fmt::Display for InstantOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "instant is too far from the anchor to be represented")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for InstantOutOfRange {}
+
 /// A wrapper that allows serialize-unsafe types to be serialized.
 ///
 /// Types like `Cell` and `UnsafeCell` may contain serializable types, but have
@@ -527,7 +1110,8 @@ impl ::std::error::Error for UnixTimestampError {}
 /// serialize, but only under conditions that rkyv is unable to guarantee.
 ///
 /// This wrapper enables serializing these types, and places the burden of
-/// verifying that their access semantics are used safely on the user.
+/// verifying that their access semantics are used safely on the user. `Cell`s
+/// of `Copy` types and `RefCell`s can be archived safely without this wrapper.
 ///
 /// # Safety
 ///
@@ -572,6 +1156,227 @@ pub struct Unsafe;
 #[derive(Debug)]
 pub struct Skip;
 
+/// A wrapper that shares the serialized bytes of values with identical
+/// contents.
+///
+/// This can be used with `String`, `Box<str>`, and `Box<[u8]>`. The archived
+/// type is the same as without the wrapper, but multiple fields with the same
+/// contents will point to a single copy of the bytes. Bytes are only shared if
+/// the [`Sharing`](crate::ser::Sharing) implementation of the serializer
+/// supports content deduplication, like a [`Share`] created with
+/// [`with_content_dedup`].
+/// Strings short enough to be stored inline are never shared.
+///
+/// It can also be used with `Box<T>` and `Vec<T>` to share whole subtrees,
+/// like repeated branches of a syntax tree. A boxed value or the elements of a
+/// vector are shared if their archived bytes and the positions of everything
+/// they point to are identical to those of a previously-serialized subtree.
+/// Subtrees are only shared if the serializer supports subtree deduplication,
+/// like a [`Share`] created with [`with_subtree_dedup`], and the archived type
+/// implements [`Relocate`](crate::relocate::Relocate) (see
+/// `#[archive(relocate)]`). Since the dependencies of a subtree must already be
+/// shared for it to be identical to another, the pointer fields inside shared
+/// subtrees should also use `Dedup`. Each value is only serialized once, so
+/// other dependencies of a subtree that turns out to be identical to a previous
+/// one are left unused in the archive.
+///
+/// Validation requires that every byte of an archive is claimed by at most one
+/// pointer, so archives with shared bytes can't be validated. Sharing is only
+/// enabled by the `unsafe` [`with_content_dedup`] and [`with_subtree_dedup`]
+/// constructors, and the resulting archives must be accessed with
+/// [`access_unchecked`](crate::access_unchecked) instead. Without a sharing
+/// serializer, `Dedup` writes every value separately and the archive validates
+/// as usual.
+///
+/// [`Share`]: crate::ser::sharing::Share
+/// [`with_content_dedup`]: crate::ser::sharing::Share::with_content_dedup
+/// [`with_subtree_dedup`]: crate::ser::sharing::Share::with_subtree_dedup
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     ser::{sharing::Share, Serializer},
+///     util::{serialize_into, with_arena, AlignedVec},
+///     with::Dedup,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[with(Dedup)]
+///     a: String,
+///     #[with(Dedup)]
+///     b: String,
+/// }
+///
+/// let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+/// let value = Example {
+///     a: id.to_string(),
+///     b: id.to_string(),
+/// };
+///
+/// let bytes = with_arena(|arena| {
+///     let serializer = Serializer::new(
+///         AlignedVec::<16>::new(),
+///         arena.acquire(),
+///         // SAFETY: The archive is never accessed.
+///         unsafe { Share::with_content_dedup() },
+///     );
+///     serialize_into::<_, Error>(&value, serializer)
+///         .map(|serializer| serializer.into_writer())
+/// })
+/// .unwrap();
+///
+/// // The id is only written once.
+/// assert!(bytes.len() < 2 * id.len());
+/// ```
+#[derive(Debug)]
+pub struct Dedup;
+
+/// A wrapper that shares the serialized bytes of byte slices and strings which
+/// lie within previously-serialized ones.
+///
+/// This can be used with `&[u8]`, `&str`, `Cow<[u8]>`, and `Cow<str>`. The
+/// field is archived as an [`ArchivedVec<u8>`] or [`ArchivedString`]. If the
+/// field borrows from the same memory as a slice which has already been
+/// serialized, it points into the serialized bytes of that slice instead of
+/// being written again. This is useful for rope- or window-style data where
+/// many views overlap. Bytes are only shared if the
+/// [`Sharing`](crate::ser::Sharing) implementation of the serializer supports
+/// subslice sharing, like a [`Share`] created with [`with_subslice_sharing`].
+/// Strings short enough to be stored inline are never shared.
+///
+/// Slices are only shared with slices which were serialized before them, so
+/// the widest views should be serialized first. Like [`Dedup`], archives with
+/// shared bytes can't be validated, so sharing is only enabled by the `unsafe`
+/// [`with_subslice_sharing`] constructor and the resulting archives must be
+/// accessed with [`access_unchecked`](crate::access_unchecked) instead.
+/// Without a sharing serializer, `Subslice` writes every slice separately and
+/// the archive validates as usual.
+///
+/// [`ArchivedVec<u8>`]: crate::vec::ArchivedVec
+/// [`ArchivedString`]: crate::string::ArchivedString
+/// [`Share`]: crate::ser::sharing::Share
+/// [`with_subslice_sharing`]: crate::ser::sharing::Share::with_subslice_sharing
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access_unchecked,
+///     rancor::Error,
+///     ser::{sharing::Share, Serializer},
+///     util::{serialize_into, with_arena, AlignedVec},
+///     with::Subslice,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example<'a> {
+///     #[with(Subslice)]
+///     text: &'a str,
+///     #[with(Subslice)]
+///     a: &'a [u8],
+///     #[with(Subslice)]
+///     b: &'a [u8],
+/// }
+///
+/// let text = "the quick brown fox jumps over the lazy dog";
+/// let value = Example {
+///     text,
+///     a: &text.as_bytes()[4..19],
+///     b: &text.as_bytes()[10..34],
+/// };
+///
+/// let bytes = with_arena(|arena| {
+///     let serializer = Serializer::new(
+///         AlignedVec::<16>::new(),
+///         arena.acquire(),
+///         // SAFETY: The archive is only accessed with `access_unchecked`.
+///         unsafe { Share::with_subslice_sharing() },
+///     );
+///     serialize_into::<_, Error>(&value, serializer)
+///         .map(|serializer| serializer.into_writer())
+/// })
+/// .unwrap();
+///
+/// // The text is only written once.
+/// assert!(bytes.len() < 2 * text.len());
+/// let archived = unsafe { access_unchecked::<ArchivedExample>(&bytes) };
+/// assert_eq!(archived.a.as_slice(), b"quick brown fox");
+/// assert_eq!(archived.b.as_slice(), b"brown fox jumps over the");
+/// ```
+#[derive(Debug)]
+pub struct Subslice;
+
+/// A wrapper that archives an `Arc<str>` as a string and interns it when
+/// deserializing.
+///
+/// The field is archived as an [`ArchivedString`] instead of a shared pointer.
+/// When deserialized with an [`Interning`] deserializer like
+/// [`Pool::with_string_interning`], equal strings share a single allocation.
+/// This is useful for archives with many repeated strings, like map keys.
+///
+/// [`ArchivedString`]: crate::string::ArchivedString
+/// [`Interning`]: crate::de::Interning
+/// [`Pool::with_string_interning`]: crate::de::Pool::with_string_interning
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv::{with::Intern, Archive, Deserialize, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example {
+///     #[with(Intern)]
+///     name: Arc<str>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Intern;
+
 /// A wrapper that clones the contents of `Arc` and `Rc` pointers.
 #[derive(Debug)]
 pub struct Cloned;
+
+/// A wrapper that serializes a private copy of the value behind an `Arc` or
+/// `Rc` pointer.
+///
+/// Unlike [`Cloned`], the field is still archived as a shared pointer. However,
+/// the pointee is serialized again instead of being shared with other pointers
+/// to the same value. When deserialized, the field gets its own allocation and
+/// does not alias any other deserialized pointers.
+///
+/// # Example
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use rkyv::{rancor::Error, with::Unshare, Archive, Deserialize, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// #[archive(check_bytes)]
+/// struct Example {
+///     shared: Rc<String>,
+///     #[with(Unshare)]
+///     private: Rc<String>,
+/// }
+///
+/// let name = Rc::new("rkyv".to_string());
+/// let value = Example {
+///     shared: name.clone(),
+///     private: name,
+/// };
+///
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let deserialized = rkyv::from_bytes::<Example, Error>(&bytes).unwrap();
+///
+/// assert_eq!(*deserialized.private, "rkyv");
+/// assert!(!Rc::ptr_eq(&deserialized.shared, &deserialized.private));
+/// ```
+#[derive(Debug)]
+pub struct Unshare;
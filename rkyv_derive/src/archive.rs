@@ -5,10 +5,16 @@ mod r#struct;
 use core::fmt::Display;
 
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{Data, DeriveInput, Error, Field, Ident, Meta};
+use quote::{quote, ToTokens};
+use syn::{
+    parse_quote, Data, DeriveInput, Error, Field, Ident, Meta, Type,
+    WhereClause,
+};
 
-use crate::attributes::Attributes;
+use crate::{
+    attributes::{parse_default, Attributes, LayoutAssertion},
+    util::is_recursive,
+};
 
 pub fn derive(input: &mut DeriveInput) -> Result<TokenStream, Error> {
     let attributes = Attributes::parse(input)?;
@@ -16,19 +22,29 @@ pub fn derive(input: &mut DeriveInput) -> Result<TokenStream, Error> {
 }
 
 fn field_archive_attrs(
+    printing: &printing::Printing,
+    input: &DeriveInput,
     field: &Field,
-) -> impl '_ + Iterator<Item = &TokenStream> {
-    field.attrs.iter().filter_map(|attr| {
-        if let Meta::List(list) = &attr.meta {
-            if list.path.is_ident("archive_attr") {
-                Some(&list.tokens)
+) -> Vec<TokenStream> {
+    let mut attrs = field
+        .attrs
+        .iter()
+        .filter_map(|attr| {
+            if let Meta::List(list) = &attr.meta {
+                if list.path.is_ident("archive_attr") {
+                    Some(list.tokens.clone())
+                } else {
+                    None
+                }
             } else {
                 None
             }
-        } else {
-            None
-        }
-    })
+        })
+        .collect::<Vec<_>>();
+    if printing.omit_recursive_check_bytes && is_recursive(field, input) {
+        attrs.push(quote! { omit_bounds });
+    }
+    attrs
 }
 
 fn archived_doc(name: &Ident) -> String {
@@ -73,6 +89,44 @@ fn enum_resolver_field_doc(
     )
 }
 
+/// Checks that `#[rkyv(default)]` is only used on fields of length-prefixed
+/// structs.
+fn check_field_defaults(
+    input: &DeriveInput,
+    attributes: &Attributes,
+) -> Result<(), Error> {
+    let (variants, fields): (Vec<_>, Vec<_>) = match input.data {
+        Data::Struct(ref data) => (Vec::new(), data.fields.iter().collect()),
+        Data::Enum(ref data) => (
+            data.variants.iter().collect(),
+            data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        ),
+        Data::Union(_) => return Ok(()),
+    };
+
+    for variant in variants {
+        if let Some((path, _)) = parse_default(&variant.attrs)? {
+            return Err(Error::new_spanned(
+                path,
+                "default can only be used on fields",
+            ));
+        }
+    }
+    if attributes.length_prefixed.is_none() {
+        for field in fields {
+            if let Some((path, _)) = parse_default(&field.attrs)? {
+                return Err(Error::new_spanned(
+                    path,
+                    "default can only be used on fields of length_prefixed \
+                     structs",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn derive_archive_impl(
     input: &mut DeriveInput,
     attributes: &Attributes,
@@ -84,6 +138,11 @@ fn derive_archive_impl(
         }
     }
 
+    if let Some(ref with) = attributes.with {
+        return derive_archive_with_impl(input, attributes, with);
+    }
+
+    check_field_defaults(input, attributes)?;
     let printing = printing::Printing::new(input, attributes)?;
 
     let (archive_types, archive_impls) = match input.data {
@@ -97,11 +156,19 @@ fn derive_archive_impl(
         }
     };
 
+    let layout_assertions = attributes
+        .assert_layout
+        .as_ref()
+        .map(|layout| generate_layout_assertions(input, layout, &printing))
+        .transpose()?;
+
     let rkyv_path = &printing.rkyv_path;
 
     Ok(quote! {
         #archive_types
 
+        #layout_assertions
+
         #[automatically_derived]
         const _: () = {
             use core::marker::PhantomData;
@@ -111,3 +178,166 @@ fn derive_archive_impl(
         };
     })
 }
+
+/// Implements `Archive` for a type which is archived through the wrapper given
+/// with `#[archive(with = ...)]`.
+fn derive_archive_with_impl(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    with: &Type,
+) -> Result<TokenStream, Error> {
+    fn reject<T: ToTokens>(value: Option<&T>, name: &str) -> Result<(), Error> {
+        match value {
+            Some(value) => Err(Error::new_spanned(
+                value,
+                format!(
+                    "{} may not be used with with = ... because no type is \
+                     generated",
+                    name,
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    reject(attributes.archived.as_ref(), "archived = ...")?;
+    reject(attributes.resolver.as_ref(), "resolver = ...")?;
+    reject(attributes.archive_as.as_ref(), "as = \"...\"")?;
+    reject(attributes.repr.as_ref(), "repr(...)")?;
+    reject(attributes.compares.as_ref(), "compare(...)")?;
+    reject(attributes.check_bytes.as_ref(), "check_bytes")?;
+    reject(attributes.swap_endian.as_ref(), "swap_endian")?;
+    reject(attributes.relocate.as_ref(), "relocate")?;
+    reject(attributes.getters.as_ref(), "getters")?;
+    reject(attributes.setters.as_ref(), "setters")?;
+    reject(attributes.derive_serde.as_ref(), "derive_serde")?;
+    reject(attributes.length_prefixed.as_ref(), "length_prefixed")?;
+    reject(attributes.schema.as_ref(), "schema")?;
+    reject(attributes.splice.as_ref(), "splice")?;
+    reject(
+        attributes.assert_layout.as_ref().map(|layout| &layout.path),
+        "assert_layout(...)",
+    )?;
+    reject(attributes.attrs.first(), "archive_attr(...)")?;
+
+    let rkyv_path = attributes.crate_path();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let mut where_clause = where_clause.unwrap().clone();
+    where_clause.predicates.push(parse_quote! {
+        #with: #rkyv_path::with::ArchiveWith<#name #ty_generics>
+    });
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::Archive for #name #ty_generics
+        #where_clause
+        {
+            type Archived = <
+                #with as #rkyv_path::with::ArchiveWith<Self>
+            >::Archived;
+            type Resolver = <
+                #with as #rkyv_path::with::ArchiveWith<Self>
+            >::Resolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: #rkyv_path::Place<Self::Archived>,
+            ) {
+                <#with as #rkyv_path::with::ArchiveWith<Self>>::resolve_with(
+                    self, resolver, out,
+                );
+            }
+        }
+    })
+}
+
+/// Implements `SerializeArchived` and `serde::Serialize` for the archived type
+/// with the given body for `serialize_archived`.
+fn serde_impls(
+    input: &DeriveInput,
+    printing: &printing::Printing,
+    where_clause: &WhereClause,
+    body: TokenStream,
+) -> TokenStream {
+    let rkyv_path = &printing.rkyv_path;
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ..) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #rkyv_path::serde::SerializeArchived
+            for #archived_type
+        #where_clause
+        {
+            fn serialize_archived<__S: #rkyv_path::serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                #body
+            }
+        }
+
+        impl #impl_generics #rkyv_path::serde::Serialize for #archived_type
+        #where_clause
+        {
+            fn serialize<__S: #rkyv_path::serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                #rkyv_path::serde::SerializeArchived::serialize_archived(
+                    self,
+                    serializer,
+                )
+            }
+        }
+    }
+}
+
+fn generate_layout_assertions(
+    input: &DeriveInput,
+    layout: &LayoutAssertion,
+    printing: &printing::Printing,
+) -> Result<TokenStream, Error> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &layout.path,
+            "assert_layout can not be used with generic types",
+        ));
+    }
+
+    let archived_type = &printing.archived_type;
+
+    let size_assertion = layout.size.as_ref().map(|size| {
+        let message = format!(
+            "the size of the archived `{}` does not match `assert_layout`",
+            input.ident,
+        );
+        quote! {
+            assert!(
+                ::core::mem::size_of::<#archived_type>() == #size,
+                #message,
+            );
+        }
+    });
+    let align_assertion = layout.align.as_ref().map(|align| {
+        let message = format!(
+            "the alignment of the archived `{}` does not match `assert_layout`",
+            input.ident,
+        );
+        quote! {
+            assert!(
+                ::core::mem::align_of::<#archived_type>() == #align,
+                #message,
+            );
+        }
+    });
+
+    Ok(quote! {
+        const _: () = {
+            #size_assertion
+            #align_assertion
+        };
+    })
+}
@@ -1,20 +1,21 @@
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
 use syn::{
     parse_quote, spanned::Spanned as _, Data, DataEnum, DeriveInput, Error,
-    Fields, Ident,
+    Expr, ExprLit, Field, Fields, FieldsNamed, Ident, Index, Lit, Member, Type,
 };
 
 use crate::{
     archive::{
         archived_doc, enum_field_doc, enum_resolver_field_doc,
         field_archive_attrs, printing::Printing, resolver_doc,
-        resolver_variant_doc, variant_doc,
+        resolver_variant_doc, serde_impls, variant_doc,
     },
     attributes::Attributes,
     util::{
-        archive_bound, archived, is_not_omitted, members_starting_at, resolve,
-        resolver, strip_raw,
+        archive_bound, archived, archived_field_name, archived_member,
+        archived_variant, bound_fields, has_fixed_endianness,
+        max_serialized_size, members_starting_at, resolve, resolver, strip_raw,
     },
 };
 
@@ -28,27 +29,35 @@ pub fn impl_enum(
         _ => unreachable!(),
     };
 
-    if data.variants.len() > 256 {
+    let tag_repr = tag_repr(input, attributes, data)?;
+    let discriminants = tag_discriminants(printing, data, &tag_repr);
+
+    if let Some(ref path) = attributes.getters {
+        return Err(Error::new_spanned(
+            path,
+            "getters can only be generated for structs with named fields",
+        ));
+    }
+    if let Some(ref path) = attributes.setters {
+        return Err(Error::new_spanned(
+            path,
+            "setters can only be generated for structs with named fields",
+        ));
+    }
+    if let Some(ref path) = attributes.splice {
         return Err(Error::new_spanned(
-            &input.ident,
-            "enums with more than 256 variants cannot derive Archive",
+            path,
+            "splice can only be implemented for structs",
         ));
     }
 
     let rkyv_path = &printing.rkyv_path;
 
-    let where_clause = input.generics.make_where_clause();
-
-    for field in data
-        .variants
-        .iter()
-        .flat_map(|v| v.fields.iter())
-        .filter(is_not_omitted)
-    {
-        where_clause
-            .predicates
-            .push(archive_bound(rkyv_path, field)?);
-    }
+    let bounds =
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
+            .map(|field| archive_bound(rkyv_path, field))
+            .collect::<Result<Vec<_>, Error>>()?;
+    input.generics.make_where_clause().predicates.extend(bounds);
 
     let (impl_generics, ty_generics, where_clause) =
         input.generics.split_for_impl();
@@ -57,20 +66,22 @@ pub fn impl_enum(
     let archived_def = attributes
         .archive_as
         .is_none()
-        .then(|| generate_archived_def(input, printing, data))
+        .then(|| {
+            generate_archived_def(
+                input,
+                printing,
+                data,
+                &tag_repr,
+                &discriminants,
+            )
+        })
         .transpose()?;
 
     let resolver_def = generate_resolver_def(input, printing, data)?;
     let resolve_arms = generate_resolve_arms(input, printing, data)?;
 
-    let archived_variant_tags = data.variants.iter().map(|v| {
-        let variant = &v.ident;
-        let discriminant = v
-            .discriminant
-            .as_ref()
-            .map(|(eq, expr)| quote! { #eq #expr });
-        quote! { #variant #discriminant }
-    });
+    let archived_tag_def =
+        generate_archived_tag_def(printing, data, &tag_repr, &discriminants);
 
     let archived_variant_structs =
         generate_variant_structs(input, printing, data)?;
@@ -89,12 +100,49 @@ pub fn impl_enum(
                 return Err(Error::new_spanned(
                     compare,
                     "unrecognized compare argument, supported compares are \
-                     PartialEq (PartialOrd is not supported for enums)",
+                     PartialEq and PartialOrd (Hash is not supported for \
+                     enums)",
                 ));
             }
         }
     }
 
+    let swap_endian_impl = attributes
+        .swap_endian
+        .is_some()
+        .then(|| generate_swap_endian_impl(input, data, printing, &tag_repr))
+        .transpose()?;
+
+    let relocate_impl = attributes
+        .relocate
+        .is_some()
+        .then(|| generate_relocate_impl(input, data, printing))
+        .transpose()?;
+
+    let serde_impls = attributes
+        .derive_serde
+        .is_some()
+        .then(|| generate_serde_impls(input, data, printing))
+        .transpose()?;
+
+    let schema_impl = attributes
+        .schema
+        .is_some()
+        .then(|| generate_schema_impl(input, data, printing, &tag_repr))
+        .transpose()?;
+
+    let walk_impl = attributes
+        .schema
+        .is_some()
+        .then(|| generate_walk_impl(input, data, printing))
+        .transpose()?;
+
+    let max_serialized_size = max_serialized_size(
+        rkyv_path,
+        input,
+        data.variants.iter().flat_map(|v| &v.fields),
+    )?;
+
     let name = &input.ident;
     let archived_type = &printing.archived_type;
     let resolver_name = &printing.resolver_name;
@@ -105,11 +153,7 @@ pub fn impl_enum(
             #resolver_def
         },
         quote! {
-            #[derive(PartialEq, PartialOrd)]
-            #[repr(u8)]
-            enum ArchivedTag {
-                #(#archived_variant_tags,)*
-            }
+            #archived_tag_def
 
             #(#archived_variant_structs)*
 
@@ -117,6 +161,8 @@ pub fn impl_enum(
                 type Archived = #archived_type;
                 type Resolver = #resolver_name #ty_generics;
 
+                #max_serialized_size
+
                 // Some resolvers will be (), this allow is to prevent clippy
                 // from complaining
                 #[allow(clippy::unit_arg)]
@@ -133,14 +179,186 @@ pub fn impl_enum(
 
             #partial_eq_impl
             #partial_ord_impl
+            #swap_endian_impl
+            #relocate_impl
+            #serde_impls
+            #schema_impl
+            #walk_impl
         },
     ))
 }
 
+/// Returns the primitive type used for the tag of the archived enum.
+fn tag_repr(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    data: &DataEnum,
+) -> Result<Ident, Error> {
+    let max_tag = match (max_discriminant(data), &attributes.repr) {
+        (Ok(max), _) => max,
+        // The declared repr is used as-is when the discriminants can't be
+        // evaluated here; rustc checks that they fit.
+        (Err(_), Some(repr)) => return Ok(repr.clone()),
+        (Err(expr), None) => {
+            return Err(Error::new_spanned(
+                expr,
+                "tag narrowing requires non-negative integer literal \
+                 discriminants; add `#[archive(repr(...))]` to use other \
+                 discriminants",
+            ))
+        }
+    };
+    let required = match max_tag {
+        0..=0xff => "u8",
+        0x100..=0xffff => "u16",
+        _ => "u32",
+    };
+    let width = |repr: &str| match repr {
+        "u8" => 1,
+        "u16" => 2,
+        _ => 4,
+    };
+
+    match attributes.repr {
+        Some(ref repr) => {
+            if width(&repr.to_string()) < width(required) {
+                return Err(Error::new_spanned(
+                    repr,
+                    format!(
+                        "{} has too many variants or too large of a \
+                         discriminant for a `{}` tag",
+                        input.ident, repr,
+                    ),
+                ));
+            }
+            Ok(repr.clone())
+        }
+        None => Ok(Ident::new(required, Span::call_site())),
+    }
+}
+
+/// Returns the largest discriminant of the enum, or the first explicit
+/// discriminant which is not a non-negative integer literal.
+fn max_discriminant(data: &DataEnum) -> Result<u64, &Expr> {
+    let mut next = 0u64;
+    let mut max = 0u64;
+    for v in data.variants.iter() {
+        let value = match &v.discriminant {
+            Some((
+                _,
+                expr @ Expr::Lit(ExprLit {
+                    lit: Lit::Int(int), ..
+                }),
+            )) => int.base10_parse::<u64>().map_err(|_| expr)?,
+            Some((_, expr)) => return Err(expr),
+            None => next,
+        };
+        max = max.max(value);
+        next = value.saturating_add(1);
+    }
+    Ok(max)
+}
+
+/// Returns the discriminant of each variant of the archived enum.
+///
+/// Multibyte tags are stored in the byte order of the archive, so each
+/// discriminant is converted to its native representation.
+fn tag_discriminants(
+    printing: &Printing,
+    data: &DataEnum,
+    tag_repr: &Ident,
+) -> Vec<Option<TokenStream>> {
+    if tag_repr == "u8" {
+        return data
+            .variants
+            .iter()
+            .map(|v| {
+                v.discriminant
+                    .as_ref()
+                    .map(|(eq, expr)| quote! { #eq #expr })
+            })
+            .collect();
+    }
+
+    let rkyv_path = &printing.rkyv_path;
+    let convert = format_ident!("enum_tag_{}", tag_repr);
+
+    let mut base = None;
+    let mut offset = 0usize;
+    data.variants
+        .iter()
+        .map(|v| {
+            if let Some((_, expr)) = &v.discriminant {
+                base = Some(expr);
+                offset = 0;
+            }
+            let offset_lit = Literal::usize_unsuffixed(offset);
+            let value = match base {
+                Some(expr) => quote! { (#expr) + #offset_lit },
+                None => quote! { #offset_lit },
+            };
+            offset += 1;
+            Some(quote! { = #rkyv_path::primitive::#convert(#value) })
+        })
+        .collect()
+}
+
+fn generate_archived_tag_def(
+    printing: &Printing,
+    data: &DataEnum,
+    tag_repr: &Ident,
+    discriminants: &[Option<TokenStream>],
+) -> TokenStream {
+    let archived_variant_tags =
+        data.variants
+            .iter()
+            .zip(discriminants)
+            .map(|(v, discriminant)| {
+                let variant = &v.ident;
+                quote! { #variant #discriminant }
+            });
+
+    if tag_repr == "u8" {
+        return quote! {
+            #[derive(PartialEq, PartialOrd)]
+            #[repr(u8)]
+            enum ArchivedTag {
+                #(#archived_variant_tags,)*
+            }
+        };
+    }
+
+    // The discriminants of multibyte tags are in the byte order of the
+    // archive, so they have to be converted back before they are compared.
+    let rkyv_path = &printing.rkyv_path;
+    let convert = format_ident!("enum_tag_{}", tag_repr);
+    quote! {
+        #[derive(Clone, Copy, PartialEq)]
+        #[repr(#tag_repr)]
+        enum ArchivedTag {
+            #(#archived_variant_tags,)*
+        }
+
+        impl PartialOrd for ArchivedTag {
+            fn partial_cmp(
+                &self,
+                other: &Self,
+            ) -> Option<::core::cmp::Ordering> {
+                let this = #rkyv_path::primitive::#convert(*self as #tag_repr);
+                let other =
+                    #rkyv_path::primitive::#convert(*other as #tag_repr);
+                this.partial_cmp(&other)
+            }
+        }
+    }
+}
+
 fn generate_archived_def(
     input: &DeriveInput,
     printing: &Printing,
     data: &DataEnum,
+    tag_repr: &Ident,
+    discriminants: &[Option<TokenStream>],
 ) -> Result<TokenStream, Error> {
     let name = &input.ident;
     let rkyv_path = &printing.rkyv_path;
@@ -148,34 +366,33 @@ fn generate_archived_def(
     let archived_variants = data
         .variants
         .iter()
-        .map(|v| {
+        .zip(discriminants.iter())
+        .map(|(v, discriminant)| {
             let variant = &v.ident;
-            let discriminant = v
-                .discriminant
-                .as_ref()
-                .map(|(eq, expr)| quote! { #eq #expr });
+            let archived_variant = archived_variant(v)?;
 
             let variant_doc = variant_doc(name, variant);
 
             match v.fields {
                 Fields::Named(ref fields) => {
-                    let fields = fields
-                        .named
-                        .iter()
+                    let fields = packed_fields(printing, fields)
+                        .into_iter()
                         .map(|f| {
                             let field_name = f.ident.as_ref();
+                            let archived_name = archived_field_name(f)?;
                             let vis = &f.vis;
                             let field_doc = enum_field_doc(
                                 name,
                                 variant,
                                 field_name.unwrap(),
                             );
-                            let archive_attrs = field_archive_attrs(f);
+                            let archive_attrs =
+                                field_archive_attrs(printing, input, f);
                             let archived = archived(rkyv_path, f)?;
                             Ok(quote! {
                                 #[doc = #field_doc]
                                 #(#[#archive_attrs])*
-                                #vis #field_name: #archived
+                                #vis #archived_name: #archived
                             })
                         })
                         .collect::<Result<Vec<_>, Error>>()?;
@@ -183,7 +400,7 @@ fn generate_archived_def(
                     Ok(quote! {
                         #[doc = #variant_doc]
                         #[allow(dead_code)]
-                        #variant {
+                        #archived_variant {
                             #(#fields,)*
                         } #discriminant
                     })
@@ -196,7 +413,8 @@ fn generate_archived_def(
                         .map(|(i, f)| {
                             let vis = &f.vis;
                             let field_doc = enum_field_doc(name, variant, &i);
-                            let archive_attrs = field_archive_attrs(f);
+                            let archive_attrs =
+                                field_archive_attrs(printing, input, f);
                             let archived = archived(rkyv_path, f)?;
                             Ok(quote! {
                                 #[doc = #field_doc]
@@ -209,13 +427,13 @@ fn generate_archived_def(
                     Ok(quote! {
                         #[doc = #variant_doc]
                         #[allow(dead_code)]
-                        #variant(#(#fields,)*) #discriminant
+                        #archived_variant(#(#fields,)*) #discriminant
                     })
                 }
                 Fields::Unit => Ok(quote! {
                     #[doc = #variant_doc]
                     #[allow(dead_code)]
-                    #variant #discriminant
+                    #archived_variant #discriminant
                 }),
             }
         })
@@ -234,7 +452,7 @@ fn generate_archived_def(
         #[automatically_derived]
         #[doc = #archived_doc]
         #(#archive_attrs)*
-        #[repr(u8)]
+        #[repr(#tag_repr)]
         #vis enum #archived_name #generics #where_clause {
             #(#archived_variants,)*
         }
@@ -248,6 +466,56 @@ fn generate_archived_def(
     })
 }
 
+/// Returns the fields of a variant in the order they're archived in.
+///
+/// With `#[archive(pack)]`, the archived fields are ordered from smallest to
+/// largest alignment so that they pack in after the tag with as little padding
+/// as possible. Fields whose alignment can't be determined from their type are
+/// placed last. Otherwise, fields are kept in declaration order.
+fn packed_fields<'a>(
+    printing: &Printing,
+    fields: &'a FieldsNamed,
+) -> Vec<&'a Field> {
+    let mut fields = fields.named.iter().collect::<Vec<_>>();
+    if printing.pack_variant_fields {
+        fields.sort_by_key(|f| {
+            let has_with = f.attrs.iter().any(|a| a.path().is_ident("with"));
+            if has_with { None } else { align_hint(&f.ty) }
+                .unwrap_or(usize::MAX)
+        });
+    }
+    fields
+}
+
+/// Returns the alignment of the archived version of the given type if it's a
+/// primitive or an array of primitives.
+///
+/// Types are matched by name only, so a type which shadows a primitive name
+/// gets that primitive's alignment. Sorting is stable, so a wrong hint can only
+/// add padding; the archived layout is still computed by the compiler.
+fn align_hint(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Array(array) => align_hint(&array.elem),
+        Type::Group(group) => align_hint(&group.elem),
+        Type::Paren(paren) => align_hint(&paren.elem),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Some(1),
+        Type::Path(path) if path.qself.is_none() => {
+            let ident = path.path.get_ident()?.to_string();
+            let align = match ident.as_str() {
+                "bool" | "u8" | "i8" | "NonZeroU8" | "NonZeroI8" => 1,
+                "u16" | "i16" | "NonZeroU16" | "NonZeroI16" => 2,
+                "u32" | "i32" | "f32" | "char" | "NonZeroU32"
+                | "NonZeroI32" => 4,
+                "u64" | "i64" | "f64" | "NonZeroU64" | "NonZeroI64" => 8,
+                "u128" | "i128" | "NonZeroU128" | "NonZeroI128" => 16,
+                _ => return None,
+            };
+            Some(align)
+        }
+        _ => None,
+    }
+}
+
 fn generate_resolver_def(
     input: &DeriveInput,
     printing: &Printing,
@@ -261,6 +529,7 @@ fn generate_resolver_def(
         .iter()
         .map(|v| {
             let variant = &v.ident;
+            let archived_variant = archived_variant(v)?;
             let variant_doc = resolver_variant_doc(name, variant);
 
             match v.fields {
@@ -270,13 +539,14 @@ fn generate_resolver_def(
                         .iter()
                         .map(|f| {
                             let field_name = f.ident.as_ref().unwrap();
+                            let resolver_name = archived_field_name(f)?;
                             let resolver = resolver(rkyv_path, f)?;
                             let field_doc = enum_resolver_field_doc(
                                 name, variant, field_name,
                             );
                             Ok(quote! {
                                 #[doc = #field_doc]
-                                #field_name: #resolver
+                                #resolver_name: #resolver
                             })
                         })
                         .collect::<Result<Vec<_>, Error>>()?;
@@ -284,7 +554,7 @@ fn generate_resolver_def(
                     Ok(quote! {
                         #[doc = #variant_doc]
                         #[allow(dead_code)]
-                        #variant {
+                        #archived_variant {
                             #(#fields,)*
                         }
                     })
@@ -308,13 +578,13 @@ fn generate_resolver_def(
                     Ok(quote! {
                         #[doc = #variant_doc]
                         #[allow(dead_code)]
-                        #variant(#(#fields,)*)
+                        #archived_variant(#(#fields,)*)
                     })
                 }
                 Fields::Unit => Ok(quote! {
                     #[doc = #variant_doc]
                     #[allow(dead_code)]
-                    #variant
+                    #archived_variant
                 }),
             }
         })
@@ -355,9 +625,14 @@ fn generate_resolve_arms(
                 v.span(),
             );
 
+            let archived_variant = archived_variant(v)?;
+
             let members = members_starting_at(&v.fields, 1)
                 .map(|(m, _)| m)
                 .collect::<Vec<_>>();
+            let archived_members = members_starting_at(&v.fields, 1)
+                .map(|(m, f)| archived_member(&m, f))
+                .collect::<Result<Vec<_>, Error>>()?;
 
             let (self_bindings, resolver_bindings) = v
                 .fields
@@ -379,8 +654,8 @@ fn generate_resolve_arms(
 
             match v.fields {
                 Fields::Named(_) => Ok(quote! {
-                    #resolver_name::#variant {
-                        #(#members: #resolver_bindings,)*
+                    #resolver_name::#archived_variant {
+                        #(#archived_members: #resolver_bindings,)*
                     } => {
                         match self {
                             #name::#variant {
@@ -426,7 +701,9 @@ fn generate_resolve_arms(
                     }
                 }),
                 Fields::Unnamed(_) => Ok(quote! {
-                    #resolver_name::#variant( #(#resolver_bindings,)* ) => {
+                    #resolver_name::#archived_variant(
+                        #(#resolver_bindings,)*
+                    ) => {
                         match self {
                             #name::#variant(#(#self_bindings,)*) => {
                                 let out = unsafe {
@@ -467,12 +744,12 @@ fn generate_resolve_arms(
                     }
                 }),
                 Fields::Unit => Ok(quote! {
-                    #resolver_name::#variant => {
+                    #resolver_name::#archived_variant => {
                         let out = unsafe {
                             out.cast_unchecked::<ArchivedTag>()
                         };
-                        // SAFETY: `ArchivedTag` is `repr(u8)` and so is always
-                        // initialized.
+                        // SAFETY: `ArchivedTag` has a primitive representation
+                        // and so is always initialized.
                         unsafe {
                             out.write_unchecked(ArchivedTag::#variant);
                         }
@@ -505,9 +782,8 @@ fn generate_variant_structs(
 
             match v.fields {
                 Fields::Named(ref fields) => {
-                    let fields = fields
-                        .named
-                        .iter()
+                    let fields = packed_fields(printing, fields)
+                        .into_iter()
                         .map(|f| {
                             let name = &f.ident;
                             let archived = archived(rkyv_path, f)?;
@@ -547,6 +823,322 @@ fn generate_variant_structs(
         .collect()
 }
 
+fn generate_schema_impl(
+    input: &DeriveInput,
+    data: &DataEnum,
+    printing: &Printing,
+    tag_repr: &Ident,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    let variant_schemas = data
+        .variants
+        .iter()
+        .map(|v| {
+            let variant = &v.ident;
+            let name = strip_raw(&archived_variant(v)?);
+            let archived_variant_name = Ident::new(
+                &format!("ArchivedVariant{}", strip_raw(variant)),
+                v.span(),
+            );
+
+            let tag = if tag_repr == "u8" {
+                quote! { ArchivedTag::#variant as u8 as u64 }
+            } else {
+                let convert = format_ident!("enum_tag_{}", tag_repr);
+                quote! {
+                    #rkyv_path::primitive::#convert(
+                        ArchivedTag::#variant as #tag_repr
+                    ) as u64
+                }
+            };
+
+            let fields = match v.fields {
+                Fields::Named(ref fields) => packed_fields(printing, fields)
+                    .into_iter()
+                    .map(|f| {
+                        let field_name = strip_raw(&archived_field_name(f)?);
+                        Ok((
+                            field_name,
+                            Member::Named(f.ident.clone().unwrap()),
+                            f,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?,
+                Fields::Unnamed(ref fields) => fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        (i.to_string(), Member::Unnamed(Index::from(i + 1)), f)
+                    })
+                    .collect(),
+                Fields::Unit => Vec::new(),
+            };
+            let field_schemas = fields
+                .into_iter()
+                .map(|(field_name, member, f)| {
+                    let archived_ty = archived(rkyv_path, f)?;
+                    Ok(quote! {
+                        #rkyv_path::schema::FieldSchema {
+                            name: #field_name,
+                            type_name: ::core::any::type_name::<#archived_ty>,
+                            offset: ::core::mem::offset_of!(
+                                #archived_variant_name #ty_generics,
+                                #member
+                            ),
+                            size: ::core::mem::size_of::<#archived_ty>(),
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            Ok(quote! {
+                #rkyv_path::schema::VariantSchema {
+                    name: #name,
+                    tag: #tag,
+                    fields: &[#(#field_schemas,)*],
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(quote! {
+        // SAFETY: The schema is built from the tags of the archived variants
+        // and the offsets and sizes of the fields of the variant structs, which
+        // have the same layout as the archived variants.
+        unsafe impl #impl_generics #rkyv_path::schema::Schema
+            for #archived_type
+        #where_clause
+        {
+            const SCHEMA: #rkyv_path::schema::TypeSchema =
+                #rkyv_path::schema::TypeSchema {
+                    type_name: ::core::any::type_name::<Self>,
+                    size: ::core::mem::size_of::<Self>(),
+                    align: ::core::mem::align_of::<Self>(),
+                    kind: #rkyv_path::schema::SchemaKind::Enum(
+                        #rkyv_path::schema::EnumSchema {
+                            tag_size: ::core::mem::size_of::<ArchivedTag>(),
+                            variants: &[#(#variant_schemas,)*],
+                        },
+                    ),
+                };
+        }
+    })
+}
+
+fn generate_walk_impl(
+    input: &DeriveInput,
+    data: &DataEnum,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    // The bounds are higher-ranked so that the impl is omitted instead of
+    // failing to compile when a field can't be walked.
+    let mut walk_where = input.generics.where_clause.as_ref().unwrap().clone();
+    for field in
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
+    {
+        let archived = archived(rkyv_path, field)?;
+        walk_where.predicates.push(parse_quote! {
+            for<'__a> #archived: #rkyv_path::walk::Walk
+        });
+    }
+
+    let name = strip_raw(&input.ident);
+    let variant_arms = data
+        .variants
+        .iter()
+        .map(|v| {
+            let archived_variant = archived_variant(v)?;
+            let variant_name = strip_raw(&archived_variant);
+            let bindings = v
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| Ident::new(&format!("_{}", i), f.span()))
+                .collect::<Vec<_>>();
+
+            let (pattern, field_names) = match v.fields {
+                Fields::Named(ref fields) => {
+                    let archived_field_names = fields
+                        .named
+                        .iter()
+                        .map(archived_field_name)
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    let field_names =
+                        archived_field_names.iter().map(strip_raw).collect();
+                    (
+                        quote! {
+                            Self::#archived_variant {
+                                #(#archived_field_names: #bindings,)*
+                            }
+                        },
+                        field_names,
+                    )
+                }
+                Fields::Unnamed(_) => (
+                    quote! { Self::#archived_variant(#(#bindings,)*) },
+                    (0..bindings.len()).map(|i| i.to_string()).collect(),
+                ),
+                Fields::Unit => {
+                    (quote! { Self::#archived_variant }, Vec::new())
+                }
+            };
+
+            Ok(quote! {
+                #pattern => {
+                    visitor.begin_variant(#name, #variant_name);
+                    #(
+                        visitor.visit_field(#field_names);
+                        #rkyv_path::walk::Walk::walk(#bindings, visitor);
+                    )*
+                    visitor.end_variant();
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ..) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::walk::Walk for #archived_type
+        #walk_where
+        {
+            fn walk(&self, visitor: &mut dyn #rkyv_path::walk::Visitor) {
+                match self {
+                    #(#variant_arms,)*
+                }
+            }
+        }
+    })
+}
+
+fn generate_serde_impls(
+    input: &DeriveInput,
+    data: &DataEnum,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut serde_where = input.generics.where_clause.as_ref().unwrap().clone();
+    for field in
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
+    {
+        let archived = archived(rkyv_path, field)?;
+        serde_where.predicates.push(parse_quote! {
+            #archived: #rkyv_path::serde::SerializeArchived
+        });
+    }
+
+    // The archived type is serialized as the original type would be.
+    let name = strip_raw(&input.ident);
+
+    let variant_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(index, v)| {
+            let index = index as u32;
+            let variant_name = strip_raw(&v.ident);
+            let archived_variant = archived_variant(v)?;
+            let bindings = v
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| Ident::new(&format!("_{}", i), f.span()))
+                .collect::<Vec<_>>();
+            let len = bindings.len();
+
+            Ok(match v.fields {
+                Fields::Named(ref fields) => {
+                    let field_names = fields
+                        .named
+                        .iter()
+                        .map(|f| strip_raw(f.ident.as_ref().unwrap()));
+                    let archived_field_names = fields
+                        .named
+                        .iter()
+                        .map(archived_field_name)
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    quote! {
+                        Self::#archived_variant {
+                            #(#archived_field_names: #bindings,)*
+                        } => {
+                            use #rkyv_path::serde::ser::SerializeStructVariant
+                                as _;
+
+                            let mut state = serializer.serialize_struct_variant(
+                                #name,
+                                #index,
+                                #variant_name,
+                                #len,
+                            )?;
+                            #(
+                                state.serialize_field(
+                                    #field_names,
+                                    &#rkyv_path::serde::AsSerde(#bindings),
+                                )?;
+                            )*
+                            state.end()
+                        }
+                    }
+                }
+                Fields::Unnamed(_) if len == 1 => quote! {
+                    Self::#archived_variant(_0) => {
+                        serializer.serialize_newtype_variant(
+                            #name,
+                            #index,
+                            #variant_name,
+                            &#rkyv_path::serde::AsSerde(_0),
+                        )
+                    }
+                },
+                Fields::Unnamed(_) => quote! {
+                    Self::#archived_variant(#(#bindings,)*) => {
+                        use #rkyv_path::serde::ser::SerializeTupleVariant as _;
+
+                        let mut state = serializer.serialize_tuple_variant(
+                            #name,
+                            #index,
+                            #variant_name,
+                            #len,
+                        )?;
+                        #(
+                            state.serialize_field(
+                                &#rkyv_path::serde::AsSerde(#bindings),
+                            )?;
+                        )*
+                        state.end()
+                    }
+                },
+                Fields::Unit => quote! {
+                    Self::#archived_variant => {
+                        serializer.serialize_unit_variant(
+                            #name,
+                            #index,
+                            #variant_name,
+                        )
+                    }
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let body = quote! {
+        match self {
+            #(#variant_arms,)*
+        }
+    };
+
+    Ok(serde_impls(input, printing, &serde_where, body))
+}
+
 fn generate_partial_eq_impl(
     input: &DeriveInput,
     data: &DataEnum,
@@ -555,11 +1147,8 @@ fn generate_partial_eq_impl(
     let mut partial_eq_where =
         input.generics.where_clause.as_ref().unwrap().clone();
 
-    for field in data
-        .variants
-        .iter()
-        .flat_map(|v| v.fields.iter())
-        .filter(is_not_omitted)
+    for field in
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
     {
         let ty = &field.ty;
         let archived = archived(&printing.rkyv_path, field)?;
@@ -573,58 +1162,73 @@ fn generate_partial_eq_impl(
     let name = &input.ident;
     let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
 
-    let variant_impls = data.variants.iter().map(|v| {
-        let variant = &v.ident;
+    let variant_impls = data
+        .variants
+        .iter()
+        .map(|v| {
+            let variant = &v.ident;
+            let archived_variant = archived_variant(v)?;
 
-        let (self_fields, other_fields) = v
-            .fields
-            .iter()
-            .enumerate()
-            .map(|(i, f)| {
-                (
-                    Ident::new(&format!("self_{}", i), f.span()),
-                    Ident::new(&format!("other_{}", i), f.span()),
-                )
-            })
-            .unzip::<_, _, Vec<_>, Vec<_>>();
+            let (self_fields, other_fields) = v
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    (
+                        Ident::new(&format!("self_{}", i), f.span()),
+                        Ident::new(&format!("other_{}", i), f.span()),
+                    )
+                })
+                .unzip::<_, _, Vec<_>, Vec<_>>();
 
-        match v.fields {
-            Fields::Named(ref fields) => {
-                let field_names =
-                    fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+            Ok(match v.fields {
+                Fields::Named(ref fields) => {
+                    let field_names = fields
+                        .named
+                        .iter()
+                        .map(|f| &f.ident)
+                        .collect::<Vec<_>>();
+                    let archived_field_names = fields
+                        .named
+                        .iter()
+                        .map(archived_field_name)
+                        .collect::<Result<Vec<_>, Error>>()?;
 
-                quote! {
-                    #name::#variant {
-                        #(#field_names: #self_fields,)*
-                    } => match other {
-                        #archived_name::#variant {
-                            #(#field_names: #other_fields,)*
-                        } => true #(&& #other_fields.eq(#self_fields))*,
-                        #[allow(unreachable_patterns)]
-                        _ => false,
+                    quote! {
+                        #name::#variant {
+                            #(#field_names: #self_fields,)*
+                        } => match other {
+                            #archived_name::#archived_variant {
+                                #(#archived_field_names: #other_fields,)*
+                            } => true #(&& #other_fields.eq(#self_fields))*,
+                            #[allow(unreachable_patterns)]
+                            _ => false,
+                        }
                     }
                 }
-            }
-            Fields::Unnamed(_) => {
-                quote! {
-                    #name::#variant(#(#self_fields,)*) => match other {
-                        #archived_name::#variant(#(#other_fields,)*) => {
-                            true #(&& #other_fields.eq(#self_fields))*
+                Fields::Unnamed(_) => {
+                    quote! {
+                        #name::#variant(#(#self_fields,)*) => match other {
+                            #archived_name::#archived_variant(
+                                #(#other_fields,)*
+                            ) => {
+                                true #(&& #other_fields.eq(#self_fields))*
+                            }
+                            #[allow(unreachable_patterns)]
+                            _ => false,
                         }
+                    }
+                }
+                Fields::Unit => quote! {
+                    #name::#variant => match other {
+                        #archived_name::#archived_variant => true,
                         #[allow(unreachable_patterns)]
                         _ => false,
                     }
-                }
-            }
-            Fields::Unit => quote! {
-                #name::#variant => match other {
-                    #archived_name::#variant => true,
-                    #[allow(unreachable_patterns)]
-                    _ => false,
-                }
-            },
-        }
-    });
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
 
     Ok(quote! {
         impl #impl_generics PartialEq<#archived_type> for #name #ty_generics
@@ -655,11 +1259,8 @@ fn generate_partial_ord_impl(
     let mut partial_ord_where =
         input.generics.where_clause.as_ref().unwrap().clone();
 
-    for field in data
-        .variants
-        .iter()
-        .flat_map(|v| v.fields.iter())
-        .filter(is_not_omitted)
+    for field in
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
     {
         let ty = &field.ty;
         let archived = archived(&printing.rkyv_path, field)?;
@@ -687,23 +1288,31 @@ fn generate_partial_ord_impl(
             },
         }
     });
-    let other_disc = data.variants.iter().map(|v| {
-        let variant = &v.ident;
-        match v.fields {
-            Fields::Named(_) => quote! {
-                #archived_name::#variant { .. } => ArchivedTag::#variant
-            },
-            Fields::Unnamed(_) => quote! {
-                #archived_name::#variant ( .. ) => ArchivedTag::#variant
-            },
-            Fields::Unit => quote! {
-                #archived_name::#variant => ArchivedTag::#variant
-            },
-        }
-    });
+    let other_disc = data
+        .variants
+        .iter()
+        .map(|v| {
+            let variant = &v.ident;
+            let archived_variant = archived_variant(v)?;
+            Ok(match v.fields {
+                Fields::Named(_) => quote! {
+                    #archived_name::#archived_variant { .. }
+                        => ArchivedTag::#variant
+                },
+                Fields::Unnamed(_) => quote! {
+                    #archived_name::#archived_variant ( .. )
+                        => ArchivedTag::#variant
+                },
+                Fields::Unit => quote! {
+                    #archived_name::#archived_variant => ArchivedTag::#variant
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
 
     let variant_impls = data.variants.iter().map(|v| {
         let variant = &v.ident;
+        let archived_variant = archived_variant(v)?;
 
         let (self_fields, other_fields) = v
             .fields
@@ -717,17 +1326,22 @@ fn generate_partial_ord_impl(
             })
             .unzip::<_, _, Vec<_>, Vec<_>>();
 
-        match v.fields {
+        Ok(match v.fields {
             Fields::Named(ref fields) => {
                 let field_names =
                     fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+                let archived_field_names = fields
+                    .named
+                    .iter()
+                    .map(archived_field_name)
+                    .collect::<Result<Vec<_>, Error>>()?;
 
                 quote! {
                     #name::#variant {
                         #(#field_names: #self_fields,)*
                     } => match other {
-                        #archived_name::#variant {
-                            #(#field_names: #other_fields,)*
+                        #archived_name::#archived_variant {
+                            #(#archived_field_names: #other_fields,)*
                         } => {
                             #(
                                 match #other_fields.partial_cmp(#self_fields) {
@@ -745,7 +1359,9 @@ fn generate_partial_ord_impl(
             Fields::Unnamed(_) => {
                 quote! {
                     #name::#variant(#(#self_fields,)*) => match other {
-                        #archived_name::#variant(#(#other_fields,)*) => {
+                        #archived_name::#archived_variant(
+                            #(#other_fields,)*
+                        ) => {
                             #(
                                 match #other_fields.partial_cmp(#self_fields) {
                                     Some(::core::cmp::Ordering::Equal) => (),
@@ -761,15 +1377,16 @@ fn generate_partial_ord_impl(
             }
             Fields::Unit => quote! {
                 #name::#variant => match other {
-                    #archived_name::#variant => {
+                    #archived_name::#archived_variant => {
                         Some(::core::cmp::Ordering::Equal)
                     }
                     #[allow(unreachable_patterns)]
                     _ => unsafe { ::core::hint::unreachable_unchecked() },
                 }
             },
-        }
-    });
+        })
+    })
+    .collect::<Result<Vec<_>, Error>>()?;
 
     Ok(quote! {
         impl #impl_generics PartialOrd<#archived_type> for #name #ty_generics
@@ -811,3 +1428,193 @@ fn generate_partial_ord_impl(
         }
     })
 }
+
+fn generate_swap_endian_impl(
+    input: &DeriveInput,
+    data: &DataEnum,
+    printing: &Printing,
+    tag_repr: &Ident,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut swap_endian_where =
+        input.generics.where_clause.as_ref().unwrap().clone();
+
+    for field in
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
+    {
+        if !has_fixed_endianness(field)? {
+            let archived = archived(rkyv_path, field)?;
+            swap_endian_where.predicates.push(parse_quote! {
+                #archived: #rkyv_path::endian::SwapEndian
+            });
+        }
+    }
+
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+
+    let variant_arms = data
+        .variants
+        .iter()
+        .map(|v| {
+            let variant = &v.ident;
+            let archived_variant_name = Ident::new(
+                &format!("ArchivedVariant{}", strip_raw(variant)),
+                v.span(),
+            );
+
+            // Fields with a fixed byte order are never converted.
+            let mut swapped = Vec::new();
+            for (member, field) in members_starting_at(&v.fields, 1) {
+                if !has_fixed_endianness(field)? {
+                    swapped.push((member, field));
+                }
+            }
+
+            let swap_statements = swapped
+                .into_iter()
+                .map(|(member, field)| {
+                    let archived = archived(rkyv_path, field)?;
+                    Ok(quote! {
+                        <
+                            #archived as #rkyv_path::endian::SwapEndian
+                        >::swap_endian(
+                            ::core::ptr::addr_of_mut!((*this).#member),
+                        );
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            if swap_statements.is_empty() {
+                Ok(quote! { ArchivedTag::#variant => () })
+            } else {
+                Ok(quote! {
+                    ArchivedTag::#variant => {
+                        let this = this.cast::<
+                            #archived_variant_name #ty_generics
+                        >();
+                        unsafe {
+                            #(#swap_statements)*
+                        }
+                    }
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // Multibyte tags are converted before they are read.
+    let swap_tag = (tag_repr != "u8").then(|| {
+        quote! {
+            let tag_ptr = this.cast::<#tag_repr>();
+            unsafe {
+                tag_ptr.write(tag_ptr.read().swap_bytes());
+            }
+        }
+    });
+
+    let archived_type = &printing.archived_type;
+
+    Ok(quote! {
+        // SAFETY: The tag of the archived enum is converted if it has more
+        // than one byte, and every field of the active variant is converted.
+        unsafe impl #impl_generics #rkyv_path::endian::SwapEndian
+            for #archived_type
+        #swap_endian_where
+        {
+            unsafe fn swap_endian(this: *mut Self) {
+                #swap_tag
+                let tag = unsafe { this.cast::<ArchivedTag>().read() };
+                match tag {
+                    #(#variant_arms,)*
+                }
+            }
+        }
+    })
+}
+
+fn generate_relocate_impl(
+    input: &DeriveInput,
+    data: &DataEnum,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut relocate_where =
+        input.generics.where_clause.as_ref().unwrap().clone();
+    for field in
+        bound_fields(input, data.variants.iter().flat_map(|v| &v.fields))
+    {
+        let archived = archived(rkyv_path, field)?;
+        relocate_where.predicates.push(parse_quote! {
+            #archived: #rkyv_path::relocate::Relocate
+        });
+    }
+
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+
+    let variant_arms = data
+        .variants
+        .iter()
+        .map(|v| {
+            let variant = &v.ident;
+            let archived_variant_name = Ident::new(
+                &format!("ArchivedVariant{}", strip_raw(variant)),
+                v.span(),
+            );
+
+            let relocate_statements = members_starting_at(&v.fields, 1)
+                .map(|(member, field)| {
+                    let archived = archived(rkyv_path, field)?;
+                    Ok(quote! {
+                        <
+                            #archived as #rkyv_path::relocate::Relocate
+                        >::relocate(
+                            ::core::ptr::addr_of_mut!((*this).#member),
+                            base,
+                            mode,
+                        )?;
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            if relocate_statements.is_empty() {
+                Ok(quote! { ArchivedTag::#variant => () })
+            } else {
+                Ok(quote! {
+                    ArchivedTag::#variant => {
+                        let this = this.cast::<
+                            #archived_variant_name #ty_generics
+                        >();
+                        unsafe {
+                            #(#relocate_statements)*
+                        }
+                    }
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let archived_type = &printing.archived_type;
+
+    Ok(quote! {
+        // SAFETY: Every field of the active variant is converted. The tag does
+        // not contain any relative pointers.
+        unsafe impl #impl_generics #rkyv_path::relocate::Relocate
+            for #archived_type
+        #relocate_where
+        {
+            #[allow(unused_variables)]
+            unsafe fn relocate<E: #rkyv_path::rancor::Source>(
+                this: *mut Self,
+                base: *mut u8,
+                mode: #rkyv_path::relocate::OffsetMode,
+            ) -> ::core::result::Result<(), E> {
+                let tag = unsafe { this.cast::<ArchivedTag>().read() };
+                match tag {
+                    #(#variant_arms,)*
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
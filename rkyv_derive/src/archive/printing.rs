@@ -1,10 +1,13 @@
 use quote::quote;
 use syn::{
-    parse_quote, spanned::Spanned as _, Attribute, DeriveInput, Error, Ident,
-    LitStr, Path, Type,
+    parse_quote, spanned::Spanned as _, Attribute, Data, DataStruct,
+    DeriveInput, Error, Fields, Ident, LitStr, Path, Type,
 };
 
-use crate::{attributes::Attributes, util::strip_raw};
+use crate::{
+    attributes::Attributes,
+    util::{has_recursive_fields, strip_raw},
+};
 
 pub struct Printing {
     pub rkyv_path: Path,
@@ -12,6 +15,8 @@ pub struct Printing {
     pub archived_type: Type,
     pub resolver_name: Ident,
     pub archive_attrs: Vec<Attribute>,
+    pub omit_recursive_check_bytes: bool,
+    pub pack_variant_fields: bool,
 }
 
 impl Printing {
@@ -34,6 +39,55 @@ impl Printing {
                      because no type is generated",
                 ));
             }
+            if let Some(ref path) = attributes.swap_endian {
+                return Err(Error::new_spanned(
+                    path,
+                    "swap_endian may not be used with as = \"...\" because no \
+                     type is generated",
+                ));
+            }
+            if let Some(ref path) = attributes.getters {
+                return Err(Error::new_spanned(
+                    path,
+                    "getters may not be used with as = \"...\" because no \
+                     type is generated",
+                ));
+            }
+            if let Some(ref path) = attributes.setters {
+                return Err(Error::new_spanned(
+                    path,
+                    "setters may not be used with as = \"...\" because no \
+                     type is generated",
+                ));
+            }
+            if let Some(ref path) = attributes.derive_serde {
+                return Err(Error::new_spanned(
+                    path,
+                    "derive_serde may not be used with as = \"...\" because \
+                     no type is generated",
+                ));
+            }
+            if let Some(ref path) = attributes.pack {
+                return Err(Error::new_spanned(
+                    path,
+                    "pack may not be used with as = \"...\" because no type \
+                     is generated",
+                ));
+            }
+            if let Some(ref path) = attributes.length_prefixed {
+                return Err(Error::new_spanned(
+                    path,
+                    "length_prefixed may not be used with as = \"...\" \
+                     because no type is generated",
+                ));
+            }
+            if let Some(ref path) = attributes.schema {
+                return Err(Error::new_spanned(
+                    path,
+                    "schema may not be used with as = \"...\" because no type \
+                     is generated",
+                ));
+            }
             if let Some(first) = attributes.attrs.first() {
                 return Err(Error::new_spanned(
                     first,
@@ -48,6 +102,43 @@ impl Printing {
             }
         }
 
+        if let Some(ref path) = attributes.pack {
+            if !matches!(input.data, Data::Enum(_)) {
+                return Err(Error::new_spanned(
+                    path,
+                    "pack can only be used on enums",
+                ));
+            }
+        }
+
+        if let Some(ref path) = attributes.length_prefixed {
+            if !matches!(
+                input.data,
+                Data::Struct(DataStruct {
+                    fields: Fields::Named(_),
+                    ..
+                })
+            ) {
+                return Err(Error::new_spanned(
+                    path,
+                    "length_prefixed can only be used on structs with named \
+                     fields",
+                ));
+            }
+            if let Some(ref path) = attributes.swap_endian {
+                return Err(Error::new_spanned(
+                    path,
+                    "swap_endian may not be used with length_prefixed",
+                ));
+            }
+            if let Some(ref path) = attributes.derive_serde {
+                return Err(Error::new_spanned(
+                    path,
+                    "derive_serde may not be used with length_prefixed",
+                ));
+            }
+        }
+
         let archived_name = attributes.archived.as_ref().map_or_else(
             || Ident::new(&format!("Archived{}", strip_raw(name)), name.span()),
             |value| value.clone(),
@@ -66,15 +157,40 @@ impl Printing {
             |lit| lit.parse::<Type>(),
         )?;
 
+        let pack_variant_fields = attributes.pack.is_some();
+
+        let mut omit_recursive_check_bytes = false;
         let derive_check_bytes = if attributes.check_bytes.is_some()
             && cfg!(feature = "bytecheck")
         {
             let path = quote!(#rkyv_path::bytecheck).to_string();
             let path_lit_str = LitStr::new(&path, rkyv_path.span());
-            vec![
+            let mut attrs: Vec<Attribute> = vec![
                 parse_quote! { #[derive(#rkyv_path::bytecheck::CheckBytes)] },
                 parse_quote! { #[check_bytes(crate = #path_lit_str)] },
-            ]
+            ];
+
+            // Recursive fields can't be bounded without overflowing, so
+            // their bounds are omitted and replaced with the context bounds
+            // that rkyv's own containers require. Custom `check_bytes(...)`
+            // attributes take precedence.
+            let has_custom_check_bytes = attributes
+                .attrs
+                .iter()
+                .any(|meta| meta.path().is_ident("check_bytes"));
+            if has_recursive_fields(input) && !has_custom_check_bytes {
+                omit_recursive_check_bytes = true;
+                attrs.push(parse_quote! {
+                    #[check_bytes(bounds(
+                        __C: #rkyv_path::validation::ArchiveContext
+                            + #rkyv_path::validation::SharedContext,
+                        <__C as #rkyv_path::rancor::Fallible>::Error:
+                            #rkyv_path::rancor::Source,
+                    ))]
+                });
+            }
+
+            attrs
         } else {
             Vec::new()
         };
@@ -95,6 +211,8 @@ impl Printing {
             archived_type,
             resolver_name,
             archive_attrs,
+            omit_recursive_check_bytes,
+            pack_variant_fields,
         })
     }
 }
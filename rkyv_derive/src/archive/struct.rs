@@ -1,18 +1,20 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     parse_quote, punctuated::Punctuated, Data, DeriveInput, Error, Fields,
-    FieldsNamed, FieldsUnnamed,
+    FieldsNamed, FieldsUnnamed, Generics, Index, Member, Token, Visibility,
 };
 
 use crate::{
     archive::{
         archived_doc, field_archive_attrs, printing::Printing, resolver_doc,
-        struct_field_doc,
+        serde_impls, struct_field_doc,
     },
-    attributes::Attributes,
+    attributes::{parse_default, Attributes},
     util::{
-        archive_bound, archived, is_not_omitted, members, resolve, resolver,
+        archive_bound, archived, archived_field_name, archived_member,
+        bound_fields, has_fixed_endianness, has_recursive_fields,
+        max_serialized_size, members, resolve, resolver, strip_raw,
     },
 };
 
@@ -26,15 +28,20 @@ pub fn impl_struct(
         _ => unreachable!(),
     };
 
-    let rkyv_path = &printing.rkyv_path;
+    if let Some(ref repr) = attributes.repr {
+        return Err(Error::new_spanned(
+            repr,
+            "repr(...) sets the tag of archived enums and can't be used on \
+             structs, use #[archive_attr(repr(...))] instead",
+        ));
+    }
 
-    let where_clause = input.generics.make_where_clause();
+    let rkyv_path = &printing.rkyv_path;
 
-    for field in fields.iter().filter(is_not_omitted) {
-        where_clause
-            .predicates
-            .push(archive_bound(rkyv_path, field)?);
-    }
+    let bounds = bound_fields(input, fields)
+        .map(|field| archive_bound(rkyv_path, field))
+        .collect::<Result<Vec<_>, Error>>()?;
+    input.generics.make_where_clause().predicates.extend(bounds);
 
     let (impl_generics, ty_generics, where_clause) =
         input.generics.split_for_impl();
@@ -50,21 +57,27 @@ pub fn impl_struct(
 
     let resolve_statements = members(fields)
         .map(|(member, field)| {
+            let archived_member = archived_member(&member, field)?;
             let resolves = resolve(rkyv_path, field)?;
             Ok(quote! {
                 let field_ptr = unsafe {
-                    ::core::ptr::addr_of_mut!((*out.ptr()).#member)
+                    ::core::ptr::addr_of_mut!((*out.ptr()).#archived_member)
                 };
                 let out_field = unsafe {
                     #rkyv_path::Place::from_field_unchecked(out, field_ptr)
                 };
-                #resolves(&self.#member, resolver.#member, out_field);
+                #resolves(
+                    &self.#member,
+                    resolver.#archived_member,
+                    out_field,
+                );
             })
         })
         .collect::<Result<Vec<_>, Error>>()?;
 
     let mut partial_eq_impl = None;
     let mut partial_ord_impl = None;
+    let mut hash_impl = None;
     for compare in attributes.compares.iter().flat_map(Punctuated::iter) {
         if compare.is_ident("PartialEq") {
             partial_eq_impl =
@@ -72,24 +85,119 @@ pub fn impl_struct(
         } else if compare.is_ident("PartialOrd") {
             partial_ord_impl =
                 Some(generate_partial_ord_impl(input, fields, printing)?);
+        } else if compare.is_ident("Hash") {
+            hash_impl = Some(generate_hash_impl(input, fields, printing)?);
         } else {
             return Err(Error::new_spanned(
                 compare,
                 "unrecognized compare argument, supported compares are \
-                 PartialEq and PartialOrd",
+                 PartialEq, PartialOrd, and Hash",
             ));
         }
     }
 
+    let swap_endian_impl = attributes
+        .swap_endian
+        .is_some()
+        .then(|| generate_swap_endian_impl(input, fields, printing))
+        .transpose()?;
+
+    let relocate_impl = attributes
+        .relocate
+        .is_some()
+        .then(|| generate_relocate_impl(input, fields, printing))
+        .transpose()?;
+
+    let getters_impl = attributes
+        .getters
+        .is_some()
+        .then(|| generate_getters_impl(input, fields, printing))
+        .transpose()?;
+
+    let setters_impl = attributes
+        .setters
+        .is_some()
+        .then(|| generate_setters_impl(input, fields, printing))
+        .transpose()?;
+
+    let serde_impls = attributes
+        .derive_serde
+        .is_some()
+        .then(|| generate_serde_impls(input, fields, printing))
+        .transpose()?;
+
+    let schema_impl = attributes
+        .schema
+        .is_some()
+        .then(|| generate_schema_impl(input, fields, printing))
+        .transpose()?;
+
+    let walk_impl = attributes
+        .schema
+        .is_some()
+        .then(|| generate_walk_impl(input, fields, printing))
+        .transpose()?;
+
+    let splice_impl = attributes
+        .splice
+        .is_some()
+        .then(|| generate_splice_impl(input, attributes, fields, printing))
+        .transpose()?;
+
     let name = &input.ident;
     let archived_type = &printing.archived_type;
     let resolver_name = &printing.resolver_name;
 
-    Ok((
+    let max_serialized_size = max_serialized_size(rkyv_path, input, fields)?;
+
+    let archive_impl = if attributes.length_prefixed.is_some() {
+        let check_prefix_impl = (attributes.check_bytes.is_some()
+            && cfg!(feature = "bytecheck"))
+        .then(|| generate_check_prefix_impl(input, fields, printing))
+        .transpose()?;
+
         quote! {
-            #archived_def
-            #resolver_def
-        },
+            impl #impl_generics #rkyv_path::prefixed::ArchiveFields
+                for #name #ty_generics
+            #where_clause
+            {
+                type Fields = #archived_type;
+                type FieldsResolver = #resolver_name #ty_generics;
+
+                // Some resolvers will be (), this allow is to prevent clippy
+                // from complaining.
+                #[allow(clippy::unit_arg)]
+                fn resolve_fields(
+                    &self,
+                    resolver: Self::FieldsResolver,
+                    out: #rkyv_path::Place<Self::Fields>,
+                ) {
+                    #(#resolve_statements)*
+                }
+            }
+
+            impl #impl_generics #rkyv_path::Archive for #name #ty_generics
+            #where_clause
+            {
+                type Archived =
+                    #rkyv_path::prefixed::LengthPrefixed<#archived_type>;
+                type Resolver = #rkyv_path::prefixed::LengthPrefixedResolver;
+
+                fn resolve(
+                    &self,
+                    resolver: Self::Resolver,
+                    out: #rkyv_path::Place<Self::Archived>,
+                ) {
+                    #rkyv_path::prefixed::LengthPrefixed::resolve_from_resolver(
+                        resolver,
+                        out,
+                    );
+                }
+            }
+
+            #check_prefix_impl
+        }
+    } else {
         quote! {
             impl #impl_generics #rkyv_path::Archive for #name #ty_generics
             #where_clause
@@ -97,6 +205,8 @@ pub fn impl_struct(
                 type Archived = #archived_type;
                 type Resolver = #resolver_name #ty_generics;
 
+                #max_serialized_size
+
                 // Some resolvers will be (), this allow is to prevent clippy
                 // from complaining.
                 #[allow(clippy::unit_arg)]
@@ -108,9 +218,28 @@ pub fn impl_struct(
                     #(#resolve_statements)*
                 }
             }
+        }
+    };
+
+    Ok((
+        quote! {
+            #archived_def
+            #resolver_def
+        },
+        quote! {
+            #archive_impl
 
             #partial_eq_impl
             #partial_ord_impl
+            #hash_impl
+            #swap_endian_impl
+            #relocate_impl
+            #getters_impl
+            #setters_impl
+            #serde_impls
+            #schema_impl
+            #walk_impl
+            #splice_impl
         },
     ))
 }
@@ -160,14 +289,15 @@ fn generate_archived_def_named(
         .map(|field| {
             let field_ty = archived(rkyv_path, field)?;
             let vis = &field.vis;
-            let archive_attrs = field_archive_attrs(field);
+            let archive_attrs = field_archive_attrs(printing, input, field);
 
             let field_name = field.ident.as_ref().unwrap();
             let field_doc = struct_field_doc(&input.ident, field_name);
+            let archived_name = archived_field_name(field)?;
             Ok(quote! {
                 #[doc = #field_doc]
                 #(#[#archive_attrs])*
-                #vis #field_name: #field_ty
+                #vis #archived_name: #field_ty
             })
         })
         .collect::<Result<Vec<_>, Error>>()?;
@@ -203,7 +333,7 @@ fn generate_archived_def_unnamed(
         .enumerate()
         .map(|(i, field)| {
             let field_doc = struct_field_doc(&input.ident, &i);
-            let archive_attrs = field_archive_attrs(field);
+            let archive_attrs = field_archive_attrs(printing, input, field);
             let vis = &field.vis;
             let field_ty = archived(rkyv_path, field)?;
 
@@ -285,10 +415,10 @@ fn generate_resolver_def_named(
         .named
         .iter()
         .map(|field| {
-            let field_name = &field.ident;
+            let resolver_name = archived_field_name(field)?;
             let resolver_ty = resolver(rkyv_path, field)?;
 
-            Ok(quote! { #field_name: #resolver_ty })
+            Ok(quote! { #resolver_name: #resolver_ty })
         })
         .collect::<Result<Vec<_>, Error>>()?;
 
@@ -348,6 +478,129 @@ fn generate_resolver_def_unit(
     })
 }
 
+fn generate_getters_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let Fields::Named(fields) = fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "getters can only be generated for structs with named fields",
+        ));
+    };
+
+    let rkyv_path = &printing.rkyv_path;
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    let getters = fields
+        .named
+        .iter()
+        .filter(|field| matches!(field.vis, Visibility::Inherited))
+        .map(|field| {
+            let field_name = field.ident.as_ref().unwrap();
+            let archived_name = archived_field_name(field)?;
+            let field_ty = archived(rkyv_path, field)?;
+            let doc = format!(
+                "Returns the archived counterpart of `{}::{}`.",
+                name,
+                strip_raw(field_name),
+            );
+            Ok(quote! {
+                #[doc = #doc]
+                #[inline]
+                #vis fn #archived_name(&self) -> &#field_ty {
+                    &self.#archived_name
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #archived_type #where_clause {
+            #(#getters)*
+        }
+    })
+}
+
+fn generate_setters_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let Fields::Named(fields) = fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "setters can only be generated for structs with named fields",
+        ));
+    };
+
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    let setters = fields
+        .named
+        .iter()
+        .map(|field| {
+            let field_name = field.ident.as_ref().unwrap();
+            let archived_name = archived_field_name(field)?;
+            let setter_name =
+                format_ident!("set_{}", strip_raw(&archived_name));
+            let ty = &field.ty;
+            let field_ty = archived(&printing.rkyv_path, field)?;
+            let doc = format!(
+                "Sets the archived counterpart of `{}::{}` to the given value.",
+                name,
+                strip_raw(field_name),
+            );
+            // The bounds are higher-ranked so that they aren't rejected when
+            // they can't be satisfied. The setter just can't be called then.
+            Ok(quote! {
+                #[doc = #doc]
+                #[inline]
+                #vis fn #setter_name(
+                    self: ::core::pin::Pin<&mut Self>,
+                    value: #ty,
+                )
+                where
+                    for<'__a> #field_ty: ::core::convert::From<#ty>
+                        + ::core::marker::Unpin,
+                {
+                    // SAFETY: The field is `Unpin`, so it isn't structurally
+                    // pinned and may be replaced.
+                    let this = unsafe { self.get_unchecked_mut() };
+                    this.#archived_name = value.into();
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #archived_type #where_clause {
+            #(#setters)*
+        }
+    })
+}
+
+/// Returns the members of the original type and the corresponding members of
+/// the archived type.
+fn split_members(fields: &Fields) -> Result<(Vec<Member>, Vec<Member>), Error> {
+    let pairs = members(fields)
+        .map(|(member, field)| {
+            let archived_member = archived_member(&member, field)?;
+            Ok((member, archived_member))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(pairs.into_iter().unzip())
+}
+
 fn generate_partial_eq_impl(
     input: &DeriveInput,
     fields: &Fields,
@@ -356,7 +609,7 @@ fn generate_partial_eq_impl(
     let mut partial_eq_where =
         input.generics.where_clause.as_ref().unwrap().clone();
 
-    for field in fields.iter().filter(is_not_omitted) {
+    for field in bound_fields(input, fields) {
         let ty = &field.ty;
         let archived_ty = archived(&printing.rkyv_path, field)?;
         partial_eq_where
@@ -364,7 +617,7 @@ fn generate_partial_eq_impl(
             .push(parse_quote! { #archived_ty: PartialEq<#ty> });
     }
 
-    let members = members(fields).map(|(member, _)| member);
+    let (members, archived_members) = split_members(fields)?;
 
     let archived_type = &printing.archived_type;
     let name = &input.ident;
@@ -375,7 +628,7 @@ fn generate_partial_eq_impl(
         #partial_eq_where
         {
             fn eq(&self, other: &#archived_type) -> bool {
-                true #(&& other.#members.eq(&self.#members))*
+                true #(&& other.#archived_members.eq(&self.#members))*
             }
         }
 
@@ -397,7 +650,7 @@ fn generate_partial_ord_impl(
     let mut partial_ord_where =
         input.generics.where_clause.as_ref().unwrap().clone();
 
-    for field in fields.iter().filter(is_not_omitted) {
+    for field in bound_fields(input, fields) {
         let ty = &field.ty;
         let archived_ty = archived(&printing.rkyv_path, field)?;
         partial_ord_where
@@ -405,7 +658,7 @@ fn generate_partial_ord_impl(
             .push(parse_quote! { #archived_ty: PartialOrd<#ty> });
     }
 
-    let members = members(fields).map(|(member, _)| member);
+    let (members, archived_members) = split_members(fields)?;
 
     let archived_type = &printing.archived_type;
     let name = &input.ident;
@@ -420,7 +673,9 @@ fn generate_partial_ord_impl(
                 other: &#archived_type,
             ) -> Option<::core::cmp::Ordering> {
                 #(
-                    match other.#members.partial_cmp(&self.#members) {
+                    match other.#archived_members.partial_cmp(
+                        &self.#members,
+                    ) {
                         Some(::core::cmp::Ordering::Equal) => (),
                         x => return x,
                     }
@@ -441,3 +696,532 @@ fn generate_partial_ord_impl(
         }
     })
 }
+
+fn generate_hash_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let mut hash_where = input.generics.where_clause.as_ref().unwrap().clone();
+
+    for field in bound_fields(input, fields) {
+        let archived_ty = archived(&printing.rkyv_path, field)?;
+        hash_where
+            .predicates
+            .push(parse_quote! { #archived_ty: ::core::hash::Hash });
+    }
+
+    // Fields are hashed in declaration order, the same as the derived `Hash`
+    // implementation for the original type.
+    let (_, archived_members) = split_members(fields)?;
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ..) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #archived_type #hash_where {
+            fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) {
+                #(::core::hash::Hash::hash(&self.#archived_members, state);)*
+            }
+        }
+    })
+}
+
+fn generate_serde_impls(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut serde_where = input.generics.where_clause.as_ref().unwrap().clone();
+    for field in bound_fields(input, fields) {
+        let archived_ty = archived(rkyv_path, field)?;
+        serde_where.predicates.push(parse_quote! {
+            #archived_ty: #rkyv_path::serde::SerializeArchived
+        });
+    }
+
+    // The archived type is serialized as the original type would be.
+    let name = strip_raw(&input.ident);
+    let (members, archived_members) = split_members(fields)?;
+    let len = members.len();
+
+    let body = match fields {
+        Fields::Named(_) => {
+            let field_names = members.iter().map(|member| match member {
+                Member::Named(ident) => strip_raw(ident),
+                Member::Unnamed(_) => unreachable!(),
+            });
+            quote! {
+                use #rkyv_path::serde::ser::SerializeStruct as _;
+
+                let mut state = serializer.serialize_struct(#name, #len)?;
+                #(
+                    state.serialize_field(
+                        #field_names,
+                        &#rkyv_path::serde::AsSerde(&self.#archived_members),
+                    )?;
+                )*
+                state.end()
+            }
+        }
+        Fields::Unnamed(_) if len == 1 => quote! {
+            serializer.serialize_newtype_struct(
+                #name,
+                &#rkyv_path::serde::AsSerde(&self.0),
+            )
+        },
+        Fields::Unnamed(_) => quote! {
+            use #rkyv_path::serde::ser::SerializeTupleStruct as _;
+
+            let mut state = serializer.serialize_tuple_struct(#name, #len)?;
+            #(
+                state.serialize_field(
+                    &#rkyv_path::serde::AsSerde(&self.#archived_members),
+                )?;
+            )*
+            state.end()
+        },
+        Fields::Unit => quote! {
+            serializer.serialize_unit_struct(#name)
+        },
+    };
+
+    Ok(serde_impls(input, printing, &serde_where, body))
+}
+
+fn generate_schema_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+    let archived_type = &printing.archived_type;
+
+    let field_schemas = members(fields)
+        .map(|(member, field)| {
+            let archived_member = archived_member(&member, field)?;
+            let archived_ty = archived(rkyv_path, field)?;
+            let name = match archived_member {
+                Member::Named(ref ident) => strip_raw(ident),
+                Member::Unnamed(ref index) => index.index.to_string(),
+            };
+            Ok(quote! {
+                #rkyv_path::schema::FieldSchema {
+                    name: #name,
+                    type_name: ::core::any::type_name::<#archived_ty>,
+                    offset: ::core::mem::offset_of!(Self, #archived_member),
+                    size: ::core::mem::size_of::<#archived_ty>(),
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        // SAFETY: The schema is built from the offsets and sizes of the fields
+        // of the archived type.
+        unsafe impl #impl_generics #rkyv_path::schema::Schema
+            for #archived_type
+        #where_clause
+        {
+            const SCHEMA: #rkyv_path::schema::TypeSchema =
+                #rkyv_path::schema::TypeSchema {
+                    type_name: ::core::any::type_name::<Self>,
+                    size: ::core::mem::size_of::<Self>(),
+                    align: ::core::mem::align_of::<Self>(),
+                    kind: #rkyv_path::schema::SchemaKind::Struct(&[
+                        #(#field_schemas,)*
+                    ]),
+                };
+        }
+    })
+}
+
+fn generate_walk_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    // The bounds are higher-ranked so that the impl is omitted instead of
+    // failing to compile when a field can't be walked.
+    let mut walk_where = input.generics.where_clause.as_ref().unwrap().clone();
+    for field in bound_fields(input, fields) {
+        let archived_ty = archived(rkyv_path, field)?;
+        walk_where.predicates.push(parse_quote! {
+            for<'__a> #archived_ty: #rkyv_path::walk::Walk
+        });
+    }
+
+    let name = strip_raw(&input.ident);
+    let walk_fields = members(fields)
+        .map(|(member, field)| {
+            let archived_member = archived_member(&member, field)?;
+            let field_name = match archived_member {
+                Member::Named(ref ident) => strip_raw(ident),
+                Member::Unnamed(ref index) => index.index.to_string(),
+            };
+            Ok(quote! {
+                visitor.visit_field(#field_name);
+                #rkyv_path::walk::Walk::walk(&self.#archived_member, visitor);
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ..) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::walk::Walk for #archived_type
+        #walk_where
+        {
+            fn walk(&self, visitor: &mut dyn #rkyv_path::walk::Visitor) {
+                visitor.begin_struct(#name);
+                #(#walk_fields)*
+                visitor.end_struct();
+            }
+        }
+    })
+}
+
+fn generate_swap_endian_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut swap_endian_where =
+        input.generics.where_clause.as_ref().unwrap().clone();
+
+    // Fields with a fixed byte order are never converted.
+    let mut swapped = Vec::new();
+    for (member, field) in members(fields) {
+        if !has_fixed_endianness(field)? {
+            swapped.push((archived_member(&member, field)?, field));
+        }
+    }
+
+    for field in bound_fields(input, swapped.iter().map(|(_, f)| *f)) {
+        let archived_ty = archived(rkyv_path, field)?;
+        swap_endian_where.predicates.push(parse_quote! {
+            #archived_ty: #rkyv_path::endian::SwapEndian
+        });
+    }
+
+    let swap_statements = swapped
+        .into_iter()
+        .map(|(member, field)| {
+            let archived_ty = archived(rkyv_path, field)?;
+            Ok(quote! {
+                <#archived_ty as #rkyv_path::endian::SwapEndian>::swap_endian(
+                    ::core::ptr::addr_of_mut!((*this).#member),
+                );
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let body = (!swap_statements.is_empty()).then(|| {
+        quote! {
+            unsafe {
+                #(#swap_statements)*
+            }
+        }
+    });
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ..) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        // SAFETY: Every field of the archived type is converted.
+        unsafe impl #impl_generics #rkyv_path::endian::SwapEndian
+            for #archived_type
+        #swap_endian_where
+        {
+            #[allow(unused_variables)]
+            unsafe fn swap_endian(this: *mut Self) {
+                #body
+            }
+        }
+    })
+}
+
+fn generate_relocate_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut relocate_where =
+        input.generics.where_clause.as_ref().unwrap().clone();
+    for field in bound_fields(input, fields) {
+        let archived_ty = archived(rkyv_path, field)?;
+        relocate_where.predicates.push(parse_quote! {
+            #archived_ty: #rkyv_path::relocate::Relocate
+        });
+    }
+
+    let relocate_statements = members(fields)
+        .map(|(member, field)| {
+            let member = archived_member(&member, field)?;
+            let archived_ty = archived(rkyv_path, field)?;
+            Ok(quote! {
+                <#archived_ty as #rkyv_path::relocate::Relocate>::relocate(
+                    ::core::ptr::addr_of_mut!((*this).#member),
+                    base,
+                    mode,
+                )?;
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let body = (!relocate_statements.is_empty()).then(|| {
+        quote! {
+            unsafe {
+                #(#relocate_statements)*
+            }
+        }
+    });
+
+    let archived_type = &printing.archived_type;
+    let (impl_generics, ..) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        // SAFETY: Every field of the archived type is converted.
+        unsafe impl #impl_generics #rkyv_path::relocate::Relocate
+            for #archived_type
+        #relocate_where
+        {
+            #[allow(unused_variables)]
+            unsafe fn relocate<E: #rkyv_path::rancor::Source>(
+                this: *mut Self,
+                base: *mut u8,
+                mode: #rkyv_path::relocate::OffsetMode,
+            ) -> ::core::result::Result<(), E> {
+                #body
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+fn generate_splice_impl(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    if let Some(ref path) = attributes.length_prefixed {
+        return Err(Error::new_spanned(
+            path,
+            "splice can't be implemented for length_prefixed structs",
+        ));
+    }
+
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut splice_where =
+        input.generics.where_clause.as_ref().unwrap().clone();
+    for field in bound_fields(input, fields) {
+        let ty = &field.ty;
+        splice_where.predicates.push(parse_quote! {
+            #ty: #rkyv_path::splice::Splice<__S>
+        });
+    }
+
+    let mut resolver_tys = Vec::new();
+    let mut splice_exprs = Vec::new();
+    let mut resolve_statements = Vec::new();
+    for (i, (member, field)) in members(fields).enumerate() {
+        if let Some(with) =
+            field.attrs.iter().find(|attr| attr.path().is_ident("with"))
+        {
+            return Err(Error::new_spanned(
+                with,
+                "splice can't be implemented for structs with wrapped fields",
+            ));
+        }
+
+        let ty = &field.ty;
+        let archived_member = archived_member(&member, field)?;
+        let index = Index::from(i);
+        resolver_tys.push(quote! {
+            #rkyv_path::splice::Spliced<
+                <#ty as #rkyv_path::splice::Splice<__S>>::SpliceResolver
+            >
+        });
+        splice_exprs.push(quote! {
+            edits.splice::<#ty>(&archived.#archived_member, serializer)?
+        });
+        resolve_statements.push(quote! {
+            let field_ptr = unsafe {
+                ::core::ptr::addr_of_mut!((*out.ptr()).#archived_member)
+            };
+            let out_field = unsafe {
+                #rkyv_path::Place::from_field_unchecked(out, field_ptr)
+            };
+            edits.resolve::<#ty>(
+                &archived.#archived_member,
+                resolver.#index,
+                out_field,
+            );
+        });
+    }
+
+    let mut impl_params = Punctuated::<_, Token![,]>::new();
+    impl_params.push(parse_quote! {
+        __S: #rkyv_path::rancor::Fallible + ?Sized
+    });
+    impl_params.extend(input.generics.params.iter().cloned());
+    let impl_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_params,
+        gt_token: Some(Default::default()),
+        where_clause: None,
+    };
+
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::splice::Splice<__S>
+            for #name #ty_generics
+        #splice_where
+        {
+            type SpliceResolver = (#(#resolver_tys,)*);
+
+            #[allow(unused_variables)]
+            fn splice(
+                archived: &Self::Archived,
+                serializer: &mut __S,
+                edits: &mut #rkyv_path::splice::Edits<'_, __S>,
+            ) -> ::core::result::Result<
+                Self::SpliceResolver,
+                <__S as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                Ok((#(#splice_exprs,)*))
+            }
+
+            #[allow(unused_variables)]
+            fn resolve_spliced(
+                archived: &Self::Archived,
+                resolver: Self::SpliceResolver,
+                out: #rkyv_path::Place<Self::Archived>,
+                edits: &mut #rkyv_path::splice::Edits<'_, __S>,
+            ) {
+                #(#resolve_statements)*
+            }
+        }
+    })
+}
+
+fn generate_check_prefix_impl(
+    input: &DeriveInput,
+    fields: &Fields,
+    printing: &Printing,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = &printing.rkyv_path;
+
+    let mut check_where = input.generics.where_clause.as_ref().unwrap().clone();
+    for field in bound_fields(input, fields) {
+        let archived_ty = archived(rkyv_path, field)?;
+        check_where.predicates.push(parse_quote! {
+            #archived_ty: #rkyv_path::bytecheck::CheckBytes<__C>
+        });
+    }
+    if has_recursive_fields(input) {
+        check_where.predicates.push(parse_quote! {
+            __C: #rkyv_path::validation::ArchiveContext
+                + #rkyv_path::validation::SharedContext
+        });
+        check_where.predicates.push(parse_quote! {
+            <__C as #rkyv_path::rancor::Fallible>::Error:
+                #rkyv_path::rancor::Source
+        });
+    }
+
+    let mut min_len = Vec::new();
+    let mut check_fields = Vec::new();
+    for field in fields.iter() {
+        let archived_ty = archived(rkyv_path, field)?;
+        let archived_name = archived_field_name(field)?;
+        let end = quote! {
+            ::core::mem::offset_of!(Self, #archived_name)
+                + ::core::mem::size_of::<#archived_ty>()
+        };
+
+        if parse_default(&field.attrs)?.is_none() {
+            min_len.push(quote! {
+                let end = #end;
+                let min_len = if end > min_len { end } else { min_len };
+            });
+        }
+        check_fields.push(quote! {
+            if #end <= len {
+                let offset = ::core::mem::offset_of!(Self, #archived_name);
+                <#archived_ty as #rkyv_path::bytecheck::CheckBytes<__C>>
+                    ::check_bytes(
+                        value.cast::<u8>().add(offset).cast(),
+                        context,
+                    )?;
+            }
+        });
+    }
+
+    let check_body = (!check_fields.is_empty()).then(|| {
+        quote! {
+            unsafe {
+                #(#check_fields)*
+            }
+        }
+    });
+
+    let mut impl_params = Punctuated::<_, Token![,]>::new();
+    impl_params.push(parse_quote! {
+        __C: #rkyv_path::rancor::Fallible + ?Sized
+    });
+    for param in input.generics.params.iter() {
+        impl_params.push(param.clone());
+    }
+    let impl_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_params,
+        gt_token: Some(Default::default()),
+        where_clause: None,
+    };
+    let (impl_generics, ..) = impl_generics.split_for_impl();
+    let archived_type = &printing.archived_type;
+
+    Ok(quote! {
+        // SAFETY: `MIN_LEN` covers every field without a default, and every
+        // field which lies within `len` bytes is checked.
+        unsafe impl #impl_generics #rkyv_path::prefixed::CheckPrefix<__C>
+            for #archived_type
+        #check_where
+        {
+            const MIN_LEN: usize = {
+                let min_len = 0;
+                #(#min_len)*
+                min_len
+            };
+
+            #[allow(unused_variables)]
+            unsafe fn check_prefix(
+                value: *const Self,
+                len: usize,
+                context: &mut __C,
+            ) -> ::core::result::Result<
+                (),
+                <__C as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                #check_body
+                Ok(())
+            }
+        }
+    })
+}
@@ -1,8 +1,8 @@
 use quote::ToTokens;
 use syn::{
     meta::ParseNestedMeta, parenthesized, parse::Parse, parse_quote,
-    punctuated::Punctuated, AttrStyle, DeriveInput, Error, Ident, LitStr, Meta,
-    Path, Token, WherePredicate,
+    punctuated::Punctuated, AttrStyle, Attribute, DeriveInput, Error, Expr,
+    Ident, LitStr, Meta, Path, Token, Type, WherePredicate,
 };
 
 fn try_set_attribute<T: ToTokens>(
@@ -21,6 +21,17 @@ fn try_set_attribute<T: ToTokens>(
     }
 }
 
+pub struct Projection {
+    pub name: Ident,
+    pub fields: Punctuated<Ident, Token![,]>,
+}
+
+pub struct LayoutAssertion {
+    pub path: Path,
+    pub size: Option<Expr>,
+    pub align: Option<Expr>,
+}
+
 #[derive(Default)]
 pub struct Attributes {
     pub archive_as: Option<LitStr>,
@@ -28,11 +39,26 @@ pub struct Attributes {
     pub resolver: Option<Ident>,
     pub attrs: Vec<Meta>,
     pub compares: Option<Punctuated<Path, Token![,]>>,
+    pub repr: Option<Ident>,
     pub archive_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
     pub serialize_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
     pub deserialize_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
     pub check_bytes: Option<Path>,
+    pub swap_endian: Option<Path>,
+    pub relocate: Option<Path>,
+    pub getters: Option<Path>,
+    pub setters: Option<Path>,
+    pub from_archived: Option<Path>,
+    pub derive_serde: Option<Path>,
+    pub length_prefixed: Option<Path>,
+    pub pack: Option<Path>,
+    pub schema: Option<Path>,
+    pub splice: Option<Path>,
+    pub assert_layout: Option<LayoutAssertion>,
     pub crate_path: Option<Path>,
+    pub projections: Vec<Projection>,
+    pub borrow_from: Option<Type>,
+    pub with: Option<Type>,
 }
 
 impl Attributes {
@@ -43,6 +69,75 @@ impl Attributes {
             }
 
             try_set_attribute(&mut self.check_bytes, meta.path, "check_bytes")
+        } else if meta.path.is_ident("swap_endian") {
+            if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+                return Err(meta.error("swap_endian argument must be a path"));
+            }
+
+            try_set_attribute(&mut self.swap_endian, meta.path, "swap_endian")
+        } else if meta.path.is_ident("relocate") {
+            try_set_attribute(&mut self.relocate, meta.path, "relocate")
+        } else if meta.path.is_ident("getters") {
+            try_set_attribute(&mut self.getters, meta.path, "getters")
+        } else if meta.path.is_ident("setters") {
+            try_set_attribute(&mut self.setters, meta.path, "setters")
+        } else if meta.path.is_ident("derive_serde") {
+            try_set_attribute(&mut self.derive_serde, meta.path, "derive_serde")
+        } else if meta.path.is_ident("length_prefixed") {
+            try_set_attribute(
+                &mut self.length_prefixed,
+                meta.path,
+                "length_prefixed",
+            )
+        } else if meta.path.is_ident("pack") {
+            try_set_attribute(&mut self.pack, meta.path, "pack")
+        } else if meta.path.is_ident("schema") {
+            try_set_attribute(&mut self.schema, meta.path, "schema")
+        } else if meta.path.is_ident("splice") {
+            try_set_attribute(&mut self.splice, meta.path, "splice")
+        } else if meta.path.is_ident("from_archived") {
+            try_set_attribute(
+                &mut self.from_archived,
+                meta.path,
+                "from_archived",
+            )
+        } else if meta.path.is_ident("assert_layout") {
+            if self.assert_layout.is_some() {
+                return Err(meta.error("assert_layout already specified"));
+            }
+
+            let mut size = None;
+            let mut align = None;
+            meta.parse_nested_meta(|meta| {
+                if meta.path.is_ident("size") {
+                    try_set_attribute(&mut size, meta.value()?.parse()?, "size")
+                } else if meta.path.is_ident("align") {
+                    try_set_attribute(
+                        &mut align,
+                        meta.value()?.parse()?,
+                        "align",
+                    )
+                } else {
+                    Err(meta.error("expected `size = ...` or `align = ...`"))
+                }
+            })?;
+            self.assert_layout = Some(LayoutAssertion {
+                path: meta.path,
+                size,
+                align,
+            });
+            Ok(())
+        } else if meta.path.is_ident("repr") {
+            let content;
+            parenthesized!(content in meta.input);
+            let repr = content.parse::<Ident>()?;
+            if repr != "u8" && repr != "u16" && repr != "u32" {
+                return Err(Error::new_spanned(
+                    repr,
+                    "expected `u8`, `u16`, or `u32`",
+                ));
+            }
+            try_set_attribute(&mut self.repr, repr, "repr")
         } else if meta.path.is_ident("compare") {
             let traits;
             parenthesized!(traits in meta.input);
@@ -78,6 +173,24 @@ impl Attributes {
                 clauses,
                 "deserialize_bounds",
             )
+        } else if meta.path.is_ident("project") {
+            meta.parse_nested_meta(|meta| {
+                let name = meta.path.require_ident()?.clone();
+                let fields;
+                parenthesized!(fields in meta.input);
+                let fields =
+                    fields.parse_terminated(Ident::parse, Token![,])?;
+                self.projections.push(Projection { name, fields });
+                Ok(())
+            })
+        } else if meta.path.is_ident("borrow_from") {
+            try_set_attribute(
+                &mut self.borrow_from,
+                meta.value()?.parse()?,
+                "borrow_from",
+            )
+        } else if meta.path.is_ident("with") {
+            try_set_attribute(&mut self.with, meta.value()?.parse()?, "with")
         } else if meta.path.is_ident("archived") {
             try_set_attribute(
                 &mut self.archived,
@@ -121,7 +234,7 @@ impl Attributes {
                 continue;
             }
 
-            if attr.path().is_ident("archive") {
+            if attr.path().is_ident("archive") || attr.path().is_ident("rkyv") {
                 attr.parse_nested_meta(|meta| result.parse_meta(meta))?;
             } else if attr.path().is_ident("archive_attr") {
                 result.attrs.extend(
@@ -142,3 +255,60 @@ impl Attributes {
             .unwrap_or_else(|| parse_quote! { ::rkyv })
     }
 }
+
+/// The attributes given to a field or variant with `#[rkyv(...)]`.
+#[derive(Default)]
+pub struct FieldAttributes {
+    pub rename: Option<Ident>,
+    pub default: Option<(Path, Expr)>,
+}
+
+impl FieldAttributes {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut result = Self::default();
+        for attr in attrs.iter() {
+            if !matches!(attr.style, AttrStyle::Outer)
+                || !attr.path().is_ident("rkyv")
+            {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let name =
+                        meta.value()?.parse::<LitStr>()?.parse::<Ident>()?;
+                    try_set_attribute(&mut result.rename, name, "rename")
+                } else if meta.path.is_ident("default") {
+                    if result.default.is_some() {
+                        return Err(meta.error("default already specified"));
+                    }
+
+                    let expr = if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<LitStr>()?.parse::<Expr>()?
+                    } else {
+                        parse_quote! { ::core::default::Default::default() }
+                    };
+                    result.default = Some((meta.path, expr));
+                    Ok(())
+                } else {
+                    Err(meta.error("unrecognized rkyv argument"))
+                }
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// Parses the name given to a field or variant with `#[rkyv(rename = "...")]`.
+pub fn parse_rename(attrs: &[Attribute]) -> Result<Option<Ident>, Error> {
+    Ok(FieldAttributes::parse(attrs)?.rename)
+}
+
+/// Parses the default given to a field with `#[rkyv(default)]` or
+/// `#[rkyv(default = "...")]`.
+pub fn parse_default(
+    attrs: &[Attribute],
+) -> Result<Option<(Path, Expr)>, Error> {
+    Ok(FieldAttributes::parse(attrs)?.default)
+}
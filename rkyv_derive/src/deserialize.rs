@@ -2,24 +2,112 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
     parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput,
-    Error, Fields, Generics, Ident, Index,
+    Error, Field, Fields, Generics, Ident, Index, Path, Type, WhereClause,
 };
 
 use crate::{
-    attributes::Attributes,
-    util::{archive_bound, deserialize, deserialize_bound, is_not_omitted},
+    attributes::{parse_default, Attributes, Projection},
+    util::{
+        archive_bound, archived, archived_field_name, archived_variant,
+        bound_fields, deserialize, deserialize_bound, has_recursive_fields,
+    },
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let attributes = Attributes::parse(&input)?;
+    if let Some(ref with) = attributes.with {
+        return derive_deserialize_with_impl(input, &attributes, with);
+    }
     derive_deserialize_impl(input, &attributes)
 }
 
+/// Implements `Deserialize` for a type which is archived through the wrapper
+/// given with `#[archive(with = ...)]`.
+fn derive_deserialize_with_impl(
+    mut input: DeriveInput,
+    attributes: &Attributes,
+    with: &Type,
+) -> Result<TokenStream, Error> {
+    if let Some(projection) = attributes.projections.first() {
+        return Err(Error::new_spanned(
+            &projection.name,
+            "projections can not be derived for types archived with with = ...",
+        ));
+    }
+    if let Some(ref path) = attributes.from_archived {
+        return Err(Error::new_spanned(
+            path,
+            "from_archived may not be used with with = ...",
+        ));
+    }
+
+    let rkyv_path = attributes.crate_path();
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ty = quote! { #name #ty_generics };
+    let archived = quote! {
+        <#with as #rkyv_path::with::ArchiveWith<#ty>>::Archived
+    };
+
+    let where_clause = input.generics.make_where_clause();
+    for bounds in [&attributes.archive_bounds, &attributes.deserialize_bounds]
+        .into_iter()
+        .flatten()
+    {
+        where_clause.predicates.extend(bounds.iter().cloned());
+    }
+    where_clause.predicates.push(parse_quote! {
+        #with: #rkyv_path::with::ArchiveWith<#ty>
+            + #rkyv_path::with::DeserializeWith<
+                #archived,
+                #ty,
+                __D,
+            >
+    });
+
+    let mut impl_input_params = Punctuated::default();
+    impl_input_params
+        .push(parse_quote! { __D: #rkyv_path::rancor::Fallible + ?Sized });
+    for param in input.generics.params.iter() {
+        impl_input_params.push(param.clone());
+    }
+    let impl_input_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_input_params,
+        gt_token: Some(Default::default()),
+        where_clause: input.generics.where_clause.clone(),
+    };
+    let (impl_generics, _, where_clause) = impl_input_generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::Deserialize<#ty, __D>
+            for #archived
+        #where_clause
+        {
+            fn deserialize(
+                &self,
+                deserializer: &mut __D,
+            ) -> ::core::result::Result<
+                #ty,
+                <__D as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                <#with as #rkyv_path::with::DeserializeWith<
+                    Self,
+                    #ty,
+                    __D,
+                >>::deserialize_with(self, deserializer)
+            }
+        }
+    })
+}
+
 fn derive_deserialize_impl(
     mut input: DeriveInput,
     attributes: &Attributes,
 ) -> Result<TokenStream, Error> {
     let rkyv_path = attributes.crate_path();
+    let is_recursive = has_recursive_fields(&input);
 
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bounds {
@@ -27,10 +115,23 @@ fn derive_deserialize_impl(
             where_clause.predicates.push(bound.clone());
         }
     }
+    // The deserialize bounds may refer to `__D`, so they can't be used for the
+    // `From` impl.
+    let mut from_archived_where = where_clause.clone();
     if let Some(ref bounds) = attributes.deserialize_bounds {
         for bound in bounds {
             where_clause.predicates.push(bound.clone());
         }
+    } else if is_recursive {
+        // Recursive fields don't get bounds, so require what rkyv's own
+        // containers need from the deserializer instead.
+        where_clause.predicates.push(parse_quote! {
+            __D: #rkyv_path::de::Pooling
+        });
+        where_clause.predicates.push(parse_quote! {
+            <__D as #rkyv_path::rancor::Fallible>::Error:
+                #rkyv_path::rancor::Source
+        });
     }
 
     let mut impl_input_params = Punctuated::default();
@@ -55,7 +156,7 @@ fn derive_deserialize_impl(
         Data::Struct(ref data) => match data.fields {
             Fields::Named(ref fields) => {
                 let mut deserialize_where = where_clause.clone();
-                for field in fields.named.iter().filter(is_not_omitted) {
+                for field in bound_fields(&input, &fields.named) {
                     deserialize_where
                         .predicates
                         .push(archive_bound(&rkyv_path, field)?);
@@ -69,10 +170,23 @@ fn derive_deserialize_impl(
                     .iter()
                     .map(|field| {
                         let name = &field.ident;
+                        let archived_name = archived_field_name(field)?;
                         let deserialize = deserialize(&rkyv_path, field)?;
-                        Ok(quote! {
-                            #name: #deserialize(&self.#name, deserializer)?
-                        })
+                        if attributes.length_prefixed.is_some() {
+                            deserialize_prefixed_field(
+                                &rkyv_path,
+                                &input,
+                                field,
+                                &deserialize,
+                            )
+                        } else {
+                            Ok(quote! {
+                                #name: #deserialize(
+                                    &self.#archived_name,
+                                    deserializer,
+                                )?
+                            })
+                        }
                     })
                     .collect::<Result<Vec<_>, Error>>()?;
 
@@ -98,7 +212,7 @@ fn derive_deserialize_impl(
             }
             Fields::Unnamed(ref fields) => {
                 let mut deserialize_where = where_clause.clone();
-                for field in fields.unnamed.iter().filter(is_not_omitted) {
+                for field in bound_fields(&input, &fields.unnamed) {
                     deserialize_where
                         .predicates
                         .push(archive_bound(&rkyv_path, field)?);
@@ -166,8 +280,7 @@ fn derive_deserialize_impl(
             for variant in data.variants.iter() {
                 match variant.fields {
                     Fields::Named(ref fields) => {
-                        for field in fields.named.iter().filter(is_not_omitted)
-                        {
+                        for field in bound_fields(&input, &fields.named) {
                             deserialize_where
                                 .predicates
                                 .push(archive_bound(&rkyv_path, field)?);
@@ -177,9 +290,7 @@ fn derive_deserialize_impl(
                         }
                     }
                     Fields::Unnamed(ref fields) => {
-                        for field in
-                            fields.unnamed.iter().filter(is_not_omitted)
-                        {
+                        for field in bound_fields(&input, &fields.unnamed) {
                             deserialize_where
                                 .predicates
                                 .push(archive_bound(&rkyv_path, field)?);
@@ -197,12 +308,23 @@ fn derive_deserialize_impl(
                 .iter()
                 .map(|v| {
                     let variant = &v.ident;
+                    let archived_variant = archived_variant(v)?;
                     match v.fields {
                         Fields::Named(ref fields) => {
-                            let bindings = fields.named.iter().map(|field| {
-                                let name = &field.ident;
-                                quote! { #name }
-                            });
+                            let bindings = fields
+                                .named
+                                .iter()
+                                .map(|field| {
+                                    let name = &field.ident;
+                                    let archived_name =
+                                        archived_field_name(field)?;
+                                    if Some(&archived_name) == name.as_ref() {
+                                        Ok(quote! { #name })
+                                    } else {
+                                        Ok(quote! { #archived_name: #name })
+                                    }
+                                })
+                                .collect::<Result<Vec<_>, Error>>()?;
                             let fields = fields
                                 .named
                                 .iter()
@@ -219,7 +341,7 @@ fn derive_deserialize_impl(
                                 })
                                 .collect::<Result<Vec<_>, Error>>()?;
                             Ok(quote! {
-                                Self::#variant {
+                                Self::#archived_variant {
                                     #(#bindings,)*
                                 } => #name::#variant { #(#fields,)* }
                             })
@@ -251,14 +373,14 @@ fn derive_deserialize_impl(
                                 })
                                 .collect::<Result<Vec<_>, Error>>()?;
                             Ok(quote! {
-                                Self::#variant(
+                                Self::#archived_variant(
                                     #(#bindings,)*
                                 ) => #name::#variant(#(#fields,)*)
                             })
                         }
-                        Fields::Unit => {
-                            Ok(quote! { Self::#variant => #name::#variant })
-                        }
+                        Fields::Unit => Ok(quote! {
+                            Self::#archived_variant => #name::#variant
+                        }),
                     }
                 })
                 .collect::<Result<Vec<_>, Error>>()?;
@@ -291,8 +413,242 @@ fn derive_deserialize_impl(
         }
     };
 
+    let projections = attributes
+        .projections
+        .iter()
+        .map(|projection| {
+            derive_projection(
+                &input,
+                &rkyv_path,
+                &impl_generics,
+                where_clause,
+                projection,
+                attributes.length_prefixed.is_some(),
+            )
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let from_archived_impl = if attributes.from_archived.is_some() {
+        let fields: Vec<_> = match input.data {
+            Data::Struct(ref data) => data.fields.iter().collect(),
+            Data::Enum(ref data) => {
+                data.variants.iter().flat_map(|v| v.fields.iter()).collect()
+            }
+            Data::Union(_) => unreachable!(),
+        };
+        for field in bound_fields(&input, fields) {
+            from_archived_where
+                .predicates
+                .push(archive_bound(&rkyv_path, field)?);
+        }
+        from_archived_where.predicates.push(parse_quote! {
+            #rkyv_path::Archived<#name #ty_generics>: #rkyv_path::Deserialize<
+                #name #ty_generics,
+                #rkyv_path::rancor::Strategy<
+                    #rkyv_path::de::Unpool,
+                    #rkyv_path::rancor::Infallible,
+                >,
+            >
+        });
+        let (impl_generics, ..) = input.generics.split_for_impl();
+        Some(quote! {
+            #[automatically_derived]
+            impl #impl_generics
+                ::core::convert::From<
+                    &#rkyv_path::Archived<#name #ty_generics>
+                >
+                for #name #ty_generics
+            #from_archived_where
+            {
+                fn from(
+                    archived: &#rkyv_path::Archived<#name #ty_generics>,
+                ) -> Self {
+                    use #rkyv_path::rancor::ResultExt as _;
+
+                    #rkyv_path::util::deserialize::<
+                        Self,
+                        _,
+                        #rkyv_path::rancor::Infallible,
+                    >(archived, &mut #rkyv_path::de::Unpool)
+                    .always_ok()
+                }
+            }
+        })
+    } else {
+        None
+    };
+
     Ok(quote! {
         #[automatically_derived]
         #deserialize_impl
+
+        #(#projections)*
+
+        #from_archived_impl
+    })
+}
+
+/// Deserializes a field of a length-prefixed struct, which may be missing if
+/// it has a default.
+fn deserialize_prefixed_field(
+    rkyv_path: &Path,
+    input: &DeriveInput,
+    field: &Field,
+    deserialize: &TokenStream,
+) -> Result<TokenStream, Error> {
+    let name = &field.ident;
+    let archived_name = archived_field_name(field)?;
+    let archived_ty = archived(rkyv_path, field)?;
+    let ty_name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let offset = quote! {
+        ::core::mem::offset_of!(
+            <#ty_name #ty_generics as #rkyv_path::prefixed::ArchiveFields>
+                ::Fields,
+            #archived_name
+        )
+    };
+
+    // SAFETY: `offset` is the offset of the field in the archived fields, and
+    // fields without defaults are always present in valid archives.
+    Ok(match parse_default(&field.attrs)? {
+        Some((_, default)) => quote! {
+            #name: match unsafe {
+                self.field::<#archived_ty>(#offset)
+            } {
+                Some(field) => #deserialize(field, deserializer)?,
+                None => #default,
+            }
+        },
+        None => quote! {
+            #name: #deserialize(
+                unsafe { self.field_unchecked::<#archived_ty>(#offset) },
+                deserializer,
+            )?
+        },
+    })
+}
+
+fn derive_projection(
+    input: &DeriveInput,
+    rkyv_path: &Path,
+    impl_generics: &syn::ImplGenerics<'_>,
+    where_clause: &WhereClause,
+    projection: &Projection,
+    is_length_prefixed: bool,
+) -> Result<TokenStream, Error> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields,
+            _ => {
+                return Err(Error::new_spanned(
+                    &projection.name,
+                    "projections can only be derived for structs with named \
+                     fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &projection.name,
+                "projections can only be derived for structs",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &projection.name,
+            "projections can not be derived for generic structs",
+        ));
+    }
+    if is_length_prefixed {
+        return Err(Error::new_spanned(
+            &projection.name,
+            "projections can not be derived for length_prefixed structs",
+        ));
+    }
+
+    let mut selected = Vec::with_capacity(projection.fields.len());
+    for ident in projection.fields.iter() {
+        if selected
+            .iter()
+            .any(|field: &&syn::Field| field.ident.as_ref() == Some(ident))
+        {
+            return Err(Error::new_spanned(
+                ident,
+                "field already included in this projection",
+            ));
+        }
+        let field = fields
+            .named
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(ident))
+            .ok_or_else(|| {
+                Error::new_spanned(ident, "no field with this name")
+            })?;
+        selected.push(field);
+    }
+
+    let mut projection_where = where_clause.clone();
+    for field in bound_fields(input, selected.iter().copied()) {
+        projection_where
+            .predicates
+            .push(archive_bound(rkyv_path, field)?);
+        projection_where
+            .predicates
+            .push(deserialize_bound(rkyv_path, field)?);
+    }
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let projection_name = &projection.name;
+    let doc = format!(
+        "A projection of [`{}`] which can be deserialized from its archived \
+         type.",
+        name,
+    );
+
+    let projection_fields = selected.iter().map(|field| {
+        let vis = &field.vis;
+        let name = &field.ident;
+        let ty = &field.ty;
+        quote! { #vis #name: #ty }
+    });
+    let deserialize_fields = selected
+        .iter()
+        .map(|field| {
+            let name = &field.ident;
+            let archived_name = archived_field_name(field)?;
+            let deserialize = deserialize(rkyv_path, field)?;
+            Ok(quote! {
+                #name: #deserialize(&self.#archived_name, deserializer)?
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #projection_name {
+            #(#projection_fields,)*
+        }
+
+        #[automatically_derived]
+        impl #impl_generics
+            #rkyv_path::DeserializeProjection<#projection_name, __D>
+            for #rkyv_path::Archived<#name>
+        #projection_where
+        {
+            fn deserialize_projection(
+                &self,
+                deserializer: &mut __D,
+            ) -> ::core::result::Result<
+                #projection_name,
+                <__D as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                Ok(#projection_name {
+                    #(#deserialize_fields,)*
+                })
+            }
+        }
     })
 }
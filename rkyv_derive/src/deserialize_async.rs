@@ -0,0 +1,216 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse_quote, punctuated::Punctuated, Data, DeriveInput, Error, Field,
+    Fields, Generics, Path, Variant, WherePredicate,
+};
+
+use crate::{
+    attributes::Attributes,
+    util::{
+        archive_bound, archived, archived_member, archived_variant,
+        bound_fields, deserialize_bound, map_with_or_else, members,
+    },
+};
+
+pub fn derive(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let attributes = Attributes::parse(&input)?;
+    let rkyv_path = attributes.crate_path();
+
+    if let Some(ref with) = attributes.with {
+        return Err(Error::new_spanned(
+            with,
+            "DeserializeAsync can not be derived for types archived with \
+             `with = ...`",
+        ));
+    }
+    if let Some(ref path) = attributes.length_prefixed {
+        return Err(Error::new_spanned(
+            path,
+            "DeserializeAsync can not be derived for length_prefixed structs",
+        ));
+    }
+
+    let fields: Vec<&Field> = match input.data {
+        Data::Struct(ref data) => data.fields.iter().collect(),
+        Data::Enum(ref data) => {
+            data.variants.iter().flat_map(|v| v.fields.iter()).collect()
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "DeserializeAsync cannot be derived for unions",
+            ))
+        }
+    };
+
+    let mut predicates = Vec::new();
+    for field in bound_fields(&input, fields.iter().copied()) {
+        predicates.push(archive_bound(&rkyv_path, field)?);
+        predicates.push(deserialize_async_bound(&rkyv_path, field)?);
+    }
+    let has_fields = !fields.is_empty();
+
+    let where_clause = input.generics.make_where_clause();
+    for bounds in [&attributes.archive_bounds, &attributes.deserialize_bounds]
+        .into_iter()
+        .flatten()
+    {
+        where_clause.predicates.extend(bounds.iter().cloned());
+    }
+    where_clause.predicates.extend(predicates);
+
+    let mut impl_input_params = Punctuated::default();
+    impl_input_params
+        .push(parse_quote! { __D: #rkyv_path::rancor::Fallible + ?Sized });
+    for param in input.generics.params.iter() {
+        impl_input_params.push(param.clone());
+    }
+    let impl_input_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_input_params,
+        gt_token: Some(Default::default()),
+        where_clause: None,
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ..) = impl_input_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match input.data {
+        Data::Struct(ref data) => {
+            let fields = members(&data.fields)
+                .map(|(member, field)| {
+                    let archived_member = archived_member(&member, field)?;
+                    let deserialize = deserialize_async(
+                        &rkyv_path,
+                        field,
+                        quote! { &self.#archived_member },
+                    )?;
+                    Ok(quote! { #member: #deserialize })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            quote! { #name { #(#fields,)* } }
+        }
+        Data::Enum(ref data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|v| deserialize_variant(&rkyv_path, name, v))
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            quote! {
+                match self {
+                    #(#variants,)*
+                }
+            }
+        }
+        Data::Union(_) => unreachable!(),
+    };
+
+    let deserializer = if has_fields {
+        quote! { deserializer }
+    } else {
+        quote! { _ }
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics
+            #rkyv_path::DeserializeAsync<#name #ty_generics, __D>
+            for #rkyv_path::Archived<#name #ty_generics>
+        #where_clause
+        {
+            async fn deserialize_async(
+                &self,
+                #deserializer: &mut __D,
+            ) -> ::core::result::Result<
+                #name #ty_generics,
+                <__D as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                Ok(#body)
+            }
+        }
+    })
+}
+
+/// Returns a match arm which deserializes a variant of the archived enum.
+fn deserialize_variant(
+    rkyv_path: &Path,
+    name: &Ident,
+    variant: &Variant,
+) -> Result<TokenStream, Error> {
+    let ident = &variant.ident;
+    let archived_variant = archived_variant(variant)?;
+    if let Fields::Unit = variant.fields {
+        return Ok(quote! { Self::#archived_variant => #name::#ident });
+    }
+
+    let mut bindings = Vec::new();
+    let mut fields = Vec::new();
+    for (i, (member, field)) in members(&variant.fields).enumerate() {
+        let archived_member = archived_member(&member, field)?;
+        let binding = Ident::new(&format!("__f{}", i), Span::call_site());
+        let deserialize =
+            deserialize_async(rkyv_path, field, quote! { #binding })?;
+        bindings.push(quote! { #archived_member: #binding });
+        fields.push(quote! { #member: #deserialize });
+    }
+
+    Ok(quote! {
+        Self::#archived_variant { #(#bindings,)* } =>
+            #name::#ident { #(#fields,)* }
+    })
+}
+
+/// Returns the bound required to deserialize a field asynchronously.
+///
+/// Fields with wrappers are deserialized synchronously with `DeserializeWith`.
+fn deserialize_async_bound(
+    rkyv_path: &Path,
+    field: &Field,
+) -> Result<WherePredicate, Error> {
+    let ty = &field.ty;
+    let archived = archived(rkyv_path, field)?;
+
+    if field.attrs.iter().any(|attr| attr.path().is_ident("with")) {
+        deserialize_bound(rkyv_path, field)
+    } else {
+        Ok(parse_quote! {
+            #archived: #rkyv_path::DeserializeAsync<#ty, __D>
+        })
+    }
+}
+
+/// Returns an expression which deserializes the archived field `value`.
+fn deserialize_async(
+    rkyv_path: &Path,
+    field: &Field,
+    value: TokenStream,
+) -> Result<TokenStream, Error> {
+    let ty = &field.ty;
+    let archived = archived(rkyv_path, field)?;
+
+    map_with_or_else(
+        field,
+        |with_ty| {
+            quote! {
+                <
+                    #with_ty as #rkyv_path::with::DeserializeWith<
+                        #archived,
+                        #ty,
+                        __D,
+                    >
+                >::deserialize_with(#value, deserializer)?
+            }
+        },
+        || {
+            quote! {
+                <
+                    #archived as #rkyv_path::DeserializeAsync<#ty, __D>
+                >::deserialize_async(#value, deserializer).await?
+            }
+        },
+    )
+}
@@ -0,0 +1,109 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_quote, punctuated::Punctuated, Data, DeriveInput, Error,
+    GenericParam, Generics, Lifetime,
+};
+
+use crate::{
+    attributes::Attributes,
+    util::{archived_member, members},
+};
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let attributes = Attributes::parse(&input)?;
+    let rkyv_path = attributes.crate_path();
+
+    let Some(borrow_from) = attributes.borrow_from.as_ref() else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "DeserializeBorrowed requires the type to borrow from to be \
+             specified with `#[archive(borrow_from = ...)]`",
+        ));
+    };
+
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "DeserializeBorrowed can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut lifetime = None;
+    for param in input.generics.params.iter() {
+        match param {
+            GenericParam::Lifetime(param) => {
+                lifetime.get_or_insert_with(|| param.lifetime.clone());
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    param,
+                    "DeserializeBorrowed can only be derived for structs \
+                     which are generic over lifetimes",
+                ))
+            }
+        }
+    }
+
+    let mut impl_input_params = Punctuated::default();
+    let lifetime = if let Some(lifetime) = lifetime {
+        lifetime
+    } else {
+        let lifetime: Lifetime = parse_quote! { '__a };
+        impl_input_params.push(parse_quote! { #lifetime });
+        lifetime
+    };
+    for param in input.generics.params.iter() {
+        impl_input_params.push(param.clone());
+    }
+    impl_input_params
+        .push(parse_quote! { __D: #rkyv_path::rancor::Fallible + ?Sized });
+    let impl_input_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_input_params,
+        gt_token: Some(Default::default()),
+        where_clause: input.generics.where_clause.clone(),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ..) = impl_input_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let deserialize_fields = members(fields)
+        .map(|(member, field)| {
+            let archived_member = archived_member(&member, field)?;
+            let ty = &field.ty;
+            Ok(quote! {
+                #member: #rkyv_path::DeserializeBorrowed::<
+                    #lifetime,
+                    #ty,
+                    __D,
+                >::deserialize_borrowed(&self.#archived_member, deserializer)?
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics
+            #rkyv_path::DeserializeBorrowed<#lifetime, #name #ty_generics, __D>
+            for #rkyv_path::Archived<#borrow_from>
+        #where_clause
+        {
+            fn deserialize_borrowed(
+                &#lifetime self,
+                deserializer: &mut __D,
+            ) -> ::core::result::Result<
+                #name #ty_generics,
+                <__D as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                Ok(#name {
+                    #(#deserialize_fields,)*
+                })
+            }
+        }
+    })
+}
@@ -9,6 +9,8 @@
 mod archive;
 mod attributes;
 mod deserialize;
+mod deserialize_async;
+mod deserialize_borrowed;
 mod portable;
 mod repr;
 mod serde;
@@ -23,7 +25,7 @@ use syn::{parse_macro_input, DeriveInput};
 ///
 /// This macro also supports the `#[omit_bounds]` attribute. See [`Archive`] for
 /// more information.
-#[proc_macro_derive(Portable, attributes(archive, omit_bounds))]
+#[proc_macro_derive(Portable, attributes(archive, omit_bounds, rkyv))]
 pub fn derive_portable(
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
@@ -41,7 +43,8 @@ pub fn derive_portable(
 /// # Attributes
 ///
 /// Additional arguments can be specified using the `#[archive(...)]` and
-/// `#[archive_attr(...)]` attributes.
+/// `#[archive_attr(...)]` attributes. `#[rkyv(...)]` is accepted anywhere
+/// `#[archive(...)]` is.
 ///
 /// `#[archive(...)]` takes the following arguments:
 ///
@@ -51,13 +54,32 @@ pub fn derive_portable(
 /// - `resolver = "..."`: Changes the name of the generated resolver type to the
 ///   given value. By default, resolver types are named `the name of the type` +
 ///   "Resolver".
-/// - `repr(...)`: *Deprecated, use `#[archive_attr(repr(...))]` instead.* Sets
-///   the representation for the archived type to the given representation.
-///   Available representation options may vary depending on features and type
-///   layout.
+/// - `repr(...)`: Sets the type of the tag of an archived enum to `u8`, `u16`,
+///   or `u32`. By default, the smallest type which can hold every variant is
+///   used, so enums with more than 256 variants get a multibyte tag. Explicit
+///   discriminants (i.e. `Foo = 7`) are kept as the tags of their archived
+///   variants, and integer literal discriminants are taken into account when
+///   picking the default tag type. Multibyte tags are stored in the byte order
+///   of the archive and are not supported with the `unaligned` feature. To set
+///   the representation of other archived types, use
+///   `#[archive_attr(repr(...))]` instead.
+/// - `pack`: Reorders the named fields of each archived enum variant from
+///   smallest to largest alignment so that they pack in after the tag with less
+///   padding. By default, fields are archived in the order they're declared in.
+///   Adding or removing `pack` changes the archived layout, so archives written
+///   without it can't be read with it and vice versa. Alignments are inferred
+///   from the names of primitive types and arrays of them, like `u16`, without
+///   resolving paths. Type aliases, generic types, fields with wrappers, and
+///   all other types keep their relative order after the primitive fields.
+///   Tuple variants are never reordered.
 /// - `compare(...)`: Implements common comparison operators between the
-///   original and archived types. Supported comparisons are `PartialEq` and
-///   `PartialOrd` (i.e. `#[archive(compare(PartialEq, PartialOrd))]`).
+///   original and archived types. Supported comparisons are `PartialEq`,
+///   `PartialOrd`, and `Hash` (i.e. `#[archive(compare(PartialEq, PartialOrd,
+///   Hash))]`). `Hash` hashes the fields of an archived struct in declaration
+///   order, so it produces the same hash as a derived `Hash` implementation on
+///   the original struct as long as each archived field hashes the same as its
+///   original. This holds for archived primitives (except `usize` and `isize`),
+///   strings, vectors, boxes, and options. `Hash` is not supported for enums.
 /// - `bound(...)`: Adds additional bounds to trait implementations. This can be
 ///   especially useful when dealing with recursive structures, where bounds may
 ///   need to be omitted to prevent recursive type definitions. Use `archive =
@@ -68,25 +90,104 @@ pub fn derive_portable(
 ///   enable safe deserialization. Requires `validation` feature. Not compatible
 ///   with `as = "..."`. In that case, use `#[derive(CheckBytes)]` on the
 ///   archived type, and include a `use rkyv::bytecheck` statement.
+/// - `swap_endian`: Implement `SwapEndian` for the archived type, so that
+///   archives written with the opposite endianness can be converted with
+///   `normalize_endianness`. Fields archived with the `LittleEndian` or
+///   `BigEndian` wrappers are not converted. Not compatible with `as = "..."`.
+/// - `relocate`: Implement `Relocate` for the archived type, so that archives
+///   can be converted between relative and absolute offsets. Not compatible
+///   with `as = "..."`.
 /// - `as = "..."`: Instead of generating a separate archived type, this type
 ///   will archive as the named type. This is useful for types which are generic
 ///   over their parameters.
+/// - `with = ...`: Archives the entire type through the given wrapper, which
+///   must implement `ArchiveWith`, `SerializeWith`, and `DeserializeWith` for
+///   it. No archived or resolver type is generated. This is useful for types
+///   which should be archived as a different type (e.g. a bitfield archived as
+///   a `u64`) without wrapping them in a newtype field.
 /// - `crate = "..."`: Chooses an alternative crate path to import rkyv from.
+/// - `assert_layout(size = ..., align = ...)`: Asserts at compile time that the
+///   archived type has the given size and alignment. Either may be omitted.
+///   This locks down the layout of types which are stored long-term so that
+///   accidental layout changes fail the build. Field offsets can be checked
+///   with the `assert_offsets!` macro. Not supported for generic types.
+/// - `getters`: Generates a method on the archived type for each private field
+///   of the struct, which returns a reference to the archived field. The
+///   methods have the same visibility as the struct. Only supported for structs
+///   with named fields. Not compatible with `as = "..."`.
+/// - `setters`: Generates a `set_` method on the archived type for each field
+///   of the struct, which takes a pinned mutable reference to the archived type
+///   and overwrites the archived field with the given value. The setters can
+///   only be called for fields whose archived type implements `From` for the
+///   field type and `Unpin`, like primitives. This allows archives accessed
+///   with `access_mut` to be modified in place. Only supported for structs with
+///   named fields. Not compatible with `as = "..."`.
+/// - `derive_serde`: Implements `serde::Serialize` and `SerializeArchived` for
+///   the archived type so that it can be serialized with serde without being
+///   deserialized first. The archived type is serialized with the names of the
+///   original type and its fields. Requires the `serde` feature. Not compatible
+///   with `as = "..."`.
+/// - `project(Name(field, ...), ...)`: Generates a struct `Name` containing
+///   only the listed fields and implements `DeserializeProjection` for it on
+///   the archived type. This allows deserializing a few fields without
+///   deserializing the entire value. Only supported by `Deserialize` for
+///   non-generic structs with named fields.
+/// - `from_archived`: Implements `From<&Archived<T>>` for the type, which
+///   deserializes the archived value without a pool and with an infallible
+///   error type. This allows simple value types to be converted with `From` and
+///   `Into` instead of threading a deserializer through. Only supported by
+///   `Deserialize`, and only usable when every field can be deserialized that
+///   way.
+/// - `length_prefixed`: Archives the fields of the struct out-of-line along
+///   with the number of bytes written for them, and makes the archived type a
+///   `LengthPrefixed` of the generated archived type. Fields can then be added
+///   to the end of the struct without breaking existing archives. Only
+///   supported for structs with named fields. Not compatible with `as = "..."`,
+///   `swap_endian`, `derive_serde`, or `project(...)`.
+/// - `schema`: Implements `Schema` for the archived type, which describes the
+///   name, archived type, offset, and size of each field and the tag of each
+///   enum variant at runtime. This lets generic tools like viewers and binding
+///   generators inspect archives without knowing their types ahead of time.
+///   Also implements `Walk` for the archived type when all of its archived
+///   fields implement it, so that it can be traversed and dumped to JSON. Not
+///   compatible with `as = "..."`.
 ///
 /// `#[archive_attr(...)]` adds the attributes passed as arguments as attributes
 /// to the generated type. This is commonly used with attributes like
 /// `derive(...)` to derive trait implementations for the archived type.
 ///
+/// Fields and variants can be renamed in the generated archived and resolver
+/// types with `#[rkyv(rename = "...")]`. This is useful when the generated
+/// names would collide or when exposing the archived type publicly. Only named
+/// fields can be renamed.
+///
+/// Fields of `length_prefixed` structs can be given a default with
+/// `#[rkyv(default)]` or `#[rkyv(default = "...")]`. When a field with a
+/// default is missing from an archive written by an older definition of the
+/// struct, it is filled in with `Default::default()` or the given expression
+/// when deserializing instead of failing validation.
+///
 /// # Recursive types
 ///
 /// This derive macro automatically adds a type bound `field: Archive` for each
-/// field type. This can cause an overflow while evaluating trait bounds if the
-/// structure eventually references its own type, as the implementation of
-/// `Archive` for a struct depends on each field type implementing it
-/// as well. Adding the attribute `#[omit_bounds]` to a field will suppress this
-/// trait bound and allow recursive structures. This may be too coarse for some
-/// types, in which case additional type bounds may be required with
-/// `bound(...)`.
+/// field type. If the structure eventually references its own type, this would
+/// cause an overflow while evaluating trait bounds, as the implementation of
+/// `Archive` for a struct depends on each field type implementing it as well.
+///
+/// To prevent this, fields whose types refer to the type being derived (like
+/// `Vec<Node>` or `Box<Self>`) don't get type bounds. Instead, the serializer,
+/// deserializer, and validator are required to provide the capabilities that
+/// rkyv's own containers need (`Writer + Allocator + Sharing`, `Pooling`, and
+/// `ArchiveContext + SharedContext` respectively, with errors that implement
+/// `Source`). These requirements are replaced by any bounds specified with
+/// `serialize_bounds(...)`, `deserialize_bounds(...)`, or
+/// `archive_attr(check_bytes(...))`. Associated types like `T::Node` or
+/// `<T as Config>::Node` are not considered to refer to the type being derived
+/// even if they share its name, so they still get type bounds.
+///
+/// Adding the attribute `#[omit_bounds]` to a field will suppress its trait
+/// bound as well. This may be too coarse for some types, in which case
+/// additional type bounds may be required with `bound(...)`.
 ///
 /// # Wrappers
 ///
@@ -99,7 +200,7 @@ pub fn derive_portable(
 /// `With<With<With<MyType, C>, B, A>`).
 #[proc_macro_derive(
     Archive,
-    attributes(archive, archive_attr, omit_bounds, with)
+    attributes(archive, archive_attr, omit_bounds, rkyv, with)
 )]
 pub fn derive_archive(
     input: proc_macro::TokenStream,
@@ -117,7 +218,7 @@ pub fn derive_archive(
 ///
 /// This macro also supports the `#[archive]`, `#[omit_bounds]`, and `#[with]`
 /// attributes. See [`Archive`] for more information.
-#[proc_macro_derive(Serialize, attributes(archive, omit_bounds, with))]
+#[proc_macro_derive(Serialize, attributes(archive, omit_bounds, rkyv, with))]
 pub fn derive_serialize(
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
@@ -134,7 +235,7 @@ pub fn derive_serialize(
 ///
 /// This macro also supports the `#[archive]`, `#[omit_bounds]`, and `#[with]`
 /// attributes. See [`Archive`] for more information.
-#[proc_macro_derive(Deserialize, attributes(archive, omit_bounds, with))]
+#[proc_macro_derive(Deserialize, attributes(archive, omit_bounds, rkyv, with))]
 pub fn derive_deserialize(
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
@@ -146,3 +247,50 @@ pub fn derive_deserialize(
         Err(e) => e.to_compile_error().into(),
     }
 }
+
+/// Derives `DeserializeAsync` for the labeled type.
+///
+/// Each field is deserialized asynchronously with `DeserializeAsync`, except
+/// for fields with wrappers, which are deserialized synchronously with
+/// `DeserializeWith`. Recursive types are not supported.
+///
+/// This macro also supports the `#[archive]`, `#[omit_bounds]`, and `#[with]`
+/// attributes. See [`Archive`] for more information.
+#[proc_macro_derive(
+    DeserializeAsync,
+    attributes(archive, omit_bounds, rkyv, with)
+)]
+pub fn derive_deserialize_async(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let mut derive_input = parse_macro_input!(input as DeriveInput);
+    serde::receiver::replace_receiver(&mut derive_input);
+
+    match deserialize_async::derive(derive_input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Derives `DeserializeBorrowed` for the labeled type.
+///
+/// The labeled type must be a struct which is generic only over lifetimes. The
+/// type it borrows from is specified with `#[archive(borrow_from = ...)]`, and
+/// each of its fields is deserialized from the field of the same name in the
+/// archived type. The first lifetime parameter of the struct is used as the
+/// lifetime of the borrow.
+///
+/// For example, a `String` field can be borrowed as a `&str`, and a `Vec<u8>`
+/// field can be borrowed as a `&[u8]`. Any field can also be borrowed as a
+/// reference to its archived type.
+#[proc_macro_derive(DeserializeBorrowed, attributes(archive, rkyv))]
+pub fn derive_deserialize_borrowed(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+
+    match deserialize_borrowed::derive(derive_input) {
+        Ok(result) => result.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
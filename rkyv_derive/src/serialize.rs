@@ -2,24 +2,87 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
     parse_quote, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput,
-    Error, Fields, Generics, Ident, Index,
+    Error, Fields, Generics, Ident, Index, Type,
 };
 
 use crate::{
     attributes::Attributes,
-    util::{is_not_omitted, serialize, serialize_bound, strip_raw},
+    util::{
+        archived_member, archived_variant, bound_fields, has_recursive_fields,
+        members, serialize, serialize_bound, strip_raw,
+    },
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let attributes = Attributes::parse(&input)?;
+    if let Some(ref with) = attributes.with {
+        return derive_serialize_with_impl(input, &attributes, with);
+    }
     derive_serialize_impl(input, &attributes)
 }
 
+/// Implements `Serialize` for a type which is archived through the wrapper
+/// given with `#[archive(with = ...)]`.
+fn derive_serialize_with_impl(
+    mut input: DeriveInput,
+    attributes: &Attributes,
+    with: &Type,
+) -> Result<TokenStream, Error> {
+    let rkyv_path = attributes.crate_path();
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ty = quote! { #name #ty_generics };
+
+    let where_clause = input.generics.make_where_clause();
+    for bounds in [&attributes.archive_bounds, &attributes.serialize_bounds]
+        .into_iter()
+        .flatten()
+    {
+        where_clause.predicates.extend(bounds.iter().cloned());
+    }
+    where_clause.predicates.push(parse_quote! {
+        #with: #rkyv_path::with::SerializeWith<#ty, __S>
+    });
+
+    let mut impl_input_params = Punctuated::default();
+    impl_input_params
+        .push(parse_quote! { __S: #rkyv_path::rancor::Fallible + ?Sized });
+    for param in input.generics.params.iter() {
+        impl_input_params.push(param.clone());
+    }
+    let impl_input_generics = Generics {
+        lt_token: Some(Default::default()),
+        params: impl_input_params,
+        gt_token: Some(Default::default()),
+        where_clause: input.generics.where_clause.clone(),
+    };
+    let (impl_generics, _, where_clause) = impl_input_generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::Serialize<__S> for #ty
+        #where_clause
+        {
+            fn serialize(
+                &self,
+                serializer: &mut __S,
+            ) -> ::core::result::Result<
+                Self::Resolver,
+                <__S as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                <#with as #rkyv_path::with::SerializeWith<Self, __S>>
+                    ::serialize_with(self, serializer)
+            }
+        }
+    })
+}
+
 fn derive_serialize_impl(
     mut input: DeriveInput,
     attributes: &Attributes,
 ) -> Result<TokenStream, Error> {
     let rkyv_path = attributes.crate_path();
+    let is_recursive = has_recursive_fields(&input);
 
     let where_clause = input.generics.make_where_clause();
     if let Some(ref bounds) = attributes.archive_bounds {
@@ -31,6 +94,14 @@ fn derive_serialize_impl(
         for bound in bounds {
             where_clause.predicates.push(bound.clone());
         }
+    } else if is_recursive {
+        // Recursive fields don't get bounds, so require what rkyv's own
+        // containers need from the serializer instead.
+        where_clause.predicates.push(parse_quote! {
+            __S: #rkyv_path::ser::Writer
+                + #rkyv_path::ser::Allocator
+                + #rkyv_path::ser::Sharing
+        });
     }
 
     let mut impl_input_params = Punctuated::default();
@@ -61,17 +132,36 @@ fn derive_serialize_impl(
             Data::Struct(ref data) => match data.fields {
                 Fields::Named(ref fields) => {
                     let mut serialize_where = where_clause.clone();
-                    for field in fields.named.iter().filter(is_not_omitted) {
+                    for field in bound_fields(&input, &fields.named) {
                         serialize_where
                             .predicates
                             .push(serialize_bound(&rkyv_path, field)?);
                     }
 
-                    let resolver_values = fields.named.iter().map(|field| {
-                    let name = &field.ident;
-                    let serialize = serialize(&rkyv_path, field)?;
-                    Ok(quote! { #name: #serialize(&self.#name, serializer)? })
+                    let resolver_values = members(&data.fields).map(|(m, f)| {
+                    let archived = archived_member(&m, f)?;
+                    let serialize = serialize(&rkyv_path, f)?;
+                    Ok(quote! { #archived: #serialize(&self.#m, serializer)? })
                 }).collect::<Result<Vec<_>, Error>>()?;
+                    let resolver_value = quote! {
+                        #resolver { #(#resolver_values,)* }
+                    };
+
+                    let body = if attributes.length_prefixed.is_some() {
+                        serialize_where.predicates.push(parse_quote! {
+                            __S: #rkyv_path::ser::Writer
+                        });
+                        quote! {
+                            #rkyv_path::prefixed::LengthPrefixed
+                                ::serialize_fields(
+                                    self,
+                                    #resolver_value,
+                                    serializer,
+                                )
+                        }
+                    } else {
+                        quote! { Ok(#resolver_value) }
+                    };
 
                     quote! {
                         impl #impl_generics #rkyv_path::Serialize<__S>
@@ -85,16 +175,14 @@ fn derive_serialize_impl(
                                 Self::Resolver,
                                 <__S as #rkyv_path::rancor::Fallible>::Error,
                             > {
-                                Ok(#resolver {
-                                    #(#resolver_values,)*
-                                })
+                                #body
                             }
                         }
                     }
                 }
                 Fields::Unnamed(ref fields) => {
                     let mut serialize_where = where_clause.clone();
-                    for field in fields.unnamed.iter().filter(is_not_omitted) {
+                    for field in bound_fields(&input, &fields.unnamed) {
                         serialize_where
                             .predicates
                             .push(serialize_bound(&rkyv_path, field)?);
@@ -154,18 +242,14 @@ fn derive_serialize_impl(
                 for variant in data.variants.iter() {
                     match variant.fields {
                         Fields::Named(ref fields) => {
-                            for field in
-                                fields.named.iter().filter(is_not_omitted)
-                            {
+                            for field in bound_fields(&input, &fields.named) {
                                 serialize_where
                                     .predicates
                                     .push(serialize_bound(&rkyv_path, field)?);
                             }
                         }
                         Fields::Unnamed(ref fields) => {
-                            for field in
-                                fields.unnamed.iter().filter(is_not_omitted)
-                            {
+                            for field in bound_fields(&input, &fields.unnamed) {
                                 serialize_where
                                     .predicates
                                     .push(serialize_bound(&rkyv_path, field)?);
@@ -177,20 +261,22 @@ fn derive_serialize_impl(
 
                 let serialize_arms = data.variants.iter().map(|v| {
                 let variant = &v.ident;
+                let archived_variant = archived_variant(v)?;
                 match v.fields {
                     Fields::Named(ref fields) => {
                         let bindings = fields.named.iter().map(|f| &f.ident);
-                        let fields = fields.named.iter().map(|field| {
+                        let fields = members(&v.fields).map(|(m, field)| {
                             let name = &field.ident;
+                            let archived = archived_member(&m, field)?;
                             let serialize = serialize(&rkyv_path, field)?;
                             Ok(quote! {
-                                #name: #serialize(#name, serializer)?
+                                #archived: #serialize(#name, serializer)?
                             })
                         }).collect::<Result<Vec<_>, Error>>()?;
                         Ok(quote! {
                             Self::#variant {
                                 #(#bindings,)*
-                            } => #resolver::#variant {
+                            } => #resolver::#archived_variant {
                                 #(#fields,)*
                             }
                         })
@@ -220,11 +306,15 @@ fn derive_serialize_impl(
                         Ok(quote! {
                             Self::#variant(
                                 #(#bindings,)*
-                            ) => #resolver::#variant(#(#fields,)*)
+                            ) => #resolver::#archived_variant(
+                                #(#fields,)*
+                            )
                         })
                     }
                     Fields::Unit => {
-                        Ok(quote! { Self::#variant => #resolver::#variant })
+                        Ok(quote! {
+                            Self::#variant => #resolver::#archived_variant
+                        })
                     }
                 }
             }).collect::<Result<Vec<_>, Error>>()?;
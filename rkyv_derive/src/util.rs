@@ -1,10 +1,12 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse_quote, Error, Field, Fields, Index, Member, Meta, Path, Type,
-    WherePredicate,
+    parse_quote, Data, DeriveInput, Error, Field, Fields, GenericArgument,
+    Index, Member, Meta, Path, PathArguments, Type, Variant, WherePredicate,
 };
 
+use crate::attributes::parse_rename;
+
 pub fn strip_raw(ident: &Ident) -> String {
     let as_string = ident.to_string();
     as_string
@@ -23,6 +25,98 @@ pub fn is_not_omitted(f: &&Field) -> bool {
     })
 }
 
+/// Returns whether `ty` refers to the type being derived.
+///
+/// Associated types (e.g. `T::Output` or `<T as Trait>::Output`) only refer to
+/// the type being derived if their generic arguments do, even if they share a
+/// name with it.
+fn refers_to(ty: &Type, input: &DeriveInput) -> bool {
+    match ty {
+        Type::Path(path) => {
+            if let Some(qself) = &path.qself {
+                if refers_to(&qself.ty, input) {
+                    return true;
+                }
+            }
+
+            let segments = &path.path.segments;
+            let is_projection = path.qself.is_some()
+                || segments.len() > 1
+                    && input
+                        .generics
+                        .type_params()
+                        .any(|param| param.ident == segments[0].ident);
+            if !is_projection {
+                if let Some(last) = segments.last() {
+                    if last.ident == input.ident
+                        || segments.len() == 1 && last.ident == "Self"
+                    {
+                        return true;
+                    }
+                }
+            }
+
+            segments.iter().any(|segment| {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments
+                {
+                    args.args.iter().any(|arg| match arg {
+                        GenericArgument::Type(ty) => refers_to(ty, input),
+                        GenericArgument::AssocType(assoc) => {
+                            refers_to(&assoc.ty, input)
+                        }
+                        _ => false,
+                    })
+                } else {
+                    false
+                }
+            })
+        }
+        Type::Array(array) => refers_to(&array.elem, input),
+        Type::Group(group) => refers_to(&group.elem, input),
+        Type::Paren(paren) => refers_to(&paren.elem, input),
+        Type::Ptr(ptr) => refers_to(&ptr.elem, input),
+        Type::Reference(reference) => refers_to(&reference.elem, input),
+        Type::Slice(slice) => refers_to(&slice.elem, input),
+        Type::Tuple(tuple) => tuple.elems.iter().any(|ty| refers_to(ty, input)),
+        _ => false,
+    }
+}
+
+/// Returns whether the type of a field refers to the type being derived.
+pub fn is_recursive(field: &Field, input: &DeriveInput) -> bool {
+    refers_to(&field.ty, input)
+}
+
+/// Returns whether any field of the input type refers to the type itself.
+pub fn has_recursive_fields(input: &DeriveInput) -> bool {
+    match input.data {
+        Data::Struct(ref data) => {
+            data.fields.iter().any(|f| is_recursive(f, input))
+        }
+        Data::Enum(ref data) => data
+            .variants
+            .iter()
+            .flat_map(|v| v.fields.iter())
+            .any(|f| is_recursive(f, input)),
+        Data::Union(_) => false,
+    }
+}
+
+/// Returns the fields which trait bounds should be generated for.
+///
+/// Fields marked with `#[omit_bounds]` are skipped, as are fields which refer
+/// to the type being derived. Bounds on those fields would never finish
+/// evaluating.
+pub fn bound_fields<'a>(
+    input: &'a DeriveInput,
+    fields: impl IntoIterator<Item = &'a Field> + 'a,
+) -> impl Iterator<Item = &'a Field> + 'a {
+    fields
+        .into_iter()
+        .filter(is_not_omitted)
+        .filter(move |field| !is_recursive(field, input))
+}
+
 pub fn members_starting_at(
     fields: &Fields,
     start: usize,
@@ -41,6 +135,32 @@ pub fn members(fields: &Fields) -> impl Iterator<Item = (Member, &Field)> {
     members_starting_at(fields, 0)
 }
 
+/// Returns the member used for a field in the archived and resolver types.
+pub fn archived_member(
+    member: &Member,
+    field: &Field,
+) -> Result<Member, Error> {
+    match (parse_rename(&field.attrs)?, member) {
+        (None, _) => Ok(member.clone()),
+        (Some(name), Member::Named(_)) => Ok(Member::Named(name)),
+        (Some(name), Member::Unnamed(_)) => Err(Error::new_spanned(
+            name,
+            "rename can only be used on named fields",
+        )),
+    }
+}
+
+/// Returns the name used for a named field in the archived and resolver types.
+pub fn archived_field_name(field: &Field) -> Result<Ident, Error> {
+    Ok(parse_rename(&field.attrs)?
+        .unwrap_or_else(|| field.ident.clone().unwrap()))
+}
+
+/// Returns the name used for a variant in the archived and resolver types.
+pub fn archived_variant(variant: &Variant) -> Result<Ident, Error> {
+    Ok(parse_rename(&variant.attrs)?.unwrap_or_else(|| variant.ident.clone()))
+}
+
 pub fn map_with_or_else<T>(
     field: &Field,
     f: impl FnOnce(Type) -> T,
@@ -57,6 +177,94 @@ pub fn map_with_or_else<T>(
     }
 }
 
+pub fn has_fixed_endianness(field: &Field) -> Result<bool, Error> {
+    map_with_or_else(
+        field,
+        |with_ty| match with_ty {
+            Type::Path(path) => {
+                path.qself.is_none()
+                    && path.path.segments.last().is_some_and(|segment| {
+                        segment.arguments.is_empty()
+                            && (segment.ident == "LittleEndian"
+                                || segment.ident == "BigEndian")
+                    })
+            }
+            _ => false,
+        },
+        || false,
+    )
+}
+
+/// Returns the element type of the given type if it's an array, looking
+/// through nested arrays.
+///
+/// Array fields are bounded by their element types instead of the array
+/// types. Otherwise, the bound on the array type would shadow the `Archive`
+/// impl for arrays, and the archived type of arrays with generic lengths
+/// couldn't be normalized to an array of archived elements.
+fn array_elem(ty: &Type) -> &Type {
+    match ty {
+        Type::Array(array) => array_elem(&array.elem),
+        Type::Group(group) => array_elem(&group.elem),
+        Type::Paren(paren) => array_elem(&paren.elem),
+        _ => ty,
+    }
+}
+
+/// Returns the archived type of the given type, with array lengths carried
+/// through to the archived type.
+fn archived_type(rkyv_path: &Path, ty: &Type) -> TokenStream {
+    match ty {
+        Type::Array(array) => {
+            let elem = archived_type(rkyv_path, &array.elem);
+            let len = &array.len;
+            quote! { [#elem; #len] }
+        }
+        Type::Group(group) => archived_type(rkyv_path, &group.elem),
+        Type::Paren(paren) => archived_type(rkyv_path, &paren.elem),
+        _ => quote! { <#ty as #rkyv_path::Archive>::Archived },
+    }
+}
+
+/// Returns the `MAX_SERIALIZED_SIZE` constant for a type with the given fields.
+///
+/// The archived type has a fixed size if every field does. Fields archived
+/// with a wrapper, and fields which bounds are not generated for, are never
+/// treated as fixed-size.
+pub fn max_serialized_size<'a>(
+    rkyv_path: &Path,
+    input: &DeriveInput,
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> Result<TokenStream, Error> {
+    let mut checks = Vec::new();
+    for field in fields {
+        let ty = &field.ty;
+        let check = map_with_or_else(
+            field,
+            |_| quote! { false },
+            || {
+                if is_not_omitted(&field) && !is_recursive(field, input) {
+                    quote! { #rkyv_path::traits::is_fixed_size::<#ty>() }
+                } else {
+                    quote! { false }
+                }
+            },
+        )?;
+        checks.push(check);
+    }
+
+    Ok(quote! {
+        const MAX_SERIALIZED_SIZE: ::core::option::Option<usize> =
+            if true #(&& #checks)* {
+                ::core::option::Option::Some(::core::mem::size_of::<
+                    <Self as #rkyv_path::Archive>::Archived,
+                >())
+            } else {
+                ::core::option::Option::None
+            };
+    })
+}
+
 pub fn archive_bound(
     rkyv_path: &Path,
     field: &Field,
@@ -71,8 +279,9 @@ pub fn archive_bound(
             }
         },
         || {
+            let elem = array_elem(ty);
             parse_quote! {
-                #ty: #rkyv_path::Archive
+                #elem: #rkyv_path::Archive
             }
         },
     )
@@ -92,8 +301,9 @@ pub fn serialize_bound(
             }
         },
         || {
+            let elem = array_elem(ty);
             parse_quote! {
-                #ty: #rkyv_path::Serialize<__S>
+                #elem: #rkyv_path::Serialize<__S>
             }
         },
     )
@@ -115,8 +325,10 @@ pub fn deserialize_bound(
             }
         },
         || {
+            let elem = array_elem(ty);
+            let archived_elem = archived_type(rkyv_path, elem);
             parse_quote! {
-                #archived: #rkyv_path::Deserialize<#ty, __D>
+                #archived_elem: #rkyv_path::Deserialize<#elem, __D>
             }
         },
     )
@@ -148,7 +360,17 @@ fn archive_item(
 }
 
 pub fn archived(rkyv_path: &Path, field: &Field) -> Result<TokenStream, Error> {
-    archive_item(rkyv_path, field, "Archived", "Archived")
+    let ty = &field.ty;
+
+    map_with_or_else(
+        field,
+        |with_ty| {
+            quote! {
+                <#with_ty as #rkyv_path::with::ArchiveWith<#ty>>::Archived
+            }
+        },
+        || archived_type(rkyv_path, ty),
+    )
 }
 
 pub fn resolver(rkyv_path: &Path, field: &Field) -> Result<TokenStream, Error> {
@@ -5,6 +5,9 @@
 //!
 //! See [`SerializeDyn`] for an example of how to use rkyv_dyn.
 //!
+//! Values of unrelated types can also be archived together by registering
+//! them with stable type IDs. See the [`registry`] module for more details.
+//!
 //! ## Features
 //!
 //! - `bytecheck`: Enables validation support through `bytecheck`.
@@ -14,6 +17,7 @@
 #![deny(rustdoc::missing_crate_level_docs)]
 
 mod lazy_static;
+pub mod registry;
 // TODO: re-enable
 // #[cfg(feature = "bytecheck")]
 // mod bytecheck;
@@ -0,0 +1,466 @@
+//! A registry of types which can be archived together without an enum.
+//!
+//! Heterogeneous message streams are usually archived as one large enum with a
+//! variant for each type of message. Instead, each type can be given a stable
+//! ID in a registry declared with [`register_types`](crate::register_types).
+//! Values are then archived as an [`AnyArchived`], which stores the ID of the
+//! type alongside the archived value. The registry maps each ID back to the
+//! functions needed to check and deserialize it.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     access,
+//!     rancor::{Error, Strategy},
+//!     to_bytes, Archive, Deserialize, Serialize,
+//! };
+//! use rkyv_dyn::{
+//!     register_types,
+//!     registry::{AnyArchived, AsAny},
+//! };
+//!
+//! #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+//! #[archive(check_bytes)]
+//! struct Ping {
+//!     id: u32,
+//! }
+//!
+//! #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+//! #[archive(check_bytes)]
+//! struct Say {
+//!     message: String,
+//! }
+//!
+//! register_types! {
+//!     /// The messages which can be sent.
+//!     enum Messages {
+//!         Ping = 1,
+//!         Say = 2,
+//!     }
+//! }
+//!
+//! let value = Say {
+//!     message: "hello world".to_string(),
+//! };
+//! let bytes = to_bytes::<Error>(&AsAny::<Messages, _>::new(&value)).unwrap();
+//! let archived = access::<AnyArchived<Messages>, Error>(&bytes).unwrap();
+//!
+//! assert_eq!(archived.type_id(), 2);
+//! assert!(archived.downcast_ref::<Ping>().is_none());
+//! assert_eq!(
+//!     archived.downcast_ref::<Say>().unwrap().message,
+//!     "hello world"
+//! );
+//!
+//! let deserialized = archived
+//!     .deserialize(Strategy::<_, Error>::wrap(&mut ()))
+//!     .unwrap();
+//! assert_eq!(deserialized.downcast_ref::<Say>(), Some(&value));
+//! ```
+
+use core::{any::Any, fmt, marker::PhantomData};
+
+use rancor::{Fallible, Source};
+use rkyv::{
+    munge::munge, ser::Writer, Archive, Archived, Deserialize, Place, Portable,
+    RawRelPtr, Serialize,
+};
+
+/// The type of stable type IDs.
+pub type TypeId = u32;
+
+/// A type which has a stable ID in the registry `R`.
+///
+/// This is implemented by [`register_types`](crate::register_types).
+///
+/// # Safety
+///
+/// `TYPE_ID` must be unique among the types registered in `R`.
+pub unsafe trait RegisteredType<R>: Archive {
+    /// The ID of this type in the registry.
+    const TYPE_ID: TypeId;
+}
+
+/// A registry of types with stable IDs.
+///
+/// This is implemented by [`register_types`](crate::register_types).
+pub trait Registry {
+    /// Returns whether the given type ID is registered.
+    fn contains(type_id: TypeId) -> bool;
+}
+
+/// A registry which can deserialize any of its registered types.
+pub trait DeserializeRegistry<D: Fallible + ?Sized>: Registry {
+    /// Deserializes the archived value of the type with the given ID.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid archived value of the type with ID
+    /// `type_id`.
+    unsafe fn deserialize(
+        type_id: TypeId,
+        ptr: *const (),
+        deserializer: &mut D,
+    ) -> Result<Box<dyn Any>, D::Error>;
+}
+
+/// Deserializes the archived value of `T` at the given pointer.
+///
+/// This is used by [`register_types`](crate::register_types).
+///
+/// # Safety
+///
+/// `ptr` must point to a valid `T::Archived`.
+pub unsafe fn deserialize_registered<T, D>(
+    ptr: *const (),
+    deserializer: &mut D,
+) -> Result<Box<dyn Any>, D::Error>
+where
+    T: Archive + Any,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    // SAFETY: The caller has guaranteed that `ptr` points to a valid
+    // `T::Archived`.
+    let archived = unsafe { &*ptr.cast::<T::Archived>() };
+    Ok(Box::new(archived.deserialize(deserializer)?))
+}
+
+/// An error resulting from a type ID which is not in the registry.
+#[derive(Debug)]
+pub struct UnknownTypeIdError {
+    type_id: TypeId,
+}
+
+impl fmt::Display for UnknownTypeIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "type ID {} is not registered", self.type_id)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownTypeIdError {}
+
+/// Returns an error for a type ID which is not in the registry.
+///
+/// This is used by [`register_types`](crate::register_types).
+pub fn unknown_type_id<T, E: Source>(type_id: TypeId) -> Result<T, E> {
+    Err(E::new(UnknownTypeIdError { type_id }))
+}
+
+/// A value of any type in the registry `R` along with its type ID.
+///
+/// Values can be archived as an `AnyArchived` by wrapping them in [`AsAny`].
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(::bytecheck::CheckBytes),
+    check_bytes(verify)
+)]
+#[repr(C)]
+pub struct AnyArchived<R> {
+    type_id: Archived<TypeId>,
+    ptr: RawRelPtr,
+    _phantom: PhantomData<R>,
+}
+
+impl<R> AnyArchived<R> {
+    /// Returns the ID of the type of the archived value.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id.to_native()
+    }
+
+    /// Returns whether the archived value is a `T`.
+    pub fn is<T: RegisteredType<R>>(&self) -> bool {
+        self.type_id() == T::TYPE_ID
+    }
+
+    /// Returns a pointer to the archived value.
+    pub fn as_ptr(&self) -> *const () {
+        self.ptr.as_ptr_wrapping()
+    }
+
+    /// Returns a reference to the archived value if it is a `T`.
+    pub fn downcast_ref<T: RegisteredType<R>>(&self) -> Option<&T::Archived> {
+        if self.is::<T>() {
+            // SAFETY: The type ID of the archived value matches `T`, so it
+            // points to a valid `T::Archived`.
+            Some(unsafe { &*self.as_ptr().cast::<T::Archived>() })
+        } else {
+            None
+        }
+    }
+
+    /// Deserializes the archived value into a box.
+    ///
+    /// The box can be downcast to the unarchived type of the value.
+    pub fn deserialize<D>(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Box<dyn Any>, D::Error>
+    where
+        R: DeserializeRegistry<D>,
+        D: Fallible + ?Sized,
+    {
+        // SAFETY: The archived value pointed to is a valid archived value of
+        // the type with ID `type_id`.
+        unsafe { R::deserialize(self.type_id(), self.as_ptr(), deserializer) }
+    }
+}
+
+impl<R> fmt::Debug for AnyArchived<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyArchived")
+            .field("type_id", &self.type_id())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A wrapper which archives a value as an [`AnyArchived`] in the registry `R`.
+pub struct AsAny<'a, R, T> {
+    value: &'a T,
+    _phantom: PhantomData<R>,
+}
+
+impl<'a, R, T> AsAny<'a, R, T> {
+    /// Wraps the given value.
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            value,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// The resolver for [`AnyArchived`].
+pub struct AnyResolver {
+    pos: usize,
+}
+
+impl<R, T: RegisteredType<R>> Archive for AsAny<'_, R, T> {
+    type Archived = AnyArchived<R>;
+    type Resolver = AnyResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let AnyArchived { type_id, ptr, _phantom: _ } = out);
+        type_id.write(Archived::<TypeId>::from_native(T::TYPE_ID));
+        RawRelPtr::emplace(resolver.pos, ptr);
+    }
+}
+
+impl<R, T, S> Serialize<S> for AsAny<'_, R, T>
+where
+    T: RegisteredType<R> + Serialize<S>,
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<AnyResolver, S::Error> {
+        Ok(AnyResolver {
+            pos: self.value.serialize_and_resolve(serializer)?,
+        })
+    }
+}
+
+/// Declares a registry of types with stable IDs.
+///
+/// This declares an uninhabited type which implements [`Registry`], and
+/// implements [`RegisteredType`] for each of the listed types with its given
+/// ID. Type IDs must be unique, and should not be changed or reused once
+/// archives containing them have been written.
+///
+/// See the [module docs](crate::registry) for an example.
+#[macro_export]
+macro_rules! register_types {
+    (
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident {
+            $($type:ty = $id:expr),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis enum $name {}
+
+        $(
+            unsafe impl $crate::registry::RegisteredType<$name> for $type {
+                const TYPE_ID: $crate::registry::TypeId = $id;
+            }
+        )*
+
+        const _: () = {
+            struct TypeIds;
+
+            trait Registered<const ID: $crate::registry::TypeId> {}
+
+            $(
+                impl Registered<{
+                    <$type as $crate::registry::RegisteredType<$name>>::TYPE_ID
+                }> for TypeIds {}
+            )*
+        };
+
+        impl $crate::registry::Registry for $name {
+            fn contains(type_id: $crate::registry::TypeId) -> bool {
+                $(
+                    type_id
+                        == <$type as $crate::registry::RegisteredType<$name>>
+                            ::TYPE_ID ||
+                )* false
+            }
+        }
+
+        impl<D> $crate::registry::DeserializeRegistry<D> for $name
+        where
+            D: ::rkyv::rancor::Fallible + ?Sized,
+            D::Error: ::rkyv::rancor::Source,
+            $(::rkyv::Archived<$type>: ::rkyv::Deserialize<$type, D>,)*
+        {
+            unsafe fn deserialize(
+                type_id: $crate::registry::TypeId,
+                ptr: *const (),
+                deserializer: &mut D,
+            ) -> ::core::result::Result<
+                ::std::boxed::Box<dyn ::core::any::Any>,
+                D::Error,
+            > {
+                $(
+                    if type_id
+                        == <$type as $crate::registry::RegisteredType<$name>>
+                            ::TYPE_ID
+                    {
+                        return unsafe {
+                            $crate::registry::deserialize_registered::<
+                                $type,
+                                D,
+                            >(ptr, deserializer)
+                        };
+                    }
+                )*
+                $crate::registry::unknown_type_id(type_id)
+            }
+        }
+
+        $crate::__register_types_check_bytes!($name { $($type),* });
+    };
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::verify::*;
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        CheckBytes, Verify,
+    };
+    use rkyv::validation::{ArchiveContext, ArchiveContextExt};
+
+    use super::{AnyArchived, RegisteredType, Registry, TypeId};
+
+    /// A registry which can check any of its registered types.
+    ///
+    /// # Safety
+    ///
+    /// `check_bytes` must only succeed if `ptr` points to a valid archived
+    /// value of the type with ID `type_id`.
+    pub unsafe trait CheckRegistry<C: Fallible + ?Sized>:
+        Registry
+    {
+        /// Checks the archived value of the type with the given ID.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must be located in the archive being checked by `context`.
+        unsafe fn check_bytes(
+            type_id: TypeId,
+            ptr: *const (),
+            context: &mut C,
+        ) -> Result<(), C::Error>;
+    }
+
+    /// Checks the archived value of `T` at the given pointer.
+    ///
+    /// This is used by [`register_types`](crate::register_types).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be located in the archive being checked by `context`.
+    pub unsafe fn check_registered<T, R, C>(
+        ptr: *const (),
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: RegisteredType<R>,
+        T::Archived: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        let ptr = ptr.cast::<T::Archived>();
+        context.in_subtree(ptr, |context| {
+            // SAFETY: `in_subtree` has checked that `ptr` is located in the
+            // archive and properly aligned.
+            unsafe { T::Archived::check_bytes(ptr, context) }
+        })
+    }
+
+    unsafe impl<R, C> Verify<C> for AnyArchived<R>
+    where
+        R: CheckRegistry<C>,
+        C: Fallible + ?Sized,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            // SAFETY: The pointer is checked to be located in the archive by
+            // the registry.
+            unsafe { R::check_bytes(self.type_id(), self.as_ptr(), context) }
+        }
+    }
+
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __register_types_check_bytes {
+        ($name:ident { $($type:ty),* }) => {
+            unsafe impl<C> $crate::registry::CheckRegistry<C> for $name
+            where
+                C: ::rkyv::rancor::Fallible
+                    + ::rkyv::validation::ArchiveContext
+                    + ?Sized,
+                C::Error: ::rkyv::rancor::Source,
+                $(
+                    ::rkyv::Archived<$type>:
+                        ::rkyv::bytecheck::CheckBytes<C>,
+                )*
+            {
+                unsafe fn check_bytes(
+                    type_id: $crate::registry::TypeId,
+                    ptr: *const (),
+                    context: &mut C,
+                ) -> ::core::result::Result<(), C::Error> {
+                    $(
+                        if type_id
+                            == <
+                                $type as $crate::registry::RegisteredType<
+                                    $name,
+                                >
+                            >::TYPE_ID
+                        {
+                            return unsafe {
+                                $crate::registry::check_registered::<
+                                    $type,
+                                    $name,
+                                    C,
+                                >(ptr, context)
+                            };
+                        }
+                    )*
+                    $crate::registry::unknown_type_id(type_id)
+                }
+            }
+        };
+    }
+}
+
+#[cfg(not(feature = "bytecheck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_types_check_bytes {
+    ($name:ident { $($type:ty),* }) => {};
+}
@@ -208,6 +208,104 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn any_archived() {
+        use rkyv::{
+            access_unchecked,
+            de::pooling::Pool,
+            rancor::{Error, Strategy},
+            to_bytes, Archive, Deserialize, Serialize,
+        };
+        use rkyv_dyn::{
+            register_types,
+            registry::{AnyArchived, AsAny, Registry},
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        pub struct Ping {
+            id: u32,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        pub struct Say {
+            from: u32,
+            message: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        pub struct Batch(Vec<Ping>);
+
+        register_types! {
+            pub enum Messages {
+                Ping = 1,
+                Say = 2,
+                Batch = 100,
+            }
+        }
+
+        assert!(Messages::contains(1));
+        assert!(Messages::contains(100));
+        assert!(!Messages::contains(3));
+
+        let say = Say {
+            from: 42,
+            message: "hello world".to_string(),
+        };
+        let bytes =
+            to_bytes::<Error>(&AsAny::<Messages, _>::new(&say)).unwrap();
+        let archived =
+            unsafe { access_unchecked::<AnyArchived<Messages>>(&bytes) };
+        assert_eq!(archived.type_id(), 2);
+        assert!(archived.is::<Say>());
+        assert!(archived.downcast_ref::<Ping>().is_none());
+        let archived_say = archived.downcast_ref::<Say>().unwrap();
+        assert_eq!(archived_say.from, 42);
+        assert_eq!(archived_say.message, "hello world");
+
+        let deserialized = archived
+            .deserialize(Strategy::<_, Error>::wrap(&mut Pool::new()))
+            .unwrap();
+        assert_eq!(deserialized.downcast_ref::<Say>(), Some(&say));
+
+        let batch = Batch(vec![Ping { id: 1 }, Ping { id: 2 }]);
+        let bytes =
+            to_bytes::<Error>(&AsAny::<Messages, _>::new(&batch)).unwrap();
+        let archived =
+            unsafe { access_unchecked::<AnyArchived<Messages>>(&bytes) };
+        assert_eq!(archived.type_id(), 100);
+        assert_eq!(archived.downcast_ref::<Batch>().unwrap().0.len(), 2);
+        let deserialized = archived
+            .deserialize(Strategy::<_, Error>::wrap(&mut Pool::new()))
+            .unwrap();
+        assert_eq!(*deserialized.downcast::<Batch>().unwrap(), batch);
+
+        #[cfg(feature = "bytecheck")]
+        {
+            use rkyv::access;
+
+            let bytes =
+                to_bytes::<Error>(&AsAny::<Messages, _>::new(&say)).unwrap();
+            let archived =
+                access::<AnyArchived<Messages>, Error>(&bytes).unwrap();
+            assert!(archived.is::<Say>());
+
+            // The type ID is stored first in the root, which is at the end
+            let mut bytes = bytes;
+            let root =
+                bytes.len() - core::mem::size_of::<AnyArchived<Messages>>();
+            bytes[root..root + 4].copy_from_slice(&3u32.to_le_bytes());
+            assert!(access::<AnyArchived<Messages>, Error>(&bytes).is_err());
+
+            // A registered type which doesn't match the archived value
+            bytes[root..root + 4].copy_from_slice(&100u32.to_le_bytes());
+            assert!(access::<AnyArchived<Messages>, Error>(&bytes).is_err());
+        }
+    }
+
     // TODO: uncomment and fix
     // #[test]
     // #[cfg(not(feature = "wasm"))]
@@ -9,6 +9,8 @@ extern crate alloc;
 
 #[cfg(feature = "alloc")]
 mod test_alloc;
+#[cfg(feature = "serde")]
+mod test_serde;
 #[cfg(feature = "std")]
 mod test_std;
 pub mod util;
@@ -100,6 +102,74 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn offset_overflow() {
+        use rkyv::{
+            primitive::FixedIsize, string::repr::ArchivedStringRepr, Place,
+            RawRelPtr,
+        };
+
+        let max = FixedIsize::MAX as usize;
+
+        let mut out = MaybeUninit::<RawRelPtr>::zeroed();
+        let place = unsafe { Place::new_unchecked(0, out.as_mut_ptr()) };
+        RawRelPtr::try_emplace::<Error>(max, place).unwrap();
+        RawRelPtr::try_emplace::<Error>(max + 1, place)
+            .expect_err("expected offset overflow error");
+
+        let mut out = MaybeUninit::<ArchivedStringRepr>::zeroed();
+        let place = unsafe { Place::new_unchecked(0, out.as_mut_ptr()) };
+        let value = "a string too long to be inlined";
+        unsafe {
+            ArchivedStringRepr::try_emplace_out_of_line::<Error>(
+                value, max, place,
+            )
+            .unwrap();
+            ArchivedStringRepr::try_emplace_out_of_line::<Error>(
+                value,
+                max + 1,
+                place,
+            )
+            .expect_err("expected offset overflow error");
+        }
+    }
+
+    #[cfg(feature = "pointer_width_16")]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn offset_overflow_16() {
+        use rkyv::{Place, RawRelPtr};
+
+        let mut out = MaybeUninit::<RawRelPtr>::zeroed();
+        let place = unsafe { Place::new_unchecked(0x10, out.as_mut_ptr()) };
+        RawRelPtr::try_emplace::<Error>(0x800f, place).unwrap();
+        RawRelPtr::try_emplace::<Error>(0x8010, place)
+            .expect_err("expected offset overflow error");
+        let place = unsafe { Place::new_unchecked(0x8010, out.as_mut_ptr()) };
+        RawRelPtr::try_emplace::<Error>(0x10, place).unwrap();
+        RawRelPtr::try_emplace::<Error>(0x0f, place)
+            .expect_err("expected offset overflow error");
+    }
+
+    #[cfg(feature = "pointer_width_32")]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn offset_overflow_32() {
+        use rkyv::{Place, RawRelPtr};
+
+        let mut out = MaybeUninit::<RawRelPtr>::zeroed();
+        let place = unsafe { Place::new_unchecked(0x10, out.as_mut_ptr()) };
+        RawRelPtr::try_emplace::<Error>(0x8000_000f, place).unwrap();
+        RawRelPtr::try_emplace::<Error>(0x8000_0010, place)
+            .expect_err("expected offset overflow error");
+        let place =
+            unsafe { Place::new_unchecked(0x8000_0010, out.as_mut_ptr()) };
+        RawRelPtr::try_emplace::<Error>(0x10, place).unwrap();
+        RawRelPtr::try_emplace::<Error>(0x0f, place)
+            .expect_err("expected offset overflow error");
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_zst() {
@@ -142,6 +212,54 @@ mod tests {
         test_archive(&r#try::r#try { r#try: 42 });
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_rename() {
+        use rkyv::{Archive, Deserialize, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[rkyv(compare(PartialEq, PartialOrd))]
+        #[archive_attr(derive(Debug))]
+        struct Example {
+            #[rkyv(rename = "archived_id")]
+            id: i32,
+            value: u8,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum Shape {
+            #[rkyv(rename = "Disc")]
+            Circle {
+                #[rkyv(rename = "archived_radius")]
+                radius: u32,
+            },
+            #[rkyv(rename = "Quad")]
+            Square(u32),
+            #[rkyv(rename = "Dot")]
+            Point,
+        }
+
+        test_archive(&Example { id: 42, value: 7 });
+        test_archive(&Shape::Circle { radius: 42 });
+        test_archive(&Shape::Square(42));
+        test_archive(&Shape::Point);
+
+        let archived = ArchivedExample {
+            archived_id: 42.into(),
+            value: 7,
+        };
+        assert_eq!(archived.archived_id, 42);
+        assert!(matches!(
+            ArchivedShape::Disc {
+                archived_radius: 42.into(),
+            },
+            ArchivedShape::Disc { .. },
+        ));
+        assert!(matches!(ShapeResolver::Dot, ShapeResolver::Dot,));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_enum_explicit_discriminants() {
@@ -159,6 +277,576 @@ mod tests {
         assert_eq!(ArchivedFoo::C as usize, 6);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_enum_explicit_discriminants_with_fields() {
+        use core::mem::size_of;
+
+        use rkyv::{
+            access_unchecked, rancor::Error, to_bytes_in_slice, util::Align,
+            Archive, Deserialize, Serialize,
+        };
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[repr(u8)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum Message {
+            Ping = 7,
+            Data(u32) = 3,
+            Close,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum Code {
+            Ok = 200,
+            NotFound = 404,
+            Teapot,
+        }
+
+        test_archive(&Message::Ping);
+        test_archive(&Message::Data(42));
+        test_archive(&Message::Close);
+        test_archive(&Code::Ok);
+        test_archive(&Code::NotFound);
+        test_archive(&Code::Teapot);
+
+        // A discriminant larger than `u8::MAX` requires a `u16` tag.
+        assert_eq!(size_of::<ArchivedCode>(), 2);
+
+        let mut output = Align([0u8; 16]);
+        let len = to_bytes_in_slice::<Error>(&Message::Data(42), &mut *output)
+            .unwrap()
+            .len();
+        let bytes = &output[..len];
+        let archived = unsafe { access_unchecked::<ArchivedMessage>(bytes) };
+        assert_eq!(bytes[0], 3);
+        assert!(matches!(archived, ArchivedMessage::Data(x) if *x == 42));
+
+        to_bytes_in_slice::<Error>(&Message::Close, &mut *output).unwrap();
+        assert_eq!(output[0], 4);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_enum_wide_tag() {
+        use core::mem::size_of;
+
+        use rkyv::{
+            access_unchecked, endian::normalize_endianness, to_bytes_in_slice,
+            util::Align, Archive, Deserialize, Serialize,
+        };
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq, PartialOrd))]
+        #[archive_attr(derive(Debug))]
+        enum Large {
+            V0,
+            V1,
+            V2,
+            V3,
+            V4,
+            V5,
+            V6,
+            V7,
+            V8,
+            V9,
+            V10,
+            V11,
+            V12,
+            V13,
+            V14,
+            V15,
+            V16,
+            V17,
+            V18,
+            V19,
+            V20,
+            V21,
+            V22,
+            V23,
+            V24,
+            V25,
+            V26,
+            V27,
+            V28,
+            V29,
+            V30,
+            V31,
+            V32,
+            V33,
+            V34,
+            V35,
+            V36,
+            V37,
+            V38,
+            V39,
+            V40,
+            V41,
+            V42,
+            V43,
+            V44,
+            V45,
+            V46,
+            V47,
+            V48,
+            V49,
+            V50,
+            V51,
+            V52,
+            V53,
+            V54,
+            V55,
+            V56,
+            V57,
+            V58,
+            V59,
+            V60,
+            V61,
+            V62,
+            V63,
+            V64,
+            V65,
+            V66,
+            V67,
+            V68,
+            V69,
+            V70,
+            V71,
+            V72,
+            V73,
+            V74,
+            V75,
+            V76,
+            V77,
+            V78,
+            V79,
+            V80,
+            V81,
+            V82,
+            V83,
+            V84,
+            V85,
+            V86,
+            V87,
+            V88,
+            V89,
+            V90,
+            V91,
+            V92,
+            V93,
+            V94,
+            V95,
+            V96,
+            V97,
+            V98,
+            V99,
+            V100,
+            V101,
+            V102,
+            V103,
+            V104,
+            V105,
+            V106,
+            V107,
+            V108,
+            V109,
+            V110,
+            V111,
+            V112,
+            V113,
+            V114,
+            V115,
+            V116,
+            V117,
+            V118,
+            V119,
+            V120,
+            V121,
+            V122,
+            V123,
+            V124,
+            V125,
+            V126,
+            V127,
+            V128,
+            V129,
+            V130,
+            V131,
+            V132,
+            V133,
+            V134,
+            V135,
+            V136,
+            V137,
+            V138,
+            V139,
+            V140,
+            V141,
+            V142,
+            V143,
+            V144,
+            V145,
+            V146,
+            V147,
+            V148,
+            V149,
+            V150,
+            V151,
+            V152,
+            V153,
+            V154,
+            V155,
+            V156,
+            V157,
+            V158,
+            V159,
+            V160,
+            V161,
+            V162,
+            V163,
+            V164,
+            V165,
+            V166,
+            V167,
+            V168,
+            V169,
+            V170,
+            V171,
+            V172,
+            V173,
+            V174,
+            V175,
+            V176,
+            V177,
+            V178,
+            V179,
+            V180,
+            V181,
+            V182,
+            V183,
+            V184,
+            V185,
+            V186,
+            V187,
+            V188,
+            V189,
+            V190,
+            V191,
+            V192,
+            V193,
+            V194,
+            V195,
+            V196,
+            V197,
+            V198,
+            V199,
+            V200,
+            V201,
+            V202,
+            V203,
+            V204,
+            V205,
+            V206,
+            V207,
+            V208,
+            V209,
+            V210,
+            V211,
+            V212,
+            V213,
+            V214,
+            V215,
+            V216,
+            V217,
+            V218,
+            V219,
+            V220,
+            V221,
+            V222,
+            V223,
+            V224,
+            V225,
+            V226,
+            V227,
+            V228,
+            V229,
+            V230,
+            V231,
+            V232,
+            V233,
+            V234,
+            V235,
+            V236,
+            V237,
+            V238,
+            V239,
+            V240,
+            V241,
+            V242,
+            V243,
+            V244,
+            V245,
+            V246,
+            V247,
+            V248,
+            V249,
+            V250,
+            V251,
+            V252,
+            V253,
+            V254,
+            V255,
+            V256,
+            V257,
+            V258,
+            V259,
+            V260,
+            V261,
+            V262,
+            V263,
+            V264,
+            V265,
+            V266,
+            V267,
+            V268,
+            V269,
+            V270,
+            V271,
+            V272,
+            V273,
+            V274,
+            V275,
+            V276,
+            V277,
+            V278,
+            V279,
+            V280,
+            V281,
+            V282,
+            V283,
+            V284,
+            V285,
+            V286,
+            V287,
+            V288,
+            V289,
+            V290,
+            V291,
+            V292,
+            V293,
+            V294,
+            V295,
+            V296,
+            V297,
+            V298,
+            V299,
+            Value(u32),
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(repr(u16), swap_endian, compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        #[repr(u16)]
+        enum Small {
+            A = 2,
+            B,
+            C(u8) = 1000,
+        }
+
+        assert_eq!(size_of::<ArchivedLarge>(), 8);
+        assert_eq!(size_of::<ArchivedSmall>(), 4);
+
+        test_archive(&Large::V0);
+        test_archive(&Large::V255);
+        test_archive(&Large::V299);
+        test_archive(&Large::Value(42));
+        test_archive(&Small::A);
+        test_archive(&Small::B);
+        test_archive(&Small::C(42));
+
+        assert!(Large::V1 < ArchivedLarge::V256);
+        assert!(Large::V257 > ArchivedLarge::V2);
+
+        let mut output = Align([0u8; 4]);
+        let len = to_bytes_in_slice::<Error>(&Small::C(42), &mut *output)
+            .unwrap()
+            .len();
+        let bytes = &mut output[..len];
+        bytes[0..2].reverse();
+        let archived = unsafe {
+            normalize_endianness::<ArchivedSmall>(bytes);
+            access_unchecked::<ArchivedSmall>(bytes)
+        };
+        assert_eq!(*archived, Small::C(42));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_enum_packing() {
+        use core::mem::size_of;
+
+        use rkyv::{Archive, Deserialize, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(pack, compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum Packed {
+            A { a: u8, b: u64, c: u16 },
+            B { a: u32, b: u8 },
+            C,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum Unpacked {
+            A { a: u8, b: u64, c: u16 },
+            B { a: u32, b: u8 },
+            C,
+        }
+
+        assert_eq!(size_of::<ArchivedPacked>(), 16);
+        assert_eq!(size_of::<ArchivedUnpacked>(), 24);
+
+        test_archive(&Packed::A { a: 1, b: 2, c: 3 });
+        test_archive(&Packed::B { a: 4, b: 5 });
+        test_archive(&Packed::C);
+        test_archive(&Unpacked::A { a: 1, b: 2, c: 3 });
+        test_archive(&Unpacked::B { a: 4, b: 5 });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_assert_layout() {
+        use core::mem::{align_of, size_of};
+
+        use rkyv::{assert_offsets, Archive};
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[archive(assert_layout(size = 16, align = 8))]
+        struct Example {
+            a: u8,
+            b: u64,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[archive(assert_layout(size = 4))]
+        struct Tuple(u16, u16);
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[archive(assert_layout(align = 1))]
+        enum Unit {
+            A,
+            B,
+        }
+
+        assert_offsets!(ArchivedExample { a: 0, b: 8 });
+        assert_offsets!(ArchivedTuple { 0: 0, 1: 2 });
+
+        assert_eq!(size_of::<ArchivedExample>(), 16);
+        assert_eq!(size_of::<ArchivedTuple>(), 4);
+        assert_eq!(align_of::<ArchivedUnit>(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_getters() {
+        use rkyv::{access_unchecked, to_bytes_in_slice, util::Align};
+
+        mod example {
+            use rkyv::{Archive, Serialize};
+
+            #[derive(Archive, Serialize)]
+            #[archive(getters)]
+            pub struct Example {
+                id: u32,
+                r#type: u8,
+                pub flag: bool,
+            }
+
+            impl Example {
+                pub fn new(id: u32, r#type: u8, flag: bool) -> Self {
+                    Self { id, r#type, flag }
+                }
+            }
+        }
+
+        use example::{ArchivedExample, Example};
+
+        let mut output = Align([0u8; 16]);
+        let bytes = to_bytes_in_slice::<Error>(
+            &Example::new(42, 7, true),
+            &mut *output,
+        )
+        .unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(bytes) };
+        assert_eq!(*archived.id(), 42);
+        assert_eq!(*archived.r#type(), 7);
+        assert!(archived.flag);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_setters() {
+        use rkyv::{
+            access_unchecked, access_unchecked_mut, to_bytes_in_slice,
+            util::Align, Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        struct Inner {
+            value: u32,
+        }
+
+        // Setters for fields like `inner` can't be called, since the archived
+        // type can't be created from the unarchived one.
+        #[derive(Archive, Serialize)]
+        #[archive(setters)]
+        struct Example {
+            count: u32,
+            r#type: u8,
+            ratio: f32,
+            flag: bool,
+            inner: Inner,
+        }
+
+        let mut output = Align([0u8; 32]);
+        let len = to_bytes_in_slice::<Error>(
+            &Example {
+                count: 1,
+                r#type: 2,
+                ratio: 0.5,
+                flag: false,
+                inner: Inner { value: 3 },
+            },
+            &mut *output,
+        )
+        .unwrap()
+        .len();
+
+        let mut archived = unsafe {
+            access_unchecked_mut::<ArchivedExample>(&mut output[..len])
+        };
+        archived.as_mut().set_count(42);
+        archived.as_mut().set_type(7);
+        archived.as_mut().set_ratio(1.5);
+        archived.as_mut().set_flag(true);
+
+        let archived =
+            unsafe { access_unchecked::<ArchivedExample>(&output[..len]) };
+        assert_eq!(archived.count, 42);
+        assert_eq!(archived.r#type, 7);
+        assert_eq!(archived.ratio, 1.5);
+        assert!(archived.flag);
+        assert_eq!(archived.inner.value, 3);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn buffer_serializer_zeroes_padding() {
@@ -182,4 +870,335 @@ mod tests {
             .iter()
             .all(|&b| b == 0));
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn to_bytes_into_uninit() {
+        use rkyv::{access_unchecked, to_bytes_into, util::Align};
+
+        let mut output = Align([MaybeUninit::<u8>::uninit(); 64]);
+        let start = output.as_ptr().cast::<u8>();
+        let bytes =
+            to_bytes_into::<Error>(&(1u8, 2u16, 3u32), &mut *output).unwrap();
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(bytes.as_ptr(), start);
+
+        type ArchivedExample = ArchivedTuple3<u8, Archived<u16>, Archived<u32>>;
+        let archived = unsafe { access_unchecked::<ArchivedExample>(bytes) };
+        assert_eq!(archived.0, 1);
+        assert_eq!(archived.1, 2);
+        assert_eq!(archived.2, 3);
+
+        let mut small = Align([MaybeUninit::<u8>::uninit(); 4]);
+        assert!(
+            to_bytes_into::<Error>(&(1u8, 2u16, 3u32), &mut *small).is_err()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn to_bytes_in_slice_sizing() {
+        use rkyv::{
+            access_unchecked, ser::writer::SizingBuffer, to_bytes_in_slice,
+            util::Align,
+        };
+
+        let value = [1u32, 2, 3, 4];
+
+        let mut output = Align([0u8; 16]);
+        let bytes = to_bytes_in_slice::<Error>(&value, &mut *output).unwrap();
+        let archived = unsafe { access_unchecked::<[Archived<u32>; 4]>(bytes) };
+        assert_eq!(archived, &[1, 2, 3, 4]);
+
+        let mut output = Align([0u8; 15]);
+        assert!(to_bytes_in_slice::<Error>(&value, &mut *output).is_err());
+
+        let writer = serialize_into::<_, Error>(
+            &value,
+            SizingBuffer::from(&mut *output),
+        )
+        .unwrap();
+        assert!(writer.is_overflowed());
+        let error = writer.finish().unwrap_err();
+        assert_eq!(error.needed, 16);
+        assert_eq!(error.capacity, 15);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn root_placement() {
+        use rkyv::{
+            ser::Writer,
+            util::{
+                access_pos_unchecked, root_position, serialize_root, Align,
+            },
+        };
+
+        type ArchivedExample = ArchivedTuple3<u8, Archived<u16>, Archived<u32>>;
+        let value = (1u8, 2u16, 3u32);
+
+        let mut page = Align([0u8; 32]);
+        let mut writer = Buffer::from(&mut *page);
+        let pending =
+            serialize_root::<_, _, Error>(&value, &mut writer).unwrap();
+        Writer::<Error>::write(&mut writer, b"header").unwrap();
+        let pos = pending.resolve_at(&mut writer, 16).unwrap();
+        assert_eq!(pos, 16);
+        assert_eq!(&page[..6], b"header");
+        assert_eq!(root_position::<ArchivedExample>(24), Some(pos));
+
+        let archived =
+            unsafe { access_pos_unchecked::<ArchivedExample>(&*page, pos) };
+        assert_eq!(archived.0, 1);
+        assert_eq!(archived.1, 2);
+        assert_eq!(archived.2, 3);
+
+        let mut page = Align([0u8; 32]);
+        let mut writer = Buffer::from(&mut *page);
+        Writer::<Error>::write(&mut writer, b"header").unwrap();
+        let pending =
+            serialize_root::<_, _, Error>(&value, &mut writer).unwrap();
+        assert!(pending.resolve_at(&mut writer, 4).is_err());
+
+        let pending =
+            serialize_root::<_, _, Error>(&value, &mut writer).unwrap();
+        assert!(pending.resolve_at(&mut writer, 10).is_err());
+
+        let pending =
+            serialize_root::<_, _, Error>(&value, &mut writer).unwrap();
+        assert_eq!(pending.resolve(&mut writer).unwrap(), 8);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn from_archived() {
+        use rkyv::{
+            access_unchecked, to_bytes_in_slice, util::Align, Archive,
+            Deserialize, Serialize,
+        };
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(from_archived)]
+        struct Point<T> {
+            x: T,
+            y: T,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(from_archived)]
+        enum Shape {
+            Dot(Point<i32>),
+            Line { from: Point<i32>, to: Point<i32> },
+        }
+
+        let value = Shape::Line {
+            from: Point { x: 1, y: 2 },
+            to: Point { x: 3, y: 4 },
+        };
+
+        let mut output = Align([0u8; 32]);
+        let bytes = to_bytes_in_slice::<Error>(&value, &mut *output).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedShape>(bytes) };
+        assert_eq!(Shape::from(archived), value);
+
+        let ArchivedShape::Line { to, .. } = archived else {
+            panic!("expected a line");
+        };
+        let to: Point<i32> = to.into();
+        assert_eq!(to, Point { x: 3, y: 4 });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_container_with() {
+        use rkyv::{
+            access_unchecked,
+            rancor::Fallible,
+            to_bytes_in_slice,
+            util::{deserialize, Align},
+            with::{ArchiveWith, DeserializeWith, SerializeWith},
+            Archive, Deserialize, Place, Serialize,
+        };
+
+        struct AsBits;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(with = AsBits)]
+        struct Permissions {
+            read: bool,
+            write: bool,
+            execute: bool,
+        }
+
+        impl ArchiveWith<Permissions> for AsBits {
+            type Archived = Archived<u8>;
+            type Resolver = ();
+
+            fn resolve_with(
+                field: &Permissions,
+                _: (),
+                out: Place<Self::Archived>,
+            ) {
+                let bits = field.read as u8
+                    | (field.write as u8) << 1
+                    | (field.execute as u8) << 2;
+                bits.resolve((), out);
+            }
+        }
+
+        impl<S: Fallible + ?Sized> SerializeWith<Permissions, S> for AsBits {
+            fn serialize_with(
+                _: &Permissions,
+                _: &mut S,
+            ) -> Result<(), S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> DeserializeWith<Archived<u8>, Permissions, D>
+            for AsBits
+        {
+            fn deserialize_with(
+                field: &Archived<u8>,
+                _: &mut D,
+            ) -> Result<Permissions, D::Error> {
+                Ok(Permissions {
+                    read: field & 1 != 0,
+                    write: field & 2 != 0,
+                    execute: field & 4 != 0,
+                })
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct File {
+            size: u32,
+            permissions: Permissions,
+        }
+
+        let value = File {
+            size: 42,
+            permissions: Permissions {
+                read: true,
+                write: false,
+                execute: true,
+            },
+        };
+
+        let mut output = Align([0u8; 16]);
+        let bytes = to_bytes_in_slice::<Error>(&value, &mut *output).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedFile>(bytes) };
+        assert_eq!(archived.size, 42);
+        assert_eq!(archived.permissions, 0b101);
+
+        let deserialized =
+            deserialize::<File, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+    }
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_schema() {
+        use core::{any::type_name, mem::size_of};
+
+        use rkyv::{
+            schema::{Schema, SchemaKind},
+            to_bytes_in_slice,
+            util::Align,
+            Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(schema)]
+        struct Header<T> {
+            version: u16,
+            #[rkyv(rename = "body")]
+            value: T,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Archive, Serialize)]
+        #[archive(schema)]
+        struct Pair(u8, u32);
+
+        #[allow(dead_code)]
+        #[derive(Archive, Serialize)]
+        #[archive(schema)]
+        #[repr(u8)]
+        enum Shape {
+            Circle { radius: u32, filled: bool },
+            Line(u16, u16),
+            Empty = 7,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[archive(schema, repr(u16))]
+        #[repr(u16)]
+        enum Wide {
+            Small,
+            Large = 300,
+        }
+
+        let schema = Archived::<Header<u64>>::SCHEMA;
+        assert_eq!(schema.size, size_of::<Archived<Header<u64>>>());
+        assert_eq!(schema.align, 8);
+        assert_eq!((schema.type_name)(), type_name::<Archived<Header<u64>>>());
+        let version = schema.field("version").unwrap();
+        assert_eq!((version.offset, version.size), (0, 2));
+        let body = schema.field("body").unwrap();
+        assert_eq!((body.offset, body.size), (8, 8));
+        assert_eq!((body.type_name)(), type_name::<Archived<u64>>());
+        assert!(schema.field("value").is_none());
+
+        let SchemaKind::Struct(fields) = ArchivedPair::SCHEMA.kind else {
+            panic!("expected a struct");
+        };
+        assert_eq!(fields.len(), 2);
+        assert_eq!((fields[0].name, fields[0].offset), ("0", 0));
+        assert_eq!((fields[1].name, fields[1].offset), ("1", 4));
+
+        let SchemaKind::Enum(shape) = ArchivedShape::SCHEMA.kind else {
+            panic!("expected an enum");
+        };
+        assert_eq!(shape.tag_size, 1);
+        assert_eq!(shape.variants.len(), 3);
+        let circle = shape.variant("Circle").unwrap();
+        assert_eq!(circle.tag, 0);
+        assert_eq!(circle.field("filled").unwrap().offset, 1);
+        assert_eq!(circle.field("radius").unwrap().offset, 4);
+        let line = shape.variant("Line").unwrap();
+        assert_eq!(line.tag, 1);
+        assert_eq!((line.fields[0].name, line.fields[0].offset), ("0", 2));
+        assert_eq!((line.fields[1].name, line.fields[1].offset), ("1", 4));
+        let empty = shape.variant_for_tag(7).unwrap();
+        assert_eq!(empty.name, "Empty");
+        assert!(empty.fields.is_empty());
+
+        let SchemaKind::Enum(wide) = ArchivedWide::SCHEMA.kind else {
+            panic!("expected an enum");
+        };
+        assert_eq!(wide.tag_size, 2);
+        assert_eq!(wide.variant_for_tag(300).unwrap().name, "Large");
+
+        // Read an archived value using only its schema
+        let value = Shape::Circle {
+            radius: 42,
+            filled: true,
+        };
+        let mut output = Align([0u8; 16]);
+        let bytes = to_bytes_in_slice::<Error>(&value, &mut *output).unwrap();
+        let root = bytes.len() - ArchivedShape::SCHEMA.size;
+        let variant = shape.variant_for_tag(bytes[root] as u64).unwrap();
+        assert_eq!(variant.name, "Circle");
+        let radius = variant.field("radius").unwrap();
+        let radius = unsafe {
+            &*bytes
+                .as_ptr()
+                .add(root + radius.offset)
+                .cast::<Archived<u32>>()
+        };
+        assert_eq!(*radius, 42);
+        let filled = variant.field("filled").unwrap();
+        assert_eq!(bytes[root + filled.offset], 1);
+    }
 }
@@ -630,6 +630,81 @@ mod tests {
         });
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn recursive_inferred_bounds() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Tree {
+            value: u32,
+            children: Vec<Tree>,
+        }
+
+        test_archive(&Tree {
+            value: 1,
+            children: vec![
+                Tree {
+                    value: 2,
+                    children: Vec::new(),
+                },
+                Tree {
+                    value: 3,
+                    children: vec![Tree {
+                        value: 4,
+                        children: Vec::new(),
+                    }],
+                },
+            ],
+        });
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum List<T: Archive>
+        where
+            T::Archived: core::fmt::Debug,
+        {
+            Nil,
+            Cons(T, Box<List<T>>),
+        }
+
+        test_archive(&List::Cons(
+            1i32,
+            Box::new(List::Cons(2, Box::new(List::Nil))),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn length_prefixed() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(length_prefixed)]
+        struct Test<T> {
+            a: T,
+            b: String,
+            #[rkyv(default)]
+            c: Option<Box<u32>>,
+        }
+
+        let value = Test {
+            a: 10u8,
+            b: "hello world".to_string(),
+            c: Some(Box::new(20)),
+        };
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Archived<Test<u8>>>(&buf) };
+        assert!(archived.is_complete());
+        let fields = archived.get().unwrap();
+        assert_eq!(fields.a, 10);
+        assert_eq!(**fields.c.as_ref().unwrap(), 20);
+        assert_eq!(fields.c.as_deref(), Some(&20.into()));
+
+        let deserialized =
+            deserialize::<Test<u8>, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn complex_bounds() {
@@ -729,6 +804,241 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_integer_ranges() {
+        use core::ops::{Range, RangeFrom, RangeInclusive, RangeTo};
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            a: Range<u64>,
+            b: RangeInclusive<i16>,
+            c: RangeFrom<u8>,
+            d: RangeTo<u32>,
+        }
+
+        let value = Test {
+            a: 3..6,
+            b: -2..=1,
+            c: 250..,
+            d: ..2,
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        assert_eq!(archived.a.as_range(), 3..6);
+        assert_eq!(archived.a.iter().collect::<Vec<_>>(), [3, 4, 5]);
+        assert_eq!((&archived.b).into_iter().sum::<i16>(), -2);
+        assert_eq!(archived.b.as_range_inclusive(), -2..=1);
+        assert_eq!(archived.c.iter().take(2).collect::<Vec<_>>(), [250, 251]);
+        assert_eq!(archived.d.as_range_to(), ..2);
+
+        let map = (0..10u64).map(|i| (i, i * i)).collect::<BTreeMap<_, _>>();
+        let squares = map
+            .range(archived.a.as_range())
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        assert_eq!(squares, [9, 16, 25]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_unordered_btree() {
+        use rkyv::{
+            collections::{
+                btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+                btree_set::{ArchivedBTreeSet, BTreeSetResolver},
+            },
+            ser::Allocator,
+            string::ArchivedString,
+        };
+
+        type Map = ArchivedBTreeMap<Archived<u32>, ArchivedString>;
+        type Set = ArchivedBTreeSet<Archived<u32>>;
+
+        struct Unordered(Vec<(u32, String)>);
+
+        impl Archive for Unordered {
+            type Archived = Map;
+            type Resolver = BTreeMapResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                Map::resolve_from_len(self.0.len(), resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Unordered
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Map::serialize_from_iter(
+                    self.0.iter().map(|(k, v)| (k, v)),
+                    serializer,
+                )
+            }
+        }
+
+        struct UnorderedKeys(Vec<u32>);
+
+        impl Archive for UnorderedKeys {
+            type Archived = Set;
+            type Resolver = BTreeSetResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                Set::resolve_from_len(self.0.len(), resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for UnorderedKeys
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Set::serialize_from_iter(self.0.iter(), serializer)
+            }
+        }
+
+        let keys = [17, 3, 42, 8, 0, 25, 11, 30, 5, 19, 1, 36];
+        let value =
+            Unordered(keys.iter().map(|&k| (k, (k * 2).to_string())).collect());
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Map>(&bytes) };
+        assert_eq!(archived.len(), keys.len());
+        for k in keys {
+            let k_le = Archived::<u32>::from_native(k);
+            assert_eq!(archived.get(&k_le).unwrap(), &(k * 2).to_string());
+        }
+        assert!(archived.get(&Archived::<u32>::from_native(2)).is_none());
+
+        let mut sorted = Vec::new();
+        archived.visit(|k, _| {
+            sorted.push(k.to_native());
+            core::ops::ControlFlow::<()>::Continue(())
+        });
+        let mut expected = keys.to_vec();
+        expected.sort();
+        assert_eq!(sorted, expected);
+
+        let bytes = to_bytes::<Error>(&UnorderedKeys(keys.to_vec())).unwrap();
+        let archived = unsafe { access_unchecked::<Set>(&bytes) };
+        for k in keys {
+            assert!(archived.contains_key(&Archived::<u32>::from_native(k)));
+        }
+
+        let duplicates = Unordered(vec![(1, "a".into()), (1, "b".into())]);
+        assert!(to_bytes::<Error>(&duplicates).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_control_flow_and_poll() {
+        use core::{ops::ControlFlow, task::Poll};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            a: ControlFlow<String, u32>,
+            b: ControlFlow<String, u32>,
+            c: ControlFlow<u8>,
+            d: Poll<Vec<i32>>,
+            e: Poll<Vec<i32>>,
+        }
+
+        let value = Test {
+            a: ControlFlow::Continue(42),
+            b: ControlFlow::Break("stop".to_string()),
+            c: ControlFlow::Continue(()),
+            d: Poll::Ready(vec![1, 2, 3]),
+            e: Poll::Pending,
+        };
+
+        test_archive(&value);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        assert!(archived.a.is_continue());
+        assert_eq!(
+            archived.a.continue_value().map(|v| v.to_native()),
+            Some(42)
+        );
+        assert!(archived.b.is_break());
+        assert_eq!(archived.b.break_value().map(|s| s.as_str()), Some("stop"));
+        assert!(archived.c.continue_value().is_some());
+        assert!(archived.d.is_ready());
+        assert!(matches!(archived.d.as_ref(), Poll::Ready(v) if v.len() == 3));
+        assert!(archived.e.is_pending());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_wrapping_and_saturating() {
+        use core::num::{Saturating, Wrapping};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive_attr(derive(Debug))]
+        struct Counters {
+            hits: Wrapping<u32>,
+            level: Saturating<i16>,
+            history: Vec<Wrapping<u64>>,
+        }
+
+        let value = Counters {
+            hits: Wrapping(u32::MAX) + Wrapping(2),
+            level: Saturating(i16::MIN) - Saturating(1),
+            history: vec![Wrapping(1), Wrapping(u64::MAX), Wrapping(0)],
+        };
+
+        test_archive_with(&value, |a, b| {
+            b.hits == a.hits.0
+                && b.level == a.level.0
+                && b.history.iter().zip(&a.history).all(|(b, a)| *b == a.0)
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_cell_and_ref_cell() {
+        use core::cell::{Cell, RefCell};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive_attr(derive(Debug))]
+        struct Node {
+            visits: Cell<u32>,
+            children: RefCell<Vec<String>>,
+        }
+
+        let value = Node {
+            visits: Cell::new(3),
+            children: RefCell::new(vec!["a".to_string(), "b".to_string()]),
+        };
+
+        test_archive_with(&value, |a, b| {
+            b.visits == a.visits.get() && b.children == *a.children.borrow()
+        });
+
+        let _guard = value.children.borrow_mut();
+        assert!(to_bytes::<Error>(&value).is_err());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_shared_ptr() {
@@ -1075,6 +1385,107 @@ mod tests {
         pub struct Array<T, const N: usize>([T; N]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn const_generic_array_lengths() {
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq), swap_endian, getters, from_archived)]
+        #[archive_attr(derive(Debug))]
+        pub struct Block<const N: usize = 4>
+        where
+            [u16; N]: Default,
+        {
+            data: [u16; N],
+            crc: u32,
+        }
+
+        test_archive(&Block::<4> {
+            data: [1, 2, 3, 4],
+            crc: 5,
+        });
+        test_archive(&Block::<0> { data: [], crc: 6 });
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        pub enum Chunk<T: Archive, const N: usize>
+        where
+            T::Archived: core::fmt::Debug,
+        {
+            Full([[T; N]; 2]),
+            Partial { data: [T; N], len: u32 },
+            Empty,
+        }
+
+        test_archive(&Chunk::Full([[1u8, 2, 3], [4, 5, 6]]));
+        test_archive(&Chunk::Partial {
+            data: [String::from("a"), String::from("b")],
+            len: 1,
+        });
+        test_archive(&Chunk::<u32, 8>::Empty);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn associated_type_fields() {
+        pub trait Config {
+            type Hash;
+            type Output;
+        }
+
+        #[derive(Debug, PartialEq)]
+        pub struct Sha;
+
+        impl Config for Sha {
+            type Hash = [u8; 4];
+            type Output = String;
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        pub struct Hash<T: Config> {
+            hash: <T as Config>::Hash,
+            output: T::Output,
+            items: Vec<T::Hash>,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        pub enum Output<T: Config> {
+            Hash(T::Hash),
+            Output {
+                output: T::Output,
+                next: Option<Box<Self>>,
+            },
+        }
+
+        let value = Hash::<Sha> {
+            hash: [1, 2, 3, 4],
+            output: "hello".to_string(),
+            items: vec![[5, 6, 7, 8]],
+        };
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Archived<Hash<Sha>>>(&buf) };
+        assert!(*archived == value);
+        assert_eq!(archived.output, "hello");
+        let deserialized =
+            deserialize::<Hash<Sha>, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+
+        let value = Output::<Sha>::Output {
+            output: "hello".to_string(),
+            next: Some(Box::new(Output::Hash([1, 2, 3, 4]))),
+        };
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<Output<Sha>>>(&buf) };
+        assert!(*archived == value);
+        let deserialized =
+            deserialize::<Output<Sha>, _, Error>(archived, &mut Pool::new())
+                .unwrap();
+        assert_eq!(deserialized, value);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn repr_c_packed() {
@@ -1430,6 +1841,78 @@ mod tests {
         assert_eq!(archived.value.load(Ordering::Relaxed), 42);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_rend_atomics() {
+        use core::sync::atomic::Ordering;
+
+        use rkyv::{
+            rend::{AtomicI64_le, AtomicU32_be},
+            with::{AsAtomic, AtomicLoad, Relaxed},
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AtomicLoad<Relaxed>)]
+            a: AtomicU32_be,
+            #[with(AsAtomic<Relaxed, Relaxed>)]
+            b: AtomicI64_le,
+        }
+
+        let value = Test {
+            a: AtomicU32_be::new(42),
+            b: AtomicI64_le::new(-42),
+        };
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked_mut::<ArchivedTest>(&mut bytes) };
+        assert_eq!(archived.a, 42);
+        assert_eq!(archived.b.load(Ordering::Relaxed), -42);
+
+        let deserialized =
+            deserialize::<Test, _, Error>(&*archived, &mut ()).unwrap();
+        assert_eq!(deserialized.a.load(Ordering::Relaxed), 42);
+        assert_eq!(deserialized.b.load(Ordering::Relaxed), -42);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_atomic_ptr_as_box() {
+        use core::{
+            ptr,
+            sync::atomic::{AtomicPtr, Ordering},
+        };
+
+        use rkyv::with::{AtomicPtrAsBox, Relaxed};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AtomicPtrAsBox<Relaxed>)]
+            some: AtomicPtr<String>,
+            #[with(AtomicPtrAsBox<Relaxed>)]
+            none: AtomicPtr<String>,
+        }
+
+        let mut hello = "hello world".to_string();
+        let value = Test {
+            some: AtomicPtr::new(&mut hello),
+            none: AtomicPtr::new(ptr::null_mut()),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+        assert_eq!(archived.some.as_deref().unwrap(), "hello world");
+        assert!(archived.none.is_none());
+
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        let some = deserialized.some.load(Ordering::Relaxed);
+        assert!(!some.is_null());
+        assert_ne!(some, &mut hello as *mut String);
+        let some = unsafe { Box::from_raw(some) };
+        assert_eq!(*some, "hello world");
+        assert!(deserialized.none.load(Ordering::Relaxed).is_null());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_inline() {
@@ -1516,31 +1999,165 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_as_vec() {
-        #[cfg(not(feature = "std"))]
-        use alloc::collections::{BTreeMap, BTreeSet};
-        #[cfg(feature = "std")]
-        use std::collections::{BTreeMap, BTreeSet};
-
-        use rkyv::with::AsVec;
+    fn with_as_cow() {
+        use rkyv::with::AsCow;
 
         #[derive(Archive, Serialize, Deserialize)]
-        struct Test {
-            #[with(AsVec)]
-            a: BTreeMap<String, String>,
-            #[with(AsVec)]
-            b: BTreeSet<String>,
-            #[with(AsVec)]
-            c: BTreeMap<String, String>,
+        #[allow(clippy::owned_cow)]
+        struct Test<'a> {
+            #[with(AsCow)]
+            a: Cow<'a, String>,
+            #[with(AsCow)]
+            b: Cow<'a, String>,
+            #[with(AsCow)]
+            c: Cow<'a, String>,
+            #[with(AsCow)]
+            d: Cow<'a, Vec<u32>>,
         }
 
-        let mut a = BTreeMap::new();
-        a.insert("foo".to_string(), "hello".to_string());
-        a.insert("bar".to_string(), "world".to_string());
-        a.insert("baz".to_string(), "bat".to_string());
+        let payload = "a payload which is too long to inline".to_string();
+        let value = Test {
+            a: Cow::Borrowed(&payload),
+            b: Cow::Borrowed(&payload),
+            c: Cow::Owned("owned".to_string()),
+            d: Cow::Owned(vec![1, 2, 3]),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
 
-        let mut b = BTreeSet::new();
-        b.insert("foo".to_string());
+        assert!(archived.a.is_borrowed());
+        assert!(archived.b.is_borrowed());
+        assert!(archived.c.is_owned());
+        assert!(archived.d.is_owned());
+        assert_eq!(*archived.a, payload);
+        assert_eq!(archived.b.get(), &payload);
+        assert_eq!(*archived.c, "owned");
+        assert_eq!(*archived.d, [1, 2, 3]);
+        // Both borrowed fields point to the same copy of the payload.
+        assert!(core::ptr::eq(archived.a.get(), archived.b.get()));
+
+        // Borrowing the payload again only stores it once.
+        let owned = Test {
+            a: Cow::Owned(payload.clone()),
+            b: Cow::Owned(payload.clone()),
+            c: Cow::Owned("owned".to_string()),
+            d: Cow::Owned(vec![1, 2, 3]),
+        };
+        let owned_bytes = to_bytes::<Error>(&owned).unwrap();
+        assert!(bytes.len() < owned_bytes.len());
+
+        let deserialized =
+            deserialize::<Test<'_>, _, Error>(archived, &mut ()).unwrap();
+        assert!(matches!(deserialized.a, Cow::Owned(_)));
+        assert_eq!(*deserialized.a, payload);
+        assert_eq!(*deserialized.b, payload);
+        assert_eq!(*deserialized.c, "owned");
+        assert_eq!(*deserialized.d, [1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_front_coded() {
+        use rkyv::with::AsFrontCoded;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AsFrontCoded)]
+            a: Vec<String>,
+            #[with(AsFrontCoded)]
+            b: BTreeSet<String>,
+            #[with(AsFrontCoded)]
+            c: Vec<String>,
+        }
+
+        let mut a = Vec::new();
+        for i in (0..50).rev() {
+            a.push(format!("prefix/{}/{}", i % 7, i));
+        }
+        // Shares a partial char with "caf\u{e9}"
+        a.push("caf\u{e8}".to_string());
+        a.push("caf\u{e9}".to_string());
+        a.push("caf\u{e9}".to_string());
+        a.push(String::new());
+
+        let b = ["apple", "applesauce", "application", "banana"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<BTreeSet<_>>();
+
+        let value = Test {
+            a: a.clone(),
+            b: b.clone(),
+            c: Vec::new(),
+        };
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        assert_eq!(archived.a.len(), sorted.len());
+        for (i, expected) in sorted.iter().enumerate() {
+            let string = archived.a.get(i).unwrap();
+            assert_eq!(string, expected.as_str());
+            assert_eq!(string.to_string(), *expected);
+            let found = archived.a.binary_search(expected).unwrap();
+            assert_eq!(sorted[found], *expected);
+        }
+        assert!(archived.a.get(sorted.len()).is_none());
+        assert!(archived.a.iter().eq(sorted.iter().map(|s| s.as_str())));
+        assert_eq!(archived.a.binary_search(""), Ok(0));
+        for missing in ["caf", "prefix/3/", "prefix/9", "zzz"] {
+            assert_eq!(
+                archived.a.binary_search(missing),
+                sorted.binary_search(&missing.to_string()),
+            );
+            assert!(!archived.a.contains(missing));
+        }
+
+        assert_eq!(archived.b.len(), 4);
+        assert!(archived.b.iter().eq(b.iter().map(|s| s.as_str())));
+        assert_eq!(archived.b.get(1).unwrap(), "applesauce");
+        assert_eq!(archived.b.binary_search("applet"), Err(2));
+
+        assert!(archived.c.is_empty());
+        assert!(archived.c.get(0).is_none());
+        assert_eq!(archived.c.binary_search("anything"), Err(0));
+
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized.a, sorted);
+        assert_eq!(deserialized.b, b);
+        assert!(deserialized.c.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_vec() {
+        #[cfg(not(feature = "std"))]
+        use alloc::collections::{BTreeMap, BTreeSet};
+        #[cfg(feature = "std")]
+        use std::collections::{BTreeMap, BTreeSet};
+
+        use rkyv::with::AsVec;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AsVec)]
+            a: BTreeMap<String, String>,
+            #[with(AsVec)]
+            b: BTreeSet<String>,
+            #[with(AsVec)]
+            c: BTreeMap<String, String>,
+        }
+
+        let mut a = BTreeMap::new();
+        a.insert("foo".to_string(), "hello".to_string());
+        a.insert("bar".to_string(), "world".to_string());
+        a.insert("baz".to_string(), "bat".to_string());
+
+        let mut b = BTreeSet::new();
+        b.insert("foo".to_string());
         b.insert("hello world!".to_string());
         b.insert("bar".to_string());
         b.insert("fizzbuzz".to_string());
@@ -1576,6 +2193,148 @@ mod tests {
         assert!(archived.b.iter().find(|&e| e == "fizzbuzz").is_some());
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_compact() {
+        use rkyv::with::Compact;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Compact)]
+            a: String,
+            #[with(Compact)]
+            b: String,
+            #[with(Compact)]
+            c: String,
+            #[with(Compact)]
+            d: Vec<u32>,
+            #[with(Compact)]
+            e: Vec<String>,
+            #[with(Compact)]
+            f: Vec<u64>,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Plain {
+            a: String,
+            b: String,
+            c: String,
+            d: Vec<u32>,
+            e: Vec<String>,
+            f: Vec<u64>,
+        }
+
+        let long = "x".repeat(200);
+        let value = Test {
+            a: "a string which is not inlined".to_string(),
+            b: String::new(),
+            c: long.clone(),
+            d: vec![1, 2, 3],
+            e: vec!["hello".to_string(), String::new()],
+            f: Vec::new(),
+        };
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        assert_eq!(archived.a, "a string which is not inlined");
+        assert!(archived.b.is_empty());
+        assert_eq!(archived.b, "");
+        assert_eq!(archived.c.len(), 200);
+        assert_eq!(archived.c, long.as_str());
+        assert_eq!(archived.d.as_slice(), &[1, 2, 3]);
+        assert_eq!(archived.d.as_ptr() as usize % 4, 0);
+        assert_eq!(archived.e.len(), 2);
+        assert_eq!(archived.e[0], "hello");
+        assert_eq!(archived.e[1], "");
+        assert!(archived.f.is_empty());
+
+        let plain = to_bytes::<Error>(&Plain {
+            a: value.a.clone(),
+            b: value.b.clone(),
+            c: value.c.clone(),
+            d: value.d.clone(),
+            e: value.e.clone(),
+            f: value.f.clone(),
+        })
+        .unwrap();
+        assert!(bytes.len() < plain.len());
+
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized.a, value.a);
+        assert_eq!(deserialized.b, value.b);
+        assert_eq!(deserialized.c, value.c);
+        assert_eq!(deserialized.d, value.d);
+        assert_eq!(deserialized.e, value.e);
+        assert_eq!(deserialized.f, value.f);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_small_vec() {
+        use rkyv::{vec::small::ArchivedSmallVec, with::AsSmallVec};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AsSmallVec<4>)]
+            a: Vec<u32>,
+            #[with(AsSmallVec<4>)]
+            b: Vec<u32>,
+            #[with(AsSmallVec<2>)]
+            c: Vec<String>,
+            #[with(AsSmallVec<2>)]
+            d: Vec<String>,
+            #[with(AsSmallVec<0>)]
+            e: Vec<u8>,
+        }
+
+        assert_eq!(
+            core::mem::size_of::<ArchivedSmallVec<Archived<u32>, 4>>(),
+            core::mem::size_of::<Archived<usize>>() + 16,
+        );
+
+        let value = Test {
+            a: vec![1, 2, 3],
+            b: vec![1, 2, 3, 4, 5],
+            c: vec!["a string which is too long to inline".to_string()],
+            d: vec!["x".to_string(), "y".to_string(), "z".to_string()],
+            e: Vec::new(),
+        };
+
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        assert!(archived.a.is_inline());
+        assert_eq!(archived.a, [1, 2, 3][..]);
+        assert!(!archived.b.is_inline());
+        assert_eq!(archived.b, [1, 2, 3, 4, 5][..]);
+        assert!(archived.c.is_inline());
+        assert_eq!(archived.c[0], "a string which is too long to inline");
+        assert!(!archived.d.is_inline());
+        assert_eq!(archived.d.len(), 3);
+        assert_eq!(archived.d[2], "z");
+        assert!(archived.e.is_inline());
+        assert!(archived.e.is_empty());
+
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized.a, value.a);
+        assert_eq!(deserialized.b, value.b);
+        assert_eq!(deserialized.c, value.c);
+        assert_eq!(deserialized.d, value.d);
+        assert_eq!(deserialized.e, value.e);
+
+        let mut archived =
+            unsafe { access_unchecked_mut::<ArchivedTest>(&mut bytes) };
+        let a = unsafe { archived.as_mut().map_unchecked_mut(|s| &mut s.a) };
+        *a.index_pin(0) = 10.into();
+        let b = unsafe { archived.as_mut().map_unchecked_mut(|s| &mut s.b) };
+        *b.index_pin(4) = 50.into();
+        assert_eq!(archived.a, [10, 2, 3][..]);
+        assert_eq!(archived.b, [1, 2, 3, 4, 50][..]);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_niche() {
@@ -1618,476 +2377,2524 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_niche_nonzero() {
-        use core::{
-            mem::size_of,
-            num::{
-                NonZeroI32, NonZeroI8, NonZeroIsize, NonZeroU32, NonZeroU8,
-                NonZeroUsize,
-            },
-        };
+    fn with_niche_string_and_vec() {
+        use core::mem::size_of;
 
-        use rkyv::with::Niche;
+        use rkyv::{deserialize, with::Niche};
 
-        #[derive(Archive, Serialize, Deserialize)]
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
         struct Test {
             #[with(Niche)]
-            a: Option<NonZeroI8>,
-            #[with(Niche)]
-            b: Option<NonZeroI32>,
-            #[with(Niche)]
-            c: Option<NonZeroIsize>,
+            short: Option<String>,
             #[with(Niche)]
-            d: Option<NonZeroU8>,
+            long: Option<String>,
             #[with(Niche)]
-            e: Option<NonZeroU32>,
+            vec: Option<Vec<u32>>,
             #[with(Niche)]
-            f: Option<NonZeroUsize>,
+            slice: Option<Box<[u32]>>,
         }
 
-        #[derive(Archive, Serialize, Deserialize)]
-        struct TestNoNiching {
-            a: Option<NonZeroI8>,
-            b: Option<NonZeroI32>,
-            c: Option<NonZeroIsize>,
-            d: Option<NonZeroU8>,
-            e: Option<NonZeroU32>,
-            f: Option<NonZeroUsize>,
-        }
+        assert!(
+            size_of::<Archived<Option<String>>>()
+                > size_of::<Archived<String>>()
+        );
+        assert_eq!(
+            size_of::<Archived<Test>>(),
+            size_of::<Archived<(String, String, Vec<u32>, Box<[u32]>)>>(),
+        );
 
         let value = Test {
-            a: Some(NonZeroI8::new(10).unwrap()),
-            b: Some(NonZeroI32::new(10).unwrap()),
-            c: Some(NonZeroIsize::new(10).unwrap()),
-            d: Some(NonZeroU8::new(10).unwrap()),
-            e: Some(NonZeroU32::new(10).unwrap()),
-            f: Some(NonZeroUsize::new(10).unwrap()),
+            short: Some("hi".to_string()),
+            long: Some("a string which is too long to inline".to_string()),
+            vec: Some(vec![]),
+            slice: Some(vec![1, 2, 3].into_boxed_slice()),
         };
         let bytes = to_bytes::<Error>(&value).unwrap();
         let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
-
-        assert!(archived.a.is_some());
-        assert_eq!(archived.a.as_ref().unwrap().get(), 10);
-        assert!(archived.b.is_some());
-        assert_eq!(archived.b.as_ref().unwrap().get(), 10);
-        assert!(archived.c.is_some());
-        assert_eq!(archived.c.as_ref().unwrap().get(), 10);
-        assert!(archived.d.is_some());
-        assert_eq!(archived.d.as_ref().unwrap().get(), 10);
-        assert!(archived.e.is_some());
-        assert_eq!(archived.e.as_ref().unwrap().get(), 10);
-        assert!(archived.f.is_some());
-        assert_eq!(archived.f.as_ref().unwrap().get(), 10);
+        assert_eq!(archived.short.as_deref(), Some("hi"));
+        assert_eq!(
+            archived.long.as_deref(),
+            Some("a string which is too long to inline"),
+        );
+        assert_eq!(archived.vec.as_deref(), Some(&[][..]));
+        assert_eq!(archived.slice, value.slice);
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
 
         let value = Test {
-            a: None,
-            b: None,
-            c: None,
-            d: None,
-            e: None,
-            f: None,
+            short: None,
+            long: None,
+            vec: None,
+            slice: None,
         };
         let bytes = to_bytes::<Error>(&value).unwrap();
         let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
-
-        assert!(archived.a.is_none());
-        assert!(archived.b.is_none());
-        assert!(archived.c.is_none());
-        assert!(archived.d.is_none());
-        assert!(archived.e.is_none());
-        assert!(archived.f.is_none());
-
-        assert!(
-            size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>()
-        );
+        assert!(archived.short.is_none());
+        assert!(archived.long.is_none());
+        assert!(archived.vec.is_none());
+        assert!(archived.slice.is_none());
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_unsafe() {
-        use core::cell::UnsafeCell;
+    fn with_endian() {
+        use core::num::NonZeroU16;
 
-        use rkyv::{primitive::ArchivedU32, with::Unsafe};
+        use rkyv::{
+            endian::normalize_endianness,
+            with::{BigEndian, LittleEndian},
+        };
 
-        #[derive(Archive, Serialize, Deserialize)]
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(swap_endian)]
         struct Test {
-            #[with(Unsafe)]
-            inner: UnsafeCell<u32>,
+            #[with(LittleEndian)]
+            a: u32,
+            #[with(BigEndian)]
+            b: u32,
+            #[with(BigEndian)]
+            c: NonZeroU16,
+            #[with(LittleEndian)]
+            d: f64,
+            e: u32,
         }
 
         let value = Test {
-            inner: UnsafeCell::new(100),
+            a: 0x01020304,
+            b: 0x01020304,
+            c: NonZeroU16::new(0x0102).unwrap(),
+            d: 1.5,
+            e: 0x01020304,
         };
-        let mut bytes = to_bytes::<Error>(&value).unwrap();
-        let archived =
-            unsafe { access_unchecked_mut::<ArchivedTest>(&mut bytes) };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
 
-        unsafe {
-            assert_eq!(*archived.inner.get(), 100);
-            *archived.inner.get() = ArchivedU32::from_native(42u32);
-            assert_eq!(*archived.inner.get(), 42);
+        assert_eq!(archived.a.to_native(), 0x01020304);
+        assert_eq!(archived.b.to_native(), 0x01020304);
+        assert_eq!(archived.c.to_native().get(), 0x0102);
+        assert_eq!(archived.d.to_native(), 1.5);
+
+        fn raw_bytes<T>(value: &T) -> &[u8] {
+            unsafe {
+                core::slice::from_raw_parts(
+                    (value as *const T).cast(),
+                    core::mem::size_of::<T>(),
+                )
+            }
         }
 
+        assert_eq!(raw_bytes(&archived.a), [4, 3, 2, 1]);
+        assert_eq!(raw_bytes(&archived.b), [1, 2, 3, 4]);
+        assert_eq!(raw_bytes(&archived.c), [1, 2]);
+
         let deserialized =
-            deserialize::<Test, _, Error>(&*archived, &mut Pool::new())
-                .unwrap();
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Fields with a fixed byte order are not converted.
+        let mut foreign = bytes.clone();
+        let pos =
+            raw_bytes(&archived.e).as_ptr() as usize - bytes.as_ptr() as usize;
+        foreign[pos..pos + 4].reverse();
         unsafe {
-            assert_eq!(*deserialized.inner.get(), 42);
-            *deserialized.inner.get() = 88;
-            assert_eq!(*deserialized.inner.get(), 88);
+            normalize_endianness::<ArchivedTest>(&mut foreign);
         }
+        assert_eq!(foreign.as_slice(), bytes.as_slice());
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_crate_path() {
-        use rkyv as alt_path;
+    fn with_c_str_array() {
+        use core::mem::{align_of, size_of};
 
-        #[derive(Archive, Deserialize, Serialize)]
-        #[archive(crate = alt_path)]
-        struct Test<'a> {
-            #[with(alt_path::with::BoxedInline)]
-            value: &'a str,
-            other: i32,
+        use rkyv::with::CStrArray;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive_attr(repr(C))]
+        struct Device {
+            #[with(CStrArray<8>)]
+            name: String,
+            #[with(CStrArray<4>)]
+            code: [u8; 4],
         }
-    }
 
-    #[test]
-    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_btree_map() {
-        let mut value = BTreeMap::new();
-        value.insert("foo".to_string(), 10);
-        value.insert("bar".to_string(), 20);
-        value.insert("baz".to_string(), 40);
-        value.insert("bat".to_string(), 80);
+        assert_eq!(size_of::<ArchivedDevice>(), 12);
+        assert_eq!(align_of::<ArchivedDevice>(), 1);
 
-        let result = to_bytes::<Error>(&value).unwrap();
-        let archived = unsafe {
-            access_unchecked::<Archived<BTreeMap<String, i32>>>(
-                result.as_slice(),
-            )
+        let value = Device {
+            name: "eth0".to_string(),
+            code: *b"ab\0c",
         };
-
-        assert_eq!(archived.len(), 4);
-        for (k, v) in value.iter() {
-            let (ak, av) = archived
-                .get_key_value(k.as_str())
-                .expect("failed to find key in archived B-tree map");
-            assert_eq!(k, ak);
-            assert_eq!(v, av);
-        }
-        assert!(archived.get_key_value("wrong!").is_none());
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDevice>(&bytes) };
+        assert_eq!(&archived.name, b"eth0\0\0\0\0");
+        assert_eq!(&archived.code, b"ab\0\0");
 
         let deserialized =
-            deserialize::<BTreeMap<_, _>, _, Infallible>(archived, &mut ())
+            deserialize::<Device, _, Error>(archived, &mut Pool::new())
                 .unwrap();
-        assert_eq!(value, deserialized);
+        assert_eq!(deserialized.name, value.name);
+        assert_eq!(&deserialized.code, b"ab\0\0");
+
+        let too_long = Device {
+            name: "12345678".to_string(),
+            code: *b"abc\0",
+        };
+        assert!(to_bytes::<Error>(&too_long).is_err());
+
+        let interior_nul = Device {
+            name: "a\0b".to_string(),
+            code: *b"abc\0",
+        };
+        assert!(to_bytes::<Error>(&interior_nul).is_err());
+
+        let unterminated = Device {
+            name: "eth0".to_string(),
+            code: *b"abcd",
+        };
+        assert!(to_bytes::<Error>(&unterminated).is_err());
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_empty_btree_map() {
-        let value: BTreeMap<String, i32> = BTreeMap::new();
+    fn with_align8() {
+        use core::mem::{align_of, size_of, transmute};
 
-        let result = to_bytes::<Error>(&value).unwrap();
-        let archived = unsafe {
-            access_unchecked::<Archived<BTreeMap<String, i32>>>(
-                result.as_slice(),
-            )
+        use rkyv::{
+            primitive::{ArchivedAlign8U128, ArchivedU128},
+            with::Align8,
         };
 
-        assert_eq!(archived.len(), 0);
-        #[allow(clippy::never_loop)]
-        archived.visit(|_, _| -> ::core::ops::ControlFlow<()> {
-            panic!("there should be no values in the archived empty btree");
-        });
-        assert!(archived.get_key_value("wrong!").is_none());
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive_attr(repr(C))]
+        struct Test {
+            a: u32,
+            #[with(Align8)]
+            b: u128,
+            #[with(Align8)]
+            c: i128,
+        }
+
+        assert!(align_of::<ArchivedTest>() <= 8);
+        assert!(size_of::<ArchivedTest>() <= 40);
+
+        let value = Test {
+            a: 42,
+            b: 0x0011_2233_4455_6677_8899_aabb_ccdd_eeff,
+            c: i128::MIN + 12345,
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+        assert_eq!(archived.b, value.b);
+        assert_eq!(archived.c, value.c);
+
+        // The words are stored in the same order as an archived `u128`.
+        let (words, expected) = unsafe {
+            (
+                transmute::<ArchivedAlign8U128, [u8; 16]>(archived.b),
+                transmute::<ArchivedU128, [u8; 16]>(ArchivedU128::from_native(
+                    value.b,
+                )),
+            )
+        };
+        assert_eq!(words, expected);
 
         let deserialized =
-            deserialize::<BTreeMap<_, _>, _, Infallible>(archived, &mut ())
-                .unwrap();
-        assert_eq!(value, deserialized);
+            deserialize::<Test, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_btree_set() {
-        let mut value = BTreeSet::new();
-        value.insert("foo".to_string());
-        value.insert("bar".to_string());
-        value.insert("baz".to_string());
-        value.insert("bat".to_string());
+    fn with_total_ord_float() {
+        use core::{cmp::Ordering, ops::ControlFlow};
 
-        let result = to_bytes::<Error>(&value).unwrap();
-        let archived = unsafe {
-            access_unchecked::<Archived<BTreeSet<String>>>(result.as_slice())
+        use rkyv::{
+            primitive::{ArchivedTotalF32, ArchivedTotalF64},
+            with::TotalOrdFloat,
         };
 
-        assert_eq!(archived.len(), 4);
-        for k in value.iter() {
-            let ak = archived
-                .get(k.as_str())
-                .expect("failed to find key in archived B-tree map");
-            assert_eq!(k, ak);
+        #[derive(Archive, Serialize, Deserialize, Clone, Copy, Debug)]
+        #[archive_attr(derive(Debug, PartialEq, Eq, PartialOrd, Ord))]
+        struct Key {
+            #[with(TotalOrdFloat)]
+            value: f64,
         }
-        assert!(archived.get("wrong!").is_none());
+
+        impl Ord for Key {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.value.total_cmp(&other.value)
+            }
+        }
+
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for Key {}
+
+        let values = [f64::NAN, 1.5, -0.0, 0.0, f64::NEG_INFINITY, -2.0];
+        let map = values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| (Key { value }, i as u32))
+            .collect::<BTreeMap<_, _>>();
+
+        let bytes = to_bytes::<Error>(&map).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<BTreeMap<Key, u32>>>(&bytes) };
+        let mut keys = Vec::new();
+        archived.visit(|key, _| {
+            keys.push(key.value.to_native().to_bits());
+            ControlFlow::<()>::Continue(())
+        });
+        let expected = map
+            .keys()
+            .map(|key| key.value.to_bits())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, expected);
+
+        let get = |value: f64| {
+            archived
+                .get(&ArchivedKey {
+                    value: ArchivedTotalF64::from_native(value),
+                })
+                .map(|index| index.to_native())
+        };
+        assert_eq!(get(f64::NAN), Some(0));
+        assert_eq!(get(-0.0), Some(2));
+        assert_eq!(get(0.0), Some(3));
+        assert_eq!(get(3.0), None);
 
         let deserialized =
-            deserialize::<BTreeSet<_>, _, Infallible>(archived, &mut ())
+            deserialize::<BTreeMap<Key, u32>, _, Error>(archived, &mut ())
                 .unwrap();
-        assert_eq!(value, deserialized);
+        assert_eq!(deserialized, map);
+
+        assert!(ArchivedTotalF32::from_native(-0.0) < 0.0);
+        assert!(ArchivedTotalF32::from_native(f32::NAN) > f32::INFINITY);
+        assert_eq!(ArchivedTotalF32::from_native(f32::NAN), f32::NAN);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_btree_map_large() {
-        use core::ops::ControlFlow;
-
-        // This test creates structures too big to fit in 16-bit offsets, and
-        // MIRI can't run it quickly enough.
-        #[cfg(any(feature = "size_16", miri))]
-        const ENTRIES: usize = 100;
-        #[cfg(not(miri))]
-        const ENTRIES: usize = 100_000;
+    fn with_dedup() {
+        use rkyv::with::Dedup;
 
-        let mut value = BTreeMap::new();
-        for i in 0..ENTRIES {
-            value.insert(i.to_string(), i as i32);
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Example {
+            #[with(Dedup)]
+            a: String,
+            #[with(Dedup)]
+            b: String,
+            #[with(Dedup)]
+            c: Box<str>,
+            #[with(Dedup)]
+            d: Box<[u8]>,
+            #[with(Dedup)]
+            e: String,
         }
 
-        let result = to_bytes::<Error>(&value).unwrap();
-        let archived = unsafe {
-            access_unchecked::<Archived<BTreeMap<String, i32>>>(
-                result.as_slice(),
-            )
+        let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        let value = Example {
+            a: id.to_string(),
+            b: id.to_string(),
+            c: id.into(),
+            d: id.as_bytes().into(),
+            e: "short".to_string(),
         };
 
-        assert_eq!(archived.len(), ENTRIES);
-        let mut iter = value.iter();
-        archived.visit(|ak, av| {
-            let (k, v) = iter.next().unwrap();
-            assert_eq!(k, ak);
-            assert_eq!(v, av);
-            ControlFlow::<()>::Continue(())
-        });
-
-        for (k, v) in value.iter() {
-            let av = archived
-                .get(k.as_str())
-                .expect("failed to find key in archived B-tree map");
-            assert_eq!(v, av);
+        fn serialize(value: &Example, sharing: Share) -> AlignedVec {
+            let mut arena = Arena::new();
+            serialize_into::<_, Error>(
+                value,
+                Serializer::new(AlignedVec::new(), arena.acquire(), sharing),
+            )
+            .unwrap()
+            .into_writer()
         }
-        assert!(archived.get("wrong!").is_none());
+
+        // SAFETY: The archive is only accessed with `access_unchecked`.
+        let shared = serialize(&value, unsafe { Share::with_content_dedup() });
+        let unshared = serialize(&value, Share::new());
+        assert!(shared.len() + 3 * id.len() <= unshared.len());
+
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&shared) };
+        assert_eq!(archived.a, id);
+        assert_eq!(archived.b, id);
+        assert_eq!(&*archived.c, id);
+        assert_eq!(&*archived.d, id.as_bytes());
+        assert_eq!(archived.e, "short");
+        assert_eq!(archived.a.as_ptr(), archived.b.as_ptr());
+        assert_eq!(archived.a.as_ptr(), archived.c.as_ptr());
+        assert_eq!(archived.a.as_ptr(), archived.d.as_ptr());
 
         let deserialized =
-            deserialize::<BTreeMap<_, _>, _, Infallible>(archived, &mut ())
+            deserialize::<Example, _, Error>(archived, &mut Pool::new())
                 .unwrap();
-        assert_eq!(value, deserialized);
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_zst_containers() {
-        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
-        #[archive(compare(PartialEq))]
-        #[archive_attr(derive(Debug))]
-        struct MyZST;
-
-        test_archive(&Box::new(MyZST));
-
-        test_archive(&Vec::<MyZST>::new().into_boxed_slice());
-        test_archive(&vec![MyZST, MyZST, MyZST, MyZST].into_boxed_slice());
-
-        test_archive(&Vec::<MyZST>::new());
-        test_archive(&vec![MyZST, MyZST, MyZST, MyZST]);
-
-        let mut value = BTreeMap::new();
-        value.insert(0, ());
-        value.insert(1, ());
-        test_archive(&value);
+    fn with_dedup_subtrees() {
+        use rkyv::with::Dedup;
 
-        let mut value = BTreeMap::new();
-        value.insert((), 10);
-        test_archive(&value);
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(
+            relocate,
+            serialize_bounds(
+                __S: rkyv::ser::Allocator
+                    + rkyv::ser::Writer
+                    + rkyv::ser::Sharing,
+            ),
+            deserialize_bounds(__D::Error: rkyv::rancor::Source),
+        )]
+        enum Expr {
+            Lit(u32),
+            Var(#[with(Dedup)] String),
+            Add(
+                #[omit_bounds]
+                #[with(Dedup)]
+                Box<Expr>,
+                #[omit_bounds]
+                #[with(Dedup)]
+                Box<Expr>,
+            ),
+            Call(
+                #[with(Dedup)] String,
+                #[omit_bounds]
+                #[with(Dedup)]
+                Vec<Expr>,
+            ),
+        }
 
-        let mut value = BTreeMap::new();
-        value.insert((), ());
-        test_archive(&value);
+        fn branch() -> Expr {
+            Expr::Call(
+                "a function name which is too long to inline".to_string(),
+                vec![
+                    Expr::Var("a variable which is too long to inline".into()),
+                    Expr::Add(Box::new(Expr::Lit(1)), Box::new(Expr::Lit(2))),
+                ],
+            )
+        }
 
-        let mut value = BTreeSet::new();
-        value.insert(());
-        test_archive(&value);
+        let value = Expr::Add(
+            Box::new(Expr::Add(Box::new(branch()), Box::new(branch()))),
+            Box::new(branch()),
+        );
 
-        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
-        #[archive(compare(PartialEq))]
-        #[archive_attr(derive(Debug))]
-        struct TestRcZST {
-            a: Rc<()>,
-            b: Rc<()>,
+        fn serialize(value: &Expr, sharing: Share) -> AlignedVec {
+            let mut arena = Arena::new();
+            serialize_into::<_, Error>(
+                value,
+                Serializer::new(AlignedVec::new(), arena.acquire(), sharing),
+            )
+            .unwrap()
+            .into_writer()
         }
 
-        let rc_zst = Rc::new(());
-        test_archive(&TestRcZST {
-            a: rc_zst.clone(),
-            b: rc_zst.clone(),
-        });
+        // SAFETY: The archive is only accessed with `access_unchecked`.
+        let shared = serialize(&value, unsafe { Share::with_subtree_dedup() });
+        // SAFETY: This archive is never accessed.
+        let content = serialize(&value, unsafe { Share::with_content_dedup() });
+        let unshared = serialize(&value, Share::new());
+        assert!(shared.len() < content.len());
+        assert!(content.len() < unshared.len());
+
+        let archived = unsafe { access_unchecked::<ArchivedExpr>(&shared) };
+        let ArchivedExpr::Add(left, right) = archived else {
+            panic!("expected an addition");
+        };
+        let ArchivedExpr::Add(a, b) = &**left else {
+            panic!("expected an addition");
+        };
+        // All three branches point to the same copy.
+        assert!(core::ptr::eq(&**a, &**b));
+        assert!(core::ptr::eq(&**a, &**right));
+
+        let deserialized =
+            deserialize::<Expr, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn scratch_tracker() {
-        use rkyv::ser::{
-            allocator::{AllocationTracker, Arena, ArenaHandle},
-            Serializer,
-        };
+    fn with_dedup_unique_subtrees() {
+        use rkyv::with::Dedup;
 
-        type TrackerSerializer<'a, E> = Strategy<
-            Serializer<AlignedVec, AllocationTracker<ArenaHandle<'a>>, ()>,
-            E,
-        >;
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(
+            relocate,
+            serialize_bounds(
+                __S: rkyv::ser::Allocator
+                    + rkyv::ser::Writer
+                    + rkyv::ser::Sharing,
+            ),
+            deserialize_bounds(__D::Error: rkyv::rancor::Source),
+        )]
+        enum Expr {
+            Lit(u32),
+            Var(String),
+            Add(
+                #[omit_bounds]
+                #[with(Dedup)]
+                Box<Expr>,
+                #[omit_bounds]
+                #[with(Dedup)]
+                Box<Expr>,
+            ),
+            Call(
+                String,
+                #[omit_bounds]
+                #[with(Dedup)]
+                Vec<Expr>,
+            ),
+        }
 
-        fn track_serialize<T>(value: &T) -> AllocationStats
-        where
-            T: for<'a> Serialize<TrackerSerializer<'a, Error>>,
-        {
-            let mut arena = Arena::new();
+        fn branch(i: u32) -> Expr {
+            Expr::Call(
+                format!("function number {i}, which is too long to inline"),
+                vec![
+                    Expr::Var(format!("variable {i}, which is too long too")),
+                    Expr::Add(
+                        Box::new(Expr::Lit(2 * i)),
+                        Box::new(Expr::Lit(2 * i + 1)),
+                    ),
+                ],
+            )
+        }
 
-            let serializer = serialize_into(
+        let value = Expr::Add(
+            Box::new(Expr::Add(Box::new(branch(0)), Box::new(branch(1)))),
+            Box::new(branch(2)),
+        );
+
+        fn serialize(value: &Expr, sharing: Share) -> AlignedVec {
+            let mut arena = Arena::new();
+            serialize_into::<_, Error>(
                 value,
-                Serializer::new(
-                    AlignedVec::new(),
-                    AllocationTracker::new(arena.acquire()),
-                    (),
-                ),
+                Serializer::new(AlignedVec::new(), arena.acquire(), sharing),
             )
-            .unwrap();
-            serializer.into_raw_parts().1.into_stats()
+            .unwrap()
+            .into_writer()
         }
 
-        let stats = track_serialize(&42);
-        assert_eq!(stats.max_bytes_allocated, 0);
-        assert_eq!(stats.max_allocations, 0);
-        assert_eq!(stats.max_alignment, 1);
-        assert_eq!(stats.min_arena_capacity(), 0);
-        assert_eq!(stats.min_arena_capacity_max_error(), 0);
-
-        let stats = track_serialize(&vec![1, 2, 3, 4]);
-        assert_eq!(stats.max_bytes_allocated, 0);
-        assert_eq!(stats.max_allocations, 0);
-        assert_eq!(stats.max_alignment, 1);
-        assert_eq!(stats.min_arena_capacity(), 0);
-        assert_eq!(stats.min_arena_capacity_max_error(), 0);
+        // SAFETY: The archive is only accessed with `access_unchecked`.
+        let shared = serialize(&value, unsafe { Share::with_subtree_dedup() });
+        let unshared = serialize(&value, Share::new());
+        // Unique subtrees are only written once.
+        assert_eq!(shared.len(), unshared.len());
 
-        let stats = track_serialize(&vec![vec![1, 2], vec![3, 4]]);
-        assert_ne!(stats.max_bytes_allocated, 0);
-        assert_eq!(stats.max_allocations, 1);
-        assert_ne!(stats.min_arena_capacity(), 0);
+        let archived = unsafe { access_unchecked::<ArchivedExpr>(&shared) };
+        let deserialized =
+            deserialize::<Expr, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_manually_drop() {
-        use core::mem::ManuallyDrop;
+    fn with_subslice() {
+        use rkyv::with::Subslice;
 
-        let vec = ManuallyDrop::new(vec![
-            "hello world".to_string(),
-            "me too!".to_string(),
-        ]);
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Example<'a> {
+            #[with(Subslice)]
+            a: Cow<'a, [u8]>,
+            #[with(Subslice)]
+            b: Cow<'a, str>,
+            #[with(Subslice)]
+            c: Cow<'a, [u8]>,
+            #[with(Subslice)]
+            d: Cow<'a, str>,
+            #[with(Subslice)]
+            e: Cow<'a, [u8]>,
+        }
 
-        let bytes = to_bytes::<Error>(&vec).unwrap();
-        let archived = unsafe {
-            access_unchecked::<Archived<ManuallyDrop<Vec<String>>>>(&bytes)
+        let text = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUV";
+        let value = Example {
+            // Serialized before the wider view, so it isn't shared.
+            a: Cow::Borrowed(&text.as_bytes()[8..40]),
+            b: Cow::Borrowed(&text[0..50]),
+            c: Cow::Borrowed(&text.as_bytes()[20..45]),
+            d: Cow::Borrowed(&text[10..50]),
+            e: Cow::Owned(text.as_bytes()[10..50].to_vec()),
         };
 
-        assert_eq!(archived.len(), vec.len());
-        for (a, b) in archived.iter().zip(vec.iter()) {
-            assert_eq!(a, b);
+        fn serialize(value: &Example<'_>, sharing: Share) -> AlignedVec {
+            let mut arena = Arena::new();
+            serialize_into::<_, Error>(
+                value,
+                Serializer::new(AlignedVec::new(), arena.acquire(), sharing),
+            )
+            .unwrap()
+            .into_writer()
         }
 
-        drop(ManuallyDrop::into_inner(vec));
+        // SAFETY: The archive is only accessed with `access_unchecked`.
+        let shared =
+            serialize(&value, unsafe { Share::with_subslice_sharing() });
+        let unshared = serialize(&value, Share::new());
+        // The root may need up to one alignment of extra padding.
+        let padding = core::mem::align_of::<ArchivedExample>();
+        assert!(shared.len() + 25 + 40 <= unshared.len() + padding);
+
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&shared) };
+        assert_eq!(archived.a.as_slice(), &text.as_bytes()[8..40]);
+        assert_eq!(archived.b, &text[0..50]);
+        assert_eq!(archived.c.as_slice(), &text.as_bytes()[20..45]);
+        assert_eq!(archived.d, &text[10..50]);
+        assert_eq!(archived.e.as_slice(), &text.as_bytes()[10..50]);
+        let base = archived.b.as_ptr();
+        assert_eq!(archived.c.as_ptr(), base.wrapping_add(20));
+        assert_eq!(archived.d.as_ptr(), base.wrapping_add(10));
+        assert_ne!(archived.a.as_ptr(), base.wrapping_add(8));
+        assert_ne!(archived.e.as_ptr(), base.wrapping_add(10));
+
+        let deserialized =
+            deserialize::<Example<'_>, _, Error>(archived, &mut ()).unwrap();
+        assert!(matches!(deserialized.c, Cow::Owned(_)));
+        assert_eq!(*deserialized.c, text.as_bytes()[20..45]);
+        assert_eq!(deserialized.d, &text[10..50]);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_bound() {
-        use core::ops::Bound;
+    fn with_intern() {
+        #[cfg(not(feature = "std"))]
+        use alloc::sync::Arc;
+        #[cfg(feature = "std")]
+        use std::sync::Arc;
 
-        test_archive(&Bound::Included("hello world".to_string()));
-        test_archive(&Bound::Excluded("hello world".to_string()));
-        test_archive(&Bound::<String>::Unbounded);
+        use rkyv::with::{Intern, Map};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Example {
+            #[with(Intern)]
+            name: Arc<str>,
+            #[with(Map<Intern>)]
+            keys: Vec<Arc<str>>,
+        }
+
+        let value = Example {
+            name: Arc::from("a"),
+            keys: vec![Arc::from("a"), Arc::from("b"), Arc::from("a")],
+        };
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&bytes) };
+        assert_eq!(archived.name, "a");
+        assert_eq!(archived.keys[1], "b");
+
+        let interned = deserialize::<Example, _, Error>(
+            archived,
+            &mut Pool::with_string_interning(),
+        )
+        .unwrap();
+        assert_eq!(&*interned.name, "a");
+        assert_eq!(&*interned.keys[1], "b");
+        assert!(Arc::ptr_eq(&interned.name, &interned.keys[0]));
+        assert!(Arc::ptr_eq(&interned.name, &interned.keys[2]));
+        assert!(!Arc::ptr_eq(&interned.name, &interned.keys[1]));
+
+        let uninterned =
+            deserialize::<Example, _, Error>(archived, &mut Pool::new())
+                .unwrap();
+        assert_eq!(&*uninterned.keys[2], "a");
+        assert!(!Arc::ptr_eq(&uninterned.name, &uninterned.keys[0]));
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn ambiguous_niched_archived_box() {
-        use rkyv::{rancor::Failure, with::Niche};
+    fn with_unshare() {
+        use rkyv::with::Unshare;
 
-        #[derive(Archive, Deserialize, Serialize)]
-        struct HasNiche {
-            #[with(Niche)]
-            inner: Option<Box<[u32]>>,
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            a: Rc<u32>,
+            b: Rc<u32>,
+            #[with(Unshare)]
+            c: Rc<u32>,
+            #[with(Unshare)]
+            d: Rc<[u32]>,
         }
 
-        let some_empty = HasNiche {
-            inner: Some(Box::<[u32]>::from([])),
+        let shared = Rc::new(42);
+        let value = Example {
+            a: shared.clone(),
+            b: shared.clone(),
+            c: shared,
+            d: Rc::from([1, 2, 3]),
         };
-        let bytes = rkyv::to_bytes::<Failure>(&some_empty).unwrap();
-        eprintln!("some bytes: {:?}", bytes);
-        let archived =
-            unsafe { rkyv::access_unchecked::<ArchivedHasNiche>(&bytes) };
-        let deser =
-            deserialize::<HasNiche, _, Failure>(archived, &mut ()).unwrap();
 
-        assert!(archived.inner.is_some());
-        assert!(deser.inner.is_some());
-        assert_eq!(some_empty.inner, deser.inner);
-        assert_eq!(archived.inner.as_ref().unwrap().len(), 0);
-        assert_eq!(deser.inner.as_ref().unwrap().len(), 0);
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+        assert_eq!(*archived.c, 42);
+        assert_eq!(&*archived.d, &[1, 2, 3]);
+        assert_eq!(archived.a.get() as *const _, archived.b.get() as *const _);
+        assert_ne!(archived.a.get() as *const _, archived.c.get() as *const _);
+
+        let mut deserialized =
+            deserialize::<Example, _, Error>(archived, &mut Pool::new())
+                .unwrap();
+        assert!(Rc::ptr_eq(&deserialized.a, &deserialized.b));
+        assert!(!Rc::ptr_eq(&deserialized.a, &deserialized.c));
 
-        let none = HasNiche { inner: None };
-        let bytes = rkyv::to_bytes::<Failure>(&none).unwrap();
-        eprintln!("none bytes: {:?}", bytes);
+        *Rc::get_mut(&mut deserialized.c).unwrap() = 7;
+        assert_eq!(*deserialized.a, 42);
+        assert_eq!(*deserialized.c, 7);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_niche_nonzero() {
+        use core::{
+            mem::size_of,
+            num::{
+                NonZeroI32, NonZeroI8, NonZeroIsize, NonZeroU32, NonZeroU8,
+                NonZeroUsize,
+            },
+        };
+
+        use rkyv::with::Niche;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Niche)]
+            a: Option<NonZeroI8>,
+            #[with(Niche)]
+            b: Option<NonZeroI32>,
+            #[with(Niche)]
+            c: Option<NonZeroIsize>,
+            #[with(Niche)]
+            d: Option<NonZeroU8>,
+            #[with(Niche)]
+            e: Option<NonZeroU32>,
+            #[with(Niche)]
+            f: Option<NonZeroUsize>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct TestNoNiching {
+            a: Option<NonZeroI8>,
+            b: Option<NonZeroI32>,
+            c: Option<NonZeroIsize>,
+            d: Option<NonZeroU8>,
+            e: Option<NonZeroU32>,
+            f: Option<NonZeroUsize>,
+        }
+
+        let value = Test {
+            a: Some(NonZeroI8::new(10).unwrap()),
+            b: Some(NonZeroI32::new(10).unwrap()),
+            c: Some(NonZeroIsize::new(10).unwrap()),
+            d: Some(NonZeroU8::new(10).unwrap()),
+            e: Some(NonZeroU32::new(10).unwrap()),
+            f: Some(NonZeroUsize::new(10).unwrap()),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        assert!(archived.a.is_some());
+        assert_eq!(archived.a.as_ref().unwrap().get(), 10);
+        assert!(archived.b.is_some());
+        assert_eq!(archived.b.as_ref().unwrap().get(), 10);
+        assert!(archived.c.is_some());
+        assert_eq!(archived.c.as_ref().unwrap().get(), 10);
+        assert!(archived.d.is_some());
+        assert_eq!(archived.d.as_ref().unwrap().get(), 10);
+        assert!(archived.e.is_some());
+        assert_eq!(archived.e.as_ref().unwrap().get(), 10);
+        assert!(archived.f.is_some());
+        assert_eq!(archived.f.as_ref().unwrap().get(), 10);
+
+        let value = Test {
+            a: None,
+            b: None,
+            c: None,
+            d: None,
+            e: None,
+            f: None,
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+
+        assert!(archived.a.is_none());
+        assert!(archived.b.is_none());
+        assert!(archived.c.is_none());
+        assert!(archived.d.is_none());
+        assert!(archived.e.is_none());
+        assert!(archived.f.is_none());
+
+        assert!(
+            size_of::<Archived<Test>>() < size_of::<Archived<TestNoNiching>>()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_unsafe() {
+        use core::cell::UnsafeCell;
+
+        use rkyv::{primitive::ArchivedU32, with::Unsafe};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Unsafe)]
+            inner: UnsafeCell<u32>,
+        }
+
+        let value = Test {
+            inner: UnsafeCell::new(100),
+        };
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
         let archived =
-            unsafe { rkyv::access_unchecked::<ArchivedHasNiche>(&bytes) };
-        let deser =
-            deserialize::<HasNiche, _, Failure>(archived, &mut ()).unwrap();
+            unsafe { access_unchecked_mut::<ArchivedTest>(&mut bytes) };
 
-        assert!(archived.inner.is_none());
-        assert!(deser.inner.is_none());
-        assert_eq!(none.inner, deser.inner);
+        unsafe {
+            assert_eq!(*archived.inner.get(), 100);
+            *archived.inner.get() = ArchivedU32::from_native(42u32);
+            assert_eq!(*archived.inner.get(), 42);
+        }
+
+        let deserialized =
+            deserialize::<Test, _, Error>(&*archived, &mut Pool::new())
+                .unwrap();
+        unsafe {
+            assert_eq!(*deserialized.inner.get(), 42);
+            *deserialized.inner.get() = 88;
+            assert_eq!(*deserialized.inner.get(), 88);
+        }
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn reuse_arena() {
-        let mut bytes = AlignedVec::<16>::with_capacity(1024);
-        let mut arena = Arena::with_capacity(2);
+    fn archive_crate_path() {
+        use rkyv as alt_path;
 
-        let value = vec![
-            "hello".to_string(),
-            "world".to_string(),
-            "foo".to_string(),
-            "bar".to_string(),
-            "baz".to_string(),
-        ];
+        #[derive(Archive, Deserialize, Serialize)]
+        #[archive(crate = alt_path)]
+        struct Test<'a> {
+            #[with(alt_path::with::BoxedInline)]
+            value: &'a str,
+            other: i32,
+        }
+    }
 
-        for _ in 0..10 {
-            let mut buffer = core::mem::take(&mut bytes);
-            buffer.clear();
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_map() {
+        let mut value = BTreeMap::new();
+        value.insert("foo".to_string(), 10);
+        value.insert("bar".to_string(), 20);
+        value.insert("baz".to_string(), 40);
+        value.insert("bat".to_string(), 80);
 
-            serialize_into::<_, Error>(
-                &value,
-                Serializer::new(buffer, arena.acquire(), Share::new()),
+        let result = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<Archived<BTreeMap<String, i32>>>(
+                result.as_slice(),
             )
-            .unwrap();
+        };
+
+        assert_eq!(archived.len(), 4);
+        for (k, v) in value.iter() {
+            let (ak, av) = archived
+                .get_key_value(k.as_str())
+                .expect("failed to find key in archived B-tree map");
+            assert_eq!(k, ak);
+            assert_eq!(v, av);
         }
+        assert!(archived.get_key_value("wrong!").is_none());
+
+        let deserialized =
+            deserialize::<BTreeMap<_, _>, _, Infallible>(archived, &mut ())
+                .unwrap();
+        assert_eq!(value, deserialized);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn to_bytes_in_vec() {
-        let value = "hello world".to_string();
-        let bytes = to_bytes_in::<_, Error>(&value, Vec::new()).unwrap();
-        assert!(!bytes.is_empty());
+    fn archive_empty_btree_map() {
+        let value: BTreeMap<String, i32> = BTreeMap::new();
+
+        let result = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<Archived<BTreeMap<String, i32>>>(
+                result.as_slice(),
+            )
+        };
+
+        assert_eq!(archived.len(), 0);
+        #[allow(clippy::never_loop)]
+        archived.visit(|_, _| -> ::core::ops::ControlFlow<()> {
+            panic!("there should be no values in the archived empty btree");
+        });
+        assert!(archived.get_key_value("wrong!").is_none());
+
+        let deserialized =
+            deserialize::<BTreeMap<_, _>, _, Infallible>(archived, &mut ())
+                .unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_set() {
+        let mut value = BTreeSet::new();
+        value.insert("foo".to_string());
+        value.insert("bar".to_string());
+        value.insert("baz".to_string());
+        value.insert("bat".to_string());
+
+        let result = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<Archived<BTreeSet<String>>>(result.as_slice())
+        };
+
+        assert_eq!(archived.len(), 4);
+        for k in value.iter() {
+            let ak = archived
+                .get(k.as_str())
+                .expect("failed to find key in archived B-tree map");
+            assert_eq!(k, ak);
+        }
+        assert!(archived.get("wrong!").is_none());
+
+        let deserialized =
+            deserialize::<BTreeSet<_>, _, Infallible>(archived, &mut ())
+                .unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_map_large() {
+        use core::ops::ControlFlow;
+
+        // This test creates structures too big to fit in 16-bit offsets, and
+        // MIRI can't run it quickly enough.
+        #[cfg(any(feature = "size_16", miri))]
+        const ENTRIES: usize = 100;
+        #[cfg(not(miri))]
+        const ENTRIES: usize = 100_000;
+
+        let mut value = BTreeMap::new();
+        for i in 0..ENTRIES {
+            value.insert(i.to_string(), i as i32);
+        }
+
+        let result = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<Archived<BTreeMap<String, i32>>>(
+                result.as_slice(),
+            )
+        };
+
+        assert_eq!(archived.len(), ENTRIES);
+        let mut iter = value.iter();
+        archived.visit(|ak, av| {
+            let (k, v) = iter.next().unwrap();
+            assert_eq!(k, ak);
+            assert_eq!(v, av);
+            ControlFlow::<()>::Continue(())
+        });
+
+        for (k, v) in value.iter() {
+            let av = archived
+                .get(k.as_str())
+                .expect("failed to find key in archived B-tree map");
+            assert_eq!(v, av);
+        }
+        assert!(archived.get("wrong!").is_none());
+
+        let deserialized =
+            deserialize::<BTreeMap<_, _>, _, Infallible>(archived, &mut ())
+                .unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_zst_containers() {
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct MyZST;
+
+        test_archive(&Box::new(MyZST));
+
+        test_archive(&Vec::<MyZST>::new().into_boxed_slice());
+        test_archive(&vec![MyZST, MyZST, MyZST, MyZST].into_boxed_slice());
+
+        test_archive(&Vec::<MyZST>::new());
+        test_archive(&vec![MyZST, MyZST, MyZST, MyZST]);
+
+        let mut value = BTreeMap::new();
+        value.insert(0, ());
+        value.insert(1, ());
+        test_archive(&value);
+
+        let mut value = BTreeMap::new();
+        value.insert((), 10);
+        test_archive(&value);
+
+        let mut value = BTreeMap::new();
+        value.insert((), ());
+        test_archive(&value);
+
+        let mut value = BTreeSet::new();
+        value.insert(());
+        test_archive(&value);
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct TestRcZST {
+            a: Rc<()>,
+            b: Rc<()>,
+        }
+
+        let rc_zst = Rc::new(());
+        test_archive(&TestRcZST {
+            a: rc_zst.clone(),
+            b: rc_zst.clone(),
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn scratch_tracker() {
+        use rkyv::ser::{
+            allocator::{AllocationTracker, Arena, ArenaHandle},
+            Serializer,
+        };
+
+        type TrackerSerializer<'a, E> = Strategy<
+            Serializer<AlignedVec, AllocationTracker<ArenaHandle<'a>>, ()>,
+            E,
+        >;
+
+        fn track_serialize<T>(value: &T) -> AllocationStats
+        where
+            T: for<'a> Serialize<TrackerSerializer<'a, Error>>,
+        {
+            let mut arena = Arena::new();
+
+            let serializer = serialize_into(
+                value,
+                Serializer::new(
+                    AlignedVec::new(),
+                    AllocationTracker::new(arena.acquire()),
+                    (),
+                ),
+            )
+            .unwrap();
+            serializer.into_raw_parts().1.into_stats()
+        }
+
+        let stats = track_serialize(&42);
+        assert_eq!(stats.max_bytes_allocated, 0);
+        assert_eq!(stats.max_allocations, 0);
+        assert_eq!(stats.max_alignment, 1);
+        assert_eq!(stats.min_arena_capacity(), 0);
+        assert_eq!(stats.min_arena_capacity_max_error(), 0);
+
+        let stats = track_serialize(&vec![1, 2, 3, 4]);
+        assert_eq!(stats.max_bytes_allocated, 0);
+        assert_eq!(stats.max_allocations, 0);
+        assert_eq!(stats.max_alignment, 1);
+        assert_eq!(stats.min_arena_capacity(), 0);
+        assert_eq!(stats.min_arena_capacity_max_error(), 0);
+
+        let stats = track_serialize(&vec![vec![1, 2], vec![3, 4]]);
+        assert_ne!(stats.max_bytes_allocated, 0);
+        assert_eq!(stats.max_allocations, 1);
+        assert_ne!(stats.min_arena_capacity(), 0);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_manually_drop() {
+        use core::mem::ManuallyDrop;
+
+        let vec = ManuallyDrop::new(vec![
+            "hello world".to_string(),
+            "me too!".to_string(),
+        ]);
+
+        let bytes = to_bytes::<Error>(&vec).unwrap();
+        let archived = unsafe {
+            access_unchecked::<Archived<ManuallyDrop<Vec<String>>>>(&bytes)
+        };
+
+        assert_eq!(archived.len(), vec.len());
+        for (a, b) in archived.iter().zip(vec.iter()) {
+            assert_eq!(a, b);
+        }
+
+        drop(ManuallyDrop::into_inner(vec));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_bound() {
+        use core::ops::Bound;
+
+        test_archive(&Bound::Included("hello world".to_string()));
+        test_archive(&Bound::Excluded("hello world".to_string()));
+        test_archive(&Bound::<String>::Unbounded);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn ambiguous_niched_archived_box() {
+        use rkyv::{rancor::Failure, with::Niche};
+
+        #[derive(Archive, Deserialize, Serialize)]
+        struct HasNiche {
+            #[with(Niche)]
+            inner: Option<Box<[u32]>>,
+        }
+
+        let some_empty = HasNiche {
+            inner: Some(Box::<[u32]>::from([])),
+        };
+        let bytes = rkyv::to_bytes::<Failure>(&some_empty).unwrap();
+        eprintln!("some bytes: {:?}", bytes);
+        let archived =
+            unsafe { rkyv::access_unchecked::<ArchivedHasNiche>(&bytes) };
+        let deser =
+            deserialize::<HasNiche, _, Failure>(archived, &mut ()).unwrap();
+
+        assert!(archived.inner.is_some());
+        assert!(deser.inner.is_some());
+        assert_eq!(some_empty.inner, deser.inner);
+        assert_eq!(archived.inner.as_ref().unwrap().len(), 0);
+        assert_eq!(deser.inner.as_ref().unwrap().len(), 0);
+
+        let none = HasNiche { inner: None };
+        let bytes = rkyv::to_bytes::<Failure>(&none).unwrap();
+        eprintln!("none bytes: {:?}", bytes);
+        let archived =
+            unsafe { rkyv::access_unchecked::<ArchivedHasNiche>(&bytes) };
+        let deser =
+            deserialize::<HasNiche, _, Failure>(archived, &mut ()).unwrap();
+
+        assert!(archived.inner.is_none());
+        assert!(deser.inner.is_none());
+        assert_eq!(none.inner, deser.inner);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn reuse_arena() {
+        let mut bytes = AlignedVec::<16>::with_capacity(1024);
+        let mut arena = Arena::with_capacity(2);
+
+        let value = vec![
+            "hello".to_string(),
+            "world".to_string(),
+            "foo".to_string(),
+            "bar".to_string(),
+            "baz".to_string(),
+        ];
+
+        for _ in 0..10 {
+            let mut buffer = core::mem::take(&mut bytes);
+            buffer.clear();
+
+            serialize_into::<_, Error>(
+                &value,
+                Serializer::new(buffer, arena.acquire(), Share::new()),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn allocation_failure() {
+        use core::alloc::Layout;
+
+        use rkyv::ser::allocator::{Allocator, OutOfMemory};
+
+        let mut bytes = AlignedVec::<16>::new();
+        bytes.extend_from_slice(&[1, 2, 3]);
+        let error = bytes.try_reserve(usize::MAX).unwrap_err();
+        assert_eq!(
+            error,
+            OutOfMemory {
+                size: usize::MAX,
+                align: 16,
+            }
+        );
+        assert!(bytes.try_extend_from_slice(&[4]).is_ok());
+        assert_eq!(bytes.as_slice(), &[1, 2, 3, 4]);
+
+        assert!(Arena::try_with_capacity(usize::MAX).is_err());
+        assert!(Arena::try_with_capacity(isize::MAX as usize).is_err());
+
+        let mut arena = Arena::try_new().unwrap();
+        let mut handle = arena.acquire();
+        let layout = Layout::from_size_align(isize::MAX as usize, 1).unwrap();
+        unsafe {
+            assert!(
+                Allocator::<Error>::push_alloc(&mut handle, layout).is_err()
+            );
+        }
+
+        let value = vec!["hello".to_string(), "world".to_string()];
+        let bytes = serialize_into::<_, Error>(
+            &value,
+            Serializer::new(AlignedVec::<16>::new(), handle, Share::new()),
+        )
+        .unwrap()
+        .into_writer();
+        let archived =
+            unsafe { access_unchecked::<Archived<Vec<String>>>(&bytes) };
+        assert_eq!(archived[1], "world");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn to_bytes_in_vec() {
+        let value = "hello world".to_string();
+        let bytes = to_bytes_in::<_, Error>(&value, Vec::new()).unwrap();
+        assert!(!bytes.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn to_bytes_with_alignment() {
+        use core::mem::align_of;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(repr(C, align(64)))]
+        struct CacheLine {
+            a: u8,
+            b: Box<u32>,
+        }
+
+        let value = CacheLine {
+            a: 1,
+            b: Box::new(2),
+        };
+
+        let bytes =
+            to_bytes_in::<_, Error>(&value, AlignedVec::<64>::new()).unwrap();
+        assert_eq!(align_of::<ArchivedCacheLine>(), 64);
+        assert_eq!(bytes.as_ptr() as usize % 64, 0);
+        let archived = unsafe { access_unchecked::<ArchivedCacheLine>(&bytes) };
+        assert_eq!(archived.a, 1);
+        assert_eq!(*archived.b, 2);
+
+        let mut prefix = AlignedVec::<64>::new();
+        prefix.extend_from_slice(&[0xff; 3]);
+        prefix.resize(64, 0);
+        let bytes = to_bytes_in::<_, Error>(&value, prefix).unwrap();
+        assert_eq!(&bytes[..3], &[0xff; 3]);
+        assert!(bytes[3..64].iter().all(|&b| b == 0));
+        let archived = unsafe { access_unchecked::<ArchivedCacheLine>(&bytes) };
+        assert_eq!(archived.a, 1);
+        assert_eq!(*archived.b, 2);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn owned_archive() {
+        use rkyv::util::OwnedArchive;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            name: String,
+            values: Vec<u32>,
+        }
+
+        fn make() -> OwnedArchive<Example> {
+            let value = Example {
+                name: "owned".to_string(),
+                values: vec![1, 2, 3],
+            };
+            OwnedArchive::from_value::<Error>(&value).unwrap()
+        }
+
+        let archive = make();
+        assert_eq!(archive.name, "owned");
+        assert_eq!(archive.values.as_slice(), &[1, 2, 3]);
+
+        let bytes = archive.into_inner();
+        let archive = OwnedArchive::<Example>::new::<Error>(bytes).unwrap();
+        assert_eq!(archive.name, "owned");
+
+        let mut bytes = archive.into_inner();
+        let len = bytes.len();
+        bytes[len - 1] = 0xff;
+        assert!(OwnedArchive::<Example>::new::<Error>(bytes).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn splice_fragments() {
+        use rkyv::util::Fragment;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Dictionary {
+            words: Vec<String>,
+            lengths: Box<[u16]>,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Message {
+            prefix: Vec<u8>,
+            dictionary: Fragment<Dictionary>,
+            id: u32,
+        }
+
+        let words = ["alpha", "beta", "gamma"].map(str::to_string).to_vec();
+        let dictionary = Dictionary {
+            lengths: words.iter().map(|word| word.len() as u16).collect(),
+            words,
+        };
+        let fragment = Fragment::new::<Error>(&dictionary).unwrap();
+        assert_eq!(fragment.archived().words[2], "gamma");
+
+        // The same fragment is spliced at a different position in each
+        // message.
+        for (id, prefix_len) in [(1, 0), (2, 3), (3, 17)] {
+            let message = Message {
+                prefix: vec![0xaa; prefix_len],
+                dictionary: fragment.clone(),
+                id,
+            };
+            let bytes = to_bytes::<Error>(&message).unwrap();
+            let archived =
+                rkyv::access::<ArchivedMessage, Error>(&bytes).unwrap();
+            assert_eq!(archived.id, id);
+            assert_eq!(archived.prefix.len(), prefix_len);
+            assert_eq!(archived.dictionary.words.len(), 3);
+            assert_eq!(archived.dictionary.words[1], "beta");
+            assert_eq!(archived.dictionary.lengths.as_ref(), &[5, 4, 5]);
+        }
+
+        // Fragments can be stored as bytes and checked when they are loaded.
+        let bytes = fragment.into_bytes();
+        let fragment = Fragment::<Dictionary>::from_bytes::<Error>(bytes)
+            .expect("failed to validate fragment");
+        assert_eq!(fragment.archived().lengths.len(), 3);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn pointer_reprs() {
+        use rkyv::{
+            boxed::{ArchivedBox, BoxResolver},
+            rel_ptr::Repr,
+            rend::{i16_le, i64_be},
+            ser::Allocator,
+            vec::{ArchivedVec, VecResolver},
+        };
+
+        type WideVec = ArchivedVec<Archived<u32>, Repr<i64_be>>;
+        type NarrowBox = ArchivedBox<str, Repr<i16_le>>;
+
+        struct Wide(Vec<u32>);
+
+        impl Archive for Wide {
+            type Archived = WideVec;
+            type Resolver = VecResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                WideVec::resolve_from_slice(&self.0, resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Wide
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedVec::serialize_from_slice(&self.0, serializer)
+            }
+        }
+
+        struct Narrow(String);
+
+        impl Archive for Narrow {
+            type Archived = NarrowBox;
+            type Resolver = BoxResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                NarrowBox::resolve_from_ref(self.0.as_str(), resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Narrow
+        where
+            S: Fallible + Writer + ?Sized,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedBox::serialize_from_ref(self.0.as_str(), serializer)
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Mixed {
+            wide: Wide,
+            narrow: Narrow,
+            default: Vec<u32>,
+        }
+
+        let value = Mixed {
+            wide: Wide(vec![1, 2, 3, 4]),
+            narrow: Narrow("hello world".to_string()),
+            default: vec![1, 2, 3, 4],
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = rkyv::access::<ArchivedMixed, Error>(&bytes).unwrap();
+        assert_eq!(archived.wide, [1, 2, 3, 4]);
+        assert_eq!(archived.wide, archived.default);
+        assert_eq!(&*archived.narrow, "hello world");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn pointer_reprs_lengths_and_shared_pointers() {
+        use rkyv::{
+            niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
+            rc::{ArchivedRc, RcFlavor, RcResolver},
+            rel_ptr::Repr,
+            rend::{i16_le, i32_be, i64_be, u16_le},
+            ser::{Allocator, Sharing},
+            vec::{ArchivedVec, VecResolver},
+        };
+
+        type ShortVec = ArchivedVec<Archived<u32>, Repr<i16_le, u16_le>>;
+        type WideOptionBox = ArchivedOptionBox<Archived<u32>, Repr<i64_be>>;
+        type NarrowRc = ArchivedRc<Archived<u32>, RcFlavor, Repr<i32_be>>;
+
+        struct Short(Vec<u32>);
+
+        impl Archive for Short {
+            type Archived = ShortVec;
+            type Resolver = VecResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ShortVec::resolve_from_slice(&self.0, resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Short
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedVec::serialize_from_slice(&self.0, serializer)
+            }
+        }
+
+        struct Optional(Option<Box<u32>>);
+
+        impl Archive for Optional {
+            type Archived = WideOptionBox;
+            type Resolver = OptionBoxResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                WideOptionBox::resolve_from_option(
+                    self.0.as_deref(),
+                    resolver,
+                    out,
+                );
+            }
+        }
+
+        impl<S> Serialize<S> for Optional
+        where
+            S: Fallible + Writer + ?Sized,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedOptionBox::serialize_from_option(
+                    self.0.as_deref(),
+                    serializer,
+                )
+            }
+        }
+
+        struct Shared(Rc<u32>);
+
+        impl Archive for Shared {
+            type Archived = NarrowRc;
+            type Resolver = RcResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                NarrowRc::resolve_from_ref(self.0.as_ref(), resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Shared
+        where
+            S: Fallible + Writer + Sharing + ?Sized,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                ArchivedRc::<Archived<u32>, RcFlavor>::serialize_from_ref(
+                    self.0.as_ref(),
+                    serializer,
+                )
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Mixed {
+            short: Short,
+            some: Optional,
+            none: Optional,
+            first: Shared,
+            second: Shared,
+        }
+
+        assert_eq!(core::mem::size_of::<ShortVec>(), 4);
+
+        let shared = Rc::new(42);
+        let value = Mixed {
+            short: Short(vec![1, 2, 3, 4]),
+            some: Optional(Some(Box::new(10))),
+            none: Optional(None),
+            first: Shared(shared.clone()),
+            second: Shared(shared),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = rkyv::access::<ArchivedMixed, Error>(&bytes).unwrap();
+        assert_eq!(archived.short.len(), 4);
+        assert_eq!(archived.short, [1, 2, 3, 4]);
+        assert_eq!(archived.some.as_deref().map(|x| x.to_native()), Some(10));
+        assert!(archived.none.is_none());
+        assert_eq!(archived.first.to_native(), 42);
+        assert!(core::ptr::eq(archived.first.get(), archived.second.get()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn lazy() {
+        use rkyv::{lazy::Lazy, util::SharedAlignedVec};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Cold {
+            names: Vec<String>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(check_bytes)]
+        struct Message {
+            id: u32,
+            cold: Lazy<Cold>,
+        }
+
+        let cold = Cold {
+            names: vec!["a".to_string(), "b".to_string()],
+        };
+        let value = Message {
+            id: 7,
+            cold: Lazy::new(cold),
+        };
+
+        let bytes = SharedAlignedVec::from(to_bytes::<Error>(&value).unwrap());
+        let archived = rkyv::access::<ArchivedMessage, Error>(&bytes).unwrap();
+        assert_eq!(archived.cold.names[1], "b");
+
+        // Without a source buffer, lazy values are deserialized eagerly.
+        let eager =
+            deserialize::<Message, _, Error>(archived, &mut Pool::new())
+                .unwrap();
+        assert!(eager.cold.is_loaded());
+        assert!(eager.cold.archived().is_none());
+
+        let mut pool = Pool::with_source_buffer(bytes.clone());
+        let deferred =
+            deserialize::<Message, _, Error>(archived, &mut pool).unwrap();
+        assert_eq!(deferred.id, 7);
+        assert!(!deferred.cold.is_loaded());
+        assert_eq!(deferred.cold.archived().unwrap().names[0], "a");
+
+        // Serializing a deferred value loads it.
+        let reserialized = to_bytes::<Error>(&deferred).unwrap();
+        assert!(deferred.cold.is_loaded());
+        assert_eq!(deferred.cold.get::<Error>().unwrap().names, ["a", "b"]);
+        assert_eq!(reserialized.as_slice(), bytes.as_slice());
+
+        let deferred =
+            deserialize::<Message, _, Error>(archived, &mut pool).unwrap();
+        assert_eq!(
+            deferred.cold.into_inner::<Error>().unwrap(),
+            eager.cold.into_inner::<Error>().unwrap(),
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_projection() {
+        use rkyv::{deserialize_projection, with::AsVec};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(project(Header(id, name), Body(body, tags)))]
+        struct Message {
+            id: u32,
+            name: String,
+            body: String,
+            #[with(AsVec)]
+            tags: BTreeSet<String>,
+        }
+
+        let value = Message {
+            id: 3,
+            name: "greeting".to_string(),
+            body: "hello".to_string(),
+            tags: ["a".to_string(), "b".to_string()].into_iter().collect(),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedMessage>(&bytes) };
+
+        let header =
+            deserialize_projection::<Header, _, _, Error>(archived, &mut ())
+                .unwrap();
+        assert_eq!(header.id, 3);
+        assert_eq!(header.name, "greeting");
+
+        let body =
+            deserialize_projection::<Body, _, _, Error>(archived, &mut ())
+                .unwrap();
+        assert_eq!(body.body, "hello");
+        assert_eq!(body.tags, value.tags);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_borrowed() {
+        use rkyv::{deserialize_borrowed, DeserializeBorrowed};
+
+        #[derive(Archive, Serialize)]
+        struct Inner {
+            value: i32,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Example {
+            id: u64,
+            name: String,
+            nickname: Option<String>,
+            bytes: Box<[u8]>,
+            tags: Vec<String>,
+            inner: Inner,
+        }
+
+        #[derive(DeserializeBorrowed)]
+        #[archive(borrow_from = Example)]
+        struct ExampleRef<'a> {
+            id: u64,
+            name: &'a str,
+            nickname: Option<&'a str>,
+            bytes: &'a [u8],
+            tags: Vec<&'a str>,
+            inner: &'a ArchivedInner,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Pair(u8, String);
+
+        #[derive(DeserializeBorrowed)]
+        #[archive(borrow_from = Pair)]
+        struct PairRef<'a>(u8, &'a str);
+
+        let value = Example {
+            id: 42,
+            name: "name".to_string(),
+            nickname: Some("nickname".to_string()),
+            bytes: Box::new([1, 2, 3]),
+            tags: vec!["a".to_string(), "b".to_string()],
+            inner: Inner { value: -1 },
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&bytes) };
+
+        let borrowed =
+            deserialize_borrowed::<ExampleRef, _, _, Error>(archived, &mut ())
+                .unwrap();
+        assert_eq!(borrowed.id, 42);
+        assert_eq!(borrowed.name, "name");
+        assert_eq!(borrowed.nickname, Some("nickname"));
+        assert_eq!(borrowed.bytes, &[1, 2, 3]);
+        assert_eq!(borrowed.tags, ["a", "b"]);
+        assert_eq!(borrowed.inner.value, -1);
+        assert_eq!(borrowed.name.as_ptr(), archived.name.as_ptr());
+
+        let bytes = to_bytes::<Error>(&Pair(1, "one".to_string())).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedPair>(&bytes) };
+        let PairRef(number, name) =
+            deserialize_borrowed::<PairRef, _, _, Error>(archived, &mut ())
+                .unwrap();
+        assert_eq!(number, 1);
+        assert_eq!(name, "one");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn normalize_endianness() {
+        use core::mem::{size_of, size_of_val};
+
+        use rkyv::{endian::normalize_endianness, primitive::ArchivedIsize};
+
+        #[derive(Archive, Serialize, Debug, PartialEq)]
+        #[archive(swap_endian, compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum ExampleEnum {
+            A,
+            B(u16, u32),
+        }
+
+        #[derive(Archive, Serialize, Debug, PartialEq)]
+        #[archive(swap_endian, compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Example {
+            a: u32,
+            b: Vec<u16>,
+            c: String,
+            d: Option<u64>,
+            e: Box<i32>,
+            f: Vec<ExampleEnum>,
+        }
+
+        fn addr<T>(value: &T) -> *const u8 {
+            (value as *const T).cast()
+        }
+
+        fn reverse(
+            foreign: &mut [u8],
+            native: &[u8],
+            ptr: *const u8,
+            len: usize,
+        ) {
+            let pos = ptr as usize - native.as_ptr() as usize;
+            foreign[pos..pos + len].reverse();
+        }
+
+        let value = Example {
+            a: 0x01020304,
+            b: vec![1, 2, 3],
+            c: "a string which is too long to be inlined".to_string(),
+            d: Some(42),
+            e: Box::new(-7),
+            f: vec![ExampleEnum::A, ExampleEnum::B(5, 6)],
+        };
+
+        let native = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&native) };
+
+        // Reverse every multibyte primitive to get the bytes that a build with
+        // the opposite endianness would have written.
+        let mut foreign = native.clone();
+        let offset_size = size_of::<ArchivedIsize>();
+        let mut reverse_value = |ptr: *const u8, len: usize| {
+            reverse(&mut foreign, &native, ptr, len)
+        };
+
+        reverse_value(addr(&archived.a), size_of_val(&archived.a));
+        reverse_value(addr(&archived.b), offset_size);
+        reverse_value(addr(&archived.b).wrapping_add(offset_size), offset_size);
+        for element in archived.b.iter() {
+            reverse_value(addr(element), size_of_val(element));
+        }
+        reverse_value(addr(&archived.c), offset_size);
+        let d = archived.d.as_ref().unwrap();
+        reverse_value(addr(d), size_of_val(d));
+        reverse_value(addr(&archived.e), offset_size);
+        reverse_value(addr(archived.e.get()), size_of_val(archived.e.get()));
+        reverse_value(addr(&archived.f), offset_size);
+        reverse_value(addr(&archived.f).wrapping_add(offset_size), offset_size);
+        for element in archived.f.iter() {
+            if let ArchivedExampleEnum::B(x, y) = element {
+                reverse_value(addr(x), size_of_val(x));
+                reverse_value(addr(y), size_of_val(y));
+            }
+        }
+
+        assert_ne!(foreign.as_slice(), native.as_slice());
+        unsafe {
+            normalize_endianness::<ArchivedExample>(&mut foreign);
+        }
+        assert_eq!(foreign.as_slice(), native.as_slice());
+
+        let normalized =
+            unsafe { access_unchecked::<ArchivedExample>(&foreign) };
+        assert_eq!(normalized, &value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn absolute_offsets() {
+        use rkyv::{
+            primitive::ArchivedIsize,
+            relocate::{to_absolute_offsets, to_relative_offsets},
+        };
+
+        #[derive(Archive, Serialize, Debug, PartialEq)]
+        #[archive(relocate, check_bytes, compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum ExampleEnum {
+            A,
+            B(String, Box<u32>),
+        }
+
+        #[derive(Archive, Serialize, Debug, PartialEq)]
+        #[archive(relocate, check_bytes, compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Example {
+            a: u32,
+            b: Vec<u16>,
+            c: String,
+            d: Option<Box<u64>>,
+            e: Box<i32>,
+            f: Vec<ExampleEnum>,
+        }
+
+        let value = Example {
+            a: 1,
+            b: vec![1, 2, 3],
+            c: "a string which is too long to be inlined".to_string(),
+            d: Some(Box::new(42)),
+            e: Box::new(-7),
+            f: vec![
+                ExampleEnum::A,
+                ExampleEnum::B(
+                    "another string which is too long".to_string(),
+                    Box::new(5),
+                ),
+            ],
+        };
+
+        let native = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&native) };
+        let e_pos =
+            (&archived.e as *const _ as usize) - native.as_ptr() as usize;
+        let e_target =
+            archived.e.get() as *const _ as usize - native.as_ptr() as usize;
+
+        let mut bytes = native.clone();
+        unsafe {
+            to_absolute_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+        }
+        assert_ne!(bytes.as_slice(), native.as_slice());
+
+        // The offset of the box now holds the position of its target.
+        let offset = unsafe {
+            bytes
+                .as_ptr()
+                .add(e_pos)
+                .cast::<ArchivedIsize>()
+                .read_unaligned()
+        };
+        assert_eq!(offset.to_native() as usize, e_target);
+
+        unsafe {
+            to_relative_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+        }
+        assert_eq!(bytes.as_slice(), native.as_slice());
+
+        let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+        assert_eq!(archived, &value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_option_box() {
+        use core::mem::size_of;
+
+        use rkyv::{
+            endian::normalize_endianness,
+            niche::option_box::ArchivedOptionBox,
+            primitive::ArchivedIsize,
+            relocate::{to_absolute_offsets, to_relative_offsets},
+            with::Niche,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(relocate, swap_endian)]
+        struct Example {
+            #[with(Niche)]
+            some: Option<Box<u32>>,
+            #[with(Niche)]
+            none: Option<Box<u32>>,
+        }
+
+        impl ArchivedExample {
+            fn some(
+                self: Pin<&mut Self>,
+            ) -> Pin<&mut ArchivedOptionBox<Archived<u32>>> {
+                unsafe { self.map_unchecked_mut(|s| &mut s.some) }
+            }
+        }
+
+        let value = Example {
+            some: Some(Box::new(42)),
+            none: None,
+        };
+        let native = to_bytes::<Error>(&value).unwrap();
+
+        let mut bytes = native.clone();
+        unsafe {
+            to_absolute_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+            to_relative_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+        }
+        assert_eq!(bytes.as_slice(), native.as_slice());
+
+        // Reverse the offsets and the boxed value to get the bytes that a build
+        // with the opposite endianness would have written. The invalid pointer
+        // of the `None` must be converted without being followed.
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&native) };
+        let base = native.as_ptr() as usize;
+        let offset_size = size_of::<ArchivedIsize>();
+        let some_pos = &archived.some as *const _ as usize - base;
+        let none_pos = &archived.none as *const _ as usize - base;
+        let value_pos =
+            archived.some.as_deref().unwrap() as *const _ as usize - base;
+        bytes[some_pos..some_pos + offset_size].reverse();
+        bytes[none_pos..none_pos + offset_size].reverse();
+        bytes[value_pos..value_pos + 4].reverse();
+        assert_ne!(bytes.as_slice(), native.as_slice());
+        unsafe {
+            normalize_endianness::<ArchivedExample>(&mut bytes);
+        }
+        assert_eq!(bytes.as_slice(), native.as_slice());
+
+        let mut archived =
+            unsafe { access_unchecked_mut::<ArchivedExample>(&mut bytes) };
+        assert_eq!(archived.some.as_deref(), Some(&42.into()));
+        assert!(archived.none.as_deref().is_none());
+        assert_eq!((&archived.some).into_iter().count(), 1);
+        assert_eq!((&archived.none).into_iter().count(), 0);
+
+        *archived.as_mut().some().as_pin_deref_mut().unwrap() = 7.into();
+        assert_eq!(archived.some.as_deref(), Some(&7.into()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn absolute_offsets_invalid_pointer() {
+        use rkyv::{
+            primitive::ArchivedIsize,
+            relocate::{to_absolute_offsets, to_relative_offsets},
+            with::Niche,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(relocate)]
+        struct Example {
+            value: Box<u32>,
+            #[with(Niche)]
+            none: Option<Box<u32>>,
+        }
+
+        let value = Example {
+            value: Box::new(42),
+            none: None,
+        };
+        let native = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&native) };
+        let base = native.as_ptr() as usize;
+        let value_pos = &archived.value as *const _ as usize - base;
+        let none_pos = &archived.none as *const _ as usize - base;
+
+        let read_offset = |bytes: &[u8], pos: usize| unsafe {
+            bytes
+                .as_ptr()
+                .add(pos)
+                .cast::<ArchivedIsize>()
+                .read_unaligned()
+                .to_native()
+        };
+        let write_offset = |bytes: &mut [u8], pos: usize, offset| unsafe {
+            bytes
+                .as_mut_ptr()
+                .add(pos)
+                .cast::<ArchivedIsize>()
+                .write_unaligned(ArchivedIsize::from_native(offset))
+        };
+
+        // Invalid pointers hold -1 in absolute mode, which no valid pointer
+        // can hold.
+        let mut bytes = native.clone();
+        unsafe {
+            to_absolute_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+        }
+        assert_eq!(read_offset(bytes.as_slice(), none_pos), -1);
+        unsafe {
+            to_relative_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+        }
+        assert_eq!(bytes.as_slice(), native.as_slice());
+
+        // A valid pointer to the byte right after it would become invalid in
+        // relative mode, so it's rejected.
+        unsafe {
+            to_absolute_offsets::<ArchivedExample, Error>(&mut bytes).unwrap();
+        }
+        write_offset(bytes.as_mut_slice(), value_pos, (value_pos + 1) as _);
+        let result = unsafe {
+            to_relative_offsets::<ArchivedExample, Error>(&mut bytes)
+        };
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn walk_json() {
+        use rkyv::walk::{to_json_value, JsonValue, Walk};
+
+        #[derive(Archive, Serialize)]
+        #[archive(schema)]
+        struct Point(i32, i32);
+
+        #[derive(Archive, Serialize)]
+        #[archive(schema)]
+        enum Shape {
+            Empty,
+            Dot(Point),
+            Line(Point, Point),
+            Group {
+                #[rkyv(rename = "members")]
+                shapes: Vec<Shape>,
+                label: Option<String>,
+            },
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(schema)]
+        struct Drawing {
+            name: String,
+            scale: f32,
+            layers: BTreeMap<u8, Shape>,
+            next: Option<Box<Drawing>>,
+        }
+
+        // Fields which can't be walked only omit the `Walk` impl.
+        #[derive(Archive)]
+        struct Opaque(u8);
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[archive(schema)]
+        struct Unwalkable {
+            opaque: Opaque,
+        }
+
+        let mut layers = BTreeMap::new();
+        layers.insert(1, Shape::Empty);
+        layers.insert(
+            2,
+            Shape::Group {
+                shapes: vec![
+                    Shape::Dot(Point(1, -2)),
+                    Shape::Line(Point(0, 0), Point(3, 4)),
+                ],
+                label: Some("a \"quoted\" label".to_string()),
+            },
+        );
+        let value = Drawing {
+            name: "example".to_string(),
+            scale: 1.5,
+            layers,
+            next: Some(Box::new(Drawing {
+                name: "inner".to_string(),
+                scale: 0.0,
+                layers: BTreeMap::new(),
+                next: None,
+            })),
+        };
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDrawing>(&bytes) };
+
+        let json = to_json_value(archived);
+        assert_eq!(
+            json.to_string(),
+            concat!(
+                r#"{"name":"example","scale":1.5,"layers":{"1":"Empty","2":"#,
+                r#"{"Group":{"members":[{"Dot":[1,-2]},"#,
+                r#"{"Line":[[0,0],[3,4]]}],"label":"a \"quoted\" label"}}},"#,
+                r#""next":{"name":"inner","scale":0.0,"layers":{},"#,
+                r#""next":null}}"#,
+            ),
+        );
+        assert_eq!(
+            json.get("next").and_then(|next| next.get("name")),
+            Some(&JsonValue::String("inner".to_string())),
+        );
+
+        let pretty = format!("{:#}", to_json_value(&archived.next));
+        assert_eq!(
+            pretty,
+            "{\n  \"name\": \"inner\",\n  \"scale\": 0.0,\n  \"layers\": \
+             {},\n  \"next\": null\n}",
+        );
+
+        // Archived values can be walked through trait objects.
+        let values: [&dyn Walk; 2] = [&archived.name, &archived.scale];
+        let json = values.map(|value| to_json_value(value).to_string());
+        assert_eq!(json, [r#""example""#, "1.5"]);
+    }
+
+    #[test]
+    fn diff_archives() {
+        use rkyv::walk::{diff, DifferenceKind, JsonValue, PathSegment};
+
+        #[derive(Archive, Serialize, Clone)]
+        #[archive(schema)]
+        enum Backend {
+            Local,
+            Remote { host: String, port: u16 },
+        }
+
+        #[derive(Archive, Serialize, Clone)]
+        #[archive(schema)]
+        struct Config {
+            name: String,
+            backend: Backend,
+            limits: BTreeMap<String, u32>,
+            replicas: Vec<Backend>,
+            timeout: Option<u32>,
+        }
+
+        let mut limits = BTreeMap::new();
+        limits.insert("cpu".to_string(), 4);
+        limits.insert("memory".to_string(), 1024);
+        let old = Config {
+            name: "service".to_string(),
+            backend: Backend::Remote {
+                host: "a.example".to_string(),
+                port: 80,
+            },
+            limits,
+            replicas: vec![Backend::Local, Backend::Local],
+            timeout: Some(30),
+        };
+
+        let mut new = old.clone();
+        new.backend = Backend::Remote {
+            host: "a.example".to_string(),
+            port: 8080,
+        };
+        new.limits.remove("cpu");
+        new.limits.insert("disk".to_string(), 10);
+        new.replicas = vec![Backend::Remote {
+            host: "b.example".to_string(),
+            port: 80,
+        }];
+        new.timeout = None;
+
+        let old_bytes = to_bytes::<Error>(&old).unwrap();
+        let new_bytes = to_bytes::<Error>(&new).unwrap();
+        let old = unsafe { access_unchecked::<ArchivedConfig>(&old_bytes) };
+        let new = unsafe { access_unchecked::<ArchivedConfig>(&new_bytes) };
+
+        assert!(diff(old, old).is_empty());
+
+        let differences = diff(old, new);
+        assert_eq!(
+            differences
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            [
+                ".backend.Remote.port: 80 -> 8080",
+                ".limits.cpu: removed 4",
+                ".limits.disk: added 10",
+                ".replicas[0]: \"Local\" -> \
+                 {\"Remote\":{\"host\":\"b.example\",\"port\":80}}",
+                ".replicas[1]: removed \"Local\"",
+                ".timeout: 30 -> null",
+            ],
+        );
+
+        assert_eq!(
+            differences[0].path.segments(),
+            [
+                PathSegment::Key("backend".to_string()),
+                PathSegment::Key("Remote".to_string()),
+                PathSegment::Key("port".to_string()),
+            ],
+        );
+        assert_eq!(
+            differences[0].kind,
+            DifferenceKind::Changed {
+                old: JsonValue::Unsigned(80),
+                new: JsonValue::Unsigned(8080),
+            },
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn deserialize_async() {
+        use core::{
+            future::Future,
+            task::{Context, Poll},
+        };
+
+        use rkyv::{
+            de::{ErasedPtr, Pooling},
+            util::deserialize_async,
+            with::Skip,
+            DeserializeAsync,
+        };
+
+        use crate::util::core::block_on;
+
+        /// A future which is pending once before it completes.
+        struct YieldOnce(bool);
+
+        impl Future for YieldOnce {
+            type Output = ();
+
+            fn poll(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<()> {
+                if self.0 {
+                    Poll::Ready(())
+                } else {
+                    self.0 = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+
+        #[derive(Default)]
+        struct BlobStore {
+            pool: Pool,
+            blobs: BTreeMap<u32, Vec<u8>>,
+            fetches: usize,
+        }
+
+        impl<E: Source> Pooling<E> for BlobStore {
+            fn get_shared_ptr(&mut self, address: usize) -> Option<ErasedPtr> {
+                Pooling::<E>::get_shared_ptr(&mut self.pool, address)
+            }
+
+            unsafe fn add_shared_ptr(
+                &mut self,
+                address: usize,
+                ptr: ErasedPtr,
+                drop: unsafe fn(ErasedPtr),
+            ) -> Result<(), E> {
+                unsafe { self.pool.add_shared_ptr(address, ptr, drop) }
+            }
+        }
+
+        #[derive(Archive, Serialize, Debug, PartialEq)]
+        struct Blob {
+            id: u32,
+            #[with(Skip)]
+            data: Vec<u8>,
+        }
+
+        impl<E: Source> DeserializeAsync<Blob, Strategy<BlobStore, E>>
+            for ArchivedBlob
+        {
+            async fn deserialize_async(
+                &self,
+                deserializer: &mut Strategy<BlobStore, E>,
+            ) -> Result<Blob, E> {
+                YieldOnce(false).await;
+                let id = self.id.to_native();
+                deserializer.fetches += 1;
+                let data = deserializer.blobs[&id].clone();
+                Ok(Blob { id, data })
+            }
+        }
+
+        #[derive(Archive, Serialize, DeserializeAsync, Debug, PartialEq)]
+        enum Attachment {
+            None,
+            Blob(Blob),
+            Shared { blob: Rc<Blob> },
+        }
+
+        #[derive(Archive, Serialize, DeserializeAsync, Debug, PartialEq)]
+        struct Document {
+            title: String,
+            attachments: Vec<Attachment>,
+            cover: Option<Box<Blob>>,
+        }
+
+        let shared = Rc::new(Blob {
+            id: 2,
+            data: Vec::new(),
+        });
+        let value = Document {
+            title: "report".to_string(),
+            attachments: vec![
+                Attachment::None,
+                Attachment::Blob(Blob {
+                    id: 1,
+                    data: Vec::new(),
+                }),
+                Attachment::Shared {
+                    blob: shared.clone(),
+                },
+                Attachment::Shared { blob: shared },
+            ],
+            cover: Some(Box::new(Blob {
+                id: 3,
+                data: Vec::new(),
+            })),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedDocument>(&bytes) };
+
+        let mut store = BlobStore::default();
+        store.blobs.insert(1, vec![1]);
+        store.blobs.insert(2, vec![2, 2]);
+        store.blobs.insert(3, vec![3, 3, 3]);
+
+        let document = block_on(deserialize_async::<Document, _, Error>(
+            archived, &mut store,
+        ))
+        .unwrap();
+
+        assert_eq!(document.title, "report");
+        assert_eq!(document.attachments[0], Attachment::None);
+        let Attachment::Blob(ref blob) = document.attachments[1] else {
+            panic!("expected a blob attachment");
+        };
+        assert_eq!(blob.data, [1]);
+        let (
+            Attachment::Shared { blob: ref a },
+            Attachment::Shared { blob: ref b },
+        ) = (&document.attachments[2], &document.attachments[3])
+        else {
+            panic!("expected shared attachments");
+        };
+        assert!(Rc::ptr_eq(a, b));
+        assert_eq!(a.data, [2, 2]);
+        assert_eq!(document.cover.unwrap().data, [3, 3, 3]);
+        // The shared blob is only fetched once.
+        assert_eq!(store.fetches, 3);
+    }
+
+    #[test]
+    #[cfg(all(target_pointer_width = "64", not(feature = "pointer_width_64")))]
+    #[should_panic = "exceeds the archived pointer width"]
+    fn usize_exceeding_pointer_width() {
+        use rkyv::primitive::FixedUsize;
+
+        let _ = to_bytes::<Error>(&(FixedUsize::MAX as usize + 1));
+    }
+
+    #[test]
+    #[cfg(all(target_pointer_width = "64", not(feature = "pointer_width_64")))]
+    #[should_panic = "exceeds the archived pointer width"]
+    fn nonzero_usize_exceeding_pointer_width() {
+        use core::num::NonZeroUsize;
+
+        use rkyv::primitive::FixedUsize;
+
+        // Truncating this value would make it zero
+        let value = NonZeroUsize::new(FixedUsize::MAX as usize + 1).unwrap();
+        let _ = to_bytes::<Error>(&value);
+    }
+
+    #[test]
+    #[cfg(all(target_pointer_width = "64", not(feature = "pointer_width_64")))]
+    fn slice_len_exceeding_pointer_width() {
+        use rkyv::primitive::FixedUsize;
+
+        // Zero-sized elements don't take up any space, so only the length is
+        // too large.
+        let value = vec![(); FixedUsize::MAX as usize + 1];
+        assert!(to_bytes::<Error>(&value).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "pointer_width_16")]
+    fn offset_exceeding_pointer_width() {
+        // The length fits in 16 bits, but the offset back to the start of the
+        // elements doesn't.
+        let value = vec![0u8; 40_000];
+        assert!(to_bytes::<Error>(&value).is_err());
+    }
+
+    #[test]
+    #[cfg(all(target_pointer_width = "64", feature = "pointer_width_64"))]
+    fn values_exceeding_32_bits() {
+        let value = (1usize << 40, -(1isize << 40));
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<(usize, isize)>>(&bytes) };
+        assert_eq!(archived.0, 1u64 << 40);
+        assert_eq!(archived.1, -(1i64 << 40));
+
+        let value = vec![(); (1 << 32) + 1];
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Archived<Vec<()>>>(&bytes) };
+        assert_eq!(archived.len(), (1 << 32) + 1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn max_serialized_size() {
+        use core::mem::size_of;
+
+        use rkyv::{
+            ser::DefaultSerializer, traits::is_fixed_size, with::Boxed,
+        };
+
+        #[derive(Archive, Serialize)]
+        struct Point {
+            x: f32,
+            y: f32,
+        }
+
+        #[derive(Archive, Serialize)]
+        enum Shape {
+            Circle { center: Point, radius: f32 },
+            Line(Point, Point),
+            Empty,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Named {
+            name: String,
+            point: Point,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Wrapped {
+            #[with(Boxed)]
+            value: u32,
+        }
+
+        fn assert_fixed<T>(value: &T)
+        where
+            T: for<'a> Serialize<DefaultSerializer<'a, AlignedVec, Error>>,
+        {
+            assert!(is_fixed_size::<T>());
+            let bytes = to_bytes::<Error>(value).unwrap();
+            assert_eq!(Some(bytes.len()), T::MAX_SERIALIZED_SIZE);
+        }
+
+        assert_fixed(&Point { x: 1.0, y: 2.0 });
+        assert_fixed(&Shape::Line(
+            Point { x: 1.0, y: 2.0 },
+            Point { x: 3.0, y: 4.0 },
+        ));
+        assert_fixed(&Shape::Circle {
+            center: Point { x: 0.0, y: 0.0 },
+            radius: 1.0,
+        });
+        assert_fixed(&Shape::Empty);
+        assert_fixed(&(1u8, 2u32, Some(3u16)));
+        assert_fixed(&[Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }]);
+        assert_fixed(&Ok::<u64, bool>(1));
+        assert_eq!(
+            <(u8, u32)>::MAX_SERIALIZED_SIZE,
+            Some(size_of::<Archived<(u8, u32)>>()),
+        );
+
+        assert_eq!(Named::MAX_SERIALIZED_SIZE, None);
+        assert_eq!(Wrapped::MAX_SERIALIZED_SIZE, None);
+        assert_eq!(<Option<String>>::MAX_SERIALIZED_SIZE, None);
+        assert_eq!(<[Vec<u8>; 2]>::MAX_SERIALIZED_SIZE, None);
+        assert_eq!(<(u32, Box<u32>)>::MAX_SERIALIZED_SIZE, None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn to_bytes_noalloc() {
+        use core::mem::MaybeUninit;
+
+        use rkyv::{
+            ser::{writer::Buffer, SerializeNoAlloc},
+            to_bytes_noalloc,
+            util::Align,
+        };
+
+        #[derive(Archive, Serialize)]
+        struct Reading {
+            sensor: String,
+            values: (u16, [i32; 3]),
+            offset: Option<Box<i64>>,
+        }
+
+        fn assert_no_alloc<T: for<'a> SerializeNoAlloc<Buffer<'a>, Error>>() {}
+
+        assert_no_alloc::<Reading>();
+
+        let value = Reading {
+            sensor: "thermocouple".to_string(),
+            values: (7, [-1, 0, 1]),
+            offset: Some(Box::new(-40)),
+        };
+
+        let mut output = Align([MaybeUninit::<u8>::uninit(); 256]);
+        let bytes = to_bytes_noalloc::<Error>(&value, &mut *output).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedReading>(bytes) };
+        assert_eq!(archived.sensor, "thermocouple");
+        assert_eq!(archived.values.0, 7);
+        assert_eq!(archived.values.1, [-1, 0, 1]);
+        assert_eq!(**archived.offset.as_ref().unwrap(), -40);
+
+        let mut output = Align([MaybeUninit::<u8>::uninit(); 8]);
+        assert!(to_bytes_noalloc::<Error>(&value, &mut *output).is_err());
     }
 }
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use rkyv::{
+        access_unchecked, rancor::Error, serde::AsSerde, to_bytes, Archive,
+        Serialize,
+    };
+    #[cfg(feature = "wasm")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_serde_struct() {
+        #[derive(Archive, Serialize)]
+        #[archive(derive_serde)]
+        struct Point(i32, i32);
+
+        #[derive(Archive, Serialize)]
+        #[archive(derive_serde)]
+        struct Id(u64);
+
+        #[derive(Archive, Serialize)]
+        #[archive(derive_serde)]
+        struct Marker;
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(derive_serde)]
+        struct Example {
+            id: Id,
+            #[rkyv(rename = "archived_name")]
+            name: String,
+            points: Vec<Point>,
+            scores: BTreeMap<String, f32>,
+            parent: Option<Box<str>>,
+            flags: (bool, char),
+            marker: Marker,
+        }
+
+        let value = Example {
+            id: Id(42),
+            name: "example".to_string(),
+            points: vec![Point(1, 2), Point(-3, 4)],
+            scores: [("a".to_string(), 1.5)].into_iter().collect(),
+            parent: None,
+            flags: (true, 'x'),
+            marker: Marker,
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&bytes) };
+
+        assert_eq!(
+            serde_json::to_string(archived).unwrap(),
+            concat!(
+                r#"{"id":42,"name":"example","points":[[1,2],[-3,4]],"#,
+                r#""scores":{"a":1.5},"parent":null,"flags":[true,"x"],"#,
+                r#""marker":null}"#,
+            ),
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_serde_enum() {
+        #[derive(Archive, Serialize)]
+        #[archive(derive_serde)]
+        enum Shape {
+            Dot,
+            Circle(u32),
+            Line(i16, i16),
+            Rect { width: u32, height: u32 },
+        }
+
+        let shapes = vec![
+            Shape::Dot,
+            Shape::Circle(5),
+            Shape::Line(-1, 1),
+            Shape::Rect {
+                width: 2,
+                height: 3,
+            },
+        ];
+        let bytes = to_bytes::<Error>(&shapes).unwrap();
+        let archived =
+            unsafe { access_unchecked::<rkyv::Archived<Vec<Shape>>>(&bytes) };
+
+        assert_eq!(
+            serde_json::to_string(&AsSerde(archived)).unwrap(),
+            concat!(
+                r#"["Dot",{"Circle":5},{"Line":[-1,1]},"#,
+                r#"{"Rect":{"width":2,"height":3}}]"#,
+            ),
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialize_archived_hash_map() {
+        let value: HashMap<String, Option<u32>> =
+            [("a".to_string(), Some(1))].into_iter().collect();
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe {
+            access_unchecked::<rkyv::Archived<HashMap<String, Option<u32>>>>(
+                &bytes,
+            )
+        };
+
+        assert_eq!(
+            serde_json::to_string(&AsSerde(archived)).unwrap(),
+            r#"{"a":1}"#,
+        );
+    }
+}
@@ -84,6 +84,178 @@ mod tests {
         assert_eq!(get_with.as_str(), "value");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_from_stream() {
+        use rkyv::{
+            collections::swiss_table::{ArchivedHashMap, HashMapResolver},
+            rancor::{Fallible, Source},
+            ser::{Allocator, Writer},
+            string::ArchivedString,
+            Place,
+        };
+
+        type Map = ArchivedHashMap<Archived<u32>, ArchivedString>;
+
+        // A source that produces rows one at a time and can't be rewound.
+        struct Rows(u32);
+
+        impl Iterator for Rows {
+            type Item = (u32, String);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0 = self.0.checked_sub(1)?;
+                Some((self.0, format!("row {}", self.0)))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.0 as usize, Some(self.0 as usize))
+            }
+        }
+
+        impl ExactSizeIterator for Rows {}
+
+        struct Table(u32);
+
+        impl Archive for Table {
+            type Archived = Map;
+            type Resolver = HashMapResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                Map::resolve_from_len(self.0 as usize, (7, 8), resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Table
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Map::serialize_from_owned_iter(Rows(self.0), (7, 8), serializer)
+            }
+        }
+
+        let buf = to_bytes::<Error>(&Table(100)).unwrap();
+        let archived = unsafe { access_unchecked::<Map>(buf.as_ref()) };
+        assert_eq!(archived.len(), 100);
+        for (key, value) in Rows(100) {
+            let key = Archived::<u32>::from_native(key);
+            assert_eq!(archived.get(&key).unwrap(), &value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_with_builders() {
+        use rkyv::{
+            collections::swiss_table::{ArchivedHashMap, HashMapResolver},
+            rancor::{Fallible, Source},
+            ser::{Allocator, BuilderExt, Writer},
+            string::ArchivedString,
+            tuple::ArchivedTuple2,
+            vec::{ArchivedVec, VecResolver},
+            Place,
+        };
+
+        type Squares = ArchivedVec<Archived<u32>>;
+        type Names = ArchivedHashMap<Archived<u32>, ArchivedString>;
+
+        struct Generated(u32);
+
+        impl Archive for Generated {
+            type Archived = ArchivedTuple2<Squares, Names>;
+            type Resolver = (VecResolver, HashMapResolver);
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                let len = self.0 as usize;
+                rkyv::munge::munge!(let ArchivedTuple2(squares, names) = out);
+                Squares::resolve_from_len(len, resolver.0, squares);
+                Names::resolve_from_len(len, (7, 8), resolver.1, names);
+            }
+        }
+
+        impl<S> Serialize<S> for Generated
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                let len = self.0 as usize;
+                let squares = serializer.build_vec(len, |builder| {
+                    for i in 0..self.0 {
+                        builder.push(i * i)?;
+                    }
+                    Ok(())
+                })?;
+                let names = serializer.build_map(len, (7, 8), |builder| {
+                    for i in 0..self.0 {
+                        builder.push(i, format!("number {}", i))?;
+                    }
+                    Ok(())
+                })?;
+                Ok((squares, names))
+            }
+        }
+
+        let buf = to_bytes::<Error>(&Generated(50)).unwrap();
+        let archived = unsafe {
+            access_unchecked::<ArchivedTuple2<Squares, Names>>(buf.as_ref())
+        };
+        assert_eq!(archived.0.len(), 50);
+        assert_eq!(archived.1.len(), 50);
+        for i in 0..50 {
+            assert_eq!(archived.0[i as usize], i * i);
+            let key = Archived::<u32>::from_native(i);
+            assert_eq!(archived.1.get(&key).unwrap(), &format!("number {}", i));
+        }
+
+        // Builders must be filled to exactly the requested length.
+        struct Short;
+
+        impl Archive for Short {
+            type Archived = Squares;
+            type Resolver = VecResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                Squares::resolve_from_len(2, resolver, out);
+            }
+        }
+
+        impl<S> Serialize<S> for Short
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                serializer.build_vec(2, |builder| builder.push(1u32))
+            }
+        }
+
+        assert!(to_bytes::<Error>(&Short).is_err());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     #[allow(deprecated)]
@@ -229,6 +401,52 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_net_helpers() {
+        use std::net::{
+            IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
+        };
+
+        let sock = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080));
+        let bytes = to_bytes::<Error>(&sock).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<SocketAddr>>(&bytes) };
+        assert_eq!(archived.port(), 8080);
+        assert!(archived.ip().is_loopback());
+        assert_eq!(archived.to_string(), "127.0.0.1:8080");
+        assert!(*archived < SocketAddr::from(([127, 0, 0, 1], 8081)));
+        assert!(SocketAddr::from(([127, 0, 0, 1], 8081)) > *archived);
+
+        let ip = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        let bytes = to_bytes::<Error>(&ip).unwrap();
+        let archived = unsafe { access_unchecked::<Archived<IpAddr>>(&bytes) };
+        assert!(archived.is_loopback());
+        assert_eq!(archived.to_string(), "::1");
+
+        let ipv4 = Ipv4Addr::new(10, 0, 0, 1);
+        let bytes = to_bytes::<Error>(&ipv4).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<Ipv4Addr>>(&bytes) };
+        assert_eq!(archived.octets(), [10, 0, 0, 1]);
+        assert!(!archived.is_loopback());
+        assert_eq!(archived.to_string(), "10.0.0.1");
+        assert!(Ipv4Addr::new(10, 0, 0, 2) > *archived);
+
+        let sockv6 = SocketAddrV6::new(
+            Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1),
+            53,
+            0,
+            0,
+        );
+        let bytes = to_bytes::<Error>(&sockv6).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<SocketAddrV6>>(&bytes) };
+        assert_eq!(archived.port(), 53);
+        assert_eq!(archived.ip().octets(), sockv6.ip().octets());
+        assert_eq!(format!("{archived:>16}"), "    [fe80::1]:53");
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn c_string() {
@@ -238,6 +456,215 @@ mod tests {
             CString::from_vec_unchecked("hello world".to_string().into_bytes())
         };
         test_archive(&value);
+
+        let value = CString::new(vec![b'a', 0xff, 0xfe, b'z']).unwrap();
+        test_archive(&value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn os_string() {
+        use std::ffi::OsString;
+
+        let value = OsString::from("hello world");
+        test_archive(&value);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<OsString>>(&bytes) };
+        assert_eq!(archived, "hello world");
+        assert_eq!(archived.to_str(), Some("hello world"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_string_non_utf8() {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt as _};
+
+        let value = OsString::from_vec(vec![b'/', b't', 0xff, b'/', 0x80]);
+        test_archive(&value);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived =
+            unsafe { access_unchecked::<Archived<OsString>>(&bytes) };
+        assert_eq!(archived.to_str(), None);
+        assert_eq!(archived.as_os_str(), value.as_os_str());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn path_buf() {
+        use std::path::PathBuf;
+
+        use rkyv::path::ArchivedComponent;
+
+        let value = PathBuf::from("/home/user/archive.tar.gz");
+        test_archive(&value);
+
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<Archived<PathBuf>>(&bytes) };
+        assert_eq!(archived, "/home/user/archive.tar.gz");
+        assert!(archived.has_root());
+        assert_eq!(
+            archived.components().collect::<Vec<_>>(),
+            [
+                ArchivedComponent::RootDir,
+                ArchivedComponent::Normal(b"home"),
+                ArchivedComponent::Normal(b"user"),
+                ArchivedComponent::Normal(b"archive.tar.gz"),
+            ]
+        );
+        assert_eq!(archived.file_name(), Some(&b"archive.tar.gz"[..]));
+        assert_eq!(archived.file_stem(), Some(&b"archive.tar"[..]));
+        assert_eq!(archived.extension(), Some(&b"gz"[..]));
+        assert_eq!(archived.to_path_buf(), Some(value));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_path_components() {
+        use rkyv::path::{ArchivedComponent::*, ArchivedPath};
+
+        fn components(path: &str) -> Vec<rkyv::path::ArchivedComponent<'_>> {
+            ArchivedPath::from_encoded_bytes(path.as_bytes())
+                .components()
+                .collect()
+        }
+
+        assert_eq!(components(""), []);
+        assert_eq!(components("."), [CurDir]);
+        assert_eq!(
+            components("./a/./b/"),
+            [CurDir, Normal(b"a"), Normal(b"b")]
+        );
+        assert_eq!(
+            components("//a//..//b"),
+            [RootDir, Normal(b"a"), ParentDir, Normal(b"b")]
+        );
+        assert_eq!(
+            components("C:/Users/me"),
+            [Normal(b"C:"), Normal(b"Users"), Normal(b"me")]
+        );
+
+        let path = ArchivedPath::from_encoded_bytes(b"C:/Users/me/.bashrc");
+        assert_eq!(path.file_name(), Some(&b".bashrc"[..]));
+        assert_eq!(path.file_stem(), Some(&b".bashrc"[..]));
+        assert_eq!(path.extension(), None);
+
+        let path = ArchivedPath::from_encoded_bytes(b"a/b/..");
+        assert_eq!(path.file_name(), None);
+        assert_eq!(path.extension(), None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_option() {
+        use std::{
+            cell::OnceCell,
+            sync::{LazyLock, OnceLock},
+        };
+
+        use rkyv::{deserialize, with::AsOption};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Cache {
+            #[with(AsOption)]
+            cell: OnceCell<String>,
+            #[with(AsOption)]
+            lock: OnceLock<u32>,
+        }
+
+        let value = Cache {
+            cell: OnceCell::from("cached".to_string()),
+            lock: OnceLock::new(),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedCache>(&bytes) };
+        assert_eq!(archived.cell.as_ref().unwrap(), "cached");
+        assert!(archived.lock.is_none());
+
+        let deserialized =
+            deserialize::<Cache, _, Error>(archived, &mut ()).unwrap();
+        assert_eq!(deserialized.cell.get().unwrap(), "cached");
+        assert!(deserialized.lock.get().is_none());
+
+        #[derive(Archive, Serialize)]
+        struct Lazy {
+            #[with(AsOption)]
+            value: LazyLock<u32>,
+        }
+
+        let value = Lazy {
+            value: LazyLock::new(|| 42),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedLazy>(&bytes) };
+        assert_eq!(archived.value.as_ref().unwrap(), &42);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_instant_since() {
+        use std::time::{Duration, Instant};
+
+        use rkyv::{
+            deserialize,
+            ser::{allocator::Arena, sharing::Share, Serializer},
+            util::{serialize_into, AlignedVec},
+            with::{Anchored, InstantSince},
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Test {
+            #[with(InstantSince)]
+            before: Instant,
+            #[with(InstantSince)]
+            after: Instant,
+        }
+
+        let anchor = Instant::now() + Duration::from_secs(10);
+        let value = Test {
+            before: anchor - Duration::from_secs(3),
+            after: anchor + Duration::from_nanos(1_234),
+        };
+
+        let mut arena = Arena::new();
+        let serializer = Anchored::new(
+            Serializer::new(
+                AlignedVec::<16>::new(),
+                arena.acquire(),
+                Share::new(),
+            ),
+            anchor,
+        );
+        let bytes = serialize_into::<_, Error>(&value, serializer)
+            .unwrap()
+            .into_inner()
+            .into_writer();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+        assert_eq!(archived.before, -3_000_000_000);
+        assert_eq!(archived.after, 1_234);
+
+        let mut same_anchor = anchor;
+        let deserialized =
+            deserialize::<Test, _, Error>(archived, &mut same_anchor).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Instants more than ~292 years from the anchor can't be represented.
+        if let Some(far_anchor) =
+            anchor.checked_add(Duration::from_secs(1 << 40))
+        {
+            let mut arena = Arena::new();
+            let serializer = Anchored::new(
+                Serializer::new(
+                    AlignedVec::<16>::new(),
+                    arena.acquire(),
+                    Share::new(),
+                ),
+                far_anchor,
+            );
+            assert!(serialize_into::<_, Error>(&value, serializer).is_err());
+        }
     }
 
     // TODO: figure out errors
@@ -406,4 +833,601 @@ mod tests {
             y: Some(ExampleEnum::Bar(0)),
         };
     }
+
+    #[test]
+    fn aligned_vec_from_file() {
+        use rkyv::util::AlignedVec;
+
+        let value = vec![1u32, 2, 3, 4];
+        let bytes = to_bytes::<Error>(&value).unwrap();
+
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_aligned_vec_from_file_{}", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        let read = AlignedVec::<16>::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let read = read.unwrap();
+        assert_eq!(read.as_ptr() as usize % 16, 0);
+        assert_eq!(read.as_slice(), bytes.as_slice());
+        let archived = unsafe { access_unchecked::<Archived<Vec<u32>>>(&read) };
+        assert_eq!(archived.as_slice(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "memmap2")]
+    fn owned_archive_map_file() {
+        use rkyv::util::OwnedArchive;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            name: String,
+            values: Vec<u32>,
+        }
+
+        let value = Example {
+            name: "mapped".to_string(),
+            values: vec![1, 2, 3],
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_owned_archive_map_file_{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, &bytes).unwrap();
+        let archive =
+            unsafe { OwnedArchive::<Example, _>::map_file::<Error>(&path) }
+                .unwrap();
+
+        assert_eq!(archive.name, "mapped");
+        assert_eq!(archive.values.as_slice(), &[1, 2, 3]);
+        assert_eq!(archive.as_bytes(), bytes.as_slice());
+
+        drop(archive);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "memmap2")]
+    fn archive_file_commit() {
+        use rkyv::util::ArchiveFile;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            name: String,
+            padding: Vec<u8>,
+            enabled: bool,
+            count: u32,
+        }
+
+        let value = Example {
+            name: "patched".to_string(),
+            padding: vec![0; 10_000],
+            enabled: false,
+            count: 1,
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_archive_file_commit_{}", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        let mut file =
+            unsafe { ArchiveFile::<Example>::open::<Error>(&path) }.unwrap();
+
+        // Edits are only visible through the archive file until they are
+        // committed.
+        let mut example = file.get_mut();
+        let example = unsafe { example.as_mut().get_unchecked_mut() };
+        example.enabled = true;
+        example.count = 2.into();
+        assert!(file.is_dirty());
+        assert!(file.get().enabled);
+        assert_eq!(std::fs::read(&path).unwrap(), bytes.as_slice());
+
+        // Only the page containing the root is written.
+        assert_eq!(file.commit::<Error>().unwrap(), 1);
+        assert!(!file.is_dirty());
+        let written = std::fs::read(&path).unwrap();
+        let archived =
+            rkyv::access::<ArchivedExample, Error>(&written).unwrap();
+        assert!(archived.enabled);
+        assert_eq!(archived.count, 2);
+        assert_eq!(archived.name, "patched");
+
+        // Invalid edits are rejected and can be discarded.
+        let mut example = file.get_mut();
+        unsafe {
+            let enabled = &mut example.as_mut().get_unchecked_mut().enabled;
+            (enabled as *mut bool).cast::<u8>().write(2);
+        }
+        assert!(file.commit::<Error>().is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), written);
+        file.discard::<Error>().unwrap();
+        assert!(!file.is_dirty());
+        assert!(file.get().enabled);
+
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "memmap2")]
+    fn mapped_archive_mut_flush() {
+        use rkyv::util::MappedArchiveMut;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Example {
+            name: String,
+            count: u32,
+        }
+
+        let value = Example {
+            name: "mapped".to_string(),
+            count: 1,
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_mapped_archive_mut_flush_{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, &bytes).unwrap();
+        let mut map =
+            unsafe { MappedArchiveMut::<Example>::open::<Error>(&path) }
+                .unwrap();
+
+        let mut example = map.get_mut();
+        let count = unsafe { &mut example.as_mut().get_unchecked_mut().count };
+        *count = 2.into();
+        assert_eq!(map.get().count, 2);
+
+        let offset = (&map.get().count as *const _ as usize)
+            - (map.as_bytes().as_ptr() as usize);
+        map.flush_range::<Error>(offset, 4).unwrap();
+        let written = std::fs::read(&path).unwrap();
+        let archived =
+            rkyv::access::<ArchivedExample, Error>(&written).unwrap();
+        assert_eq!(archived.count, 2);
+        assert_eq!(archived.name, "mapped");
+
+        map.flush::<Error>().unwrap();
+        drop(map);
+        std::fs::remove_file(&path).unwrap();
+
+        // Invalid files are rejected when they are mapped.
+        std::fs::write(&path, [0xff; 4]).unwrap();
+        assert!(unsafe { MappedArchiveMut::<Example>::open::<Error>(&path) }
+            .is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn archive_log_recovery() {
+        use std::fs::OpenOptions;
+
+        use rkyv::util::ArchiveLog;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Entry {
+            key: String,
+            value: u64,
+        }
+
+        fn keys(log: &ArchiveLog) -> Vec<String> {
+            log.iter::<Entry, Error>()
+                .map(|entry| entry.unwrap().key.to_string())
+                .collect()
+        }
+
+        let path = std::env::temp_dir()
+            .join(format!("rkyv_archive_log_recovery_{}", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let mut log = ArchiveLog::open::<Error>(&path).unwrap();
+        assert!(log.is_empty());
+        for (i, key) in ["a", "b", "c"].into_iter().enumerate() {
+            let entry = Entry {
+                key: key.to_string(),
+                value: i as u64,
+            };
+            log.append::<_, Error>(&entry).unwrap();
+        }
+        log.sync::<Error>().unwrap();
+        drop(log);
+
+        // Every record is aligned, so records can be accessed in place.
+        let len = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(len % 16, 0);
+
+        // Simulate a crash in the middle of appending the last record.
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(len - 5).unwrap();
+        drop(file);
+
+        let mut log = ArchiveLog::open::<Error>(&path).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(keys(&log), ["a", "b"]);
+        let recovered_len = std::fs::metadata(&path).unwrap().len() as usize;
+        let entry = Entry {
+            key: "d".to_string(),
+            value: 3,
+        };
+        log.append::<_, Error>(&entry).unwrap();
+        assert_eq!(keys(&log), ["a", "b", "d"]);
+        drop(log);
+
+        // Corrupted records at the end of the log are also removed.
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[recovered_len + 16] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+
+        let log = ArchiveLog::open::<Error>(&path).unwrap();
+        assert_eq!(keys(&log), ["a", "b"]);
+        let value = log.iter::<Entry, Error>().last().unwrap().unwrap().value;
+        assert_eq!(value, 1);
+        drop(log);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stream_frames() {
+        use std::io::Read;
+
+        use rkyv::util::{
+            frame::{read_frame, write_frame, write_frame_checksummed},
+            AlignedVec,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Message {
+            id: u64,
+            body: String,
+        }
+
+        // A reader which returns a single byte at a time, like a slow socket.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                Read::take(&mut self.0, 1).read(buf)
+            }
+        }
+
+        let mut stream = Vec::new();
+        for id in 0..3 {
+            let message = Message {
+                id,
+                body: "x".repeat(id as usize * 10),
+            };
+            let bytes = to_bytes::<Error>(&message).unwrap();
+            if id % 2 == 0 {
+                write_frame::<_, Error>(&mut stream, &bytes).unwrap();
+            } else {
+                write_frame_checksummed::<_, Error>(&mut stream, &bytes)
+                    .unwrap();
+            }
+        }
+
+        // Start the frames at an odd offset so that none of them are aligned
+        // in the stream.
+        let mut unaligned = vec![0];
+        unaligned.extend_from_slice(&stream);
+        let mut reader = Trickle(&unaligned[1..]);
+        let mut buffer = AlignedVec::<16>::new();
+        let mut ids = Vec::new();
+        while read_frame::<_, _, Error>(&mut reader, &mut buffer).unwrap() {
+            let message =
+                rkyv::access::<ArchivedMessage, Error>(&buffer).unwrap();
+            assert_eq!(
+                message.body.len(),
+                message.id.to_native() as usize * 10
+            );
+            ids.push(message.id.to_native());
+        }
+        assert_eq!(ids, [0, 1, 2]);
+
+        // Truncated frames are errors.
+        let mut reader = &stream[..stream.len() - 1];
+        for _ in 0..2 {
+            read_frame::<_, _, Error>(&mut reader, &mut buffer).unwrap();
+        }
+        assert!(read_frame::<_, _, Error>(&mut reader, &mut buffer).is_err());
+
+        // Corrupted checksummed frames are errors.
+        let bytes = to_bytes::<Error>(&Message {
+            id: 7,
+            body: "checked".to_string(),
+        })
+        .unwrap();
+        let mut stream = Vec::new();
+        write_frame_checksummed::<_, Error>(&mut stream, &bytes).unwrap();
+        let last = stream.len() - 1;
+        stream[last] ^= 1;
+        let mut reader = stream.as_slice();
+        assert!(read_frame::<_, _, Error>(&mut reader, &mut buffer).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_compare_hash() {
+        use std::hash::BuildHasher;
+
+        #[derive(Archive, Serialize, Hash)]
+        #[archive(compare(PartialEq, Hash))]
+        struct Example {
+            id: u32,
+            name: String,
+            tags: Vec<String>,
+            parent: Option<Box<u64>>,
+        }
+
+        let value = Example {
+            id: 42,
+            name: "hello".to_string(),
+            tags: vec!["a".to_string(), "b".to_string()],
+            parent: Some(Box::new(7)),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedExample>(&bytes) };
+
+        let state = std::collections::hash_map::RandomState::new();
+        assert_eq!(state.hash_one(&value), state.hash_one(archived));
+
+        assert!(value == *archived);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn fragment_cache() {
+        use std::sync::Arc;
+
+        use rkyv::util::{Fragment, FragmentCache, SharedAlignedVec};
+
+        #[derive(Archive, Serialize)]
+        struct Message {
+            id: u32,
+            dictionary: Fragment<Vec<String>, SharedAlignedVec>,
+            schema: Fragment<String, SharedAlignedVec>,
+        }
+
+        let dictionary =
+            Arc::new((0..100).map(|i| format!("word {}", i)).collect());
+        let mut cache = FragmentCache::<&str>::new();
+
+        let mut first = None;
+        for id in 0..3 {
+            let message = Message {
+                id,
+                dictionary: cache
+                    .get_or_insert_arc::<_, Error>(&dictionary)
+                    .unwrap(),
+                schema: cache
+                    .get_or_insert::<_, Error>("schema", &"v1".to_string())
+                    .unwrap(),
+            };
+            let bytes = to_bytes::<Error>(&message).unwrap();
+            let archived =
+                unsafe { access_unchecked::<ArchivedMessage>(&bytes) };
+            assert_eq!(archived.id, id);
+            assert_eq!(archived.dictionary.len(), 100);
+            assert_eq!(archived.dictionary[42], "word 42");
+            assert_eq!(*archived.schema, "v1");
+
+            // Every message reuses the same cached bytes.
+            let ptr = message.dictionary.as_bytes().as_ptr();
+            assert_eq!(*first.get_or_insert(ptr), ptr);
+        }
+        assert_eq!(cache.len(), 2);
+
+        // The same key is cached separately for each type.
+        let other = cache.get_or_insert::<_, Error>("schema", &1u32).unwrap();
+        assert_eq!(*other.archived(), 1);
+        assert_eq!(cache.len(), 3);
+
+        // The cache keeps cached `Arc`s alive.
+        assert_eq!(Arc::strong_count(&dictionary), 2);
+        cache.clear();
+        assert_eq!(Arc::strong_count(&dictionary), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialize_error_position() {
+        use core::mem::MaybeUninit;
+
+        use rkyv::to_bytes_noalloc;
+
+        let value = ("thermocouple".to_string(), Box::new(-40i64));
+
+        let mut output = Align([MaybeUninit::<u8>::uninit(); 16]);
+        let error =
+            to_bytes_noalloc::<Error>(&value, &mut *output).unwrap_err();
+        let trace = format!("{error:?}");
+        assert!(trace.contains("SerializerPosition { pos: 16 }"));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn wrapper_errors_do_not_panic() {
+        use std::{
+            ffi::OsString,
+            num::{NonZeroIsize, NonZeroUsize},
+            panic::catch_unwind,
+            path::PathBuf,
+            time::{Duration, SystemTime, UNIX_EPOCH},
+        };
+
+        use rkyv::{
+            primitive::{FixedIsize, FixedUsize},
+            with::{AsString, CStrArray, Niche, UnixTimestamp},
+        };
+
+        // Errors must be returned from serialize, not raised while resolving.
+        macro_rules! assert_serialize_fails {
+            ($value:expr) => {{
+                let value = $value;
+                let result =
+                    catch_unwind(|| to_bytes::<Error>(&value).is_err());
+                assert_eq!(result.ok(), Some(true));
+            }};
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Timestamp {
+            #[with(UnixTimestamp)]
+            time: SystemTime,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct NichedIsize {
+            #[with(Niche)]
+            value: Option<NonZeroIsize>,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct NichedUsize {
+            #[with(Niche)]
+            value: Option<NonZeroUsize>,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct Strings {
+            #[with(AsString)]
+            os_string: OsString,
+            #[with(AsString)]
+            path: PathBuf,
+        }
+
+        #[derive(Archive, Serialize)]
+        struct CName {
+            #[with(CStrArray<4>)]
+            name: String,
+        }
+
+        let value = Timestamp {
+            time: UNIX_EPOCH + Duration::from_secs(60),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTimestamp>(&bytes) };
+        assert_eq!(archived.time.as_secs(), 60);
+
+        assert_serialize_fails!(Timestamp {
+            time: UNIX_EPOCH - Duration::from_secs(60),
+        });
+
+        if FixedIsize::try_from(isize::MAX).is_err() {
+            assert_serialize_fails!(NichedIsize {
+                value: NonZeroIsize::new(isize::MAX),
+            });
+        }
+        if FixedUsize::try_from(usize::MAX).is_err() {
+            assert_serialize_fails!(NichedUsize {
+                value: NonZeroUsize::new(usize::MAX),
+            });
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt as _;
+
+            assert_serialize_fails!(Strings {
+                os_string: OsString::from_vec(vec![0xff]),
+                path: PathBuf::new(),
+            });
+            assert_serialize_fails!(Strings {
+                os_string: OsString::new(),
+                path: PathBuf::from(OsString::from_vec(vec![0xff])),
+            });
+        }
+
+        assert_serialize_fails!(CName {
+            name: "too long".to_string(),
+        });
+        assert_serialize_fails!(CName {
+            name: "a\0b".to_string(),
+        });
+
+        #[cfg(target_has_atomic = "64")]
+        {
+            use std::sync::atomic::{AtomicIsize, AtomicUsize};
+
+            use rkyv::with::{AtomicLoad, Relaxed};
+
+            #[derive(Archive, Serialize)]
+            struct Atomics {
+                #[with(AtomicLoad<Relaxed>)]
+                unsigned: AtomicUsize,
+                #[with(AtomicLoad<Relaxed>)]
+                signed: AtomicIsize,
+            }
+
+            if FixedUsize::try_from(usize::MAX).is_err() {
+                assert_serialize_fails!(Atomics {
+                    unsigned: AtomicUsize::new(usize::MAX),
+                    signed: AtomicIsize::new(0),
+                });
+                assert_serialize_fails!(Atomics {
+                    unsigned: AtomicUsize::new(0),
+                    signed: AtomicIsize::new(isize::MIN),
+                });
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn lock_poisoning_policies() {
+        use std::{
+            panic::{catch_unwind, AssertUnwindSafe},
+            sync::{Mutex, RwLock},
+        };
+
+        use rkyv::with::{DefaultOnPoison, IgnorePoison, Lock};
+
+        #[derive(Archive, Serialize)]
+        struct Test {
+            #[with(Lock)]
+            error: Mutex<u32>,
+            #[with(Lock<IgnorePoison>)]
+            ignore: Mutex<u32>,
+            #[with(Lock<DefaultOnPoison>)]
+            default: RwLock<String>,
+        }
+
+        let value = Test {
+            error: Mutex::new(1),
+            ignore: Mutex::new(2),
+            default: RwLock::new("a string that is not inlined".to_string()),
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+        assert_eq!(*archived.error, 1);
+        assert_eq!(*archived.ignore, 2);
+        assert_eq!(*archived.default, "a string that is not inlined");
+
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _ignore = value.ignore.lock().unwrap();
+            let _default = value.default.write().unwrap();
+            panic!("poisoning locks");
+        }));
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = unsafe { access_unchecked::<ArchivedTest>(&bytes) };
+        assert_eq!(*archived.ignore, 2);
+        assert_eq!(*archived.default, "");
+
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _error = value.error.lock().unwrap();
+            panic!("poisoning lock");
+        }));
+        assert!(to_bytes::<Error>(&value).is_err());
+    }
 }
@@ -2,7 +2,13 @@
 wasm_bindgen_test::wasm_bindgen_test_configure!();
 
 pub mod core {
-    use core::{fmt::Debug, mem::MaybeUninit};
+    use core::{
+        fmt::Debug,
+        future::Future,
+        mem::MaybeUninit,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
 
     use rkyv::{
         access_unchecked,
@@ -57,6 +63,17 @@ pub mod core {
     {
         test_archive_with(value, |a, b| b == a);
     }
+
+    /// Runs a future to completion on the current thread.
+    pub fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -18,7 +18,7 @@ mod tests {
     use rkyv::{
         access,
         bytecheck::CheckBytes,
-        from_bytes,
+        from_bytes, from_bytes_async,
         rancor::{Error, Failure, Source},
         ser::Writer,
         to_bytes,
@@ -314,6 +314,63 @@ mod tests {
         ])));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_enum_wide_tag() {
+        use core::mem::size_of;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes, repr(u16))]
+        enum Test {
+            A(u32),
+            B(String),
+            C,
+        }
+
+        serialize_and_check::<_, Error>(&Test::A(42));
+        serialize_and_check::<_, Error>(&Test::B("hello world".to_string()));
+        serialize_and_check::<_, Error>(&Test::C);
+
+        // An archived `Test::C` with an invalid tag.
+        let mut bytes = Align([0xffu8; size_of::<ArchivedTest>()]);
+        assert!(access::<ArchivedTest, Error>(&*bytes).is_err());
+
+        let tag = if cfg!(feature = "big_endian") {
+            2u16.to_be_bytes()
+        } else {
+            2u16.to_le_bytes()
+        };
+        bytes[..2].copy_from_slice(&tag);
+        assert!(access::<ArchivedTest, Error>(&*bytes).is_ok());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn derive_enum_explicit_discriminants() {
+        use core::mem::size_of;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[repr(u8)]
+        enum Test {
+            A(u32) = 10,
+            B(String) = 20,
+            C,
+        }
+
+        serialize_and_check::<_, Error>(&Test::A(42));
+        serialize_and_check::<_, Error>(&Test::B("hello world".to_string()));
+        serialize_and_check::<_, Error>(&Test::C);
+
+        // Tags are only valid if they match an explicit discriminant.
+        let mut bytes = Align([0u8; size_of::<ArchivedTest>()]);
+        assert!(access::<ArchivedTest, Error>(&*bytes).is_err());
+        bytes[0] = 2;
+        assert!(access::<ArchivedTest, Error>(&*bytes).is_err());
+        bytes[0] = 21;
+        assert!(access::<ArchivedTest, Error>(&*bytes).is_ok());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn recursive_type() {
@@ -342,6 +399,127 @@ mod tests {
         ))));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn recursive_type_inferred_bounds() {
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        enum Node {
+            Nil,
+            Cons(Box<Node>),
+        }
+
+        serialize_and_check::<_, Error>(&Node::Cons(Box::new(Node::Cons(
+            Box::new(Node::Nil),
+        ))));
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Tree {
+            value: u32,
+            children: Vec<Tree>,
+        }
+
+        serialize_and_check::<_, Error>(&Tree {
+            value: 1,
+            children: vec![Tree {
+                value: 2,
+                children: Vec::new(),
+            }],
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_const_generics() {
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Block<T, const N: usize> {
+            data: [T; N],
+            crc: u32,
+        }
+
+        serialize_and_check::<_, Error>(&Block {
+            data: [1u16, 2, 3, 4],
+            crc: 5,
+        });
+        serialize_and_check::<_, Error>(&Block {
+            data: [vec![1u8], vec![2, 3]],
+            crc: 5,
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn length_prefixed_defaults() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes, length_prefixed)]
+        struct TestV1 {
+            a: u32,
+            b: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes, length_prefixed)]
+        struct TestV2 {
+            a: u32,
+            b: String,
+            #[rkyv(default)]
+            c: Vec<u32>,
+            #[rkyv(default = "7")]
+            d: u16,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes, length_prefixed)]
+        struct TestV3 {
+            a: u32,
+            b: String,
+            c: Vec<u32>,
+        }
+
+        let v1 = TestV1 {
+            a: 42,
+            b: "hello world".to_string(),
+        };
+        let buf = to_bytes::<Error>(&v1).unwrap();
+        assert_eq!(from_bytes::<TestV1, Error>(&buf).unwrap(), v1);
+        assert_eq!(
+            from_bytes::<TestV2, Error>(&buf).unwrap(),
+            TestV2 {
+                a: 42,
+                b: "hello world".to_string(),
+                c: Vec::new(),
+                d: 7,
+            },
+        );
+        from_bytes::<TestV3, Error>(&buf).unwrap_err();
+
+        let v2 = TestV2 {
+            a: 1,
+            b: "foo".to_string(),
+            c: vec![2, 3, 4],
+            d: 5,
+        };
+        let buf = to_bytes::<Error>(&v2).unwrap();
+        assert_eq!(from_bytes::<TestV2, Error>(&buf).unwrap(), v2);
+        assert_eq!(
+            from_bytes::<TestV1, Error>(&buf).unwrap(),
+            TestV1 {
+                a: 1,
+                b: "foo".to_string(),
+            },
+        );
+        assert_eq!(
+            from_bytes::<TestV3, Error>(&buf).unwrap(),
+            TestV3 {
+                a: 1,
+                b: "foo".to_string(),
+                c: vec![2, 3, 4],
+            },
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn check_shared_ptr() {
@@ -470,6 +648,196 @@ mod tests {
         rkyv::from_bytes::<String, Error>(&*data).unwrap_err();
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_niched_string_and_vec() {
+        use rkyv::with::Niche;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(Niche)]
+            string: Option<String>,
+            #[with(Niche)]
+            vec: Option<Vec<u32>>,
+        }
+
+        serialize_and_check::<_, Error>(&Test {
+            string: Some("a string which is too long to inline".to_string()),
+            vec: Some(vec![1, 2, 3]),
+        });
+        serialize_and_check::<_, Error>(&Test {
+            string: None,
+            vec: None,
+        });
+
+        // An inline string with an impossible length is not a `None`.
+        let data = Align([0x10; 16]);
+        assert!(access::<Archived<Test>, Error>(&*data).is_err());
+    }
+
+    #[test]
+    fn check_cow() {
+        use std::borrow::Cow;
+
+        use rkyv::with::AsCow;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        #[allow(clippy::owned_cow)]
+        struct Test<'a> {
+            #[with(AsCow)]
+            a: Cow<'a, String>,
+            #[with(AsCow)]
+            b: Cow<'a, String>,
+            #[with(AsCow)]
+            c: Cow<'a, Vec<u32>>,
+        }
+
+        let payload = "a payload which is too long to inline".to_string();
+        serialize_and_check::<_, Error>(&Test {
+            a: Cow::Borrowed(&payload),
+            b: Cow::Borrowed(&payload),
+            c: Cow::Owned(vec![1, 2, 3]),
+        });
+
+        // A borrowed value pointing out of bounds is invalid.
+        let mut bytes = to_bytes::<Error>(&Test {
+            a: Cow::Borrowed(&payload),
+            b: Cow::Owned(payload.clone()),
+            c: Cow::Owned(vec![]),
+        })
+        .unwrap();
+        let root = bytes.len() - core::mem::size_of::<Archived<Test>>();
+        bytes[root + 4..root + 8].copy_from_slice(&0x1000i32.to_le_bytes());
+        assert!(access::<Archived<Test>, Error>(&bytes).is_err());
+    }
+
+    #[test]
+    fn check_front_coded() {
+        use rkyv::with::AsFrontCoded;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(AsFrontCoded)]
+            words: Vec<String>,
+        }
+
+        let words = (0..40)
+            .map(|i| format!("w\u{e9}rd {}", i))
+            .collect::<Vec<_>>();
+        serialize_and_check::<_, Error>(&Test {
+            words: words.clone(),
+        });
+        serialize_and_check::<_, Error>(&Test { words: Vec::new() });
+
+        // The string data is written first, so the archive starts with the
+        // prefix length of the first string. A first string can't have a
+        // prefix.
+        let mut bytes = to_bytes::<Error>(&Test { words }).unwrap();
+        assert!(access::<Archived<Test>, Error>(&bytes).is_ok());
+        bytes[0] = 1;
+        assert!(access::<Archived<Test>, Error>(&bytes).is_err());
+    }
+
+    #[test]
+    fn check_compact() {
+        use rkyv::with::Compact;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(Compact)]
+            a: String,
+            #[with(Compact)]
+            b: Vec<u32>,
+            #[with(Compact)]
+            c: Vec<String>,
+        }
+
+        serialize_and_check::<_, Error>(&Test {
+            a: "hello world".to_string(),
+            b: vec![1, 2, 3],
+            c: vec!["a".to_string(), "x".repeat(300)],
+        });
+        serialize_and_check::<_, Error>(&Test {
+            a: String::new(),
+            b: Vec::new(),
+            c: Vec::new(),
+        });
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Name {
+            #[with(Compact)]
+            name: String,
+        }
+
+        // The string is written first, starting with its length.
+        let bytes = to_bytes::<Error>(&Name {
+            name: "hello world".to_string(),
+        })
+        .unwrap();
+        assert!(access::<Archived<Name>, Error>(&bytes).is_ok());
+
+        // A length which runs past the end of the archive is invalid.
+        let mut long = bytes.clone();
+        long[0] = 100;
+        assert!(access::<Archived<Name>, Error>(&long).is_err());
+
+        // A length which doesn't end is invalid.
+        let mut unterminated = bytes.clone();
+        for byte in unterminated[..12].iter_mut() {
+            *byte = 0x80;
+        }
+        assert!(access::<Archived<Name>, Error>(&unterminated).is_err());
+
+        // Contents which aren't valid UTF-8 are invalid.
+        let mut invalid = bytes.clone();
+        invalid[1] = 0xff;
+        assert!(access::<Archived<Name>, Error>(&invalid).is_err());
+    }
+
+    #[test]
+    fn check_small_vec() {
+        use rkyv::with::AsSmallVec;
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Test {
+            #[with(AsSmallVec<2>)]
+            a: Vec<u32>,
+            #[with(AsSmallVec<2>)]
+            b: Vec<String>,
+        }
+
+        serialize_and_check::<_, Error>(&Test {
+            a: vec![1, 2],
+            b: vec!["hello".to_string(), "world".to_string(), String::new()],
+        });
+        serialize_and_check::<_, Error>(&Test {
+            a: vec![1, 2, 3, 4],
+            b: vec!["a string which is too long to inline".to_string()],
+        });
+
+        #[derive(Archive, Serialize)]
+        #[archive(check_bytes)]
+        struct Values {
+            #[with(AsSmallVec<2>)]
+            values: Vec<u32>,
+        }
+
+        // An inline vec whose length is too long becomes an out-of-line vec
+        // with a pointer that is out of bounds.
+        let mut bytes =
+            to_bytes::<Error>(&Values { values: vec![1, 2] }).unwrap();
+        assert!(access::<Archived<Values>, Error>(&bytes).is_ok());
+        let root = bytes.len() - core::mem::size_of::<Archived<Values>>();
+        bytes[root..root + 4].copy_from_slice(&3u32.to_le_bytes());
+        assert!(access::<Archived<Values>, Error>(&bytes).is_err());
+    }
+
     #[test]
     fn rc_btreemap() {
         use rkyv::{Archive, Deserialize, Serialize};
@@ -528,4 +896,191 @@ mod tests {
             let _ = access::<ArchivedJsonValue, Failure>(&buf).unwrap();
         }
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_deserialize_async() {
+        use crate::util::core::block_on;
+
+        let value = vec![Some("hello".to_string()), None];
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let deserialized =
+            block_on(from_bytes_async::<Vec<Option<String>>, Error>(&buf))
+                .unwrap();
+        assert_eq!(deserialized, value);
+
+        let truncated = &buf[..buf.len() - 1];
+        block_on(from_bytes_async::<Vec<Option<String>>, Error>(truncated))
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_splice_edits() {
+        use rkyv::splice::ArchiveEditor;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes, splice)]
+        struct Author {
+            name: String,
+            id: u32,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes, splice)]
+        struct Document {
+            title: String,
+            author: Box<Author>,
+            tags: Vec<String>,
+            scores: Vec<u32>,
+            editor: Option<Author>,
+        }
+
+        let value = Document {
+            title: "Hello world!!!!!".to_string(),
+            author: Box::new(Author {
+                name: "ferris".to_string(),
+                id: 1,
+            }),
+            tags: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            scores: vec![1, 2, 3],
+            editor: Some(Author {
+                name: "crab".to_string(),
+                id: 2,
+            }),
+        };
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived = access::<ArchivedDocument, Error>(&buf).unwrap();
+
+        // Nothing edited
+        let edited = ArchiveEditor::<Document, Error>::new(archived)
+            .finish()
+            .unwrap();
+        let document = from_bytes::<Document, Error>(&edited).unwrap();
+        assert_eq!(document, value);
+
+        let mut editor = ArchiveEditor::<Document, Error>::new(archived);
+        editor
+            .replace(|doc| &doc.author.name, "a much longer name".to_string())
+            .replace(|doc| &doc.tags[1], "replaced".to_string())
+            .replace(|doc| &doc.scores[2], 30u32)
+            .update(
+                |doc| doc.editor.as_ref().unwrap(),
+                |editor: &mut Author| editor.id += 40,
+            )
+            .unwrap();
+        let edited = editor.finish().unwrap();
+
+        let document = from_bytes::<Document, Error>(&edited).unwrap();
+        assert_eq!(document.title, value.title);
+        assert_eq!(document.author.name, "a much longer name");
+        assert_eq!(document.author.id, 1);
+        assert_eq!(document.tags, ["a", "replaced", "c"]);
+        assert_eq!(document.scores, [1, 2, 30]);
+        assert_eq!(
+            document.editor,
+            Some(Author {
+                name: "crab".to_string(),
+                id: 42,
+            })
+        );
+
+        // Values inside strings are not spliced, so they can't be replaced
+        let mut editor = ArchiveEditor::<Document, Error>::new(archived);
+        editor.replace(|doc| &doc.title.as_bytes()[0], b'h');
+        editor.finish().unwrap_err();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_extern_ref() {
+        use rkyv::{extern_ref::ExternRef, string::ArchivedString};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive(check_bytes)]
+        struct Entry {
+            id: u32,
+            names: Vec<ExternRef<ArchivedString>>,
+        }
+
+        let words = vec!["hello world!!!!!".to_string(), "ferris".to_string()];
+        let dictionary = to_bytes::<Error>(&words).unwrap();
+        let archived_words =
+            access::<Archived<Vec<String>>, Error>(&dictionary).unwrap();
+
+        let value = Entry {
+            id: 42,
+            names: vec![
+                ExternRef::from_archived(7, &dictionary, &archived_words[1]),
+                ExternRef::from_archived(7, &dictionary, &archived_words[0]),
+            ],
+        };
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived = access::<ArchivedEntry, Error>(&buf).unwrap();
+        assert_eq!(from_bytes::<Entry, Error>(&buf).unwrap(), value);
+
+        #[cfg(feature = "std")]
+        {
+            let mut archives = std::collections::HashMap::new();
+            archives.insert(7u64, dictionary.clone());
+            assert_eq!(
+                archived.names[0].get::<_, Error>(&archives).unwrap(),
+                "ferris"
+            );
+            assert_eq!(
+                archived.names[1].get::<_, Error>(&archives).unwrap(),
+                "hello world!!!!!"
+            );
+        }
+
+        // Missing archives are an error
+        archived.names[0]
+            .get::<_, Error>(&[dictionary.clone()][..])
+            .unwrap_err();
+
+        // Positions which don't point to a valid value are an error
+        let archives = (0..8).map(|_| dictionary.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            archived.names[0].get::<_, Error>(&archives[..]).unwrap(),
+            "ferris"
+        );
+        ExternRef::<ArchivedString>::new(7, dictionary.len())
+            .get::<_, Error>(&archives[..])
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg(all(target_pointer_width = "64", feature = "pointer_width_64"))]
+    #[ignore = "builds an archive larger than 4 GiB"]
+    fn check_archive_larger_than_4_gib() {
+        use rkyv::{to_bytes_in, util::AlignedVec};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[archive(check_bytes)]
+        struct Large {
+            data: Vec<u8>,
+            tail: String,
+            values: Vec<u64>,
+        }
+
+        const DATA_LEN: usize = (1 << 32) + 4096;
+
+        // The zeroed source buffer is allocated lazily by the OS, so only the
+        // archive itself takes up memory.
+        let value = Large {
+            data: vec![0; DATA_LEN],
+            tail: "written after the first four gibibytes".to_string(),
+            values: vec![1, 2, 3],
+        };
+        let writer = AlignedVec::<16>::with_capacity(DATA_LEN + 1024);
+        let buf = to_bytes_in::<_, Error>(&value, writer).unwrap();
+        assert!(buf.len() > DATA_LEN);
+
+        // The root is more than 4 GiB away from the start of `data`.
+        let archived = access::<ArchivedLarge, Error>(&buf).unwrap();
+        assert_eq!(archived.data.len(), DATA_LEN);
+        assert!(archived.data.iter().all(|b| *b == 0));
+        assert_eq!(archived.tail, value.tail);
+        assert_eq!(archived.values.as_slice(), &[1, 2, 3]);
+    }
 }
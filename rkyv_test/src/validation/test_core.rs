@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use core::{any::TypeId, mem::MaybeUninit};
+
+    use rkyv::{
+        rancor::{Error, Strategy},
+        ser::{allocator::SubAllocator, sharing::Unshare, writer::Buffer},
+        util::{serialize_into, Align},
+        validation::{
+            util::{access_pos_with_context, access_with_context},
+            validators::{BufferSharedValidator, CoreValidator},
+            SharedContext,
+        },
+        Archived,
+    };
+    #[cfg(feature = "wasm")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn basic_functionality() {
+        let value = (42u32, [1u8, 2, 3, 4], Some(-7i16));
+
+        let mut output = Align([MaybeUninit::<u8>::uninit(); 256]);
+        let mut scratch = [MaybeUninit::<u8>::uninit(); 256];
+        let buffer = serialize_into::<_, Error>(
+            &value,
+            rkyv::ser::Serializer::new(
+                Buffer::from(&mut *output),
+                SubAllocator::new(&mut scratch),
+                Unshare,
+            ),
+        )
+        .expect("failed to serialize value")
+        .into_writer();
+
+        let mut shared = [MaybeUninit::uninit(); 4];
+        let mut validator = CoreValidator::new(&buffer, &mut shared);
+        let archived = access_with_context::<
+            Archived<(u32, [u8; 4], Option<i16>)>,
+            _,
+            Error,
+        >(&buffer, &mut validator)
+        .unwrap();
+        assert_eq!(archived.0, 42);
+        assert_eq!(archived.1, [1, 2, 3, 4]);
+        assert_eq!(archived.2, Some(-7));
+
+        // Out of bounds
+        let bytes = Align([0u8, 1, 2, 3, 4]);
+        let mut validator = CoreValidator::new(&*bytes, &mut shared);
+        access_pos_with_context::<Archived<u32>, _, Error>(
+            &*bytes,
+            8,
+            &mut validator,
+        )
+        .expect_err("expected out of bounds error");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn buffer_shared_validator() {
+        let mut entries = [MaybeUninit::uninit(); 2];
+        let mut validator = BufferSharedValidator::new(&mut entries);
+        let context = Strategy::<_, Error>::wrap(&mut validator);
+
+        assert!(context.register_shared_ptr(8, TypeId::of::<u32>()).unwrap());
+        assert!(!context.register_shared_ptr(8, TypeId::of::<u32>()).unwrap());
+        context
+            .register_shared_ptr(8, TypeId::of::<u64>())
+            .expect_err("expected type mismatch error");
+        assert!(context.register_shared_ptr(16, TypeId::of::<u8>()).unwrap());
+        context
+            .register_shared_ptr(24, TypeId::of::<u8>())
+            .expect_err("expected out of space error");
+
+        assert_eq!(validator.len(), 2);
+        assert_eq!(validator.capacity(), 2);
+    }
+}
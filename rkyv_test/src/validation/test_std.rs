@@ -27,4 +27,23 @@ mod tests {
         set.insert("baz".to_string());
         serialize_and_check::<_, Error>(&set);
     }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn error_positions() {
+        use rkyv::{access, to_bytes, Archived};
+
+        let mut bytes = to_bytes::<Error>(&vec![true, false, true]).unwrap();
+        bytes[1] = 2;
+        let error = access::<Archived<Vec<bool>>, Error>(&bytes).unwrap_err();
+        let trace = format!("{error:?}");
+        assert!(trace.contains("SubtreePosition { pos: 0 }"));
+        assert!(trace.contains("SubtreePosition { pos: 4 }"));
+
+        let mut bytes = to_bytes::<Error>(&vec![true, false, true]).unwrap();
+        bytes[4] = 0x40;
+        let error = access::<Archived<Vec<bool>>, Error>(&bytes).unwrap_err();
+        assert!(error.to_string().contains("(byte position -188) size 3"));
+    }
 }